@@ -2,9 +2,10 @@ use anyhow::{Context, Result};
 use inquire::MultiSelect;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-use crate::commands::login::{account_id_to_dirname, resolve_data_root};
+use crate::commands::login::{account_id_to_dirname, resolve_data_root, SessionMetaFile};
 
 /// Preferences for account selection, stored globally.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -50,6 +51,17 @@ impl Preferences {
     }
 }
 
+/// Account directories logged into the same user ID, e.g. after logging in
+/// twice with different spellings of the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateAccount {
+    pub user_id: String,
+    /// Directory of the latest login, the one the stored credentials belong to
+    pub latest: PathBuf,
+    /// Directories of earlier logins
+    pub others: Vec<PathBuf>,
+}
+
 /// Account selector handles account discovery and selection with preference memory.
 pub struct AccountSelector {
     preferences: Preferences,
@@ -64,26 +76,32 @@ impl AccountSelector {
 
     /// Discover all accounts in the accounts directory.
     /// Returns Vec of (user_id, account_dir) tuples.
+    ///
+    /// Directories logged into the same user ID are listed once, with the latest
+    /// login, so that their data is not counted twice; a warning points to
+    /// `my merge`.
     pub fn discover_accounts() -> Result<Vec<(String, PathBuf)>> {
-        let data_root = resolve_data_root()?;
-        let accounts_root = data_root.join("accounts");
-
-        if !accounts_root.exists() {
-            return Ok(Vec::new());
+        let (accounts, duplicates) = group_accounts(scan_accounts()?);
+        for duplicate in &duplicates {
+            eprintln!(
+                "⚠️  Warning: {} is logged in from {} account directories, using the latest \
+                 login ({}). Run 'my merge' to merge them.",
+                duplicate.user_id,
+                duplicate.others.len() + 1,
+                duplicate.latest.display()
+            );
         }
+        Ok(accounts)
+    }
 
-        let mut accounts = Vec::new();
-        for entry in fs::read_dir(&accounts_root).context("Failed to read accounts directory")? {
-            let entry = entry?;
-            if entry.file_type()?.is_dir() {
-                let dirname = entry.file_name().to_string_lossy().to_string();
-                let uid = dirname.replace('_', ":");
-                let account_dir = entry.path();
-                accounts.push((uid, account_dir));
-            }
-        }
+    /// Number of accounts logged in, each user ID counted once
+    pub fn account_count() -> Result<usize> {
+        Ok(group_accounts(scan_accounts()?).0.len())
+    }
 
-        Ok(accounts)
+    /// Account directories logged into the same user ID
+    pub fn duplicate_accounts() -> Result<Vec<DuplicateAccount>> {
+        Ok(group_accounts(scan_accounts()?).1)
     }
 
     /// Select accounts based on user_id flag, account count, and preferences.
@@ -108,6 +126,9 @@ impl AccountSelector {
 
         // If user_id is specified, use only that account
         if let Some(uid) = user_id_flag {
+            if let Some(account) = all_accounts.iter().find(|(id, _)| *id == uid) {
+                return Ok(vec![account.clone()]);
+            }
             let data_root = resolve_data_root()?;
             let accounts_root = data_root.join("accounts");
             let dirname = account_id_to_dirname(&uid);
@@ -223,6 +244,81 @@ impl AccountSelector {
     }
 }
 
+/// Account directories with their user ID and login time.
+///
+/// The user ID comes from the session file written at login, else from the
+/// directory name.
+fn scan_accounts() -> Result<Vec<(String, PathBuf, Option<SystemTime>)>> {
+    let data_root = resolve_data_root()?;
+    let accounts_root = data_root.join("accounts");
+
+    if !accounts_root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut accounts = Vec::new();
+    for entry in fs::read_dir(&accounts_root).context("Failed to read accounts directory")? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            let dirname = entry.file_name().to_string_lossy().to_string();
+            let account_dir = entry.path();
+            let (uid, login_time) = match read_session(&account_dir) {
+                Some((session, login_time)) => (session.user_id, Some(login_time)),
+                None => (dirname.replace('_', ":"), None),
+            };
+            accounts.push((uid, account_dir, login_time));
+        }
+    }
+    accounts.sort_by(|a, b| a.1.cmp(&b.1));
+
+    Ok(accounts)
+}
+
+/// Session file of an account directory, with its modification time.
+fn read_session(account_dir: &Path) -> Option<(SessionMetaFile, SystemTime)> {
+    let path = account_dir.join("meta").join("session.json");
+    let contents = fs::read(&path).ok()?;
+    let session = serde_json::from_slice(&contents).ok()?;
+    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+    Some((session, modified))
+}
+
+/// Keeps one directory per user ID, the latest login, and lists the others.
+fn group_accounts(
+    accounts: Vec<(String, PathBuf, Option<SystemTime>)>,
+) -> (Vec<(String, PathBuf)>, Vec<DuplicateAccount>) {
+    let mut unique: Vec<(String, PathBuf, Option<SystemTime>)> = Vec::new();
+    let mut duplicates: Vec<DuplicateAccount> = Vec::new();
+
+    for (uid, dir, login_time) in accounts {
+        let Some(kept) = unique.iter_mut().find(|(id, _, _)| *id == uid) else {
+            unique.push((uid, dir, login_time));
+            continue;
+        };
+        let earlier = if login_time > kept.2 {
+            let earlier = std::mem::replace(&mut kept.1, dir);
+            kept.2 = login_time;
+            earlier
+        } else {
+            dir
+        };
+        match duplicates.iter_mut().find(|d| d.user_id == uid) {
+            Some(duplicate) => {
+                duplicate.latest = kept.1.clone();
+                duplicate.others.push(earlier);
+            }
+            None => duplicates.push(DuplicateAccount {
+                user_id: uid,
+                latest: kept.1.clone(),
+                others: vec![earlier],
+            }),
+        }
+    }
+
+    let unique = unique.into_iter().map(|(uid, dir, _)| (uid, dir)).collect();
+    (unique, duplicates)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -470,4 +566,52 @@ mod tests {
 
         env::remove_var("MY_DATA_DIR");
     }
+
+    #[test]
+    fn test_discover_accounts_merges_duplicate_logins() {
+        let _lock = ENV_MUTEX.lock().unwrap();
+        let temp_dir = setup_test_env(&["@alice:example.org", "@bob:example.com"]);
+        env::set_var("MY_DATA_DIR", temp_dir.path());
+
+        // A second login of alice, spelled after the server URL
+        let accounts_dir = temp_dir.path().join("accounts");
+        let earlier = accounts_dir.join("@alice_example.org");
+        let latest = accounts_dir.join("@alice_matrix.example.org");
+        fs::create_dir_all(latest.join("meta")).unwrap();
+        let now = SystemTime::now();
+        for (dir, age) in [(&earlier, 60), (&latest, 0)] {
+            let session = SessionMetaFile {
+                user_id: "@alice:example.org".to_string(),
+                device_id: "DEVICE".to_string(),
+                homeserver: "https://matrix.example.org".to_string(),
+            };
+            let path = dir.join("meta/session.json");
+            fs::write(&path, serde_json::to_vec(&session).unwrap()).unwrap();
+            let file = fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(now - std::time::Duration::from_secs(age))
+                .unwrap();
+        }
+
+        let accounts = AccountSelector::discover_accounts().unwrap();
+        assert_eq!(
+            accounts,
+            vec![
+                ("@alice:example.org".to_string(), latest.clone()),
+                (
+                    "@bob:example.com".to_string(),
+                    accounts_dir.join("@bob_example.com")
+                ),
+            ]
+        );
+        assert_eq!(
+            AccountSelector::duplicate_accounts().unwrap(),
+            vec![DuplicateAccount {
+                user_id: "@alice:example.org".to_string(),
+                latest,
+                others: vec![earlier],
+            }]
+        );
+
+        env::remove_var("MY_DATA_DIR");
+    }
 }
@@ -0,0 +1,34 @@
+/// Summary badges from saved stats.
+///
+/// Writes a small SVG (e.g. "2025 | 12,345 messages") to embed in a profile or
+/// README, without connecting to Matrix.
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::commands::export::load_saved_stats;
+use crate::commands::render::badge::{self, BadgeMetric};
+
+/// Writes the badge of `metric` for a previously crawled window into `output_dir`.
+///
+/// Returns the written path.
+pub fn run(
+    window: &str,
+    user_id_flag: Option<String>,
+    metric: BadgeMetric,
+    output_dir: &Path,
+) -> Result<PathBuf> {
+    let stats = load_saved_stats(window, user_id_flag)?;
+    let svg = badge::render(&stats, metric)?;
+
+    std::fs::create_dir_all(output_dir).with_context(|| {
+        format!(
+            "Failed to create output directory: {}",
+            output_dir.display()
+        )
+    })?;
+    let output_path = output_dir.join(badge::filename(&stats, metric));
+    std::fs::write(&output_path, svg)
+        .with_context(|| format!("Failed to write badge: {}", output_path.display()))?;
+
+    Ok(output_path)
+}
@@ -0,0 +1,499 @@
+/// Benchmark of the event processing pipeline.
+///
+/// Generates synthetic timeline events (messages, replies, reactions) and runs
+/// them through the same event processing and stats building as a crawl, so
+/// performance regressions in `crawl/pagination.rs` and `crawl/stats_builder.rs`
+/// are measurable without a homeserver. Reports throughput, allocation counts
+/// and the time spent in each metric collector.
+///
+/// Allocations are counted by `CountingAllocator`, the global allocator of
+/// the `my` binary when built with the `bench` feature; other builds report
+/// them as unavailable.
+use anyhow::{Context, Result};
+use matrix_sdk::deserialized_responses::TimelineEvent;
+use matrix_sdk::ruma::events::AnySyncTimelineEvent;
+use matrix_sdk::ruma::serde::Raw;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::json;
+#[cfg(feature = "bench")]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::crawl::metrics::{self, EventContext, MetricCollector};
+use super::crawl::stats_builder::{self, RoomStatsInput};
+use super::crawl::types::DetailedPaginationStats;
+use super::crawl::{pagination, CrawlOptions, RoomType};
+use crate::stats::Stats;
+use crate::window::WindowScope;
+
+/// Window the synthetic events are spread over.
+const BENCH_WINDOW: &str = "2025";
+
+const BENCH_USER_ID: &str = "@me:bench.example.org";
+
+/// Other members of each synthetic room.
+const FRIENDS: usize = 5;
+
+const WORDS: [&str; 16] = [
+    "hello", "matrix", "release", "coffee", "meeting", "tomorrow", "thanks", "great", "bug",
+    "fixed", "lunch", "weekend", "deploy", "review", "idea", "later",
+];
+
+const EMOJIS: [&str; 6] = ["👍", "❤️", "😂", "🎉", "👀", "🚀"];
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// System allocator that counts allocations (reallocations included).
+#[cfg(feature = "bench")]
+pub struct CountingAllocator;
+
+#[cfg(feature = "bench")]
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Benchmark parameters, set from CLI flags.
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    /// Synthetic events generated, spread evenly over the rooms
+    pub events: usize,
+    pub rooms: usize,
+    /// Seed of the event generator, for reproducible runs
+    pub seed: u64,
+}
+
+/// Time and allocations of a pipeline phase.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PhaseCost {
+    pub elapsed: Duration,
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+}
+
+impl PhaseCost {
+    /// Runs `f`, adding its time and allocations to this phase.
+    fn measure<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+        let start = Instant::now();
+        let result = f();
+        self.elapsed += start.elapsed();
+        self.allocations += ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+        self.allocated_bytes += ALLOCATED_BYTES.load(Ordering::Relaxed) - allocated_bytes;
+        result
+    }
+}
+
+/// Results of a benchmark run.
+#[derive(Debug)]
+pub struct BenchReport {
+    pub events: usize,
+    pub rooms: usize,
+    pub processing: PhaseCost,
+    pub building: PhaseCost,
+    /// Time spent in each metric collector, in collector order
+    pub metrics: Vec<(&'static str, Duration)>,
+    pub stats: Stats,
+}
+
+/// Runs the benchmark and prints its report.
+pub fn run(options: &BenchOptions) -> Result<()> {
+    eprintln!(
+        "🏁 Processing {} synthetic events in {} rooms (window {}, seed {})...",
+        group_digits(options.events as u64),
+        options.rooms,
+        BENCH_WINDOW,
+        options.seed
+    );
+    let report = measure(options)?;
+    println!("{}", format_report(&report));
+    Ok(())
+}
+
+/// Generates the synthetic events room by room and measures the pipeline.
+///
+/// Event generation is left out of the measures.
+pub fn measure(options: &BenchOptions) -> Result<BenchReport> {
+    anyhow::ensure!(options.rooms > 0, "The benchmark needs at least one room");
+
+    let window_scope = WindowScope::parse(BENCH_WINDOW).context("Failed to parse window")?;
+    let (window_start_ts, window_end_ts) = window_scope.to_timestamp_range();
+    let window_start_ts = window_start_ts.context("Benchmark window has no start")?;
+
+    let crawl_options = CrawlOptions::default();
+    let mut timers = Vec::new();
+    let collectors: Vec<Box<dyn MetricCollector>> = metrics::builtin_collectors(&crawl_options)
+        .into_iter()
+        .map(|inner| {
+            let nanos = Arc::new(AtomicU64::new(0));
+            timers.push((inner.name(), Arc::clone(&nanos)));
+            Box::new(TimedCollector { inner, nanos }) as Box<dyn MetricCollector>
+        })
+        .collect();
+
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let mut processing = PhaseCost::default();
+    let mut room_inputs = Vec::with_capacity(options.rooms);
+    for room_index in 0..options.rooms {
+        let count = options.events / options.rooms
+            + usize::from(room_index < options.events % options.rooms);
+        let room_id = format!("!room{}:bench.example.org", room_index);
+        let events = synthetic_events(&mut rng, &room_id, count, window_start_ts, window_end_ts)?;
+
+        let stats = processing.measure(|| {
+            pagination::collect_detailed_stats_from_events(
+                &events,
+                Some(window_start_ts),
+                window_end_ts,
+                BENCH_USER_ID,
+                &room_id,
+                &collectors,
+            )
+        });
+        room_inputs.push(RoomStatsInput {
+            room_id,
+            room_name: Some(format!("Room {}", room_index + 1)),
+            room_type: [RoomType::Dm, RoomType::Private, RoomType::Public][room_index % 3],
+            stats,
+        });
+    }
+
+    let mut building = PhaseCost::default();
+    let stats = building.measure(|| {
+        stats_builder::build_stats(
+            &room_inputs,
+            BENCH_USER_ID,
+            None,
+            None,
+            &window_scope,
+            room_inputs.len(),
+            crawl_options.top,
+            &collectors,
+        )
+    })?;
+
+    Ok(BenchReport {
+        events: options.events,
+        rooms: options.rooms,
+        processing,
+        building,
+        metrics: timers
+            .into_iter()
+            .map(|(name, nanos)| (name, Duration::from_nanos(nanos.load(Ordering::Relaxed))))
+            .collect(),
+        stats,
+    })
+}
+
+/// Collector that times the calls to the collector it wraps.
+struct TimedCollector {
+    inner: Box<dyn MetricCollector>,
+    nanos: Arc<AtomicU64>,
+}
+
+impl TimedCollector {
+    fn time<T>(&self, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.nanos
+            .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        result
+    }
+}
+
+impl MetricCollector for TimedCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        self.time(|| self.inner.on_event(event, ctx, room))
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        self.time(|| self.inner.aggregate(rooms, stats))
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
+
+/// Generates `count` events of a room, evenly spread over the window.
+///
+/// About half of the messages are sent by the benchmark user; a fifth of the
+/// events are replies and a fifth are reactions to recent messages.
+fn synthetic_events(
+    rng: &mut StdRng,
+    room_id: &str,
+    count: usize,
+    window_start_ts: i64,
+    window_end_ts: i64,
+) -> Result<Vec<TimelineEvent>> {
+    let step = (window_end_ts - window_start_ts) / (count as i64 + 1);
+    let mut recent: Vec<String> = Vec::new();
+    let mut events = Vec::with_capacity(count);
+
+    for i in 0..count {
+        let event_id = format!("${}-{}", room_id.trim_start_matches('!'), i);
+        let sender = if rng.gen_bool(0.5) {
+            BENCH_USER_ID.to_string()
+        } else {
+            format!("@friend{}:bench.example.org", rng.gen_range(0..FRIENDS))
+        };
+        let ts = window_start_ts + step * (i as i64 + 1);
+        let target = (!recent.is_empty()).then(|| recent[rng.gen_range(0..recent.len())].clone());
+
+        let kind: f64 = rng.gen();
+        let content = match target {
+            Some(target) if kind < 0.2 => {
+                let key = EMOJIS[rng.gen_range(0..EMOJIS.len())];
+                let event = json!({
+                    "type": "m.reaction",
+                    "event_id": event_id,
+                    "sender": sender,
+                    "origin_server_ts": ts,
+                    "content": {
+                        "m.relates_to": { "rel_type": "m.annotation", "event_id": target, "key": key }
+                    }
+                });
+                events.push(timeline_event(&event)?);
+                continue;
+            }
+            Some(target) if kind < 0.4 => json!({
+                "msgtype": "m.text",
+                "body": sentence(rng),
+                "m.relates_to": { "m.in_reply_to": { "event_id": target } }
+            }),
+            _ => json!({ "msgtype": "m.text", "body": sentence(rng) }),
+        };
+
+        let event = json!({
+            "type": "m.room.message",
+            "event_id": event_id,
+            "sender": sender,
+            "origin_server_ts": ts,
+            "content": content,
+        });
+        events.push(timeline_event(&event)?);
+
+        recent.push(event_id);
+        if recent.len() > 20 {
+            recent.remove(0);
+        }
+    }
+
+    Ok(events)
+}
+
+fn timeline_event(event: &serde_json::Value) -> Result<TimelineEvent> {
+    let raw = serde_json::value::to_raw_value(event).context("Failed to encode event")?;
+    Ok(TimelineEvent::from_plaintext(
+        Raw::<AnySyncTimelineEvent>::from_json(raw),
+    ))
+}
+
+fn sentence(rng: &mut StdRng) -> String {
+    let words = rng.gen_range(3..12);
+    (0..words)
+        .map(|_| WORDS[rng.gen_range(0..WORDS.len())])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Formats the report printed by `my bench`.
+pub fn format_report(report: &BenchReport) -> String {
+    let total = report.processing.elapsed + report.building.elapsed;
+    let throughput = report.events as f64 / report.processing.elapsed.as_secs_f64().max(1e-9);
+    let counted = cfg!(feature = "bench");
+
+    let mut lines = vec![
+        format!(
+            "📊 {} events in {} rooms ({} messages sent by the user)",
+            group_digits(report.events as u64),
+            report.rooms,
+            group_digits(report.stats.summary.messages_sent.get() as u64)
+        ),
+        String::new(),
+        phase_line("Event processing", &report.processing, counted),
+        format!(
+            "  {:<18} {:>9} events/s",
+            "Throughput",
+            group_digits(throughput as u64)
+        ),
+        phase_line("Stats building", &report.building, counted),
+        phase_line(
+            "Total",
+            &PhaseCost {
+                elapsed: total,
+                allocations: report.processing.allocations + report.building.allocations,
+                allocated_bytes: report.processing.allocated_bytes
+                    + report.building.allocated_bytes,
+            },
+            counted,
+        ),
+        String::new(),
+        "Per-metric cost (time in the collector, share of total):".to_string(),
+    ];
+
+    let metrics_time: Duration = report.metrics.iter().map(|(_, elapsed)| *elapsed).sum();
+    for (name, elapsed) in &report.metrics {
+        lines.push(metric_line(name, *elapsed, total));
+    }
+    lines.push(metric_line(
+        "Core pipeline",
+        total.saturating_sub(metrics_time),
+        total,
+    ));
+
+    if !counted {
+        lines.push(String::new());
+        lines.push(
+            "Allocations are not counted in this build: build with `--features bench`.".to_string(),
+        );
+    }
+    lines.join("\n")
+}
+
+fn phase_line(label: &str, cost: &PhaseCost, counted: bool) -> String {
+    let mut line = format!("  {:<18} {:>9.3} s", label, cost.elapsed.as_secs_f64());
+    if counted {
+        line.push_str(&format!(
+            "   {} allocations ({})",
+            group_digits(cost.allocations),
+            format_bytes(cost.allocated_bytes)
+        ));
+    }
+    line
+}
+
+fn metric_line(name: &str, elapsed: Duration, total: Duration) -> String {
+    let share = elapsed.as_secs_f64() / total.as_secs_f64().max(1e-9) * 100.0;
+    format!(
+        "  {:<24} {:>9.3} s {:>6.1}%",
+        name,
+        elapsed.as_secs_f64(),
+        share
+    )
+}
+
+/// Parses an event count, with an optional `k` or `M` suffix (e.g. 250k, 1M).
+pub fn parse_count(value: &str) -> Result<usize, String> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.strip_suffix(['k', 'K']) {
+        Some(digits) => (digits, 1_000),
+        None => match value.strip_suffix('M') {
+            Some(digits) => (digits, 1_000_000),
+            None => (value, 1),
+        },
+    };
+    match digits.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(count * multiplier),
+        _ => Err(format!(
+            "expected a number of events such as 1000, 250k or 1M, got '{}'",
+            value
+        )),
+    }
+}
+
+/// Formats an integer with thousands separators (12,345).
+fn group_digits(n: u64) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_count() {
+        assert_eq!(parse_count("1000"), Ok(1000));
+        assert_eq!(parse_count("250k"), Ok(250_000));
+        assert_eq!(parse_count("1M"), Ok(1_000_000));
+        assert!(parse_count("0").is_err());
+        assert!(parse_count("1G").is_err());
+        assert!(parse_count("many").is_err());
+    }
+
+    #[test]
+    fn test_measure() {
+        let options = BenchOptions {
+            events: 2_000,
+            rooms: 3,
+            seed: 7,
+        };
+        let report = measure(&options).unwrap();
+        let again = measure(&options).unwrap();
+
+        assert!(report.stats.summary.messages_sent.get() > 0);
+        assert_eq!(report.stats.summary.active_rooms.get(), 3);
+        // Same seed, same events
+        assert_eq!(
+            report.stats.summary.messages_sent,
+            again.stats.summary.messages_sent
+        );
+        let names: Vec<&str> = report.metrics.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names[0], "ReactionCollector");
+        // One measure per collector, plugins included
+        assert_eq!(
+            names.len(),
+            metrics::builtin_collectors(&CrawlOptions::default()).len()
+        );
+        assert!(format_report(&report).contains("Per-metric cost"));
+    }
+
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(1_234_567), "1,234,567");
+    }
+}
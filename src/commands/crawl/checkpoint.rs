@@ -0,0 +1,292 @@
+/// Checkpoints of long-running crawls.
+///
+/// Once the rooms of a crawl have been paginated for the checkpoint interval,
+/// and then at every interval, a summary of the progress (rooms done, events so
+/// far, estimated time left) goes to the crawl log and, when configured, to a
+/// webhook and as a notice in a Matrix room, so that unattended crawls can be
+/// followed remotely. A checkpoint that cannot be delivered is logged and the
+/// crawl goes on.
+use anyhow::{Context, Result};
+use matrix_sdk::ruma::events::room::message::RoomMessageEventContent;
+use matrix_sdk::{Client, RoomState};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tokio::time::{Interval, MissedTickBehavior};
+use url::Url;
+
+use super::progress::CrawlProgress;
+use crate::commands::render::i18n::Lang;
+use crate::commands::share::resolve_room_id;
+
+/// Time a webhook has to answer before the checkpoint is given up.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where and how often checkpoints are sent, set from CLI flags.
+#[derive(Debug, Clone)]
+pub struct CheckpointOptions {
+    /// Time between checkpoints; crawls shorter than that send none
+    pub every: Duration,
+    /// URL each checkpoint is POSTed to as JSON
+    pub webhook: Option<Url>,
+    /// Room (ID or alias) each checkpoint is sent to as a notice, by the crawled account
+    pub notice_room: Option<String>,
+}
+
+/// Parses an interval such as `90s`, `30m` or `2h`; a bare number is minutes.
+pub fn parse_interval(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let (number, unit_seconds) = match input.char_indices().last() {
+        Some((i, 's')) => (&input[..i], 1),
+        Some((i, 'm')) => (&input[..i], 60),
+        Some((i, 'h')) => (&input[..i], 3600),
+        _ => (input, 60),
+    };
+    let count: u64 = number
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid interval: {} (expected e.g. 90s, 30m or 2h)", input))?;
+    if count == 0 {
+        anyhow::bail!("The checkpoint interval must be longer than 0");
+    }
+    let seconds = count
+        .checked_mul(unit_seconds)
+        .with_context(|| format!("Checkpoint interval too long: {}", input))?;
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Parses a webhook URL, which must be http or https.
+pub fn parse_webhook(input: &str) -> Result<Url> {
+    let url = Url::parse(input).with_context(|| format!("Invalid webhook URL: {}", input))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        anyhow::bail!("Webhook URLs must be http or https: {}", input);
+    }
+    Ok(url)
+}
+
+/// Progress of a crawl at a checkpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub elapsed: Duration,
+    /// Rooms crawled or failed
+    pub rooms_done: usize,
+    pub rooms_total: usize,
+    /// Events fetched so far, including those of the rooms in flight
+    pub events: usize,
+}
+
+impl Checkpoint {
+    /// Time left at the pace of the rooms done so far, unknown before the
+    /// first room is done.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.rooms_done == 0 {
+            return None;
+        }
+        let remaining = self.rooms_total.saturating_sub(self.rooms_done);
+        let ratio = remaining as f64 / self.rooms_done as f64;
+        Some(self.elapsed.mul_f64(ratio))
+    }
+
+    /// One-line summary, e.g. `⏱️  Checkpoint after 2 hr: 120/480 rooms, 356208 events, about 6 hr left`.
+    pub fn line(&self) -> String {
+        let t = Lang::En.locale();
+        let left = match self.eta() {
+            Some(eta) => format!("about {} left", t.duration(eta.as_secs() as i64)),
+            None => "time left not known yet".to_string(),
+        };
+        format!(
+            "⏱️  Checkpoint after {}: {}/{} rooms, {} events, {}",
+            t.duration(self.elapsed.as_secs() as i64),
+            self.rooms_done,
+            self.rooms_total,
+            self.events,
+            left
+        )
+    }
+
+    /// Webhook body. `text` holds the summary line, for chat webhooks that
+    /// show it as is.
+    pub fn payload(&self, account_id: &str, window_key: &str) -> Value {
+        json!({
+            "account": account_id,
+            "window": window_key,
+            "elapsed_seconds": self.elapsed.as_secs(),
+            "rooms_done": self.rooms_done,
+            "rooms_total": self.rooms_total,
+            "events": self.events,
+            "eta_seconds": self.eta().map(|eta| eta.as_secs()),
+            "text": self.line(),
+        })
+    }
+}
+
+/// Sends the checkpoints of one account's crawl.
+pub struct Checkpointer {
+    options: CheckpointOptions,
+    client: Client,
+    account_id: String,
+    window_key: String,
+    started: Instant,
+    interval: Interval,
+}
+
+impl Checkpointer {
+    /// Starts counting from now: the first checkpoint is due after
+    /// `options.every`. Must be called from within a Tokio runtime.
+    pub fn new(
+        options: CheckpointOptions,
+        client: Client,
+        account_id: &str,
+        window_key: &str,
+    ) -> Self {
+        let started = Instant::now();
+        let mut interval = tokio::time::interval_at(
+            tokio::time::Instant::from_std(started + options.every),
+            options.every,
+        );
+        // A checkpoint delayed by a busy crawl does not make the next ones closer
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            options,
+            client,
+            account_id: account_id.to_string(),
+            window_key: window_key.to_string(),
+            started,
+            interval,
+        }
+    }
+
+    /// Waits until the next checkpoint is due.
+    pub async fn tick(&mut self) {
+        self.interval.tick().await;
+    }
+
+    /// Sends the checkpoint of the crawl progress to the log, the webhook and
+    /// the notice room.
+    pub async fn send(&self, rooms_done: usize, rooms_total: usize, progress: &CrawlProgress) {
+        let checkpoint = Checkpoint {
+            elapsed: self.started.elapsed(),
+            rooms_done,
+            rooms_total,
+            events: progress.events(),
+        };
+        progress.println(&checkpoint.line());
+
+        if let Some(ref webhook) = self.options.webhook {
+            if let Err(e) = self.post(webhook, &checkpoint).await {
+                progress.println(&format!(
+                    "Warning: checkpoint not sent to the webhook: {:#}",
+                    e
+                ));
+            }
+        }
+        if let Some(ref room) = self.options.notice_room {
+            if let Err(e) = self.notify(room, &checkpoint).await {
+                progress.println(&format!(
+                    "Warning: checkpoint not sent to {}: {:#}",
+                    room, e
+                ));
+            }
+        }
+    }
+
+    async fn post(&self, webhook: &Url, checkpoint: &Checkpoint) -> Result<()> {
+        self.client
+            .http_client()
+            .post(webhook.as_str())
+            .header("Content-Type", "application/json")
+            .body(
+                checkpoint
+                    .payload(&self.account_id, &self.window_key)
+                    .to_string(),
+            )
+            .timeout(WEBHOOK_TIMEOUT)
+            .send()
+            .await
+            .context("Request failed")?
+            .error_for_status()
+            .context("Webhook refused the checkpoint")?;
+        Ok(())
+    }
+
+    async fn notify(&self, room: &str, checkpoint: &Checkpoint) -> Result<()> {
+        let room_id = resolve_room_id(&self.client, room).await?;
+        let Some(joined) = self
+            .client
+            .get_room(&room_id)
+            .filter(|joined| joined.state() == RoomState::Joined)
+        else {
+            anyhow::bail!("{} has not joined {}", self.account_id, room);
+        };
+        let text = format!(
+            "{} · {} › {}",
+            checkpoint.line(),
+            self.account_id,
+            self.window_key
+        );
+        joined
+            .send(RoomMessageEventContent::notice_plain(text))
+            .await
+            .context("Failed to send the notice")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("90s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_interval("30m").unwrap(), Duration::from_secs(30 * 60));
+        assert_eq!(
+            parse_interval(" 2h ").unwrap(),
+            Duration::from_secs(2 * 3600)
+        );
+        assert_eq!(parse_interval("45").unwrap(), Duration::from_secs(45 * 60));
+        assert!(parse_interval("0m").is_err());
+        assert!(parse_interval("soon").is_err());
+        assert!(parse_interval("1d").is_err());
+        assert!(parse_interval("99999999999999999h").is_err());
+    }
+
+    #[test]
+    fn test_parse_webhook() {
+        assert!(parse_webhook("https://hooks.example.org/crawl").is_ok());
+        assert!(parse_webhook("ftp://example.org/crawl").is_err());
+        assert!(parse_webhook("not a url").is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_line() {
+        let checkpoint = Checkpoint {
+            elapsed: Duration::from_secs(2 * 3600),
+            rooms_done: 120,
+            rooms_total: 480,
+            events: 356_208,
+        };
+        assert_eq!(checkpoint.eta(), Some(Duration::from_secs(6 * 3600)));
+        assert_eq!(
+            checkpoint.line(),
+            "⏱️  Checkpoint after 2 hr: 120/480 rooms, 356208 events, about 6 hr left"
+        );
+        let payload = checkpoint.payload("@alice:example.org", "life");
+        assert_eq!(payload["eta_seconds"], 6 * 3600);
+        assert_eq!(payload["window"], "life");
+    }
+
+    #[test]
+    fn test_checkpoint_before_first_room() {
+        let checkpoint = Checkpoint {
+            elapsed: Duration::from_secs(1800),
+            rooms_done: 0,
+            rooms_total: 12,
+            events: 4_000,
+        };
+        assert_eq!(checkpoint.eta(), None);
+        assert!(checkpoint
+            .line()
+            .ends_with("4000 events, time left not known yet"));
+        assert!(checkpoint.payload("@alice:example.org", "2025")["eta_seconds"].is_null());
+    }
+}
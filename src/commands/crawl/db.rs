@@ -5,6 +5,8 @@ use anyhow::{Context, Result};
 use rusqlite::{params, Connection, OptionalExtension};
 use std::path::Path;
 
+use super::types::{RoomInfo, RoomJoinState};
+
 /// Time window available from crawled data
 #[derive(Debug, Clone)]
 pub struct TimeWindow {
@@ -12,10 +14,72 @@ pub struct TimeWindow {
     pub window_start: Option<i64>,
     /// Window end: newest (latest) message across all rooms
     pub window_end: Option<i64>,
-    /// Account creation: oldest message across all rooms
+    /// Account creation: stored creation date if known, else oldest message across all rooms
     pub account_creation_ts: Option<i64>,
 }
 
+/// Where the account creation timestamp comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreationSource {
+    /// Reported by the homeserver
+    Server,
+    /// Estimated from the oldest event of fully crawled rooms
+    Events,
+}
+
+impl CreationSource {
+    /// Convert to database string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Server => "server",
+            Self::Events => "events",
+        }
+    }
+}
+
+/// Account creation timestamp and how it was obtained
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountCreation {
+    /// Unix timestamp in milliseconds
+    pub ts: i64,
+    pub source: CreationSource,
+}
+
+/// Room list from the latest discovery (sliding sync or its regular sync fallback)
+#[derive(Debug, Clone)]
+pub struct CachedRoomList {
+    /// When the room list was discovered (Unix timestamp in milliseconds)
+    pub discovered_ts: i64,
+    pub rooms: Vec<RoomInfo>,
+}
+
+/// Progress of the latest crawl of a window, for `my render --live`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveCrawl {
+    pub window_key: String,
+    /// When the crawl started (Unix timestamp in milliseconds)
+    pub started_ts: i64,
+    /// Joined rooms of the account, whether crawled or not
+    pub joined_rooms: usize,
+    /// Rooms the crawl paginates
+    pub rooms_total: usize,
+    /// Rooms whose stats are stored so far
+    pub rooms_done: usize,
+    /// When the crawl completed, if it did (Unix timestamp in milliseconds)
+    pub finished_ts: Option<i64>,
+}
+
+/// Stats of a room stored while a crawl runs, or for a year or month of a life crawl
+#[derive(Debug, Clone)]
+pub struct LiveRoomStats {
+    pub room_id: String,
+    pub room_name: Option<String>,
+    /// Room type key (dm, public, private)
+    pub room_type: String,
+    /// Serialized pagination stats
+    pub stats_json: String,
+}
+
 /// Crawl status for a room
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CrawlStatus {
@@ -58,7 +122,9 @@ pub struct RoomCrawlMetadata {
     pub oldest_event_ts: Option<i64>,    // Unix timestamp in milliseconds
     pub newest_event_id: Option<String>, // Event ID of the newest message crawled
     pub newest_event_ts: Option<i64>,    // Unix timestamp in milliseconds
-    pub fully_crawled: bool,             // True if back-paginated to room creation
+    pub fully_crawled: bool,             // True if back-paginated to the start of visible history
+    pub history_limited: bool,           // True if that start was a history visibility boundary
+    pub joined_at_ts: Option<i64>,       // User's join, when history visibility starts there
     pub total_events_fetched: usize,     // Cumulative count of events fetched across all crawls
     pub user_events_fetched: usize,      // Cumulative count of user's messages fetched
     pub last_crawl_status: Option<CrawlStatus>, // Status of last crawl operation
@@ -77,6 +143,9 @@ impl CrawlDb {
         let db_path = account_dir.join("db.sqlite");
         let conn = Connection::open(&db_path)
             .with_context(|| format!("Failed to open database at {}", db_path.display()))?;
+        // `my render --live` reads while a crawl writes
+        conn.busy_timeout(std::time::Duration::from_secs(5))
+            .context("Failed to set database busy timeout")?;
 
         // Create schema if it doesn't exist
         conn.execute(
@@ -87,6 +156,8 @@ impl CrawlDb {
                 newest_event_id TEXT,
                 newest_event_ts INTEGER,
                 fully_crawled INTEGER NOT NULL DEFAULT 0,
+                history_limited INTEGER NOT NULL DEFAULT 0,
+                joined_at_ts INTEGER,
                 total_events_fetched INTEGER NOT NULL DEFAULT 0,
                 user_events_fetched INTEGER NOT NULL DEFAULT 0,
                 last_crawl_status TEXT,
@@ -96,6 +167,73 @@ impl CrawlDb {
         )
         .context("Failed to create room_crawl_metadata table")?;
 
+        // Single-row table of account-level metadata
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS account_metadata (
+                id INTEGER NOT NULL PRIMARY KEY CHECK (id = 1),
+                created_ts INTEGER,
+                created_source TEXT
+            )",
+            [],
+        )
+        .context("Failed to create account_metadata table")?;
+
+        // Latest discovery results, so rooms can be listed without syncing
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS room_list_cache (
+                room_id TEXT NOT NULL PRIMARY KEY,
+                join_state TEXT NOT NULL,
+                last_event_id TEXT,
+                last_event_ts INTEGER,
+                discovered_ts INTEGER NOT NULL
+            )",
+            [],
+        )
+        .context("Failed to create room_list_cache table")?;
+
+        // Crawls in progress and the stats of their rooms crawled so far
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS live_crawls (
+                window_key TEXT NOT NULL PRIMARY KEY,
+                started_ts INTEGER NOT NULL,
+                joined_rooms INTEGER NOT NULL,
+                rooms_total INTEGER NOT NULL,
+                finished_ts INTEGER
+            )",
+            [],
+        )
+        .context("Failed to create live_crawls table")?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS live_room_stats (
+                window_key TEXT NOT NULL,
+                room_id TEXT NOT NULL,
+                room_name TEXT,
+                room_type TEXT NOT NULL,
+                stats_json TEXT NOT NULL,
+                PRIMARY KEY (window_key, room_id)
+            )",
+            [],
+        )
+        .context("Failed to create live_room_stats table")?;
+
+        // Stats of each year and month of the rooms, kept by life crawls (`my render-all`)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS period_room_stats (
+                period TEXT NOT NULL,
+                room_id TEXT NOT NULL,
+                room_name TEXT,
+                room_type TEXT NOT NULL,
+                stats_json TEXT NOT NULL,
+                PRIMARY KEY (period, room_id)
+            )",
+            [],
+        )
+        .context("Failed to create period_room_stats table")?;
+
+        // Databases created before history visibility tracking lack these columns
+        add_column_if_missing(&conn, "history_limited", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "joined_at_ts", "INTEGER")?;
+
         Ok(Self { conn })
     }
 
@@ -138,12 +276,32 @@ impl CrawlDb {
         Ok(())
     }
 
+    /// Record whether the start of a room's visible history is a history visibility
+    /// boundary (true) or the room creation (false), and when the user joined if known
+    pub fn set_history_limited(
+        &self,
+        room_id: &str,
+        history_limited: bool,
+        joined_at_ts: Option<i64>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO room_crawl_metadata (room_id, history_limited, joined_at_ts)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(room_id) DO UPDATE SET
+                history_limited = excluded.history_limited,
+                joined_at_ts = excluded.joined_at_ts",
+            params![room_id, history_limited, joined_at_ts],
+        )?;
+        Ok(())
+    }
+
     /// Get crawl metadata for a room
     #[allow(dead_code)]
     pub fn get_room_metadata(&self, room_id: &str) -> Result<Option<RoomCrawlMetadata>> {
         let mut stmt = self.conn.prepare(
             "SELECT room_id, oldest_event_id, oldest_event_ts, newest_event_id, newest_event_ts, fully_crawled,
-                    total_events_fetched, user_events_fetched, last_crawl_status, last_crawl_error
+                    total_events_fetched, user_events_fetched, last_crawl_status, last_crawl_error,
+                    history_limited, joined_at_ts
              FROM room_crawl_metadata
              WHERE room_id = ?1",
         )?;
@@ -167,6 +325,8 @@ impl CrawlDb {
                     newest_event_id: row.get(3)?,
                     newest_event_ts: row.get(4)?,
                     fully_crawled: row.get(5)?,
+                    history_limited: row.get(10)?,
+                    joined_at_ts: row.get(11)?,
                     total_events_fetched: row.get(6)?,
                     user_events_fetched: row.get(7)?,
                     last_crawl_status: status,
@@ -186,7 +346,7 @@ impl CrawlDb {
         Ok(count)
     }
 
-    /// Get the number of rooms that have been crawled back to creation
+    /// Get the number of rooms that have been crawled back to the start of visible history
     pub fn fully_crawled_room_count(&self) -> Result<usize> {
         let mut stmt = self
             .conn
@@ -195,6 +355,16 @@ impl CrawlDb {
         Ok(count)
     }
 
+    /// Get the number of rooms whose crawl stopped at a history visibility boundary
+    /// instead of the room creation
+    pub fn history_limited_room_count(&self) -> Result<usize> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COUNT(*) FROM room_crawl_metadata WHERE fully_crawled = 1 AND history_limited = 1",
+        )?;
+        let count: usize = stmt.query_row([], |row| row.get(0))?;
+        Ok(count)
+    }
+
     /// Get the global time window available from crawled data
     ///
     /// Window start logic:
@@ -237,13 +407,18 @@ impl CrawlDb {
         )?;
         let window_end: Option<i64> = stmt.query_row([], |row| row.get(0))?;
 
-        // Account creation: oldest message across all rooms
-        let mut stmt = self.conn.prepare(
-            "SELECT MIN(oldest_event_ts)
-             FROM room_crawl_metadata
-             WHERE oldest_event_ts IS NOT NULL",
-        )?;
-        let account_creation_ts: Option<i64> = stmt.query_row([], |row| row.get(0))?;
+        // Account creation: stored date if known, else oldest message across all rooms
+        let account_creation_ts = match self.get_account_creation()? {
+            Some(creation) => Some(creation.ts),
+            None => {
+                let mut stmt = self.conn.prepare(
+                    "SELECT MIN(oldest_event_ts)
+                     FROM room_crawl_metadata
+                     WHERE oldest_event_ts IS NOT NULL",
+                )?;
+                stmt.query_row([], |row| row.get(0))?
+            }
+        };
 
         Ok(Some(TimeWindow {
             window_start,
@@ -252,6 +427,291 @@ impl CrawlDb {
         }))
     }
 
+    /// Get the stored account creation timestamp
+    pub fn get_account_creation(&self) -> Result<Option<AccountCreation>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT created_ts, created_source FROM account_metadata
+             WHERE id = 1 AND created_ts IS NOT NULL",
+        )?;
+        let result = stmt
+            .query_row([], |row| {
+                let source: Option<String> = row.get(1)?;
+                Ok(AccountCreation {
+                    ts: row.get(0)?,
+                    source: match source.as_deref() {
+                        Some("server") => CreationSource::Server,
+                        _ => CreationSource::Events,
+                    },
+                })
+            })
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Store the account creation timestamp
+    ///
+    /// A value from the homeserver always replaces the stored one. An estimate from
+    /// events never replaces a homeserver value, and only moves the date earlier.
+    pub fn set_account_creation(&self, creation: &AccountCreation) -> Result<()> {
+        if creation.source == CreationSource::Events {
+            if let Some(stored) = self.get_account_creation()? {
+                if stored.source == CreationSource::Server || stored.ts <= creation.ts {
+                    return Ok(());
+                }
+            }
+        }
+        self.conn.execute(
+            "INSERT INTO account_metadata (id, created_ts, created_source)
+             VALUES (1, ?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET
+                created_ts = excluded.created_ts,
+                created_source = excluded.created_source",
+            params![creation.ts, creation.source.as_str()],
+        )?;
+        Ok(())
+    }
+
+    /// Estimate the account creation from the oldest event across fully crawled rooms
+    ///
+    /// Uses the user's join when the room history starts there. Rooms that are not
+    /// fully crawled are ignored: their oldest crawled event says nothing about
+    /// when the account joined them.
+    pub fn estimate_account_creation_ts(&self) -> Result<Option<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT MIN(COALESCE(joined_at_ts, oldest_event_ts))
+             FROM room_crawl_metadata
+             WHERE fully_crawled = 1",
+        )?;
+        let ts: Option<i64> = stmt.query_row([], |row| row.get(0))?;
+        Ok(ts)
+    }
+
+    /// Replace the cached room list with the latest discovery results
+    pub fn replace_room_list(&self, rooms: &[RoomInfo], discovered_ts: i64) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM room_list_cache", [])?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO room_list_cache
+                    (room_id, join_state, last_event_id, last_event_ts, discovered_ts)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for room in rooms {
+                stmt.execute(params![
+                    room.room_id,
+                    room.join_state.as_str(),
+                    room.last_event_id,
+                    room.last_event_ts,
+                    discovered_ts
+                ])?;
+            }
+        }
+        tx.commit().context("Failed to store the room list")?;
+        Ok(())
+    }
+
+    /// Get the room list cached by the latest discovery, if any
+    pub fn get_room_list(&self) -> Result<Option<CachedRoomList>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT room_id, join_state, last_event_id, last_event_ts, discovered_ts
+             FROM room_list_cache
+             ORDER BY room_id",
+        )?;
+        let mut discovered_ts = None;
+        let mut rooms = Vec::new();
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let join_state: String = row.get(1)?;
+            let Some(join_state) = RoomJoinState::parse(&join_state) else {
+                continue;
+            };
+            discovered_ts = Some(row.get::<_, i64>(4)?);
+            rooms.push(RoomInfo {
+                room_id: row.get(0)?,
+                last_event_id: row.get(2)?,
+                last_event_ts: row.get(3)?,
+                join_state,
+            });
+        }
+        Ok(discovered_ts.map(|discovered_ts| CachedRoomList {
+            discovered_ts,
+            rooms,
+        }))
+    }
+
+    /// Record the start of a crawl of `window_key`, dropping the rooms stored
+    /// by any earlier crawl of it
+    pub fn start_live_crawl(
+        &self,
+        window_key: &str,
+        joined_rooms: usize,
+        rooms_total: usize,
+        started_ts: i64,
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM live_room_stats WHERE window_key = ?1",
+            params![window_key],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO live_crawls
+                (window_key, started_ts, joined_rooms, rooms_total, finished_ts)
+             VALUES (?1, ?2, ?3, ?4, NULL)",
+            params![window_key, started_ts, joined_rooms, rooms_total],
+        )?;
+        tx.commit().context("Failed to record the crawl start")?;
+        Ok(())
+    }
+
+    /// Store the stats of a room crawled by the running crawl of `window_key`
+    pub fn save_live_room_stats(&self, window_key: &str, room: &LiveRoomStats) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO live_room_stats
+                    (window_key, room_id, room_name, room_type, stats_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    window_key,
+                    room.room_id,
+                    room.room_name,
+                    room.room_type,
+                    room.stats_json
+                ],
+            )
+            .context("Failed to store live room stats")?;
+        Ok(())
+    }
+
+    /// Record the end of the crawl of `window_key`: its stats file now has
+    /// everything, so the stored rooms are dropped
+    pub fn finish_live_crawl(&self, window_key: &str, finished_ts: i64) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM live_room_stats WHERE window_key = ?1",
+            params![window_key],
+        )?;
+        tx.execute(
+            "UPDATE live_crawls SET finished_ts = ?2 WHERE window_key = ?1",
+            params![window_key, finished_ts],
+        )?;
+        tx.commit().context("Failed to record the crawl end")?;
+        Ok(())
+    }
+
+    /// Get the crawl of `window_key`, or the latest started one when `None`
+    pub fn get_live_crawl(&self, window_key: Option<&str>) -> Result<Option<LiveCrawl>> {
+        let crawl = self
+            .conn
+            .query_row(
+                "SELECT c.window_key, c.started_ts, c.joined_rooms, c.rooms_total, c.finished_ts,
+                    (SELECT COUNT(*) FROM live_room_stats s WHERE s.window_key = c.window_key)
+                 FROM live_crawls c
+                 WHERE ?1 IS NULL OR c.window_key = ?1
+                 ORDER BY c.started_ts DESC
+                 LIMIT 1",
+                params![window_key],
+                |row| {
+                    Ok(LiveCrawl {
+                        window_key: row.get(0)?,
+                        started_ts: row.get(1)?,
+                        joined_rooms: row.get::<_, i64>(2)? as usize,
+                        rooms_total: row.get::<_, i64>(3)? as usize,
+                        finished_ts: row.get(4)?,
+                        rooms_done: row.get::<_, i64>(5)? as usize,
+                    })
+                },
+            )
+            .optional()
+            .context("Failed to read live crawl")?;
+        Ok(crawl)
+    }
+
+    /// Get the room stats stored so far by the crawl of `window_key`
+    pub fn get_live_room_stats(&self, window_key: &str) -> Result<Vec<LiveRoomStats>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT room_id, room_name, room_type, stats_json
+             FROM live_room_stats
+             WHERE window_key = ?1
+             ORDER BY room_id",
+        )?;
+        let rooms = stmt
+            .query_map(params![window_key], |row| {
+                Ok(LiveRoomStats {
+                    room_id: row.get(0)?,
+                    room_name: row.get(1)?,
+                    room_type: row.get(2)?,
+                    stats_json: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read live room stats")?;
+        Ok(rooms)
+    }
+
+    /// Replace the stats of the years and months of a room with `periods`,
+    /// pairs of a window key (`2024`, `2024-03`) and the room stats in it
+    pub fn replace_period_room_stats(
+        &self,
+        room_id: &str,
+        periods: &[(String, LiveRoomStats)],
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM period_room_stats WHERE room_id = ?1",
+            params![room_id],
+        )?;
+        for (period, room) in periods {
+            tx.execute(
+                "INSERT INTO period_room_stats
+                    (period, room_id, room_name, room_type, stats_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    period,
+                    room.room_id,
+                    room.room_name,
+                    room.room_type,
+                    room.stats_json
+                ],
+            )?;
+        }
+        tx.commit().context("Failed to store period room stats")?;
+        Ok(())
+    }
+
+    /// Get the years and months with stored room stats, in order
+    pub fn get_periods(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT period FROM period_room_stats ORDER BY period")?;
+        let periods = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()
+            .context("Failed to read periods")?;
+        Ok(periods)
+    }
+
+    /// Get the room stats stored for `period` (`2024`, `2024-03`)
+    pub fn get_period_room_stats(&self, period: &str) -> Result<Vec<LiveRoomStats>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT room_id, room_name, room_type, stats_json
+             FROM period_room_stats
+             WHERE period = ?1
+             ORDER BY room_id",
+        )?;
+        let rooms = stmt
+            .query_map(params![period], |row| {
+                Ok(LiveRoomStats {
+                    room_id: row.get(0)?,
+                    room_name: row.get(1)?,
+                    room_type: row.get(2)?,
+                    stats_json: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read period room stats")?;
+        Ok(rooms)
+    }
+
     /// Set the crawl status for a room
     pub fn set_crawl_status(&self, room_id: &str, status: CrawlStatus) -> Result<()> {
         let error = status.error_message();
@@ -266,6 +726,27 @@ impl CrawlDb {
         Ok(())
     }
 
+    /// Forget the crawl coverage of a room, so the next crawl paginates it again
+    ///
+    /// The room is kept, as virgin; cumulative event counts are kept too.
+    pub fn reset_room_coverage(&self, room_id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE room_crawl_metadata SET
+                oldest_event_id = NULL,
+                oldest_event_ts = NULL,
+                newest_event_id = NULL,
+                newest_event_ts = NULL,
+                fully_crawled = 0,
+                history_limited = 0,
+                joined_at_ts = NULL,
+                last_crawl_status = ?2,
+                last_crawl_error = NULL
+             WHERE room_id = ?1",
+            params![room_id, CrawlStatus::Virgin.as_str()],
+        )?;
+        Ok(())
+    }
+
     /// Track maximum event counts for a room (keeps highest count seen)
     /// Uses MAX to store the largest count observed across multiple crawl attempts.
     pub fn update_max_event_counts(
@@ -289,7 +770,8 @@ impl CrawlDb {
     pub fn get_all_rooms_sorted(&self) -> Result<Vec<RoomCrawlMetadata>> {
         let mut stmt = self.conn.prepare(
             "SELECT room_id, oldest_event_id, oldest_event_ts, newest_event_id, newest_event_ts, 
-                    fully_crawled, total_events_fetched, user_events_fetched, last_crawl_status, last_crawl_error
+                    fully_crawled, total_events_fetched, user_events_fetched, last_crawl_status, last_crawl_error,
+                    history_limited, joined_at_ts
              FROM room_crawl_metadata
              ORDER BY 
                 CASE last_crawl_status
@@ -321,6 +803,8 @@ impl CrawlDb {
                     newest_event_id: row.get(3)?,
                     newest_event_ts: row.get(4)?,
                     fully_crawled: row.get(5)?,
+                    history_limited: row.get(10)?,
+                    joined_at_ts: row.get(11)?,
                     total_events_fetched: row.get(6)?,
                     user_events_fetched: row.get(7)?,
                     last_crawl_status: status,
@@ -331,3 +815,21 @@ impl CrawlDb {
         Ok(rooms)
     }
 }
+
+/// Adds a column to `room_crawl_metadata` unless it already exists
+fn add_column_if_missing(conn: &Connection, column: &str, definition: &str) -> Result<()> {
+    let exists = conn
+        .prepare("SELECT 1 FROM pragma_table_info('room_crawl_metadata') WHERE name = ?1")?
+        .exists(params![column])?;
+    if !exists {
+        conn.execute(
+            &format!(
+                "ALTER TABLE room_crawl_metadata ADD COLUMN {} {}",
+                column, definition
+            ),
+            [],
+        )
+        .with_context(|| format!("Failed to add {} column", column))?;
+    }
+    Ok(())
+}
@@ -6,6 +6,7 @@ use anyhow::Result;
 use std::collections::{HashMap, HashSet};
 
 use super::db;
+use super::types::{RoomInfo, RoomJoinState};
 
 /// Decides whether a given room should be crawled based on window coverage and metadata.
 ///
@@ -156,6 +157,125 @@ pub fn record_skipped_virgin_rooms(
     record_skipped_virgin_rooms_ids(db, &joined_ids, &crawl_ids, latest_events)
 }
 
+/// Selects the joined rooms of a cached room list that a crawl of the window would
+/// paginate, without syncing (`my crawl --dry-run`).
+pub fn select_cached_rooms_to_crawl(
+    db: &db::CrawlDb,
+    room_list: &db::CachedRoomList,
+    window_start_ts: Option<i64>,
+    window_end_ts: i64,
+) -> Vec<String> {
+    let joined: Vec<&RoomInfo> = room_list
+        .rooms
+        .iter()
+        .filter(|room| room.join_state == RoomJoinState::Joined)
+        .collect();
+    let ids: Vec<String> = joined.iter().map(|room| room.room_id.clone()).collect();
+    let latest_events: HashMap<String, (String, i64)> = joined
+        .iter()
+        .filter_map(|room| {
+            Some((
+                room.room_id.clone(),
+                (room.last_event_id.clone()?, room.last_event_ts?),
+            ))
+        })
+        .collect();
+    select_room_ids_to_crawl(
+        &ids,
+        db,
+        window_start_ts,
+        Some(window_end_ts),
+        &latest_events,
+    )
+}
+
+/// What keeps a room's crawled events from covering a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverageGap {
+    /// The room has events in the window but was never crawled
+    NeverCrawled,
+    /// The last crawl of the room failed or was interrupted
+    CrawlUnfinished,
+    /// The crawl stopped after the window start and before the room start
+    MissingOlderEvents,
+    /// Discovery saw events newer than the crawled ones, before the window end
+    MissingNewerEvents,
+}
+
+/// Checks whether the crawl database covers a window for a cached room, the
+/// reverse of [`should_crawl_room`]: rooms without events in the window are
+/// covered, the others must have been crawled from the window start (or the
+/// room start) up to the window end (or the latest event seen by discovery).
+///
+/// Returns the first gap found, `None` when the room is covered.
+pub fn window_coverage_gap(
+    db: &db::CrawlDb,
+    room: &RoomInfo,
+    window_start_ts: Option<i64>,
+    window_end_ts: i64,
+) -> Result<Option<CoverageGap>> {
+    let latest_event = room.last_event_id.clone().zip(room.last_event_ts);
+    if !should_crawl_room(
+        db,
+        &room.room_id,
+        window_start_ts,
+        window_end_ts,
+        latest_event.as_ref(),
+    )? {
+        return Ok(None);
+    }
+
+    let metadata = db.get_room_metadata(&room.room_id)?;
+    let Some(meta) = metadata
+        .as_ref()
+        .filter(|meta| meta.last_crawl_status != Some(db::CrawlStatus::Virgin))
+    else {
+        return Ok(Some(CoverageGap::NeverCrawled));
+    };
+    if matches!(
+        meta.last_crawl_status,
+        Some(db::CrawlStatus::InProgress | db::CrawlStatus::Error(_))
+    ) {
+        return Ok(Some(CoverageGap::CrawlUnfinished));
+    }
+
+    let old_end_covered = meta.fully_crawled
+        || matches!(
+            (window_start_ts, meta.oldest_event_ts),
+            (Some(start), Some(oldest)) if oldest <= start
+        );
+    if !old_end_covered {
+        return Ok(Some(CoverageGap::MissingOlderEvents));
+    }
+
+    let new_end_covered = meta
+        .newest_event_ts
+        .is_some_and(|newest| newest >= window_end_ts)
+        || !has_new_events(Some(meta), room);
+    if !new_end_covered {
+        return Ok(Some(CoverageGap::MissingNewerEvents));
+    }
+    Ok(None)
+}
+
+/// Whether discovery saw events newer than the newest crawled one.
+///
+/// Rooms never crawled have new events as soon as discovery saw any.
+pub fn has_new_events(metadata: Option<&db::RoomCrawlMetadata>, room: &RoomInfo) -> bool {
+    let Some(latest_ts) = room.last_event_ts else {
+        return false;
+    };
+    let Some(metadata) = metadata else {
+        return true;
+    };
+    if room.last_event_id.is_some() && room.last_event_id == metadata.newest_event_id {
+        return false;
+    }
+    metadata
+        .newest_event_ts
+        .is_none_or(|newest_ts| latest_ts > newest_ts)
+}
+
 /// Helper: selects room IDs to crawl. Testable without Matrix SDK types.
 fn select_room_ids_to_crawl(
     joined_room_ids: &[String],
@@ -530,4 +650,121 @@ mod tests {
         assert!(db.get_room_metadata("!C")?.is_none());
         Ok(())
     }
+
+    fn cached_room(room_id: &str, last_event: Option<(&str, i64)>) -> RoomInfo {
+        RoomInfo {
+            room_id: room_id.to_owned(),
+            last_event_id: last_event.map(|(id, _)| id.to_owned()),
+            last_event_ts: last_event.map(|(_, ts)| ts),
+            join_state: RoomJoinState::Joined,
+        }
+    }
+
+    #[test]
+    fn cached_room_list_drives_dry_run() -> anyhow::Result<()> {
+        let (db, _dir) = setup_db()?;
+        assert!(db.get_room_list()?.is_none());
+
+        db.update_room_metadata(
+            "!crawled",
+            Some("oldest".to_owned()),
+            Some(500),
+            Some("evt1".to_owned()),
+            Some(1_000),
+            false,
+        )?;
+        let mut left = cached_room("!left", Some(("evt9", 2_500)));
+        left.join_state = RoomJoinState::Left;
+        db.replace_room_list(
+            &[
+                cached_room("!crawled", Some(("evt1", 1_000))),
+                cached_room("!fresh", Some(("evt2", 2_500))),
+                left,
+            ],
+            3_000,
+        )?;
+
+        let room_list = db.get_room_list()?.expect("room list should be cached");
+        assert_eq!(room_list.discovered_ts, 3_000);
+        assert_eq!(room_list.rooms.len(), 3);
+
+        // Only the joined room with events in the window is crawled
+        let selected = select_cached_rooms_to_crawl(&db, &room_list, Some(2_000), 3_000);
+        assert_eq!(selected, vec!["!fresh".to_owned()]);
+
+        let crawled = db.get_room_metadata("!crawled")?;
+        assert!(!has_new_events(crawled.as_ref(), &room_list.rooms[0]));
+        assert!(has_new_events(None, &room_list.rooms[1]));
+        let newer = cached_room("!crawled", Some(("evt3", 1_200)));
+        assert!(has_new_events(crawled.as_ref(), &newer));
+
+        // A new discovery replaces the previous list
+        db.replace_room_list(&[cached_room("!fresh", None)], 4_000)?;
+        let room_list = db.get_room_list()?.expect("room list should be cached");
+        assert_eq!(room_list.rooms.len(), 1);
+        assert_eq!(room_list.discovered_ts, 4_000);
+        Ok(())
+    }
+
+    #[test]
+    fn window_coverage_gaps() -> anyhow::Result<()> {
+        let (db, _dir) = setup_db()?;
+        // Crawled from 500 to 1_000, latest event at discovery
+        db.update_room_metadata(
+            "!covered",
+            Some("oldest".to_owned()),
+            Some(500),
+            Some("evt1".to_owned()),
+            Some(1_000),
+            false,
+        )?;
+        db.set_crawl_status("!covered", db::CrawlStatus::Success)?;
+        let covered = cached_room("!covered", Some(("evt1", 1_000)));
+
+        assert_eq!(window_coverage_gap(&db, &covered, Some(600), 2_000)?, None);
+        assert_eq!(
+            window_coverage_gap(&db, &covered, Some(100), 2_000)?,
+            Some(CoverageGap::MissingOlderEvents)
+        );
+        assert_eq!(
+            window_coverage_gap(&db, &covered, None, 2_000)?,
+            Some(CoverageGap::MissingOlderEvents)
+        );
+        // Newer events seen by discovery only matter before the window end
+        let newer = cached_room("!covered", Some(("evt2", 1_500)));
+        assert_eq!(
+            window_coverage_gap(&db, &newer, Some(600), 2_000)?,
+            Some(CoverageGap::MissingNewerEvents)
+        );
+        assert_eq!(window_coverage_gap(&db, &newer, Some(600), 900)?, None);
+        // No events in the window: nothing to cover
+        assert_eq!(
+            window_coverage_gap(&db, &covered, Some(1_200), 2_000)?,
+            None
+        );
+
+        let fresh = cached_room("!fresh", Some(("evt3", 1_500)));
+        assert_eq!(
+            window_coverage_gap(&db, &fresh, Some(600), 2_000)?,
+            Some(CoverageGap::NeverCrawled)
+        );
+
+        db.update_room_metadata(
+            "!failed",
+            Some("oldest".to_owned()),
+            Some(100),
+            Some("evt4".to_owned()),
+            Some(1_000),
+            true,
+        )?;
+        db.set_crawl_status("!failed", db::CrawlStatus::Error("timeout".to_owned()))?;
+        let failed = cached_room("!failed", Some(("evt4", 1_000)));
+        assert_eq!(
+            window_coverage_gap(&db, &failed, None, 2_000)?,
+            Some(CoverageGap::CrawlUnfinished)
+        );
+        db.set_crawl_status("!failed", db::CrawlStatus::Success)?;
+        assert_eq!(window_coverage_gap(&db, &failed, None, 2_000)?, None);
+        Ok(())
+    }
 }
@@ -1,12 +1,15 @@
-/// Room discovery via Matrix sliding sync.
+/// Room discovery via Matrix sliding sync, or regular sync as a fallback.
 ///
 /// Discovers joined rooms and fetches their latest event information
 /// in a single, efficient sync operation. Does not paginate events.
 use anyhow::{Context, Result};
 use futures_util::StreamExt;
-use matrix_sdk::ruma::events::StateEventType;
+use matrix_sdk::ruma::api::client::sync::sync_events::v5 as http;
+use matrix_sdk::ruma::events::{AnyRoomAccountDataEvent, RoomAccountDataEventType, StateEventType};
+use matrix_sdk::ruma::serde::Raw;
 use std::path::Path;
 
+use super::progress::CrawlLog;
 use super::types::{RoomInfo, RoomJoinState};
 
 /// State event types needed for room list sync.
@@ -30,6 +33,13 @@ const REQUIRED_STATE: &[(StateEventType, &str)] = &[
 /// Batch size for sliding sync room discovery (rooms per batch).
 const SLIDING_SYNC_BATCH_SIZE: usize = 50;
 
+/// Rooms probed concurrently for their latest event when discovering rooms
+/// without sliding sync.
+const PROBE_CONCURRENCY: usize = 8;
+
+/// Room account data type holding the user's personal name for a room.
+const ROOM_NAME_OVERRIDE_TYPE: &str = "m.room.name";
+
 /// Initializes the account's client and database.
 ///
 /// Restores an existing SDK session from the account directory and initializes
@@ -57,6 +67,27 @@ pub async fn setup_account(
     Ok((account_dir.to_path_buf(), client, db))
 }
 
+/// Discovers joined rooms and their latest event.
+///
+/// Uses sliding sync, and falls back to a regular sync when it fails, as on
+/// homeservers that do not support it.
+pub async fn fetch_room_list(client: &matrix_sdk::Client, log: &CrawlLog) -> Result<Vec<RoomInfo>> {
+    match fetch_room_list_via_sliding_sync(client, log).await {
+        Ok(room_list) => Ok(room_list),
+        Err(e) => {
+            log.println(&format!(
+                "⚠️  Sliding sync failed ({:#}), discovering rooms with a regular sync",
+                e
+            ));
+            fetch_room_list_via_sync(client, log)
+                .await
+                .with_context(|| {
+                    format!("Room discovery failed, sliding sync failed with: {:#}", e)
+                })
+        }
+    }
+}
+
 /// Discovers joined rooms and their latest event via sliding sync.
 ///
 /// Uses growing-mode sliding sync to fetch all joined rooms in batches,
@@ -76,6 +107,7 @@ pub async fn setup_account(
 /// A vector of `RoomInfo` containing room ID, latest event ID/timestamp, and join state.
 pub async fn fetch_room_list_via_sliding_sync(
     client: &matrix_sdk::Client,
+    log: &CrawlLog,
 ) -> Result<Vec<RoomInfo>> {
     use matrix_sdk::sliding_sync::{SlidingSyncList, SlidingSyncListLoadingState, SlidingSyncMode};
 
@@ -87,13 +119,23 @@ pub async fn fetch_room_list_via_sliding_sync(
             REQUIRED_STATE
                 .iter()
                 .map(|(state_event, value)| (state_event.clone(), (*value).to_owned()))
+                // Room image packs name the stickers and emotes the user sends
+                .chain(std::iter::once((
+                    StateEventType::from(super::pagination::ROOM_EMOTES_EVENT_TYPE),
+                    "*".to_owned(),
+                )))
                 .collect(),
         );
 
+    // Room account data carries the user's personal room names (see `resolve_room_name`)
+    let mut account_data = http::request::AccountData::default();
+    account_data.enabled = Some(true);
+
     let sliding = client
         .sliding_sync("my-all")?
         .add_cached_list(list_builder)
         .await?
+        .with_account_data_extension(account_data)
         .share_pos()
         .poll_timeout(std::time::Duration::from_secs(0))
         .build()
@@ -135,11 +177,11 @@ pub async fn fetch_room_list_via_sliding_sync(
             sync_result = sync_stream.next() => {
                 if let Some(result) = sync_result {
                     if let Err(e) = result {
-                        eprintln!("\n❌ Sync error details: {:#}", e);
+                        log.println(&format!("\n❌ Sync error details: {:#}", e));
                         return Err(e).context("Sync failed");
                     }
                     sync_count += 1;
-                    eprintln!("  🔄 Sync #{} completed", sync_count);
+                    log.println(&format!("  🔄 Sync #{} completed", sync_count));
                 }
             }
         }
@@ -148,13 +190,13 @@ pub async fn fetch_room_list_via_sliding_sync(
     // Do one final sync iteration to ensure pagination sync state is updated with latest events
     if let Some(result) = sync_stream.next().await {
         result.context("Final sync iteration failed")?;
-        eprintln!("  🔄 Final sync iteration completed");
+        log.println("  🔄 Final sync iteration completed");
     }
 
     // Extract room list with latest events
     let mut room_list = Vec::new();
 
-    eprintln!("🔍 Extracting room list...");
+    log.println("🔍 Extracting room list...");
     for room in client.joined_rooms() {
         let room_id = room.room_id().to_string();
         let last_event = match room.event_cache().await {
@@ -178,6 +220,193 @@ pub async fn fetch_room_list_via_sliding_sync(
         });
     }
 
-    eprintln!("  ✓ Extracted {} rooms", room_list.len());
+    log.println(&format!("  ✓ Extracted {} rooms", room_list.len()));
+    Ok(room_list)
+}
+
+/// Discovers joined rooms and their latest event via a regular `/sync`.
+///
+/// Fallback for homeservers without sliding sync. A single sync, with one
+/// timeline event per room and lazy-loaded members, makes the joined rooms and
+/// their state known to the client. The latest event of each room is then asked
+/// with `/messages?limit=1`, as the sync timeline of a room may be a state event
+/// or cut by a gap.
+pub async fn fetch_room_list_via_sync(
+    client: &matrix_sdk::Client,
+    log: &CrawlLog,
+) -> Result<Vec<RoomInfo>> {
+    use matrix_sdk::config::SyncSettings;
+    use matrix_sdk::room::MessagesOptions;
+    use matrix_sdk::ruma::api::client::filter::{FilterDefinition, RoomEventFilter};
+    use matrix_sdk::ruma::uint;
+
+    let mut filter = FilterDefinition::default();
+    filter.room.timeline.limit = Some(uint!(1));
+    filter.room.state = RoomEventFilter::with_lazy_loading();
+    let settings = SyncSettings::default()
+        .filter(filter.into())
+        .timeout(std::time::Duration::from_secs(0));
+    client.sync_once(settings).await.context("Sync failed")?;
+    log.println("  🔄 Sync completed");
+
+    log.println("🔍 Probing the latest event of each room...");
+    let probes = client.joined_rooms().into_iter().map(|room| async move {
+        let mut options = MessagesOptions::backward();
+        options.limit = uint!(1);
+        let last_event = room.messages(options).await.ok().and_then(|messages| {
+            let event = messages.chunk.first()?;
+            let ts: i64 = event.timestamp()?.get().into();
+            Some((event.event_id()?.to_string(), ts))
+        });
+        RoomInfo {
+            room_id: room.room_id().to_string(),
+            last_event_id: last_event.as_ref().map(|(id, _)| id.clone()),
+            last_event_ts: last_event.map(|(_, ts)| ts),
+            join_state: RoomJoinState::Joined,
+        }
+    });
+    let room_list: Vec<RoomInfo> = futures_util::stream::iter(probes)
+        .buffer_unordered(PROBE_CONCURRENCY)
+        .collect()
+        .await;
+
+    log.println(&format!("  ✓ Extracted {} rooms", room_list.len()));
     Ok(room_list)
 }
+
+/// Resolves the room name as the user sees it in their client.
+///
+/// A personal name the user gave the room, stored in the room's account data
+/// (`m.room.name` with a `name` field), wins over the name computed from room
+/// state. Falls back to the room ID when neither is available. Per-room tags
+/// (`m.tag`) are not read: they only carry a tag name and an order, never a
+/// name for the room, so they do not change the name clients show.
+pub async fn resolve_room_name(room: &matrix_sdk::Room) -> String {
+    let override_name = room
+        .account_data(RoomAccountDataEventType::from(ROOM_NAME_OVERRIDE_TYPE))
+        .await
+        .ok()
+        .flatten()
+        .and_then(|raw| name_override(&raw));
+    if let Some(name) = override_name {
+        return name;
+    }
+
+    room.display_name()
+        .await
+        .ok()
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| room.room_id().to_string())
+}
+
+/// The other party of a DM and their display name in the room, from room
+/// state. `None` unless the room has exactly one DM target.
+pub async fn resolve_dm_partner(room: &matrix_sdk::Room) -> Option<(String, Option<String>)> {
+    let mut targets = room.direct_targets().into_iter();
+    let (Some(target), None) = (targets.next(), targets.next()) else {
+        return None;
+    };
+    let user_id = target.as_user_id()?;
+    let display_name = room
+        .get_member_no_sync(user_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|member| member.display_name().map(str::to_string));
+    Some((user_id.to_string(), display_name))
+}
+
+/// Asks the homeserver when the account was created.
+///
+/// The client-server API has no endpoint for it, so this uses the Synapse admin
+/// API, which only answers for server admins. Returns None when the homeserver
+/// does not answer; callers then fall back to an estimate from crawled events.
+pub async fn fetch_account_creation_ts(client: &matrix_sdk::Client, user_id: &str) -> Option<i64> {
+    let access_token = client.access_token()?;
+    let mut url = client.homeserver();
+    url.path_segments_mut()
+        .ok()?
+        .pop_if_empty()
+        .extend(["_synapse", "admin", "v2", "users", user_id]);
+
+    let response = client
+        .http_client()
+        .get(url)
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let body: serde_json::Value = serde_json::from_str(&response.text().await.ok()?).ok()?;
+    creation_ts_millis(&body)
+}
+
+/// Reads `creation_ts` from a Synapse user info response, in milliseconds (private).
+///
+/// Synapse reports it in seconds; values already in milliseconds are kept.
+fn creation_ts_millis(body: &serde_json::Value) -> Option<i64> {
+    let ts = body.get("creation_ts")?.as_i64().filter(|ts| *ts > 0)?;
+    // 10^11 seconds is far in the future, 10^11 milliseconds is 1973
+    Some(if ts < 100_000_000_000 { ts * 1000 } else { ts })
+}
+
+/// Extracts a non-empty `content.name` from a room account data event (private).
+fn name_override(raw: &Raw<AnyRoomAccountDataEvent>) -> Option<String> {
+    let name = raw
+        .get_field::<serde_json::Value>("content")
+        .ok()
+        .flatten()?
+        .get("name")?
+        .as_str()?
+        .trim()
+        .to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_event(json: serde_json::Value) -> Raw<AnyRoomAccountDataEvent> {
+        Raw::from_json(serde_json::value::to_raw_value(&json).unwrap())
+    }
+
+    #[test]
+    fn test_name_override() {
+        let raw = raw_event(serde_json::json!({
+            "type": "m.room.name",
+            "content": { "name": "  Mum & Dad  " }
+        }));
+        assert_eq!(name_override(&raw), Some("Mum & Dad".to_string()));
+    }
+
+    #[test]
+    fn test_name_override_ignores_empty_or_missing_name() {
+        let empty = raw_event(serde_json::json!({
+            "type": "m.room.name",
+            "content": { "name": "" }
+        }));
+        assert_eq!(name_override(&empty), None);
+
+        let missing = raw_event(serde_json::json!({ "type": "m.room.name", "content": {} }));
+        assert_eq!(name_override(&missing), None);
+    }
+
+    #[test]
+    fn test_creation_ts_millis() {
+        let seconds =
+            serde_json::json!({ "name": "@alice:example.org", "creation_ts": 1560432506 });
+        assert_eq!(creation_ts_millis(&seconds), Some(1_560_432_506_000));
+
+        let millis = serde_json::json!({ "creation_ts": 1_560_432_506_000i64 });
+        assert_eq!(creation_ts_millis(&millis), Some(1_560_432_506_000));
+
+        assert_eq!(
+            creation_ts_millis(&serde_json::json!({ "creation_ts": 0 })),
+            None
+        );
+        assert_eq!(creation_ts_millis(&serde_json::json!({})), None);
+    }
+}
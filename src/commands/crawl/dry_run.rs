@@ -0,0 +1,56 @@
+/// Crawl preview (`my crawl <window> --dry-run`).
+///
+/// Reads the room list cached by the latest discovery and the crawl metadata to
+/// show which rooms a crawl of the window would paginate, without syncing: the
+/// answer is only as fresh as the last crawl.
+use anyhow::{Context, Result};
+
+use super::db::CrawlDb;
+use super::decision::{has_new_events, select_cached_rooms_to_crawl};
+use crate::account_selector::AccountSelector;
+use crate::timefmt::format_timestamp;
+use crate::window::WindowScope;
+
+/// Run the dry run for the selected accounts
+pub fn run(window: &str, user_id_flag: Option<String>) -> Result<()> {
+    let window_scope = WindowScope::parse(window).context("Failed to parse window")?;
+    let (window_start_ts, window_end_ts) = window_scope.to_timestamp_range();
+
+    let mut selector = AccountSelector::new()?;
+    let accounts = selector.select_accounts(user_id_flag, true)?;
+
+    for (account_id, account_dir) in &accounts {
+        println!("\nAccount: {}", account_id);
+        let db = CrawlDb::init(account_dir)?;
+        let Some(room_list) = db.get_room_list()? else {
+            println!("  No cached room list yet: run 'my crawl' once to discover rooms");
+            continue;
+        };
+
+        let selected =
+            select_cached_rooms_to_crawl(&db, &room_list, window_start_ts, window_end_ts);
+        println!(
+            "  Room list from {} (run a crawl to refresh it)",
+            format_timestamp(room_list.discovered_ts)
+        );
+        println!(
+            "  Would crawl {} of {} room(s) for {}",
+            selected.len(),
+            room_list.rooms.len(),
+            window
+        );
+        for room_id in &selected {
+            let Some(room) = room_list.rooms.iter().find(|room| &room.room_id == room_id) else {
+                continue;
+            };
+            let metadata = db.get_room_metadata(room_id)?;
+            let freshness = match &metadata {
+                None => "never crawled",
+                Some(_) if has_new_events(metadata.as_ref(), room) => "new events",
+                Some(_) => "up to date, stats recomputed",
+            };
+            println!("    {} ({})", room_id, freshness);
+        }
+    }
+    Ok(())
+}
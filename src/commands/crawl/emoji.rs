@@ -0,0 +1,165 @@
+//! Emoji segmentation of message bodies.
+//!
+//! Finds the emoji of a text as they are displayed: ZWJ sequences (👩‍💻),
+//! flags (🇫🇷, 🏴󠁧󠁢󠁳󠁣󠁴󠁿), keycaps (1️⃣) and skin tone variants (👍🏽) stay whole.
+//! This is a small approximation of the Unicode emoji grammar that needs no
+//! emoji data tables: symbols drawn as text by default (↔, ©, ™) only count
+//! when followed by the emoji variation selector.
+
+/// Variation selector asking for the emoji presentation of the previous character.
+const VS16: char = '\u{FE0F}';
+/// Zero width joiner, gluing emoji into one (👨‍👩‍👧).
+const ZWJ: char = '\u{200D}';
+/// Combining enclosing keycap, after a digit, `#` or `*`.
+const KEYCAP: char = '\u{20E3}';
+
+/// Emoji of `text`, in order, one entry per emoji as displayed.
+pub fn emojis(text: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match emoji_len(&chars[i..]) {
+            Some(len) => {
+                let end = chars.get(i + len).map_or(text.len(), |(index, _)| *index);
+                found.push(&text[chars[i].0..end]);
+                i += len;
+            }
+            None => i += 1,
+        }
+    }
+    found
+}
+
+/// Characters of the emoji `chars` starts with, if it starts with one.
+fn emoji_len(chars: &[(usize, char)]) -> Option<usize> {
+    let next = |k: usize| chars.get(k).map(|(_, c)| *c);
+    let first = next(0)?;
+
+    // A flag is a pair of regional indicators
+    if is_regional_indicator(first) {
+        return next(1).filter(|&c| is_regional_indicator(c)).map(|_| 2);
+    }
+    // A keycap is a digit, `#` or `*`, then the keycap mark
+    if first.is_ascii_digit() || first == '#' || first == '*' {
+        let k = if next(1) == Some(VS16) { 2 } else { 1 };
+        return (next(k) == Some(KEYCAP)).then_some(k + 1);
+    }
+
+    let mut len = element_len(chars)?;
+    while next(len) == Some(ZWJ) {
+        match element_len(&chars[len + 1..]) {
+            Some(joined) => len += 1 + joined,
+            None => break,
+        }
+    }
+    Some(len)
+}
+
+/// Characters of a single emoji with its modifiers: variation selector, skin
+/// tone and tags (subdivision flags).
+fn element_len(chars: &[(usize, char)]) -> Option<usize> {
+    let next = |k: usize| chars.get(k).map(|(_, c)| *c);
+    let first = next(0)?;
+    if !is_pictographic(first) || !(is_emoji_presentation(first) || next(1) == Some(VS16)) {
+        return None;
+    }
+
+    let mut len = 1;
+    if next(len) == Some(VS16) {
+        len += 1;
+    }
+    if next(len).is_some_and(is_skin_tone) {
+        len += 1;
+    }
+    while next(len).is_some_and(is_tag) {
+        len += 1;
+    }
+    Some(len)
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+fn is_skin_tone(c: char) -> bool {
+    matches!(c, '\u{1F3FB}'..='\u{1F3FF}')
+}
+
+fn is_tag(c: char) -> bool {
+    matches!(c, '\u{E0020}'..='\u{E007F}')
+}
+
+/// Characters that can be emoji, drawn as text or as emoji by default.
+fn is_pictographic(c: char) -> bool {
+    matches!(
+        c,
+        '\u{A9}'
+            | '\u{AE}'
+            | '\u{203C}'..='\u{3299}'
+            | '\u{1F000}'..='\u{1FAFF}'
+    ) && !is_regional_indicator(c)
+}
+
+/// Characters drawn as emoji without a variation selector.
+fn is_emoji_presentation(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1F000}'..='\u{1FAFF}'
+            | '\u{231A}'..='\u{231B}'
+            | '\u{23E9}'..='\u{23EC}'
+            | '\u{23F0}'
+            | '\u{23F3}'
+            | '\u{25FD}'..='\u{25FE}'
+            | '\u{2614}'..='\u{2615}'
+            | '\u{2648}'..='\u{2653}'
+            | '\u{267F}'
+            | '\u{2693}'
+            | '\u{26A1}'
+            | '\u{26AA}'..='\u{26AB}'
+            | '\u{26BD}'..='\u{26BE}'
+            | '\u{26C4}'..='\u{26C5}'
+            | '\u{26CE}'
+            | '\u{26D4}'
+            | '\u{26EA}'
+            | '\u{26F2}'..='\u{26F3}'
+            | '\u{26F5}'
+            | '\u{26FA}'
+            | '\u{26FD}'
+            | '\u{2705}'
+            | '\u{270A}'..='\u{270B}'
+            | '\u{2728}'
+            | '\u{274C}'
+            | '\u{274E}'
+            | '\u{2753}'..='\u{2755}'
+            | '\u{2757}'
+            // The red heart is text by default, but typed as an emoji everywhere
+            | '\u{2764}'
+            | '\u{2795}'..='\u{2797}'
+            | '\u{27B0}'
+            | '\u{27BF}'
+            | '\u{2B1B}'..='\u{2B1C}'
+            | '\u{2B50}'
+            | '\u{2B55}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emojis() {
+        assert_eq!(emojis("ship it 🚀🚀, thanks 🙏🏽!"), vec!["🚀", "🚀", "🙏🏽"]);
+        assert_eq!(emojis("👩‍💻 and 👨‍👩‍👧 ❤️ ❤"), vec!["👩‍💻", "👨‍👩‍👧", "❤️", "❤"]);
+        assert_eq!(emojis("🇫🇷🇩🇪 🏴󠁧󠁢󠁳󠁣󠁴󠁿"), vec!["🇫🇷", "🇩🇪", "🏴󠁧󠁢󠁳󠁣󠁴󠁿"]);
+        assert_eq!(emojis("press 1️⃣ or #⃣, not 1"), vec!["1️⃣", "#⃣"]);
+    }
+
+    #[test]
+    fn test_text_symbols_are_not_emojis() {
+        assert!(emojis("a ↔ b, © 2025 ACME™ — 5 €…").is_empty());
+        assert_eq!(emojis("↔️ ©️"), vec!["↔️", "©️"]);
+        assert!(emojis("🇫 alone").is_empty());
+    }
+}
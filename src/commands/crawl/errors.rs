@@ -0,0 +1,351 @@
+//! Error report for rooms that failed to crawl.
+//!
+//! Failures otherwise only scroll by in the progress output; the report keeps
+//! them in `errors-<window>.json` in the account directory, with the full
+//! error chain and a hint on how to retry.
+//!
+//! Failures of the whole homeserver (resource limits, outages) are told apart
+//! by [`ServerHealth`], so that they pause the crawl instead of being reported
+//! against every room crawled at the time.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Failed rooms of one crawl run.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorReport {
+    pub account: String,
+    pub window: String,
+    /// RFC 3339 timestamp of the crawl run
+    pub generated_at: String,
+    pub rooms: Vec<RoomError>,
+}
+
+/// A room that failed to crawl.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomError {
+    pub room_id: String,
+    pub room_name: String,
+    /// Error messages from outermost to root cause
+    pub chain: Vec<String>,
+    pub retry_hint: String,
+}
+
+impl RoomError {
+    pub fn new(room_id: String, room_name: String, error: &anyhow::Error, window: &str) -> Self {
+        let chain: Vec<String> = error.chain().map(|cause| cause.to_string()).collect();
+        let retry_hint = retry_hint(&chain, window);
+        Self {
+            room_id,
+            room_name,
+            chain,
+            retry_hint,
+        }
+    }
+}
+
+/// Consecutive room failures with a server or network error after which the
+/// homeserver is taken as unavailable rather than each room as broken.
+pub const OUTAGE_THRESHOLD: usize = 3;
+
+/// Why the crawl of an account stops before its remaining rooms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PauseReason {
+    /// The homeserver refuses requests over its resource limits (`M_RESOURCE_LIMIT_EXCEEDED`)
+    ResourceLimit,
+    /// Several rooms in a row failed with server or network errors
+    Outage,
+    /// The crawl was cancelled (Ctrl-C or the embedding application)
+    Cancelled,
+}
+
+impl PauseReason {
+    pub fn describe(&self) -> String {
+        match self {
+            PauseReason::ResourceLimit => {
+                "the homeserver is over its resource limits (M_RESOURCE_LIMIT_EXCEEDED)".to_string()
+            }
+            PauseReason::Outage => format!(
+                "{} rooms in a row failed with server or network errors, the homeserver looks unavailable",
+                OUTAGE_THRESHOLD
+            ),
+            PauseReason::Cancelled => "the crawl was cancelled".to_string(),
+        }
+    }
+}
+
+/// Tells room failures apart from failures of the whole homeserver.
+///
+/// Server and network errors are held back until a room succeeds again (they
+/// were the rooms' own) or until enough of them pile up to pause the crawl
+/// (they were the homeserver's, and their rooms are left to the next crawl).
+#[derive(Debug, Default)]
+pub struct ServerHealth {
+    suspects: Vec<RoomError>,
+}
+
+impl ServerHealth {
+    /// Records a room that crawled fine; returns the held failures, now known
+    /// to be the rooms' own.
+    pub fn success(&mut self) -> Vec<RoomError> {
+        std::mem::take(&mut self.suspects)
+    }
+
+    /// Records a room failure. Returns the failures to report now, or why the
+    /// crawl should pause (the failure and the held ones are then dropped).
+    pub fn failure(&mut self, error: RoomError) -> Result<Vec<RoomError>, PauseReason> {
+        let text = error.chain.join(" ").to_lowercase();
+        if text.contains("m_resource_limit_exceeded") {
+            self.suspects.clear();
+            return Err(PauseReason::ResourceLimit);
+        }
+        if !is_server_failure(&text) {
+            let mut failed = std::mem::take(&mut self.suspects);
+            failed.push(error);
+            return Ok(failed);
+        }
+
+        self.suspects.push(error);
+        if self.suspects.len() >= OUTAGE_THRESHOLD {
+            self.suspects.clear();
+            return Err(PauseReason::Outage);
+        }
+        Ok(Vec::new())
+    }
+
+    /// Failures still held at the end of the crawl, reported as the rooms' own.
+    pub fn finish(self) -> Vec<RoomError> {
+        self.suspects
+    }
+}
+
+/// Whether a lowercased error chain points at the homeserver or the network
+/// rather than at the room.
+fn is_server_failure(text: &str) -> bool {
+    [
+        "timed out",
+        "timeout",
+        "connect",
+        "network",
+        "m_limit_exceeded",
+        "429",
+        "internal server error",
+        "bad gateway",
+        "service unavailable",
+        "gateway timeout",
+    ]
+    .iter()
+    .any(|pattern| text.contains(pattern))
+}
+
+/// Path of the error report for a window in an account directory.
+pub fn report_path(account_dir: &Path, window_key: &str) -> PathBuf {
+    account_dir.join(format!("errors-{}.json", window_key))
+}
+
+/// Writes the report of failed rooms, or removes a stale one when all rooms succeeded.
+///
+/// Returns the path of the written report, if any.
+pub fn write_report(
+    account_dir: &Path,
+    account: &str,
+    window_key: &str,
+    rooms: Vec<RoomError>,
+) -> Result<Option<PathBuf>> {
+    let path = report_path(account_dir, window_key);
+
+    if rooms.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove error report: {}", path.display()))?;
+        }
+        return Ok(None);
+    }
+
+    let report = ErrorReport {
+        account: account.to_string(),
+        window: window_key.to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        rooms,
+    };
+    let json = serde_json::to_string_pretty(&report).context("Failed to serialize error report")?;
+    std::fs::write(&path, json)
+        .with_context(|| format!("Failed to write error report: {}", path.display()))?;
+
+    Ok(Some(path))
+}
+
+/// Suggests how to recover from a failure, based on its error chain.
+fn retry_hint(chain: &[String], window: &str) -> String {
+    let text = chain.join(" ").to_lowercase();
+    let rerun = format!("my crawl {}", window);
+
+    if text.contains("m_resource_limit_exceeded") {
+        format!(
+            "The homeserver is over its resource limits. Ask its admin, or run '{}' again later.",
+            rerun
+        )
+    } else if text.contains("m_limit_exceeded")
+        || text.contains("429")
+        || text.contains("rate limit")
+    {
+        format!(
+            "Rate limited by the homeserver. Wait a bit and run '{}' again, or throttle it with --quiet-hours.",
+            rerun
+        )
+    } else if text.contains("m_forbidden") || text.contains("403") {
+        "The homeserver refused access to the room history; check that you are still in the room."
+            .to_string()
+    } else if text.contains("m_unknown_token") || text.contains("401") {
+        "The session is no longer valid. Run 'my login' again, then re-run the crawl.".to_string()
+    } else if text.contains("sqlite") || text.contains("database") {
+        format!(
+            "Local database error. Run '{}' again; if it persists, 'my reset' clears crawl data.",
+            rerun
+        )
+    } else if text.contains("timed out")
+        || text.contains("timeout")
+        || text.contains("connect")
+        || text.contains("network")
+    {
+        format!(
+            "Network error. Check the connection and run '{}' again.",
+            rerun
+        )
+    } else {
+        format!(
+            "Run '{}' again; rooms that already succeeded are not crawled twice.",
+            rerun
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_room_error_keeps_chain() {
+        let error = anyhow::anyhow!("connection reset").context("Failed to paginate");
+        let room_error = RoomError::new(
+            "!abc:example.org".to_string(),
+            "Friends".to_string(),
+            &error,
+            "2025",
+        );
+
+        assert_eq!(
+            room_error.chain,
+            vec!["Failed to paginate", "connection reset"]
+        );
+        assert_eq!(
+            room_error.retry_hint,
+            "Network error. Check the connection and run 'my crawl 2025' again."
+        );
+    }
+
+    #[test]
+    fn test_retry_hint() {
+        let hint = |msg: &str| retry_hint(&[msg.to_string()], "life");
+        assert!(hint("M_LIMIT_EXCEEDED: Too many requests").contains("--quiet-hours"));
+        assert!(hint("M_RESOURCE_LIMIT_EXCEEDED: MAU limit").contains("its admin"));
+        assert!(hint("M_FORBIDDEN: not in room").contains("still in the room"));
+        assert!(hint("disk I/O error in sqlite").contains("my reset"));
+        assert!(hint("something odd").contains("my crawl life"));
+    }
+
+    fn room_error(room_id: &str, message: &str) -> RoomError {
+        RoomError::new(
+            room_id.to_string(),
+            room_id.to_string(),
+            &anyhow::anyhow!(message.to_string()),
+            "2025",
+        )
+    }
+
+    #[test]
+    fn test_resource_limit_pauses_at_once() {
+        let mut health = ServerHealth::default();
+        assert_eq!(
+            health.failure(room_error(
+                "!a",
+                "[403 / M_RESOURCE_LIMIT_EXCEEDED] Monthly active user limit exceeded"
+            )),
+            Err(PauseReason::ResourceLimit)
+        );
+    }
+
+    #[test]
+    fn test_outage_pauses_after_consecutive_failures() {
+        let mut health = ServerHealth::default();
+        assert_eq!(
+            health.failure(room_error("!a", "503 Service Unavailable")),
+            Ok(Vec::new())
+        );
+        assert_eq!(
+            health.failure(room_error("!b", "operation timed out")),
+            Ok(Vec::new())
+        );
+        assert_eq!(
+            health.failure(room_error("!c", "error trying to connect")),
+            Err(PauseReason::Outage)
+        );
+        // The held rooms are left to the next crawl, not reported
+        assert!(health.finish().is_empty());
+    }
+
+    #[test]
+    fn test_held_failures_are_reported_after_a_success() {
+        let mut health = ServerHealth::default();
+        assert_eq!(
+            health.failure(room_error("!a", "operation timed out")),
+            Ok(Vec::new())
+        );
+        let reported = health.success();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].room_id, "!a");
+
+        // A room's own failure is reported at once, with the held ones
+        assert_eq!(
+            health.failure(room_error("!b", "operation timed out")),
+            Ok(Vec::new())
+        );
+        let reported = health
+            .failure(room_error("!c", "M_FORBIDDEN: not in room"))
+            .unwrap();
+        let ids: Vec<&str> = reported
+            .iter()
+            .map(|error| error.room_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["!b", "!c"]);
+    }
+
+    #[test]
+    fn test_write_report_and_cleanup() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = anyhow::anyhow!("boom");
+        let rooms = vec![RoomError::new(
+            "!abc:example.org".to_string(),
+            "Friends".to_string(),
+            &error,
+            "2025",
+        )];
+
+        let path = write_report(dir.path(), "@alice:example.org", "2025", rooms)
+            .unwrap()
+            .unwrap();
+        assert_eq!(path, dir.path().join("errors-2025.json"));
+        let report: ErrorReport =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(report.rooms[0].room_id, "!abc:example.org");
+
+        // A clean run removes the stale report
+        assert!(
+            write_report(dir.path(), "@alice:example.org", "2025", Vec::new())
+                .unwrap()
+                .is_none()
+        );
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,330 @@
+/// Crawl simulation from fixture files.
+///
+/// Replays synthetic event streams through the same event processing, metric
+/// collectors and stats building as a live crawl, without a homeserver or
+/// credentials: for end-to-end tests, reproducible benchmarks and demo reports.
+///
+/// A fixture is a directory with an `account.json` file and one JSON file per
+/// room in `rooms/`:
+///
+/// ```text
+/// account.json      {"user_id": "@alice:example.org", "display_name": "Alice"}
+/// rooms/team.json   {"room_id": "!team:example.org", "name": "Team", "type": "private",
+///                    "events": [{"type": "m.room.message", "event_id": "$1", ...}]}
+/// ```
+///
+/// Events are Matrix timeline events as served by the client-server API, oldest
+/// first. Each room history is taken as complete, as if pagination had reached
+/// the start of the room.
+use anyhow::{Context, Result};
+use matrix_sdk::deserialized_responses::TimelineEvent;
+use matrix_sdk::ruma::events::AnySyncTimelineEvent;
+use matrix_sdk::ruma::serde::Raw;
+use serde::Deserialize;
+use std::path::Path;
+
+use super::metrics::{self, MetricCollector};
+use super::stats_builder::{self, RoomStatsInput};
+use super::{pagination, room_type_override, CrawlOptions, RoomType};
+use crate::stats::Stats;
+use crate::window::WindowScope;
+
+/// Account the fixture events are crawled for.
+#[derive(Debug, Deserialize)]
+pub struct FixtureAccount {
+    pub user_id: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    /// Account creation, in milliseconds since the epoch
+    #[serde(default)]
+    pub created_ts: Option<i64>,
+}
+
+/// A room and its full event history.
+#[derive(Debug, Deserialize)]
+pub struct FixtureRoom {
+    pub room_id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// `dm`, `public` or `private`
+    #[serde(rename = "type", default = "default_room_type")]
+    pub room_type: String,
+    pub events: Vec<Raw<AnySyncTimelineEvent>>,
+}
+
+fn default_room_type() -> String {
+    RoomType::Private.key().to_string()
+}
+
+/// A fixture loaded from disk.
+#[derive(Debug)]
+pub struct Fixture {
+    pub account: FixtureAccount,
+    pub rooms: Vec<FixtureRoom>,
+}
+
+impl Fixture {
+    /// Loads `account.json` and the room files of `rooms/`, in file name order.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let account: FixtureAccount = read_json(&dir.join("account.json"))?;
+
+        let rooms_dir = dir.join("rooms");
+        let mut paths: Vec<_> = std::fs::read_dir(&rooms_dir)
+            .with_context(|| format!("Failed to read fixture rooms: {}", rooms_dir.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+
+        let rooms = paths
+            .iter()
+            .map(|path| read_json(path))
+            .collect::<Result<Vec<FixtureRoom>>>()?;
+
+        Ok(Self { account, rooms })
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read fixture file: {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Invalid fixture file: {}", path.display()))
+}
+
+/// Crawls a fixture directory for a time window.
+///
+/// Returns the fixture account ID and its stats, like one entry of
+/// [`super::run`].
+pub fn run(window: &str, dir: &Path, options: &CrawlOptions) -> Result<(String, Stats)> {
+    let window_scope = WindowScope::parse(window).context("Failed to parse window")?;
+    let fixture = Fixture::load(dir)?;
+
+    eprintln!(
+        "🧪 Replaying {} room(s) from fixture {} for window: {}",
+        fixture.rooms.len(),
+        dir.display(),
+        window
+    );
+
+    let stats = build_stats(
+        &fixture,
+        &window_scope,
+        options,
+        &metrics::builtin_collectors(options),
+    )?;
+    Ok((fixture.account.user_id, stats))
+}
+
+/// Builds the stats of a loaded fixture with the given metric collectors.
+pub fn build_stats(
+    fixture: &Fixture,
+    window_scope: &WindowScope,
+    options: &CrawlOptions,
+    collectors: &[Box<dyn MetricCollector>],
+) -> Result<Stats> {
+    let (window_start_ts, window_end_ts) = window_scope.to_timestamp_range();
+    let overrides = room_type_override::by_room(&options.room_type_overrides);
+    let account = &fixture.account;
+
+    let mut room_inputs = Vec::new();
+    for room in &fixture.rooms {
+        let room_type = match overrides.get(&room.room_id) {
+            Some(room_type) => *room_type,
+            None => RoomType::from_key(&room.room_type).with_context(|| {
+                format!(
+                    "Unknown type '{}' for fixture room {} (expected dm, public or private)",
+                    room.room_type, room.room_id
+                )
+            })?,
+        };
+        let events: Vec<TimelineEvent> = room
+            .events
+            .iter()
+            .cloned()
+            .map(TimelineEvent::from_plaintext)
+            .collect();
+
+        room_inputs.push(RoomStatsInput {
+            room_id: room.room_id.clone(),
+            room_name: room.name.clone(),
+            room_type,
+            stats: pagination::collect_detailed_stats_from_events(
+                &events,
+                window_start_ts,
+                window_end_ts,
+                &account.user_id,
+                &room.room_id,
+                collectors,
+            ),
+        });
+    }
+
+    let mut stats = stats_builder::build_stats(
+        &room_inputs,
+        &account.user_id,
+        account.display_name.clone(),
+        account.avatar_url.clone(),
+        window_scope,
+        fixture.rooms.len(),
+        options.top,
+        collectors,
+    )
+    .context("Failed to build fixture stats")?;
+    if let Some(created_ts) = account.created_ts {
+        stats_builder::apply_account_creation(&mut stats, created_ts, window_scope);
+    }
+    if options.per_room {
+        stats.per_room = Some(stats_builder::build_room_breakdowns(
+            &room_inputs,
+            &account.user_id,
+            window_scope,
+            options.top,
+            collectors,
+        )?);
+    }
+    stats_builder::apply_dm_names(&mut stats, &room_inputs, options.dm_names);
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DmNames;
+    use crate::stats::Count;
+    use std::path::PathBuf;
+
+    fn demo_fixture() -> Fixture {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("examples/fixtures/demo");
+        Fixture::load(&dir).expect("demo fixture should load")
+    }
+
+    #[test]
+    fn test_build_stats_from_demo_fixture() {
+        let fixture = demo_fixture();
+        assert_eq!(fixture.rooms.len(), 3);
+
+        let window_scope = WindowScope::parse("2025").unwrap();
+        let options = CrawlOptions {
+            per_room: true,
+            ..CrawlOptions::default()
+        };
+        let collectors = metrics::builtin_collectors(&options);
+        let stats = build_stats(&fixture, &window_scope, &options, &collectors).unwrap();
+
+        // The 2024 message of Matrix HQ is out of the window
+        assert_eq!(stats.summary.messages_sent.get(), 5);
+        assert_eq!(stats.summary.active_rooms.get(), 3);
+        assert_eq!(stats.summary.dm_rooms.map(Count::get), Some(1));
+        assert_eq!(stats.account.display_name.as_deref(), Some("Alice"));
+        assert!(stats.per_room.is_some());
+
+        // Bob wrote in the DM and in Team, where his member event names him
+        let people = stats.people.unwrap();
+        let partners = people.dm_partners.unwrap();
+        assert_eq!(partners.len(), 1);
+        assert_eq!(partners[0].user_id.as_deref(), Some("@bob:example.org"));
+        assert_eq!(partners[0].display_name.as_deref(), Some("Bob"));
+        assert_eq!(partners[0].messages, 3);
+        let senders = people.group_senders.unwrap();
+        assert_eq!(senders.len(), 1);
+        assert_eq!((senders[0].messages, senders[0].rooms), (1, Some(1)));
+        // Alice wrote to Bob twice
+        let dms = stats.dms.unwrap();
+        assert_eq!(dms.total, 1);
+        assert_eq!(dms.top[0].display_name.as_deref(), Some("Bob"));
+        assert_eq!(dms.top[0].messages, 2);
+
+        // Alice's release note in Team replies to Bob
+        let conversations = stats.conversations.unwrap();
+        assert_eq!(conversations.top_level, 5);
+        assert_eq!(conversations.in_threads, 0);
+        assert_eq!(conversations.replies, 1);
+
+        // The image sent to Bob has no length; the welcome message is the longest
+        let lengths = stats.message_lengths.unwrap();
+        assert_eq!((lengths.short, lengths.medium, lengths.long), (3, 1, 0));
+        let longest = lengths.longest.unwrap();
+        assert_eq!(longest.chars, 25);
+        assert_eq!(
+            longest.permalink,
+            "https://matrix.to/#/!team:example.org/$team-1"
+        );
+
+        // Alice reacted once, to Bob's thanks in Team
+        let given = stats.reactions.as_ref().unwrap().given.as_ref().unwrap();
+        assert_eq!(given.total, 1);
+        assert_eq!(given.top_emojis[0].emoji, "❤️");
+
+        // Alice spoke first on 4 of the 5 days with messages; Bob opened the DM on June 1
+        let fun = stats.fun.as_ref().unwrap();
+        assert_eq!(fun.fields["conversation_starts"], serde_json::json!(4));
+
+        // Alice waved once in Matrix HQ
+        let typed = stats.typed_emojis.unwrap();
+        assert_eq!(typed.total, 1);
+        assert_eq!(typed.top[0].emoji, "👋");
+    }
+
+    #[test]
+    fn test_people_opt_out() {
+        let window_scope = WindowScope::parse("2025").unwrap();
+        let options = CrawlOptions {
+            people: false,
+            ..CrawlOptions::default()
+        };
+        let collectors = metrics::builtin_collectors(&options);
+        let stats = build_stats(&demo_fixture(), &window_scope, &options, &collectors).unwrap();
+        assert!(stats.people.is_none());
+        assert!(stats.dms.is_none());
+    }
+
+    #[test]
+    fn test_dm_names_setting() {
+        let window_scope = WindowScope::parse("2025").unwrap();
+        let options = CrawlOptions {
+            per_room: true,
+            dm_names: DmNames::Initials,
+            ..CrawlOptions::default()
+        };
+        let collectors = metrics::builtin_collectors(&options);
+        let stats = build_stats(&demo_fixture(), &window_scope, &options, &collectors).unwrap();
+        let people = stats.people.unwrap();
+        let partner = &people.dm_partners.unwrap()[0];
+        assert_eq!(partner.display_name.as_deref(), Some("B."));
+        assert!(partner.user_id.is_none());
+        // Group senders are not DM partners
+        assert!(people.group_senders.unwrap()[0].user_id.is_some());
+        let correspondent = &stats.dms.unwrap().top[0];
+        assert_eq!(correspondent.display_name.as_deref(), Some("B."));
+        assert!(correspondent.user_id.is_none());
+
+        let options = CrawlOptions {
+            dm_names: DmNames::Hidden,
+            ..options
+        };
+        let collectors = metrics::builtin_collectors(&options);
+        let stats = build_stats(&demo_fixture(), &window_scope, &options, &collectors).unwrap();
+        let people = stats.people.unwrap();
+        assert!(people.dm_partners.is_none());
+        assert!(people.group_senders.is_some());
+        assert!(stats.dms.is_none());
+        let team = stats.rooms.unwrap().top.unwrap();
+        assert!(team.iter().any(|room| room.name.as_deref() == Some("Team")));
+    }
+
+    #[test]
+    fn test_unknown_room_type() {
+        let mut fixture = demo_fixture();
+        fixture.rooms[0].room_type = "secret".to_string();
+
+        let window_scope = WindowScope::parse("2025").unwrap();
+        let options = CrawlOptions::default();
+        let collectors = metrics::builtin_collectors(&options);
+        let err = build_stats(&fixture, &window_scope, &options, &collectors).unwrap_err();
+        assert!(err.to_string().contains("Unknown type 'secret'"));
+    }
+}
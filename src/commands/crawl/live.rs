@@ -0,0 +1,141 @@
+/// Provisional stats of a running crawl (`my render --live`).
+///
+/// The crawl stores the stats of each room as soon as it is paginated. They are
+/// aggregated here the way the crawl does once every room is done, so a long
+/// crawl (e.g. the life scope) can be previewed before it completes.
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use super::db::{CrawlDb, LiveCrawl, LiveRoomStats};
+use super::metrics::builtin_collectors;
+use super::stats_builder::{build_stats, RoomStatsInput};
+use super::{CrawlOptions, RoomType};
+use crate::stats::Stats;
+use crate::window::WindowScope;
+
+/// Account stats from the rooms crawled so far, with the crawl progress
+pub struct LiveSnapshot {
+    pub crawl: LiveCrawl,
+    pub stats: Stats,
+}
+
+/// Store the stats of a crawled room for live reports
+pub fn record_room(db: &CrawlDb, window_key: &str, input: &RoomStatsInput) -> Result<()> {
+    let stats_json =
+        serde_json::to_string(&input.stats).context("Failed to serialize room stats")?;
+    db.save_live_room_stats(
+        window_key,
+        &LiveRoomStats {
+            room_id: input.room_id.clone(),
+            room_name: input.room_name.clone(),
+            room_type: input.room_type.key().to_string(),
+            stats_json,
+        },
+    )
+}
+
+/// Aggregates the rooms crawled so far for `window`, or for the latest started
+/// crawl when `None`.
+///
+/// Returns `None` when no crawl of the window was recorded. Rankings are cut at
+/// the default length and the profile is left out: those come with the final
+/// stats.
+pub fn snapshot(
+    account_id: &str,
+    account_dir: &Path,
+    window: Option<&str>,
+) -> Result<Option<LiveSnapshot>> {
+    let db = CrawlDb::init(account_dir)?;
+    let Some(crawl) = db.get_live_crawl(window)? else {
+        return Ok(None);
+    };
+    let window_scope = WindowScope::parse(&crawl.window_key).context("Failed to parse window")?;
+    let inputs = room_inputs(db.get_live_room_stats(&crawl.window_key)?)?;
+
+    let options = CrawlOptions::default();
+    let stats = build_stats(
+        &inputs,
+        account_id,
+        None,
+        None,
+        &window_scope,
+        crawl.joined_rooms,
+        options.top,
+        &builtin_collectors(&options),
+    )
+    .context("Failed to build live stats")?;
+
+    Ok(Some(LiveSnapshot { crawl, stats }))
+}
+
+/// Rooms stored in the crawl database, ready for aggregation. Rooms of an
+/// unknown type are left out.
+pub(super) fn room_inputs(rooms: Vec<LiveRoomStats>) -> Result<Vec<RoomStatsInput>> {
+    let mut inputs = Vec::new();
+    for room in rooms {
+        let Some(room_type) = RoomType::from_key(&room.room_type) else {
+            continue;
+        };
+        let stats = serde_json::from_str(&room.stats_json)
+            .with_context(|| format!("Failed to parse stored stats of {}", room.room_id))?;
+        inputs.push(RoomStatsInput {
+            room_id: room.room_id,
+            room_name: room.room_name,
+            room_type,
+            stats,
+        });
+    }
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::crawl::pagination::empty_detailed_stats;
+
+    #[test]
+    fn test_live_snapshot_follows_crawl() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = CrawlDb::init(dir.path()).unwrap();
+        assert!(snapshot("@alice:example.org", dir.path(), None)
+            .unwrap()
+            .is_none());
+
+        db.start_live_crawl("2025", 5, 2, 1_000).unwrap();
+        let mut stats = empty_detailed_stats(None, None);
+        stats.user_events = 3;
+        stats.by_month.insert("2025-03".to_string(), 3);
+        stats.active_dates.insert("2025-03-10".to_string(), true);
+        let input = RoomStatsInput {
+            room_id: "!room:example.org".to_string(),
+            room_name: Some("Room".to_string()),
+            room_type: RoomType::Private,
+            stats,
+        };
+        record_room(&db, "2025", &input).unwrap();
+
+        let live = snapshot("@alice:example.org", dir.path(), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(live.crawl.window_key, "2025");
+        assert_eq!((live.crawl.rooms_done, live.crawl.rooms_total), (1, 2));
+        assert_eq!(live.crawl.finished_ts, None);
+        assert_eq!(live.stats.summary.messages_sent.get(), 3);
+        assert!(snapshot("@alice:example.org", dir.path(), Some("2024"))
+            .unwrap()
+            .is_none());
+
+        // Once finished, the stats file takes over
+        db.finish_live_crawl("2025", 2_000).unwrap();
+        let crawl = db.get_live_crawl(Some("2025")).unwrap().unwrap();
+        assert_eq!(crawl.finished_ts, Some(2_000));
+        assert_eq!(crawl.rooms_done, 0);
+
+        // A new crawl of the window starts over
+        db.start_live_crawl("2025", 5, 4, 3_000).unwrap();
+        record_room(&db, "2025", &input).unwrap();
+        db.start_live_crawl("2025", 5, 4, 4_000).unwrap();
+        let crawl = db.get_live_crawl(None).unwrap().unwrap();
+        assert_eq!((crawl.rooms_done, crawl.finished_ts), (0, None));
+    }
+}
@@ -0,0 +1,1938 @@
+/// Pluggable metric collectors.
+///
+/// A [`MetricCollector`] observes every in-window event while a room is paginated
+/// and later turns the per-room results into account-level statistics. Built-in
+/// optional metrics (reactions, first responder, typing time, message twins,
+/// message kinds and types, conversations, message lengths, image packs, typed
+/// emojis, DM balance, people, DM correspondents) are implemented on top of this
+/// trait; downstream builds can add their own collectors to `plugins.rs`
+/// (`metric-plugins` cargo feature) or pass them to [`super::run_with_collectors`]
+/// without touching the pagination code.
+use anyhow::Result;
+use chrono::{Datelike, TimeZone};
+use matrix_sdk::ruma::events::room::message::{MessageType, Relation};
+use matrix_sdk::ruma::events::sticker::StickerMediaSource;
+use matrix_sdk::ruma::events::{AnySyncMessageLikeEvent, AnySyncStateEvent, AnySyncTimelineEvent};
+use std::collections::{HashMap, HashSet};
+
+use super::emoji;
+use super::stats_builder::RoomStatsInput;
+use super::types::DetailedPaginationStats;
+use super::{CrawlOptions, RoomType};
+use crate::config::TravelPeriod;
+use crate::stats::{
+    Conversations, DmBalanceEntry, Dms, EmojiEntry, Fun, GivenReactions, LongestMessage,
+    MessageLengths, MessageReactionEntry, People, PersonEntry, Reactions, RoomReactionEntry, Stats,
+    TypedEmojis,
+};
+
+/// Metadata about the event being processed, shared with every collector.
+pub struct EventContext<'a> {
+    #[cfg(feature = "metric-plugins")]
+    pub user_id: &'a str,
+    pub event_id: Option<&'a str>,
+    pub ts_millis: i64,
+    pub is_user_event: bool,
+}
+
+/// A metric computed from timeline events.
+///
+/// Collectors are stateless and shared across concurrently crawled rooms:
+/// per-room state lives in [`DetailedPaginationStats`] (built-in fields or
+/// `custom_metrics` for the collectors of the `metric-plugins` feature).
+pub trait MetricCollector: Send + Sync {
+    /// Called for every in-window event of a room, after the core temporal
+    /// bucketing has run (so the user's own message IDs are already known).
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    );
+
+    /// Called once during aggregation with every active room (rooms where the
+    /// user sent at least one message); writes results into `stats`.
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()>;
+
+    /// Short name shown in diagnostics such as `my bench` (the type name by default).
+    fn name(&self) -> &'static str {
+        let type_name = std::any::type_name::<Self>();
+        type_name.rsplit("::").next().unwrap_or(type_name)
+    }
+}
+
+/// Typing speed assumed when estimating time spent writing messages.
+pub const DEFAULT_TYPING_WPM: u32 = 40;
+
+/// Returns the collectors enabled in this build, configured from crawl options.
+pub fn builtin_collectors(options: &CrawlOptions) -> Vec<Box<dyn MetricCollector>> {
+    let mut collectors: Vec<Box<dyn MetricCollector>> = vec![
+        Box::new(ReactionCollector {
+            previews: options.message_previews,
+            fold: options.fold_emojis,
+            top: options.top,
+        }),
+        Box::new(FirstResponderCollector),
+        Box::new(ConversationStarterCollector),
+        Box::new(TypingTimeCollector {
+            wpm: options.typing_wpm,
+        }),
+        Box::new(MessageTwinsCollector),
+        Box::new(MessageKindCollector),
+        Box::new(MessageTypeCollector),
+        Box::new(ConversationCollector),
+        Box::new(MessageLengthCollector),
+        Box::new(ImagePackCollector),
+        Box::new(TypedEmojiCollector {
+            fold: options.fold_emojis,
+            top: options.top,
+        }),
+        Box::new(TravelCollector {
+            travel: options.travel.clone(),
+            home: crate::config::system_timezone(),
+        }),
+        Box::new(DmBalanceCollector { top: options.top }),
+    ];
+    // Senders are only recorded when the user did not opt out
+    if options.people {
+        collectors.push(Box::new(PeopleCollector { top: options.top }));
+        collectors.push(Box::new(DmCorrespondentCollector { top: options.top }));
+    }
+    #[cfg(feature = "metric-plugins")]
+    collectors.extend(super::plugins::collectors(options));
+    collectors
+}
+
+/// Appends a field to the Fun section, creating the section if needed.
+pub fn insert_fun_field(stats: &mut Stats, key: &str, value: serde_json::Value) {
+    stats
+        .fun
+        .get_or_insert_with(|| Fun {
+            fields: indexmap::IndexMap::new(),
+        })
+        .fields
+        .insert(key.to_string(), value);
+}
+
+// ============================================================================
+// Reactions
+// ============================================================================
+
+/// Maximum characters kept from a message body in previews.
+const PREVIEW_MAX_CHARS: usize = 80;
+
+/// Counts reactions received on the user's messages, per emoji, per message and
+/// per month of the reaction, and the reactions the user sent, per emoji. The
+/// distinct emoji of each message make the `reaction_variety` fun fact.
+///
+/// With `previews`, also keeps a short excerpt of each of the user's messages so
+/// the most reacted ones can show what they said. With `fold`, the forms of an
+/// emoji (skin tones, variation selectors) are ranked as one, shown in their
+/// most used form.
+pub struct ReactionCollector {
+    pub previews: bool,
+    pub fold: bool,
+    /// Entries kept in each ranking
+    pub top: usize,
+}
+
+impl MetricCollector for ReactionCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        if self.previews && ctx.is_user_event {
+            if let AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(
+                message,
+            )) = event
+            {
+                if let (Some(original), Some(event_id)) = (message.as_original(), ctx.event_id) {
+                    if let Some(preview) = message_preview(&original.content.msgtype) {
+                        room.message_previews.insert(event_id.to_string(), preview);
+                    }
+                }
+            }
+        }
+
+        let AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::Reaction(r)) = event else {
+            return;
+        };
+        let Some(original) = r.as_original() else {
+            return;
+        };
+
+        // Extract emoji from annotation
+        let emoji = original.content.relates_to.key.clone();
+        let event_id = original.content.relates_to.event_id.to_string();
+
+        // Reactions the user sent, whoever they reacted to
+        if ctx.is_user_event {
+            *room
+                .reactions_given_by_emoji
+                .entry(emoji.clone())
+                .or_insert(0) += 1;
+        }
+
+        // Only track reactions on user's messages
+        // NOTE: This approach has a known limitation: if a reaction event
+        // is encountered before its corresponding message event (in cached
+        // events or pagination batches), the reaction will be skipped because
+        // the message ID is not yet in user_message_ids. Since pagination
+        // proceeds backward from recent to older events, reactions are
+        // typically processed before their messages, so most reactions may
+        // be skipped. A two-pass approach or forward-first traversal would
+        // be needed to fully capture reactions.
+        if room.user_message_ids.contains_key(&event_id) {
+            room.reaction_emojis_by_message
+                .entry(event_id.clone())
+                .or_default()
+                .insert(emoji.clone());
+            *room.reactions_by_emoji.entry(emoji).or_insert(0) += 1;
+            *room.reactions_by_message.entry(event_id).or_insert(0) += 1;
+            if let Some(dt) = chrono::Local.timestamp_millis_opt(ctx.ts_millis).single() {
+                let month = format!("{:02}", dt.month());
+                *room.reactions_by_month.entry(month).or_insert(0) += 1;
+            }
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let mut by_emoji: HashMap<String, i32> = HashMap::new();
+        let mut by_message: HashMap<String, i32> = HashMap::new();
+        let mut by_month: HashMap<String, i32> = HashMap::new();
+        let mut given_by_emoji: HashMap<String, i32> = HashMap::new();
+        let mut emojis_by_message: HashMap<&str, HashSet<&str>> = HashMap::new();
+        let mut previews: HashMap<&str, &str> = HashMap::new();
+        let mut emote_names: HashMap<&str, &(String, Option<String>)> = HashMap::new();
+        let mut by_room: Vec<(&RoomStatsInput, i32)> = Vec::new();
+
+        for room in rooms {
+            let room_total: i32 = room.stats.reactions_by_emoji.values().sum();
+            if room_total > 0 {
+                by_room.push((room, room_total));
+            }
+            for (emoji, count) in &room.stats.reactions_by_emoji {
+                *by_emoji.entry(emoji.clone()).or_insert(0) += count;
+            }
+            for (msg_id, count) in &room.stats.reactions_by_message {
+                *by_message.entry(msg_id.clone()).or_insert(0) += count;
+            }
+            for (month, count) in &room.stats.reactions_by_month {
+                *by_month.entry(month.clone()).or_insert(0) += count;
+            }
+            for (emoji, count) in &room.stats.reactions_given_by_emoji {
+                *given_by_emoji.entry(emoji.clone()).or_insert(0) += count;
+            }
+            for (msg_id, emojis) in &room.stats.reaction_emojis_by_message {
+                emojis_by_message
+                    .entry(msg_id.as_str())
+                    .or_default()
+                    .extend(emojis.iter().map(String::as_str));
+            }
+            for (msg_id, preview) in &room.stats.message_previews {
+                previews.insert(msg_id, preview);
+            }
+            for (url, name) in &room.stats.emote_names {
+                emote_names.insert(url, name);
+            }
+        }
+
+        let name_emotes = |entries: &mut Vec<EmojiEntry>| {
+            for entry in entries {
+                if let Some((shortcode, pack)) = emote_names.get(entry.emoji.as_str()) {
+                    entry.shortcode = Some(shortcode.clone());
+                    entry.pack = pack.clone();
+                }
+            }
+        };
+        let variety = most_varied_message(&emojis_by_message, &by_message, self.fold).map(
+            |(event_id, emojis, reactions)| {
+                let mut fact = serde_json::json!({
+                    "emojis": emojis,
+                    "reactions": reactions,
+                    "permalink": format!("https://matrix.to/#/{}", event_id),
+                });
+                if let Some(preview) = previews.get(event_id) {
+                    fact["preview"] = serde_json::Value::from(*preview);
+                }
+                fact
+            },
+        );
+        let mut top_emojis = rank_top_emojis(by_emoji, self.fold, self.top);
+        name_emotes(&mut top_emojis);
+        let top_messages = rank_top_messages(by_message, &previews, self.top);
+        let top_rooms = rank_top_reacted_rooms(by_room, self.top);
+        let total_reactions: i32 = top_emojis.iter().map(|e| e.count).sum();
+
+        let given_total: i32 = given_by_emoji.values().sum();
+        let given = if given_total > 0 {
+            let mut top_emojis = rank_top_emojis(given_by_emoji, self.fold, self.top);
+            name_emotes(&mut top_emojis);
+            Some(GivenReactions {
+                total: given_total,
+                top_emojis,
+            })
+        } else {
+            None
+        };
+
+        if total_reactions == 0 && given.is_none() {
+            return Ok(());
+        }
+
+        stats.reactions = Some(Reactions {
+            total: if total_reactions > 0 {
+                Some(total_reactions)
+            } else {
+                None
+            },
+            top_emojis: if !top_emojis.is_empty() {
+                Some(top_emojis)
+            } else {
+                None
+            },
+            top_messages: if !top_messages.is_empty() {
+                Some(top_messages)
+            } else {
+                None
+            },
+            top_rooms: if !top_rooms.is_empty() {
+                Some(top_rooms)
+            } else {
+                None
+            },
+            by_month: if !by_month.is_empty() {
+                Some(by_month)
+            } else {
+                None
+            },
+            given,
+        });
+        if let Some(variety) = variety {
+            insert_fun_field(stats, "reaction_variety", variety);
+        }
+
+        Ok(())
+    }
+}
+
+/// Distinct emoji a message needs to make the `reaction_variety` fun fact.
+const VARIETY_MIN_EMOJIS: usize = 3;
+
+/// Finds the user's message that collected the most distinct emoji (private).
+///
+/// Returns its event ID, distinct emoji and reactions; ties go to the most
+/// reacted message. With `fold`, the forms of an emoji count once.
+fn most_varied_message<'a>(
+    emojis_by_message: &HashMap<&'a str, HashSet<&str>>,
+    reactions_by_message: &HashMap<String, i32>,
+    fold: bool,
+) -> Option<(&'a str, usize, i32)> {
+    emojis_by_message
+        .iter()
+        .map(|(&event_id, emojis)| {
+            let distinct = if fold {
+                emojis
+                    .iter()
+                    .map(|emoji| fold_emoji(emoji))
+                    .collect::<HashSet<_>>()
+                    .len()
+            } else {
+                emojis.len()
+            };
+            let reactions = reactions_by_message.get(event_id).copied().unwrap_or(0);
+            (event_id, distinct, reactions)
+        })
+        .filter(|(_, distinct, _)| *distinct >= VARIETY_MIN_EMOJIS)
+        .max_by(|a, b| {
+            a.1.cmp(&b.1)
+                .then_with(|| a.2.cmp(&b.2))
+                .then_with(|| b.0.cmp(a.0))
+        })
+}
+
+/// Ranks top emojis by count, folding the forms of an emoji together
+/// when `fold` is set (private).
+fn rank_top_emojis(emojis: HashMap<String, i32>, fold: bool, top: usize) -> Vec<EmojiEntry> {
+    let mut groups: HashMap<String, Vec<(String, i32)>> = HashMap::new();
+    for (emoji, count) in emojis {
+        let key = if fold {
+            fold_emoji(&emoji)
+        } else {
+            emoji.clone()
+        };
+        groups.entry(key).or_default().push((emoji, count));
+    }
+
+    let mut entries: Vec<EmojiEntry> = groups
+        .into_values()
+        .map(|mut forms| {
+            // Most used form first, shown for the whole group
+            forms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let count = forms.iter().map(|(_, count)| count).sum();
+            let mut forms = forms.into_iter().map(|(emoji, _)| emoji);
+            let emoji = forms.next().unwrap_or_default();
+            let variants: Vec<String> = forms.collect();
+            EmojiEntry {
+                emoji,
+                count,
+                shortcode: None,
+                pack: None,
+                variants: if !variants.is_empty() {
+                    Some(variants)
+                } else {
+                    None
+                },
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.emoji.cmp(&b.emoji)));
+    entries.truncate(top);
+    entries
+}
+
+/// Strips skin-tone modifiers and variation selectors, so that 👍, 👍🏻 and 👍️
+/// share a key. Keys made only of those are kept as is.
+fn fold_emoji(emoji: &str) -> String {
+    let folded: String = emoji
+        .chars()
+        .filter(|c| !matches!(c, '\u{FE0E}' | '\u{FE0F}' | '\u{1F3FB}'..='\u{1F3FF}'))
+        .collect();
+    if folded.is_empty() {
+        emoji.to_string()
+    } else {
+        folded
+    }
+}
+
+/// Ranks top messages by reaction count, with their preview when kept (private).
+fn rank_top_messages(
+    messages: HashMap<String, i32>,
+    previews: &HashMap<&str, &str>,
+    top: usize,
+) -> Vec<MessageReactionEntry> {
+    let mut message_vec: Vec<_> = messages.into_iter().collect();
+    message_vec.sort_by_key(|a| std::cmp::Reverse(a.1));
+
+    message_vec
+        .into_iter()
+        .take(top)
+        .map(|(event_id, count)| MessageReactionEntry {
+            permalink: format!("https://matrix.to/#/{}", event_id),
+            reaction_count: count,
+            preview: previews.get(event_id.as_str()).map(|p| p.to_string()),
+        })
+        .collect()
+}
+
+/// Single-line excerpt of a message, capped at [`PREVIEW_MAX_CHARS`] (private).
+///
+/// Media are replaced by their kind so captions and file names are not kept,
+/// and the `> ` quoted reply fallback is skipped.
+fn message_preview(msgtype: &MessageType) -> Option<String> {
+    let body = match msgtype {
+        MessageType::Text(content) => &content.body,
+        MessageType::Notice(content) => &content.body,
+        MessageType::Emote(content) => &content.body,
+        MessageType::Image(_) => return Some("[image]".to_string()),
+        MessageType::Video(_) => return Some("[video]".to_string()),
+        MessageType::Audio(_) => return Some("[audio]".to_string()),
+        MessageType::File(_) => return Some("[file]".to_string()),
+        MessageType::Location(_) => return Some("[location]".to_string()),
+        _ => return None,
+    };
+
+    let text = body
+        .lines()
+        .skip_while(|line| line.starts_with("> "))
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    if text.chars().count() <= PREVIEW_MAX_CHARS {
+        return Some(text);
+    }
+    let cut: String = text.chars().take(PREVIEW_MAX_CHARS - 1).collect();
+    Some(format!("{}…", cut.trim_end()))
+}
+
+/// Ranks rooms by reactions received on the user's messages (private).
+///
+/// Ties are broken by room ID so the ranking is stable across runs.
+fn rank_top_reacted_rooms(
+    mut rooms: Vec<(&RoomStatsInput, i32)>,
+    top: usize,
+) -> Vec<RoomReactionEntry> {
+    rooms.sort_by(|(a, a_count), (b, b_count)| {
+        b_count.cmp(a_count).then_with(|| a.room_id.cmp(&b.room_id))
+    });
+
+    rooms
+        .into_iter()
+        .take(top)
+        .map(|(room, count)| RoomReactionEntry {
+            name: room.room_name.clone(),
+            reaction_count: count,
+            permalink: format!("https://matrix.to/#/{}", room.room_id),
+        })
+        .collect()
+}
+
+// ============================================================================
+// First responder
+// ============================================================================
+
+/// Counts messages from others where the user's reply came first.
+///
+/// Tracks the earliest reply per replied-to message, whoever sent it. Both rich
+/// replies and in-thread replies count; thread fallbacks for older clients do not.
+///
+/// Only messages seen in the window from someone else count, so that replies to
+/// the user's own older messages are not taken for first responses. The sender
+/// of a replied-to message is recorded when it comes after its replies, as in
+/// backward pagination; messages replayed oldest first (cached events,
+/// fixtures) come before their replies and are left out.
+pub struct FirstResponderCollector;
+
+impl MetricCollector for FirstResponderCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        let AnySyncTimelineEvent::MessageLike(message) = event else {
+            return;
+        };
+        if let Some(event_id) = ctx.event_id {
+            if room.first_replies.contains_key(event_id) {
+                room.replied_to_senders
+                    .insert(event_id.to_string(), ctx.is_user_event);
+            }
+        }
+        let AnySyncMessageLikeEvent::RoomMessage(message) = message else {
+            return;
+        };
+        let Some(original) = message.as_original() else {
+            return;
+        };
+
+        let in_reply_to = match &original.content.relates_to {
+            Some(Relation::Reply { in_reply_to }) => in_reply_to,
+            Some(Relation::Thread(thread)) if !thread.is_falling_back => {
+                match thread.in_reply_to.as_ref() {
+                    Some(in_reply_to) => in_reply_to,
+                    None => return,
+                }
+            }
+            _ => return,
+        };
+
+        let first_reply = room
+            .first_replies
+            .entry(in_reply_to.event_id.to_string())
+            .or_insert((ctx.ts_millis, ctx.is_user_event));
+        if ctx.ts_millis < first_reply.0 {
+            *first_reply = (ctx.ts_millis, ctx.is_user_event);
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let first_responses: usize = rooms
+            .iter()
+            .map(|room| {
+                room.stats
+                    .first_replies
+                    .iter()
+                    .filter(|(event_id, (_, by_user))| {
+                        *by_user
+                            && room.stats.replied_to_senders.get(event_id.as_str()) == Some(&false)
+                    })
+                    .count()
+            })
+            .sum();
+
+        if first_responses > 0 {
+            insert_fun_field(
+                stats,
+                "first_responder_replies",
+                serde_json::Value::from(first_responses),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Conversation starter
+// ============================================================================
+
+/// Counts the days the user sent the first message of a room.
+///
+/// Tracks the earliest message of each local day per room, whoever sent it.
+/// Edits do not start a conversation; encrypted messages count since their
+/// sender is known even when they cannot be decrypted.
+pub struct ConversationStarterCollector;
+
+impl MetricCollector for ConversationStarterCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        let AnySyncTimelineEvent::MessageLike(message) = event else {
+            return;
+        };
+        match message {
+            AnySyncMessageLikeEvent::RoomMessage(message) => {
+                let Some(original) = message.as_original() else {
+                    return;
+                };
+                if matches!(original.content.relates_to, Some(Relation::Replacement(_))) {
+                    return;
+                }
+            }
+            AnySyncMessageLikeEvent::RoomEncrypted(_) | AnySyncMessageLikeEvent::Sticker(_) => {}
+            _ => return,
+        }
+        let Some(dt) = chrono::Local.timestamp_millis_opt(ctx.ts_millis).single() else {
+            return;
+        };
+
+        let first_message = room
+            .first_messages_by_day
+            .entry(dt.format("%Y-%m-%d").to_string())
+            .or_insert((ctx.ts_millis, ctx.is_user_event));
+        if ctx.ts_millis < first_message.0 {
+            *first_message = (ctx.ts_millis, ctx.is_user_event);
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let starts: usize = rooms
+            .iter()
+            .map(|room| {
+                room.stats
+                    .first_messages_by_day
+                    .values()
+                    .filter(|(_, by_user)| *by_user)
+                    .count()
+            })
+            .sum();
+
+        if starts > 0 {
+            insert_fun_field(
+                stats,
+                "conversation_starts",
+                serde_json::Value::from(starts),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Typing time
+// ============================================================================
+
+/// Estimates the time the user spent typing messages, from the words they sent.
+///
+/// Counts words in text, notice and emote bodies, ignoring the quoted fallback
+/// of rich replies, and converts them to minutes at `wpm` words per minute.
+pub struct TypingTimeCollector {
+    pub wpm: u32,
+}
+
+impl MetricCollector for TypingTimeCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        if !ctx.is_user_event {
+            return;
+        }
+        let AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(message)) =
+            event
+        else {
+            return;
+        };
+        let Some(original) = message.as_original() else {
+            return;
+        };
+
+        let body = match &original.content.msgtype {
+            MessageType::Text(content) => &content.body,
+            MessageType::Notice(content) => &content.body,
+            MessageType::Emote(content) => &content.body,
+            _ => return,
+        };
+
+        room.typed_words += count_typed_words(body) as i64;
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        if self.wpm == 0 {
+            return Ok(());
+        }
+
+        let words: i64 = rooms.iter().map(|room| room.stats.typed_words).sum();
+        let minutes = (words as f64 / self.wpm as f64).round() as i64;
+
+        if minutes > 0 {
+            insert_fun_field(stats, "typing_minutes", serde_json::Value::from(minutes));
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts words in a message body, skipping the `> ` quoted reply fallback (private).
+fn count_typed_words(body: &str) -> usize {
+    body.lines()
+        .skip_while(|line| line.starts_with("> "))
+        .map(|line| line.split_whitespace().count())
+        .sum()
+}
+
+// ============================================================================
+// Message twins
+// ============================================================================
+
+/// Number of top rooms (by messages sent) compared for message twins.
+const TWIN_CANDIDATE_ROOMS: usize = 5;
+
+/// Minimum messages sent in a room for its activity profile to be meaningful.
+const TWIN_MIN_MESSAGES: usize = 10;
+
+/// Finds the two top rooms whose weekday × hour activity profiles are the most alike.
+///
+/// Profiles are compared with cosine similarity over the 168 weekday/hour buckets,
+/// so rooms match on *when* the user writes there, not on how much.
+pub struct MessageTwinsCollector;
+
+impl MetricCollector for MessageTwinsCollector {
+    fn on_event(
+        &self,
+        _event: &AnySyncTimelineEvent,
+        _ctx: &EventContext<'_>,
+        _room: &mut DetailedPaginationStats,
+    ) {
+        // Uses the per-room weekday × hour matrix filled by the core bucketing
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let mut candidates: Vec<&RoomStatsInput> = rooms
+            .iter()
+            .copied()
+            .filter(|room| room.stats.user_events >= TWIN_MIN_MESSAGES)
+            .collect();
+        candidates.sort_by(|a, b| {
+            b.stats
+                .user_events
+                .cmp(&a.stats.user_events)
+                .then_with(|| a.room_id.cmp(&b.room_id))
+        });
+        candidates.truncate(TWIN_CANDIDATE_ROOMS);
+
+        let mut best: Option<(f64, &RoomStatsInput, &RoomStatsInput)> = None;
+        for (i, a) in candidates.iter().enumerate() {
+            for b in &candidates[i + 1..] {
+                let similarity = profile_similarity(a, b);
+                if best.is_none_or(|(top, _, _)| similarity > top) {
+                    best = Some((similarity, a, b));
+                }
+            }
+        }
+
+        let Some((similarity, a, b)) = best else {
+            return Ok(());
+        };
+        if similarity <= 0.0 {
+            return Ok(());
+        }
+
+        let room_label = |room: &RoomStatsInput| {
+            room.room_name
+                .clone()
+                .unwrap_or_else(|| room.room_id.clone())
+        };
+        insert_fun_field(
+            stats,
+            "message_twins",
+            serde_json::json!({
+                "rooms": [room_label(a), room_label(b)],
+                "similarity": (similarity * 100.0).round() / 100.0,
+            }),
+        );
+
+        Ok(())
+    }
+}
+
+/// Cosine similarity of two rooms' weekday × hour activity (private).
+fn profile_similarity(a: &RoomStatsInput, b: &RoomStatsInput) -> f64 {
+    let profile_a = &a.stats.by_weekday_hour;
+    let profile_b = &b.stats.by_weekday_hour;
+
+    let norm = |profile: &HashMap<String, HashMap<String, i32>>| {
+        profile
+            .values()
+            .flat_map(|hours| hours.values())
+            .map(|&count| (count as f64).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    };
+
+    let dot: f64 = profile_a
+        .iter()
+        .flat_map(|(weekday, hours)| {
+            hours.iter().map(move |(hour, &count)| {
+                let other = profile_b
+                    .get(weekday)
+                    .and_then(|hours| hours.get(hour))
+                    .copied()
+                    .unwrap_or(0);
+                count as f64 * other as f64
+            })
+        })
+        .sum();
+
+    let norms = norm(profile_a) * norm(profile_b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+// ============================================================================
+// Message kinds
+// ============================================================================
+
+/// Splits the user's messages of each room type by kind: text, media or voice.
+///
+/// Messages of other kinds (locations, verification requests, undecryptable
+/// messages...) are left out, so a room type's kinds may sum to less than its
+/// messages.
+pub struct MessageKindCollector;
+
+impl MetricCollector for MessageKindCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        if !ctx.is_user_event {
+            return;
+        }
+        let AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(message)) =
+            event
+        else {
+            return;
+        };
+        let Some(original) = message.as_original() else {
+            return;
+        };
+        if let Some(kind) = message_kind(&original.content.msgtype) {
+            *room.messages_by_kind.entry(kind.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let mut by_type: HashMap<String, HashMap<String, i32>> = HashMap::new();
+        for room in rooms {
+            for (kind, count) in &room.stats.messages_by_kind {
+                *by_type
+                    .entry(room.room_type.key().to_string())
+                    .or_default()
+                    .entry(kind.clone())
+                    .or_insert(0) += count;
+            }
+        }
+
+        if let Some(ref mut rooms_section) = stats.rooms {
+            if !by_type.is_empty() {
+                rooms_section.messages_by_room_type_kind = Some(by_type);
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Message types
+// ============================================================================
+
+/// Counts the user's messages by msgtype (text, images, files, emotes...), their
+/// edits, and the length of their longest text message.
+///
+/// These are per-room figures only: the Fun section sums them with the other
+/// core facts when the stats are built, so that its facts keep their order.
+pub struct MessageTypeCollector;
+
+impl MetricCollector for MessageTypeCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        if !ctx.is_user_event {
+            return;
+        }
+        let AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(message)) =
+            event
+        else {
+            return;
+        };
+        let Some(original) = message.as_original() else {
+            return;
+        };
+        if matches!(original.content.relates_to, Some(Relation::Replacement(_))) {
+            room.user_edits += 1;
+            return;
+        }
+        *room
+            .user_messages_by_msgtype
+            .entry(original.content.msgtype.msgtype().to_string())
+            .or_insert(0) += 1;
+        let body = match &original.content.msgtype {
+            MessageType::Text(content) => &content.body,
+            MessageType::Notice(content) => &content.body,
+            MessageType::Emote(content) => &content.body,
+            _ => return,
+        };
+        room.longest_message_chars = room.longest_message_chars.max(message_length(body));
+    }
+
+    fn aggregate(&self, _rooms: &[&RoomStatsInput], _stats: &mut Stats) -> Result<()> {
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Conversations
+// ============================================================================
+
+/// Splits the user's messages between threads and the main timeline, and
+/// counts those replying to another message.
+///
+/// Edits are not messages of their own; undecryptable messages are left out
+/// since their relations are encrypted too.
+pub struct ConversationCollector;
+
+impl MetricCollector for ConversationCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        if !ctx.is_user_event {
+            return;
+        }
+        let AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(message)) =
+            event
+        else {
+            return;
+        };
+        let Some(original) = message.as_original() else {
+            return;
+        };
+        match &original.content.relates_to {
+            Some(Relation::Replacement(_)) => {}
+            Some(Relation::Thread(thread)) => {
+                room.user_thread_messages += 1;
+                room.user_threads.insert(thread.event_id.to_string());
+                // Thread messages point at the previous one as a fallback for
+                // clients without threads: only a real reply counts
+                if thread.in_reply_to.is_some() && !thread.is_falling_back {
+                    room.user_replies += 1;
+                }
+            }
+            Some(Relation::Reply { .. }) => {
+                room.user_top_level_messages += 1;
+                room.user_replies += 1;
+            }
+            _ => room.user_top_level_messages += 1,
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let mut conversations = Conversations {
+            top_level: 0,
+            in_threads: 0,
+            threads: 0,
+            replies: 0,
+        };
+        for room in rooms {
+            conversations.top_level += room.stats.user_top_level_messages;
+            conversations.in_threads += room.stats.user_thread_messages;
+            conversations.threads += room.stats.user_threads.len() as i32;
+            conversations.replies += room.stats.user_replies;
+        }
+
+        if conversations.top_level + conversations.in_threads > 0 {
+            stats.conversations = Some(conversations);
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Message lengths
+// ============================================================================
+
+/// Longest message counted as short, in characters.
+pub const SHORT_MESSAGE_MAX_CHARS: usize = 20;
+
+/// Longest message counted as medium, in characters; longer ones are long.
+pub const MEDIUM_MESSAGE_MAX_CHARS: usize = 140;
+
+/// Sorts the user's text messages into short, medium and long ones and finds
+/// the longest.
+///
+/// Lengths are counted in characters of the text, notice or emote body,
+/// without the quoted fallback of rich replies. Edits are not messages of
+/// their own and are left out.
+pub struct MessageLengthCollector;
+
+impl MetricCollector for MessageLengthCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        if !ctx.is_user_event {
+            return;
+        }
+        let AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(message)) =
+            event
+        else {
+            return;
+        };
+        let Some(original) = message.as_original() else {
+            return;
+        };
+        if matches!(original.content.relates_to, Some(Relation::Replacement(_))) {
+            return;
+        }
+        let body = match &original.content.msgtype {
+            MessageType::Text(content) => &content.body,
+            MessageType::Notice(content) => &content.body,
+            MessageType::Emote(content) => &content.body,
+            _ => return,
+        };
+
+        let chars = message_length(body);
+        *room
+            .messages_by_length
+            .entry(length_bucket(chars).to_string())
+            .or_insert(0) += 1;
+        if let Some(event_id) = ctx.event_id {
+            if room
+                .longest_message
+                .as_ref()
+                .is_none_or(|(longest, _)| chars > *longest)
+            {
+                room.longest_message = Some((chars, event_id.to_string()));
+            }
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let mut lengths = MessageLengths {
+            short: 0,
+            medium: 0,
+            long: 0,
+            longest: None,
+        };
+        for room in rooms {
+            for (bucket, count) in &room.stats.messages_by_length {
+                match bucket.as_str() {
+                    "short" => lengths.short += count,
+                    "medium" => lengths.medium += count,
+                    _ => lengths.long += count,
+                }
+            }
+        }
+
+        // Ties go to the first room by ID, so reruns pick the same message
+        let longest = rooms
+            .iter()
+            .filter_map(|room| {
+                let (chars, event_id) = room.stats.longest_message.as_ref()?;
+                Some((*chars, room.room_id.as_str(), event_id))
+            })
+            .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(a.1)));
+        lengths.longest = longest.map(|(chars, room_id, event_id)| LongestMessage {
+            chars: chars as i32,
+            permalink: format!("https://matrix.to/#/{}/{}", room_id, event_id),
+        });
+
+        if lengths.short + lengths.medium + lengths.long > 0 {
+            stats.message_lengths = Some(lengths);
+        }
+        Ok(())
+    }
+}
+
+/// Characters of a message body, without the `> ` quoted reply fallback.
+pub(super) fn message_length(body: &str) -> usize {
+    let lines: Vec<&str> = body
+        .lines()
+        .skip_while(|line| line.starts_with("> "))
+        .collect();
+    lines.join("\n").trim().chars().count()
+}
+
+/// Bucket of a message of `chars` characters in `messages_by_length` (private).
+fn length_bucket(chars: usize) -> &'static str {
+    if chars <= SHORT_MESSAGE_MAX_CHARS {
+        "short"
+    } else if chars <= MEDIUM_MESSAGE_MAX_CHARS {
+        "medium"
+    } else {
+        "long"
+    }
+}
+
+// ============================================================================
+// Image packs
+// ============================================================================
+
+/// Packs kept in the `favorite_packs` fun fact.
+const FAVORITE_PACKS: usize = 3;
+
+/// Ranks the image packs (MSC2545) the user's stickers and custom emotes come
+/// from (`favorite_packs` fun fact).
+///
+/// Stickers, emotes inlined in messages and custom emote reactions are matched
+/// to a pack by their mxc URL, through the packs of the active rooms. Images of
+/// packs those rooms do not hold (personal packs, packs of other rooms) are
+/// left out.
+pub struct ImagePackCollector;
+
+impl MetricCollector for ImagePackCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        if !ctx.is_user_event {
+            return;
+        }
+        let AnySyncTimelineEvent::MessageLike(event) = event else {
+            return;
+        };
+        let mut record = |url: &str| {
+            if url.starts_with("mxc://") {
+                *room.image_uses.entry(url.to_string()).or_insert(0) += 1;
+            }
+        };
+        match event {
+            AnySyncMessageLikeEvent::Sticker(sticker) => {
+                if let Some(original) = sticker.as_original() {
+                    if let StickerMediaSource::Plain(url) = &original.content.source {
+                        record(url.as_str());
+                    }
+                }
+            }
+            AnySyncMessageLikeEvent::RoomMessage(message) => {
+                let Some(original) = message.as_original() else {
+                    return;
+                };
+                // An edit repeats the emotes of the message it replaces
+                if matches!(original.content.relates_to, Some(Relation::Replacement(_))) {
+                    return;
+                }
+                let formatted = match &original.content.msgtype {
+                    MessageType::Text(content) => &content.formatted,
+                    MessageType::Notice(content) => &content.formatted,
+                    MessageType::Emote(content) => &content.formatted,
+                    _ => return,
+                };
+                if let Some(formatted) = formatted {
+                    for url in inline_emote_urls(&formatted.body) {
+                        record(url);
+                    }
+                }
+            }
+            AnySyncMessageLikeEvent::Reaction(reaction) => {
+                if let Some(original) = reaction.as_original() {
+                    record(&original.content.relates_to.key);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let mut pack_names: HashMap<&str, &str> = HashMap::new();
+        for room in rooms {
+            for (url, (_, pack)) in &room.stats.emote_names {
+                if let Some(pack) = pack {
+                    pack_names.insert(url, pack);
+                }
+            }
+        }
+
+        let mut by_pack: HashMap<&str, i32> = HashMap::new();
+        for room in rooms {
+            for (url, uses) in &room.stats.image_uses {
+                if let Some(pack) = pack_names.get(url.as_str()) {
+                    *by_pack.entry(*pack).or_insert(0) += uses;
+                }
+            }
+        }
+
+        let mut packs: Vec<(&str, i32)> = by_pack.into_iter().collect();
+        packs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        packs.truncate(FAVORITE_PACKS);
+        if !packs.is_empty() {
+            let packs: Vec<serde_json::Value> = packs
+                .into_iter()
+                .map(|(pack, uses)| serde_json::json!({ "pack": pack, "uses": uses }))
+                .collect();
+            insert_fun_field(stats, "favorite_packs", serde_json::Value::from(packs));
+        }
+        Ok(())
+    }
+}
+
+/// Sources of the custom emotes (`<img data-mx-emoticon>`) of a formatted
+/// message body (private).
+fn inline_emote_urls(html: &str) -> Vec<&str> {
+    html.split("<img")
+        .skip(1)
+        .filter_map(|rest| {
+            let tag = &rest[..rest.find('>')?];
+            if !tag.contains("data-mx-emoticon") {
+                return None;
+            }
+            let start = tag.find("src=\"")? + "src=\"".len();
+            let len = tag[start..].find('"')?;
+            Some(&tag[start..start + len])
+        })
+        .collect()
+}
+
+// ============================================================================
+// Typed emojis
+// ============================================================================
+
+/// Ranks the emoji the user typed in their text, notice and emote messages,
+/// apart from the reactions they received.
+///
+/// The quoted fallback of rich replies and edits are left out, so an emoji is
+/// counted once, in the message that introduced it. With `fold`, the forms of
+/// an emoji are ranked as one, as for reactions.
+pub struct TypedEmojiCollector {
+    pub fold: bool,
+    /// Entries kept in the ranking
+    pub top: usize,
+}
+
+impl MetricCollector for TypedEmojiCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        if !ctx.is_user_event {
+            return;
+        }
+        let AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(message)) =
+            event
+        else {
+            return;
+        };
+        let Some(original) = message.as_original() else {
+            return;
+        };
+        if matches!(original.content.relates_to, Some(Relation::Replacement(_))) {
+            return;
+        }
+        let body = match &original.content.msgtype {
+            MessageType::Text(content) => &content.body,
+            MessageType::Notice(content) => &content.body,
+            MessageType::Emote(content) => &content.body,
+            _ => return,
+        };
+
+        for line in body.lines().skip_while(|line| line.starts_with("> ")) {
+            for emoji in emoji::emojis(line) {
+                *room.typed_emojis.entry(emoji.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let mut by_emoji: HashMap<String, i32> = HashMap::new();
+        for room in rooms {
+            for (emoji, count) in &room.stats.typed_emojis {
+                *by_emoji.entry(emoji.clone()).or_insert(0) += count;
+            }
+        }
+
+        let total = by_emoji.values().sum();
+        if total > 0 {
+            stats.typed_emojis = Some(TypedEmojis {
+                total,
+                top: rank_top_emojis(by_emoji, self.fold, self.top),
+            });
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Travel timezones
+// ============================================================================
+
+/// Key of the messages sent outside travel periods.
+const HOME_TIMEZONE: &str = "home";
+
+/// Counts the timezones the user sent messages from (`timezones` fun fact).
+///
+/// Events only carry a UTC timestamp, so where the user was comes from the
+/// travel periods of the config file: messages sent on a travel day count for
+/// the timezone of the trip, others for home. Activity stays bucketed in home
+/// local time.
+pub struct TravelCollector {
+    pub travel: Vec<TravelPeriod>,
+    /// Home timezone, when known (`TZ`, from `--timezone` or the config file)
+    pub home: Option<String>,
+}
+
+impl TravelCollector {
+    /// Timezone the user was in at `ts_millis`, `None` without travel periods.
+    fn timezone_at(&self, ts_millis: i64) -> Option<&str> {
+        if self.travel.is_empty() {
+            return None;
+        }
+        let date = chrono::Local
+            .timestamp_millis_opt(ts_millis)
+            .single()?
+            .date_naive();
+        let timezone = self
+            .travel
+            .iter()
+            .find(|trip| trip.covers(date))
+            .map(|trip| trip.timezone.as_str())
+            .filter(|timezone| Some(*timezone) != self.home.as_deref());
+        Some(timezone.unwrap_or(HOME_TIMEZONE))
+    }
+}
+
+impl MetricCollector for TravelCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        if !ctx.is_user_event
+            || !matches!(
+                event,
+                AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(_))
+            )
+        {
+            return;
+        }
+        if let Some(timezone) = self.timezone_at(ctx.ts_millis) {
+            *room
+                .messages_by_timezone
+                .entry(timezone.to_string())
+                .or_insert(0) += 1;
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let timezones: HashSet<&str> = rooms
+            .iter()
+            .flat_map(|room| &room.stats.messages_by_timezone)
+            .filter(|(_, count)| **count > 0)
+            .map(|(timezone, _)| timezone.as_str())
+            .collect();
+        // A single timezone is no trip
+        if timezones.len() > 1 {
+            insert_fun_field(stats, "timezones", timezones.len().into());
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// DM balance
+// ============================================================================
+
+/// Compares the messages the user sent in each DM with those of the other party.
+///
+/// Counts messages from other members in every room (the user's own are already
+/// counted by the core bucketing), then keeps the DMs with the most messages in
+/// total.
+pub struct DmBalanceCollector {
+    /// Entries kept in the ranking
+    pub top: usize,
+}
+
+impl MetricCollector for DmBalanceCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        if ctx.is_user_event {
+            return;
+        }
+        if matches!(
+            event,
+            AnySyncTimelineEvent::MessageLike(
+                AnySyncMessageLikeEvent::RoomMessage(_) | AnySyncMessageLikeEvent::RoomEncrypted(_)
+            )
+        ) {
+            room.others_messages += 1;
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let mut dms: Vec<&RoomStatsInput> = rooms
+            .iter()
+            .copied()
+            .filter(|room| room.room_type == RoomType::Dm)
+            .collect();
+        let total = |room: &RoomStatsInput| room.stats.user_events + room.stats.others_messages;
+        dms.sort_by(|a, b| {
+            total(b)
+                .cmp(&total(a))
+                .then_with(|| a.room_id.cmp(&b.room_id))
+        });
+
+        let balance: Vec<DmBalanceEntry> = dms
+            .into_iter()
+            .take(self.top)
+            .map(|room| DmBalanceEntry {
+                name: room.room_name.clone(),
+                sent: room.stats.user_events as i32,
+                received: room.stats.others_messages as i32,
+                permalink: format!("https://matrix.to/#/{}", room.room_id),
+            })
+            .collect();
+
+        if let Some(ref mut rooms_section) = stats.rooms {
+            if !balance.is_empty() {
+                rooms_section.dm_balance = Some(balance);
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// People
+// ============================================================================
+
+/// Ranks the people the user talked with: the other party of each DM, by
+/// messages exchanged, and the other members of group rooms, by messages sent.
+///
+/// Counts messages by sender in every room and keeps the display names set by
+/// the member events seen in the window. The other party of a DM comes from
+/// [`dm_partner`].
+pub struct PeopleCollector {
+    /// Entries kept in each ranking
+    pub top: usize,
+}
+
+impl MetricCollector for PeopleCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        match event {
+            AnySyncTimelineEvent::State(AnySyncStateEvent::RoomMember(member)) => {
+                if let Some(original) = member.as_original() {
+                    if let Some(ref name) = original.content.displayname {
+                        room.sender_names
+                            .insert(original.state_key.to_string(), name.clone());
+                    }
+                }
+            }
+            AnySyncTimelineEvent::MessageLike(
+                AnySyncMessageLikeEvent::RoomMessage(_) | AnySyncMessageLikeEvent::RoomEncrypted(_),
+            ) if !ctx.is_user_event => {
+                *room
+                    .messages_by_sender
+                    .entry(event.sender().to_string())
+                    .or_insert(0) += 1;
+            }
+            _ => {}
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let names = member_names(rooms);
+
+        // user ID -> (messages, name in the DM) and (messages, group rooms)
+        let mut partners: HashMap<&str, (i32, Option<&str>)> = HashMap::new();
+        let mut senders: HashMap<&str, (i32, i32)> = HashMap::new();
+        for room in rooms {
+            if room.room_type == RoomType::Dm {
+                let Some(partner) = dm_partner(&room.stats) else {
+                    continue;
+                };
+                let received = room
+                    .stats
+                    .messages_by_sender
+                    .get(partner)
+                    .copied()
+                    .unwrap_or(0);
+                let exchanged = room.stats.user_events as i32 + received;
+                if exchanged == 0 {
+                    continue;
+                }
+                let entry = partners.entry(partner).or_insert((0, None));
+                entry.0 += exchanged;
+                entry.1 = entry
+                    .1
+                    .or(room.stats.dm_partner_name.as_deref())
+                    .or(room.room_name.as_deref());
+            } else {
+                for (sender, messages) in &room.stats.messages_by_sender {
+                    let entry = senders.entry(sender.as_str()).or_insert((0, 0));
+                    entry.0 += messages;
+                    entry.1 += 1;
+                }
+            }
+        }
+
+        let dm_partners = rank_people(
+            partners
+                .into_iter()
+                .map(|(user_id, (messages, room_name))| PersonEntry {
+                    user_id: Some(user_id.to_string()),
+                    display_name: names
+                        .get(user_id)
+                        .copied()
+                        .or(room_name)
+                        .map(str::to_string),
+                    messages,
+                    rooms: None,
+                })
+                .collect(),
+            self.top,
+        );
+        let group_senders = rank_people(
+            senders
+                .into_iter()
+                .map(|(user_id, (messages, rooms))| PersonEntry {
+                    user_id: Some(user_id.to_string()),
+                    display_name: names.get(user_id).map(|name| name.to_string()),
+                    messages,
+                    rooms: Some(rooms),
+                })
+                .collect(),
+            self.top,
+        );
+
+        if dm_partners.is_some() || group_senders.is_some() {
+            stats.people = Some(People {
+                dm_partners,
+                group_senders,
+            });
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// DMs
+// ============================================================================
+
+/// Ranks the people the user wrote to the most in DMs, by the user's messages.
+///
+/// The other party of a DM comes from the room state (`m.direct`), recorded at
+/// crawl time; DMs crawled without it fall back to whoever wrote the most
+/// there. Several DMs with the same person add up.
+pub struct DmCorrespondentCollector {
+    /// Entries kept in the ranking
+    pub top: usize,
+}
+
+impl MetricCollector for DmCorrespondentCollector {
+    fn on_event(
+        &self,
+        _event: &AnySyncTimelineEvent,
+        _ctx: &EventContext<'_>,
+        _room: &mut DetailedPaginationStats,
+    ) {
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let names = member_names(rooms);
+
+        // user ID -> (messages sent, display name)
+        let mut correspondents: HashMap<&str, (i32, Option<&str>)> = HashMap::new();
+        for room in rooms {
+            if room.room_type != RoomType::Dm || room.stats.user_events == 0 {
+                continue;
+            }
+            let Some(partner) = dm_partner(&room.stats) else {
+                continue;
+            };
+            let name = room
+                .stats
+                .dm_partner_name
+                .as_deref()
+                .or_else(|| names.get(partner).copied())
+                .or(room.room_name.as_deref());
+            let entry = correspondents.entry(partner).or_insert((0, None));
+            entry.0 += room.stats.user_events as i32;
+            entry.1 = entry.1.or(name);
+        }
+
+        let total = correspondents.len() as i32;
+        let top = rank_people(
+            correspondents
+                .into_iter()
+                .map(|(user_id, (messages, name))| PersonEntry {
+                    user_id: Some(user_id.to_string()),
+                    display_name: name.map(str::to_string),
+                    messages,
+                    rooms: None,
+                })
+                .collect(),
+            self.top,
+        );
+        if let Some(top) = top {
+            stats.dms = Some(Dms { total, top });
+        }
+        Ok(())
+    }
+}
+
+/// The other party of a DM: from the room state (`m.direct`), recorded at
+/// crawl time, else whoever wrote the most there.
+fn dm_partner(room: &DetailedPaginationStats) -> Option<&str> {
+    room.dm_partner.as_deref().or_else(|| {
+        room.messages_by_sender
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(sender, _)| sender.as_str())
+    })
+}
+
+/// Display names set by the member events seen in the window, in any room.
+fn member_names<'a>(rooms: &[&'a RoomStatsInput]) -> HashMap<&'a str, &'a str> {
+    rooms
+        .iter()
+        .flat_map(|room| &room.stats.sender_names)
+        .map(|(user_id, name)| (user_id.as_str(), name.as_str()))
+        .collect()
+}
+
+/// Most messages first, ties by user ID; `None` when nobody is left.
+fn rank_people(mut people: Vec<PersonEntry>, top: usize) -> Option<Vec<PersonEntry>> {
+    people.sort_by(|a, b| {
+        b.messages
+            .cmp(&a.messages)
+            .then_with(|| a.user_id.cmp(&b.user_id))
+    });
+    people.truncate(top);
+    (!people.is_empty()).then_some(people)
+}
+
+/// Kind of a message in `messages_by_room_type_kind` (private).
+fn message_kind(msgtype: &MessageType) -> Option<&'static str> {
+    match msgtype {
+        MessageType::Text(_) | MessageType::Notice(_) | MessageType::Emote(_) => Some("text"),
+        // Voice messages are audio messages flagged as such (MSC3245)
+        MessageType::Audio(content) if content.voice.is_some() => Some("voice"),
+        MessageType::Image(_)
+        | MessageType::Video(_)
+        | MessageType::Audio(_)
+        | MessageType::File(_) => Some("media"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_emoji() {
+        assert_eq!(fold_emoji("👍🏻"), "👍");
+        assert_eq!(fold_emoji("👍\u{FE0F}"), "👍");
+        assert_eq!(fold_emoji("❤️"), "❤");
+        assert_eq!(fold_emoji("🧑🏽‍💻"), "🧑‍💻");
+        assert_eq!(fold_emoji("🏻"), "🏻");
+        assert_eq!(fold_emoji("lgtm"), "lgtm");
+    }
+
+    #[test]
+    fn test_rank_top_emojis_folds_forms() {
+        let emojis = HashMap::from([
+            ("👍".to_string(), 3),
+            ("👍🏻".to_string(), 5),
+            ("👍\u{FE0F}".to_string(), 1),
+            ("😂".to_string(), 4),
+        ]);
+
+        let folded = rank_top_emojis(emojis.clone(), true, 5);
+        assert_eq!(folded.len(), 2);
+        assert_eq!((folded[0].emoji.as_str(), folded[0].count), ("👍🏻", 9));
+        assert_eq!(
+            folded[0].variants.as_deref(),
+            Some(&["👍".to_string(), "👍\u{FE0F}".to_string()][..])
+        );
+        assert!(folded[1].variants.is_none());
+
+        let raw = rank_top_emojis(emojis, false, 5);
+        assert_eq!(raw.len(), 4);
+        assert_eq!((raw[0].emoji.as_str(), raw[0].count), ("👍🏻", 5));
+    }
+
+    #[test]
+    fn test_count_typed_words() {
+        assert_eq!(count_typed_words("hello there, how are you?"), 5);
+        assert_eq!(count_typed_words("  spaced   out\n\nlines  "), 3);
+        assert_eq!(count_typed_words(""), 0);
+    }
+
+    #[test]
+    fn test_message_preview() {
+        use matrix_sdk::ruma::events::room::message::{
+            ImageMessageEventContent, TextMessageEventContent,
+        };
+
+        let text = |body: &str| MessageType::Text(TextMessageEventContent::plain(body));
+        assert_eq!(
+            message_preview(&text("> <@bob:example.org> lunch?\n\nyes,\n  let's go")),
+            Some("yes, let's go".to_string())
+        );
+        assert_eq!(message_preview(&text("   ")), None);
+
+        let long = message_preview(&text(&"word ".repeat(40))).unwrap();
+        assert_eq!(long.chars().count(), PREVIEW_MAX_CHARS);
+        assert!(long.ends_with("word…"));
+
+        let image = MessageType::Image(ImageMessageEventContent::plain(
+            "holiday.jpg".to_string(),
+            "mxc://example.org/abc".into(),
+        ));
+        assert_eq!(message_preview(&image), Some("[image]".to_string()));
+    }
+
+    #[test]
+    fn test_message_kind() {
+        use matrix_sdk::ruma::events::room::message::{
+            AudioMessageEventContent, LocationMessageEventContent, NoticeMessageEventContent,
+            UnstableVoiceContentBlock,
+        };
+
+        let notice = MessageType::Notice(NoticeMessageEventContent::plain("build passed"));
+        assert_eq!(message_kind(&notice), Some("text"));
+
+        let mut audio =
+            AudioMessageEventContent::plain("song.ogg".to_string(), "mxc://example.org/abc".into());
+        assert_eq!(
+            message_kind(&MessageType::Audio(audio.clone())),
+            Some("media")
+        );
+        audio.voice = Some(UnstableVoiceContentBlock::new());
+        assert_eq!(message_kind(&MessageType::Audio(audio)), Some("voice"));
+
+        let location = MessageType::Location(LocationMessageEventContent::new(
+            "Home".to_string(),
+            "geo:51.5,-0.1".to_string(),
+        ));
+        assert_eq!(message_kind(&location), None);
+    }
+
+    #[test]
+    fn test_message_type_collector() {
+        let mut room = super::super::pagination::empty_detailed_stats(None, None);
+        let ctx = EventContext {
+            #[cfg(feature = "metric-plugins")]
+            user_id: "@alice:example.org",
+            event_id: None,
+            ts_millis: 0,
+            is_user_event: true,
+        };
+        let contents = [
+            serde_json::json!({ "msgtype": "m.text", "body": "hello there" }),
+            serde_json::json!({ "msgtype": "m.image", "body": "cat.png", "url": "mxc://example.org/cat" }),
+            serde_json::json!({
+                "msgtype": "m.text",
+                "body": "* hello there, edited at length",
+                "m.new_content": { "msgtype": "m.text", "body": "hello there, edited at length" },
+                "m.relates_to": { "rel_type": "m.replace", "event_id": "$m1" },
+            }),
+        ];
+        for content in contents {
+            let event: AnySyncTimelineEvent = serde_json::from_value(serde_json::json!({
+                "type": "m.room.message",
+                "event_id": "$m",
+                "sender": "@alice:example.org",
+                "origin_server_ts": 1,
+                "content": content,
+            }))
+            .unwrap();
+            MessageTypeCollector.on_event(&event, &ctx, &mut room);
+        }
+
+        assert_eq!(
+            room.user_messages_by_msgtype,
+            HashMap::from([("m.text".to_string(), 1), ("m.image".to_string(), 1)])
+        );
+        assert_eq!(room.user_edits, 1);
+        // Edits and file names are no text messages
+        assert_eq!(room.longest_message_chars, 11);
+    }
+
+    #[test]
+    fn test_message_length() {
+        assert_eq!(message_length("ok"), 2);
+        assert_eq!(message_length("  héllo 👋\n"), 7);
+        assert_eq!(
+            message_length("> <@bob:example.org> lunch?\n\nyes,\nlet's go"),
+            13
+        );
+
+        assert_eq!(length_bucket(0), "short");
+        assert_eq!(length_bucket(SHORT_MESSAGE_MAX_CHARS), "short");
+        assert_eq!(length_bucket(SHORT_MESSAGE_MAX_CHARS + 1), "medium");
+        assert_eq!(length_bucket(MEDIUM_MESSAGE_MAX_CHARS), "medium");
+        assert_eq!(length_bucket(MEDIUM_MESSAGE_MAX_CHARS + 1), "long");
+    }
+
+    #[test]
+    fn test_inline_emote_urls() {
+        let html = "ship it <img data-mx-emoticon src=\"mxc://example.org/party\" alt=\":party:\" \
+                    height=\"32\"> <img src=\"mxc://example.org/photo\"> \
+                    <img alt=\":cat:\" data-mx-emoticon=\"\" src=\"mxc://example.org/cat\">";
+        assert_eq!(
+            inline_emote_urls(html),
+            vec!["mxc://example.org/party", "mxc://example.org/cat"]
+        );
+        assert!(inline_emote_urls("no emotes").is_empty());
+    }
+
+    #[test]
+    fn test_travel_timezones() {
+        let date = |d| chrono::NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        let noon = |d| {
+            chrono::Local
+                .from_local_datetime(&date(d).and_hms_opt(12, 0, 0).unwrap())
+                .unwrap()
+                .timestamp_millis()
+        };
+        let trip = |from, to, timezone: &str| TravelPeriod {
+            from: date(from),
+            to: date(to),
+            timezone: timezone.to_string(),
+        };
+        let collector = TravelCollector {
+            travel: vec![
+                trip(10, 12, "America/New_York"),
+                trip(20, 21, "Europe/Paris"),
+            ],
+            home: Some("Europe/Paris".to_string()),
+        };
+
+        assert_eq!(collector.timezone_at(noon(9)), Some(HOME_TIMEZONE));
+        assert_eq!(collector.timezone_at(noon(12)), Some("America/New_York"));
+        // A trip within the home timezone is home
+        assert_eq!(collector.timezone_at(noon(20)), Some(HOME_TIMEZONE));
+
+        let no_travel = TravelCollector {
+            travel: Vec::new(),
+            home: None,
+        };
+        assert_eq!(no_travel.timezone_at(noon(12)), None);
+    }
+
+    #[test]
+    fn test_dm_correspondents() {
+        use crate::commands::crawl::pagination::empty_detailed_stats;
+
+        let dm = |room_id: &str, sent: usize, partner: Option<&str>, bob_wrote: i32| {
+            let mut stats = empty_detailed_stats(None, None);
+            stats.user_events = sent;
+            stats.dm_partner = partner.map(str::to_string);
+            stats
+                .messages_by_sender
+                .insert("@bob:example.org".to_string(), bob_wrote);
+            stats
+                .sender_names
+                .insert("@bob:example.org".to_string(), "Bob".to_string());
+            RoomStatsInput {
+                room_id: room_id.to_string(),
+                room_name: None,
+                room_type: RoomType::Dm,
+                stats,
+            }
+        };
+        let carol = {
+            let mut room = dm("!carol:example.org", 7, Some("@carol:example.org"), 9);
+            room.stats.dm_partner_name = Some("Carol".to_string());
+            room
+        };
+        let bob = dm("!bob:example.org", 3, None, 12);
+        let bob_again = dm("!bob2:example.org", 5, Some("@bob:example.org"), 1);
+        let silent = dm("!dan:example.org", 0, Some("@dan:example.org"), 4);
+        let rooms = [&carol, &bob, &bob_again, &silent];
+
+        let mut stats: Stats =
+            serde_json::from_str(include_str!("../../../examples/stats/example-stats.json"))
+                .unwrap();
+        stats.dms = None;
+        DmCorrespondentCollector { top: 10 }
+            .aggregate(&rooms, &mut stats)
+            .unwrap();
+        let dms = stats.dms.unwrap();
+        assert_eq!(dms.total, 2);
+        let ranked: Vec<(Option<&str>, Option<&str>, i32)> = dms
+            .top
+            .iter()
+            .map(|person| {
+                (
+                    person.user_id.as_deref(),
+                    person.display_name.as_deref(),
+                    person.messages,
+                )
+            })
+            .collect();
+        // Carol is recorded from the room state, Bob by his messages in the older DM
+        assert_eq!(
+            ranked,
+            [
+                (Some("@bob:example.org"), Some("Bob"), 8),
+                (Some("@carol:example.org"), Some("Carol"), 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_count_typed_words_skips_reply_fallback() {
+        let body = "> <@bob:example.org> are we still on for tonight?\n> see you\n\nyes, 8pm works";
+        assert_eq!(count_typed_words(body), 3);
+    }
+}
@@ -6,41 +6,139 @@
 ///
 /// The module is organized into focused submodules:
 /// - **types**: Data structures for room metadata and statistics
+/// - **checkpoint**: Progress summaries of long crawls, to the log, a webhook or a room
 /// - **decision**: Core logic for determining which rooms to crawl
-/// - **discovery**: Room list sync via sliding sync
+/// - **discovery**: Room list sync via sliding sync, or regular sync as a fallback
+/// - **dry_run**: Rooms a crawl would paginate, from the cached room list
+/// - **emoji**: Emoji segmentation of message bodies
 /// - **pagination**: Event backward pagination and aggregation
+/// - **errors**: Report file of rooms that failed to crawl
+/// - **fixture**: Crawl simulation from synthetic event files
+/// - **live**: Stats of the rooms crawled so far, while a crawl runs
+/// - **metrics**: Pluggable metric collectors (reactions, fun facts, ...)
+/// - **profile**: Account display name and avatar, top room avatars
+/// - **quiet_hours**: Daily time range during which crawling is throttled
+/// - **room_type_override**: User-forced room classifications
 /// - **progress**: Progress reporting and UI
+/// - **suggest**: Next window to crawl, from the crawled data window
+///
+/// Crawls stop cooperatively through a [`CancellationToken`] (Ctrl-C, see
+/// [`cancel_on_ctrl_c`]): rooms in flight keep the events paginated so far,
+/// the others are left to the next crawl.
 use anyhow::{Context, Result};
-use futures_util::StreamExt;
+use futures_util::stream::{FuturesUnordered, StreamExt};
 use std::collections::HashMap;
 use std::path::Path;
+pub use tokio_util::sync::CancellationToken;
 
 use crate::account_selector::AccountSelector;
+use crate::config::{DmNames, TravelPeriod};
 use crate::stats;
 use crate::window::WindowScope;
 
+pub mod checkpoint;
+use checkpoint::{CheckpointOptions, Checkpointer};
+
 pub mod db;
 pub mod types;
 pub use types::RoomCrawlStats;
 use types::RoomJoinState;
 
-mod decision;
+pub(crate) mod decision;
 use decision::{record_skipped_virgin_rooms, select_rooms_to_crawl};
 
-mod discovery;
-use discovery::{fetch_room_list_via_sliding_sync, setup_account};
+pub(crate) mod discovery;
+use discovery::resolve_dm_partner;
+pub use discovery::resolve_room_name;
+use discovery::{fetch_account_creation_ts, fetch_room_list, setup_account};
+
+pub mod dry_run;
+
+pub(crate) mod emoji;
+
+pub(crate) mod pagination;
+
+pub mod errors;
+use errors::{PauseReason, RoomError, ServerHealth};
+
+pub mod fixture;
+
+pub mod live;
 
-mod pagination;
+pub mod periods;
+
+pub mod metrics;
+use metrics::MetricCollector;
+
+#[cfg(feature = "metric-plugins")]
+pub mod plugins;
+
+pub mod profile;
 
 pub mod progress;
 
+pub mod quiet_hours;
+use quiet_hours::{QuietHours, Throttle};
+
+pub mod room_type_override;
+use room_type_override::RoomTypeOverride;
+
 pub mod stats_builder;
-use progress::CrawlProgress;
+pub mod suggest;
+use progress::{CrawlLog, CrawlProgress};
 
 /// Maximum number of rooms to crawl concurrently.
 /// Balances throughput against server load.
 const MAX_CONCURRENCY: usize = 8;
 
+/// Tunables for a crawl run, set from CLI flags.
+#[derive(Debug, Clone)]
+pub struct CrawlOptions {
+    /// Local time range during which the crawler throttles itself
+    pub quiet_hours: Option<QuietHours>,
+    /// Rooms crawled concurrently during quiet hours (0 pauses until they end)
+    pub quiet_concurrency: usize,
+    /// Typing speed used to estimate time spent writing messages
+    pub typing_wpm: u32,
+    /// Rooms whose DM/public/private classification is forced
+    pub room_type_overrides: Vec<RoomTypeOverride>,
+    /// Also record stats isolated to each of the top rooms
+    pub per_room: bool,
+    /// Keep a short excerpt of the most reacted messages
+    pub message_previews: bool,
+    /// Record who sent the messages of the crawled rooms (the People section)
+    pub people: bool,
+    /// Count the skin-tone and variation-selector forms of an emoji as one (reactions, typed emojis)
+    pub fold_emojis: bool,
+    /// Entries kept in each ranking (top rooms, emojis, messages)
+    pub top: usize,
+    /// Trips to other timezones, from the config file
+    pub travel: Vec<TravelPeriod>,
+    /// How DM partners are named in the stats, from the config file
+    pub dm_names: DmNames,
+    /// Progress summaries sent while the crawl runs longer than their interval
+    pub checkpoints: Option<CheckpointOptions>,
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        Self {
+            quiet_hours: None,
+            quiet_concurrency: 1,
+            typing_wpm: metrics::DEFAULT_TYPING_WPM,
+            room_type_overrides: Vec::new(),
+            per_room: false,
+            message_previews: false,
+            people: true,
+            fold_emojis: true,
+            top: stats::DEFAULT_TOP,
+            travel: Vec::new(),
+            dm_names: DmNames::Full,
+            checkpoints: None,
+        }
+    }
+}
+
 /// Main entry point for the crawl command.
 ///
 /// Discovers all logged-in accounts and crawls them for the requested time window.
@@ -52,9 +150,57 @@ const MAX_CONCURRENCY: usize = 8;
 ///
 /// * `window` - Time window specification (e.g., "2025", "2025-03", "life")
 /// * `user_id_flag` - Optional Matrix user ID to restrict crawling to one account
+/// * `options` - Crawl tunables (quiet hours, ...)
+/// * `cancel` - Stops the crawl cooperatively; accounts not reached are skipped
 pub async fn run(
     window: String,
     user_id_flag: Option<String>,
+    options: &CrawlOptions,
+    cancel: &CancellationToken,
+) -> Result<Vec<(String, stats::Stats)>> {
+    run_with_collectors(
+        window,
+        user_id_flag,
+        options,
+        &metrics::builtin_collectors(options),
+        cancel,
+    )
+    .await
+}
+
+/// Cancellation token cancelled by the first Ctrl-C.
+///
+/// Paginations in flight stop after their current request and the rooms
+/// crawled so far are saved; a second Ctrl-C exits right away. Must be called
+/// from within a Tokio runtime.
+pub fn cancel_on_ctrl_c() -> CancellationToken {
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        eprintln!(
+            "\n⏹️  Stopping the crawl, saving the rooms crawled so far (Ctrl-C again to quit)..."
+        );
+        token.cancel();
+        if tokio::signal::ctrl_c().await.is_ok() {
+            std::process::exit(130);
+        }
+    });
+    cancel
+}
+
+/// Same as [`run`], with an explicit list of metric collectors.
+///
+/// Lets downstream builds register custom metrics alongside (or instead of)
+/// [`metrics::builtin_collectors`].
+pub async fn run_with_collectors(
+    window: String,
+    user_id_flag: Option<String>,
+    options: &CrawlOptions,
+    collectors: &[Box<dyn MetricCollector>],
+    cancel: &CancellationToken,
 ) -> Result<Vec<(String, stats::Stats)>> {
     // Parse the window
     let window_scope = WindowScope::parse(&window).context("Failed to parse window")?;
@@ -78,7 +224,20 @@ pub async fn run(
     // Crawl each account and collect stats
     let mut account_stats = Vec::new();
     for (account_id, account_dir) in &accounts {
-        match crawl_account(account_id, account_dir, &window_scope).await {
+        if cancel.is_cancelled() {
+            eprintln!("⏹️  Crawl cancelled, skipping {}", account_id);
+            continue;
+        }
+        match crawl_account(
+            account_id,
+            account_dir,
+            &window_scope,
+            options,
+            collectors,
+            cancel,
+        )
+        .await
+        {
             Ok(stats) => {
                 account_stats.push((account_id.clone(), stats));
             }
@@ -97,27 +256,38 @@ pub async fn run(
 ///
 /// Coordinates the full crawl workflow:
 /// 1. Sets up the account (client + database)
-/// 2. Discovers joined rooms via sliding sync
+/// 2. Discovers joined rooms via sliding sync (regular sync when unsupported)
 /// 3. Decides which rooms need pagination
 /// 4. Records virgin rooms that were skipped
 /// 5. Crawls rooms in parallel with progress reporting
-/// 6. Aggregates room statistics into account-level Stats
+/// 6. Resolves the account creation date and profile (display name, avatar)
+/// 7. Aggregates room statistics into account-level Stats, with the top room avatars
 ///
-/// Returns the computed Stats for the account.
+/// Returns the computed Stats for the account. When `cancel` fires during step 5,
+/// the rooms crawled so far are saved and the crawl ends like a paused one.
 async fn crawl_account(
     account_id: &str,
     account_dir: &Path,
     window_scope: &WindowScope,
+    options: &CrawlOptions,
+    collectors: &[Box<dyn MetricCollector>],
+    cancel: &CancellationToken,
 ) -> Result<stats::Stats> {
-    eprintln!("📱 Crawling account: {}", account_id);
+    let log = CrawlLog::new(account_id);
+    log.println(&format!("📱 Crawling account: {}", account_id));
+    let started = std::time::Instant::now();
 
     // 1) Account setup
     let (_account_dir_path, client, db) = setup_account(account_id, account_dir)
         .await
         .context("Account setup failed")?;
 
-    // 2) Discover rooms via sliding sync
-    let room_list = fetch_room_list_via_sliding_sync(&client).await?;
+    // 2) Discover rooms via sliding sync, or regular sync when it fails
+    let room_list = fetch_room_list(&client, &log).await?;
+    // Cached for `my status` and `my crawl --dry-run`, which do not sync
+    if let Err(e) = db.replace_room_list(&room_list, chrono::Utc::now().timestamp_millis()) {
+        log.println(&format!("Warning: failed to cache the room list: {:#}", e));
+    }
 
     // 3) Check which rooms need crawl
     let joined_room_ids: Vec<_> = room_list
@@ -127,7 +297,7 @@ async fn crawl_account(
         .collect();
 
     if joined_room_ids.is_empty() {
-        eprintln!("ℹ️  No rooms to crawl");
+        log.println("ℹ️  No rooms to crawl");
     }
 
     let (window_start_ts, window_end_ts) = window_scope.to_timestamp_range();
@@ -157,76 +327,300 @@ async fn crawl_account(
     record_skipped_virgin_rooms(&db, &joined_rooms, &rooms_to_crawl, &latest_events)
         .context("Failed to record skipped virgin rooms")?;
 
-    eprintln!(
+    log.println(&format!(
         "📚 Found {} joined room(s), {} to crawl...",
         joined_rooms.len(),
         rooms_to_crawl.len()
-    );
+    ));
+
+    // Rooms are stored as they complete, for `my render --live`
+    if let Err(e) = db.start_live_crawl(
+        &window_scope.key,
+        joined_rooms.len(),
+        rooms_to_crawl.len(),
+        chrono::Utc::now().timestamp_millis(),
+    ) {
+        log.println(&format!(
+            "Warning: failed to record the crawl start: {:#}",
+            e
+        ));
+    }
 
     // 4) Crawl rooms (parallel pagination, sequential DB updates)
-    let total_rooms = rooms_to_crawl.len();
-    let (success_count, error_count, room_stats_inputs) =
-        crawl_rooms_parallel(rooms_to_crawl, window_scope, &db, account_id, total_rooms).await;
+    let throttle = Throttle {
+        quiet_hours: options.quiet_hours,
+        quiet_concurrency: options.quiet_concurrency,
+        max_concurrency: MAX_CONCURRENCY,
+    };
+    let rooms_to_crawl_count = rooms_to_crawl.len();
+    let checkpointer = options.checkpoints.clone().map(|checkpoints| {
+        Checkpointer::new(checkpoints, client.clone(), account_id, &window_scope.key)
+    });
+    let (success_count, room_errors, room_stats_inputs, pause) = crawl_rooms_parallel(
+        rooms_to_crawl,
+        window_scope,
+        &db,
+        account_id,
+        throttle,
+        &room_type_override::by_room(&options.room_type_overrides),
+        collectors,
+        &log,
+        checkpointer,
+        cancel,
+    )
+    .await;
 
-    eprintln!(
+    log.println(&format!(
         "✅ Crawled {} rooms ({} errors)",
-        success_count, error_count
-    );
+        success_count,
+        room_errors.len()
+    ));
+
+    let error_count = room_errors.len();
+    match errors::write_report(account_dir, account_id, &window_scope.key, room_errors) {
+        Ok(Some(path)) => log.println(&format!(
+            "⚠️  {} room(s) failed, details and retry hints: {}",
+            error_count,
+            path.display()
+        )),
+        Ok(None) => {}
+        Err(e) => log.println(&format!("Warning: {:#}", e)),
+    }
+
+    // Rooms left over keep their crawl state, so the next crawl picks them up
+    if let Some(reason) = pause {
+        log.println(&format!(
+            "⏸️  Crawl paused: {}. The {} room(s) crawled so far are saved; run 'my crawl {}' later to resume with the {} remaining.",
+            reason.describe(),
+            success_count,
+            window_scope.key,
+            rooms_to_crawl_count - success_count - error_count
+        ));
+        anyhow::bail!("Crawl paused, {}", reason.describe());
+    }
+
+    // 5) Account creation date, from the homeserver or the crawled history, and profile
+    let account_creation = resolve_account_creation(&client, &db, account_id, &log).await;
+    let profile = profile::fetch_profile(&client, account_dir, &log).await;
 
-    // 5) Build account-level stats from room statistics
-    // Note: Account profile fetch is not available in current SDK; passing None for now
-    let stats = stats_builder::build_stats(
-        room_stats_inputs,
+    // 6) Build account-level stats from room statistics
+    let mut stats = stats_builder::build_stats(
+        &room_stats_inputs,
         account_id,
-        None,
-        None,
+        profile.display_name,
+        profile.avatar_url,
         window_scope,
         joined_rooms.len(),
+        options.top,
+        collectors,
     )
     .context("Failed to build account stats")?;
+    stats.account.avatar_file = profile.avatar_file;
+    if let Some(top) = stats.rooms.as_mut().and_then(|rooms| rooms.top.as_mut()) {
+        profile::fetch_room_avatars(&client, top, account_dir, &log).await;
+    }
+    if let Some(creation) = account_creation {
+        stats_builder::apply_account_creation(&mut stats, creation.ts, window_scope);
+    }
+    if options.per_room {
+        stats.per_room = Some(
+            stats_builder::build_room_breakdowns(
+                &room_stats_inputs,
+                account_id,
+                window_scope,
+                options.top,
+                collectors,
+            )
+            .context("Failed to build per-room stats")?,
+        );
+    }
+    stats_builder::apply_dm_names(&mut stats, &room_stats_inputs, options.dm_names);
+    metrics::insert_fun_field(
+        &mut stats,
+        "crawl_duration_seconds",
+        started.elapsed().as_secs().into(),
+    );
+
+    if let Err(e) = db.finish_live_crawl(&window_scope.key, chrono::Utc::now().timestamp_millis()) {
+        log.println(&format!("Warning: failed to record the crawl end: {:#}", e));
+    }
 
     Ok(stats)
 }
 
+/// Resolves and stores when the account was created.
+///
+/// Asks the homeserver unless its answer is already stored, and otherwise
+/// estimates the date from the oldest event of fully crawled rooms, keeping the
+/// earliest estimate across crawls. Returns the stored value, so a date found by
+/// an earlier crawl still applies when this one could not resolve it.
+async fn resolve_account_creation(
+    client: &matrix_sdk::Client,
+    db: &db::CrawlDb,
+    account_id: &str,
+    log: &CrawlLog,
+) -> Option<db::AccountCreation> {
+    let stored = db.get_account_creation().ok().flatten();
+    if stored
+        .as_ref()
+        .is_some_and(|creation| creation.source == db::CreationSource::Server)
+    {
+        return stored;
+    }
+
+    let creation = match fetch_account_creation_ts(client, account_id).await {
+        Some(ts) => Some(db::AccountCreation {
+            ts,
+            source: db::CreationSource::Server,
+        }),
+        None => db
+            .estimate_account_creation_ts()
+            .ok()
+            .flatten()
+            .map(|ts| db::AccountCreation {
+                ts,
+                source: db::CreationSource::Events,
+            }),
+    };
+    if let Some(ref creation) = creation {
+        if let Err(e) = db.set_account_creation(creation) {
+            log.println(&format!(
+                "Warning: Failed to store account creation date: {}",
+                e
+            ));
+        }
+    }
+
+    db.get_account_creation().ok().flatten().or(creation)
+}
+
+/// Logs the room concurrency when quiet hours start, end, or are already on
+/// as the crawl starts.
+fn log_quiet_hours(throttle: &Throttle, quiet: bool, now: chrono::NaiveTime, log: &CrawlLog) {
+    let Some(quiet_hours) = throttle.quiet_hours else {
+        return;
+    };
+    if !quiet {
+        log.println(&format!(
+            "☀️ Quiet hours ({}) over, crawling {} room(s) at a time",
+            quiet_hours, throttle.max_concurrency
+        ));
+    } else if throttle.concurrency(now) == 0 {
+        log.println(&format!(
+            "🌙 Quiet hours ({}), pausing for {} min...",
+            quiet_hours,
+            quiet_hours.remaining(now).num_minutes() + 1
+        ));
+    } else {
+        log.println(&format!(
+            "🌙 Quiet hours ({}), crawling {} room(s) at a time",
+            quiet_hours,
+            throttle.concurrency(now)
+        ));
+    }
+}
+
+/// Waits until the quiet hours of `throttle` start or end, forever without any.
+async fn quiet_hours_change(throttle: &Throttle) {
+    let now = chrono::Local::now().time();
+    match throttle
+        .until_change(now)
+        .and_then(|delta| delta.to_std().ok())
+    {
+        Some(delay) => tokio::time::sleep(delay).await,
+        None => std::future::pending().await,
+    }
+}
+
 /// Crawls a set of rooms in parallel, respecting concurrency limits.
 ///
-/// Uses async streams to manage concurrent pagination operations.
+/// Keeps up to the concurrency of `throttle` rooms in flight, checking the
+/// quiet hours again before starting each room and when they start or end.
 /// Updates the database after each room completes.
 ///
-/// Returns tuple of (success_count, error_count, room_stats_inputs).
+/// Stops early when the homeserver as a whole fails (see [`ServerHealth`]):
+/// the rooms crawled at the time are then neither successes nor errors.
+/// Once `cancel` fires, no room starts anymore and the rooms in flight end
+/// with the events paginated so far, saved as not fully crawled.
+///
+/// With a `checkpointer`, a summary of the progress is sent whenever a
+/// checkpoint is due, even while rooms are still being paginated.
+///
+/// Returns tuple of (success_count, room_errors, room_stats_inputs, pause reason).
+#[allow(clippy::too_many_arguments)]
 async fn crawl_rooms_parallel(
     rooms: Vec<matrix_sdk::Room>,
     window_scope: &WindowScope,
     db: &db::CrawlDb,
     account_id: &str,
-    total_rooms: usize,
-) -> (usize, usize, Vec<stats_builder::RoomStatsInput>) {
+    throttle: Throttle,
+    room_type_overrides: &HashMap<String, RoomType>,
+    collectors: &[Box<dyn MetricCollector>],
+    log: &CrawlLog,
+    mut checkpointer: Option<Checkpointer>,
+    cancel: &CancellationToken,
+) -> (
+    usize,
+    Vec<RoomError>,
+    Vec<stats_builder::RoomStatsInput>,
+    Option<PauseReason>,
+) {
     let mut success_count = 0usize;
-    let mut error_count = 0usize;
+    let mut room_errors = Vec::new();
     let mut room_stats_inputs = Vec::new();
+    let mut health = ServerHealth::default();
+    let mut pause = None;
+    let total_rooms = rooms.len();
+    let mut rooms_done = 0usize;
 
     let (window_start_ts, window_end_ts) = window_scope.to_timestamp_range();
     let user_id = account_id.to_string();
 
-    let progress = CrawlProgress::new(total_rooms);
-    let progress_for_stream = progress.clone();
+    let progress = CrawlProgress::new(total_rooms, log.clone());
+
+    let mut pending = rooms.into_iter();
+    let mut in_flight = FuturesUnordered::new();
+    let mut quiet = false;
 
-    let mut stream = futures_util::stream::iter(rooms)
-        .map(move |room| {
-            let uid = user_id.clone();
-            let progress_for_room = progress_for_stream.clone();
-            crawl_single_room(
+    loop {
+        let now = chrono::Local::now().time();
+        // Once `cancel` fires, no room starts anymore
+        while in_flight.len() < throttle.concurrency(now) && !cancel.is_cancelled() {
+            let Some(room) = pending.next() else {
+                break;
+            };
+            in_flight.push(crawl_single_room(
                 room,
                 window_start_ts,
                 window_end_ts,
-                uid,
-                progress_for_room,
+                user_id.clone(),
+                progress.clone(),
                 db,
-            )
-        })
-        .buffer_unordered(MAX_CONCURRENCY);
+                collectors,
+                cancel,
+            ));
+        }
+        if in_flight.is_empty() && (pending.len() == 0 || cancel.is_cancelled()) {
+            break;
+        }
+        if throttle.is_quiet(now) != quiet {
+            quiet = !quiet;
+            log_quiet_hours(&throttle, quiet, now, log);
+        }
 
-    while let Some((room, stats_res, room_type, detailed_stats, spinner)) = stream.next().await {
+        let next = tokio::select! {
+            Some(next) = in_flight.next() => Some(next),
+            _ = checkpoint_due(checkpointer.as_mut()) => None,
+            // Start more rooms, or fewer, as quiet hours start or end
+            _ = quiet_hours_change(&throttle) => continue,
+            _ = cancel.cancelled(), if !cancel.is_cancelled() => continue,
+        };
+        let Some((room, stats_res, room_type, detailed_stats, period_stats, spinner)) = next else {
+            if let Some(ref checkpointer) = checkpointer {
+                checkpointer.send(rooms_done, total_rooms, &progress).await;
+            }
+            continue;
+        };
         // Finish spinner before printing results
         if let Some(ref sp) = spinner {
             sp.finish_and_clear();
@@ -246,69 +640,126 @@ async fn crawl_rooms_parallel(
                     stats.newest_ts,
                     stats.fully_crawled,
                 ) {
-                    error_count += 1;
                     // Mark as error
                     let _ = db.set_crawl_status(&room_id, db::CrawlStatus::Error(e.to_string()));
-                    progress.println(&format!("  \x1b[31m✗\x1b[0m {} ({})", room_name, e));
+                    progress.println_room(&room_name, false, &e.to_string());
+                    room_errors.push(RoomError::new(room_id, room_name, &e, &window_scope.key));
                 } else {
                     success_count += 1;
+                    for error in health.success() {
+                        report_room_error(db, &progress, error, &mut room_errors);
+                    }
                     // Mark as success and update event counts
                     let _ = db.set_crawl_status(&room_id, db::CrawlStatus::Success);
                     let _ =
                         db.update_max_event_counts(&room_id, stats.total_events, stats.user_events);
+                    if stats.fully_crawled {
+                        let _ = db.set_history_limited(
+                            &room_id,
+                            stats.history_limited,
+                            stats.joined_at,
+                        );
+                    }
 
-                    use progress::format_completed_room;
-                    let formatted = format_completed_room(
-                        &room_name,
+                    let summary = progress::format_room_summary(
                         stats.total_events,
                         stats.user_events,
+                        stats.untimed_events,
                         stats.oldest_ts,
-                        stats.newest_ts,
                         stats.fully_crawled,
+                        stats.history_limited,
                     );
-                    progress.println(&format!("  ✓ {}", formatted));
+                    progress.println_room(&room_name, true, summary.as_deref().unwrap_or(""));
 
-                    // Collect room stats input for aggregation
+                    // Collect room stats input for aggregation, with the user's override if any
+                    let room_type = room_type_overrides.get(&room_id).copied().or(room_type);
                     if let (Some(room_type), Some(detailed)) = (room_type, detailed_stats) {
-                        room_stats_inputs.push(stats_builder::RoomStatsInput {
+                        let input = stats_builder::RoomStatsInput {
                             room_id: stats.room_id,
                             room_name: Some(stats.room_name),
                             room_type,
                             stats: detailed,
-                        });
+                        };
+                        if let Err(e) = live::record_room(db, &window_scope.key, &input) {
+                            progress.println(&format!(
+                                "Warning: failed to store live stats of {}: {:#}",
+                                room_name, e
+                            ));
+                        }
+                        if let Some(period_stats) = period_stats {
+                            if let Err(e) = periods::record_room(db, &input, period_stats) {
+                                progress.println(&format!(
+                                    "Warning: failed to store the stats by year and month of {}: {:#}",
+                                    room_name, e
+                                ));
+                            }
+                        }
+                        room_stats_inputs.push(input);
                     }
                 }
             }
             Err(e) => {
-                error_count += 1;
-
-                // Mark as error
-                let _ = db.set_crawl_status(&room_id, db::CrawlStatus::Error(e.to_string()));
-
                 // Fetch room name for error reporting
-                let room_name = room
-                    .display_name()
-                    .await
-                    .ok()
-                    .map(|n| n.to_string())
-                    .unwrap_or_else(|| room.room_id().to_string());
-                progress.println(&format!("  \x1b[31m✗\x1b[0m {} ({})", room_name, e));
+                let room_name = resolve_room_name(&room).await;
+                let error = RoomError::new(room_id, room_name, &e, &window_scope.key);
+                match health.failure(error) {
+                    Ok(failed) => {
+                        for error in failed {
+                            report_room_error(db, &progress, error, &mut room_errors);
+                        }
+                    }
+                    Err(reason) => {
+                        // Rooms still in flight are dropped with `in_flight`
+                        pause = Some(reason);
+                        break;
+                    }
+                }
             }
         }
 
+        rooms_done += 1;
         progress.inc();
     }
 
+    for error in health.finish() {
+        report_room_error(db, &progress, error, &mut room_errors);
+    }
     progress.finish();
+    if pause.is_none() && cancel.is_cancelled() {
+        pause = Some(PauseReason::Cancelled);
+    }
 
-    (success_count, error_count, room_stats_inputs)
+    (success_count, room_errors, room_stats_inputs, pause)
+}
+
+/// Waits for the next checkpoint, forever without a checkpointer.
+async fn checkpoint_due(checkpointer: Option<&mut Checkpointer>) {
+    match checkpointer {
+        Some(checkpointer) => checkpointer.tick().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Marks a room as failed and prints its error.
+fn report_room_error(
+    db: &db::CrawlDb,
+    progress: &CrawlProgress,
+    error: RoomError,
+    room_errors: &mut Vec<RoomError>,
+) {
+    let message = error.chain.first().cloned().unwrap_or_default();
+    let _ = db.set_crawl_status(&error.room_id, db::CrawlStatus::Error(message.clone()));
+    progress.println_room(&error.room_name, false, &message);
+    room_errors.push(error);
 }
 
 /// Crawls events from a single room.
 ///
 /// Sets up pagination and delegates to the pagination module.
 /// Collects detailed statistics for stats aggregation.
-/// Returns the room, result, room type, detailed stats, and optional spinner handle.
+/// Returns the room, result, room type, detailed stats, the stats of each year
+/// and month for life crawls, and optional spinner handle.
+#[allow(clippy::too_many_arguments)]
 async fn crawl_single_room(
     room: matrix_sdk::Room,
     window_start_ts: Option<i64>,
@@ -316,20 +767,18 @@ async fn crawl_single_room(
     user_id: String,
     progress: CrawlProgress,
     db: &db::CrawlDb,
+    collectors: &[Box<dyn MetricCollector>],
+    cancel: &CancellationToken,
 ) -> (
     matrix_sdk::Room,
     Result<RoomCrawlStats>,
     Option<RoomType>,
     Option<types::DetailedPaginationStats>,
+    Option<Vec<(String, types::DetailedPaginationStats)>>,
     Option<indicatif::ProgressBar>,
 ) {
     // Fetch the room's display name before creating the progress callback
-    let room_name = room
-        .display_name()
-        .await
-        .ok()
-        .map(|n| n.to_string())
-        .unwrap_or_else(|| room.room_id().to_string());
+    let room_name = resolve_room_name(&room).await;
 
     let (progress_callback, spinner) = progress.make_callback(room_name.clone());
 
@@ -346,33 +795,58 @@ async fn crawl_single_room(
     // Note: Keep drop_handles alive throughout pagination to maintain cache subscription
     let room_event_cache_res = pagination::setup_event_cache(&room).await;
 
-    let (stats_res, detailed_stats, room_type) =
-        if let Ok((room_event_cache, _drop_handles)) = room_event_cache_res {
-            // Call the unified pagination function that collects both basic and detailed stats
-            match pagination::paginate_and_collect_detailed_stats(
-                &room,
-                &room_event_cache,
-                window_start_ts,
-                window_end_ts,
-                &user_id,
-                &room_name,
-                None, // No initial newest event - start from current
-                None, // No initial newest ts
-                &*progress_callback,
-            )
-            .await
-            {
-                Ok((crawl_stats, detailed)) => {
-                    let room_type = classify_room_type(&room).await.ok();
-                    (Ok(crawl_stats), Some(detailed), room_type)
+    // Life crawls also keep the stats of each year and month (`my render-all`)
+    let mut periods = window_start_ts
+        .is_none()
+        .then(pagination::PeriodStats::default);
+
+    let (stats_res, detailed_stats, room_type) = if let Ok((room_event_cache, _drop_handles)) =
+        room_event_cache_res
+    {
+        // Call the unified pagination function that collects both basic and detailed stats
+        match pagination::paginate_and_collect_detailed_stats(
+            &room,
+            &room_event_cache,
+            window_start_ts,
+            window_end_ts,
+            &user_id,
+            &room_name,
+            None, // No initial newest event - start from current
+            None, // No initial newest ts
+            collectors,
+            periods.as_mut(),
+            cancel,
+            &*progress_callback,
+        )
+        .await
+        {
+            Ok((crawl_stats, mut detailed)) => {
+                let room_type = classify_room_type(&room).await.ok();
+                if let Some((partner, name)) = resolve_dm_partner(&room).await {
+                    detailed.dm_partner = Some(partner);
+                    detailed.dm_partner_name = name;
                 }
-                Err(e) => (Err(e), None, None),
+                detailed.canonical_alias = room.canonical_alias().map(|alias| alias.to_string());
+                (Ok(crawl_stats), Some(detailed), room_type)
             }
-        } else {
-            (Err(room_event_cache_res.unwrap_err()), None, None)
-        };
-
-    (room, stats_res, room_type, detailed_stats, spinner)
+            Err(e) => (Err(e), None, None),
+        }
+    } else {
+        (Err(room_event_cache_res.unwrap_err()), None, None)
+    };
+
+    let period_stats = periods
+        .zip(detailed_stats.as_ref())
+        .map(|(periods, room)| periods.finish(room));
+
+    (
+        room,
+        stats_res,
+        room_type,
+        detailed_stats,
+        period_stats,
+        spinner,
+    )
 }
 
 /// Room classification (DM, public, private).
@@ -383,6 +857,27 @@ pub enum RoomType {
     Private,
 }
 
+impl RoomType {
+    /// Name of the room type in stats files and CLI flags (`dm`, `public`, `private`).
+    pub fn key(&self) -> &'static str {
+        match self {
+            RoomType::Dm => "dm",
+            RoomType::Public => "public",
+            RoomType::Private => "private",
+        }
+    }
+
+    /// Parses a room type name (`dm`, `public`, `private`), ignoring case.
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key.trim().to_ascii_lowercase().as_str() {
+            "dm" => Some(RoomType::Dm),
+            "public" => Some(RoomType::Public),
+            "private" => Some(RoomType::Private),
+            _ => None,
+        }
+    }
+}
+
 /// Classifies a room as DM, public, or private.
 ///
 /// Uses the Matrix SDK's direct-message flag and join rules to determine room type:
@@ -3,12 +3,19 @@
 /// Handles backward pagination through a room's timeline, aggregating event
 /// statistics and respecting window boundaries.
 use anyhow::{Context, Result};
-use chrono::{Datelike, Local, TimeZone, Timelike};
-use matrix_sdk::ruma::events::{AnySyncMessageLikeEvent, AnySyncTimelineEvent};
-use std::collections::HashMap;
+use chrono::{DateTime, Datelike, Local, TimeZone, Timelike};
+use matrix_sdk::deserialized_responses::{RawAnySyncOrStrippedState, TimelineEvent};
+use matrix_sdk::ruma::events::{
+    AnySyncMessageLikeEvent, AnySyncStateEvent, AnySyncTimelineEvent, StateEventType,
+};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
+use super::metrics::{EventContext, MetricCollector};
 use super::types::{DetailedPaginationStats, RoomCrawlStats};
+use crate::window::WindowScope;
 
 /// Batch size for event pagination (events per fetch).
 /// Determined by Matrix SDK and server limits.
@@ -41,14 +48,19 @@ pub async fn setup_event_cache(
 /// Similar to `paginate_and_aggregate_stats` but collects comprehensive analytics:
 /// - Temporal buckets (year, month, week, weekday, day, hour) using local timezone
 /// - User message IDs for reaction filtering
-/// - Reaction tracking (emojis and per-message counts)
 /// - Room creation detection
 /// - Active dates for days_active calculation
+/// - History visibility detection (start of timeline reached without room creation),
+///   with the user's join date when history starts there
+/// - Per-event hooks for every metric collector (reactions, replies, ...)
+/// - With `periods`, the same stats for each year and month (see [`PeriodStats`])
 ///
 /// Stops when:
-/// - The room's creation is reached (`reached_start`), OR
+/// - The start of the visible timeline is reached (`reached_start`), OR
 /// - No more events are returned, OR
-/// - The window start is reached (if specified)
+/// - The window start is reached (if specified), OR
+/// - `cancel` is cancelled: the pagination in flight is dropped and the stats
+///   cover the events processed so far, as a room that is not fully crawled
 ///
 /// # Returns
 ///
@@ -71,6 +83,9 @@ pub async fn paginate_and_collect_detailed_stats<F>(
     room_name: &str,
     newest_event_id_initial: Option<String>,
     newest_ts_initial: Option<i64>,
+    collectors: &[Box<dyn MetricCollector>],
+    mut periods: Option<&mut PeriodStats>,
+    cancel: &CancellationToken,
     progress_callback: F,
 ) -> Result<(RoomCrawlStats, DetailedPaginationStats)>
 where
@@ -80,343 +95,823 @@ where
 
     let room_id = room.room_id().to_string();
 
-    let mut stats = DetailedPaginationStats {
+    let mut stats = empty_detailed_stats(newest_event_id_initial, newest_ts_initial);
+
+    // Tracks the number of events processed (for progress only). This includes
+    // events outside the requested window to ensure the spinner count
+    // monotonically increases as we load more history.
+    let mut progress_events: usize = 0;
+
+    let mut stop_at_window = false;
+
+    // Track event IDs we've already processed to avoid double-counting
+    let mut processed_event_ids = HashSet::new();
+
+    // The cache holds events oldest first
+    let mut untimed = UntimedEvents::new(TimelineOrder::Chronological);
+
+    let ctx = PaginationContext {
+        window_start_ts,
+        window_end_ts,
+        user_id,
+        room_id: &room_id,
+        collectors,
+    };
+
+    // Load all events currently in the cache before starting backward pagination
+    let cached_events = room_event_cache.events().await?;
+
+    // Process all cached events first
+    for event in cached_events.iter() {
+        process_event(
+            event,
+            &ctx,
+            &mut stats,
+            &mut processed_event_ids,
+            &mut untimed,
+            &mut progress_events,
+            periods.as_deref_mut(),
+        );
+    }
+
+    // Paginated batches come newest first, starting before the oldest cached event
+    untimed.turn_backward(stats.oldest_ts);
+
+    loop {
+        let outcome = tokio::select! {
+            _ = cancel.cancelled() => break,
+            outcome = pagination.run_backwards_once(PAGINATION_BATCH_SIZE as u16) => {
+                outcome.context("Pagination failed")?
+            }
+        };
+
+        if outcome.events.is_empty() {
+            if outcome.reached_start {
+                stats.fully_crawled = true;
+            }
+            break;
+        }
+
+        // Mark as fully crawled but still process these final events
+        if outcome.reached_start {
+            stats.fully_crawled = true;
+        }
+
+        for event in outcome.events.iter() {
+            let event_outcome = process_event(
+                event,
+                &ctx,
+                &mut stats,
+                &mut processed_event_ids,
+                &mut untimed,
+                &mut progress_events,
+                periods.as_deref_mut(),
+            );
+            if event_outcome == EventOutcome::BeforeWindow {
+                stop_at_window = true;
+            }
+        }
+
+        progress_callback(room_name, stats.oldest_ts, stats.newest_ts, progress_events);
+
+        if stop_at_window || stats.fully_crawled {
+            break;
+        }
+    }
+
+    finish_history(&mut stats);
+    record_room_packs(room, room_name, &mut stats).await;
+
+    // Build RoomCrawlStats for DB updates
+    let crawl_stats = RoomCrawlStats {
+        room_id: room.room_id().to_string(),
+        oldest_event_id: stats.oldest_event_id.clone(),
+        oldest_ts: stats.oldest_ts,
+        newest_event_id: stats.newest_event_id.clone(),
+        newest_ts: stats.newest_ts,
+        fully_crawled: stats.fully_crawled,
+        history_limited: stats.history_limited,
+        joined_at: stats.joined_at,
+        room_name: room_name.to_string(),
+        total_events: stats.total_events,
+        user_events: stats.user_events,
+        untimed_events: stats.untimed_events,
+    };
+
+    Ok((crawl_stats, stats))
+}
+
+/// Aggregates a complete room history held in memory, oldest event first.
+///
+/// Runs the same per-event processing as [`paginate_and_collect_detailed_stats`]
+/// for fixture replays; the history is taken as complete, as if pagination had
+/// reached the start of the room.
+pub fn collect_detailed_stats_from_events(
+    events: &[TimelineEvent],
+    window_start_ts: Option<i64>,
+    window_end_ts: i64,
+    user_id: &str,
+    room_id: &str,
+    collectors: &[Box<dyn MetricCollector>],
+) -> DetailedPaginationStats {
+    let mut stats = empty_detailed_stats(None, None);
+    let mut processed_event_ids = HashSet::new();
+    let mut untimed = UntimedEvents::new(TimelineOrder::Chronological);
+    let mut progress_events: usize = 0;
+
+    let ctx = PaginationContext {
+        window_start_ts,
+        window_end_ts,
+        user_id,
+        room_id,
+        collectors,
+    };
+    for event in events {
+        process_event(
+            event,
+            &ctx,
+            &mut stats,
+            &mut processed_event_ids,
+            &mut untimed,
+            &mut progress_events,
+            None,
+        );
+    }
+
+    stats.fully_crawled = true;
+    finish_history(&mut stats);
+    stats
+}
+
+/// Detailed statistics before any event is processed.
+pub(super) fn empty_detailed_stats(
+    newest_event_id: Option<String>,
+    newest_ts: Option<i64>,
+) -> DetailedPaginationStats {
+    DetailedPaginationStats {
         fully_crawled: false,
+        history_limited: false,
+        saw_room_create: false,
+        joined_at: None,
         oldest_event_id: None,
         oldest_ts: None,
-        newest_event_id: newest_event_id_initial,
-        newest_ts: newest_ts_initial,
+        newest_event_id,
+        newest_ts,
         total_events: 0,
         user_events: 0,
+        untimed_events: 0,
+        others_messages: 0,
+        messages_by_sender: HashMap::new(),
+        sender_names: HashMap::new(),
+        dm_partner: None,
+        dm_partner_name: None,
+        canonical_alias: None,
         by_year: HashMap::new(),
         by_month: HashMap::new(),
         by_week: HashMap::new(),
         by_weekday: HashMap::new(),
         by_day: HashMap::new(),
         by_hour: HashMap::new(),
+        by_weekday_hour: HashMap::new(),
+        by_day_hour: HashMap::new(),
         user_message_ids: HashMap::new(),
         reactions_by_emoji: HashMap::new(),
         reactions_by_message: HashMap::new(),
+        reactions_by_month: HashMap::new(),
+        reactions_given_by_emoji: HashMap::new(),
+        reaction_emojis_by_message: HashMap::new(),
+        message_previews: HashMap::new(),
+        emote_names: HashMap::new(),
         room_created_by_user: false,
         active_dates: HashMap::new(),
-    };
+        first_replies: HashMap::new(),
+        replied_to_senders: HashMap::new(),
+        first_messages_by_day: HashMap::new(),
+        typed_words: 0,
+        messages_by_kind: HashMap::new(),
+        user_top_level_messages: 0,
+        user_thread_messages: 0,
+        user_threads: HashSet::new(),
+        user_replies: 0,
+        messages_by_length: HashMap::new(),
+        longest_message: None,
+        image_uses: HashMap::new(),
+        typed_emojis: HashMap::new(),
+        longest_message_chars: 0,
+        user_edits: 0,
+        user_reactions: 0,
+        user_encrypted_messages: 0,
+        user_messages_by_msgtype: HashMap::new(),
+        messages_by_timezone: HashMap::new(),
+        #[cfg(feature = "metric-plugins")]
+        custom_metrics: HashMap::new(),
+    }
+}
 
-    // Tracks the number of events processed (for progress only). This includes
-    // events outside the requested window to ensure the spinner count
-    // monotonically increases as we load more history.
-    let mut progress_events: usize = 0;
+/// Tells a history visibility boundary apart from the real start of the room.
+fn finish_history(stats: &mut DetailedPaginationStats) {
+    // The server reports the start of the timeline, but if the room creation event was never
+    // seen, history visibility hid older events from us rather than the room actually starting.
+    stats.history_limited = stats.fully_crawled && !stats.saw_room_create;
 
-    let mut stop_at_window = false;
+    // In that case history starts where the user joined; otherwise the join date says
+    // nothing about coverage.
+    if !stats.history_limited {
+        stats.joined_at = None;
+    }
+}
 
-    // Track event IDs we've already processed to avoid double-counting
-    let mut processed_event_ids = std::collections::HashSet::new();
+/// Whether one of the user's messages was sent encrypted, for the Fun section.
+///
+/// Decrypted messages keep their encryption info; undecryptable ones stay encrypted.
+fn is_encrypted(event: &TimelineEvent, deserialized: &AnySyncTimelineEvent) -> bool {
+    !matches!(
+        deserialized,
+        AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(_))
+    ) || event.encryption_info().is_some()
+}
 
-    // Load all events currently in the cache before starting backward pagination
-    let cached_events = room_event_cache.events().await?;
+/// State event type of a room's image pack (MSC2545), one per state key.
+pub(super) const ROOM_EMOTES_EVENT_TYPE: &str = "im.ponies.room_emotes";
 
-    // Process all cached events first
-    for event in cached_events.iter() {
-        let event_id_str = event.event_id().map(|id| id.to_string());
-
-        // Skip if already processed (deduplication by stable event ID only)
-        // Events without IDs are rare in Matrix and may be double-counted, but deduplicating
-        // by timestamp alone is unreliable as multiple events can share the same timestamp.
-        if let Some(ref event_id) = event_id_str {
-            if processed_event_ids.contains(event_id) {
-                continue;
+/// Remembers the shortcode of custom emotes (image packs, MSC2545), whose
+/// reactions are keyed by an mxc URL: from the room's emote packs, else from
+/// the shortcode some clients send along with the reaction (MSC4027).
+fn record_emote_names(event: &TimelineEvent, stats: &mut DetailedPaginationStats) {
+    let raw = event.raw();
+    let Some(content) = raw.get_field::<serde_json::Value>("content").ok().flatten() else {
+        return;
+    };
+    match raw.get_field::<String>("type").ok().flatten().as_deref() {
+        Some(ROOM_EMOTES_EVENT_TYPE) => record_pack(&content, None, stats),
+        Some("m.reaction") => {
+            let Some(key) = content["m.relates_to"]["key"].as_str() else {
+                return;
+            };
+            let shortcode = content["shortcode"]
+                .as_str()
+                .or_else(|| content["com.beeper.reaction.shortcode"].as_str());
+            if let (true, Some(shortcode)) = (key.starts_with("mxc://"), shortcode) {
+                // Colons are part of the shortcode on the wire (":party:")
+                let shortcode = shortcode.trim_matches(':').to_string();
+                stats
+                    .emote_names
+                    .entry(key.to_string())
+                    .or_insert((shortcode, None));
             }
-            processed_event_ids.insert(event_id.clone());
         }
+        _ => {}
+    }
+}
 
-        let ts_millis_opt: Option<i64> = event.timestamp().map(|ts| ts.get().into());
+/// Names the images of a room pack after their shortcode and the pack's display
+/// name, else `fallback_name`.
+fn record_pack(
+    content: &serde_json::Value,
+    fallback_name: Option<&str>,
+    stats: &mut DetailedPaginationStats,
+) {
+    let pack = content["pack"]["display_name"]
+        .as_str()
+        .or(fallback_name)
+        .map(str::to_string);
+    let Some(images) = content["images"].as_object() else {
+        return;
+    };
+    for (shortcode, image) in images {
+        if let Some(url) = image["url"].as_str() {
+            stats
+                .emote_names
+                .insert(url.to_string(), (shortcode.clone(), pack.clone()));
+        }
+    }
+}
 
-        let Some(ts_millis) = ts_millis_opt else {
+/// Names the images of the room's current packs, which usually predate the
+/// window. Packs without a display name are named after the room, as clients
+/// show them.
+async fn record_room_packs(
+    room: &matrix_sdk::Room,
+    room_name: &str,
+    stats: &mut DetailedPaginationStats,
+) {
+    let Ok(packs) = room
+        .get_state_events(StateEventType::from(ROOM_EMOTES_EVENT_TYPE))
+        .await
+    else {
+        return;
+    };
+    for pack in packs {
+        let RawAnySyncOrStrippedState::Sync(raw) = pack else {
             continue;
         };
-
-        // Track oldest/newest events for metadata (regardless of window)
-        if stats.oldest_ts.is_none_or(|old_ts| ts_millis < old_ts) {
-            stats.oldest_ts = Some(ts_millis);
-            stats.oldest_event_id = event_id_str.clone();
-        }
-        if stats.newest_ts.is_none_or(|new_ts| ts_millis > new_ts) {
-            stats.newest_ts = Some(ts_millis);
-            stats.newest_event_id = event_id_str.clone();
+        if let Ok(Some(content)) = raw.get_field::<serde_json::Value>("content") {
+            record_pack(&content, Some(room_name), stats);
         }
+    }
+}
+
+/// Returns true if the event is a membership event making `user_id` join the room.
+fn is_own_join(event: &TimelineEvent, user_id: &str) -> bool {
+    let raw = event.raw();
+    let state_key = raw.get_field::<String>("state_key").ok().flatten();
+    let membership = raw
+        .get_field::<serde_json::Value>("content")
+        .ok()
+        .flatten()
+        .and_then(|content| content.get("membership")?.as_str().map(str::to_owned));
+
+    state_key.as_deref() == Some(user_id) && membership.as_deref() == Some("join")
+}
 
-        // Count event for progress regardless of window inclusion
-        progress_events += 1;
+/// Stats of the years and months of a room, gathered next to the room stats
+/// by life crawls so each of them can be reported without crawling it again
+/// (`my render-all`).
+///
+/// Events go to the periods of their UTC date, the bounds a crawl of that
+/// window would use.
+#[derive(Default)]
+pub struct PeriodStats {
+    /// Stats by window key (`2024`, `2024-03`), with the bounds of the window
+    periods: BTreeMap<String, (Option<i64>, i64, DetailedPaginationStats)>,
+    /// Event IDs for [`process_event`]: the room-wide pass already dropped
+    /// duplicates, so it is cleared before each event
+    seen: HashSet<String>,
+}
 
-        // Skip events outside the window for statistics aggregation
-        if let Some(start) = window_start_ts {
-            if ts_millis < start {
-                continue;
-            }
-        }
-        if ts_millis > window_end_ts {
-            continue;
+impl PeriodStats {
+    /// Feeds an in-window event to the stats of its year and month.
+    fn record(&mut self, event: &TimelineEvent, ts_millis: i64, ctx: &PaginationContext<'_>) {
+        let Some(date) = DateTime::from_timestamp_millis(ts_millis) else {
+            return;
+        };
+        for key in [
+            date.format("%Y").to_string(),
+            date.format("%Y-%m").to_string(),
+        ] {
+            let (start_ts, end_ts, stats) = match self.periods.entry(key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let Ok(window) = WindowScope::parse(entry.key()) else {
+                        continue;
+                    };
+                    let (start_ts, end_ts) = window.to_timestamp_range();
+                    entry.insert((start_ts, end_ts, empty_detailed_stats(None, None)))
+                }
+            };
+            let period_ctx = PaginationContext {
+                window_start_ts: *start_ts,
+                window_end_ts: *end_ts,
+                ..*ctx
+            };
+            self.seen.clear();
+            process_event(
+                event,
+                &period_ctx,
+                stats,
+                &mut self.seen,
+                &mut UntimedEvents::new(TimelineOrder::Chronological),
+                &mut 0,
+                None,
+            );
         }
+    }
 
-        stats.total_events += 1;
+    /// Stats of each year and month, by window key, once the room is done:
+    /// they share its coverage, its DM partner, its alias and the names of its packs.
+    pub fn finish(self, room: &DetailedPaginationStats) -> Vec<(String, DetailedPaginationStats)> {
+        self.periods
+            .into_iter()
+            .map(|(key, (_, _, mut stats))| {
+                stats.fully_crawled = room.fully_crawled;
+                stats.history_limited = room.history_limited;
+                stats.saw_room_create = room.saw_room_create;
+                stats.joined_at = room.joined_at;
+                stats.dm_partner = room.dm_partner.clone();
+                stats.dm_partner_name = room.dm_partner_name.clone();
+                stats.canonical_alias = room.canonical_alias.clone();
+                stats.emote_names = room.emote_names.clone();
+                (key, stats)
+            })
+            .collect()
+    }
+}
 
-        // Convert timestamp to local datetime for bucketing
-        let dt = Local.timestamp_millis_opt(ts_millis).single();
-        let Some(dt) = dt else {
-            continue;
-        };
+/// Per-room parameters shared by every processed event.
+struct PaginationContext<'a> {
+    window_start_ts: Option<i64>,
+    window_end_ts: i64,
+    user_id: &'a str,
+    room_id: &'a str,
+    collectors: &'a [Box<dyn MetricCollector>],
+}
 
-        // Deserialize event for detailed processing
-        let Ok(deserialized) = event.raw().deserialize() else {
-            continue;
-        };
+/// Order in which events reach [`process_event`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TimelineOrder {
+    /// Oldest first (cached events, fixtures)
+    Chronological,
+    /// Newest first (backward pagination)
+    Backward,
+}
 
-        let sender = deserialized.sender();
-        let is_user_event = sender == user_id;
-
-        // Process different event types
-        match deserialized {
-            AnySyncTimelineEvent::MessageLike(msg_event) => {
-                match msg_event {
-                    AnySyncMessageLikeEvent::RoomMessage(_)
-                    | AnySyncMessageLikeEvent::RoomEncrypted(_) => {
-                        // Window-specific stats
-                        if is_user_event {
-                            stats.user_events += 1;
-
-                            // Temporal bucketing (only for user's messages)
-                            let year = dt.year().to_string();
-                            let month = format!("{:02}", dt.month());
-                            let iso_week = dt.iso_week();
-                            let week = format!("{}-W{:02}", iso_week.year(), iso_week.week());
-                            let weekday = dt.weekday().number_from_monday().to_string();
-                            let day = dt.format("%Y-%m-%d").to_string();
-                            let hour = format!("{:02}", dt.hour());
-
-                            *stats.by_year.entry(year).or_insert(0) += 1;
-                            *stats.by_month.entry(month).or_insert(0) += 1;
-                            *stats.by_week.entry(week).or_insert(0) += 1;
-                            *stats.by_weekday.entry(weekday).or_insert(0) += 1;
-                            *stats.by_day.entry(day.clone()).or_insert(0) += 1;
-                            *stats.by_hour.entry(hour).or_insert(0) += 1;
-
-                            // Track active dates
-                            stats.active_dates.insert(day, true);
-
-                            // Store user's message ID for reaction filtering
-                            if let Some(ref event_id) = event_id_str {
-                                stats
-                                    .user_message_ids
-                                    .insert(event_id.clone(), room_id.clone());
-                            }
-                        }
-                    }
-                    AnySyncMessageLikeEvent::Reaction(r) => {
-                        // Track reactions
-                        let content = r.as_original().map(|o| &o.content);
-                        if let Some(content) = content {
-                            // Extract emoji from annotation
-                            let emoji = content.relates_to.key.clone();
-                            let event_id = content.relates_to.event_id.to_string();
-
-                            // Only track reactions on user's messages
-                            // NOTE: This approach has a known limitation: if a reaction event
-                            // is encountered before its corresponding message event (in cached
-                            // events or pagination batches), the reaction will be skipped because
-                            // the message ID is not yet in user_message_ids. Since pagination
-                            // proceeds backward from recent to older events, reactions are
-                            // typically processed before their messages, so most reactions may
-                            // be skipped. A two-pass approach or forward-first traversal would
-                            // be needed to fully capture reactions.
-                            if stats.user_message_ids.contains_key(&event_id) {
-                                *stats.reactions_by_emoji.entry(emoji).or_insert(0) += 1;
-                                *stats.reactions_by_message.entry(event_id).or_insert(0) += 1;
-                            }
-                        }
-                    }
-                    _ => {
-                        // Other message-like events (edits, redactions, etc.) - ignore for now
-                    }
-                }
-            }
-            AnySyncTimelineEvent::State(state_event) => {
-                // Check for room creation by this user
-                if matches!(
-                    state_event,
-                    matrix_sdk::ruma::events::AnySyncStateEvent::RoomCreate(_)
-                ) && is_user_event
-                {
-                    stats.room_created_by_user = true;
-                }
-            }
+/// Bounds events without a timestamp by their timed neighbors.
+///
+/// Such events cannot be bucketed, but they are still part of the room history: one at
+/// either end of the crawled range becomes the oldest or newest event, at the timestamp
+/// of its nearest timed neighbor, so coverage is not cut short.
+struct UntimedEvents {
+    order: TimelineOrder,
+    /// Timestamp of the last timed event processed
+    neighbor_ts: Option<i64>,
+    /// First untimed event seen before any timed one, with whether it is newer than
+    /// the timed event that will follow it
+    pending: Option<(Option<String>, bool)>,
+}
+
+impl UntimedEvents {
+    fn new(order: TimelineOrder) -> Self {
+        Self {
+            order,
+            neighbor_ts: None,
+            pending: None,
         }
     }
 
-    loop {
-        let outcome = pagination
-            .run_backwards_once(PAGINATION_BATCH_SIZE as u16)
-            .await
-            .context("Pagination failed")?;
+    /// Continues with events older than those processed so far, newest first.
+    fn turn_backward(&mut self, oldest_ts: Option<i64>) {
+        self.order = TimelineOrder::Backward;
+        if oldest_ts.is_some() {
+            self.neighbor_ts = oldest_ts;
+        }
+    }
 
-        if outcome.events.is_empty() {
-            if outcome.reached_start {
-                stats.fully_crawled = true;
+    /// Records an event without a timestamp.
+    fn on_untimed(&mut self, event_id: Option<String>, stats: &mut DetailedPaginationStats) {
+        stats.untimed_events += 1;
+        // Going backward, the event is older than the one processed before it
+        let newer = self.order == TimelineOrder::Chronological;
+        match self.neighbor_ts {
+            Some(neighbor_ts) => bound_untimed(stats, event_id, neighbor_ts, newer),
+            None => {
+                if self.pending.is_none() {
+                    self.pending = Some((event_id, !newer));
+                }
             }
-            break;
         }
+    }
 
-        // Mark as fully crawled but still process these final events
-        if outcome.reached_start {
-            stats.fully_crawled = true;
+    /// Records a timed event, once the bounds include it.
+    fn on_timed(&mut self, ts_millis: i64, stats: &mut DetailedPaginationStats) {
+        self.neighbor_ts = Some(ts_millis);
+        if let Some((event_id, newer)) = self.pending.take() {
+            bound_untimed(stats, event_id, ts_millis, newer);
         }
+    }
+}
 
-        for event in outcome.events.iter() {
-            let event_id_str = event.event_id().map(|id| id.to_string());
+/// Makes an untimed event the oldest or newest one when its neighbor is.
+fn bound_untimed(
+    stats: &mut DetailedPaginationStats,
+    event_id: Option<String>,
+    neighbor_ts: i64,
+    newer: bool,
+) {
+    if newer && stats.newest_ts == Some(neighbor_ts) {
+        stats.newest_event_id = event_id;
+    } else if !newer && stats.oldest_ts == Some(neighbor_ts) {
+        stats.oldest_event_id = event_id;
+    }
+}
 
-            // Skip if already processed (deduplication by stable event ID only)
-            // Events without IDs are rare in Matrix and may be double-counted, but deduplicating
-            // by timestamp alone is unreliable as multiple events can share the same timestamp.
-            if let Some(ref event_id) = event_id_str {
-                if processed_event_ids.contains(event_id) {
-                    continue;
-                }
-                processed_event_ids.insert(event_id.clone());
-            }
+/// Result of processing a single timeline event.
+#[derive(Debug, PartialEq, Eq)]
+enum EventOutcome {
+    /// Event was aggregated, or skipped (duplicate, no timestamp, after window).
+    Processed,
+    /// Event is older than the window start; backward pagination can stop.
+    BeforeWindow,
+}
 
-            let ts_millis_opt: Option<i64> = event.timestamp().map(|ts| ts.get().into());
+/// Processes a single timeline event into the detailed statistics.
+///
+/// Shared by cached events and paginated batches so both paths aggregate identically:
+/// - Deduplicates by event ID
+/// - Counts events without a timestamp, bounded by their neighbors (see [`UntimedEvents`])
+/// - Tracks oldest/newest bounds and room creation regardless of window
+/// - Buckets the user's messages and detects room creation
+/// - Feeds the event to every metric collector
+fn process_event(
+    event: &TimelineEvent,
+    ctx: &PaginationContext<'_>,
+    stats: &mut DetailedPaginationStats,
+    processed_event_ids: &mut HashSet<String>,
+    untimed: &mut UntimedEvents,
+    progress_events: &mut usize,
+    periods: Option<&mut PeriodStats>,
+) -> EventOutcome {
+    let event_id_str = event.event_id().map(|id| id.to_string());
+
+    // Skip if already processed (deduplication by stable event ID only)
+    // Events without IDs are rare in Matrix and may be double-counted, but deduplicating
+    // by timestamp alone is unreliable as multiple events can share the same timestamp.
+    if let Some(ref event_id) = event_id_str {
+        if !processed_event_ids.insert(event_id.clone()) {
+            return EventOutcome::Processed;
+        }
+    }
 
-            // If there's no timestamp, we cannot bucket or filter; skip further processing
-            let Some(ts_millis) = ts_millis_opt else {
-                continue;
-            };
+    // If there's no timestamp, we cannot bucket or filter; skip further processing
+    let Some(ts_millis) = event.timestamp().map(|ts| i64::from(ts.get())) else {
+        untimed.on_untimed(event_id_str, stats);
+        *progress_events += 1;
+        return EventOutcome::Processed;
+    };
 
-            // Track oldest/newest events for metadata (regardless of window)
-            if stats.oldest_ts.is_none_or(|old_ts| ts_millis < old_ts) {
-                stats.oldest_ts = Some(ts_millis);
-                stats.oldest_event_id = event_id_str.clone();
-            }
-            if stats.newest_ts.is_none_or(|new_ts| ts_millis > new_ts) {
-                stats.newest_ts = Some(ts_millis);
-                stats.newest_event_id = event_id_str.clone();
-            }
+    // Track oldest/newest events for metadata (regardless of window)
+    if stats.oldest_ts.is_none_or(|old_ts| ts_millis < old_ts) {
+        stats.oldest_ts = Some(ts_millis);
+        stats.oldest_event_id = event_id_str.clone();
+    }
+    if stats.newest_ts.is_none_or(|new_ts| ts_millis > new_ts) {
+        stats.newest_ts = Some(ts_millis);
+        stats.newest_event_id = event_id_str.clone();
+    }
+    untimed.on_timed(ts_millis, stats);
 
-            // Count event for progress regardless of window inclusion
-            progress_events += 1;
+    // Count event for progress regardless of window inclusion
+    *progress_events += 1;
 
-            // Skip events outside the window for statistics aggregation
-            if let Some(start) = window_start_ts {
-                if ts_millis < start {
-                    stop_at_window = true;
-                    continue;
-                }
-            }
-            if ts_millis > window_end_ts {
-                continue;
-            }
+    // Note the room creation and the user's own join regardless of window, to tell a real
+    // room start apart from a history visibility boundary
+    match event.raw().get_field::<String>("type") {
+        Ok(Some(t)) if t == "m.room.create" => stats.saw_room_create = true,
+        Ok(Some(t)) if t == "m.room.member" && is_own_join(event, ctx.user_id) => {
+            stats.joined_at = Some(stats.joined_at.map_or(ts_millis, |ts| ts.min(ts_millis)));
+        }
+        _ => {}
+    }
 
-            stats.total_events += 1;
+    // Skip events outside the window for statistics aggregation
+    if let Some(start) = ctx.window_start_ts {
+        if ts_millis < start {
+            return EventOutcome::BeforeWindow;
+        }
+    }
+    if ts_millis > ctx.window_end_ts {
+        return EventOutcome::Processed;
+    }
 
-            // Convert timestamp to local datetime for bucketing
-            let dt = Local.timestamp_millis_opt(ts_millis).single();
-            let Some(dt) = dt else {
-                continue;
-            };
+    if let Some(periods) = periods {
+        periods.record(event, ts_millis, ctx);
+    }
 
-            // Deserialize event for detailed processing
-            let Ok(deserialized) = event.raw().deserialize() else {
-                continue;
-            };
+    stats.total_events += 1;
 
-            let sender = deserialized.sender();
-            let is_user_event = sender == user_id;
-
-            // Process different event types
-            match deserialized {
-                AnySyncTimelineEvent::MessageLike(msg_event) => {
-                    match msg_event {
-                        AnySyncMessageLikeEvent::RoomMessage(_)
-                        | AnySyncMessageLikeEvent::RoomEncrypted(_) => {
-                            if is_user_event {
-                                stats.user_events += 1;
-
-                                // Temporal bucketing (only for user's messages)
-                                let year = dt.year().to_string();
-                                let month = format!("{:02}", dt.month());
-                                let iso_week = dt.iso_week();
-                                let week = format!("{}-W{:02}", iso_week.year(), iso_week.week());
-                                let weekday = dt.weekday().number_from_monday().to_string();
-                                let day = dt.format("%Y-%m-%d").to_string();
-                                let hour = format!("{:02}", dt.hour());
-
-                                *stats.by_year.entry(year).or_insert(0) += 1;
-                                *stats.by_month.entry(month).or_insert(0) += 1;
-                                *stats.by_week.entry(week).or_insert(0) += 1;
-                                *stats.by_weekday.entry(weekday).or_insert(0) += 1;
-                                *stats.by_day.entry(day.clone()).or_insert(0) += 1;
-                                *stats.by_hour.entry(hour).or_insert(0) += 1;
-
-                                // Track active dates
-                                stats.active_dates.insert(day, true);
-
-                                // Store user's message ID for reaction filtering
-                                if let Some(ref event_id) = event_id_str {
-                                    stats
-                                        .user_message_ids
-                                        .insert(event_id.clone(), room_id.clone());
-                                }
-                            }
-                        }
-                        AnySyncMessageLikeEvent::Reaction(r) => {
-                            // Track reactions
-                            let content = r.as_original().map(|o| &o.content);
-                            if let Some(content) = content {
-                                // Extract emoji from annotation
-                                let emoji = content.relates_to.key.clone();
-                                let event_id = content.relates_to.event_id.to_string();
-
-                                // Only track reactions on user's messages
-                                if stats.user_message_ids.contains_key(&event_id) {
-                                    *stats.reactions_by_emoji.entry(emoji).or_insert(0) += 1;
-                                    *stats.reactions_by_message.entry(event_id).or_insert(0) += 1;
-                                }
-                            }
-                        }
-                        _ => {
-                            // Other message-like events (edits, redactions, etc.) - ignore for now
-                        }
-                    }
-                }
-                AnySyncTimelineEvent::State(state_event) => {
-                    // Check for room creation by this user
-                    if matches!(
-                        state_event,
-                        matrix_sdk::ruma::events::AnySyncStateEvent::RoomCreate(_)
-                    ) && is_user_event
-                    {
-                        stats.room_created_by_user = true;
-                    }
-                }
+    // Convert timestamp to local datetime for bucketing
+    let Some(dt) = Local.timestamp_millis_opt(ts_millis).single() else {
+        return EventOutcome::Processed;
+    };
+
+    // Deserialize event for detailed processing
+    let Ok(deserialized) = event.raw().deserialize() else {
+        return EventOutcome::Processed;
+    };
+
+    let is_user_event = deserialized.sender() == ctx.user_id;
+
+    // Core processing: temporal buckets for the user's messages and room creation
+    match &deserialized {
+        AnySyncTimelineEvent::MessageLike(
+            AnySyncMessageLikeEvent::RoomMessage(_) | AnySyncMessageLikeEvent::RoomEncrypted(_),
+        ) if is_user_event => {
+            stats.user_events += 1;
+
+            // Temporal bucketing (only for user's messages)
+            let year = dt.year().to_string();
+            let month = format!("{:02}", dt.month());
+            let iso_week = dt.iso_week();
+            let week = format!("{}-W{:02}", iso_week.year(), iso_week.week());
+            let weekday = dt.format("%a").to_string();
+            let day = dt.format("%Y-%m-%d").to_string();
+            let hour = format!("{:02}", dt.hour());
+
+            *stats.by_year.entry(year).or_insert(0) += 1;
+            *stats.by_month.entry(month).or_insert(0) += 1;
+            *stats.by_week.entry(week).or_insert(0) += 1;
+            *stats.by_weekday.entry(weekday).or_insert(0) += 1;
+            *stats.by_day.entry(day.clone()).or_insert(0) += 1;
+            *stats
+                .by_day_hour
+                .entry(day.clone())
+                .or_default()
+                .entry(hour.clone())
+                .or_insert(0) += 1;
+            *stats
+                .by_weekday_hour
+                .entry(dt.format("%a").to_string())
+                .or_default()
+                .entry(hour.clone())
+                .or_insert(0) += 1;
+            *stats.by_hour.entry(hour).or_insert(0) += 1;
+
+            // Track active dates
+            stats.active_dates.insert(day, true);
+
+            // Store user's message ID for reaction filtering
+            if let Some(ref event_id) = event_id_str {
+                stats
+                    .user_message_ids
+                    .insert(event_id.clone(), ctx.room_id.to_string());
             }
+
+            if is_encrypted(event, &deserialized) {
+                stats.user_encrypted_messages += 1;
+            }
+        }
+        AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::Reaction(_))
+            if is_user_event =>
+        {
+            stats.user_reactions += 1;
+        }
+        AnySyncTimelineEvent::State(AnySyncStateEvent::RoomCreate(_)) if is_user_event => {
+            // Room creation by this user
+            stats.room_created_by_user = true;
         }
+        _ => {}
+    }
+    record_emote_names(event, stats);
+
+    // Optional metrics (reactions, replies, downstream collectors)
+    let metric_ctx = EventContext {
+        #[cfg(feature = "metric-plugins")]
+        user_id: ctx.user_id,
+        event_id: event_id_str.as_deref(),
+        ts_millis,
+        is_user_event,
+    };
+    for collector in ctx.collectors {
+        collector.on_event(&deserialized, &metric_ctx, stats);
+    }
 
-        progress_callback(room_name, stats.oldest_ts, stats.newest_ts, progress_events);
+    EventOutcome::Processed
+}
 
-        if stop_at_window || stats.fully_crawled {
-            break;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::serde::Raw;
+
+    fn event(event_id: &str, ts: Option<i64>) -> TimelineEvent {
+        let mut json = serde_json::json!({
+            "type": "m.room.message",
+            "event_id": event_id,
+            "sender": "@bob:example.org",
+            "content": { "msgtype": "m.text", "body": "hi" },
+        });
+        if let Some(ts) = ts {
+            json["origin_server_ts"] = ts.into();
         }
+        TimelineEvent::from_plaintext(Raw::new(&json).unwrap().cast_unchecked())
     }
 
-    // Build RoomCrawlStats for DB updates
-    let crawl_stats = RoomCrawlStats {
-        room_id: room.room_id().to_string(),
-        oldest_event_id: stats.oldest_event_id.clone(),
-        oldest_ts: stats.oldest_ts,
-        newest_event_id: stats.newest_event_id.clone(),
-        newest_ts: stats.newest_ts,
-        fully_crawled: stats.fully_crawled,
-        room_name: room_name.to_string(),
-        total_events: stats.total_events,
-        user_events: stats.user_events,
-    };
+    #[test]
+    fn test_record_emote_names() {
+        let mut stats = empty_detailed_stats(None, None);
+        let raw = |json: serde_json::Value| {
+            TimelineEvent::from_plaintext(Raw::new(&json).unwrap().cast_unchecked())
+        };
 
-    Ok((crawl_stats, stats))
+        let reaction = raw(serde_json::json!({
+            "type": "m.reaction",
+            "event_id": "$r1",
+            "sender": "@bob:example.org",
+            "origin_server_ts": 1,
+            "content": {
+                "m.relates_to": { "rel_type": "m.annotation", "event_id": "$m1", "key": "mxc://example.org/party" },
+                "shortcode": ":party:",
+            },
+        }));
+        record_emote_names(&reaction, &mut stats);
+        assert_eq!(
+            stats.emote_names["mxc://example.org/party"],
+            ("party".to_string(), None)
+        );
+
+        // The room's emote pack also names the pack
+        let pack = raw(serde_json::json!({
+            "type": "im.ponies.room_emotes",
+            "state_key": "",
+            "event_id": "$p1",
+            "sender": "@bob:example.org",
+            "origin_server_ts": 2,
+            "content": {
+                "pack": { "display_name": "Team emotes" },
+                "images": { "party": { "url": "mxc://example.org/party" } },
+            },
+        }));
+        record_emote_names(&pack, &mut stats);
+        assert_eq!(
+            stats.emote_names["mxc://example.org/party"],
+            ("party".to_string(), Some("Team emotes".to_string()))
+        );
+
+        // A pack of the room state without a display name is named after the room
+        let content =
+            serde_json::json!({ "images": { "cat": { "url": "mxc://example.org/cat" } } });
+        record_pack(&content, Some("Team"), &mut stats);
+        assert_eq!(
+            stats.emote_names["mxc://example.org/cat"],
+            ("cat".to_string(), Some("Team".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_untimed_events_are_counted_and_bounded_by_neighbors() {
+        let events = [
+            event("$first", None),
+            event("$a", Some(1_000)),
+            event("$middle", None),
+            event("$b", Some(2_000)),
+            event("$last", None),
+        ];
+        let stats = collect_detailed_stats_from_events(
+            &events,
+            None,
+            i64::MAX,
+            "@alice:example.org",
+            "!room:example.org",
+            &[],
+        );
+
+        assert_eq!(stats.untimed_events, 3);
+        assert_eq!(stats.total_events, 2);
+        assert_eq!(stats.oldest_ts, Some(1_000));
+        assert_eq!(stats.oldest_event_id.as_deref(), Some("$first"));
+        assert_eq!(stats.newest_ts, Some(2_000));
+        assert_eq!(stats.newest_event_id.as_deref(), Some("$last"));
+    }
+
+    #[test]
+    fn test_untimed_events_going_backward() {
+        let mut stats = empty_detailed_stats(None, None);
+        let mut untimed = UntimedEvents::new(TimelineOrder::Backward);
+
+        // Newest first: the untimed events are the newest and oldest of the room
+        untimed.on_untimed(Some("$last".to_string()), &mut stats);
+        stats.oldest_ts = Some(2_000);
+        stats.newest_ts = Some(2_000);
+        untimed.on_timed(2_000, &mut stats);
+        untimed.on_untimed(Some("$first".to_string()), &mut stats);
+
+        assert_eq!(stats.untimed_events, 2);
+        assert_eq!(stats.newest_event_id.as_deref(), Some("$last"));
+        assert_eq!(stats.oldest_event_id.as_deref(), Some("$first"));
+    }
+
+    #[test]
+    fn test_period_stats_split_years_and_months() {
+        // 2024-12-31 23:30 UTC, 2025-01-15 and 2025-01-20, plus a duplicate
+        let events = [
+            event("$a", Some(1_735_687_800_000)),
+            event("$b", Some(1_736_942_400_000)),
+            event("$c", Some(1_737_374_400_000)),
+            event("$c", Some(1_737_374_400_000)),
+        ];
+        let ctx = PaginationContext {
+            window_start_ts: None,
+            window_end_ts: i64::MAX,
+            user_id: "@alice:example.org",
+            room_id: "!room:example.org",
+            collectors: &[],
+        };
+        let mut stats = empty_detailed_stats(None, None);
+        let mut seen = HashSet::new();
+        let mut untimed = UntimedEvents::new(TimelineOrder::Chronological);
+        let mut periods = PeriodStats::default();
+        for event in &events {
+            process_event(
+                event,
+                &ctx,
+                &mut stats,
+                &mut seen,
+                &mut untimed,
+                &mut 0,
+                Some(&mut periods),
+            );
+        }
+        stats.fully_crawled = true;
+
+        let periods = periods.finish(&stats);
+        let totals: Vec<(&str, usize)> = periods
+            .iter()
+            .map(|(key, period)| (key.as_str(), period.total_events))
+            .collect();
+        assert_eq!(
+            totals,
+            [("2024", 1), ("2024-12", 1), ("2025", 2), ("2025-01", 2)]
+        );
+        assert!(periods.iter().all(|(_, period)| period.fully_crawled));
+        assert_eq!(periods[2].1.oldest_event_id.as_deref(), Some("$b"));
+    }
 }
@@ -0,0 +1,133 @@
+/// Stats of the years and months of a life crawl (`my render-all`).
+///
+/// A life crawl stores the stats of each room for every year and month it saw
+/// events in, next to the room-wide ones. Any of those windows can then be
+/// aggregated the way its own crawl would, without paginating the rooms again.
+use anyhow::{Context, Result};
+
+use super::db::{CrawlDb, LiveRoomStats};
+use super::live::room_inputs;
+use super::metrics::builtin_collectors;
+use super::stats_builder::{build_stats, RoomStatsInput};
+use super::types::DetailedPaginationStats;
+use super::CrawlOptions;
+use crate::stats::{ScopeKind, Stats};
+use crate::window::WindowScope;
+
+/// Store the stats of a room crawled for life, by window key (`2024`,
+/// `2024-03`), replacing those of an earlier life crawl
+pub fn record_room(
+    db: &CrawlDb,
+    input: &RoomStatsInput,
+    periods: Vec<(String, DetailedPaginationStats)>,
+) -> Result<()> {
+    let mut rows = Vec::with_capacity(periods.len());
+    for (period, stats) in periods {
+        let stats_json = serde_json::to_string(&stats)
+            .with_context(|| format!("Failed to serialize room stats of {}", period))?;
+        rows.push((
+            period,
+            LiveRoomStats {
+                room_id: input.room_id.clone(),
+                room_name: input.room_name.clone(),
+                room_type: input.room_type.key().to_string(),
+                stats_json,
+            },
+        ));
+    }
+    db.replace_period_room_stats(&input.room_id, &rows)
+}
+
+/// Years or months (`scope_type`) with stored stats, oldest first
+pub fn windows(db: &CrawlDb, scope_type: ScopeKind) -> Result<Vec<WindowScope>> {
+    let mut windows = Vec::new();
+    for period in db.get_periods()? {
+        let window = WindowScope::parse(&period).context("Failed to parse window")?;
+        if window.scope_type == scope_type {
+            windows.push(window);
+        }
+    }
+    Ok(windows)
+}
+
+/// Aggregates the stored room stats of `window`, a year or a month.
+///
+/// Rankings are cut at the default length and the profile is left out, as for
+/// live reports.
+pub fn build(db: &CrawlDb, account_id: &str, window: &WindowScope) -> Result<Stats> {
+    let inputs = room_inputs(db.get_period_room_stats(&window.key)?)?;
+    let joined_rooms = match db.get_live_crawl(Some("life"))? {
+        Some(crawl) => crawl.joined_rooms,
+        None => inputs.len(),
+    };
+
+    let options = CrawlOptions::default();
+    build_stats(
+        &inputs,
+        account_id,
+        None,
+        None,
+        window,
+        joined_rooms,
+        options.top,
+        &builtin_collectors(&options),
+    )
+    .with_context(|| format!("Failed to build the stats of {}", window.key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::crawl::pagination::empty_detailed_stats;
+    use crate::commands::crawl::RoomType;
+
+    fn room_stats(month: &str, messages: i32) -> DetailedPaginationStats {
+        let mut stats = empty_detailed_stats(None, None);
+        stats.user_events = messages as usize;
+        stats.by_month.insert(month.to_string(), messages);
+        stats.active_dates.insert(format!("{}-10", month), true);
+        stats
+    }
+
+    #[test]
+    fn test_periods_of_life_crawl() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = CrawlDb::init(dir.path()).unwrap();
+        db.start_live_crawl("life", 7, 1, 1_000).unwrap();
+        let input = RoomStatsInput {
+            room_id: "!room:example.org".to_string(),
+            room_name: Some("Room".to_string()),
+            room_type: RoomType::Private,
+            stats: empty_detailed_stats(None, None),
+        };
+        let periods = vec![
+            ("2024".to_string(), room_stats("2024-11", 5)),
+            ("2024-11".to_string(), room_stats("2024-11", 5)),
+            ("2025".to_string(), room_stats("2025-02", 2)),
+            ("2025-02".to_string(), room_stats("2025-02", 2)),
+        ];
+        record_room(&db, &input, periods).unwrap();
+
+        let keys = |scope_type| -> Vec<String> {
+            windows(&db, scope_type)
+                .unwrap()
+                .into_iter()
+                .map(|window| window.key)
+                .collect()
+        };
+        assert_eq!(keys(ScopeKind::Year), ["2024", "2025"]);
+        assert_eq!(keys(ScopeKind::Month), ["2024-11", "2025-02"]);
+
+        let month = WindowScope::parse("2024-11").unwrap();
+        let stats = build(&db, "@alice:example.org", &month).unwrap();
+        assert_eq!(stats.scope.key, "2024-11");
+        assert_eq!(stats.summary.messages_sent.get(), 5);
+        assert_eq!(stats.account.rooms_total.get(), 7);
+
+        // A new life crawl of the room replaces its periods
+        let periods = vec![("2025".to_string(), room_stats("2025-02", 3))];
+        record_room(&db, &input, periods).unwrap();
+        assert_eq!(keys(ScopeKind::Year), ["2025"]);
+        assert!(keys(ScopeKind::Month).is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+/// Metric collectors compiled in with the `metric-plugins` cargo feature.
+///
+/// Downstream builds register their own [`MetricCollector`]s in [`collectors`];
+/// they run after the built-in ones. Per-room state goes into
+/// `DetailedPaginationStats::custom_metrics` under the collector's name, and
+/// results into the free-form Fun section. [`MentionCollector`] shows the way.
+use anyhow::Result;
+use matrix_sdk::ruma::events::{AnySyncMessageLikeEvent, AnySyncTimelineEvent};
+
+use super::metrics::{insert_fun_field, EventContext, MetricCollector};
+use super::stats_builder::RoomStatsInput;
+use super::types::DetailedPaginationStats;
+use super::CrawlOptions;
+use crate::stats::Stats;
+
+/// Returns the plugin collectors, configured from crawl options.
+pub fn collectors(_options: &CrawlOptions) -> Vec<Box<dyn MetricCollector>> {
+    vec![Box::new(MentionCollector)]
+}
+
+/// Counts the messages of others mentioning the user, as `mentions_received`.
+pub struct MentionCollector;
+
+impl MentionCollector {
+    const KEY: &'static str = "mentions";
+}
+
+impl MetricCollector for MentionCollector {
+    fn on_event(
+        &self,
+        event: &AnySyncTimelineEvent,
+        ctx: &EventContext<'_>,
+        room: &mut DetailedPaginationStats,
+    ) {
+        if ctx.is_user_event {
+            return;
+        }
+        let AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(message)) =
+            event
+        else {
+            return;
+        };
+        let Some(original) = message.as_original() else {
+            return;
+        };
+        let mentioned = original.content.mentions.as_ref().is_some_and(|mentions| {
+            mentions
+                .user_ids
+                .iter()
+                .any(|user_id| user_id.as_str() == ctx.user_id)
+        });
+        if mentioned {
+            let count = room
+                .custom_metrics
+                .entry(Self::KEY.to_string())
+                .or_insert(serde_json::Value::from(0));
+            *count = (count.as_i64().unwrap_or(0) + 1).into();
+        }
+    }
+
+    fn aggregate(&self, rooms: &[&RoomStatsInput], stats: &mut Stats) -> Result<()> {
+        let mentions: i64 = rooms
+            .iter()
+            .filter_map(|room| room.stats.custom_metrics.get(Self::KEY)?.as_i64())
+            .sum();
+        if mentions > 0 {
+            insert_fun_field(stats, "mentions_received", mentions.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mention_collector() {
+        let mut room = super::super::pagination::empty_detailed_stats(None, None);
+        let ctx = EventContext {
+            user_id: "@alice:example.org",
+            event_id: None,
+            ts_millis: 0,
+            is_user_event: false,
+        };
+        for mentioned in [
+            "@alice:example.org",
+            "@bob:example.org",
+            "@alice:example.org",
+        ] {
+            let event: AnySyncTimelineEvent = serde_json::from_value(serde_json::json!({
+                "type": "m.room.message",
+                "event_id": "$m",
+                "sender": "@carol:example.org",
+                "origin_server_ts": 1,
+                "content": {
+                    "msgtype": "m.text",
+                    "body": "ping",
+                    "m.mentions": { "user_ids": [mentioned] },
+                },
+            }))
+            .unwrap();
+            MentionCollector.on_event(&event, &ctx, &mut room);
+        }
+        assert_eq!(room.custom_metrics["mentions"], serde_json::json!(2));
+    }
+}
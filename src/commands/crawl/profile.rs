@@ -0,0 +1,243 @@
+/// Account profile: display name and avatar, and the avatars of the top rooms.
+///
+/// Avatars are downloaded through the SDK (authenticated media when the
+/// homeserver supports it) as small thumbnails and kept in the account
+/// directory, so reports can embed them without linking to a media server.
+use anyhow::{Context, Result};
+use matrix_sdk::media::{MediaFormat, MediaRequestParameters, MediaThumbnailSettings};
+use matrix_sdk::ruma::events::room::MediaSource;
+use matrix_sdk::ruma::{uint, OwnedMxcUri, RoomId, UInt};
+use std::path::Path;
+
+use super::progress::CrawlLog;
+use crate::stats::RoomEntry;
+
+/// Prefix of avatar files in the account directory.
+const AVATAR_PREFIX: &str = "avatar-";
+
+/// Prefix of room avatar files in the account directory.
+const ROOM_AVATAR_PREFIX: &str = "room-avatar-";
+
+/// Size of room avatar thumbnails, in pixels: they sit next to room names.
+const ROOM_AVATAR_SIZE: u32 = 48;
+
+/// Profile of the crawled account.
+#[derive(Debug, Default)]
+pub struct Profile {
+    pub display_name: Option<String>,
+    /// MXC URI of the avatar
+    pub avatar_url: Option<String>,
+    /// Avatar file name in the account directory
+    pub avatar_file: Option<String>,
+}
+
+/// Fetches the account profile and stores its avatar in `account_dir`.
+///
+/// Profile errors are logged and leave the fields empty: they should not fail
+/// the crawl.
+pub async fn fetch_profile(
+    client: &matrix_sdk::Client,
+    account_dir: &Path,
+    log: &CrawlLog,
+) -> Profile {
+    let account = client.account();
+    let display_name = account.get_display_name().await.unwrap_or_else(|e| {
+        log.println(&format!("Warning: failed to fetch display name: {}", e));
+        None
+    });
+    let avatar_url = account.get_avatar_url().await.unwrap_or_else(|e| {
+        log.println(&format!("Warning: failed to fetch avatar URL: {}", e));
+        None
+    });
+
+    let avatar_file = match &avatar_url {
+        Some(url) => match store_avatar(client, url, account_dir).await {
+            Ok(file) => file,
+            Err(e) => {
+                log.println(&format!("Warning: failed to download avatar: {:#}", e));
+                None
+            }
+        },
+        None => {
+            remove_avatars(account_dir, None);
+            None
+        }
+    };
+
+    Profile {
+        display_name,
+        avatar_url: avatar_url.map(|url| url.to_string()),
+        avatar_file,
+    }
+}
+
+/// Stores the avatars of the top rooms in `account_dir` and names them in
+/// their `avatar_file`.
+///
+/// Room avatars are read from the rooms known to the client. Failures are
+/// logged and leave the room without avatar.
+pub async fn fetch_room_avatars(
+    client: &matrix_sdk::Client,
+    rooms: &mut [RoomEntry],
+    account_dir: &Path,
+    log: &CrawlLog,
+) {
+    for entry in rooms {
+        let Some(room) = entry
+            .permalink
+            .strip_prefix("https://matrix.to/#/")
+            .and_then(|room_id| RoomId::parse(room_id).ok())
+            .and_then(|room_id| client.get_room(&room_id))
+        else {
+            continue;
+        };
+        let Some(url) = room.avatar_url() else {
+            continue;
+        };
+        match store_thumbnail(
+            client,
+            &url,
+            account_dir,
+            ROOM_AVATAR_PREFIX,
+            ROOM_AVATAR_SIZE.into(),
+        )
+        .await
+        {
+            Ok(file) => entry.avatar_file = file,
+            Err(e) => log.println(&format!(
+                "Warning: failed to download the avatar of {}: {:#}",
+                room.room_id(),
+                e
+            )),
+        }
+    }
+}
+
+/// Downloads the avatar thumbnail unless it is already stored, and removes
+/// older ones.
+async fn store_avatar(
+    client: &matrix_sdk::Client,
+    url: &OwnedMxcUri,
+    account_dir: &Path,
+) -> Result<Option<String>> {
+    let file_name = store_thumbnail(client, url, account_dir, AVATAR_PREFIX, uint!(96)).await?;
+    if let Some(ref file_name) = file_name {
+        remove_avatars(account_dir, Some(file_name));
+    }
+    Ok(file_name)
+}
+
+/// Downloads a `size` pixels thumbnail unless it is already stored.
+///
+/// Files are named after the media ID, so a new avatar gets a new file.
+/// Returns `None` for images that are not PNG, JPEG, GIF or WebP.
+async fn store_thumbnail(
+    client: &matrix_sdk::Client,
+    url: &OwnedMxcUri,
+    account_dir: &Path,
+    prefix: &str,
+    size: UInt,
+) -> Result<Option<String>> {
+    let media_id = url.media_id().context("Invalid avatar URL")?;
+    let stem = format!("{}{}", prefix, file_safe(media_id));
+
+    if let Some(existing) = find_avatar(account_dir, &stem) {
+        return Ok(Some(existing));
+    }
+
+    let request = MediaRequestParameters {
+        source: MediaSource::Plain(url.clone()),
+        format: MediaFormat::Thumbnail(MediaThumbnailSettings::new(size, size)),
+    };
+    let bytes = client
+        .media()
+        .get_media_content(&request, true)
+        .await
+        .context("Failed to download avatar")?;
+
+    let Some(extension) = image_extension(&bytes) else {
+        return Ok(None);
+    };
+    let file_name = format!("{}.{}", stem, extension);
+    std::fs::write(account_dir.join(&file_name), &bytes)
+        .with_context(|| format!("Failed to write avatar: {}", file_name))?;
+
+    Ok(Some(file_name))
+}
+
+/// Stored avatar file named `stem` with any image extension.
+fn find_avatar(account_dir: &Path, stem: &str) -> Option<String> {
+    ["png", "jpg", "gif", "webp"]
+        .iter()
+        .map(|extension| format!("{}.{}", stem, extension))
+        .find(|file_name| account_dir.join(file_name).is_file())
+}
+
+/// Removes stored avatars other than `keep`.
+fn remove_avatars(account_dir: &Path, keep: Option<&str>) {
+    let Ok(entries) = std::fs::read_dir(account_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with(AVATAR_PREFIX) && Some(name.as_str()) != keep {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Extension of a raster image, from its signature.
+pub fn image_extension(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if bytes.starts_with(b"\xff\xd8\xff") {
+        Some("jpg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("gif")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("webp")
+    } else {
+        None
+    }
+}
+
+/// Keeps the characters that are safe in a file name on every platform.
+fn file_safe(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_extension() {
+        assert_eq!(image_extension(b"\x89PNG\r\n\x1a\n...."), Some("png"));
+        assert_eq!(image_extension(b"\xff\xd8\xff\xe0"), Some("jpg"));
+        assert_eq!(image_extension(b"GIF89a..."), Some("gif"));
+        assert_eq!(image_extension(b"RIFF\0\0\0\0WEBPVP8 "), Some("webp"));
+        // SVG may carry scripts: never embedded
+        assert_eq!(image_extension(b"<svg xmlns="), None);
+    }
+
+    #[test]
+    fn test_stored_avatars() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("avatar-old.png"), b"old").unwrap();
+        std::fs::write(dir.path().join("avatar-new.jpg"), b"new").unwrap();
+        std::fs::write(dir.path().join("stats-2025.json"), b"{}").unwrap();
+
+        assert_eq!(
+            find_avatar(dir.path(), "avatar-new"),
+            Some("avatar-new.jpg".to_string())
+        );
+        remove_avatars(dir.path(), Some("avatar-new.jpg"));
+        assert!(!dir.path().join("avatar-old.png").exists());
+        assert!(dir.path().join("avatar-new.jpg").exists());
+        assert!(dir.path().join("stats-2025.json").exists());
+
+        assert_eq!(file_safe("abc/../DEF_1-2"), "abcDEF_1-2");
+    }
+}
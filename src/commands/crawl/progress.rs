@@ -2,14 +2,25 @@
 ///
 /// Handles progress bar creation, updates, and result display.
 /// Can operate in TTY mode (with animated spinners) or non-TTY mode (text logging).
+/// In non-TTY mode, lines go through [`CrawlLog`], which prefixes them with the
+/// account and room and writes them one at a time.
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use std::io::IsTerminal;
+use std::cell::Cell;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
 use crate::timefmt::format_timestamp_opt;
 
 /// Maximum width for room names in progress display.
 const ROOM_NAME_WIDTH: usize = 38;
 
+/// Separator between the account, room and message of a non-TTY log line.
+const LOG_SEPARATOR: &str = " › ";
+
+/// Serializes non-TTY log lines across tasks, so lines never interleave.
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
 /// Type alias for progress callback function.
 /// Called with (room_name, oldest_ts, newest_ts, total_events).
 pub type ProgressCallback = Box<dyn Fn(&str, Option<i64>, Option<i64>, usize)>;
@@ -50,43 +61,126 @@ fn truncate_middle(s: &str, max_width: usize) -> String {
     }
 }
 
-/// Formats a completed room result on a single line.
+/// Formats a non-TTY log line as `account › room › message`.
+///
+/// Every line of a multi-line message gets the prefix, and the indentation used
+/// on a terminal is dropped since the prefix already tells where a line belongs.
+fn prefixed_lines(account: &str, room: Option<&str>, msg: &str) -> String {
+    let prefix = match room {
+        Some(room) => format!("{}{}{}{}", account, LOG_SEPARATOR, room, LOG_SEPARATOR),
+        None => format!("{}{}", account, LOG_SEPARATOR),
+    };
+    msg.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| format!("{}{}\n", prefix, line))
+        .collect()
+}
+
+/// Account-scoped crawl log.
+///
+/// On a terminal, messages are printed as-is: one account is crawled at a time
+/// and progress bars show which room is which. Otherwise (CI, redirected output),
+/// each line is prefixed with the account and, for room results, the room, and
+/// written under a process-wide lock so concurrent tasks cannot interleave.
+#[derive(Clone)]
+pub struct CrawlLog {
+    account: Arc<str>,
+    is_tty: bool,
+}
+
+impl CrawlLog {
+    pub fn new(account_id: &str) -> Self {
+        Self {
+            account: Arc::from(account_id),
+            is_tty: std::io::stderr().is_terminal(),
+        }
+    }
+
+    /// Prints an account-level message.
+    pub fn println(&self, msg: &str) {
+        if self.is_tty {
+            eprintln!("{}", msg);
+        } else {
+            write_serialized(&prefixed_lines(&self.account, None, msg));
+        }
+    }
+
+    /// Prints a message about one room (non-TTY output only).
+    fn println_room(&self, room_name: &str, msg: &str) {
+        write_serialized(&prefixed_lines(&self.account, Some(room_name), msg));
+    }
+}
+
+/// Writes whole lines to stderr while holding the log lock.
+fn write_serialized(lines: &str) {
+    let _guard = LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut stderr = std::io::stderr().lock();
+    let _ = stderr.write_all(lines.as_bytes());
+    let _ = stderr.flush();
+}
+
+/// Returns the completeness marker shown after a room's crawl summary.
+///
+/// - `💯` when back-paginated to the room creation
+/// - `🙈` when the start of history was a history visibility boundary
+/// - nothing while older history remains to be fetched
+pub fn completeness_marker(fully_crawled: bool, history_limited: bool) -> &'static str {
+    match (fully_crawled, history_limited) {
+        (true, false) => " 💯",
+        (true, true) => " 🙈",
+        (false, _) => "",
+    }
+}
+
+/// Formats a completed room result, shown after the room name, or None when no
+/// event was fetched.
+///
+/// Example output: `12329 events from 2024-12-29 02:27 (5 from you) 💯`
 ///
-/// Example output:
-/// `Element iOS (OLD)                        12329 events from 2024-12-29 02:27 (5 from you) 💯`
-pub fn format_completed_room(
-    room_name: &str,
+/// Events skipped for lacking a timestamp are counted after it, so a room made of
+/// them does not look empty.
+pub fn format_room_summary(
     total_events: usize,
     user_events: usize,
+    untimed_events: usize,
     oldest_ts: Option<i64>,
-    newest_ts: Option<i64>,
     fully_crawled: bool,
-) -> String {
-    let truncated_name = truncate_middle(room_name, ROOM_NAME_WIDTH);
-    let creation_marker = if fully_crawled { " 💯" } else { "" };
-
-    if let (Some(oldest), _) = (oldest_ts, newest_ts) {
-        // Format timestamp and truncate to minute precision
-        let oldest_full = format_timestamp_opt(Some(oldest));
-        let oldest_short = if oldest_full.len() >= 16 {
-            &oldest_full[..16]
-        } else {
-            &oldest_full
-        };
+    history_limited: bool,
+) -> Option<String> {
+    let untimed_str = match untimed_events {
+        0 => String::new(),
+        1 => "⚠️  1 event without timestamp skipped".to_string(),
+        n => format!("⚠️  {} events without timestamp skipped", n),
+    };
+    let Some(oldest) = oldest_ts else {
+        return (untimed_events > 0).then_some(untimed_str);
+    };
+    let creation_marker = completeness_marker(fully_crawled, history_limited);
 
-        let user_events_str = if user_events > 0 {
-            format!(" ({} from you)", user_events)
-        } else {
-            String::new()
-        };
+    // Format timestamp and truncate to minute precision
+    let oldest_full = format_timestamp_opt(Some(oldest));
+    let oldest_short = if oldest_full.len() >= 16 {
+        &oldest_full[..16]
+    } else {
+        &oldest_full
+    };
 
-        format!(
-            "{} {:>5} events from {}{}{}",
-            truncated_name, total_events, oldest_short, user_events_str, creation_marker
-        )
+    let user_events_str = if user_events > 0 {
+        format!(" ({} from you)", user_events)
     } else {
-        truncated_name.to_string()
+        String::new()
+    };
+
+    let mut summary = format!(
+        "{:>5} events from {}{}{}",
+        total_events, oldest_short, user_events_str, creation_marker
+    );
+    if untimed_events > 0 {
+        summary.push_str(" · ");
+        summary.push_str(&untimed_str);
     }
+    Some(summary)
 }
 
 /// Progress tracking for the entire crawl operation.
@@ -98,14 +192,17 @@ pub struct CrawlProgress {
     multi: Option<MultiProgress>,
     overall: Option<ProgressBar>,
     is_tty: bool,
+    log: CrawlLog,
+    /// Events fetched by all rooms, including the rooms in flight
+    events: Arc<AtomicUsize>,
 }
 
 impl CrawlProgress {
     /// Creates progress bars for a crawl operation.
     ///
     /// If the output is a TTY, creates animated progress bars.
-    /// Otherwise, progress is reported via text output only.
-    pub fn new(total_rooms: usize) -> Self {
+    /// Otherwise, progress is reported via text output only, through `log`.
+    pub fn new(total_rooms: usize, log: CrawlLog) -> Self {
         let is_tty = std::io::stderr().is_terminal();
 
         if is_tty {
@@ -120,30 +217,51 @@ impl CrawlProgress {
                 multi: Some(mp),
                 overall: Some(overall),
                 is_tty: true,
+                log,
+                events: Arc::default(),
             }
         } else {
             CrawlProgress {
                 multi: None,
                 overall: None,
                 is_tty: false,
+                log,
+                events: Arc::default(),
             }
         }
     }
 
+    /// Events fetched so far by the rooms of this crawl, done or in flight.
+    pub fn events(&self) -> usize {
+        self.events.load(Ordering::Relaxed)
+    }
+
     /// Creates a progress callback for a single room's pagination.
     ///
     /// Returns a tuple of (callback, optional_spinner).
-    /// The callback updates progress as events are paginated.
+    /// The callback updates progress as events are paginated, and adds the
+    /// room's new events to [`CrawlProgress::events`].
     /// The spinner (if present) should be finished when the room completes.
     pub fn make_callback(&self, room_name: String) -> (ProgressCallback, Option<ProgressBar>) {
         let multi = self.multi.clone();
         let overall = self.overall.clone();
 
+        // The callback gets the room's running total
+        let total_events = self.events.clone();
+        let room_events = Cell::new(0usize);
+        let count = move |events: usize| {
+            let new_events = events.saturating_sub(room_events.replace(events));
+            total_events.fetch_add(new_events, Ordering::Relaxed);
+        };
+
         if self.is_tty {
             let Some(ref mp) = multi else {
                 // Unexpected: TTY mode but no MultiProgress. Fallback to non-TTY behavior.
                 let callback = Box::new(
-                    |_name: &str, _oldest: Option<i64>, _newest: Option<i64>, _events: usize| {},
+                    move |_name: &str,
+                          _oldest: Option<i64>,
+                          _newest: Option<i64>,
+                          events: usize| { count(events) },
                 );
                 return (callback, None);
             };
@@ -164,6 +282,7 @@ impl CrawlProgress {
             let room_name_for_cb = room_name.clone();
             let callback = Box::new(
                 move |_name: &str, oldest: Option<i64>, _newest: Option<i64>, events: usize| {
+                    count(events);
                     let truncated_name: String =
                         truncate_middle(&room_name_for_cb, ROOM_NAME_WIDTH);
                     let msg = if let Some(ts) = oldest {
@@ -183,9 +302,11 @@ impl CrawlProgress {
             );
             (callback, Some(pb))
         } else {
-            // Non-TTY mode: no-op callback
+            // Non-TTY mode: events are only counted
             let callback = Box::new(
-                |_name: &str, _oldest: Option<i64>, _newest: Option<i64>, _events: usize| {},
+                move |_name: &str, _oldest: Option<i64>, _newest: Option<i64>, events: usize| {
+                    count(events)
+                },
             );
             (callback, None)
         }
@@ -218,7 +339,31 @@ impl CrawlProgress {
                 eprintln!("{}", msg);
             }
         } else {
-            eprintln!("{}", msg);
+            self.log.println(msg);
+        }
+    }
+
+    /// Prints the result of a room's crawl: `detail` is the crawl summary on
+    /// success, the error otherwise.
+    ///
+    /// On a TTY, the line shows above the progress bars with the room name aligned;
+    /// otherwise it goes to the log prefixed with the account and room.
+    pub fn println_room(&self, room_name: &str, success: bool, detail: &str) {
+        if self.is_tty {
+            let line = if success {
+                format!(
+                    "  ✓ {} {}",
+                    truncate_middle(room_name, ROOM_NAME_WIDTH),
+                    detail
+                )
+            } else {
+                format!("  \x1b[31m✗\x1b[0m {} ({})", room_name, detail)
+            };
+            self.println(line.trim_end());
+        } else {
+            let marker = if success { "✓" } else { "✗" };
+            self.log
+                .println_room(room_name, format!("{} {}", marker, detail).trim_end());
         }
     }
 }
@@ -229,17 +374,32 @@ mod tests {
 
     #[test]
     fn test_progress_creation() {
-        let progress = CrawlProgress::new(5);
+        let progress = CrawlProgress::new(5, CrawlLog::new("@alice:example.org"));
         assert_eq!(progress.is_tty, std::io::stderr().is_terminal());
     }
 
     #[test]
     fn test_callback_creation() {
-        let progress = CrawlProgress::new(5);
+        let progress = CrawlProgress::new(5, CrawlLog::new("@alice:example.org"));
         let _callback = progress.make_callback("Test Room".to_string());
         // Callback should be callable without panicking
     }
 
+    #[test]
+    fn test_callbacks_count_events() {
+        let progress = CrawlProgress::new(2, CrawlLog::new("@alice:example.org"));
+        let (first, first_spinner) = progress.make_callback("First".to_string());
+        let (second, second_spinner) = progress.make_callback("Second".to_string());
+        // Each call gets the room's running total
+        first("First", None, None, 100);
+        second("Second", None, None, 40);
+        first("First", None, None, 250);
+        assert_eq!(progress.events(), 290);
+        for spinner in [first_spinner, second_spinner].into_iter().flatten() {
+            spinner.finish_and_clear();
+        }
+    }
+
     #[test]
     fn test_truncate_middle_short() {
         let s = "Short name";
@@ -257,18 +417,51 @@ mod tests {
     }
 
     #[test]
-    fn test_format_completed_room_basic() {
-        let out =
-            super::format_completed_room("Room", 123, 0, Some(1_735_689_600_000), None, false);
-        assert!(out.starts_with("Room"));
+    fn test_format_room_summary_basic() {
+        let out = format_room_summary(123, 0, 0, Some(1_735_689_600_000), false, false).unwrap();
         assert!(out.contains("123 events"));
         assert!(!out.contains("from you"));
     }
 
     #[test]
-    fn test_format_completed_room_with_user_events_and_creation() {
-        let out = super::format_completed_room("Room", 5, 2, Some(1_735_689_600_000), None, true);
-        assert!(out.contains("(2 from you)"));
-        assert!(out.contains("💯"));
+    fn test_format_room_summary_with_user_events_and_creation() {
+        let out = format_room_summary(5, 2, 0, Some(1_735_689_600_000), true, false).unwrap();
+        assert!(out.starts_with("    5 events from 2025-01-01 00:00"));
+        assert!(out.ends_with("(2 from you) 💯"));
+        assert_eq!(format_room_summary(0, 0, 0, None, false, false), None);
+    }
+
+    #[test]
+    fn test_format_room_summary_history_limited() {
+        let out = format_room_summary(5, 0, 0, Some(1_735_689_600_000), true, true).unwrap();
+        assert!(out.contains("🙈"));
+        assert!(!out.contains("💯"));
+    }
+
+    #[test]
+    fn test_format_room_summary_untimed_events() {
+        let out = format_room_summary(5, 0, 2, Some(1_735_689_600_000), false, false).unwrap();
+        assert!(out.ends_with(" · ⚠️  2 events without timestamp skipped"));
+        assert_eq!(
+            format_room_summary(0, 0, 1, None, false, false).as_deref(),
+            Some("⚠️  1 event without timestamp skipped")
+        );
+    }
+
+    #[test]
+    fn test_prefixed_lines() {
+        assert_eq!(
+            prefixed_lines("@alice:example.org", None, "📚 Found 3 joined room(s)"),
+            "@alice:example.org › 📚 Found 3 joined room(s)\n"
+        );
+        assert_eq!(
+            prefixed_lines("@alice:example.org", Some("Friends"), "  ✓ 12 events\n"),
+            "@alice:example.org › Friends › ✓ 12 events\n"
+        );
+        // Each line of a multi-line message is prefixed
+        assert_eq!(
+            prefixed_lines("@bob:example.org", None, "first\n  second"),
+            "@bob:example.org › first\n@bob:example.org › second\n"
+        );
     }
 }
@@ -0,0 +1,191 @@
+/// Quiet hours for crawl throttling.
+///
+/// A daily local-time range (e.g. `09:00-18:00`, or `22:00-07:00` across midnight)
+/// during which the crawler reduces its concurrency or pauses, to stay gentle on a
+/// shared connection. The crawler checks them again before starting each room, so
+/// a long crawl slows down when they start and speeds up when they end.
+use anyhow::{Context, Result};
+use chrono::{NaiveTime, TimeDelta};
+
+/// Daily time range in local time, start inclusive and end exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Parses a `HH:MM-HH:MM` range. The range may wrap around midnight.
+    pub fn parse(input: &str) -> Result<Self> {
+        let (start, end) = input
+            .trim()
+            .split_once('-')
+            .context("Quiet hours must look like HH:MM-HH:MM (e.g. 09:00-18:00)")?;
+
+        let start = NaiveTime::parse_from_str(start.trim(), "%H:%M")
+            .with_context(|| format!("Invalid quiet hours start: {}", start))?;
+        let end = NaiveTime::parse_from_str(end.trim(), "%H:%M")
+            .with_context(|| format!("Invalid quiet hours end: {}", end))?;
+
+        if start == end {
+            anyhow::bail!("Quiet hours start and end must differ");
+        }
+
+        Ok(Self { start, end })
+    }
+
+    /// Returns true if `time` falls within the quiet hours.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            time >= self.start && time < self.end
+        } else {
+            // Wraps around midnight
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// Time left from `time` until the quiet hours end.
+    pub fn remaining(&self, time: NaiveTime) -> TimeDelta {
+        time_until(self.end, time)
+    }
+
+    /// Time left from `time` until the quiet hours start.
+    pub fn until_start(&self, time: NaiveTime) -> TimeDelta {
+        time_until(self.start, time)
+    }
+}
+
+/// Time from `time` to the next occurrence of `target`, today or tomorrow.
+fn time_until(target: NaiveTime, time: NaiveTime) -> TimeDelta {
+    let delta = target - time;
+    if delta < TimeDelta::zero() {
+        delta + TimeDelta::days(1)
+    } else {
+        delta
+    }
+}
+
+/// How many rooms a crawl paginates at once, according to the quiet hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Throttle {
+    pub quiet_hours: Option<QuietHours>,
+    /// Rooms crawled at once during quiet hours (0 pauses until they end)
+    pub quiet_concurrency: usize,
+    /// Rooms crawled at once outside quiet hours
+    pub max_concurrency: usize,
+}
+
+impl Throttle {
+    /// Returns true if `time` falls within the quiet hours, if any.
+    pub fn is_quiet(&self, time: NaiveTime) -> bool {
+        self.quiet_hours.is_some_and(|quiet| quiet.contains(time))
+    }
+
+    /// Rooms that may be crawled at once at `time`; 0 pauses the crawl.
+    pub fn concurrency(&self, time: NaiveTime) -> usize {
+        if self.is_quiet(time) {
+            self.quiet_concurrency.min(self.max_concurrency)
+        } else {
+            self.max_concurrency
+        }
+    }
+
+    /// Time from `time` until the concurrency changes, when the quiet hours
+    /// next start or end; `None` without quiet hours.
+    pub fn until_change(&self, time: NaiveTime) -> Option<TimeDelta> {
+        let quiet = self.quiet_hours?;
+        Some(if quiet.contains(time) {
+            quiet.remaining(time)
+        } else {
+            quiet.until_start(time)
+        })
+    }
+}
+
+impl std::fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_daytime_range() {
+        let quiet = QuietHours::parse("09:00-18:30").unwrap();
+        assert_eq!(quiet.start, t(9, 0));
+        assert_eq!(quiet.end, t(18, 30));
+        assert_eq!(quiet.to_string(), "09:00-18:30");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(QuietHours::parse("09:00").is_err());
+        assert!(QuietHours::parse("25:00-18:00").is_err());
+        assert!(QuietHours::parse("09:00-09:00").is_err());
+    }
+
+    #[test]
+    fn test_contains_daytime_range() {
+        let quiet = QuietHours::parse("09:00-18:00").unwrap();
+        assert!(quiet.contains(t(9, 0)));
+        assert!(quiet.contains(t(12, 0)));
+        assert!(!quiet.contains(t(18, 0)));
+        assert!(!quiet.contains(t(8, 59)));
+    }
+
+    #[test]
+    fn test_contains_overnight_range() {
+        let quiet = QuietHours::parse("22:00-07:00").unwrap();
+        assert!(quiet.contains(t(23, 0)));
+        assert!(quiet.contains(t(3, 0)));
+        assert!(!quiet.contains(t(7, 0)));
+        assert!(!quiet.contains(t(12, 0)));
+    }
+
+    #[test]
+    fn test_remaining() {
+        let quiet = QuietHours::parse("22:00-07:00").unwrap();
+        assert_eq!(quiet.remaining(t(23, 0)), TimeDelta::hours(8));
+        assert_eq!(quiet.remaining(t(6, 30)), TimeDelta::minutes(30));
+        assert_eq!(quiet.until_start(t(21, 0)), TimeDelta::hours(1));
+        assert_eq!(quiet.until_start(t(23, 0)), TimeDelta::hours(23));
+    }
+
+    #[test]
+    fn test_throttle() {
+        let throttle = Throttle {
+            quiet_hours: Some(QuietHours::parse("09:00-18:00").unwrap()),
+            quiet_concurrency: 2,
+            max_concurrency: 8,
+        };
+        assert_eq!(throttle.concurrency(t(8, 0)), 8);
+        assert_eq!(throttle.until_change(t(8, 0)), Some(TimeDelta::hours(1)));
+        assert_eq!(throttle.concurrency(t(12, 0)), 2);
+        assert_eq!(throttle.until_change(t(12, 0)), Some(TimeDelta::hours(6)));
+
+        let paused = Throttle {
+            quiet_concurrency: 0,
+            ..throttle
+        };
+        assert_eq!(paused.concurrency(t(12, 0)), 0);
+
+        let unthrottled = Throttle {
+            quiet_hours: None,
+            ..throttle
+        };
+        assert_eq!(unthrottled.concurrency(t(12, 0)), 8);
+        assert_eq!(unthrottled.until_change(t(12, 0)), None);
+    }
+}
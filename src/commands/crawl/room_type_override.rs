@@ -0,0 +1,96 @@
+/// Room-type overrides.
+///
+/// Forces the classification of specific rooms (e.g. a "public" room that is
+/// really a family room, or a DM with a bot), since the DM/public/private
+/// heuristics can't know every case. Overrides replace the result of
+/// `classify_room_type` before stats are aggregated.
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+
+use super::RoomType;
+
+/// A room forced to a given type, parsed from `<room_id>=<dm|public|private>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomTypeOverride {
+    pub room_id: String,
+    pub room_type: RoomType,
+}
+
+impl RoomTypeOverride {
+    /// Parses `<room_id>=<type>` (e.g. `!abc:example.org=private`).
+    pub fn parse(input: &str) -> Result<Self> {
+        let (room_id, room_type) = input
+            .trim()
+            .rsplit_once('=')
+            .context("Room type override must look like <room_id>=<dm|public|private>")?;
+
+        let room_id = room_id.trim();
+        if !room_id.starts_with('!') || !room_id.contains(':') {
+            anyhow::bail!(
+                "Invalid room id '{}' (expected e.g. !abc:example.org)",
+                room_id
+            );
+        }
+
+        let room_type = RoomType::from_key(room_type).with_context(|| {
+            format!(
+                "Unknown room type '{}' (expected dm, public or private)",
+                room_type.trim()
+            )
+        })?;
+
+        Ok(Self {
+            room_id: room_id.to_string(),
+            room_type,
+        })
+    }
+}
+
+/// Indexes overrides by room id; the last override of a room wins.
+pub fn by_room(overrides: &[RoomTypeOverride]) -> HashMap<String, RoomType> {
+    overrides
+        .iter()
+        .map(|o| (o.room_id.clone(), o.room_type))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            RoomTypeOverride::parse("!abc:example.org=Private").unwrap(),
+            RoomTypeOverride {
+                room_id: "!abc:example.org".to_string(),
+                room_type: RoomType::Private,
+            }
+        );
+        assert_eq!(
+            RoomTypeOverride::parse(" !bot:example.org = dm ")
+                .unwrap()
+                .room_type,
+            RoomType::Dm
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(RoomTypeOverride::parse("!abc:example.org").is_err());
+        assert!(RoomTypeOverride::parse("#alias:example.org=public").is_err());
+        assert!(RoomTypeOverride::parse("!abc:example.org=space").is_err());
+    }
+
+    #[test]
+    fn test_by_room_last_wins() {
+        let overrides = [
+            RoomTypeOverride::parse("!abc:example.org=public").unwrap(),
+            RoomTypeOverride::parse("!abc:example.org=private").unwrap(),
+        ];
+        assert_eq!(
+            by_room(&overrides).get("!abc:example.org"),
+            Some(&RoomType::Private)
+        );
+    }
+}
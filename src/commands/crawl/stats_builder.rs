@@ -3,10 +3,13 @@
 /// Combines room-level statistics into account-level Stats structures.
 /// Computes peaks, rankings, and aggregates temporal data.
 use anyhow::Result;
-use std::collections::HashMap;
+use chrono::{NaiveDate, TimeZone};
+use std::collections::{HashMap, HashSet};
 
+use super::metrics::MetricCollector;
 use super::types::DetailedPaginationStats;
 use super::RoomType;
+use crate::config::DmNames;
 use crate::stats::*;
 use crate::window::WindowScope;
 
@@ -30,6 +33,9 @@ struct TemporalAggregates {
     by_weekday: HashMap<String, i32>,
     by_day: HashMap<String, i32>,
     by_hour: HashMap<String, i32>,
+    by_weekday_hour: HashMap<String, HashMap<String, i32>>,
+    by_day_hour: HashMap<String, HashMap<String, i32>>,
+    by_room_type_hour: HashMap<String, HashMap<String, i32>>,
 }
 
 impl TemporalAggregates {
@@ -41,10 +47,13 @@ impl TemporalAggregates {
             by_weekday: HashMap::new(),
             by_day: HashMap::new(),
             by_hour: HashMap::new(),
+            by_weekday_hour: HashMap::new(),
+            by_day_hour: HashMap::new(),
+            by_room_type_hour: HashMap::new(),
         }
     }
 
-    fn aggregate_from(&mut self, other: &DetailedPaginationStats) {
+    fn aggregate_from(&mut self, other: &DetailedPaginationStats, room_type: RoomType) {
         for (key, count) in &other.by_year {
             *self.by_year.entry(key.clone()).or_insert(0) += count;
         }
@@ -63,29 +72,26 @@ impl TemporalAggregates {
         for (key, count) in &other.by_hour {
             *self.by_hour.entry(key.clone()).or_insert(0) += count;
         }
-    }
-}
-
-/// Aggregated reaction data across all rooms (private).
-struct ReactionAggregates {
-    by_emoji: HashMap<String, i32>,
-    by_message: HashMap<String, i32>,
-}
-
-impl ReactionAggregates {
-    fn new() -> Self {
-        Self {
-            by_emoji: HashMap::new(),
-            by_message: HashMap::new(),
+        for (weekday, hours) in &other.by_weekday_hour {
+            let row = self.by_weekday_hour.entry(weekday.clone()).or_default();
+            for (hour, count) in hours {
+                *row.entry(hour.clone()).or_insert(0) += count;
+            }
         }
-    }
-
-    fn aggregate_from(&mut self, other: &DetailedPaginationStats) {
-        for (emoji, count) in &other.reactions_by_emoji {
-            *self.by_emoji.entry(emoji.clone()).or_insert(0) += count;
+        for (day, hours) in &other.by_day_hour {
+            let row = self.by_day_hour.entry(day.clone()).or_default();
+            for (hour, count) in hours {
+                *row.entry(hour.clone()).or_insert(0) += count;
+            }
         }
-        for (msg_id, count) in &other.reactions_by_message {
-            *self.by_message.entry(msg_id.clone()).or_insert(0) += count;
+        if !other.by_hour.is_empty() {
+            let row = self
+                .by_room_type_hour
+                .entry(room_type.key().to_string())
+                .or_default();
+            for (hour, count) in &other.by_hour {
+                *row.entry(hour.clone()).or_insert(0) += count;
+            }
         }
     }
 }
@@ -162,11 +168,103 @@ impl CreatedRoomMetrics {
     }
 }
 
+/// Server of the rooms whose ID and alias name none.
+const UNKNOWN_SERVER: &str = "unknown";
+
+/// Messages sent by homeserver of the rooms (private).
+struct ServerMetrics {
+    messages: HashMap<String, i32>,
+}
+
+impl ServerMetrics {
+    fn new() -> Self {
+        Self {
+            messages: HashMap::new(),
+        }
+    }
+
+    /// Counts the messages of a room for the server part of its ID. Room IDs
+    /// without one (room version 12 and later) count for the server of the
+    /// room's canonical alias, else for [`UNKNOWN_SERVER`].
+    fn record(&mut self, room_id: &str, canonical_alias: Option<&str>, message_count: i32) {
+        let server = room_id
+            .split_once(':')
+            .or_else(|| canonical_alias.and_then(|alias| alias.split_once(':')))
+            .map_or(UNKNOWN_SERVER, |(_, server)| server);
+        *self.messages.entry(server.to_string()).or_insert(0) += message_count;
+    }
+
+    /// The `top` busiest servers, with their share of `messages_sent`.
+    fn rank(&self, messages_sent: i32, top: usize) -> Result<Vec<ServerEntry>> {
+        let mut servers: Vec<(&String, &i32)> = self.messages.iter().collect();
+        servers.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        servers
+            .into_iter()
+            .take(top)
+            .map(|(server, &messages)| {
+                let percentage = if messages_sent > 0 {
+                    messages as f64 / messages_sent as f64 * 100.0
+                } else {
+                    0.0
+                };
+                ServerEntry::new(server.clone(), messages, percentage)
+            })
+            .collect()
+    }
+}
+
+/// Figures behind the Fun section, summed over active rooms (private).
+struct FunMetrics {
+    longest_message_chars: usize,
+    edits: usize,
+    reactions: usize,
+    encrypted_messages: usize,
+    /// `/me` messages (`m.emote`)
+    emotes: i32,
+    /// Rooms with activity in the window where the user sent nothing
+    lurking_rooms: i32,
+}
+
+impl FunMetrics {
+    fn new() -> Self {
+        Self {
+            longest_message_chars: 0,
+            edits: 0,
+            reactions: 0,
+            encrypted_messages: 0,
+            emotes: 0,
+            lurking_rooms: 0,
+        }
+    }
+
+    fn record(&mut self, other: &DetailedPaginationStats) {
+        self.longest_message_chars = self.longest_message_chars.max(other.longest_message_chars);
+        self.edits += other.user_edits;
+        self.reactions += other.user_reactions;
+        self.encrypted_messages += other.user_encrypted_messages;
+        self.emotes += other
+            .user_messages_by_msgtype
+            .get("m.emote")
+            .copied()
+            .unwrap_or(0);
+    }
+}
+
+/// Room-level figures used for ranking (private).
+struct RoomRanking<'a> {
+    room_id: String,
+    room_name: Option<String>,
+    messages: i32,
+    joined_at: Option<String>,
+    by_day: &'a HashMap<String, i32>,
+}
+
 /// Coverage bounds tracking (private).
 struct CoverageBounds {
     oldest_ts: Option<i64>,
     newest_ts: Option<i64>,
     active_dates: HashMap<String, bool>,
+    since_joined_rooms: i32,
 }
 
 impl CoverageBounds {
@@ -175,6 +273,7 @@ impl CoverageBounds {
             oldest_ts: None,
             newest_ts: None,
             active_dates: HashMap::new(),
+            since_joined_rooms: 0,
         }
     }
 
@@ -199,6 +298,7 @@ impl CoverageBounds {
 /// - Ranks top rooms, emojis, and messages
 /// - Calculates room type distributions
 /// - Generates coverage information
+/// - Runs metric collectors (reactions, fun facts, ...)
 ///
 /// # Arguments
 ///
@@ -208,45 +308,59 @@ impl CoverageBounds {
 /// * `account_avatar_url` - User's avatar MXC URL (if available)
 /// * `window_scope` - Time window being analyzed
 /// * `total_rooms` - Total number of joined rooms for the account
+/// * `top` - Entries kept in each ranking
+/// * `collectors` - Metric collectors that contribute optional sections
+#[allow(clippy::too_many_arguments)]
 pub fn build_stats(
-    room_inputs: Vec<RoomStatsInput>,
+    room_inputs: &[RoomStatsInput],
     account_id: &str,
     account_display_name: Option<String>,
     account_avatar_url: Option<String>,
     window_scope: &WindowScope,
     total_rooms: usize,
+    top: usize,
+    collectors: &[Box<dyn MetricCollector>],
 ) -> Result<Stats> {
     // Initialize aggregation structures
     let mut temporal = TemporalAggregates::new();
-    let mut reactions = ReactionAggregates::new();
     let mut room_types = RoomTypeMetrics::new();
     let mut created_rooms = CreatedRoomMetrics::new();
+    let mut servers = ServerMetrics::new();
     let mut coverage = CoverageBounds::new();
+    let mut fun = FunMetrics::new();
 
     // Track room-level metrics for ranking
-    let mut room_message_counts: Vec<(String, Option<String>, RoomType, i32)> = Vec::new();
+    let mut room_rankings: Vec<RoomRanking> = Vec::new();
     let mut active_rooms_count = 0;
 
+    let (window_start_ts, _) = window_scope.to_timestamp_range();
+
     // Aggregate stats from each room
-    for room_input in &room_inputs {
+    for room_input in room_inputs {
         let room_stats = &room_input.stats;
         let user_messages = room_stats.user_events as i32;
 
         // Skip rooms where user sent no messages (for active rooms count)
         if user_messages == 0 {
+            if room_stats.total_events > 0 {
+                fun.lurking_rooms += 1;
+            }
+            fun.reactions += room_stats.user_reactions;
             continue;
         }
 
         active_rooms_count += 1;
 
         // Aggregate temporal data
-        temporal.aggregate_from(room_stats);
-
-        // Aggregate reactions
-        reactions.aggregate_from(room_stats);
+        temporal.aggregate_from(room_stats, room_input.room_type);
 
-        // Track room type distribution
+        // Track room type and homeserver distributions
         room_types.record(room_input.room_type, user_messages);
+        servers.record(
+            &room_input.room_id,
+            room_stats.canonical_alias.as_deref(),
+            user_messages,
+        );
 
         // Track room creation
         if room_stats.room_created_by_user {
@@ -255,14 +369,27 @@ pub fn build_stats(
 
         // Update coverage bounds and active dates
         coverage.update_from(room_stats);
+        fun.record(room_stats);
+
+        // Rooms whose visible history starts with the user's join inside the window
+        // only cover part of it
+        let joined_at = room_stats
+            .joined_at
+            .filter(|ts| window_start_ts.is_none_or(|start| *ts > start))
+            .and_then(|ts| chrono::Local.timestamp_millis_opt(ts).single())
+            .map(|dt| dt.format("%Y-%m-%d").to_string());
+        if joined_at.is_some() {
+            coverage.since_joined_rooms += 1;
+        }
 
         // Collect room info for ranking
-        room_message_counts.push((
-            room_input.room_id.clone(),
-            room_input.room_name.clone(),
-            room_input.room_type,
-            user_messages,
-        ));
+        room_rankings.push(RoomRanking {
+            room_id: room_input.room_id.clone(),
+            room_name: room_input.room_name.clone(),
+            messages: user_messages,
+            joined_at,
+            by_day: &room_stats.by_day,
+        });
     }
 
     // Calculate total messages sent
@@ -274,82 +401,366 @@ pub fn build_stats(
         &temporal.by_month,
         &temporal.by_week,
         &temporal.by_day,
-        &temporal.by_hour,
+        &temporal.by_day_hour,
     )?;
 
-    // Rank top rooms
-    let top_rooms = rank_top_rooms(&mut room_message_counts, messages_sent)?;
-
-    // Rank top emojis
-    let top_emojis = rank_top_emojis(reactions.by_emoji)?;
-
-    // Rank top messages
-    let top_messages = rank_top_messages(reactions.by_message)?;
-
-    // Calculate total reactions
-    let total_reactions: i32 = top_emojis.iter().map(|e| e.count).sum();
-
     // Build coverage information
     let (coverage_from, coverage_to, days_active) =
         compute_coverage_bounds(&coverage, window_scope)?;
 
+    // Rank top rooms, with their trend over the crawled part of the window
+    let trend_span = trend_span(window_scope, &coverage_from, &coverage_to);
+    let top_rooms = rank_top_rooms(&mut room_rankings, messages_sent, top, trend_span)?;
+
     // Build activity section early to consume temporal struct
     let activity = build_activity_section(temporal, messages_sent)?;
 
     // Build Stats struct
-    let stats = Stats {
-        schema_version: 1,
-        scope: Scope {
-            kind: window_scope.scope_type,
-            key: window_scope.key.clone(),
-            label: None,
-        },
-        generated_at: chrono::Local::now().format("%Y-%m-%d").to_string(),
-        account: Account {
-            user_id: account_id.to_string(),
-            display_name: account_display_name,
-            avatar_url: account_avatar_url,
-            rooms_total: total_rooms as i32,
-        },
-        coverage: Coverage {
-            from: coverage_from,
-            to: coverage_to,
-            days_active,
-        },
-        summary: Summary {
-            messages_sent,
-            active_rooms: active_rooms_count,
-            dm_rooms: if room_types.dm_count > 0 {
-                Some(room_types.dm_count)
-            } else {
-                None
-            },
-            public_rooms: if room_types.public_count > 0 {
-                Some(room_types.public_count)
-            } else {
-                None
-            },
-            private_rooms: if room_types.private_count > 0 {
-                Some(room_types.private_count)
-            } else {
-                None
-            },
-            peaks,
-        },
-        activity,
-        rooms: build_rooms_section(top_rooms, &room_types, active_rooms_count)?,
-        reactions: build_reactions_section(top_emojis, top_messages, total_reactions)?,
-        created_rooms: build_created_rooms_section(&created_rooms)?,
-        fun: None, // TODO: Implement fun stats later
-    };
+    let account = Account::new(account_id, total_rooms as i32)?
+        .with_display_name(account_display_name)
+        .with_avatar_url(account_avatar_url);
+    let coverage_section = Coverage::new(
+        coverage_from,
+        coverage_to,
+        days_active,
+        coverage.since_joined_rooms,
+    )?;
+    let summary = Summary::new(messages_sent, active_rooms_count)?
+        .with_room_types(
+            room_types.dm_count,
+            room_types.public_count,
+            room_types.private_count,
+        )?
+        .with_peaks(peaks);
+    let mut stats = Stats::new(
+        Scope::new(window_scope.scope_type, &window_scope.key),
+        account,
+        coverage_section,
+        summary,
+    )
+    .with_timezone(crate::config::bucketing_timezone())
+    .with_activity(activity)
+    .with_rooms(build_rooms_section(
+        top_rooms,
+        &room_types,
+        servers.rank(messages_sent, top)?,
+        active_rooms_count,
+    )?)
+    .with_created_rooms(build_created_rooms_section(&created_rooms)?);
+
+    insert_fun_facts(&mut stats, &fun, &coverage.active_dates, window_scope);
+
+    // Let metric collectors fill in their sections from active rooms
+    let active_rooms: Vec<&RoomStatsInput> = room_inputs
+        .iter()
+        .filter(|room_input| room_input.stats.user_events > 0)
+        .collect();
+    for collector in collectors {
+        collector.aggregate(&active_rooms, &mut stats)?;
+    }
 
     Ok(stats)
 }
 
+/// Builds stats isolated to each of the top rooms, ranked like `rooms.top`.
+///
+/// Each breakdown runs the same aggregation and metric collectors as the
+/// account-level stats, over that single room.
+pub fn build_room_breakdowns(
+    room_inputs: &[RoomStatsInput],
+    account_id: &str,
+    window_scope: &WindowScope,
+    top: usize,
+    collectors: &[Box<dyn MetricCollector>],
+) -> Result<Vec<RoomBreakdown>> {
+    let mut ranked: Vec<&RoomStatsInput> = room_inputs
+        .iter()
+        .filter(|room_input| room_input.stats.user_events > 0)
+        .collect();
+    ranked.sort_by_key(|room_input| std::cmp::Reverse(room_input.stats.user_events));
+
+    ranked
+        .into_iter()
+        .take(top)
+        .map(|room_input| {
+            let room_stats = build_stats(
+                std::slice::from_ref(room_input),
+                account_id,
+                None,
+                None,
+                window_scope,
+                1,
+                top,
+                collectors,
+            )?;
+            Ok(RoomBreakdown {
+                name: room_input.room_name.clone(),
+                permalink: format!("https://matrix.to/#/{}", room_input.room_id),
+                days_active: room_stats.coverage.days_active,
+                summary: room_stats.summary,
+                activity: room_stats.activity,
+                reactions: room_stats.reactions,
+            })
+        })
+        .collect()
+}
+
+/// Records the account creation date and the account age as a fun fact.
+///
+/// The age is counted up to the end of the window (or today for ongoing
+/// windows), and left out when the window ends before the account existed.
+pub fn apply_account_creation(stats: &mut Stats, created_ts: i64, window_scope: &WindowScope) {
+    let Some(created) = chrono::Local.timestamp_millis_opt(created_ts).single() else {
+        return;
+    };
+    stats.account.created_at = Some(created.format("%Y-%m-%d").to_string());
+
+    let (_, window_end_ts) = window_scope.to_timestamp_range();
+    let end_ts = window_end_ts.min(chrono::Local::now().timestamp_millis());
+    let age_days = (end_ts - created_ts) / (24 * 60 * 60 * 1000);
+    if age_days >= 0 {
+        super::metrics::insert_fun_field(stats, "matrix_age_days", age_days.into());
+    }
+}
+
+/// Names DM partners and DM rooms as the `dm_names` setting allows.
+///
+/// Runs once the stats are complete, so that every place naming a DM in the
+/// stats file follows it: the People ranking, top rooms, DM balance, rooms
+/// with the most reactions, per-room breakdowns and the DM correspondents.
+/// With `hidden`, the DM partner and correspondent rankings are left out.
+pub fn apply_dm_names(stats: &mut Stats, rooms: &[RoomStatsInput], dm_names: DmNames) {
+    if dm_names.is_full() {
+        return;
+    }
+    let dm_permalinks: HashSet<String> = rooms
+        .iter()
+        .filter(|room| room.room_type == RoomType::Dm)
+        .map(|room| format!("https://matrix.to/#/{}", room.room_id))
+        .collect();
+    let rename = |name: &mut Option<String>, permalink: &str| {
+        if dm_permalinks.contains(permalink) {
+            *name = name.as_deref().and_then(|name| dm_names.apply(name));
+        }
+    };
+    let rename_reactions = |reactions: &mut Reactions| {
+        for entry in reactions.top_rooms.iter_mut().flatten() {
+            rename(&mut entry.name, &entry.permalink);
+        }
+    };
+
+    if let Some(ref mut rooms) = stats.rooms {
+        for entry in rooms.top.iter_mut().flatten() {
+            rename(&mut entry.name, &entry.permalink);
+        }
+        for entry in rooms.dm_balance.iter_mut().flatten() {
+            rename(&mut entry.name, &entry.permalink);
+        }
+    }
+    if let Some(ref mut reactions) = stats.reactions {
+        rename_reactions(reactions);
+    }
+    for breakdown in stats.per_room.iter_mut().flatten() {
+        rename(&mut breakdown.name, &breakdown.permalink);
+        if let Some(ref mut reactions) = breakdown.reactions {
+            rename_reactions(reactions);
+        }
+    }
+
+    // User IDs name people as much as display names do
+    let rename_person = |person: &mut PersonEntry| {
+        let name = person.display_name.take().or(person.user_id.take());
+        person.display_name = name.and_then(|name| dm_names.apply(&name));
+        person.user_id = None;
+    };
+    if let Some(ref mut people) = stats.people {
+        match dm_names {
+            DmNames::Hidden => people.dm_partners = None,
+            _ => {
+                for person in people.dm_partners.iter_mut().flatten() {
+                    rename_person(person);
+                }
+            }
+        }
+        if people.dm_partners.is_none() && people.group_senders.is_none() {
+            stats.people = None;
+        }
+    }
+    match dm_names {
+        DmNames::Hidden => stats.dms = None,
+        _ => {
+            for person in stats.dms.iter_mut().flat_map(|dms| &mut dms.top) {
+                rename_person(person);
+            }
+        }
+    }
+}
+
 // ============================================================================
 // Helper Functions for Building Sections
 // ============================================================================
 
+/// Fills the Fun section with the facts computed from the core aggregates.
+///
+/// Rates are per message sent, rounded to 2 decimals; facts that would be zero
+/// are left out. The current streak is only known when the window covers today.
+fn insert_fun_facts(
+    stats: &mut Stats,
+    fun: &FunMetrics,
+    active_dates: &HashMap<String, bool>,
+    window_scope: &WindowScope,
+) {
+    let messages_sent = stats.summary.messages_sent.get();
+    if messages_sent == 0 {
+        return;
+    }
+    let rate = |count: usize| ((count as f64 / messages_sent as f64) * 100.0).round() / 100.0;
+    let mut facts: Vec<(&str, serde_json::Value)> = Vec::new();
+
+    if fun.longest_message_chars > 0 {
+        facts.push(("longest_message_chars", fun.longest_message_chars.into()));
+    }
+    let activity = stats.activity.as_ref();
+    if let Some(weekday) = busiest_key(activity.and_then(|a| a.by_weekday.as_ref()), &WEEKDAYS) {
+        let index = WEEKDAYS.iter().position(|key| *key == weekday).unwrap_or(0);
+        facts.push(("favorite_weekday", WEEKDAY_NAMES[index].into()));
+    }
+    let hours: Vec<String> = (0..24).map(|hour| format!("{:02}", hour)).collect();
+    let hour_keys: Vec<&str> = hours.iter().map(String::as_str).collect();
+    if let Some(hour) = busiest_key(activity.and_then(|a| a.by_hour.as_ref()), &hour_keys) {
+        facts.push(("peak_hour", hour.into()));
+    }
+    let streak = longest_streak(active_dates.keys());
+    if streak > 0 {
+        facts.push(("longest_streak_days", streak.into()));
+    }
+    if window_scope.covers_now() {
+        let today = chrono::Local::now().date_naive();
+        let current = current_streak(active_dates.keys(), today);
+        if current > 0 {
+            facts.push(("current_streak_days", current.into()));
+        }
+    }
+    if let Some((from, to)) = longest_silence(active_dates.keys()) {
+        facts.push((
+            "longest_silence",
+            serde_json::json!({
+                "days": (to - from).num_days() + 1,
+                "from": from.format("%Y-%m-%d").to_string(),
+                "to": to.format("%Y-%m-%d").to_string(),
+            }),
+        ));
+    }
+    facts.push(("reactions_per_message", rate(fun.reactions).into()));
+    if fun.edits > 0 {
+        facts.push(("edits_per_message", rate(fun.edits).into()));
+    }
+    if fun.emotes > 0 {
+        facts.push(("emote_messages", fun.emotes.into()));
+    }
+    if fun.lurking_rooms > 0 {
+        facts.push(("lurking_rooms", fun.lurking_rooms.into()));
+    }
+    if fun.encrypted_messages > 0 {
+        facts.push((
+            "sent_encrypted_messages_ratio",
+            rate(fun.encrypted_messages).into(),
+        ));
+    }
+
+    for (key, value) in facts {
+        super::metrics::insert_fun_field(stats, key, value);
+    }
+}
+
+/// Keys of `by_weekday`, Monday first.
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Full names of `WEEKDAYS`, as shown in `favorite_weekday`.
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Key with the highest count, the earliest of `keys` on ties.
+fn busiest_key(counts: Option<&HashMap<String, i32>>, keys: &[&str]) -> Option<String> {
+    let counts = counts?;
+    let mut best: Option<(&str, i32)> = None;
+    for &key in keys {
+        let count = counts.get(key).copied().unwrap_or(0);
+        if count > 0 && best.is_none_or(|(_, top)| count > top) {
+            best = Some((key, count));
+        }
+    }
+    best.map(|(key, _)| key.to_string())
+}
+
+/// Most consecutive days among `dates` (YYYY-MM-DD).
+fn longest_streak<'a>(dates: impl Iterator<Item = &'a String>) -> i64 {
+    let mut days: Vec<chrono::NaiveDate> = dates
+        .filter_map(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .collect();
+    days.sort();
+
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous: Option<chrono::NaiveDate> = None;
+    for day in days {
+        current = match previous {
+            Some(prev) if (day - prev).num_days() == 1 => current + 1,
+            _ => 1,
+        };
+        longest = longest.max(current);
+        previous = Some(day);
+    }
+    longest
+}
+
+/// Consecutive days among `dates` (YYYY-MM-DD) up to `today`. A streak without
+/// a message yet today still runs when it reached yesterday.
+fn current_streak<'a>(dates: impl Iterator<Item = &'a String>, today: chrono::NaiveDate) -> i64 {
+    let days: HashSet<chrono::NaiveDate> = dates
+        .filter_map(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .collect();
+    let Some(mut day) = [today, today - chrono::Duration::days(1)]
+        .into_iter()
+        .find(|day| days.contains(day))
+    else {
+        return 0;
+    };
+    let mut current = 0;
+    while days.contains(&day) {
+        current += 1;
+        day -= chrono::Duration::days(1);
+    }
+    current
+}
+
+/// First and last day of the longest run of days without a message between two
+/// days of `dates` (YYYY-MM-DD), the earliest on ties.
+fn longest_silence<'a>(
+    dates: impl Iterator<Item = &'a String>,
+) -> Option<(chrono::NaiveDate, chrono::NaiveDate)> {
+    let mut days: Vec<chrono::NaiveDate> = dates
+        .filter_map(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .collect();
+    days.sort();
+
+    let mut longest: Option<(chrono::NaiveDate, chrono::NaiveDate)> = None;
+    for pair in days.windows(2) {
+        let (from, to) = (pair[0].succ_opt()?, pair[1].pred_opt()?);
+        if from <= to && longest.is_none_or(|(start, end)| to - from > end - start) {
+            longest = Some((from, to));
+        }
+    }
+    longest
+}
+
 /// Builds the Activity section of stats from temporal aggregates (private).
 fn build_activity_section(
     temporal: TemporalAggregates,
@@ -390,6 +801,16 @@ fn build_activity_section(
         } else {
             None
         },
+        by_weekday_hour: if !temporal.by_weekday_hour.is_empty() {
+            Some(temporal.by_weekday_hour)
+        } else {
+            None
+        },
+        by_room_type_hour: if !temporal.by_room_type_hour.is_empty() {
+            Some(temporal.by_room_type_hour)
+        } else {
+            None
+        },
     }))
 }
 
@@ -397,62 +818,23 @@ fn build_activity_section(
 fn build_rooms_section(
     top_rooms: Vec<RoomEntry>,
     room_types: &RoomTypeMetrics,
+    by_server: Vec<ServerEntry>,
     active_rooms_count: i32,
 ) -> Result<Option<Rooms>> {
     if active_rooms_count == 0 {
         return Ok(None);
     }
 
-    Ok(Some(Rooms {
-        total: active_rooms_count,
-        top: if !top_rooms.is_empty() {
-            Some(top_rooms)
-        } else {
-            None
-        },
-        messages_by_room_type: Some(MessagesByRoomType {
-            dm: if room_types.dm_messages > 0 {
-                Some(room_types.dm_messages)
-            } else {
-                None
-            },
-            public: if room_types.public_messages > 0 {
-                Some(room_types.public_messages)
-            } else {
-                None
-            },
-            private: if room_types.private_messages > 0 {
-                Some(room_types.private_messages)
-            } else {
-                None
-            },
-        }),
-    }))
-}
-
-/// Builds the Reactions section of stats (private).
-fn build_reactions_section(
-    top_emojis: Vec<EmojiEntry>,
-    top_messages: Vec<MessageReactionEntry>,
-    total_reactions: i32,
-) -> Result<Option<Reactions>> {
-    if total_reactions == 0 {
-        return Ok(None);
-    }
-
-    Ok(Some(Reactions {
-        total: Some(total_reactions),
-        top_emojis: if !top_emojis.is_empty() {
-            Some(top_emojis)
-        } else {
-            None
-        },
-        top_messages: if !top_messages.is_empty() {
-            Some(top_messages)
-        } else {
-            None
-        },
-    }))
+    // Messages by room type and kind and DM balances are filled by metric collectors
+    let rooms = Rooms::new(active_rooms_count)?
+        .with_top(top_rooms)
+        .with_by_server(by_server)
+        .with_messages_by_room_type(MessagesByRoomType::new(
+            room_types.dm_messages,
+            room_types.public_messages,
+            room_types.private_messages,
+        )?);
+    Ok(Some(rooms))
 }
 
 /// Builds the CreatedRooms section of stats (private).
@@ -461,92 +843,95 @@ fn build_created_rooms_section(created_rooms: &CreatedRoomMetrics) -> Result<Opt
         return Ok(None);
     }
 
-    Ok(Some(CreatedRooms {
-        total: created_rooms.total,
-        dm_rooms: if created_rooms.dm > 0 {
-            Some(created_rooms.dm)
-        } else {
-            None
-        },
-        public_rooms: if created_rooms.public > 0 {
-            Some(created_rooms.public)
-        } else {
-            None
-        },
-        private_rooms: if created_rooms.private > 0 {
-            Some(created_rooms.private)
-        } else {
-            None
-        },
-    }))
+    Ok(Some(CreatedRooms::new(
+        created_rooms.total,
+        created_rooms.dm,
+        created_rooms.public,
+        created_rooms.private,
+    )?))
 }
 
 // ============================================================================
 // Helper Functions for Ranking
 // ============================================================================
 
-/// Ranks top rooms by message count (private).
+/// Ranks top rooms by message count, with their trend over `trend_span` (private).
 fn rank_top_rooms(
-    room_message_counts: &mut [(String, Option<String>, RoomType, i32)],
+    room_rankings: &mut [RoomRanking],
     messages_sent: i32,
+    top: usize,
+    trend_span: Option<(NaiveDate, NaiveDate)>,
 ) -> Result<Vec<RoomEntry>> {
-    room_message_counts.sort_by(|a, b| b.3.cmp(&a.3));
+    room_rankings.sort_by_key(|room| std::cmp::Reverse(room.messages));
 
-    Ok(room_message_counts
+    room_rankings
         .iter()
-        .take(5)
-        .map(|(room_id, room_name, _room_type, count)| {
-            let percentage = if messages_sent > 0 {
-                Some((*count as f64 / messages_sent as f64) * 100.0)
+        .take(top)
+        .map(|room| {
+            let entry = RoomEntry::new(
+                format!("https://matrix.to/#/{}", room.room_id),
+                room.messages,
+            )?
+            .with_name(room.room_name.clone())
+            .with_joined_at(room.joined_at.clone())
+            .with_trend(
+                trend_span
+                    .map(|span| room_trend(room.by_day, span))
+                    .transpose()?,
+            );
+            if messages_sent > 0 {
+                entry.with_percentage((room.messages as f64 / messages_sent as f64) * 100.0)
             } else {
-                None
-            };
-
-            RoomEntry {
-                name: room_name.clone(),
-                messages: *count,
-                percentage,
-                permalink: format!("https://matrix.to/#/{}", room_id),
+                Ok(entry)
             }
         })
-        .collect())
+        .collect()
 }
 
-/// Ranks top emojis by reaction count (private).
-fn rank_top_emojis(emojis: HashMap<String, i32>) -> Result<Vec<EmojiEntry>> {
-    let mut emoji_vec: Vec<_> = emojis.into_iter().collect();
-    emoji_vec.sort_by(|a, b| b.1.cmp(&a.1));
-
-    Ok(emoji_vec
-        .into_iter()
-        .take(5)
-        .map(|(emoji, count)| EmojiEntry { emoji, count })
-        .collect())
+/// Days over which room trends are measured: the crawled part of the window,
+/// when it spans more than a day (private).
+fn trend_span(
+    window_scope: &WindowScope,
+    coverage_from: &str,
+    coverage_to: &str,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let parse = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+    let from = parse(coverage_from)?.max(window_scope.from);
+    let to = parse(coverage_to)?.min(window_scope.to);
+    (to > from).then_some((from, to))
 }
 
-/// Ranks top messages by reaction count (private).
-fn rank_top_messages(messages: HashMap<String, i32>) -> Result<Vec<MessageReactionEntry>> {
-    let mut message_vec: Vec<_> = messages.into_iter().collect();
-    message_vec.sort_by(|a, b| b.1.cmp(&a.1));
-
-    Ok(message_vec
-        .into_iter()
-        .take(5)
-        .map(|(event_id, count)| MessageReactionEntry {
-            permalink: format!("https://matrix.to/#/{}", event_id),
-            reaction_count: count,
-        })
-        .collect())
+/// Messages of a room in the first and the second half of `span`. With an odd
+/// number of days, the middle day counts in neither (private).
+fn room_trend(
+    by_day: &HashMap<String, i32>,
+    (from, to): (NaiveDate, NaiveDate),
+) -> Result<RoomTrend> {
+    let days = (to - from).num_days() + 1;
+    let half = days / 2;
+    let (mut first_half, mut second_half) = (0, 0);
+    for (day, count) in by_day {
+        let Ok(date) = NaiveDate::parse_from_str(day, "%Y-%m-%d") else {
+            continue;
+        };
+        let offset = (date - from).num_days();
+        if (0..half).contains(&offset) {
+            first_half += count;
+        } else if (days - half..days).contains(&offset) {
+            second_half += count;
+        }
+    }
+    RoomTrend::new(first_half, second_half)
 }
 
 /// Computes coverage bounds from timestamps and window scope (private).
 fn compute_coverage_bounds(
     coverage: &CoverageBounds,
     window_scope: &WindowScope,
-) -> Result<(String, String, Option<i32>)> {
+) -> Result<(String, String, i32)> {
     let (coverage_from, coverage_to) =
         if let (Some(oldest), Some(newest)) = (coverage.oldest_ts, coverage.newest_ts) {
-            use chrono::{Local, TimeZone};
+            use chrono::Local;
             let from_dt = Local.timestamp_millis_opt(oldest).single();
             let to_dt = Local.timestamp_millis_opt(newest).single();
 
@@ -565,13 +950,11 @@ fn compute_coverage_bounds(
             )
         };
 
-    let days_active = if !coverage.active_dates.is_empty() {
-        Some(coverage.active_dates.len() as i32)
-    } else {
-        None
-    };
-
-    Ok((coverage_from, coverage_to, days_active))
+    Ok((
+        coverage_from,
+        coverage_to,
+        coverage.active_dates.len() as i32,
+    ))
 }
 
 /// Computes peak activity periods from temporal buckets.
@@ -580,7 +963,7 @@ fn compute_peaks(
     by_month: &HashMap<String, i32>,
     by_week: &HashMap<String, i32>,
     by_day: &HashMap<String, i32>,
-    by_hour: &HashMap<String, i32>,
+    by_day_hour: &HashMap<String, HashMap<String, i32>>,
 ) -> Result<Option<Peaks>> {
     let peak_year = by_year
         .iter()
@@ -614,13 +997,15 @@ fn compute_peaks(
             messages,
         });
 
-    let peak_hour = by_hour
+    // The busiest hour on a given day, the earliest on ties
+    let peak_hour = by_day_hour
         .iter()
-        .max_by_key(|(_, &count)| count)
-        .map(|(hour, &messages)| PeakHour {
+        .flat_map(|(day, hours)| hours.iter().map(move |(hour, &count)| (day, hour, count)))
+        .max_by(|a, b| a.2.cmp(&b.2).then_with(|| (b.0, b.1).cmp(&(a.0, a.1))))
+        .map(|(day, hour, messages)| PeakHour {
             hour: hour.clone(),
             messages,
-            date: None,
+            date: Some(day.clone()),
         });
 
     if peak_year.is_none()
@@ -644,7 +1029,10 @@ fn compute_peaks(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::crawl::metrics::builtin_collectors;
+    use crate::commands::crawl::CrawlOptions;
     use crate::stats::ScopeKind;
+    use std::collections::HashSet;
 
     fn create_test_window_scope() -> WindowScope {
         WindowScope {
@@ -668,8 +1056,8 @@ mod tests {
         by_week.insert("2025-W02".to_string(), 7);
 
         let mut by_weekday = HashMap::new();
-        by_weekday.insert("1".to_string(), 4);
-        by_weekday.insert("2".to_string(), 6);
+        by_weekday.insert("Mon".to_string(), 4);
+        by_weekday.insert("Tue".to_string(), 6);
 
         let mut by_day = HashMap::new();
         by_day.insert("2025-01-15".to_string(), 5);
@@ -679,29 +1067,88 @@ mod tests {
         by_hour.insert("09".to_string(), 3);
         by_hour.insert("14".to_string(), 7);
 
+        let mut by_weekday_hour: HashMap<String, HashMap<String, i32>> = HashMap::new();
+        by_weekday_hour
+            .entry("Mon".to_string())
+            .or_default()
+            .insert("09".to_string(), 3);
+        by_weekday_hour
+            .entry("Tue".to_string())
+            .or_default()
+            .insert("14".to_string(), 7);
+
+        let by_day_hour: HashMap<String, HashMap<String, i32>> = HashMap::from([
+            (
+                "2025-01-15".to_string(),
+                HashMap::from([("09".to_string(), 3), ("14".to_string(), 2)]),
+            ),
+            (
+                "2025-02-20".to_string(),
+                HashMap::from([("14".to_string(), 5)]),
+            ),
+        ]);
+
         let mut active_dates = HashMap::new();
         active_dates.insert("2025-01-15".to_string(), true);
         active_dates.insert("2025-02-20".to_string(), true);
 
         DetailedPaginationStats {
             fully_crawled: true,
+            history_limited: false,
+            saw_room_create: true,
+            joined_at: None,
             oldest_event_id: Some("$oldest".to_string()),
             oldest_ts: Some(1735689600000), // 2024-12-31 23:00:00 UTC (2025-01-01 in some timezones)
             newest_event_id: Some("$newest".to_string()),
             newest_ts: Some(1767225599999), // 2025-12-31 23:59:59.999 UTC
             total_events: 20,
             user_events: 10,
+            untimed_events: 0,
+            others_messages: 0,
+            messages_by_sender: HashMap::new(),
+            sender_names: HashMap::new(),
+            dm_partner: None,
+            dm_partner_name: None,
+            canonical_alias: None,
             by_year,
             by_month,
             by_week,
             by_weekday,
             by_day,
             by_hour,
+            by_weekday_hour,
+            by_day_hour,
             user_message_ids: HashMap::new(),
             reactions_by_emoji: HashMap::new(),
             reactions_by_message: HashMap::new(),
+            reactions_by_month: HashMap::new(),
+            reactions_given_by_emoji: HashMap::new(),
+            reaction_emojis_by_message: HashMap::new(),
+            message_previews: HashMap::new(),
+            emote_names: HashMap::new(),
             room_created_by_user: false,
             active_dates,
+            first_replies: HashMap::new(),
+            replied_to_senders: HashMap::new(),
+            first_messages_by_day: HashMap::new(),
+            typed_words: 0,
+            messages_by_kind: HashMap::new(),
+            user_top_level_messages: 0,
+            user_thread_messages: 0,
+            user_threads: HashSet::new(),
+            user_replies: 0,
+            messages_by_length: HashMap::new(),
+            longest_message: None,
+            image_uses: HashMap::new(),
+            typed_emojis: HashMap::new(),
+            longest_message_chars: 0,
+            user_edits: 0,
+            user_reactions: 0,
+            user_encrypted_messages: 0,
+            user_messages_by_msgtype: HashMap::new(),
+            messages_by_timezone: HashMap::new(),
+            #[cfg(feature = "metric-plugins")]
+            custom_metrics: HashMap::new(),
         }
     }
 
@@ -718,20 +1165,22 @@ mod tests {
         let window_scope = create_test_window_scope();
 
         let stats = build_stats(
-            vec![room_input],
+            &[room_input],
             "@user:example.org",
             Some("Test User".to_string()),
             None,
             &window_scope,
             5,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
         )
         .unwrap();
 
-        assert_eq!(stats.summary.messages_sent, 10);
-        assert_eq!(stats.summary.active_rooms, 1);
-        assert_eq!(stats.summary.private_rooms, Some(1));
-        assert_eq!(stats.account.rooms_total, 5);
-        assert_eq!(stats.coverage.days_active, Some(2));
+        assert_eq!(stats.summary.messages_sent.get(), 10);
+        assert_eq!(stats.summary.active_rooms.get(), 1);
+        assert_eq!(stats.summary.private_rooms.map(Count::get), Some(1));
+        assert_eq!(stats.account.rooms_total.get(), 5);
+        assert_eq!(stats.coverage.days_active.map(Count::get), Some(2));
 
         // Check activity data exists
         assert!(stats.activity.is_some());
@@ -742,15 +1191,40 @@ mod tests {
         // Check rooms data
         assert!(stats.rooms.is_some());
         let rooms = stats.rooms.unwrap();
-        assert_eq!(rooms.total, 1);
+        assert_eq!(rooms.total.get(), 1);
+        assert_eq!(rooms.by_server.as_ref().unwrap()[0].server, "example.org");
         assert!(rooms.top.is_some());
         assert_eq!(rooms.top.unwrap().len(), 1);
     }
 
+    #[test]
+    fn test_server_metrics() {
+        let mut servers = ServerMetrics::new();
+        servers.record("!a:matrix.org", None, 40);
+        servers.record("!b:mozilla.org", Some("#rust:example.org"), 25);
+        servers.record("!c:matrix.org", None, 20);
+        servers.record("!d:example.org:8448", None, 15);
+        // Room version 12 IDs have no server part: the alias names it, if any
+        servers.record("!e", Some("#lounge:matrix.org"), 10);
+        servers.record("!f", None, 5);
+
+        let ranked = servers.rank(200, 2).unwrap();
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].server, "matrix.org");
+        assert_eq!(ranked[0].messages.get(), 70);
+        assert_eq!(ranked[0].percentage.get(), 35.0);
+        assert_eq!(ranked[1].server, "mozilla.org");
+        let ranked = servers.rank(200, 5).unwrap();
+        assert_eq!(ranked[2].server, "example.org:8448");
+        assert_eq!(ranked[3].server, UNKNOWN_SERVER);
+        assert_eq!(ranked[3].messages.get(), 5);
+    }
+
     #[test]
     fn test_build_stats_multiple_rooms() {
         let mut room1_stats = create_test_room_stats();
         room1_stats.user_events = 15;
+        room1_stats.others_messages = 10;
 
         let mut room2_stats = create_test_room_stats();
         room2_stats.user_events = 25;
@@ -772,25 +1246,45 @@ mod tests {
         let window_scope = create_test_window_scope();
 
         let stats = build_stats(
-            vec![room1, room2],
+            &[room1, room2],
             "@user:example.org",
             None,
             None,
             &window_scope,
             10,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
         )
         .unwrap();
 
-        assert_eq!(stats.summary.messages_sent, 40);
-        assert_eq!(stats.summary.active_rooms, 2);
-        assert_eq!(stats.summary.dm_rooms, Some(1));
-        assert_eq!(stats.summary.public_rooms, Some(1));
+        assert_eq!(stats.summary.messages_sent.get(), 40);
+        assert_eq!(stats.summary.active_rooms.get(), 2);
+        assert_eq!(stats.summary.dm_rooms.map(Count::get), Some(1));
+        assert_eq!(stats.summary.public_rooms.map(Count::get), Some(1));
 
         // Check room type distribution
         let rooms = stats.rooms.unwrap();
         let room_type_dist = rooms.messages_by_room_type.unwrap();
-        assert_eq!(room_type_dist.dm, Some(15));
-        assert_eq!(room_type_dist.public, Some(25));
+        assert_eq!(room_type_dist.dm.map(Count::get), Some(15));
+        assert_eq!(room_type_dist.public.map(Count::get), Some(25));
+
+        // Only DMs get a conversation balance
+        let dm_balance = rooms.dm_balance.unwrap();
+        assert_eq!(dm_balance.len(), 1);
+        assert_eq!(dm_balance[0].name.as_deref(), Some("Room 1"));
+        assert_eq!((dm_balance[0].sent, dm_balance[0].received), (15, 10));
+
+        // Weekday × hour cells are summed across rooms
+        let activity = stats.activity.unwrap();
+        let by_weekday_hour = activity.by_weekday_hour.unwrap();
+        assert_eq!(by_weekday_hour["Mon"]["09"], 6);
+        assert_eq!(by_weekday_hour["Tue"]["14"], 14);
+
+        // Hour profiles are kept apart per room type
+        let by_room_type_hour = activity.by_room_type_hour.unwrap();
+        assert_eq!(by_room_type_hour["dm"]["14"], 7);
+        assert_eq!(by_room_type_hour["public"]["09"], 3);
+        assert!(!by_room_type_hour.contains_key("private"));
     }
 
     #[test]
@@ -808,19 +1302,21 @@ mod tests {
         let window_scope = create_test_window_scope();
 
         let stats = build_stats(
-            vec![room_input],
+            &[room_input],
             "@user:example.org",
             None,
             None,
             &window_scope,
             1,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
         )
         .unwrap();
 
         assert!(stats.created_rooms.is_some());
         let created = stats.created_rooms.unwrap();
-        assert_eq!(created.total, 1);
-        assert_eq!(created.dm_rooms, Some(1));
+        assert_eq!(created.total.get(), 1);
+        assert_eq!(created.dm_rooms.map(Count::get), Some(1));
         assert_eq!(created.public_rooms, None);
         assert_eq!(created.private_rooms, None);
     }
@@ -840,6 +1336,13 @@ mod tests {
 
         room_stats.reactions_by_emoji = reactions_by_emoji;
         room_stats.reactions_by_message = reactions_by_message;
+        room_stats.reactions_by_month =
+            HashMap::from([("03".to_string(), 18), ("04".to_string(), 5)]);
+        room_stats.reactions_given_by_emoji = HashMap::from([
+            ("👍".to_string(), 4),
+            ("👍🏽".to_string(), 2),
+            ("🙏".to_string(), 1),
+        ]);
 
         let room_input = RoomStatsInput {
             room_id: "!room1:example.org".to_string(),
@@ -851,12 +1354,14 @@ mod tests {
         let window_scope = create_test_window_scope();
 
         let stats = build_stats(
-            vec![room_input],
+            &[room_input],
             "@user:example.org",
             None,
             None,
             &window_scope,
             1,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
         )
         .unwrap();
 
@@ -870,6 +1375,464 @@ mod tests {
         assert_eq!(top_emojis.len(), 3);
         assert_eq!(top_emojis[0].emoji, "😂");
         assert_eq!(top_emojis[0].count, 10);
+
+        let by_month = reactions.by_month.unwrap();
+        assert_eq!(by_month.get("03"), Some(&18));
+        assert_eq!(by_month.len(), 2);
+
+        // Reactions sent, the forms of an emoji folded as for received ones
+        let given = reactions.given.unwrap();
+        assert_eq!(given.total, 7);
+        assert_eq!(given.top_emojis[0].emoji, "👍");
+        assert_eq!(given.top_emojis[0].count, 6);
+        assert_eq!(given.top_emojis[1].emoji, "🙏");
+    }
+
+    #[test]
+    fn test_build_stats_dm_partner_from_room_state() {
+        let mut room_stats = create_test_room_stats();
+        // A bot posts more than the other party of the DM
+        room_stats.messages_by_sender = HashMap::from([
+            ("@carol:example.org".to_string(), 2),
+            ("@bot:example.org".to_string(), 5),
+        ]);
+        room_stats.dm_partner = Some("@carol:example.org".to_string());
+        room_stats.dm_partner_name = Some("Carol".to_string());
+        let sent = room_stats.user_events as i32;
+        let room_input = RoomStatsInput {
+            room_id: "!dm:example.org".to_string(),
+            room_name: None,
+            room_type: RoomType::Dm,
+            stats: room_stats,
+        };
+
+        let stats = build_stats(
+            &[room_input],
+            "@user:example.org",
+            None,
+            None,
+            &create_test_window_scope(),
+            1,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
+
+        let partners = stats.people.unwrap().dm_partners.unwrap();
+        assert_eq!(partners.len(), 1);
+        assert_eq!(partners[0].user_id.as_deref(), Some("@carol:example.org"));
+        assert_eq!(partners[0].display_name.as_deref(), Some("Carol"));
+        assert_eq!(partners[0].messages, sent + 2);
+        // The DM correspondents count the user's messages only
+        let dms = stats.dms.unwrap();
+        assert_eq!(dms.total, 1);
+        assert_eq!(dms.top[0].user_id.as_deref(), Some("@carol:example.org"));
+        assert_eq!(dms.top[0].messages, sent);
+    }
+
+    #[test]
+    fn test_build_stats_reaction_variety() {
+        let emojis = |list: &[&str]| list.iter().map(|e| e.to_string()).collect::<HashSet<_>>();
+        let mut room_stats = create_test_room_stats();
+        room_stats.reactions_by_emoji = HashMap::from([
+            ("👍".to_string(), 5),
+            ("👍🏽".to_string(), 1),
+            ("👍🏿".to_string(), 1),
+            ("🎉".to_string(), 3),
+            ("🚀".to_string(), 1),
+            ("😂".to_string(), 1),
+        ]);
+        room_stats.reactions_by_message = HashMap::from([
+            ("$many".to_string(), 6),
+            ("$varied".to_string(), 4),
+            ("$few".to_string(), 2),
+        ]);
+        room_stats.reaction_emojis_by_message = HashMap::from([
+            // Skin tones of the same emoji only count once
+            ("$many".to_string(), emojis(&["👍", "👍🏽", "👍🏿", "🎉"])),
+            ("$varied".to_string(), emojis(&["👍", "🎉", "🚀", "😂"])),
+            ("$few".to_string(), emojis(&["👍", "🎉"])),
+        ]);
+        room_stats
+            .message_previews
+            .insert("$varied".to_string(), "Shipping 1.0 today".to_string());
+        let room_input = RoomStatsInput {
+            room_id: "!room1:example.org".to_string(),
+            room_name: None,
+            room_type: RoomType::Private,
+            stats: room_stats,
+        };
+
+        let stats = build_stats(
+            &[room_input],
+            "@user:example.org",
+            None,
+            None,
+            &create_test_window_scope(),
+            1,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
+
+        let fun = stats.fun.unwrap();
+        assert_eq!(
+            fun.fields["reaction_variety"],
+            serde_json::json!({
+                "emojis": 4,
+                "reactions": 4,
+                "permalink": "https://matrix.to/#/$varied",
+                "preview": "Shipping 1.0 today",
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_stats_with_given_reactions_only() {
+        let mut room_stats = create_test_room_stats();
+        room_stats.reactions_given_by_emoji = HashMap::from([("🎉".to_string(), 3)]);
+        let room_input = RoomStatsInput {
+            room_id: "!room1:example.org".to_string(),
+            room_name: None,
+            room_type: RoomType::Private,
+            stats: room_stats,
+        };
+
+        let stats = build_stats(
+            &[room_input],
+            "@user:example.org",
+            None,
+            None,
+            &create_test_window_scope(),
+            1,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
+
+        // The section shows the reactions sent even when none came back
+        let reactions = stats.reactions.unwrap();
+        assert_eq!(reactions.total, None);
+        assert!(reactions.top_emojis.is_none());
+        assert_eq!(reactions.given.unwrap().total, 3);
+    }
+
+    #[test]
+    fn test_build_stats_top_reacted_rooms() {
+        let room = |id: &str, name: Option<&str>, reactions: i32| {
+            let mut room_stats = create_test_room_stats();
+            room_stats.reactions_by_emoji = HashMap::from([("❤️".to_string(), reactions)]);
+            RoomStatsInput {
+                room_id: id.to_string(),
+                room_name: name.map(str::to_string),
+                room_type: RoomType::Private,
+                stats: room_stats,
+            }
+        };
+
+        let window_scope = create_test_window_scope();
+        let stats = build_stats(
+            &[
+                room("!quiet:example.org", Some("Quiet"), 0),
+                room("!busy:example.org", Some("Busy"), 3),
+                room("!loved:example.org", None, 12),
+            ],
+            "@user:example.org",
+            None,
+            None,
+            &window_scope,
+            3,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
+
+        // Rooms without reactions are left out of the ranking
+        let top_rooms = stats.reactions.unwrap().top_rooms.unwrap();
+        assert_eq!(top_rooms.len(), 2);
+        assert_eq!(top_rooms[0].name, None);
+        assert_eq!(top_rooms[0].reaction_count, 12);
+        assert_eq!(
+            top_rooms[0].permalink,
+            "https://matrix.to/#/!loved:example.org"
+        );
+        assert_eq!(top_rooms[1].name.as_deref(), Some("Busy"));
+    }
+
+    #[test]
+    fn test_build_room_breakdowns() {
+        let room = |id: &str, user_events: usize, reactions: i32| {
+            let mut room_stats = create_test_room_stats();
+            room_stats.user_events = user_events;
+            if reactions > 0 {
+                room_stats.reactions_by_emoji = HashMap::from([("🎉".to_string(), reactions)]);
+            }
+            RoomStatsInput {
+                room_id: id.to_string(),
+                room_name: None,
+                room_type: RoomType::Public,
+                stats: room_stats,
+            }
+        };
+        let rooms = [
+            room("!small:example.org", 4, 0),
+            room("!silent:example.org", 0, 0),
+            room("!big:example.org", 30, 7),
+        ];
+
+        let breakdowns = build_room_breakdowns(
+            &rooms,
+            "@user:example.org",
+            &create_test_window_scope(),
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
+
+        // Ranked by messages, silent rooms left out, each isolated to its room
+        assert_eq!(breakdowns.len(), 2);
+        assert_eq!(
+            breakdowns[0].permalink,
+            "https://matrix.to/#/!big:example.org"
+        );
+        assert_eq!(breakdowns[0].summary.messages_sent.get(), 30);
+        assert_eq!(breakdowns[0].summary.active_rooms.get(), 1);
+        assert_eq!(breakdowns[0].reactions.as_ref().unwrap().total, Some(7));
+        assert_eq!(breakdowns[1].summary.messages_sent.get(), 4);
+        assert!(breakdowns[1].reactions.is_none());
+    }
+
+    #[test]
+    fn test_build_stats_since_joined_coverage() {
+        // Joined mid-window: history before the join is hidden
+        let mut joined_room = create_test_room_stats();
+        joined_room.history_limited = true;
+        joined_room.joined_at = Some(1_741_435_200_000); // 2025-03-08 12:00 UTC
+
+        // Joined before the window: the window is fully covered
+        let mut old_room = create_test_room_stats();
+        old_room.history_limited = true;
+        old_room.joined_at = Some(1_600_000_000_000); // 2020-09-13
+
+        let rooms = vec![
+            RoomStatsInput {
+                room_id: "!joined:example.org".to_string(),
+                room_name: Some("Joined".to_string()),
+                room_type: RoomType::Public,
+                stats: joined_room,
+            },
+            RoomStatsInput {
+                room_id: "!old:example.org".to_string(),
+                room_name: Some("Old".to_string()),
+                room_type: RoomType::Public,
+                stats: old_room,
+            },
+        ];
+
+        let window_scope = create_test_window_scope();
+        let stats = build_stats(
+            &rooms,
+            "@user:example.org",
+            None,
+            None,
+            &window_scope,
+            2,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
+
+        assert_eq!(stats.coverage.since_joined_rooms.map(Count::get), Some(1));
+        let top = stats.rooms.unwrap().top.unwrap();
+        let joined = top.iter().find(|r| r.name.as_deref() == Some("Joined"));
+        let old = top.iter().find(|r| r.name.as_deref() == Some("Old"));
+        assert_eq!(joined.unwrap().joined_at.as_deref(), Some("2025-03-08"));
+        assert_eq!(old.unwrap().joined_at, None);
+    }
+
+    #[test]
+    fn test_build_stats_conversation_starts() {
+        let mut room_stats = create_test_room_stats();
+        room_stats.first_messages_by_day = HashMap::from([
+            ("2025-01-06".to_string(), (1000, true)),
+            ("2025-01-07".to_string(), (2000, false)),
+            ("2025-01-08".to_string(), (3000, true)),
+        ]);
+        let mut other_stats = create_test_room_stats();
+        other_stats.first_messages_by_day =
+            HashMap::from([("2025-01-06".to_string(), (1500, true))]);
+        let room = |room_id: &str, stats| RoomStatsInput {
+            room_id: room_id.to_string(),
+            room_name: None,
+            room_type: RoomType::Public,
+            stats,
+        };
+
+        let stats = build_stats(
+            &[
+                room("!room1:example.org", room_stats),
+                room("!room2:example.org", other_stats),
+            ],
+            "@user:example.org",
+            None,
+            None,
+            &create_test_window_scope(),
+            1,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
+
+        // Days count per room: the same day in two rooms is two starts
+        let fun = stats.fun.unwrap();
+        assert_eq!(fun.fields["conversation_starts"], serde_json::json!(3));
+    }
+
+    #[test]
+    fn test_build_stats_first_responder() {
+        let mut room_stats = create_test_room_stats();
+
+        // User replied first to two messages from others, someone else beat them to one,
+        // and replies to the user's own messages or to unseen ones don't count.
+        room_stats
+            .first_replies
+            .insert("$q1".to_string(), (1000, true));
+        room_stats
+            .first_replies
+            .insert("$q2".to_string(), (2000, true));
+        room_stats
+            .first_replies
+            .insert("$q3".to_string(), (3000, false));
+        room_stats
+            .first_replies
+            .insert("$own".to_string(), (4000, true));
+        room_stats
+            .first_replies
+            .insert("$before_window".to_string(), (5000, true));
+        for (event_id, by_user) in [
+            ("$q1", false),
+            ("$q2", false),
+            ("$q3", false),
+            ("$own", true),
+        ] {
+            room_stats
+                .replied_to_senders
+                .insert(event_id.to_string(), by_user);
+        }
+
+        let room_input = RoomStatsInput {
+            room_id: "!room1:example.org".to_string(),
+            room_name: Some("Help Room".to_string()),
+            room_type: RoomType::Public,
+            stats: room_stats,
+        };
+
+        let window_scope = create_test_window_scope();
+
+        let stats = build_stats(
+            &[room_input],
+            "@user:example.org",
+            None,
+            None,
+            &window_scope,
+            1,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
+
+        let fun = stats.fun.expect("fun section should be present");
+        assert_eq!(
+            fun.fields.get("first_responder_replies"),
+            Some(&serde_json::Value::from(2))
+        );
+    }
+
+    #[test]
+    fn test_build_stats_typing_time() {
+        let mut room_stats = create_test_room_stats();
+        room_stats.typed_words = 1_900;
+
+        let room_input = RoomStatsInput {
+            room_id: "!room1:example.org".to_string(),
+            room_name: Some("Chatty Room".to_string()),
+            room_type: RoomType::Public,
+            stats: room_stats,
+        };
+
+        let window_scope = create_test_window_scope();
+        let options = CrawlOptions {
+            typing_wpm: 20,
+            ..CrawlOptions::default()
+        };
+
+        let stats = build_stats(
+            &[room_input],
+            "@user:example.org",
+            None,
+            None,
+            &window_scope,
+            1,
+            DEFAULT_TOP,
+            &builtin_collectors(&options),
+        )
+        .unwrap();
+
+        // 1900 words at 20 WPM = 95 minutes
+        let fun = stats.fun.expect("fun section should be present");
+        assert_eq!(
+            fun.fields.get("typing_minutes"),
+            Some(&serde_json::Value::from(95))
+        );
+    }
+
+    #[test]
+    fn test_build_stats_message_twins() {
+        let room_input = |room_id: &str, name: &str, weekday: &str, hour: &str| {
+            let mut room_stats = create_test_room_stats();
+            room_stats.by_weekday_hour.clear();
+            room_stats
+                .by_weekday_hour
+                .entry(weekday.to_string())
+                .or_default()
+                .insert(hour.to_string(), 10);
+            RoomStatsInput {
+                room_id: room_id.to_string(),
+                room_name: Some(name.to_string()),
+                room_type: RoomType::Public,
+                stats: room_stats,
+            }
+        };
+
+        // Two rooms active on Monday mornings, one on Friday evenings
+        let rooms = vec![
+            room_input("!a:example.org", "Standup", "Mon", "09"),
+            room_input("!b:example.org", "Party", "Fri", "21"),
+            room_input("!c:example.org", "Team", "Mon", "09"),
+        ];
+
+        let window_scope = create_test_window_scope();
+
+        let stats = build_stats(
+            &rooms,
+            "@user:example.org",
+            None,
+            None,
+            &window_scope,
+            3,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
+
+        let fun = stats.fun.expect("fun section should be present");
+        assert_eq!(
+            fun.fields.get("message_twins"),
+            Some(&serde_json::json!({
+                "rooms": ["Standup", "Team"],
+                "similarity": 1.0,
+            }))
+        );
     }
 
     #[test]
@@ -887,21 +1850,142 @@ mod tests {
         let window_scope = create_test_window_scope();
 
         let stats = build_stats(
-            vec![room_input],
+            &[room_input],
             "@user:example.org",
             None,
             None,
             &window_scope,
             1,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
         )
         .unwrap();
 
-        assert_eq!(stats.summary.messages_sent, 0);
-        assert_eq!(stats.summary.active_rooms, 0);
+        assert_eq!(stats.summary.messages_sent.get(), 0);
+        assert_eq!(stats.summary.active_rooms.get(), 0);
         assert!(stats.activity.is_none());
         assert!(stats.rooms.is_none());
     }
 
+    #[test]
+    fn test_build_stats_fun_facts() {
+        let mut active = create_test_room_stats();
+        active.longest_message_chars = 420;
+        active.user_edits = 2;
+        active.user_reactions = 3;
+        active.user_encrypted_messages = 7;
+        active.user_messages_by_msgtype =
+            HashMap::from([("m.text".to_string(), 6), ("m.emote".to_string(), 2)]);
+        for date in ["2025-01-16", "2025-01-17"] {
+            active.active_dates.insert(date.to_string(), true);
+        }
+        let mut lurking = create_test_room_stats();
+        lurking.user_events = 0;
+        lurking.user_reactions = 1;
+
+        let room_inputs = [
+            ("!active:example.org", active),
+            ("!lurk:example.org", lurking),
+        ]
+        .into_iter()
+        .map(|(room_id, stats)| RoomStatsInput {
+            room_id: room_id.to_string(),
+            room_name: None,
+            room_type: RoomType::Private,
+            stats,
+        })
+        .collect::<Vec<_>>();
+
+        let stats = build_stats(
+            &room_inputs,
+            "@user:example.org",
+            None,
+            None,
+            &create_test_window_scope(),
+            2,
+            DEFAULT_TOP,
+            &[],
+        )
+        .unwrap();
+
+        let fun = stats.fun.expect("fun section should be present");
+        let keys: Vec<&str> = fun.fields.keys().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            [
+                "longest_message_chars",
+                "favorite_weekday",
+                "peak_hour",
+                "longest_streak_days",
+                "longest_silence",
+                "reactions_per_message",
+                "edits_per_message",
+                "emote_messages",
+                "lurking_rooms",
+                "sent_encrypted_messages_ratio",
+            ]
+        );
+        assert_eq!(fun.fields["longest_message_chars"], serde_json::json!(420));
+        assert_eq!(fun.fields["favorite_weekday"], serde_json::json!("Tuesday"));
+        assert_eq!(fun.fields["peak_hour"], serde_json::json!("14"));
+        assert_eq!(fun.fields["longest_streak_days"], serde_json::json!(3));
+        assert_eq!(
+            fun.fields["longest_silence"],
+            serde_json::json!({"days": 33, "from": "2025-01-18", "to": "2025-02-19"})
+        );
+        assert_eq!(fun.fields["reactions_per_message"], serde_json::json!(0.4));
+        assert_eq!(fun.fields["edits_per_message"], serde_json::json!(0.2));
+        assert_eq!(fun.fields["emote_messages"], serde_json::json!(2));
+        assert_eq!(fun.fields["lurking_rooms"], serde_json::json!(1));
+        assert_eq!(
+            fun.fields["sent_encrypted_messages_ratio"],
+            serde_json::json!(0.7)
+        );
+    }
+
+    #[test]
+    fn test_busiest_key_and_streak() {
+        let counts: HashMap<String, i32> = [("Tue", 3), ("Fri", 3), ("Sun", 1)]
+            .into_iter()
+            .map(|(key, count)| (key.to_string(), count))
+            .collect();
+        // Ties go to the earliest key
+        assert_eq!(
+            busiest_key(Some(&counts), &WEEKDAYS),
+            Some("Tue".to_string())
+        );
+        assert_eq!(busiest_key(Some(&HashMap::new()), &WEEKDAYS), None);
+
+        let dates: Vec<String> = ["2025-03-01", "2025-02-27", "2025-02-28", "2025-03-05"]
+            .iter()
+            .map(|date| date.to_string())
+            .collect();
+        // Crosses the end of February
+        assert_eq!(longest_streak(dates.iter()), 3);
+        assert_eq!(longest_streak([].iter()), 0);
+
+        let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        assert_eq!(current_streak(dates.iter(), day(1)), 3);
+        // Not broken before the end of the day
+        assert_eq!(current_streak(dates.iter(), day(2)), 3);
+        assert_eq!(current_streak(dates.iter(), day(3)), 0);
+        assert_eq!(current_streak(dates.iter(), day(5)), 1);
+
+        // March 2 to 4, between the streak and March 5
+        assert_eq!(longest_silence(dates.iter()), Some((day(2), day(4))));
+        assert_eq!(longest_silence(dates[..3].iter()), None);
+    }
+
+    #[test]
+    fn test_longest_silence_ties() {
+        let dates: Vec<String> = ["2025-06-01", "2025-06-04", "2025-06-07", "2025-06-08"]
+            .iter()
+            .map(|date| date.to_string())
+            .collect();
+        let day = |d| NaiveDate::from_ymd_opt(2025, 6, d).unwrap();
+        assert_eq!(longest_silence(dates.iter()), Some((day(2), day(3))));
+    }
+
     #[test]
     fn test_compute_peaks() {
         let mut by_year = HashMap::new();
@@ -920,11 +2004,23 @@ mod tests {
         by_day.insert("2025-03-15".to_string(), 25);
         by_day.insert("2025-03-20".to_string(), 30);
 
-        let mut by_hour = HashMap::new();
-        by_hour.insert("09".to_string(), 10);
-        by_hour.insert("14".to_string(), 20);
+        // 14:00 is the busiest hour overall, but 09:00 on March 20 the busiest one
+        let by_day_hour = HashMap::from([
+            (
+                "2025-03-15".to_string(),
+                HashMap::from([("14".to_string(), 12), ("09".to_string(), 3)]),
+            ),
+            (
+                "2025-03-20".to_string(),
+                HashMap::from([("09".to_string(), 14), ("14".to_string(), 10)]),
+            ),
+            (
+                "2025-03-21".to_string(),
+                HashMap::from([("08".to_string(), 14)]),
+            ),
+        ]);
 
-        let peaks = compute_peaks(&by_year, &by_month, &by_week, &by_day, &by_hour)
+        let peaks = compute_peaks(&by_year, &by_month, &by_week, &by_day, &by_day_hour)
             .unwrap()
             .unwrap();
 
@@ -940,8 +2036,11 @@ mod tests {
         assert_eq!(peaks.day.as_ref().unwrap().day, "2025-03-20");
         assert_eq!(peaks.day.as_ref().unwrap().messages, 30);
 
-        assert_eq!(peaks.hour.as_ref().unwrap().hour, "14");
-        assert_eq!(peaks.hour.as_ref().unwrap().messages, 20);
+        // Ties go to the earliest day
+        let hour = peaks.hour.unwrap();
+        assert_eq!(hour.hour, "09");
+        assert_eq!(hour.date.as_deref(), Some("2025-03-20"));
+        assert_eq!(hour.messages, 14);
     }
 
     #[test]
@@ -950,9 +2049,9 @@ mod tests {
         let by_month = HashMap::new();
         let by_week = HashMap::new();
         let by_day = HashMap::new();
-        let by_hour = HashMap::new();
+        let by_day_hour = HashMap::new();
 
-        let peaks = compute_peaks(&by_year, &by_month, &by_week, &by_day, &by_hour).unwrap();
+        let peaks = compute_peaks(&by_year, &by_month, &by_week, &by_day, &by_day_hour).unwrap();
         assert!(peaks.is_none());
     }
 
@@ -990,19 +2089,146 @@ mod tests {
 
         let window_scope = create_test_window_scope();
 
-        let stats = build_stats(rooms, "@user:example.org", None, None, &window_scope, 3).unwrap();
+        let stats = build_stats(
+            &rooms,
+            "@user:example.org",
+            None,
+            None,
+            &window_scope,
+            3,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
 
         let top_rooms = stats.rooms.unwrap().top.unwrap();
         assert_eq!(top_rooms.len(), 3);
 
         // Should be sorted by message count descending
         assert_eq!(top_rooms[0].name, Some("Room 2".to_string()));
-        assert_eq!(top_rooms[0].messages, 200);
+        assert_eq!(top_rooms[0].messages.get(), 200);
 
         assert_eq!(top_rooms[1].name, Some("Room 1".to_string()));
-        assert_eq!(top_rooms[1].messages, 100);
+        assert_eq!(top_rooms[1].messages.get(), 100);
 
         assert_eq!(top_rooms[2].name, Some("Room 3".to_string()));
-        assert_eq!(top_rooms[2].messages, 50);
+        assert_eq!(top_rooms[2].messages.get(), 50);
+
+        // Both days of the test rooms are in the first half of 2025
+        let trend = top_rooms[0].trend.unwrap();
+        assert_eq!((trend.first_half.get(), trend.second_half.get()), (10, 0));
+    }
+
+    #[test]
+    fn test_room_trend() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let by_day: HashMap<String, i32> = [
+            ("2025-03-01", 4),
+            ("2025-07-02", 9), // Middle day of 2025
+            ("2025-11-20", 12),
+            ("2026-01-05", 7), // Outside the span
+        ]
+        .into_iter()
+        .map(|(day, count)| (day.to_string(), count))
+        .collect();
+
+        let trend = room_trend(&by_day, (date("2025-01-01"), date("2025-12-31"))).unwrap();
+        assert_eq!((trend.first_half.get(), trend.second_half.get()), (4, 12));
+
+        let window_scope = create_test_window_scope();
+        assert_eq!(
+            trend_span(&window_scope, "2024-06-01", "2025-10-17"),
+            Some((date("2025-01-01"), date("2025-10-17")))
+        );
+        assert_eq!(trend_span(&window_scope, "2025-10-17", "2025-10-17"), None);
+    }
+
+    #[test]
+    fn test_top_limit() {
+        let rooms: Vec<RoomStatsInput> = (1..=8)
+            .map(|i| {
+                let mut room_stats = create_test_room_stats();
+                room_stats.user_events = i * 10;
+                room_stats.reactions_by_emoji = HashMap::from([(format!("e{}", i), i as i32)]);
+                RoomStatsInput {
+                    room_id: format!("!room{}:example.org", i),
+                    room_name: None,
+                    room_type: RoomType::Private,
+                    stats: room_stats,
+                }
+            })
+            .collect();
+        let window_scope = create_test_window_scope();
+        let options = CrawlOptions {
+            top: 7,
+            ..CrawlOptions::default()
+        };
+
+        let stats = build_stats(
+            &rooms,
+            "@user:example.org",
+            None,
+            None,
+            &window_scope,
+            8,
+            options.top,
+            &builtin_collectors(&options),
+        )
+        .unwrap();
+
+        assert_eq!(stats.rooms.unwrap().top.unwrap().len(), 7);
+        let reactions = stats.reactions.unwrap();
+        assert_eq!(reactions.top_emojis.unwrap().len(), 7);
+        assert_eq!(reactions.top_rooms.unwrap().len(), 7);
+    }
+
+    #[test]
+    fn test_apply_account_creation() {
+        let window_scope = create_test_window_scope();
+        let mut stats = build_stats(
+            &[],
+            "@user:example.org",
+            None,
+            None,
+            &window_scope,
+            0,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
+
+        let created = chrono::Local
+            .with_ymd_and_hms(2024, 12, 31, 12, 0, 0)
+            .unwrap()
+            .timestamp_millis();
+        apply_account_creation(&mut stats, created, &window_scope);
+        assert_eq!(stats.account.created_at, Some("2024-12-31".to_string()));
+        let fun = stats.fun.as_ref().unwrap();
+        assert_eq!(fun.fields["matrix_age_days"], serde_json::json!(365));
+
+        // An account created after the window has no age in it
+        let mut stats_before = build_stats(
+            &[],
+            "@user:example.org",
+            None,
+            None,
+            &window_scope,
+            0,
+            DEFAULT_TOP,
+            &builtin_collectors(&CrawlOptions::default()),
+        )
+        .unwrap();
+        let created = chrono::Local
+            .with_ymd_and_hms(2026, 1, 2, 12, 0, 0)
+            .unwrap()
+            .timestamp_millis();
+        apply_account_creation(&mut stats_before, created, &window_scope);
+        assert_eq!(
+            stats_before.account.created_at,
+            Some("2026-01-02".to_string())
+        );
+        assert!(stats_before
+            .fun
+            .is_none_or(|fun| !fun.fields.contains_key("matrix_age_days")));
     }
 }
@@ -0,0 +1,290 @@
+/// Next crawl window suggestion (`my crawl --suggest`).
+///
+/// Reads the data window of the crawl database and proposes the window whose
+/// crawl adds the most history: the partially covered year first, then the year
+/// before the crawled data, and the current year again once older history is
+/// complete but stale. Event counts are estimates from the rate of events
+/// already crawled.
+use anyhow::Result;
+use chrono::{Datelike, TimeZone, Utc};
+
+use super::db::{CrawlDb, RoomCrawlMetadata, TimeWindow};
+use crate::account_selector::AccountSelector;
+use crate::timefmt::format_timestamp;
+use crate::window::WindowScope;
+
+/// Crawled data older than this is worth refreshing.
+const STALE_AFTER_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+/// A window worth crawling next, and why.
+#[derive(Debug, PartialEq)]
+pub struct Suggestion {
+    /// Window to pass to `my crawl`, None when there is nothing left to crawl
+    pub window: Option<String>,
+    /// One-line explanation (e.g. "2024 is 80% covered, ~1.2k more events")
+    pub reason: String,
+    /// What a life crawl would still fetch, when history is incomplete
+    pub life: Option<String>,
+}
+
+/// Prints the suggestion of every selected account.
+pub fn run(user_id_flag: Option<String>) -> Result<()> {
+    let mut selector = AccountSelector::new()?;
+    let accounts = selector.select_accounts(user_id_flag, true)?;
+    let now = Utc::now().timestamp_millis();
+
+    for (account_id, account_dir) in &accounts {
+        let db = CrawlDb::init(account_dir)?;
+        let window = db.get_time_window()?;
+        let rooms = db.get_all_rooms_sorted()?;
+        let suggestion = suggest(window.as_ref(), &rooms, now)?;
+
+        println!("\nAccount: {}", account_id);
+        println!("  💡 {}", suggestion.reason);
+        if let Some(life) = suggestion.life {
+            println!("  {}", life);
+        }
+        if let Some(next) = suggestion.window {
+            println!("  Next: my crawl {} --user-id {}", next, account_id);
+        }
+    }
+    Ok(())
+}
+
+/// Picks the next window to crawl from the crawled data window.
+pub fn suggest(
+    window: Option<&TimeWindow>,
+    rooms: &[RoomCrawlMetadata],
+    now: i64,
+) -> Result<Suggestion> {
+    let current_year = year_of(now);
+    let Some(window) = window else {
+        return Ok(Suggestion {
+            window: Some(current_year.to_string()),
+            reason: "Nothing crawled yet, start with the current year".to_string(),
+            life: None,
+        });
+    };
+    let end = window.window_end.unwrap_or(now);
+
+    let Some(start) = window.window_start else {
+        // Every room is crawled back to its start: only newer messages are missing
+        if now - end > STALE_AFTER_MS {
+            return Ok(Suggestion {
+                window: Some(current_year.to_string()),
+                reason: format!(
+                    "All history is crawled up to {}, {} would fetch newer messages",
+                    format_timestamp(end),
+                    current_year
+                ),
+                life: None,
+            });
+        }
+        return Ok(Suggestion {
+            window: None,
+            reason: "All history is crawled and up to date".to_string(),
+            life: None,
+        });
+    };
+
+    let rate = event_rate(rooms, window.account_creation_ts, end);
+    let mut year = year_of(start);
+    let mut coverage = year_coverage(year, start, end, now)?;
+    if coverage.covered >= coverage.total {
+        // The crawled data starts on January 1st: the previous year is next
+        year -= 1;
+        coverage = year_coverage(year, start, end, now)?;
+    }
+
+    let missing = rate.map(|rate| (rate * (coverage.total - coverage.covered) as f64) as u64);
+    let percent = (coverage.covered as f64 * 100.0 / coverage.total as f64).floor();
+    let mut reason = if coverage.covered == 0 {
+        format!("{} is not crawled yet", year)
+    } else {
+        format!("{} is {:.0}% covered", year, percent)
+    };
+    if let Some(missing) = missing {
+        reason.push_str(&format!(", ~{} more events", approx_count(missing)));
+    }
+
+    Ok(Suggestion {
+        window: Some(year.to_string()),
+        reason,
+        life: Some(life_estimate(rooms, window.account_creation_ts)),
+    })
+}
+
+/// Milliseconds of a year (up to now) and how many of them are crawled.
+struct YearCoverage {
+    total: i64,
+    covered: i64,
+}
+
+fn year_coverage(year: i32, start: i64, end: i64, now: i64) -> Result<YearCoverage> {
+    let (year_start, year_end) = WindowScope::parse(&year.to_string())?.to_timestamp_range();
+    let year_start = year_start.unwrap_or(0);
+    let year_end = year_end.min(now).max(year_start + 1);
+    let covered = (year_end.min(end) - year_start.max(start)).max(0);
+    Ok(YearCoverage {
+        total: year_end - year_start,
+        covered,
+    })
+}
+
+/// Events per millisecond across the crawled data, if it spans any time.
+fn event_rate(rooms: &[RoomCrawlMetadata], from: Option<i64>, to: i64) -> Option<f64> {
+    let span = to - from?;
+    let events: usize = rooms.iter().map(|room| room.total_events_fetched).sum();
+    (span > 0 && events > 0).then(|| events as f64 / span as f64)
+}
+
+/// Rooms a life crawl would go further back in, and roughly how many events it would fetch.
+///
+/// Each room is assumed to keep its crawled event rate back to the oldest
+/// crawled message of the account, so this is a lower bound.
+fn life_estimate(rooms: &[RoomCrawlMetadata], account_creation_ts: Option<i64>) -> String {
+    let incomplete: Vec<&RoomCrawlMetadata> =
+        rooms.iter().filter(|room| !room.fully_crawled).collect();
+    let missing: f64 = incomplete
+        .iter()
+        .filter_map(|room| {
+            let oldest = room.oldest_event_ts?;
+            let newest = room.newest_event_ts?;
+            let creation = account_creation_ts?;
+            (newest > oldest && oldest > creation).then(|| {
+                room.total_events_fetched as f64 / (newest - oldest) as f64
+                    * (oldest - creation) as f64
+            })
+        })
+        .sum();
+
+    let rooms_label = if incomplete.len() == 1 {
+        "room is"
+    } else {
+        "rooms are"
+    };
+    if missing >= 1.0 {
+        format!(
+            "{} {} not fully crawled, a life crawl would need ~{} more events",
+            incomplete.len(),
+            rooms_label,
+            approx_count(missing as u64)
+        )
+    } else {
+        format!(
+            "{} {} not fully crawled, run 'my crawl life' to fetch their whole history",
+            incomplete.len(),
+            rooms_label
+        )
+    }
+}
+
+/// Rounded count for estimates: 950, 1.2k, 35k, 1.4M.
+fn approx_count(n: u64) -> String {
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=9_999 => format!("{:.1}k", n as f64 / 1_000.0).replace(".0k", "k"),
+        10_000..=999_999 => format!("{}k", (n as f64 / 1_000.0).round() as u64),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0).replace(".0M", "M"),
+    }
+}
+
+fn year_of(ts: i64) -> i32 {
+    Utc.timestamp_millis_opt(ts)
+        .single()
+        .map(|date| date.year())
+        .unwrap_or(1970)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(date: &str) -> i64 {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp_millis()
+    }
+
+    fn room(oldest: &str, newest: &str, fully_crawled: bool, events: usize) -> RoomCrawlMetadata {
+        RoomCrawlMetadata {
+            room_id: format!("!{}:example.org", oldest),
+            oldest_event_id: None,
+            oldest_event_ts: Some(ts(oldest)),
+            newest_event_id: None,
+            newest_event_ts: Some(ts(newest)),
+            fully_crawled,
+            history_limited: false,
+            joined_at_ts: None,
+            total_events_fetched: events,
+            user_events_fetched: 0,
+            last_crawl_status: None,
+        }
+    }
+
+    #[test]
+    fn test_suggest_partially_covered_year() {
+        let rooms = vec![
+            room("2024-03-01", "2025-03-01", false, 36_000),
+            room("2023-01-01", "2025-03-01", true, 1_000),
+        ];
+        let window = TimeWindow {
+            window_start: Some(ts("2024-03-01")),
+            window_end: Some(ts("2025-03-01")),
+            account_creation_ts: Some(ts("2023-01-01")),
+        };
+
+        let suggestion = suggest(Some(&window), &rooms, ts("2025-03-02")).unwrap();
+        assert_eq!(suggestion.window.as_deref(), Some("2024"));
+        assert!(suggestion.reason.starts_with("2024 is 83% covered, ~"));
+        assert!(suggestion
+            .life
+            .unwrap()
+            .starts_with("1 room is not fully crawled, a life crawl would need ~"));
+    }
+
+    #[test]
+    fn test_suggest_previous_year() {
+        let rooms = vec![room("2025-01-01", "2025-03-01", false, 100)];
+        let window = TimeWindow {
+            window_start: Some(ts("2025-01-01")),
+            window_end: Some(ts("2025-03-01")),
+            account_creation_ts: Some(ts("2025-01-01")),
+        };
+
+        let suggestion = suggest(Some(&window), &rooms, ts("2025-03-01")).unwrap();
+        assert_eq!(suggestion.window.as_deref(), Some("2024"));
+        assert!(suggestion.reason.starts_with("2024 is not crawled yet"));
+    }
+
+    #[test]
+    fn test_suggest_complete_history() {
+        let rooms = vec![room("2020-01-01", "2025-03-01", true, 100)];
+        let window = TimeWindow {
+            window_start: None,
+            window_end: Some(ts("2025-03-01")),
+            account_creation_ts: Some(ts("2020-01-01")),
+        };
+
+        let fresh = suggest(Some(&window), &rooms, ts("2025-03-02")).unwrap();
+        assert_eq!(fresh.window, None);
+
+        let stale = suggest(Some(&window), &rooms, ts("2025-06-01")).unwrap();
+        assert_eq!(stale.window.as_deref(), Some("2025"));
+
+        let empty = suggest(None, &[], ts("2025-06-01")).unwrap();
+        assert_eq!(empty.window.as_deref(), Some("2025"));
+    }
+
+    #[test]
+    fn test_approx_count() {
+        assert_eq!(approx_count(950), "950");
+        assert_eq!(approx_count(1_234), "1.2k");
+        assert_eq!(approx_count(2_000), "2k");
+        assert_eq!(approx_count(34_800), "35k");
+        assert_eq!(approx_count(1_400_000), "1.4M");
+    }
+}
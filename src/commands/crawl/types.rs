@@ -1,9 +1,10 @@
 //! Data structures for the crawl module.
 
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 /// Represents the join state of a room.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RoomJoinState {
     Joined,
     #[allow(dead_code)]
@@ -14,6 +15,29 @@ pub enum RoomJoinState {
     JoinedSpace,
 }
 
+impl RoomJoinState {
+    /// Convert to database string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Joined => "joined",
+            Self::Left => "left",
+            Self::Invited => "invited",
+            Self::JoinedSpace => "joined_space",
+        }
+    }
+
+    /// Parse the database string representation
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "joined" => Some(Self::Joined),
+            "left" => Some(Self::Left),
+            "invited" => Some(Self::Invited),
+            "joined_space" => Some(Self::JoinedSpace),
+            _ => None,
+        }
+    }
+}
+
 /// Metadata about a room discovered during room list sync.
 ///
 /// Contains the latest event information needed for crawl decision-making
@@ -39,24 +63,39 @@ pub struct RoomCrawlStats {
     pub newest_event_id: Option<String>,
     pub newest_ts: Option<i64>,
     pub fully_crawled: bool,
+    pub history_limited: bool,
+    pub joined_at: Option<i64>,
     pub room_name: String,
     pub total_events: usize,
     pub user_events: usize,
+    pub untimed_events: usize,
 }
 
 /// Detailed statistics collected during pagination for stats generation.
 ///
 /// Extends basic pagination aggregates with temporal bucketing, reaction tracking,
 /// and room creation detection. All data is aggregated in-memory during event iteration.
+/// Serialized to keep the stats of crawled rooms while a crawl runs (`my render --live`).
+#[derive(Serialize, Deserialize)]
 pub struct DetailedPaginationStats {
     // Basic metadata (same as PaginationAggregates)
     pub fully_crawled: bool,
+    pub history_limited: bool, // Start of history reached without seeing m.room.create
+    pub saw_room_create: bool,
+    pub joined_at: Option<i64>, // User's join ts, kept only when history starts there
     pub oldest_event_id: Option<String>,
     pub oldest_ts: Option<i64>,
     pub newest_event_id: Option<String>,
     pub newest_ts: Option<i64>,
     pub total_events: usize,
     pub user_events: usize,
+    pub untimed_events: usize, // Events without origin_server_ts, never bucketed
+    pub others_messages: usize, // Messages sent by other members (DM conversation balance)
+    pub messages_by_sender: HashMap<String, i32>, // Other members' messages by sender (People)
+    pub sender_names: HashMap<String, String>, // Display names from member events (People)
+    pub dm_partner: Option<String>, // Other party of a DM, from room state (DMs)
+    pub dm_partner_name: Option<String>, // Their display name in the room
+    pub canonical_alias: Option<String>, // From room state, for the homeserver of the room
 
     // Temporal buckets (local timezone)
     pub by_year: HashMap<String, i32>,
@@ -65,6 +104,8 @@ pub struct DetailedPaginationStats {
     pub by_weekday: HashMap<String, i32>,
     pub by_day: HashMap<String, i32>,
     pub by_hour: HashMap<String, i32>,
+    pub by_weekday_hour: HashMap<String, HashMap<String, i32>>, // "Mon".."Sun" -> hour -> count
+    pub by_day_hour: HashMap<String, HashMap<String, i32>>, // YYYY-MM-DD -> hour -> count (peak hour)
 
     // User's message IDs (for filtering reactions)
     pub user_message_ids: HashMap<String, String>, // event_id -> room_id
@@ -72,10 +113,58 @@ pub struct DetailedPaginationStats {
     // Reactions tracking
     pub reactions_by_emoji: HashMap<String, i32>,
     pub reactions_by_message: HashMap<String, i32>, // event_id -> count
+    pub reactions_by_month: HashMap<String, i32>,   // "01".."12" -> count, local time
+    pub reactions_given_by_emoji: HashMap<String, i32>, // Reactions the user sent
+    pub reaction_emojis_by_message: HashMap<String, HashSet<String>>, // event_id -> distinct emoji
+    pub message_previews: HashMap<String, String>,  // event_id -> excerpt, with --message-previews
+    pub emote_names: HashMap<String, (String, Option<String>)>, // mxc URL -> (shortcode, pack name)
 
     // Room creation tracking
     pub room_created_by_user: bool,
 
     // Track unique dates for days_active calculation
     pub active_dates: HashMap<String, bool>, // YYYY-MM-DD -> true
+
+    // Reply graph: earliest reply per replied-to message (any sender)
+    pub first_replies: HashMap<String, (i64, bool)>, // event_id -> (reply ts, replied by user)
+    pub replied_to_senders: HashMap<String, bool>,   // replied-to event_id -> sent by user
+
+    // Earliest message of each day (any sender), for conversation starts
+    pub first_messages_by_day: HashMap<String, (i64, bool)>, // YYYY-MM-DD -> (ts, sent by user)
+
+    // Words typed by the user in text messages (typing time estimate)
+    pub typed_words: i64,
+
+    // User's messages by kind (text, media, voice)
+    pub messages_by_kind: HashMap<String, i32>,
+
+    // User's messages by conversation (Conversations)
+    pub user_top_level_messages: i32,
+    pub user_thread_messages: i32,
+    pub user_threads: HashSet<String>, // Thread root event IDs
+    pub user_replies: i32,
+
+    // User's text messages by length bucket, and the longest one (Message lengths)
+    pub messages_by_length: HashMap<String, i32>,
+    pub longest_message: Option<(usize, String)>, // (characters, event ID)
+
+    // Images of packs the user sent: stickers, inline emotes, custom reactions (Fun)
+    pub image_uses: HashMap<String, i32>, // mxc URL -> uses
+
+    // Emoji typed by the user in text messages (Typed emojis)
+    pub typed_emojis: HashMap<String, i32>,
+
+    // Fun facts about the user's own events
+    pub longest_message_chars: usize, // Longest text message body, edits excluded
+    pub user_edits: usize,            // Messages replacing an earlier one
+    pub user_reactions: usize,        // Reactions sent
+    pub user_encrypted_messages: usize,
+    pub user_messages_by_msgtype: HashMap<String, i32>, // "m.text", "m.emote"... edits excluded
+
+    // User's messages by timezone, from the travel periods of the config file
+    pub messages_by_timezone: HashMap<String, i32>,
+
+    // Per-room state for the collectors of the `metric-plugins` feature, keyed by collector
+    #[cfg(feature = "metric-plugins")]
+    pub custom_metrics: HashMap<String, serde_json::Value>,
 }
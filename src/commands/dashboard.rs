@@ -0,0 +1,551 @@
+/// Interactive dashboard over the crawl database (`my dashboard`)
+///
+/// Lists the rooms of an account with their crawl status and how far back
+/// their history is covered, to filter and sort them, and to crawl a room
+/// again from scratch. While a crawl runs (`my crawl` in another terminal, or
+/// one started from the dashboard), the dashboard refreshes from the database
+/// and shows its progress.
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Gauge, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::account_selector::AccountSelector;
+use crate::commands::crawl::db::{CrawlDb, LiveCrawl, RoomCrawlMetadata};
+use crate::commands::crawl::progress::completeness_marker;
+use crate::commands::status::get_status_symbol;
+use crate::sdk::restore_client_for_account;
+use crate::timefmt::format_timestamp_opt;
+
+/// How often the rooms are read again from the database.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+/// Width of the coverage bars, in columns.
+const COVERAGE_WIDTH: usize = 20;
+/// Output of the crawls started from the dashboard, in the account directory.
+const CRAWL_LOG_FILE: &str = "dashboard-crawl.log";
+
+/// Order of the room list, cycled with `s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortOrder {
+    /// Virgin, complete, partial, in progress, failed (as `my status --list`)
+    Status,
+    Name,
+    /// Most events fetched first
+    Events,
+    /// Least covered first
+    Coverage,
+}
+
+impl SortOrder {
+    fn next(self) -> Self {
+        match self {
+            Self::Status => Self::Name,
+            Self::Name => Self::Events,
+            Self::Events => Self::Coverage,
+            Self::Coverage => Self::Status,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Name => "name",
+            Self::Events => "events",
+            Self::Coverage => "coverage",
+        }
+    }
+}
+
+/// What the keyboard currently edits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+    Browse,
+    /// Typing the filter
+    Filter,
+    /// Asking to confirm the re-crawl of a room
+    ConfirmRecrawl(String),
+}
+
+/// A room of the list.
+struct RoomRow {
+    name: String,
+    metadata: RoomCrawlMetadata,
+}
+
+struct App {
+    account_id: String,
+    account_dir: PathBuf,
+    rooms: Vec<RoomRow>,
+    /// Oldest point of the account history, for coverage bars
+    history_start: Option<i64>,
+    live_crawl: Option<LiveCrawl>,
+    filter: String,
+    sort: SortOrder,
+    mode: Mode,
+    table: TableState,
+    message: Option<String>,
+    /// `my crawl` started by a re-crawl, until it exits
+    crawl: Option<Child>,
+}
+
+/// Runs the dashboard of one account until `q` is pressed.
+pub async fn run(user_id: Option<String>) -> Result<()> {
+    let mut selector = AccountSelector::new()?;
+    let accounts = selector.select_accounts(user_id, false)?;
+    let (account_id, account_dir) = match accounts.as_slice() {
+        [] => anyhow::bail!("No accounts found. Use 'my login' first."),
+        [account] => account,
+        _ => anyhow::bail!(
+            "Multiple accounts found. The dashboard shows exactly one account. \
+             Use --user-id to specify which account."
+        ),
+    };
+
+    let db = CrawlDb::init(account_dir)
+        .with_context(|| format!("Failed to open crawl database for {}", account_id))?;
+    let rooms = db
+        .get_all_rooms_sorted()
+        .context("Failed to retrieve rooms from database")?;
+    if rooms.is_empty() {
+        anyhow::bail!(
+            "No rooms crawled yet for {}: start with 'my crawl <window>'",
+            account_id
+        );
+    }
+    let names = load_room_names(account_dir, account_id, &rooms).await?;
+
+    let mut app = App {
+        account_id: account_id.clone(),
+        account_dir: account_dir.clone(),
+        rooms: Vec::new(),
+        history_start: None,
+        live_crawl: None,
+        filter: String::new(),
+        sort: SortOrder::Status,
+        mode: Mode::Browse,
+        table: TableState::default().with_selected(Some(0)),
+        message: None,
+        crawl: None,
+    };
+    app.reload(&db, &names)?;
+
+    let mut terminal = ratatui::init();
+    let result = run_loop(&mut terminal, &mut app, &db, &names);
+    ratatui::restore();
+    result
+}
+
+/// Display names of the rooms, from the local session (no sync).
+async fn load_room_names(
+    account_dir: &Path,
+    account_id: &str,
+    rooms: &[RoomCrawlMetadata],
+) -> Result<HashMap<String, String>> {
+    let client = restore_client_for_account(account_dir, account_id)
+        .await
+        .context("Failed to restore Matrix session")?;
+    Ok(crate::commands::status::get_room_names(&client, rooms).await)
+}
+
+fn run_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    db: &CrawlDb,
+    names: &HashMap<String, String>,
+) -> Result<()> {
+    let mut last_reload = Instant::now();
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+
+        let timeout = REFRESH_INTERVAL.saturating_sub(last_reload.elapsed());
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !app.handle_key(key.code, db)? {
+                    return Ok(());
+                }
+            }
+        }
+        if last_reload.elapsed() >= REFRESH_INTERVAL {
+            app.reload(db, names)?;
+            last_reload = Instant::now();
+        }
+    }
+}
+
+impl App {
+    /// Reads the rooms and the running crawl again.
+    fn reload(&mut self, db: &CrawlDb, names: &HashMap<String, String>) -> Result<()> {
+        self.rooms = db
+            .get_all_rooms_sorted()?
+            .into_iter()
+            .map(|metadata| RoomRow {
+                name: names
+                    .get(&metadata.room_id)
+                    .cloned()
+                    .unwrap_or_else(|| metadata.room_id.clone()),
+                metadata,
+            })
+            .collect();
+        self.history_start = db
+            .get_time_window()?
+            .and_then(|window| window.account_creation_ts);
+        self.live_crawl = db
+            .get_live_crawl(None)?
+            .filter(|crawl| crawl.finished_ts.is_none());
+        if let Some(child) = self.crawl.as_mut() {
+            if let Some(status) = child.try_wait()? {
+                self.message = Some(if status.success() {
+                    "Crawl finished".to_string()
+                } else {
+                    format!(
+                        "Crawl failed ({}), see {}",
+                        status,
+                        self.account_dir.join(CRAWL_LOG_FILE).display()
+                    )
+                });
+                self.crawl = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts `my crawl` again for the window of the latest crawl, which
+    /// paginates the room just reset; returns the message to show.
+    ///
+    /// While a crawl already runs, the room is left for the next one.
+    fn start_recrawl(&mut self, db: &CrawlDb, room_id: &str) -> Result<String> {
+        if self.live_crawl.is_some() || self.crawl.is_some() {
+            return Ok(format!(
+                "{} will be crawled again by the next 'my crawl' (a crawl is running)",
+                room_id
+            ));
+        }
+        let Some(window) = db.get_live_crawl(None)?.map(|crawl| crawl.window_key) else {
+            return Ok(format!(
+                "{} will be crawled again by the next 'my crawl'",
+                room_id
+            ));
+        };
+        self.crawl = Some(spawn_crawl(&self.account_id, &self.account_dir, &window)?);
+        Ok(format!(
+            "Crawling {} again ('my crawl {}')",
+            room_id, window
+        ))
+    }
+
+    /// Rooms matching the filter, in the chosen order.
+    fn visible_rooms(&self) -> Vec<&RoomRow> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut rooms = filter_rooms(&self.rooms, &self.filter);
+        match self.sort {
+            // The database already returns them in status order
+            SortOrder::Status => {}
+            SortOrder::Name => rooms.sort_by_key(|room| room.name.to_lowercase()),
+            SortOrder::Events => {
+                rooms.sort_by_key(|room| std::cmp::Reverse(room.metadata.total_events_fetched))
+            }
+            SortOrder::Coverage => rooms.sort_by(|a, b| {
+                let covered = |room: &RoomRow| coverage(&room.metadata, self.history_start, now);
+                covered(a).total_cmp(&covered(b))
+            }),
+        }
+        rooms
+    }
+
+    /// Handles a key press; returns false to quit.
+    fn handle_key(&mut self, code: KeyCode, db: &CrawlDb) -> Result<bool> {
+        match self.mode.clone() {
+            Mode::Filter => match code {
+                KeyCode::Enter => self.mode = Mode::Browse,
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.mode = Mode::Browse;
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Char(ch) => self.filter.push(ch),
+                _ => {}
+            },
+            Mode::ConfirmRecrawl(room_id) => {
+                if code == KeyCode::Char('y') {
+                    db.reset_room_coverage(&room_id)?;
+                    self.message = Some(self.start_recrawl(db, &room_id)?);
+                } else {
+                    self.message = None;
+                }
+                self.mode = Mode::Browse;
+            }
+            Mode::Browse => match code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+                KeyCode::Home => self.table.select_first(),
+                KeyCode::End => self.table.select_last(),
+                KeyCode::Char('/') => {
+                    self.mode = Mode::Filter;
+                    self.table.select(Some(0));
+                }
+                KeyCode::Char('s') => self.sort = self.sort.next(),
+                KeyCode::Char('r') => {
+                    let selected = self.table.selected().unwrap_or(0);
+                    let room = self
+                        .visible_rooms()
+                        .get(selected)
+                        .map(|room| (room.name.clone(), room.metadata.room_id.clone()));
+                    if let Some((name, room_id)) = room {
+                        self.message = Some(format!("Re-crawl {} from scratch? (y/n)", name));
+                        self.mode = Mode::ConfirmRecrawl(room_id);
+                    }
+                }
+                _ => {}
+            },
+        }
+        Ok(true)
+    }
+}
+
+/// Runs `my crawl <window>` for the account in the background, its output
+/// going to [`CRAWL_LOG_FILE`] so that it does not draw over the dashboard.
+fn spawn_crawl(account_id: &str, account_dir: &Path, window: &str) -> Result<Child> {
+    let log_path = account_dir.join(CRAWL_LOG_FILE);
+    let log = File::create(&log_path)
+        .with_context(|| format!("Failed to create {}", log_path.display()))?;
+    let exe = std::env::current_exe().context("Failed to locate the my executable")?;
+    Command::new(exe)
+        .args(["crawl", window, "--user-id", account_id])
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .spawn()
+        .context("Failed to start the crawl")
+}
+
+/// Rooms whose name or ID contains `filter`, ignoring case.
+fn filter_rooms<'a>(rooms: &'a [RoomRow], filter: &str) -> Vec<&'a RoomRow> {
+    let filter = filter.to_lowercase();
+    rooms
+        .iter()
+        .filter(|room| {
+            room.name.to_lowercase().contains(&filter)
+                || room.metadata.room_id.to_lowercase().contains(&filter)
+        })
+        .collect()
+}
+
+/// Share of the account history the crawl of a room reaches back to, from 0 to 1.
+///
+/// A fully crawled room is covered, whatever its age; otherwise coverage goes
+/// from its oldest crawled event to now, over the time since `history_start`.
+fn coverage(metadata: &RoomCrawlMetadata, history_start: Option<i64>, now: i64) -> f64 {
+    if metadata.fully_crawled {
+        return 1.0;
+    }
+    match (metadata.oldest_event_ts, history_start) {
+        (Some(oldest), Some(start)) if now > start => {
+            ((now - oldest) as f64 / (now - start) as f64).clamp(0.0, 1.0)
+        }
+        _ => 0.0,
+    }
+}
+
+/// Coverage as a bar of `width` columns.
+fn coverage_bar(ratio: f64, width: usize) -> String {
+    let filled = (ratio * width as f64).round() as usize;
+    format!(
+        "{}{}",
+        "█".repeat(filled.min(width)),
+        "░".repeat(width.saturating_sub(filled))
+    )
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    match app.live_crawl {
+        Some(ref crawl) => {
+            let ratio = if crawl.rooms_total > 0 {
+                crawl.rooms_done as f64 / crawl.rooms_total as f64
+            } else {
+                0.0
+            };
+            let gauge = Gauge::default()
+                .block(Block::bordered().title(format!(" Crawl of {} ", crawl.window_key)))
+                .gauge_style(Style::new().green())
+                .ratio(ratio.clamp(0.0, 1.0))
+                .label(format!(
+                    "{} of {} room(s) crawled",
+                    crawl.rooms_done, crawl.rooms_total
+                ));
+            frame.render_widget(gauge, header);
+        }
+        None => {
+            let complete = app
+                .rooms
+                .iter()
+                .filter(|room| room.metadata.fully_crawled)
+                .count();
+            let summary = Paragraph::new(format!(
+                "{} room(s), {} fully crawled · no crawl running",
+                app.rooms.len(),
+                complete
+            ))
+            .block(Block::bordered().title(" my dashboard "));
+            frame.render_widget(summary, header);
+        }
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let rooms = app.visible_rooms();
+    let rows: Vec<Row> = rooms
+        .iter()
+        .map(|room| {
+            let metadata = &room.metadata;
+            let ratio = coverage(metadata, app.history_start, now);
+            Row::new(vec![
+                Cell::from(get_status_symbol(metadata)),
+                Cell::from(format!(
+                    "{}{}",
+                    room.name,
+                    completeness_marker(metadata.fully_crawled, metadata.history_limited)
+                )),
+                Cell::from(coverage_bar(ratio, COVERAGE_WIDTH)),
+                Cell::from(format_timestamp_opt(metadata.oldest_event_ts)),
+                Cell::from(metadata.total_events_fetched.to_string()),
+                Cell::from(metadata.user_events_fetched.to_string()),
+            ])
+        })
+        .collect();
+    let count = rows.len();
+    let title = if app.filter.is_empty() {
+        format!(" Rooms ({}) · sorted by {} ", count, app.sort.label())
+    } else {
+        format!(
+            " Rooms ({}) · filter \"{}\" · sorted by {} ",
+            count,
+            app.filter,
+            app.sort.label()
+        )
+    };
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(COVERAGE_WIDTH as u16),
+            Constraint::Length(16),
+            Constraint::Length(8),
+            Constraint::Length(8),
+        ],
+    )
+    .header(
+        Row::new(["", "Room", "Coverage", "Oldest event", "Events", "Yours"])
+            .style(Style::new().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(title))
+    .row_highlight_style(Style::new().reversed());
+    frame.render_stateful_widget(table, body, &mut app.table);
+
+    let help = match (&app.mode, &app.message) {
+        (Mode::Filter, _) => format!("Filter: {}▏ (Enter to keep, Esc to clear)", app.filter),
+        (_, Some(message)) => message.clone(),
+        (Mode::Browse, None) | (Mode::ConfirmRecrawl(_), None) => {
+            "↑/↓ select · / filter · s sort · r re-crawl room · q quit".to_string()
+        }
+    };
+    frame.render_widget(Paragraph::new(Line::from(help)), footer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::crawl::db::CrawlStatus;
+
+    fn metadata(
+        room_id: &str,
+        oldest_event_ts: Option<i64>,
+        fully_crawled: bool,
+    ) -> RoomCrawlMetadata {
+        RoomCrawlMetadata {
+            room_id: room_id.to_string(),
+            oldest_event_id: None,
+            oldest_event_ts,
+            newest_event_id: None,
+            newest_event_ts: None,
+            fully_crawled,
+            history_limited: false,
+            joined_at_ts: None,
+            total_events_fetched: 0,
+            user_events_fetched: 0,
+            last_crawl_status: Some(CrawlStatus::Success),
+        }
+    }
+
+    #[test]
+    fn test_coverage() {
+        let now = 1_000;
+        assert_eq!(coverage(&metadata("!a", None, true), Some(0), now), 1.0);
+        assert_eq!(
+            coverage(&metadata("!a", Some(500), false), Some(0), now),
+            0.5
+        );
+        assert_eq!(
+            coverage(&metadata("!a", Some(-10), false), Some(0), now),
+            1.0
+        );
+        assert_eq!(coverage(&metadata("!a", None, false), Some(0), now), 0.0);
+        assert_eq!(coverage(&metadata("!a", Some(500), false), None, now), 0.0);
+    }
+
+    #[test]
+    fn test_coverage_bar() {
+        assert_eq!(coverage_bar(0.5, 4), "██░░");
+        assert_eq!(coverage_bar(1.0, 4), "████");
+        assert_eq!(coverage_bar(0.0, 4), "░░░░");
+    }
+
+    #[test]
+    fn test_filter_rooms() {
+        let rooms = vec![
+            RoomRow {
+                name: "Rust Lovers".to_string(),
+                metadata: metadata("!abc:example.org", None, false),
+            },
+            RoomRow {
+                name: "Family".to_string(),
+                metadata: metadata("!def:example.org", None, false),
+            },
+        ];
+        let names = |filter: &str| -> Vec<String> {
+            filter_rooms(&rooms, filter)
+                .into_iter()
+                .map(|room| room.name.clone())
+                .collect()
+        };
+        assert_eq!(names("rust"), ["Rust Lovers"]);
+        assert_eq!(names("!DEF"), ["Family"]);
+        assert_eq!(names("").len(), 2);
+    }
+
+    #[test]
+    fn test_sort_order_cycles() {
+        let mut sort = SortOrder::Status;
+        for _ in 0..4 {
+            sort = sort.next();
+        }
+        assert_eq!(sort, SortOrder::Status);
+    }
+}
@@ -0,0 +1,259 @@
+/// Exports of stats into third-party formats.
+///
+/// - **ical**: Daily activity as an iCalendar feed (one all-day event per active day),
+///   to overlay Matrix activity on a calendar app
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::path::{Path, PathBuf};
+
+use crate::account_selector::AccountSelector;
+use crate::stats::{ScopeKind, Stats};
+use crate::window::WindowScope;
+
+/// Exports the saved stats of a window as an .ics file in `output_dir`.
+///
+/// Reads `stats-<window>.json` from the selected account directory (written by
+/// `my crawl`), so no Matrix connection is needed. Returns the written path.
+pub fn run_ical(window: &str, user_id_flag: Option<String>, output_dir: &Path) -> Result<PathBuf> {
+    let stats = load_saved_stats(window, user_id_flag)?;
+
+    let calendar = ical(&stats)?;
+
+    std::fs::create_dir_all(output_dir).with_context(|| {
+        format!(
+            "Failed to create output directory: {}",
+            output_dir.display()
+        )
+    })?;
+    let output_path = output_dir.join(ical_filename(&stats));
+    std::fs::write(&output_path, calendar)
+        .with_context(|| format!("Failed to write calendar: {}", output_path.display()))?;
+
+    Ok(output_path)
+}
+
+/// Loads the saved `stats-<window>.json` of the selected account (written by `my crawl`).
+pub fn load_saved_stats(window: &str, user_id_flag: Option<String>) -> Result<Stats> {
+    Ok(load_account_stats(window, user_id_flag)?.2)
+}
+
+/// Like [`load_saved_stats`], with the ID and directory of the selected account.
+pub fn load_account_stats(
+    window: &str,
+    user_id_flag: Option<String>,
+) -> Result<(String, PathBuf, Stats)> {
+    let window_scope = WindowScope::parse(window)?;
+
+    let mut selector = AccountSelector::new()?;
+    let accounts = selector.select_accounts(user_id_flag, false)?;
+    let (account_id, account_dir) = accounts
+        .into_iter()
+        .next()
+        .context("No accounts found. Use 'my login' first.")?;
+
+    let stats_path = account_dir.join(format!("stats-{}.json", window_scope.key));
+    if !stats_path.exists() {
+        anyhow::bail!(
+            "No stats for window {} and {}. Run 'my crawl {}' first.",
+            window_scope.key,
+            account_id,
+            window_scope.key
+        );
+    }
+    let stats = Stats::load_from_file(&stats_path)?;
+    Ok((account_id, account_dir, stats))
+}
+
+/// Builds an iCalendar (RFC 5545) document with one all-day event per active day.
+///
+/// Events are transparent (they don't block time) and have stable UIDs, so
+/// re-importing an updated export replaces previous events instead of duplicating them.
+pub fn ical(stats: &Stats) -> Result<String> {
+    let days = active_days(stats);
+    let dtstamp = NaiveDate::parse_from_str(&stats.generated_at, "%Y-%m-%d")
+        .map(|date| format!("{}T000000Z", date.format("%Y%m%d")))
+        .unwrap_or_else(|_| "19700101T000000Z".to_string());
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//matrix-year//my//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!(
+            "X-WR-CALNAME:{}",
+            escape_text(&format!(
+                "Matrix activity · {} · {}",
+                stats.scope.key, stats.account.user_id
+            ))
+        ),
+    ];
+
+    for (date, messages) in days {
+        let unit = if messages == 1 { "message" } else { "messages" };
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!(
+                "UID:{}-{}@matrix-year",
+                date.format("%Y%m%d"),
+                escape_text(&stats.account.user_id)
+            ),
+            format!("DTSTAMP:{}", dtstamp),
+            format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+            format!(
+                "DTEND;VALUE=DATE:{}",
+                date.succ_opt().unwrap_or(date).format("%Y%m%d")
+            ),
+            format!(
+                "SUMMARY:{}",
+                escape_text(&format!("💬 {} Matrix {}", messages, unit))
+            ),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    Ok(lines.iter().map(|line| fold_line(line) + "\r\n").collect())
+}
+
+/// Output filename for the calendar, matching report filenames (e.g. `my-year-2025.ics`).
+fn ical_filename(stats: &Stats) -> String {
+    match stats.scope.kind {
+        ScopeKind::Year => format!("my-year-{}.ics", stats.scope.key),
+        ScopeKind::Quarter => format!("my-quarter-{}.ics", stats.scope.key),
+        ScopeKind::Month => format!("my-month-{}.ics", stats.scope.key),
+        ScopeKind::Week => format!("my-week-{}.ics", stats.scope.key),
+        ScopeKind::Day => format!("my-day-{}.ics", stats.scope.key),
+        ScopeKind::Rolling => format!("my-{}.ics", stats.scope.key),
+        ScopeKind::Life => "my-life.ics".to_string(),
+    }
+}
+
+/// Days with at least one message, sorted by date.
+///
+/// `by_day` keys are full dates (`YYYY-MM-DD`); day-of-month keys (`DD`) are
+/// also accepted for month scopes and resolved against the scope key.
+fn active_days(stats: &Stats) -> Vec<(NaiveDate, i32)> {
+    let Some(by_day) = stats.activity.as_ref().and_then(|a| a.by_day.as_ref()) else {
+        return Vec::new();
+    };
+
+    let mut days: Vec<(NaiveDate, i32)> = by_day
+        .iter()
+        .filter(|(_, count)| **count > 0)
+        .filter_map(|(key, count)| {
+            let date = NaiveDate::parse_from_str(key, "%Y-%m-%d")
+                .ok()
+                .or_else(|| {
+                    if stats.scope.kind != ScopeKind::Month {
+                        return None;
+                    }
+                    NaiveDate::parse_from_str(&format!("{}-{}", stats.scope.key, key), "%Y-%m-%d")
+                        .ok()
+                })?;
+            Some((date, *count))
+        })
+        .collect();
+    days.sort();
+    days
+}
+
+/// Escapes TEXT property values (RFC 5545 §3.3.11).
+fn escape_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line at 75 octets without splitting UTF-8 characters (RFC 5545 §3.1).
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for ch in line.chars() {
+        if octets + ch.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            octets = 1;
+        }
+        folded.push(ch);
+        octets += ch.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::Activity;
+    use std::collections::HashMap;
+
+    fn stats_with_days(scope: &str, by_day: &[(&str, i32)]) -> Stats {
+        let mut stats: Stats =
+            serde_json::from_str(include_str!("../../examples/stats/example-stats.json"))
+                .expect("example stats should parse");
+        if scope.len() == 7 {
+            stats.scope.kind = ScopeKind::Month;
+        }
+        stats.scope.key = scope.to_string();
+        stats.activity = Some(Activity {
+            by_month: None,
+            by_week: None,
+            by_weekday: None,
+            by_hour: None,
+            by_day: Some(
+                by_day
+                    .iter()
+                    .map(|(day, count)| (day.to_string(), *count))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            by_year: None,
+            by_weekday_hour: None,
+            by_room_type_hour: None,
+        });
+        stats
+    }
+
+    #[test]
+    fn test_ical_one_event_per_active_day() {
+        let stats = stats_with_days(
+            "2025",
+            &[("2025-03-15", 25), ("2025-01-02", 1), ("2025-02-01", 0)],
+        );
+
+        let calendar = ical(&stats).unwrap();
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+
+        // Sorted by date, all-day, with the count in the summary
+        let first = calendar.find("DTSTART;VALUE=DATE:20250102").unwrap();
+        let second = calendar.find("DTSTART;VALUE=DATE:20250315").unwrap();
+        assert!(first < second);
+        assert!(calendar.contains("DTEND;VALUE=DATE:20250316\r\n"));
+        assert!(calendar.contains("SUMMARY:💬 25 Matrix messages\r\n"));
+        assert!(calendar.contains("SUMMARY:💬 1 Matrix message\r\n"));
+        assert!(calendar.contains("DTSTAMP:20251220T000000Z\r\n"));
+    }
+
+    #[test]
+    fn test_ical_resolves_day_of_month_keys() {
+        let stats = stats_with_days("2025-03", &[("07", 4)]);
+
+        let calendar = ical(&stats).unwrap();
+        assert!(calendar.contains("DTSTART;VALUE=DATE:20250307\r\n"));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = format!("SUMMARY:{}", "é".repeat(50));
+        let folded = fold_line(&line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= 75);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
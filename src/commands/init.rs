@@ -0,0 +1,122 @@
+/// First-run wizard
+///
+/// Walks a new user through login, timezone and output directory, saves them
+/// to the config file, and offers a month crawl as a quick taste before the
+/// full year.
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate};
+use std::path::PathBuf;
+
+use crate::account_selector::AccountSelector;
+use crate::commands::login;
+use crate::config::{parse_timezone, system_timezone, Config};
+
+/// Label of the timezone choice that keeps the system local time.
+const SYSTEM_TIMEZONE: &str = "System local time";
+
+/// Run the init wizard.
+///
+/// Returns the month window to crawl and render as a first taste, if the user
+/// accepted it.
+pub async fn run() -> Result<Option<String>> {
+    eprintln!("👋 Welcome to my, the Matrix recap tool! Let's get you set up.\n");
+
+    // 1) Login, unless accounts already exist and no other one is wanted
+    let accounts = AccountSelector::discover_accounts()?;
+    let wants_login = if accounts.is_empty() {
+        true
+    } else {
+        eprintln!("Existing accounts:");
+        for (account_id, _) in &accounts {
+            eprintln!("  - {}", account_id);
+        }
+        inquire::Confirm::new("Log into another account?")
+            .with_default(false)
+            .prompt()?
+    };
+    if wants_login {
+        login::run(None).await?;
+    }
+
+    // 2) Timezone and output directory
+    let mut config = Config::load()?;
+    config.timezone = prompt_timezone(config.timezone.as_deref())?;
+
+    let default_output = config
+        .output_dir
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("."));
+    let output = inquire::Text::new("Directory for reports:")
+        .with_default(&default_output.display().to_string())
+        .prompt()?;
+    config.output_dir = Some(PathBuf::from(output.trim()));
+
+    config.save()?;
+    config.apply_timezone();
+    eprintln!("✓ Settings saved: {}", Config::path()?.display());
+
+    // 3) Optional first crawl, on a month to keep it short
+    let window = taste_window(chrono::Local::now().date_naive());
+    let taste = inquire::Confirm::new(&format!(
+        "Crawl {} as a quick taste? (the full year can take a while)",
+        window
+    ))
+    .with_default(true)
+    .prompt()?;
+
+    if !taste {
+        let year = chrono::Local::now().format("%Y");
+        eprintln!("\n✅ All set! Run 'my {}' for your year in review.", year);
+        return Ok(None);
+    }
+    Ok(Some(window))
+}
+
+/// Prompts for an IANA timezone, with the system local time as first choice.
+fn prompt_timezone(current: Option<&str>) -> Result<Option<String>> {
+    let system = match system_timezone() {
+        Some(name) => format!("{} ({})", SYSTEM_TIMEZONE, name),
+        None => SYSTEM_TIMEZONE.to_string(),
+    };
+    let mut options = vec![system];
+    options.extend(
+        chrono_tz::TZ_VARIANTS
+            .iter()
+            .map(|tz| tz.name().to_string()),
+    );
+
+    let cursor = current
+        .and_then(|name| options.iter().position(|option| option == name))
+        .unwrap_or(0);
+    let choice = inquire::Select::new("Timezone of your activity (type to filter):", options)
+        .with_starting_cursor(cursor)
+        .prompt()?;
+
+    if choice.starts_with(SYSTEM_TIMEZONE) {
+        return Ok(None);
+    }
+    parse_timezone(&choice)?;
+    Ok(Some(choice))
+}
+
+/// Window of the month before `today`, the last complete one.
+fn taste_window(today: NaiveDate) -> String {
+    let (year, month) = if today.month() == 1 {
+        (today.year() - 1, 12)
+    } else {
+        (today.year(), today.month() - 1)
+    };
+    format!("{}-{:02}", year, month)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_taste_window() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(taste_window(date(2025, 10, 17)), "2025-09");
+        assert_eq!(taste_window(date(2026, 1, 3)), "2025-12");
+    }
+}
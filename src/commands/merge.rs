@@ -0,0 +1,146 @@
+/// Merge account directories logged into the same user ID
+///
+/// Logging in twice with different spellings of a user ID or server leaves two
+/// directories for one account, which would be crawled and counted twice. The
+/// latest login is kept, since the stored credentials belong to it, under the
+/// directory named after the user ID. Stats files and reports of earlier logins
+/// are moved over unless the latest login has files of the same name; their
+/// sessions, SDK stores and crawl metadata are dropped, as crawl metadata only
+/// describes the events of its own SDK store.
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::account_selector::{AccountSelector, DuplicateAccount};
+use crate::commands::login::{account_id_to_dirname, resolve_data_root};
+
+/// Entries of an account directory that belong to its login.
+const LOGIN_ENTRIES: &[&str] = &["meta", "sdk", "db.sqlite"];
+
+/// Run the merge command
+pub fn run(user_id: Option<String>) -> Result<()> {
+    let duplicates: Vec<DuplicateAccount> = AccountSelector::duplicate_accounts()?
+        .into_iter()
+        .filter(|duplicate| user_id.as_ref().is_none_or(|uid| *uid == duplicate.user_id))
+        .collect();
+    if duplicates.is_empty() {
+        eprintln!("✅ No account is logged in from several directories");
+        return Ok(());
+    }
+
+    let accounts_root = resolve_data_root()?.join("accounts");
+    for duplicate in &duplicates {
+        eprintln!("👥 {}", duplicate.user_id);
+        eprintln!("  Latest login: {}", duplicate.latest.display());
+        for other in &duplicate.others {
+            eprintln!("  Earlier login: {}", other.display());
+        }
+
+        let confirmed = inquire::Confirm::new(
+            "Merge them? Earlier logins are removed, their stats files and reports are kept",
+        )
+        .with_default(false)
+        .prompt()?;
+        if !confirmed {
+            eprintln!("  Skipped");
+            continue;
+        }
+
+        let account_dir = merge(duplicate, &accounts_root)?;
+        eprintln!("  ✅ Merged into {}", account_dir.display());
+    }
+    Ok(())
+}
+
+/// Merges the earlier logins of `duplicate` into its latest one, stored under
+/// the directory named after the user ID. Returns that directory.
+fn merge(duplicate: &DuplicateAccount, accounts_root: &Path) -> Result<PathBuf> {
+    for other in &duplicate.others {
+        for entry in
+            fs::read_dir(other).with_context(|| format!("Failed to read {}", other.display()))?
+        {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name_str = name.to_string_lossy();
+            let target = duplicate.latest.join(&name);
+            if is_login_entry(&name_str) || target.exists() {
+                continue;
+            }
+            fs::rename(entry.path(), &target)
+                .with_context(|| format!("Failed to move {}", entry.path().display()))?;
+            eprintln!("  ✓ Kept {}", name_str);
+        }
+        fs::remove_dir_all(other)
+            .with_context(|| format!("Failed to remove {}", other.display()))?;
+        eprintln!("  ✓ Removed {}", other.display());
+    }
+
+    let account_dir = accounts_root.join(account_id_to_dirname(&duplicate.user_id));
+    if duplicate.latest != account_dir {
+        if account_dir.exists() {
+            anyhow::bail!(
+                "Cannot move {} to {}: the directory exists",
+                duplicate.latest.display(),
+                account_dir.display()
+            );
+        }
+        fs::rename(&duplicate.latest, &account_dir).with_context(|| {
+            format!(
+                "Failed to move {} to {}",
+                duplicate.latest.display(),
+                account_dir.display()
+            )
+        })?;
+    }
+    Ok(account_dir)
+}
+
+/// Whether an account directory entry belongs to its login (session, SDK
+/// store, crawl metadata and its SQLite side files).
+fn is_login_entry(name: &str) -> bool {
+    LOGIN_ENTRIES
+        .iter()
+        .any(|entry| name == *entry || name.starts_with(&format!("{}-", entry)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_keeps_latest_login_and_stats() {
+        let root = tempfile::tempdir().unwrap();
+        let accounts_root = root.path();
+        let earlier = accounts_root.join("@alice_example.org");
+        let latest = accounts_root.join("@alice_https_example.org");
+        for dir in [&earlier, &latest] {
+            fs::create_dir_all(dir.join("meta")).unwrap();
+            fs::create_dir_all(dir.join("sdk")).unwrap();
+        }
+        fs::write(earlier.join("meta/session.json"), "earlier").unwrap();
+        fs::write(latest.join("meta/session.json"), "latest").unwrap();
+        fs::write(earlier.join("db.sqlite"), "earlier").unwrap();
+        fs::write(earlier.join("stats-2024.json"), "2024").unwrap();
+        fs::write(earlier.join("stats-2025.json"), "old 2025").unwrap();
+        fs::write(latest.join("stats-2025.json"), "2025").unwrap();
+
+        let duplicate = DuplicateAccount {
+            user_id: "@alice:example.org".to_string(),
+            latest: latest.clone(),
+            others: vec![earlier.clone()],
+        };
+        let account_dir = merge(&duplicate, accounts_root).unwrap();
+
+        assert_eq!(account_dir, earlier);
+        assert!(!latest.exists());
+        let read = |name: &str| fs::read_to_string(account_dir.join(name)).unwrap();
+        assert_eq!(read("meta/session.json"), "latest");
+        assert_eq!(read("stats-2024.json"), "2024");
+        assert_eq!(read("stats-2025.json"), "2025");
+        // Crawl metadata of the earlier login is dropped with its SDK store
+        assert!(!account_dir.join("db.sqlite").exists());
+
+        assert!(is_login_entry("db.sqlite-journal"));
+        assert!(!is_login_entry("stats-life.json"));
+    }
+}
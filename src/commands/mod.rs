@@ -1,6 +1,13 @@
+pub mod badge;
+pub mod bench;
 pub mod crawl;
+pub mod dashboard;
+pub mod export;
+pub mod init;
 pub mod login;
 pub mod logout;
+pub mod merge;
 pub mod render;
 pub mod reset;
+pub mod share;
 pub mod status;
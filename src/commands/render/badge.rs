@@ -0,0 +1,193 @@
+/// Summary badge renderer.
+///
+/// Draws a small shields-style SVG (e.g. "2025 | 12,345 messages") to embed in a
+/// profile or README. Self-contained: no external fonts or images.
+use super::common::{format_number, Provenance};
+use super::i18n::Lang;
+use crate::stats::{ScopeKind, Stats};
+use anyhow::{Context, Result};
+use unicode_width::UnicodeWidthStr;
+
+/// A stat that can be shown on a badge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadgeMetric {
+    MessagesSent,
+    ActiveRooms,
+    DaysActive,
+    Reactions,
+}
+
+impl BadgeMetric {
+    pub const ALL: [BadgeMetric; 4] = [
+        BadgeMetric::MessagesSent,
+        BadgeMetric::ActiveRooms,
+        BadgeMetric::DaysActive,
+        BadgeMetric::Reactions,
+    ];
+
+    /// Parses a metric name, as used in the stats file (e.g. `messages_sent`).
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        Self::ALL
+            .into_iter()
+            .find(|metric| metric.name() == input)
+            .with_context(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|m| m.name()).collect();
+                format!(
+                    "Unknown badge metric '{}' (expected one of: {})",
+                    input,
+                    names.join(", ")
+                )
+            })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BadgeMetric::MessagesSent => "messages_sent",
+            BadgeMetric::ActiveRooms => "active_rooms",
+            BadgeMetric::DaysActive => "days_active",
+            BadgeMetric::Reactions => "reactions",
+        }
+    }
+
+    /// Value and unit shown on the badge, if the stats have it.
+    fn value(&self, stats: &Stats) -> Option<(i32, &'static str, &'static str)> {
+        match self {
+            BadgeMetric::MessagesSent => {
+                Some((stats.summary.messages_sent.get(), "message", "messages"))
+            }
+            BadgeMetric::ActiveRooms => Some((
+                stats.summary.active_rooms.get(),
+                "active room",
+                "active rooms",
+            )),
+            BadgeMetric::DaysActive => stats
+                .coverage
+                .days_active
+                .map(|days| (days.get(), "active day", "active days")),
+            BadgeMetric::Reactions => stats
+                .reactions
+                .as_ref()
+                .and_then(|r| r.total)
+                .map(|total| (total, "reaction", "reactions")),
+        }
+    }
+}
+
+/// Renders the badge of a metric (e.g. "2025 | 12,345 messages").
+pub fn render(stats: &Stats, metric: BadgeMetric) -> Result<String> {
+    let (value, singular, plural) = metric
+        .value(stats)
+        .with_context(|| format!("No {} in these stats", metric.name()))?;
+    let unit = if value == 1 { singular } else { plural };
+    Ok(svg(
+        &badge_label(stats),
+        &format!("{} {}", format_number(value), unit),
+        &Provenance::of(stats).line(Lang::En.locale()),
+    ))
+}
+
+/// Badge filename, next to the reports (e.g. `my-year-2025-messages_sent.svg`).
+pub fn filename(stats: &Stats, metric: BadgeMetric) -> String {
+    let base = match stats.scope.kind {
+        ScopeKind::Year => format!("my-year-{}", stats.scope.key),
+        ScopeKind::Quarter => format!("my-quarter-{}", stats.scope.key),
+        ScopeKind::Month => format!("my-month-{}", stats.scope.key),
+        ScopeKind::Week => format!("my-week-{}", stats.scope.key),
+        ScopeKind::Day => format!("my-day-{}", stats.scope.key),
+        ScopeKind::Rolling => format!("my-{}", stats.scope.key),
+        ScopeKind::Life => "my-life".to_string(),
+    };
+    format!("{}-{}.svg", base, metric.name())
+}
+
+/// Short left-hand label: the window key, or "Matrix" for life.
+fn badge_label(stats: &Stats) -> String {
+    match stats.scope.kind {
+        ScopeKind::Life => "Matrix".to_string(),
+        _ => stats.scope.key.clone(),
+    }
+}
+
+/// Flat two-part badge; widths are estimated from the text (about 7px per column at 11px).
+/// `description` goes in the SVG `<desc>`: not drawn, but kept with the file.
+fn svg(label: &str, message: &str, description: &str) -> String {
+    let label_width = label.width() * 7 + 12;
+    let message_width = message.width() * 7 + 12;
+    let width = label_width + message_width;
+    let label = escape(label);
+    let message = escape(message);
+    let description = escape(description);
+
+    format!(
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"20\" role=\"img\" aria-label=\"{label}: {message}\">\n",
+            "<title>{label}: {message}</title>\n",
+            "<desc>{description}</desc>\n",
+            "<clipPath id=\"r\"><rect width=\"{width}\" height=\"20\" rx=\"3\"/></clipPath>\n",
+            "<g clip-path=\"url(#r)\">\n",
+            "<rect width=\"{label_width}\" height=\"20\" fill=\"#555\"/>\n",
+            "<rect x=\"{label_width}\" width=\"{message_width}\" height=\"20\" fill=\"#0dbd8b\"/>\n",
+            "</g>\n",
+            "<g fill=\"#fff\" text-anchor=\"middle\" font-family=\"Verdana,Geneva,DejaVu Sans,sans-serif\" font-size=\"11\">\n",
+            "<text x=\"{label_x}\" y=\"14\">{label}</text>\n",
+            "<text x=\"{message_x}\" y=\"14\">{message}</text>\n",
+            "</g>\n",
+            "</svg>\n"
+        ),
+        width = width,
+        label_width = label_width,
+        message_width = message_width,
+        label_x = label_width / 2,
+        message_x = label_width + message_width / 2,
+        label = label,
+        message = message,
+        description = description,
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::render::common::example_stats;
+
+    #[test]
+    fn test_render_badge() {
+        let stats = example_stats();
+        let svg = render(&stats, BadgeMetric::MessagesSent).unwrap();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains("<title>2025: 4,832 messages</title>"));
+        assert!(svg.contains(">2025</text>"));
+        assert!(svg.contains(">4,832 messages</text>"));
+        assert!(svg.contains("<desc>Generated 2025-12-20 by my 0.1.0 · window 2025 · "));
+        assert_eq!(
+            filename(&stats, BadgeMetric::MessagesSent),
+            "my-year-2025-messages_sent.svg"
+        );
+    }
+
+    #[test]
+    fn test_missing_metric() {
+        let mut stats = example_stats();
+        stats.coverage.days_active = None;
+        assert!(render(&stats, BadgeMetric::DaysActive).is_err());
+    }
+
+    #[test]
+    fn test_parse_metric() {
+        assert_eq!(
+            BadgeMetric::parse("active_rooms").unwrap(),
+            BadgeMetric::ActiveRooms
+        );
+        let err = BadgeMetric::parse("likes").unwrap_err().to_string();
+        assert!(err.contains("messages_sent, active_rooms, days_active, reactions"));
+    }
+}
@@ -0,0 +1,423 @@
+/// Shareable recap card renderer.
+///
+/// Draws a 1080x1920 PNG (story format) with the headline numbers of a report:
+/// messages sent, top room, peak day and top emoji. Text is drawn with an 8x8
+/// bitmap font scaled up, so no font files are needed; characters it cannot
+/// draw are dropped, and the top emoji is shown by its shortcode.
+use super::common::{format_number, scope_label, Provenance};
+use super::i18n::Lang;
+use crate::stats::Stats;
+use anyhow::{Context, Result};
+use font8x8::legacy::{BASIC_LEGACY, LATIN_LEGACY};
+
+pub const WIDTH: usize = 1080;
+pub const HEIGHT: usize = 1920;
+const MARGIN: usize = 96;
+const GLYPH: usize = 8;
+
+type Rgb = [u8; 3];
+
+/// Color theme of the card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CardTheme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl CardTheme {
+    /// Parses a theme name (`dark` or `light`).
+    pub fn parse(input: &str) -> Result<Self> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "dark" => Ok(CardTheme::Dark),
+            "light" => Ok(CardTheme::Light),
+            _ => anyhow::bail!("Unknown card theme '{}' (expected dark or light)", input),
+        }
+    }
+
+    fn palette(&self) -> Palette {
+        match self {
+            CardTheme::Dark => Palette {
+                background: [17, 19, 26],
+                panel: [32, 36, 48],
+                text: [242, 243, 247],
+                muted: [150, 156, 172],
+                accent: [13, 189, 139],
+            },
+            CardTheme::Light => Palette {
+                background: [250, 250, 247],
+                panel: [234, 237, 242],
+                text: [24, 27, 34],
+                muted: [98, 104, 116],
+                accent: [0, 133, 97],
+            },
+        }
+    }
+}
+
+struct Palette {
+    background: Rgb,
+    panel: Rgb,
+    text: Rgb,
+    muted: Rgb,
+    accent: Rgb,
+}
+
+/// A highlighted stat on the card (e.g. "TOP ROOM" / "Friends" / "1,234 messages").
+struct Highlight {
+    label: &'static str,
+    value: String,
+    detail: String,
+}
+
+/// Render stats to a PNG recap card
+pub fn render(stats: &Stats, theme: CardTheme) -> Result<Vec<u8>> {
+    let palette = theme.palette();
+    let mut canvas = Canvas::new(WIDTH, HEIGHT, palette.background);
+    let content_width = WIDTH - 2 * MARGIN;
+
+    // Accent stripe at the top
+    canvas.fill_rect(0, 0, WIDTH, 24, palette.accent);
+
+    // Card labels are English only, so is the scope
+    let scope_label = scope_label(&stats.scope, Lang::En.locale());
+    let mut y = 160;
+    canvas.text(
+        MARGIN,
+        y,
+        &scope_label.to_uppercase(),
+        fit_scale(&scope_label, content_width, 8),
+        palette.accent,
+    );
+    y += 8 * GLYPH + 32;
+
+    let name = stats
+        .account
+        .display_name
+        .as_deref()
+        .unwrap_or(&stats.account.user_id);
+    canvas.text(
+        MARGIN,
+        y,
+        name,
+        fit_scale(name, content_width, 4),
+        palette.muted,
+    );
+    y += 4 * GLYPH + 120;
+
+    let messages = format_number(stats.summary.messages_sent.get());
+    let scale = fit_scale(&messages, content_width, 22);
+    canvas.text(MARGIN, y, &messages, scale, palette.text);
+    y += scale * GLYPH + 32;
+    canvas.text(MARGIN, y, "messages sent", 5, palette.muted);
+    y += 5 * GLYPH + 120;
+
+    let panel_height = 280;
+    for highlight in highlights(stats) {
+        canvas.fill_rect(MARGIN, y, content_width, panel_height, palette.panel);
+        let inner_x = MARGIN + 48;
+        let inner_width = content_width - 96;
+
+        canvas.text(inner_x, y + 48, highlight.label, 3, palette.accent);
+        let value = truncate(&highlight.value, inner_width / (GLYPH * 6));
+        canvas.text(
+            inner_x,
+            y + 104,
+            &value,
+            fit_scale(&value, inner_width, 9),
+            palette.text,
+        );
+        canvas.text(inner_x, y + 200, &highlight.detail, 4, palette.muted);
+
+        y += panel_height + 40;
+    }
+
+    canvas.text(
+        MARGIN,
+        HEIGHT - 120,
+        &stats.account.user_id,
+        fit_scale(&stats.account.user_id, content_width, 3),
+        palette.muted,
+    );
+    let footer = footer(stats);
+    canvas.text(
+        MARGIN,
+        HEIGHT - 72,
+        &footer,
+        fit_scale(&footer, content_width, 2),
+        palette.muted,
+    );
+
+    canvas.encode()
+}
+
+/// Short provenance line under the account: tool, window, coverage and date.
+fn footer(stats: &Stats) -> String {
+    let provenance = Provenance::of(stats);
+    format!(
+        "{} · {} · {} to {} · generated {}",
+        provenance.tool,
+        provenance.window,
+        provenance.coverage_from,
+        provenance.coverage_to,
+        provenance.generated_at
+    )
+}
+
+/// Top room, peak day and top emoji, when the stats have them.
+fn highlights(stats: &Stats) -> Vec<Highlight> {
+    let mut highlights = Vec::new();
+
+    if let Some(room) = stats
+        .rooms
+        .as_ref()
+        .and_then(|r| r.top.as_ref())
+        .and_then(|top| top.first())
+    {
+        let name = room.name.as_deref().unwrap_or("(unnamed room)");
+        highlights.push(Highlight {
+            label: "TOP ROOM",
+            // Names made only of emoji would come out blank
+            value: if drawable(name).trim().is_empty() {
+                "(unnamed room)".to_string()
+            } else {
+                drawable(name).trim().to_string()
+            },
+            detail: format!("{} messages", format_number(room.messages.get())),
+        });
+    }
+
+    if let Some(day) = stats.summary.peaks.as_ref().and_then(|p| p.day.as_ref()) {
+        let value = chrono::NaiveDate::parse_from_str(&day.day, "%Y-%m-%d")
+            .map(|date| date.format("%b %-d, %Y").to_string())
+            .unwrap_or_else(|_| day.day.clone());
+        highlights.push(Highlight {
+            label: "PEAK DAY",
+            value,
+            detail: format!("{} messages", format_number(day.messages)),
+        });
+    }
+
+    if let Some(emoji) = stats
+        .reactions
+        .as_ref()
+        .and_then(|r| r.top_emojis.as_ref())
+        .and_then(|top| top.first())
+    {
+        highlights.push(Highlight {
+            label: "TOP EMOJI",
+            value: match (&emoji.shortcode, emoji.is_custom()) {
+                (Some(shortcode), true) => format!(":{}:", shortcode),
+                (None, true) => ":custom:".to_string(),
+                _ => emoji_shortcode(&emoji.emoji),
+            },
+            detail: format!("{} reactions", format_number(emoji.count)),
+        });
+    }
+
+    highlights
+}
+
+/// Shortcode for common reaction emoji (":joy:"), or the code points otherwise.
+fn emoji_shortcode(emoji: &str) -> String {
+    // Variation selectors don't change which emoji it is
+    let base: String = emoji.chars().filter(|c| *c != '\u{FE0F}').collect();
+    let name = match base.as_str() {
+        "👍" => "+1",
+        "👎" => "-1",
+        "❤" => "heart",
+        "😂" => "joy",
+        "🤣" => "rofl",
+        "😄" => "smile",
+        "😊" => "blush",
+        "😍" => "heart_eyes",
+        "😮" => "open_mouth",
+        "😢" => "cry",
+        "😭" => "sob",
+        "🙏" => "pray",
+        "🎉" => "tada",
+        "🔥" => "fire",
+        "👀" => "eyes",
+        "🚀" => "rocket",
+        "👏" => "clap",
+        "💯" => "100",
+        "✅" => "white_check_mark",
+        "🤔" => "thinking",
+        "🙂" => "slightly_smiling_face",
+        "😅" => "sweat_smile",
+        "🥳" => "partying_face",
+        _ => {
+            if drawable(&base) == base && !base.is_empty() {
+                return base;
+            }
+            return base
+                .chars()
+                .map(|c| format!("U+{:04X}", c as u32))
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+    };
+    format!(":{}:", name)
+}
+
+/// Keeps only the characters the bitmap font can draw.
+fn drawable(text: &str) -> String {
+    text.chars().filter(|c| glyph(*c).is_some()).collect()
+}
+
+/// Shortens text to `max_chars`, marking the cut with "...".
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+    format!("{}...", kept.trim_end())
+}
+
+/// Largest scale (up to `max_scale`) at which `text` fits in `width` pixels.
+fn fit_scale(text: &str, width: usize, max_scale: usize) -> usize {
+    let chars = drawable(text).chars().count().max(1);
+    (width / (chars * GLYPH)).clamp(1, max_scale)
+}
+
+/// Bitmap of a character (rows top to bottom, bit 0 is the leftmost pixel).
+fn glyph(c: char) -> Option<[u8; 8]> {
+    let code = c as usize;
+    match code {
+        0x20..=0x7E => Some(BASIC_LEGACY[code]),
+        0xA0..=0xFF => Some(LATIN_LEGACY[code - 0xA0]),
+        _ => None,
+    }
+}
+
+/// RGB pixel buffer.
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize, background: Rgb) -> Self {
+        let pixels = background.repeat(width * height);
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, color: Rgb) {
+        for row in y..(y + h).min(self.height) {
+            for col in x..(x + w).min(self.width) {
+                let offset = (row * self.width + col) * 3;
+                self.pixels[offset..offset + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Draws text with its top-left corner at (x, y), each font pixel `scale` pixels wide.
+    fn text(&mut self, x: usize, y: usize, text: &str, scale: usize, color: Rgb) {
+        let mut cursor = x;
+        for c in text.chars() {
+            let Some(bitmap) = glyph(c) else {
+                continue;
+            };
+            for (row, bits) in bitmap.iter().enumerate() {
+                for col in 0..GLYPH {
+                    if bits & (1 << col) != 0 {
+                        self.fill_rect(cursor + col * scale, y + row * scale, scale, scale, color);
+                    }
+                }
+            }
+            cursor += GLYPH * scale;
+        }
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut png = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut png, self.width as u32, self.height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.set_compression(png::Compression::High);
+            let mut writer = encoder
+                .write_header()
+                .context("Failed to write PNG header")?;
+            writer
+                .write_image_data(&self.pixels)
+                .context("Failed to write PNG data")?;
+        }
+        Ok(png)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::render::common::example_stats;
+
+    fn decode(png: &[u8]) -> (png::OutputInfo, Vec<u8>) {
+        let mut reader = png::Decoder::new(std::io::Cursor::new(png))
+            .read_info()
+            .unwrap();
+        let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        (info, buf)
+    }
+
+    #[test]
+    fn test_render_card_dimensions_and_theme() {
+        let stats = example_stats();
+
+        for theme in [CardTheme::Dark, CardTheme::Light] {
+            let png = render(&stats, theme).unwrap();
+            let (info, pixels) = decode(&png);
+            assert_eq!((info.width, info.height), (WIDTH as u32, HEIGHT as u32));
+            // Bottom-right corner is plain background
+            let last = pixels.len() - 3;
+            assert_eq!(pixels[last..], theme.palette().background);
+        }
+    }
+
+    #[test]
+    fn test_footer() {
+        let footer = footer(&example_stats());
+        assert_eq!(
+            footer,
+            "my 0.1.0 · 2025 · 2025-01-02 to 2025-12-19 · generated 2025-12-20"
+        );
+        // Fits on the card at the smallest scale
+        assert_eq!(drawable(&footer), footer);
+        assert!(footer.chars().count() * GLYPH <= WIDTH - 2 * MARGIN);
+    }
+
+    #[test]
+    fn test_highlights() {
+        let stats = example_stats();
+        let labels: Vec<&str> = highlights(&stats).iter().map(|h| h.label).collect();
+        assert_eq!(labels, vec!["TOP ROOM", "PEAK DAY", "TOP EMOJI"]);
+    }
+
+    #[test]
+    fn test_emoji_shortcode() {
+        assert_eq!(emoji_shortcode("😂"), ":joy:");
+        assert_eq!(emoji_shortcode("❤️"), ":heart:");
+        assert_eq!(emoji_shortcode("🦀"), "U+1F980");
+        assert_eq!(emoji_shortcode("+1"), "+1");
+    }
+
+    #[test]
+    fn test_parse_theme() {
+        assert_eq!(CardTheme::parse("Light").unwrap(), CardTheme::Light);
+        assert!(CardTheme::parse("neon").is_err());
+    }
+
+    #[test]
+    fn test_truncate_and_fit() {
+        assert_eq!(truncate("Project X", 20), "Project X");
+        assert_eq!(truncate("A very long room name", 10), "A very...");
+        assert_eq!(fit_scale("12,345", 888, 22), 18);
+        assert_eq!(fit_scale("1", 888, 22), 22);
+    }
+}
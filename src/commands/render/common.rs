@@ -0,0 +1,1031 @@
+/// Formatting helpers shared by the renderers.
+///
+/// Keeps wording and number formatting identical across output formats; each
+/// renderer only decides on layout and markup.
+use super::i18n::{fill, Locale};
+use crate::stats::*;
+use crate::window::RollingUnit;
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::Path;
+
+/// A Fun section entry, formatted for display.
+pub struct FunEntry {
+    pub emoji: &'static str,
+    /// Bold label before the value; `None` when the value reads as a full sentence
+    pub label: Option<String>,
+    pub value: String,
+}
+
+/// Formats the Fun fields in insertion order, skipping the typing time headline
+/// (see [`typing_headline`]) and values that cannot be displayed.
+pub fn fun_entries(fun: &Fun, t: &Locale) -> Vec<FunEntry> {
+    let mut entries = Vec::new();
+
+    for (key, value) in &fun.fields {
+        if key == "typing_minutes" {
+            continue;
+        }
+        // Durations are formatted from their unit suffix, which the label then omits
+        let unit = ["_seconds", "_minutes", "_days"]
+            .into_iter()
+            .find(|suffix| key.ends_with(suffix));
+        let display_key = match t.fun_label(key) {
+            Some(label) => label.to_string(),
+            None => {
+                let name = unit.map_or(key.as_str(), |suffix| &key[..key.len() - suffix.len()]);
+                uppercase_first_char(&name.replace('_', " "))
+            }
+        };
+
+        let formatted_value = match value {
+            serde_json::Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    if let Some(suffix) = unit {
+                        match suffix {
+                            "_seconds" => t.duration(i),
+                            "_minutes" => t.minutes(i),
+                            _ => t.days(i),
+                        }
+                    } else if key == "first_responder_replies" {
+                        fill(t.first_to_reply, &[("n", &t.number(i as i32))])
+                    } else if key == "emote_messages" {
+                        fill(t.emote_messages, &[("n", &t.number(i as i32))])
+                    } else if key == "conversation_starts" {
+                        fill(t.conversation_starts, &[("n", &t.number(i as i32))])
+                    } else if key == "timezones" {
+                        fill(t.timezones, &[("n", &t.number(i as i32))])
+                    } else {
+                        t.number(i as i32)
+                    }
+                } else if let Some(f) = n.as_f64() {
+                    // Special handling for reactions_per_message
+                    if key == "reactions_per_message" {
+                        if f > 0.0 {
+                            let messages_per_reaction = 1.0 / f;
+                            fill(
+                                t.react_every,
+                                &[("n", &t.decimal(messages_per_reaction, 0))],
+                            )
+                        } else {
+                            t.react_never.to_string()
+                        }
+                    } else if key.ends_with("_per_message") || key.ends_with("_ratio") {
+                        t.percent(f, 1)
+                    } else {
+                        t.decimal(f, 2)
+                    }
+                } else {
+                    n.to_string()
+                }
+            }
+            serde_json::Value::String(s) if key == "favorite_weekday" => t.stats_weekday(s),
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Object(_) if key == "reaction_variety" => {
+                match value.get("emojis").and_then(|n| n.as_i64()) {
+                    Some(n) => fill(t.reaction_variety, &[("n", &t.number(n as i32))]),
+                    None => continue,
+                }
+            }
+            serde_json::Value::Object(_) if key == "longest_silence" => {
+                match format_silence(value, t) {
+                    Some(text) => text,
+                    None => continue,
+                }
+            }
+            serde_json::Value::Object(_) if key == "message_twins" => {
+                match format_message_twins(value, t) {
+                    Some(text) => text,
+                    None => continue,
+                }
+            }
+            serde_json::Value::Array(packs) if key == "favorite_packs" => {
+                match format_packs(packs, t) {
+                    Some(text) => text,
+                    None => continue,
+                }
+            }
+            _ => value.to_string(),
+        };
+
+        // Add emoji based on field type
+        let emoji = match key.as_str() {
+            "longest_message_chars" => "📝",
+            "favorite_weekday" => "📅",
+            "peak_hour" => "🕐",
+            "longest_streak_days" => "🔥",
+            "current_streak_days" => "⚡",
+            "longest_silence" => "🤫",
+            "reactions_per_message" => "😊",
+            "edits_per_message" => "✏️",
+            "emote_messages" => "🎭",
+            "crawl_duration_seconds" => "⏱️",
+            "lurking_rooms" => "👀",
+            "sent_encrypted_messages_ratio" => "🔐",
+            "first_responder_replies" => "🚑",
+            "conversation_starts" => "🌅",
+            "message_twins" => "👯",
+            "reaction_variety" => "🌈",
+            "matrix_age_days" => "🎂",
+            "timezones" => "🌍",
+            "favorite_packs" => "🖼️",
+            _ => "✨",
+        };
+
+        // Some facts read as full sentences
+        let entry = if matches!(
+            key.as_str(),
+            "reactions_per_message"
+                | "reaction_variety"
+                | "conversation_starts"
+                | "emote_messages"
+                | "longest_silence"
+        ) {
+            FunEntry {
+                emoji,
+                label: None,
+                value: formatted_value,
+            }
+        } else {
+            FunEntry {
+                emoji,
+                label: Some(display_key),
+                value: formatted_value,
+            }
+        };
+
+        entries.push(entry);
+    }
+
+    entries
+}
+
+/// Headline sentence for the estimated typing time, if present.
+pub fn typing_headline(fun: &Fun, t: &Locale) -> Option<String> {
+    let minutes = fun.fields.get("typing_minutes")?.as_i64()?;
+    Some(fill(t.typing_headline, &[("time", &t.minutes(minutes))]))
+}
+
+/// Formats the favorite image packs as "Team emotes (52), Cats (18)".
+fn format_packs(packs: &[serde_json::Value], t: &Locale) -> Option<String> {
+    let packs: Vec<String> = packs
+        .iter()
+        .filter_map(|pack| {
+            let name = pack.get("pack")?.as_str()?;
+            let uses = pack.get("uses")?.as_i64()?;
+            Some(format!("{} ({})", name, t.number(uses as i32)))
+        })
+        .collect();
+    (!packs.is_empty()).then(|| packs.join(", "))
+}
+
+/// Formats the message twins pair as "A & B (92% alike hours)".
+fn format_message_twins(value: &serde_json::Value, t: &Locale) -> Option<String> {
+    let rooms = value.get("rooms")?.as_array()?;
+    let (first, second) = (rooms.first()?.as_str()?, rooms.get(1)?.as_str()?);
+    let similarity = value.get("similarity")?.as_f64()?;
+    Some(fill(
+        t.twins,
+        &[
+            ("first", first),
+            ("second", second),
+            ("percent", &t.percent(similarity, 0)),
+        ],
+    ))
+}
+
+/// Formats the longest silence as "Your quietest stretch was 23 days (Jun 2 – Jun 24)".
+fn format_silence(value: &serde_json::Value, t: &Locale) -> Option<String> {
+    use chrono::Datelike;
+    let days = value.get("days")?.as_i64()?;
+    let date = |field: &str| {
+        chrono::NaiveDate::parse_from_str(value.get(field)?.as_str()?, "%Y-%m-%d").ok()
+    };
+    let (from, to) = (date("from")?, date("to")?);
+    // The year is only needed when the silence spans two
+    let format = |date| {
+        if from.year() == to.year() {
+            t.short_date(date)
+        } else {
+            t.long_date(date)
+        }
+    };
+    let range = if from == to {
+        format(from)
+    } else {
+        format!("{} – {}", format(from), format(to))
+    };
+    Some(fill(
+        t.longest_silence,
+        &[("days", &t.days(days)), ("range", &range)],
+    ))
+}
+
+/// Avatar image of the account or of a room, stored by the crawl next to the stats file.
+pub struct Avatar {
+    pub file_name: String,
+    pub bytes: Vec<u8>,
+}
+
+impl Avatar {
+    /// Reads the `avatar_file` of the stats from `stats_dir`, if the stats have one.
+    pub fn load(stats: &Stats, stats_dir: &Path) -> Result<Option<Self>> {
+        match stats.account.avatar_file.as_deref() {
+            Some(file_name) => Self::read(file_name, stats_dir).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the avatar file `file_name` from `stats_dir`.
+    pub fn read(file_name: &str, stats_dir: &Path) -> Result<Self> {
+        // A bare file name: stats files must not point elsewhere on disk
+        if Path::new(file_name).file_name() != Some(OsStr::new(file_name)) {
+            anyhow::bail!("Invalid avatar file name: {}", file_name);
+        }
+        let path = stats_dir.join(file_name);
+        let bytes = std::fs::read(&path)
+            .with_context(|| format!("Failed to read avatar: {}", path.display()))?;
+        Ok(Self {
+            file_name: file_name.to_string(),
+            bytes,
+        })
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match Path::new(&self.file_name)
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("jpg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            _ => "image/png",
+        }
+    }
+
+    /// The image as a `data:` URI, to embed in a self-contained page.
+    pub fn data_uri(&self) -> String {
+        format!(
+            "data:{};base64,{}",
+            self.mime(),
+            STANDARD.encode(&self.bytes)
+        )
+    }
+}
+
+/// Images a report shows: the account avatar and the avatars of the top rooms,
+/// by file name.
+#[derive(Default)]
+pub struct Avatars {
+    pub account: Option<Avatar>,
+    pub rooms: HashMap<String, Avatar>,
+}
+
+impl Avatars {
+    /// Avatar of a top room, when its file was loaded.
+    pub fn room(&self, room: &RoomEntry) -> Option<&Avatar> {
+        room.avatar_file
+            .as_deref()
+            .and_then(|file_name| self.rooms.get(file_name))
+    }
+}
+
+/// Describes partial room coverage from the user's join date, at a precision fitting the scope.
+pub fn joined_phrase(date: &str, scope: &Scope, t: &Locale) -> Option<String> {
+    use chrono::Datelike;
+
+    let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let when = match scope.kind {
+        ScopeKind::Year => fill(t.joined_in, &[("month", t.month_long(date.month()))]),
+        ScopeKind::Life => {
+            let month = fill(
+                t.month_year,
+                &[
+                    ("month", t.month_long(date.month())),
+                    ("year", &date.year().to_string()),
+                ],
+            );
+            fill(t.joined_in, &[("month", &month)])
+        }
+        ScopeKind::Quarter
+        | ScopeKind::Month
+        | ScopeKind::Week
+        | ScopeKind::Day
+        | ScopeKind::Rolling => fill(t.joined_on, &[("date", &t.short_date(date))]),
+    };
+    Some(fill(t.joined_since, &[("when", &when)]))
+}
+
+/// Coverage note for rooms whose history starts at the user's join.
+pub fn since_joined_note(count: i32, t: &Locale) -> String {
+    let template = if count == 1 {
+        t.since_joined_one
+    } else {
+        t.since_joined_other
+    };
+    fill(template, &[("count", &count.to_string())])
+}
+
+/// Share of a DM's messages the user sent (e.g. "61%"), "-" when nobody wrote.
+pub fn dm_sent_share(entry: &DmBalanceEntry, t: &Locale) -> String {
+    let total = entry.sent + entry.received;
+    if total <= 0 {
+        return "-".to_string();
+    }
+    t.percent(entry.sent as f64 / total as f64, 0)
+}
+
+/// Change between the halves of the window a top room needs for a trend arrow.
+const TREND_MIN_CHANGE: f64 = 0.25;
+/// Messages in both halves below which a room shows no trend, as noise.
+const TREND_MIN_MESSAGES: i32 = 10;
+
+/// Trend arrow of a top room with its description, e.g. ("↑", "120 messages in
+/// the first half, 340 in the second"): ↑ when the second half of the window
+/// has at least a quarter more messages than the first, ↓ a quarter fewer.
+pub fn room_trend(entry: &RoomEntry, t: &Locale) -> Option<(&'static str, String)> {
+    let trend = entry.trend?;
+    if trend.first_half.get() + trend.second_half.get() < TREND_MIN_MESSAGES {
+        return None;
+    }
+    let (first, second) = (
+        trend.first_half.get() as f64,
+        trend.second_half.get() as f64,
+    );
+    let arrow = if second >= first * (1.0 + TREND_MIN_CHANGE) {
+        "↑"
+    } else if second <= first * (1.0 - TREND_MIN_CHANGE) {
+        "↓"
+    } else {
+        return None;
+    };
+    let description = fill(
+        t.room_trend,
+        &[
+            ("first", &t.number(trend.first_half.get())),
+            ("second", &t.number(trend.second_half.get())),
+        ],
+    );
+    Some((arrow, description))
+}
+
+/// Legend of the trend arrows, when some of the top rooms have one.
+pub fn trend_note(top: &[RoomEntry], t: &Locale) -> Option<String> {
+    top.iter()
+        .any(|room| room_trend(room, t).is_some())
+        .then(|| t.trend_note.to_string())
+}
+
+/// Changes of the headline numbers against the previous window, e.g. "▲ 18% vs 2024".
+/// Only set with `--compare-previous` and a previous count to compare with.
+#[derive(Default)]
+pub struct PreviousDeltas {
+    pub messages_sent: Option<String>,
+    pub active_days: Option<String>,
+    pub reactions: Option<String>,
+}
+
+pub fn previous_deltas(stats: &Stats, t: &Locale) -> PreviousDeltas {
+    let Some(previous) = &stats.previous else {
+        return PreviousDeltas::default();
+    };
+    let delta = |current: Option<i32>, before: Option<i32>| {
+        previous_delta(current?, before?, &previous.key, t)
+    };
+    PreviousDeltas {
+        messages_sent: delta(
+            Some(stats.summary.messages_sent.get()),
+            Some(previous.messages_sent),
+        ),
+        active_days: delta(
+            stats.coverage.days_active.map(Count::get),
+            previous.days_active,
+        ),
+        reactions: delta(
+            stats
+                .reactions
+                .as_ref()
+                .and_then(|reactions| reactions.total),
+            previous.reactions,
+        ),
+    }
+}
+
+fn previous_delta(current: i32, before: i32, key: &str, t: &Locale) -> Option<String> {
+    if before <= 0 {
+        return None;
+    }
+    let arrow = match current.cmp(&before) {
+        std::cmp::Ordering::Greater => "▲",
+        std::cmp::Ordering::Less => "▼",
+        std::cmp::Ordering::Equal => "=",
+    };
+    let change = (current - before).abs() as f64 / before as f64;
+    Some(fill(
+        t.vs_previous,
+        &[
+            ("change", &format!("{} {}", arrow, t.percent(change, 0))),
+            ("previous", key),
+        ],
+    ))
+}
+
+/// Text shown for a reaction: the emoji itself, or `:shortcode:` for a custom
+/// emote, never its mxc URL.
+pub fn emoji_label(entry: &EmojiEntry, t: &Locale) -> String {
+    if !entry.is_custom() {
+        return entry.emoji.clone();
+    }
+    match (&entry.shortcode, &entry.pack) {
+        (Some(shortcode), Some(pack)) => format!(":{}: ({})", shortcode, pack),
+        (Some(shortcode), None) => format!(":{}:", shortcode),
+        (None, _) => t.custom_emote.to_string(),
+    }
+}
+
+/// Label of the first of ranked emojis, empty when there is none.
+pub fn favorite_emoji(entries: &[EmojiEntry], t: &Locale) -> String {
+    entries
+        .first()
+        .map(|entry| emoji_label(entry, t))
+        .unwrap_or_default()
+}
+
+/// Share of the user's messages sent inside threads (e.g. "25%").
+pub fn thread_share(conversations: &Conversations, t: &Locale) -> String {
+    let total = conversations.top_level + conversations.in_threads;
+    if total <= 0 {
+        return t.percent(0.0, 0);
+    }
+    t.percent(conversations.in_threads as f64 / total as f64, 0)
+}
+
+/// Share of the user's text messages counted in `count` (e.g. "40%").
+pub fn length_share(lengths: &MessageLengths, count: i32, t: &Locale) -> String {
+    let total = lengths.short + lengths.medium + lengths.long;
+    if total <= 0 {
+        return t.percent(0.0, 0);
+    }
+    t.percent(count as f64 / total as f64, 0)
+}
+
+/// Length of a message in characters (e.g. "1,024 characters").
+pub fn characters(chars: i32, t: &Locale) -> String {
+    fill(t.characters, &[("count", &t.number(chars))])
+}
+
+/// Name shown for a person: their display name, else their user ID.
+pub fn person_name(person: &PersonEntry) -> &str {
+    person
+        .display_name
+        .as_deref()
+        .or(person.user_id.as_deref())
+        .unwrap_or_default()
+}
+
+/// Context for the "rooms you created" sentence (e.g. "this year").
+pub fn created_rooms_context(scope: &Scope, t: &Locale) -> String {
+    let context = match scope.kind {
+        ScopeKind::Year => t.created_year,
+        ScopeKind::Quarter => t.created_quarter,
+        ScopeKind::Month => t.created_month,
+        ScopeKind::Week => t.created_week,
+        ScopeKind::Day => t.created_day,
+        ScopeKind::Rolling => {
+            return rolling_text(scope, t.created_last_days, t.created_last_months)
+        }
+        ScopeKind::Life => t.created_life,
+    };
+    context.to_string()
+}
+
+pub fn scope_label(scope: &Scope, t: &Locale) -> String {
+    if let Some(label) = &scope.label {
+        return label.clone();
+    }
+
+    let template = match scope.kind {
+        ScopeKind::Year => t.scope_year,
+        ScopeKind::Quarter => t.scope_quarter,
+        ScopeKind::Month => t.scope_month,
+        ScopeKind::Week => t.scope_week,
+        ScopeKind::Day => t.scope_day,
+        ScopeKind::Rolling => return rolling_text(scope, t.scope_last_days, t.scope_last_months),
+        ScopeKind::Life => t.scope_life,
+    };
+    fill(template, &[("key", &scope.key)])
+}
+
+/// Wording of a rolling window from the template of its unit, e.g. "the last
+/// 30 days"; the key itself when it is not a rolling window key.
+fn rolling_text(scope: &Scope, days: &str, months: &str) -> String {
+    match crate::window::parse_rolling(&scope.key) {
+        Some((count, unit)) => {
+            let template = match unit {
+                RollingUnit::Days => days,
+                RollingUnit::Months => months,
+            };
+            fill(template, &[("count", &count.to_string())])
+        }
+        None => scope.key.clone(),
+    }
+}
+
+/// Months (1 to 12) shown by month for `scope`: all of them for a year or
+/// life, the 3 of a quarter, those of a rolling window of months in order
+/// (e.g. Apr to Mar), none for shorter windows.
+pub fn report_months(scope: &Scope) -> Vec<u32> {
+    use chrono::Datelike;
+
+    match scope.kind {
+        ScopeKind::Year | ScopeKind::Life => (1..=12).collect(),
+        ScopeKind::Quarter => crate::window::WindowScope::parse(&scope.key)
+            .map(|window| (window.from.month()..=window.to.month()).collect())
+            .unwrap_or_default(),
+        ScopeKind::Rolling => match crate::window::parse_rolling(&scope.key) {
+            Some((count, RollingUnit::Months)) => {
+                // The window ends this month; a year or more shows each month once
+                let last = chrono::Local::now().month();
+                let count = count.min(12);
+                (0..count)
+                    .map(|i| (last + 12 - count + i) % 12 + 1)
+                    .collect()
+            }
+            _ => Vec::new(),
+        },
+        ScopeKind::Month | ScopeKind::Week | ScopeKind::Day => Vec::new(),
+    }
+}
+
+/// Keeps the first `top` entries of each ranking (rooms, servers, emojis, messages).
+pub fn limit_rankings(stats: &mut Stats, top: usize) {
+    if let Some(rooms) = stats.rooms.as_mut() {
+        if let Some(ranked) = rooms.top.as_mut() {
+            ranked.truncate(top);
+        }
+        if let Some(ranked) = rooms.by_server.as_mut() {
+            ranked.truncate(top);
+        }
+        if let Some(ranked) = rooms.dm_balance.as_mut() {
+            ranked.truncate(top);
+        }
+    }
+    if let Some(people) = stats.people.as_mut() {
+        for ranked in [&mut people.dm_partners, &mut people.group_senders]
+            .into_iter()
+            .flatten()
+        {
+            ranked.truncate(top);
+        }
+    }
+    if let Some(dms) = stats.dms.as_mut() {
+        dms.top.truncate(top);
+    }
+    if let Some(typed) = stats.typed_emojis.as_mut() {
+        typed.top.truncate(top);
+    }
+    if let Some(reactions) = stats.reactions.as_mut() {
+        limit_reactions(reactions, top);
+    }
+    if let Some(breakdowns) = stats.per_room.as_mut() {
+        breakdowns.truncate(top);
+        for reactions in breakdowns.iter_mut().filter_map(|b| b.reactions.as_mut()) {
+            limit_reactions(reactions, top);
+        }
+    }
+}
+
+fn limit_reactions(reactions: &mut Reactions, top: usize) {
+    if let Some(ranked) = reactions.top_emojis.as_mut() {
+        ranked.truncate(top);
+    }
+    if let Some(given) = reactions.given.as_mut() {
+        given.top_emojis.truncate(top);
+    }
+    if let Some(ranked) = reactions.top_messages.as_mut() {
+        ranked.truncate(top);
+    }
+    if let Some(ranked) = reactions.top_rooms.as_mut() {
+        ranked.truncate(top);
+    }
+}
+
+/// Generation date shown by golden reports.
+pub const GOLDEN_GENERATED_AT: &str = "2000-01-01";
+
+/// Pins what varies between renders of the same data, for golden reports.
+///
+/// The generation date becomes [`GOLDEN_GENERATED_AT`] and the tool version is
+/// dropped, so an upgrade only shows in the output when the report itself
+/// changes. Entries tied in a ranking, whose order depends on the crawl, are
+/// ordered by name (emoji, room name, then permalink).
+pub fn make_golden(stats: &mut Stats) {
+    stats.generated_at = GOLDEN_GENERATED_AT.to_string();
+    stats.generator = None;
+
+    if let Some(ranked) = stats.rooms.as_mut().and_then(|rooms| rooms.top.as_mut()) {
+        ranked.sort_by(|a, b| {
+            b.messages
+                .cmp(&a.messages)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.permalink.cmp(&b.permalink))
+        });
+    }
+    if let Some(typed) = stats.typed_emojis.as_mut() {
+        order_emoji_ties(&mut typed.top);
+    }
+    if let Some(reactions) = stats.reactions.as_mut() {
+        order_reaction_ties(reactions);
+    }
+    for reactions in stats
+        .per_room
+        .iter_mut()
+        .flatten()
+        .filter_map(|breakdown| breakdown.reactions.as_mut())
+    {
+        order_reaction_ties(reactions);
+    }
+}
+
+fn order_emoji_ties(ranked: &mut [EmojiEntry]) {
+    ranked.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.emoji.cmp(&b.emoji)));
+}
+
+fn order_reaction_ties(reactions: &mut Reactions) {
+    if let Some(ranked) = reactions.top_emojis.as_mut() {
+        order_emoji_ties(ranked);
+    }
+    if let Some(given) = reactions.given.as_mut() {
+        order_emoji_ties(&mut given.top_emojis);
+    }
+    if let Some(ranked) = reactions.top_messages.as_mut() {
+        ranked.sort_by(|a, b| {
+            b.reaction_count
+                .cmp(&a.reaction_count)
+                .then_with(|| a.permalink.cmp(&b.permalink))
+        });
+    }
+    if let Some(ranked) = reactions.top_rooms.as_mut() {
+        ranked.sort_by(|a, b| {
+            b.reaction_count
+                .cmp(&a.reaction_count)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.permalink.cmp(&b.permalink))
+        });
+    }
+}
+
+/// Stats of one per-room breakdown, shaped for the regular renderers.
+///
+/// The scope label names the room; sections that only make sense account-wide
+/// (top rooms, created rooms, fun facts) are left out.
+pub fn room_report(stats: &Stats, breakdown: &RoomBreakdown, t: &Locale) -> Stats {
+    let room = breakdown.name.as_deref().unwrap_or(t.unnamed_room);
+    Stats {
+        schema_version: stats.schema_version,
+        scope: Scope {
+            label: Some(format!("{} · {}", scope_label(&stats.scope, t), room)),
+            ..stats.scope.clone()
+        },
+        generated_at: stats.generated_at.clone(),
+        generator: stats.generator.clone(),
+        timezone: stats.timezone.clone(),
+        account: stats.account.clone(),
+        coverage: Coverage {
+            days_active: breakdown.days_active,
+            since_joined_rooms: None,
+            ..stats.coverage.clone()
+        },
+        summary: breakdown.summary.clone(),
+        activity: breakdown.activity.clone(),
+        rooms: None,
+        reactions: breakdown.reactions.clone(),
+        created_rooms: None,
+        fun: None,
+        people: None,
+        dms: None,
+        conversations: None,
+        message_lengths: None,
+        typed_emojis: None,
+        per_room: None,
+        previous: None,
+        previous_crawl: None,
+    }
+}
+
+/// Where a report comes from, for the footer of every format.
+///
+/// A report shared months later still tells which tool built it, for which
+/// window, over which data and for whom.
+pub struct Provenance {
+    /// Tool and version that built the stats; just `my` for stats of older versions
+    pub tool: String,
+    pub window: String,
+    pub coverage_from: String,
+    pub coverage_to: String,
+    pub accounts: Vec<String>,
+    pub generated_at: String,
+}
+
+impl Provenance {
+    pub fn of(stats: &Stats) -> Self {
+        Self {
+            tool: stats.generator.clone().unwrap_or_else(|| "my".to_string()),
+            window: stats.scope.key.clone(),
+            coverage_from: stats.coverage.from.clone(),
+            coverage_to: stats.coverage.to.clone(),
+            accounts: vec![stats.account.user_id.clone()],
+            generated_at: stats.generated_at.clone(),
+        }
+    }
+
+    /// Window, coverage and accounts (e.g. "window 2025 · data from … to … · @alice:example.org").
+    pub fn details(&self, t: &Locale) -> String {
+        fill(
+            t.provenance,
+            &[
+                ("window", &self.window),
+                ("from", &self.coverage_from),
+                ("to", &self.coverage_to),
+                ("accounts", &self.accounts.join(", ")),
+            ],
+        )
+    }
+
+    /// The whole footer as plain text: "Generated … by my 0.1.0 · window … ".
+    pub fn line(&self, t: &Locale) -> String {
+        let generated = fill(
+            t.generated_by,
+            &[("date", &self.generated_at), ("tool", &self.tool)],
+        );
+        format!("{} · {}", generated, self.details(t))
+    }
+}
+
+/// Note of a report rendered from re-crawled stats with `--changelog` (e.g.
+/// "Regenerated 2026-01-05 (last generated 2025-12-20): +1,204 newly discovered messages").
+pub fn changelog_note(stats: &Stats, t: &Locale) -> Option<String> {
+    let previous = stats.previous_crawl.as_ref()?;
+    let new_messages = stats.summary.messages_sent.get() - previous.messages_sent;
+    let dates = [
+        ("date", stats.generated_at.as_str()),
+        ("previous", previous.generated_at.as_str()),
+    ];
+    if new_messages <= 0 {
+        return Some(fill(t.regenerated_unchanged, &dates));
+    }
+    let count = format!("+{}", t.number(new_messages));
+    Some(fill(
+        t.regenerated,
+        &[dates[0], dates[1], ("count", &count)],
+    ))
+}
+
+pub fn scope_phrase(scope: &Scope, t: &Locale) -> String {
+    if let Some(label) = &scope.label {
+        return label.clone();
+    }
+
+    let template = match scope.kind {
+        ScopeKind::Year => t.phrase_year,
+        ScopeKind::Quarter => t.phrase_quarter,
+        ScopeKind::Month => t.phrase_month,
+        ScopeKind::Week => t.phrase_week,
+        ScopeKind::Day => t.phrase_day,
+        ScopeKind::Rolling => return rolling_text(scope, t.phrase_last_days, t.phrase_last_months),
+        ScopeKind::Life => t.phrase_life,
+    };
+    fill(template, &[("key", &scope.key)])
+}
+
+/// Intensity levels of the calendar heatmap, including 0 for days without messages.
+pub const HEATMAP_LEVELS: usize = 4;
+
+/// A day of the calendar heatmap.
+pub struct HeatmapDay {
+    pub date: chrono::NaiveDate,
+    pub messages: i32,
+    /// 0 without messages, up to `HEATMAP_LEVELS - 1` for the busiest days
+    pub level: usize,
+}
+
+/// Weeks of a calendar heatmap, Monday first; `None` for days outside the range.
+pub type HeatmapWeek = [Option<HeatmapDay>; 7];
+
+/// Builds the per-day contribution heatmap for year and life scopes.
+///
+/// Years cover January to December; life covers the 52 weeks up to the last
+/// active day. Needs `by_day` keyed by full dates (`YYYY-MM-DD`).
+pub fn calendar_heatmap(scope: &Scope, activity: &Activity) -> Option<Vec<HeatmapWeek>> {
+    use chrono::{Datelike, Duration, NaiveDate};
+
+    let by_day = activity.by_day.as_ref()?;
+    let days: HashMap<NaiveDate, i32> = by_day
+        .iter()
+        .filter_map(|(key, count)| Some((NaiveDate::parse_from_str(key, "%Y-%m-%d").ok()?, *count)))
+        .collect();
+
+    let (start, end) = match scope.kind {
+        ScopeKind::Year => {
+            let year: i32 = scope.key.parse().ok()?;
+            (
+                NaiveDate::from_ymd_opt(year, 1, 1)?,
+                NaiveDate::from_ymd_opt(year, 12, 31)?,
+            )
+        }
+        ScopeKind::Life => {
+            let end = *days.keys().max()?;
+            let last_monday = end - Duration::days(end.weekday().num_days_from_monday() as i64);
+            (last_monday - Duration::weeks(51), end)
+        }
+        _ => return None,
+    };
+
+    let in_range: Vec<i32> = days
+        .iter()
+        .filter(|(date, _)| **date >= start && **date <= end)
+        .map(|(_, count)| *count)
+        .collect();
+    let max = in_range.iter().copied().max().filter(|max| *max > 0)?;
+
+    let mut weeks = Vec::new();
+    let mut monday = start - Duration::days(start.weekday().num_days_from_monday() as i64);
+    while monday <= end {
+        weeks.push(std::array::from_fn(|weekday| {
+            let date = monday + Duration::days(weekday as i64);
+            if date < start || date > end {
+                return None;
+            }
+            let messages = days.get(&date).copied().unwrap_or(0).max(0);
+            // Scale to the busiest day, so any activity shows at least level 1
+            let level = if messages == 0 {
+                0
+            } else {
+                (messages as usize * (HEATMAP_LEVELS - 1)).div_ceil(max as usize)
+            };
+            Some(HeatmapDay {
+                date,
+                messages,
+                level,
+            })
+        }));
+        monday += Duration::weeks(1);
+    }
+
+    Some(weeks)
+}
+
+/// Index of the weeks where a month starts, with the month's short name.
+pub fn heatmap_month_starts(weeks: &[HeatmapWeek], t: &Locale) -> Vec<(usize, &'static str)> {
+    use chrono::Datelike;
+
+    weeks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, week)| {
+            let first = week.iter().flatten().find(|day| day.date.day() == 1)?;
+            Some((index, t.month_short(first.date.month())))
+        })
+        .collect()
+}
+
+/// Partial blocks of a bar, in eighths of a column.
+const EIGHTHS: [&str; 8] = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+
+/// Bar of `value` relative to `max`, in eighths of a column.
+pub fn bar(value: i32, max: i32, width: usize) -> String {
+    if max <= 0 || value <= 0 {
+        return String::new();
+    }
+    let eighths = (value as f64 / max as f64 * (width * 8) as f64).round() as usize;
+    // A non-zero count always shows
+    let eighths = eighths.max(1);
+    format!("{}{}", "█".repeat(eighths / 8), EIGHTHS[eighths % 8])
+}
+
+/// Levels of a sparkline, from quiet to busiest.
+pub const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One block per value, scaled to the largest value; zero is a space.
+pub fn sparkline(values: &[i32]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&value| {
+            if max <= 0 || value <= 0 {
+                ' '
+            } else {
+                SPARKS[((value as f64 / max as f64) * 7.0).round() as usize]
+            }
+        })
+        .collect()
+}
+
+/// Hour marks under a 24-hour sparkline.
+/// Keys of `by_weekday` in the stats, Monday first; labels come from the locale.
+pub const WEEKDAY_KEYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+pub const HOUR_AXIS: &str = "0     6     12    18   23";
+
+/// Room types need this many messages to get a time-of-day persona.
+const MIN_PERSONA_MESSAGES: i32 = 10;
+
+/// Compares when each room type is busiest (e.g. "Public rooms in the afternoon
+/// (peak 14:00), DMs at night (peak 23:00)"), busiest room type first.
+///
+/// Needs `by_room_type_hour` with at least two active room types.
+pub fn time_of_day_personas(activity: &Activity, t: &Locale) -> Option<String> {
+    let by_type = activity.by_room_type_hour.as_ref()?;
+    let mut personas: Vec<(i32, String)> = [
+        ("dm", t.persona_dm),
+        ("public", t.persona_public),
+        ("private", t.persona_private),
+    ]
+    .into_iter()
+    .filter_map(|(key, rooms)| {
+        let hours = by_type.get(key)?;
+        let count = |hour: u32| {
+            hours
+                .get(&format!("{:02}", hour % 24))
+                .copied()
+                .unwrap_or(0)
+        };
+        let total: i32 = (0..24).map(count).sum();
+        if total < MIN_PERSONA_MESSAGES {
+            return None;
+        }
+
+        // Night wraps around midnight (22:00 to 06:00)
+        let parts = [
+            (t.morning, 6..12),
+            (t.afternoon, 12..18),
+            (t.evening, 18..22),
+            (t.night, 22..30),
+        ];
+        let (when, _) = parts
+            .into_iter()
+            .map(|(when, hours)| (when, hours.map(count).sum::<i32>()))
+            .rev()
+            .max_by_key(|(_, messages)| *messages)?;
+        let peak = (0..24).rev().max_by_key(|hour| count(*hour))?;
+        let hour = fill(t.hour_of_day, &[("hour", &format!("{:02}", peak))]);
+
+        Some((
+            total,
+            fill(
+                t.persona,
+                &[("rooms", rooms), ("when", when), ("hour", &hour)],
+            ),
+        ))
+    })
+    .collect();
+
+    if personas.len() < 2 {
+        return None;
+    }
+    personas.sort_by_key(|(total, _)| std::cmp::Reverse(*total));
+    let sentence: Vec<String> = personas.into_iter().map(|(_, persona)| persona).collect();
+    Some(format!("{}.", uppercase_first_char(&sentence.join(", "))))
+}
+
+/// Format a number with thousand separators (raw integers, no abbreviation)
+pub fn format_number(n: i32) -> String {
+    let is_negative = n < 0;
+    // Work with absolute value as i64 to safely handle i32::MIN
+    let abs_str = (n as i64).abs().to_string();
+    let mut grouped_rev = String::new();
+
+    // Insert commas every three digits, starting from the right
+    for (count, ch) in abs_str.chars().rev().enumerate() {
+        if count > 0 && count.is_multiple_of(3) {
+            grouped_rev.push(',');
+        }
+        grouped_rev.push(ch);
+    }
+
+    // Reverse back to normal order
+    let mut formatted: String = grouped_rev.chars().rev().collect();
+    if is_negative {
+        formatted.insert(0, '-');
+    }
+    formatted
+}
+
+/// Uppercase the first character of a string
+fn uppercase_first_char(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+    }
+}
+
+/// Stats of the bundled example, shared by the tests of each format.
+#[cfg(test)]
+pub(crate) fn example_stats() -> Stats {
+    serde_json::from_str(include_str!("../../../examples/stats/example-stats.json"))
+        .expect("example stats should parse")
+}
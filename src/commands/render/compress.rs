@@ -0,0 +1,97 @@
+/// Compressed copies of rendered reports.
+///
+/// Emitted next to the original file (e.g. `my-year-2025.html.gz`) so reports
+/// shared over Matrix stay small.
+use anyhow::{Context, Result};
+use std::io::Write;
+
+/// Supported compression encodings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Brotli,
+}
+
+impl Compression {
+    /// Parses a comma-separated list of encodings (`gzip`, `br`).
+    pub fn parse_list(input: &str) -> Result<Vec<Self>> {
+        let mut encodings = Vec::new();
+        for name in input.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let encoding = match name.to_ascii_lowercase().as_str() {
+                "gzip" | "gz" => Compression::Gzip,
+                "br" | "brotli" => Compression::Brotli,
+                _ => anyhow::bail!("Unknown compression '{}' (expected gzip or br)", name),
+            };
+            if !encodings.contains(&encoding) {
+                encodings.push(encoding);
+            }
+        }
+        Ok(encodings)
+    }
+
+    /// File extension appended to the original filename.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gz",
+            Compression::Brotli => "br",
+        }
+    }
+
+    /// Compresses `data` at the highest ratio (reports are small, size matters more than speed).
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+                encoder.write_all(data).context("Failed to gzip report")?;
+                encoder.finish().context("Failed to gzip report")
+            }
+            Compression::Brotli => {
+                let mut compressed = Vec::new();
+                {
+                    let mut encoder = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+                    encoder
+                        .write_all(data)
+                        .context("Failed to brotli-compress report")?;
+                }
+                Ok(compressed)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            Compression::parse_list("gzip, br,gzip").unwrap(),
+            vec![Compression::Gzip, Compression::Brotli]
+        );
+        assert!(Compression::parse_list("").unwrap().is_empty());
+        assert!(Compression::parse_list("zip").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data = "<p>hello</p>".repeat(100);
+
+        let gz = Compression::Gzip.compress(data.as_bytes()).unwrap();
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(&gz[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+
+        let br = Compression::Brotli.compress(data.as_bytes()).unwrap();
+        let mut decoded = String::new();
+        brotli::Decompressor::new(&br[..], 4096)
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+        assert!(br.len() < data.len());
+    }
+}
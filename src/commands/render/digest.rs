@@ -0,0 +1,363 @@
+/// Digest renderer.
+///
+/// A few lines of Markdown that fit on one screen: the headline numbers, the
+/// top rooms and emojis, the peaks and one sparkline, without the tables of the
+/// full report. Meant for week and day windows, to post as a status update in
+/// a Matrix room: the same lines render as Markdown, as plain text for email and
+/// as the HTML of Matrix formatted messages (`my share`).
+use anyhow::Result;
+
+use super::common::{emoji_label, scope_label, sparkline, Provenance, WEEKDAY_KEYS};
+use super::html::escape as escape_html;
+use super::i18n::{fill, Lang, Locale};
+use super::md::escape_inline;
+use crate::stats::*;
+
+/// Entries of the top rooms and top emojis lines.
+const DIGEST_TOP: usize = 3;
+
+/// Inline formatting of the digest lines.
+trait Markup {
+    /// Plain text, escaped
+    fn text(&self, text: &str) -> String;
+    fn strong(&self, text: &str) -> String;
+    fn em(&self, text: &str) -> String;
+    fn code(&self, text: &str) -> String;
+    fn link(&self, text: &str, url: &str) -> String;
+    /// Separator of lines
+    fn line_break(&self) -> &'static str;
+}
+
+/// Markdown, the digest file and the plain body of Matrix messages
+struct Markdown;
+
+impl Markup for Markdown {
+    fn text(&self, text: &str) -> String {
+        escape_inline(text)
+    }
+    fn strong(&self, text: &str) -> String {
+        format!("**{}**", escape_inline(text))
+    }
+    fn em(&self, text: &str) -> String {
+        format!("_{}_", escape_inline(text))
+    }
+    fn code(&self, text: &str) -> String {
+        format!("`{}`", text)
+    }
+    fn link(&self, text: &str, url: &str) -> String {
+        format!("[{}]({})", escape_inline(text), url)
+    }
+    fn line_break(&self) -> &'static str {
+        "  \n"
+    }
+}
+
+/// Plain text for email and chats without formatting: links keep their text only
+struct PlainText;
+
+impl Markup for PlainText {
+    fn text(&self, text: &str) -> String {
+        text.to_string()
+    }
+    fn strong(&self, text: &str) -> String {
+        text.to_string()
+    }
+    fn em(&self, text: &str) -> String {
+        text.to_string()
+    }
+    fn code(&self, text: &str) -> String {
+        text.to_string()
+    }
+    fn link(&self, text: &str, _url: &str) -> String {
+        text.to_string()
+    }
+    fn line_break(&self) -> &'static str {
+        "\n"
+    }
+}
+
+/// The HTML subset of Matrix formatted messages (`org.matrix.custom.html`)
+struct MatrixHtml;
+
+impl Markup for MatrixHtml {
+    fn text(&self, text: &str) -> String {
+        escape_html(text)
+    }
+    fn strong(&self, text: &str) -> String {
+        format!("<strong>{}</strong>", escape_html(text))
+    }
+    fn em(&self, text: &str) -> String {
+        format!("<em>{}</em>", escape_html(text))
+    }
+    fn code(&self, text: &str) -> String {
+        format!("<code>{}</code>", escape_html(text))
+    }
+    fn link(&self, text: &str, url: &str) -> String {
+        format!("<a href=\"{}\">{}</a>", escape_html(url), escape_html(text))
+    }
+    fn line_break(&self) -> &'static str {
+        "<br>\n"
+    }
+}
+
+/// Renders the digest of `stats` as Markdown.
+pub fn render(stats: &Stats, lang: Lang) -> Result<String> {
+    Ok(render_with(stats, lang.locale(), &Markdown))
+}
+
+/// Renders the digest of `stats` as plain text.
+pub fn render_text(stats: &Stats, lang: Lang) -> Result<String> {
+    Ok(render_with(stats, lang.locale(), &PlainText))
+}
+
+/// Renders the digest of `stats` in the HTML of Matrix formatted messages.
+pub fn render_matrix_html(stats: &Stats, lang: Lang) -> Result<String> {
+    Ok(render_with(stats, lang.locale(), &MatrixHtml))
+}
+
+fn render_with(stats: &Stats, t: &Locale, m: &dyn Markup) -> String {
+    let mut lines = Vec::new();
+
+    let title = fill(t.title, &[("scope", &scope_label(&stats.scope, t))]);
+    let account = match stats.account.display_name {
+        Some(ref name) => format!("{} ({})", name, stats.account.user_id),
+        None => stats.account.user_id.clone(),
+    };
+    lines.push(format!(
+        "{} · {}",
+        m.strong(&format!("🎉 {}", title)),
+        m.text(&account)
+    ));
+
+    let messages_sent = t.number(stats.summary.messages_sent.get());
+    let messages = match stats.rooms {
+        Some(ref rooms) => fill_marked(
+            m,
+            t.rooms_sentence,
+            &[
+                ("messages", m.strong(&messages_sent)),
+                ("rooms", m.strong(&rooms.total.to_string())),
+            ],
+        ),
+        None => format!("{}: {}", m.text(t.messages_sent), m.strong(&messages_sent)),
+    };
+    match stats.coverage.days_active {
+        Some(days) => lines.push(format!(
+            "💬 {} · {}: {}",
+            messages,
+            m.text(t.active_days),
+            m.strong(&t.number(days.get()))
+        )),
+        None => lines.push(format!("💬 {}", messages)),
+    }
+
+    let rooms: Vec<String> = stats
+        .rooms
+        .iter()
+        .flat_map(|rooms| rooms.top.iter().flatten())
+        .take(DIGEST_TOP)
+        .map(|room| {
+            let name = room.name.as_deref().unwrap_or(t.unnamed_room);
+            format!(
+                "{} ({})",
+                m.link(name, &room.permalink),
+                t.number(room.messages.get())
+            )
+        })
+        .collect();
+    if !rooms.is_empty() {
+        lines.push(format!(
+            "🏆 {}: {}",
+            m.text(t.most_active_rooms),
+            rooms.join(", ")
+        ));
+    }
+
+    let emojis: Vec<String> = stats
+        .reactions
+        .iter()
+        .flat_map(|reactions| reactions.top_emojis.iter().flatten())
+        .take(DIGEST_TOP)
+        .map(|entry| {
+            m.text(&format!(
+                "{} {}",
+                emoji_label(entry, t),
+                t.number(entry.count)
+            ))
+        })
+        .collect();
+    if !emojis.is_empty() {
+        lines.push(format!(
+            "😊 {}: {}",
+            m.text(t.top_reactions),
+            emojis.join(" · ")
+        ));
+    }
+
+    if let Some(peaks) = peaks_line(&stats.summary, &stats.scope, t) {
+        lines.push(m.text(&peaks));
+    }
+    if let Some(ref activity) = stats.activity {
+        if let Some((label, sparkline, range)) = trend(activity, &stats.scope, t) {
+            lines.push(format!(
+                "📈 {}: {} {}",
+                m.text(label),
+                m.code(&sparkline),
+                m.text(&range)
+            ));
+        }
+    }
+
+    lines.push(m.em(&Provenance::of(stats).line(t)));
+
+    let mut output = lines.join(m.line_break());
+    output.push('\n');
+    output
+}
+
+/// Fills a locale template: its text is escaped, the values are already marked up.
+fn fill_marked(m: &dyn Markup, template: &str, values: &[(&str, String)]) -> String {
+    let mut output = m.text(template);
+    for (key, value) in values {
+        output = output.replace(&m.text(&format!("{{{}}}", key)), value);
+    }
+    output
+}
+
+/// Peak day (for windows longer than a day) and peak hour.
+fn peaks_line(summary: &Summary, scope: &Scope, t: &Locale) -> Option<String> {
+    let peaks = summary.peaks.as_ref()?;
+    let mut parts = Vec::new();
+    if let Some(day) = peaks.day.as_ref().filter(|_| scope.kind != ScopeKind::Day) {
+        parts.push(format!(
+            "📍 {}: {} ({})",
+            t.peak_day,
+            day.day,
+            t.message_count(day.messages)
+        ));
+    }
+    if let Some(ref hour) = peaks.hour {
+        parts.push(format!(
+            "🕐 {}: {} ({})",
+            t.peak_hour,
+            fill(t.hour_of_day, &[("hour", &hour.hour)]),
+            t.message_count(hour.messages)
+        ));
+    }
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+/// Sparkline of the finest series that suits the window (hours of a day,
+/// weekdays of a week, month, quarter or rolling window, months of a year), with its label and range.
+fn trend(activity: &Activity, scope: &Scope, t: &Locale) -> Option<(&'static str, String, String)> {
+    let (label, series, keys, first, last): (_, _, Vec<String>, _, _) = match scope.kind {
+        ScopeKind::Day => (
+            t.by_hour,
+            activity.by_hour.as_ref()?,
+            (0..24).map(|hour| format!("{:02}", hour)).collect(),
+            "00",
+            "23",
+        ),
+        ScopeKind::Week | ScopeKind::Month | ScopeKind::Quarter | ScopeKind::Rolling => (
+            t.by_weekday,
+            activity.by_weekday.as_ref()?,
+            WEEKDAY_KEYS.iter().map(|key| key.to_string()).collect(),
+            t.weekdays_short[0],
+            t.weekdays_short[6],
+        ),
+        ScopeKind::Year | ScopeKind::Life => (
+            t.by_month,
+            activity.by_month.as_ref()?,
+            (1..=12).map(|month| format!("{:02}", month)).collect(),
+            t.months_short[0],
+            t.months_short[11],
+        ),
+    };
+    let counts: Vec<i32> = keys
+        .iter()
+        .map(|key| series.get(key).copied().unwrap_or(0))
+        .collect();
+    if counts.iter().all(|&count| count == 0) {
+        return None;
+    }
+    Some((label, sparkline(&counts), format!("({} → {})", first, last)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn week_stats() -> Stats {
+        serde_json::from_str(include_str!(
+            "../../../examples/stats/example-stats-2025-W12.json"
+        ))
+        .expect("example stats should parse")
+    }
+
+    #[test]
+    fn test_render_week_digest() {
+        let digest = render(&week_stats(), Lang::En).unwrap();
+        let lines: Vec<&str> = digest.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "**🎉 Your Matrix Week 2025-W12** · Alice (@alice:example.org)  "
+        );
+        assert!(lines[1].contains("**180**"));
+        assert!(lines[1].contains("Active days: **6**"));
+        assert!(digest.contains(
+            "🏆 Your most active rooms: [Friends](https://matrix.to/#/!abc123:example.org) (64), \
+             [Project X](https://matrix.to/#/!jkl012:example.org) (52)"
+        ));
+        assert!(digest.contains("😊 Top reactions: 👏 10 · 👍 8"));
+        assert!(digest.contains("📍 Peak day: 2025-03-20 (36 messages)"));
+        assert!(digest.contains("📈 By weekday: `"));
+        assert!(lines.last().unwrap().starts_with("_Generated 2025-03-25"));
+        // One screen, no tables
+        assert!(lines.len() <= 8);
+        assert!(!digest.contains('|'));
+    }
+
+    #[test]
+    fn test_render_matrix_html() {
+        let mut stats = week_stats();
+        stats.account.display_name = Some("Alice <3".to_string());
+        let html = render_matrix_html(&stats, Lang::En).unwrap();
+
+        assert!(html.starts_with(
+            "<strong>🎉 Your Matrix Week 2025-W12</strong> · Alice &lt;3 (@alice:example.org)<br>\n"
+        ));
+        assert!(
+            html.contains("You sent <strong>180</strong> messages in <strong>4</strong> rooms.")
+        );
+        assert!(
+            html.contains("<a href=\"https://matrix.to/#/!abc123:example.org\">Friends</a> (64)")
+        );
+        assert!(html.contains("<code>"));
+        assert!(html.contains("<em>Generated 2025-03-25"));
+        assert!(!html.contains("**"));
+    }
+
+    #[test]
+    fn test_render_text() {
+        let text = render_text(&week_stats(), Lang::Fr).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert!(lines[0].starts_with("🎉 "));
+        assert!(lines[0].ends_with(" · Alice (@alice:example.org)"));
+        assert!(text.contains("Friends (64)"));
+        assert!(!text.contains("https://"));
+        assert!(!text.contains("**") && !text.contains('`') && !text.contains("  \n"));
+        assert!((5..=10).contains(&lines.len()));
+    }
+
+    #[test]
+    fn test_day_digest_skips_peak_day() {
+        let mut stats = week_stats();
+        stats.scope.kind = ScopeKind::Day;
+        let digest = render(&stats, Lang::En).unwrap();
+        assert!(!digest.contains("Peak day"));
+        assert!(digest.contains("🕐 Peak hour: "));
+        assert!(digest.contains("📈 By hour (local time): `"));
+    }
+}
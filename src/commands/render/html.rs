@@ -0,0 +1,1886 @@
+use super::common::*;
+use super::i18n::{fill, Lang, Locale};
+use super::links::LinkBase;
+use super::theme::HtmlTheme;
+use crate::stats::*;
+use anyhow::Result;
+
+/// Default size budget for the single-file HTML report, in KB.
+pub const DEFAULT_BUDGET_KB: u64 = 500;
+
+/// Charts with more bars than this drop the gaps and tick labels.
+const DENSE_CHART_BARS: usize = 60;
+
+/// Inline stylesheet, so the report is a single self-contained file.
+/// Colors come from the theme's custom properties (see [`HtmlTheme::css`]).
+const STYLE: &str = "\
+* { box-sizing: border-box; }
+body { margin: 0; background: var(--bg); color: var(--text); font: 16px/1.5 -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; }
+main { max-width: 860px; margin: 0 auto; padding: 32px 16px 48px; }
+h1 { font-size: 2rem; margin: 0 0 16px; }
+h2 { font-size: 1.35rem; margin: 0 0 12px; }
+h3 { font-size: 1.05rem; margin: 20px 0 8px; color: var(--muted); }
+section { background: var(--card); border: 1px solid var(--border); border-radius: 12px; padding: 20px 24px; margin-bottom: 20px; }
+a { color: var(--accent); }
+ul { margin: 0; padding-left: 20px; }
+.note { color: var(--muted); font-style: italic; }
+.headline { font-size: 1.15rem; font-weight: 600; }
+.cards { display: grid; grid-template-columns: repeat(auto-fit, minmax(150px, 1fr)); gap: 12px; margin-bottom: 12px; }
+.card { border: 1px solid var(--border); border-radius: 10px; padding: 12px 14px; }
+.card .value { display: block; font-size: 1.6rem; font-weight: 700; }
+.card .label { color: var(--muted); font-size: 0.9rem; }
+.card .delta { display: block; color: var(--muted); font-size: 0.8rem; }
+table { width: 100%; border-collapse: collapse; }
+caption { text-align: left; }
+th, td { text-align: left; padding: 6px 8px; border-bottom: 1px solid var(--border); }
+th { color: var(--muted); font-weight: 600; font-size: 0.9rem; }
+td.num, th.num { text-align: right; font-variant-numeric: tabular-nums; }
+.chart { display: flex; align-items: flex-end; gap: 3px; height: 140px; padding-top: 8px; }
+.chart .col { flex: 1; display: flex; flex-direction: column; align-items: center; justify-content: flex-end; height: 100%; min-width: 0; }
+.chart .bar { width: 100%; background: var(--accent); border-radius: 3px 3px 0 0; min-height: 1px; }
+.chart .tick { font-size: 0.7rem; color: var(--muted); margin-top: 4px; white-space: nowrap; overflow: hidden; }
+.heatmap { width: 100%; height: auto; }
+.heatmap text { font-size: 9px; fill: var(--muted); }
+.heatmap .l0 { fill: var(--border); }
+.heatmap .l1 { fill: var(--heat1); }
+.heatmap .l2 { fill: var(--heat2); }
+.heatmap .l3 { fill: var(--heat3); }
+.chart .col:hover .bar { opacity: 0.75; }
+.chart.dense { gap: 0; }
+.chart.dense .bar { border-radius: 0; }
+.chart.dense .tick { visibility: hidden; }
+.toggles { display: flex; flex-wrap: wrap; gap: 6px; margin: 20px 0 8px; }
+.toggles button { font: inherit; font-size: 0.9rem; color: var(--muted); background: none; border: 1px solid var(--border); border-radius: 999px; padding: 2px 12px; cursor: pointer; }
+.toggles button[aria-pressed=\"true\"] { color: var(--text); border-color: var(--accent); }
+.chart-group.interactive h3 { display: none; }
+.tooltip { position: absolute; pointer-events: none; background: var(--text); color: var(--bg); font-size: 0.8rem; padding: 2px 8px; border-radius: 4px; white-space: nowrap; transform: translate(-50%, -100%); }
+.kinds { display: grid; grid-template-columns: max-content 1fr; align-items: center; gap: 6px 12px; }
+.kinds .stack { display: flex; height: 18px; }
+.kinds .stack span:first-child { border-radius: 3px 0 0 3px; }
+.kinds .stack span:last-child { border-radius: 0 3px 3px 0; }
+.kind-text { background: var(--heat3); }
+.kind-media { background: var(--heat2); }
+.kind-voice { background: var(--heat1); }
+.legend { display: flex; gap: 16px; margin-top: 8px; color: var(--muted); font-size: 0.85rem; }
+.legend i { display: inline-block; width: 10px; height: 10px; border-radius: 2px; margin-right: 6px; }
+.meter { display: flex; align-items: center; gap: 8px; }
+.meter .fill { height: 12px; background: var(--accent); border-radius: 3px; }
+.meter .num { font-variant-numeric: tabular-nums; }
+nav { display: flex; flex-wrap: wrap; gap: 8px 20px; margin-bottom: 20px; font-size: 0.9rem; }
+.avatar { width: 96px; height: 96px; border-radius: 50%; vertical-align: middle; }
+.room-avatar { width: 24px; height: 24px; border-radius: 50%; vertical-align: middle; margin-right: 6px; }
+footer { text-align: center; color: var(--muted); font-size: 0.85rem; }
+";
+
+/// Inline script: view toggles for chart groups and instant tooltips with the
+/// exact count of a bar. The report reads the same without it.
+const SCRIPT: &str = "\
+document.querySelectorAll('.chart-group').forEach(function (group) {
+  var views = group.querySelectorAll('.chart-view');
+  var toggles = document.createElement('div');
+  toggles.className = 'toggles';
+  function show(n) {
+    views.forEach(function (view, i) {
+      view.hidden = i !== n;
+      toggles.children[i].setAttribute('aria-pressed', String(i === n));
+    });
+  }
+  views.forEach(function (view, i) {
+    var button = document.createElement('button');
+    button.type = 'button';
+    button.textContent = view.querySelector('h3').textContent;
+    button.addEventListener('click', function () { show(i); });
+    toggles.appendChild(button);
+  });
+  group.insertBefore(toggles, group.firstChild);
+  group.classList.add('interactive');
+  show(0);
+});
+var tooltip = document.createElement('div');
+tooltip.className = 'tooltip';
+tooltip.hidden = true;
+document.body.appendChild(tooltip);
+document.querySelectorAll('.chart .col[title]').forEach(function (col) {
+  col.dataset.tip = col.title;
+  col.removeAttribute('title');
+  col.addEventListener('mouseenter', function () {
+    var box = col.getBoundingClientRect();
+    tooltip.textContent = col.dataset.tip;
+    tooltip.style.left = window.scrollX + box.left + box.width / 2 + 'px';
+    tooltip.style.top = window.scrollY + box.top - 4 + 'px';
+    tooltip.hidden = false;
+  });
+  col.addEventListener('mouseleave', function () { tooltip.hidden = true; });
+});
+";
+
+/// Render stats to a self-contained HTML page with the same sections as the Markdown report
+///
+/// The account and top room avatars, when given, are embedded in the page as
+/// `data:` URIs. The account is linked through `links`; permalinks of `stats`
+/// are used as they are.
+pub fn render(
+    stats: &Stats,
+    lang: Lang,
+    theme: HtmlTheme,
+    avatars: &Avatars,
+    links: &LinkBase,
+) -> Result<String> {
+    let t = lang.locale();
+    let mut output = String::new();
+    let title = report_title(stats, t);
+    let deltas = previous_deltas(stats, t);
+
+    // 1. Title, metadata, and account details
+    output.push_str(&format!("<h1>🎉 {}</h1>\n", escape(&title)));
+    render_header(
+        &mut output,
+        &stats.account,
+        avatars.account.as_ref(),
+        stats.timezone.as_deref(),
+        links,
+        t,
+    );
+
+    // 2. Summary (including active days from coverage)
+    render_summary(
+        &mut output,
+        &stats.summary,
+        stats.coverage.days_active.map(Count::get),
+        &stats.scope,
+        &deltas,
+        t,
+    );
+
+    // 3. Rooms
+    if let Some(ref rooms) = stats.rooms {
+        render_rooms(
+            &mut output,
+            rooms,
+            stats.summary.messages_sent.get(),
+            &stats.coverage,
+            &stats.scope,
+            avatars,
+            t,
+        );
+    }
+
+    // 4. People
+    if let Some(ref people) = stats.people {
+        render_people(&mut output, people, links, t);
+    }
+
+    // 5. DMs
+    if let Some(ref dms) = stats.dms {
+        render_dms(&mut output, dms, links, t);
+    }
+
+    // 6. Conversations
+    if let Some(ref conversations) = stats.conversations {
+        render_conversations(&mut output, conversations, t);
+    }
+
+    // 7. Message lengths
+    if let Some(ref lengths) = stats.message_lengths {
+        render_message_lengths(&mut output, lengths, t);
+    }
+
+    // 8. Typed emojis
+    if let Some(ref typed) = stats.typed_emojis {
+        render_typed_emojis(&mut output, typed, t);
+    }
+
+    // 9. Created rooms
+    if let Some(ref created_rooms) = stats.created_rooms {
+        render_created_rooms(&mut output, created_rooms, &stats.scope, t);
+    }
+
+    // 10. Reactions
+    if let Some(ref reactions) = stats.reactions {
+        render_reactions(&mut output, reactions, &stats.scope, &deltas, t);
+    }
+
+    // 11. Activity
+    if let Some(ref activity) = stats.activity {
+        render_activity(&mut output, activity, &stats.scope, &stats.summary, t);
+    }
+
+    // 12. Fun
+    if let Some(ref fun) = stats.fun {
+        render_fun(&mut output, fun, t);
+    }
+
+    let provenance = Provenance::of(stats);
+    output.push_str(&format!(
+        "<footer>{} · {}{}</footer>\n",
+        fill(
+            &escape(t.generated_by),
+            &[
+                ("date", &escape(&provenance.generated_at)),
+                (
+                    "tool",
+                    &format!("<code>{}</code>", escape(&provenance.tool))
+                )
+            ]
+        ),
+        escape(&provenance.details(t)),
+        changelog_note(stats, t)
+            .map(|note| format!("<br>{}", escape(&note)))
+            .unwrap_or_default()
+    ));
+
+    Ok(document(&title, t, theme, &output))
+}
+
+/// Wraps the `<main>` contents of a page with the head, stylesheet and script.
+pub(super) fn document(title: &str, t: &Locale, theme: HtmlTheme, body: &str) -> String {
+    let mut output = String::new();
+    output.push_str(&format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n",
+        t.code
+    ));
+    output.push_str("<meta charset=\"utf-8\">\n");
+    output.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    output.push_str(&format!("<title>{}</title>\n", escape(title)));
+    output.push_str(&format!("<style>\n{}{}</style>\n", theme.css(), STYLE));
+    output.push_str("</head>\n<body>\n<main>\n");
+    output.push_str(body);
+    output.push_str("</main>\n");
+    output.push_str(&format!("<script>\n{}</script>\n", SCRIPT));
+    output.push_str("</body>\n</html>\n");
+    output
+}
+
+/// Adds a `<nav>` bar at the top of the page, for reports linked together.
+pub(super) fn with_navigation(html: &str, links: &[(String, String)]) -> String {
+    let items: Vec<String> = links.iter().map(|(text, href)| link(text, href)).collect();
+    let nav = format!("<nav>{}</nav>\n", items.join("\n"));
+    match html.find("<main>\n") {
+        Some(index) => {
+            let index = index + "<main>\n".len();
+            format!("{}{}{}", &html[..index], nav, &html[index..])
+        }
+        None => format!("{}{}", nav, html),
+    }
+}
+
+/// Makes the browser reload `html` every `seconds`, for live reports.
+pub fn with_auto_refresh(html: &str, seconds: u64) -> String {
+    let meta = format!("<meta http-equiv=\"refresh\" content=\"{}\">\n", seconds);
+    match html.find("<meta charset") {
+        Some(index) => format!("{}{}{}", &html[..index], meta, &html[index..]),
+        None => format!("{}{}", meta, html),
+    }
+}
+
+/// Returns a warning when the report is larger than `budget_kb`, naming the largest section.
+pub fn budget_warning(html: &str, budget_kb: u64) -> Option<String> {
+    let size = html.len() as u64;
+    if budget_kb == 0 || size <= budget_kb * 1024 {
+        return None;
+    }
+
+    let mut warning = format!(
+        "HTML report is {} KB, over the {} KB budget",
+        size.div_ceil(1024),
+        budget_kb
+    );
+    if let Some((id, section_size)) = largest_section(html) {
+        warning.push_str(&format!(
+            " (largest section: {}, {} KB)",
+            id,
+            section_size.div_ceil(1024)
+        ));
+    }
+    Some(warning)
+}
+
+/// Finds the `<section>` with the most bytes (private).
+fn largest_section(html: &str) -> Option<(&str, u64)> {
+    html.split("<section id=\"")
+        .skip(1)
+        .filter_map(|chunk| {
+            let (id, _) = chunk.split_once('"')?;
+            let end = chunk.find("</section>").unwrap_or(chunk.len());
+            Some((id, end as u64))
+        })
+        .max_by_key(|(_, size)| *size)
+}
+
+fn report_title(stats: &Stats, t: &Locale) -> String {
+    let title = fill(t.title, &[("scope", &scope_label(&stats.scope, t))]);
+    match stats.account.display_name {
+        Some(ref display_name) => format!("{} — {}", title, display_name),
+        None => title,
+    }
+}
+
+fn render_header(
+    output: &mut String,
+    account: &Account,
+    avatar: Option<&Avatar>,
+    timezone: Option<&str>,
+    links: &LinkBase,
+    t: &Locale,
+) {
+    output.push_str(&format!(
+        "<section id=\"account\">\n<h2>🧑 {}</h2>\n<ul>\n",
+        escape(t.account)
+    ));
+
+    let user_permalink = links.link(&account.user_id);
+    output.push_str(&format!(
+        "<li><strong>{}:</strong> {}</li>\n",
+        escape(t.user_id),
+        link(&account.user_id, &user_permalink)
+    ));
+    if let Some(ref name) = account.display_name {
+        output.push_str(&format!(
+            "<li><strong>{}:</strong> {}</li>\n",
+            escape(t.display_name),
+            escape(name)
+        ));
+    }
+    if let Some(avatar) = avatar {
+        output.push_str(&format!(
+            "<li><strong>{}:</strong> <img class=\"avatar\" src=\"{}\" alt=\"{}\" width=\"96\" height=\"96\"></li>\n",
+            escape(t.avatar),
+            avatar.data_uri(),
+            escape(t.avatar)
+        ));
+    } else if let Some(ref avatar_url) = account.avatar_url {
+        output.push_str(&format!(
+            "<li><strong>{}:</strong> <code>{}</code></li>\n",
+            escape(t.avatar),
+            escape(avatar_url)
+        ));
+    }
+    if let Some(timezone) = timezone {
+        output.push_str(&format!(
+            "<li><strong>{}:</strong> {}</li>\n",
+            escape(t.timezone),
+            escape(timezone)
+        ));
+    }
+    output.push_str(&format!(
+        "<li><strong>{}:</strong> {}</li>\n",
+        escape(t.total_joined_rooms),
+        account.rooms_total
+    ));
+    output.push_str("</ul>\n</section>\n");
+}
+
+fn render_summary(
+    output: &mut String,
+    summary: &Summary,
+    active_days: Option<i32>,
+    scope: &Scope,
+    deltas: &PreviousDeltas,
+    t: &Locale,
+) {
+    output.push_str(&format!(
+        "<section id=\"summary\">\n<h2>📊 {}</h2>\n<div class=\"cards\">\n",
+        escape(t.summary)
+    ));
+
+    push_card_with_delta(
+        output,
+        &format!("💬 {}", t.messages_sent),
+        &t.number(summary.messages_sent.get()),
+        deltas.messages_sent.as_deref(),
+    );
+    if let Some(days) = active_days {
+        push_card_with_delta(
+            output,
+            &format!("🔥 {}", t.active_days),
+            &days.to_string(),
+            deltas.active_days.as_deref(),
+        );
+    }
+    if let Some(dm_rooms) = summary.dm_rooms {
+        push_card(output, &format!("👥 {}", t.dm_rooms), &dm_rooms.to_string());
+    }
+    if let Some(public_rooms) = summary.public_rooms {
+        push_card(
+            output,
+            &format!("🌐 {}", t.public_rooms),
+            &public_rooms.to_string(),
+        );
+    }
+    if let Some(private_rooms) = summary.private_rooms {
+        push_card(
+            output,
+            &format!("🔒 {}", t.private_rooms),
+            &private_rooms.to_string(),
+        );
+    }
+    output.push_str("</div>\n");
+
+    // Explicit note that the rest of the report refers to the given scope (skip for life)
+    if !matches!(scope.kind, ScopeKind::Life) {
+        output.push_str(&format!(
+            "<p class=\"note\">{}</p>\n",
+            escape(&fill(
+                t.sections_refer_to,
+                &[("scope", &scope_phrase(scope, t))]
+            ))
+        ));
+    }
+    output.push_str("</section>\n");
+}
+
+fn push_card(output: &mut String, label: &str, value: &str) {
+    push_card_with_delta(output, label, value, None);
+}
+
+/// A card whose value carries its change against the previous window, under the label.
+fn push_card_with_delta(output: &mut String, label: &str, value: &str, delta: Option<&str>) {
+    let delta = delta
+        .map(|delta| format!("<span class=\"delta\">{}</span>", escape(delta)))
+        .unwrap_or_default();
+    output.push_str(&format!(
+        "<div class=\"card\"><span class=\"value\">{}</span><span class=\"label\">{}</span>{}</div>\n",
+        escape(value),
+        escape(label),
+        delta
+    ));
+}
+
+fn render_rooms(
+    output: &mut String,
+    rooms: &Rooms,
+    messages_sent: i32,
+    coverage: &Coverage,
+    scope: &Scope,
+    avatars: &Avatars,
+    t: &Locale,
+) {
+    output.push_str(&format!(
+        "<section id=\"rooms\">\n<h2>🏘️ {}</h2>\n",
+        escape(t.rooms)
+    ));
+    output.push_str(&format!(
+        "<p>{}</p>\n",
+        fill(
+            &escape(t.rooms_sentence),
+            &[
+                ("messages", &t.number(messages_sent)),
+                ("rooms", &format!("<strong>{}</strong>", rooms.total)),
+            ]
+        )
+    ));
+
+    // Membership-aware coverage: some rooms only show history from the user's join
+    if let Some(count) = coverage.since_joined_rooms {
+        output.push_str(&format!(
+            "<p class=\"note\">{}</p>\n",
+            escape(&since_joined_note(count.get(), t))
+        ));
+    }
+
+    if let Some(ref top) = rooms.top {
+        if !top.is_empty() {
+            output.push_str(&format!(
+                "<table>\n<caption><h3>{}</h3></caption>\n",
+                escape(t.most_active_rooms)
+            ));
+            output.push_str(&format!(
+                "<tr><th scope=\"col\">{}</th><th scope=\"col\">{}</th><th scope=\"col\" class=\"num\">{}</th><th scope=\"col\" class=\"num\">{}</th></tr>\n",
+                escape(t.rank),
+                escape(t.name),
+                escape(t.messages),
+                escape(t.percent_of_total)
+            ));
+
+            for (i, room) in top.iter().enumerate() {
+                let name = room.name.as_deref().unwrap_or(t.unnamed_room);
+                let percentage_str = if let Some(pct) = room.percentage {
+                    t.decimal(pct.get(), 1)
+                } else {
+                    String::from("-")
+                };
+
+                // Clickable room name with permalink, after the room avatar
+                let mut name_display = match avatars.room(room) {
+                    Some(avatar) => format!(
+                        "<img class=\"room-avatar\" src=\"{}\" alt=\"\" width=\"24\" height=\"24\">",
+                        avatar.data_uri()
+                    ),
+                    None => String::new(),
+                };
+                name_display.push_str(&link(name, &room.permalink));
+                if let Some(phrase) = room
+                    .joined_at
+                    .as_deref()
+                    .and_then(|d| joined_phrase(d, scope, t))
+                {
+                    name_display.push_str(&format!(
+                        " · <span class=\"note\">{}</span>",
+                        escape(&phrase)
+                    ));
+                }
+
+                let mut messages = t.number(room.messages.get());
+                if let Some((arrow, description)) = room_trend(room, t) {
+                    messages.push_str(&format!(
+                        " <abbr title=\"{}\">{}</abbr>",
+                        escape(&description),
+                        arrow
+                    ));
+                }
+
+                output.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                    i + 1,
+                    name_display,
+                    messages,
+                    percentage_str
+                ));
+            }
+            output.push_str("</table>\n");
+            if let Some(note) = trend_note(top, t) {
+                output.push_str(&format!("<p class=\"note\">{}</p>\n", escape(&note)));
+            }
+        }
+    }
+
+    if let Some(ref by_server) = rooms.by_server {
+        if !by_server.is_empty() {
+            render_by_server(output, by_server, t);
+        }
+    }
+
+    if let Some(ref dms) = rooms.dm_balance {
+        if !dms.is_empty() {
+            render_dm_balance(output, dms, t);
+        }
+    }
+
+    if let Some(ref by_type) = rooms.messages_by_room_type_kind {
+        render_message_kinds(output, by_type, t);
+    }
+    output.push_str("</section>\n");
+}
+
+/// Messages sent per homeserver of the rooms, busiest first.
+fn render_by_server(output: &mut String, by_server: &[ServerEntry], t: &Locale) {
+    output.push_str(&format!(
+        "<table>\n<caption><h3>{}</h3></caption>\n",
+        escape(t.homeservers)
+    ));
+    output.push_str(&format!(
+        "<tr><th scope=\"col\">{}</th><th scope=\"col\">{}</th><th scope=\"col\" class=\"num\">{}</th><th scope=\"col\" class=\"num\">{}</th></tr>\n",
+        escape(t.rank),
+        escape(t.server),
+        escape(t.messages),
+        escape(t.percent_of_total)
+    ));
+    for (i, entry) in by_server.iter().enumerate() {
+        output.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+            i + 1,
+            escape(&entry.server),
+            t.number(entry.messages.get()),
+            t.decimal(entry.percentage.get(), 1)
+        ));
+    }
+    output.push_str("</table>\n");
+}
+
+/// Messages sent and received in the busiest DMs, with the user's share.
+fn render_dm_balance(output: &mut String, dms: &[DmBalanceEntry], t: &Locale) {
+    output.push_str(&format!(
+        "<table>\n<caption><h3>{}</h3></caption>\n",
+        escape(t.conversation_balance)
+    ));
+    output.push_str(&format!(
+        "<tr><th scope=\"col\">{}</th><th scope=\"col\">{}</th><th scope=\"col\" class=\"num\">{}</th><th scope=\"col\" class=\"num\">{}</th><th scope=\"col\" class=\"num\">{}</th></tr>\n",
+        escape(t.rank),
+        escape(t.name),
+        escape(t.sent),
+        escape(t.received),
+        escape(t.your_share)
+    ));
+    for (i, dm) in dms.iter().enumerate() {
+        let name = dm.name.as_deref().unwrap_or(t.unnamed_room);
+        output.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+            i + 1,
+            link(name, &dm.permalink),
+            t.number(dm.sent),
+            t.number(dm.received),
+            dm_sent_share(dm, t)
+        ));
+    }
+    output.push_str("</table>\n");
+
+    let busiest = &dms[0];
+    output.push_str(&format!(
+        "<p>{}</p>\n",
+        fill(
+            &escape(t.dm_balance_sentence),
+            &[
+                (
+                    "share",
+                    &format!("<strong>{}</strong>", dm_sent_share(busiest, t))
+                ),
+                (
+                    "name",
+                    &escape(busiest.name.as_deref().unwrap_or(t.unnamed_room))
+                ),
+            ]
+        )
+    ));
+}
+
+/// The top DM partners and the most active members of the group rooms.
+fn render_people(output: &mut String, people: &People, links: &LinkBase, t: &Locale) {
+    output.push_str(&format!(
+        "<section id=\"people\">\n<h2>🫂 {}</h2>\n",
+        escape(t.people)
+    ));
+
+    if let Some(person) = people.dm_partners.iter().flatten().next() {
+        output.push_str(&format!(
+            "<p>{}</p>\n",
+            fill(
+                &escape(t.people_sentence),
+                &[
+                    (
+                        "name",
+                        &format!("<strong>{}</strong>", escape(person_name(person)))
+                    ),
+                    ("messages", &escape(&t.message_count(person.messages))),
+                ]
+            )
+        ));
+    }
+
+    for (heading, ranked) in [
+        (t.talked_with_most, &people.dm_partners),
+        (t.most_seen_senders, &people.group_senders),
+    ] {
+        if let Some(ranked) = ranked.as_deref().filter(|ranked| !ranked.is_empty()) {
+            push_people_table(output, heading, ranked, links, t);
+        }
+    }
+    output.push_str("</section>\n");
+}
+
+/// Ranked people, with their shared rooms when they have some.
+fn push_people_table(
+    output: &mut String,
+    heading: &str,
+    ranked: &[PersonEntry],
+    links: &LinkBase,
+    t: &Locale,
+) {
+    // Only group senders have shared rooms
+    let with_rooms = ranked.iter().any(|person| person.rooms.is_some());
+    output.push_str(&format!(
+        "<table>\n<caption><h3>{}</h3></caption>\n",
+        escape(heading)
+    ));
+    output.push_str(&format!(
+        "<tr><th scope=\"col\">{}</th><th scope=\"col\">{}</th><th scope=\"col\" class=\"num\">{}</th>{}</tr>\n",
+        escape(t.rank),
+        escape(t.name),
+        escape(t.messages),
+        if with_rooms {
+            format!("<th scope=\"col\" class=\"num\">{}</th>", escape(t.shared_rooms))
+        } else {
+            String::new()
+        }
+    ));
+    for (i, person) in ranked.iter().enumerate() {
+        let rooms = if with_rooms {
+            let rooms = person.rooms.map(|n| n.to_string()).unwrap_or_default();
+            format!("<td class=\"num\">{}</td>", rooms)
+        } else {
+            String::new()
+        };
+        let name = match person.user_id {
+            Some(ref user_id) => link(person_name(person), &links.link(user_id)),
+            None => escape(person_name(person)),
+        };
+        output.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td>{}</tr>\n",
+            i + 1,
+            name,
+            t.number(person.messages),
+            rooms
+        ));
+    }
+    output.push_str("</table>\n");
+}
+
+/// The people the user wrote to the most in DMs.
+fn render_dms(output: &mut String, dms: &Dms, links: &LinkBase, t: &Locale) {
+    output.push_str(&format!(
+        "<section id=\"dms\">\n<h2>✉️ {}</h2>\n",
+        escape(t.dms)
+    ));
+    if let Some(person) = dms.top.first() {
+        output.push_str(&format!(
+            "<p>{}</p>\n",
+            fill(
+                &escape(t.dms_sentence),
+                &[
+                    (
+                        "name",
+                        &format!("<strong>{}</strong>", escape(person_name(person)))
+                    ),
+                    ("messages", &escape(&t.message_count(person.messages))),
+                ]
+            )
+        ));
+    }
+    push_list(
+        output,
+        &[format!(
+            "<li>👤 <strong>{}:</strong> {}</li>",
+            escape(t.dm_correspondents),
+            t.number(dms.total)
+        )],
+    );
+    if !dms.top.is_empty() {
+        push_people_table(output, t.wrote_to_most, &dms.top, links, t);
+    }
+    output.push_str("</section>\n");
+}
+
+/// Stacked bars of each room type's messages by kind, scaled to the busiest room type.
+fn render_message_kinds(
+    output: &mut String,
+    by_type: &std::collections::HashMap<String, std::collections::HashMap<String, i32>>,
+    t: &Locale,
+) {
+    let kinds = [
+        ("text", t.kind_text),
+        ("media", t.kind_media),
+        ("voice", t.kind_voice),
+    ];
+    let room_types = [
+        ("dm", t.dm_rooms),
+        ("public", t.public_rooms),
+        ("private", t.private_rooms),
+    ];
+    let count = |room_type: &str, kind: &str| {
+        by_type
+            .get(room_type)
+            .and_then(|kinds| kinds.get(kind))
+            .copied()
+            .unwrap_or(0)
+            .max(0)
+    };
+    let total =
+        |room_type: &str| -> i32 { kinds.iter().map(|(kind, _)| count(room_type, kind)).sum() };
+    let max = room_types
+        .iter()
+        .map(|(room_type, _)| total(room_type))
+        .max()
+        .unwrap_or(0);
+    if max == 0 {
+        return;
+    }
+
+    output.push_str(&format!(
+        "<h3>{}</h3>\n<div class=\"kinds\">\n",
+        escape(t.message_kinds)
+    ));
+    for (room_type, label) in room_types {
+        if total(room_type) == 0 {
+            continue;
+        }
+        let parts: Vec<String> = kinds
+            .iter()
+            .map(|(kind, kind_label)| (kind_label, count(room_type, kind)))
+            .filter(|(_, value)| *value > 0)
+            .map(|(kind_label, value)| format!("{}: {}", kind_label, t.number(value)))
+            .collect();
+        output.push_str(&format!(
+            "<span>{}</span><div class=\"stack\" role=\"img\" aria-label=\"{}\">",
+            escape(label),
+            escape(&format!("{}: {}", label, parts.join(", ")))
+        ));
+        for (kind, kind_label) in kinds {
+            let value = count(room_type, kind);
+            if value == 0 {
+                continue;
+            }
+            output.push_str(&format!(
+                "<span class=\"kind-{}\" style=\"width: {:.1}%\" title=\"{}: {}\"></span>",
+                kind,
+                value as f64 / max as f64 * 100.0,
+                escape(kind_label),
+                t.number(value)
+            ));
+        }
+        output.push_str("</div>\n");
+    }
+    output.push_str("</div>\n<div class=\"legend\">");
+    for (kind, kind_label) in kinds {
+        output.push_str(&format!(
+            "<span><i class=\"kind-{}\" aria-hidden=\"true\"></i>{}</span>",
+            kind,
+            escape(kind_label)
+        ));
+    }
+    output.push_str("</div>\n");
+}
+
+/// Messages in threads against the main timeline, and replies.
+fn render_conversations(output: &mut String, conversations: &Conversations, t: &Locale) {
+    output.push_str(&format!(
+        "<section id=\"conversations\">\n<h2>🧵 {}</h2>\n",
+        escape(t.conversations)
+    ));
+    output.push_str(&format!(
+        "<p>{}</p>\n",
+        fill(
+            &escape(t.conversations_sentence),
+            &[
+                (
+                    "share",
+                    &format!(
+                        "<strong>{}</strong>",
+                        escape(&thread_share(conversations, t))
+                    )
+                ),
+                ("replies", &t.number(conversations.replies)),
+            ]
+        )
+    ));
+
+    let items: Vec<String> = [
+        ("💬", t.main_timeline, conversations.top_level),
+        ("🧵", t.in_threads, conversations.in_threads),
+        ("🪡", t.threads_posted_in, conversations.threads),
+        ("↩️", t.replies, conversations.replies),
+    ]
+    .into_iter()
+    .map(|(emoji, label, count)| {
+        format!(
+            "<li>{} <strong>{}:</strong> {}</li>",
+            emoji,
+            escape(label),
+            t.number(count)
+        )
+    })
+    .collect();
+    push_list(output, &items);
+    output.push_str("</section>\n");
+}
+
+/// The user's text messages by length, and a link to the longest.
+fn render_message_lengths(output: &mut String, lengths: &MessageLengths, t: &Locale) {
+    output.push_str(&format!(
+        "<section id=\"message-lengths\">\n<h2>✍️ {}</h2>\n",
+        escape(t.message_lengths)
+    ));
+    output.push_str(&format!(
+        "<p>{}</p>\n",
+        fill(
+            &escape(t.message_lengths_sentence),
+            &[
+                (
+                    "short",
+                    &format!(
+                        "<strong>{}</strong>",
+                        escape(&length_share(lengths, lengths.short, t))
+                    )
+                ),
+                ("long", &escape(&length_share(lengths, lengths.long, t))),
+            ]
+        )
+    ));
+
+    let mut items: Vec<String> = [
+        ("🤏", t.short_messages, lengths.short),
+        ("💬", t.medium_messages, lengths.medium),
+        ("📜", t.long_messages, lengths.long),
+    ]
+    .into_iter()
+    .map(|(emoji, label, count)| {
+        format!(
+            "<li>{} <strong>{}:</strong> {}</li>",
+            emoji,
+            escape(label),
+            t.number(count)
+        )
+    })
+    .collect();
+    if let Some(ref longest) = lengths.longest {
+        items.push(format!(
+            "<li>🏆 <strong>{}:</strong> {}</li>",
+            escape(t.longest_message),
+            link(&characters(longest.chars, t), &longest.permalink)
+        ));
+    }
+    push_list(output, &items);
+    output.push_str("</section>\n");
+}
+
+/// The emoji the user typed in their messages, most used first.
+fn render_typed_emojis(output: &mut String, typed: &TypedEmojis, t: &Locale) {
+    output.push_str(&format!(
+        "<section id=\"typed-emojis\">\n<h2>⌨️ {}</h2>\n",
+        escape(t.typed_emojis)
+    ));
+    output.push_str(&format!(
+        "<p>{}</p>\n",
+        fill(
+            &escape(t.typed_emojis_sentence),
+            &[
+                (
+                    "count",
+                    &format!("<strong>{}</strong>", t.number(typed.total))
+                ),
+                ("emoji", &escape(&favorite_emoji(&typed.top, t))),
+            ]
+        )
+    ));
+    if !typed.top.is_empty() {
+        push_emoji_table(output, t.most_typed_emojis, &typed.top, t);
+    }
+    output.push_str("</section>\n");
+}
+
+/// A ranking of emoji under `caption`, with bars scaled to the most used one
+/// and the count after them.
+fn push_emoji_table(output: &mut String, caption: &str, entries: &[EmojiEntry], t: &Locale) {
+    output.push_str(&format!(
+        "<table>\n<caption><h3>{}</h3></caption>\n",
+        escape(caption)
+    ));
+    output.push_str(&format!(
+        "<tr><th scope=\"col\">{}</th><th scope=\"col\">{}</th><th scope=\"col\">{}</th></tr>\n",
+        escape(t.rank),
+        escape(t.emoji),
+        escape(t.count)
+    ));
+    let max = entries.iter().map(|entry| entry.count).max().unwrap_or(0);
+    for (i, entry) in entries.iter().enumerate() {
+        let width = if max > 0 {
+            entry.count.max(0) as f64 / max as f64 * 80.0
+        } else {
+            0.0
+        };
+        output.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td><div class=\"meter\"><span class=\"fill\" style=\"width: {:.1}%\"></span><span class=\"num\">{}</span></div></td></tr>\n",
+            i + 1,
+            escape(&emoji_label(entry, t)),
+            width,
+            t.number(entry.count)
+        ));
+    }
+    output.push_str("</table>\n");
+}
+
+fn render_created_rooms(
+    output: &mut String,
+    created_rooms: &CreatedRooms,
+    scope: &Scope,
+    t: &Locale,
+) {
+    output.push_str(&format!(
+        "<section id=\"created-rooms\">\n<h2>🏗️ {}</h2>\n",
+        escape(t.created_rooms)
+    ));
+    output.push_str(&format!(
+        "<p>{}</p>\n",
+        fill(
+            &escape(t.created_sentence),
+            &[
+                (
+                    "count",
+                    &format!("<strong>{}</strong>", t.number(created_rooms.total.get()))
+                ),
+                ("when", &escape(&created_rooms_context(scope, t))),
+            ]
+        )
+    ));
+
+    let mut items = Vec::new();
+    let mut push_item = |emoji: &str, label: &str, count: Option<i32>| {
+        if let Some(count) = count {
+            items.push(format!(
+                "<li>{} <strong>{}:</strong> {}</li>",
+                emoji,
+                escape(label),
+                t.number(count)
+            ));
+        }
+    };
+    push_item("👥", t.dm_rooms, created_rooms.dm_rooms.map(Count::get));
+    push_item(
+        "🌐",
+        t.public_rooms,
+        created_rooms.public_rooms.map(Count::get),
+    );
+    push_item(
+        "🔒",
+        t.private_rooms,
+        created_rooms.private_rooms.map(Count::get),
+    );
+    push_list(output, &items);
+    output.push_str("</section>\n");
+}
+
+fn render_reactions(
+    output: &mut String,
+    reactions: &Reactions,
+    scope: &Scope,
+    deltas: &PreviousDeltas,
+    t: &Locale,
+) {
+    output.push_str(&format!(
+        "<section id=\"reactions\">\n<h2>😊 {}</h2>\n",
+        escape(t.reactions)
+    ));
+
+    if let Some(total) = reactions.total {
+        let delta = deltas
+            .reactions
+            .as_ref()
+            .map(|delta| format!(" <span class=\"note\">({})</span>", escape(delta)))
+            .unwrap_or_default();
+        output.push_str(&format!(
+            "<p>{}{}</p>\n",
+            fill(
+                &escape(t.reactions_sentence),
+                &[("count", &format!("<strong>{}</strong>", t.number(total)))]
+            ),
+            delta
+        ));
+    }
+
+    // Top emojis
+    if let Some(ref top_emojis) = reactions.top_emojis {
+        if !top_emojis.is_empty() {
+            push_emoji_table(output, t.top_reactions, top_emojis, t);
+        }
+    }
+
+    // Reactions the user gave
+    if let Some(ref given) = reactions.given {
+        output.push_str(&format!(
+            "<p>{}</p>\n",
+            fill(
+                &escape(t.reactions_given_sentence),
+                &[
+                    (
+                        "count",
+                        &format!("<strong>{}</strong>", t.number(given.total))
+                    ),
+                    ("emoji", &escape(&favorite_emoji(&given.top_emojis, t))),
+                ]
+            )
+        ));
+        if !given.top_emojis.is_empty() {
+            push_emoji_table(output, t.reactions_given, &given.top_emojis, t);
+        }
+    }
+
+    // Top messages
+    if let Some(ref top_messages) = reactions.top_messages {
+        if !top_messages.is_empty() {
+            output.push_str(&format!(
+                "<table>\n<caption><h3>{}</h3></caption>\n",
+                escape(t.most_reacted_messages)
+            ));
+            output.push_str(&format!(
+                "<tr><th scope=\"col\">{}</th><th scope=\"col\">{}</th><th scope=\"col\" class=\"num\">{}</th></tr>\n",
+                escape(t.rank),
+                escape(t.link),
+                escape(t.reactions)
+            ));
+            for (i, msg_entry) in top_messages.iter().enumerate() {
+                output.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td></tr>\n",
+                    i + 1,
+                    link(
+                        msg_entry.preview.as_deref().unwrap_or(t.view),
+                        &msg_entry.permalink
+                    ),
+                    t.number(msg_entry.reaction_count)
+                ));
+            }
+            output.push_str("</table>\n");
+        }
+    }
+
+    // Rooms where the user's messages got the most reactions
+    if let Some(ref top_rooms) = reactions.top_rooms {
+        if !top_rooms.is_empty() {
+            output.push_str(&format!(
+                "<table>\n<caption><h3>{}</h3></caption>\n",
+                escape(t.most_loved_rooms)
+            ));
+            output.push_str(&format!(
+                "<tr><th scope=\"col\">{}</th><th scope=\"col\">{}</th><th scope=\"col\" class=\"num\">{}</th></tr>\n",
+                escape(t.rank),
+                escape(t.name),
+                escape(t.reactions)
+            ));
+            for (i, room_entry) in top_rooms.iter().enumerate() {
+                let name = room_entry.name.as_deref().unwrap_or(t.unnamed_room);
+                output.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td></tr>\n",
+                    i + 1,
+                    link(name, &room_entry.permalink),
+                    t.number(room_entry.reaction_count)
+                ));
+            }
+            output.push_str("</table>\n");
+        }
+    }
+
+    // When reactions came in, for scopes of several months
+    let months = report_months(scope);
+    if !months.is_empty() {
+        if let Some(ref by_month) = reactions.by_month {
+            let bars = month_bars(by_month, &months, t);
+            render_chart(output, &format!("📆 {}", t.reactions_by_month), &bars, t);
+        }
+    }
+    output.push_str("</section>\n");
+}
+
+fn render_peak_activity(output: &mut String, summary: &Summary, t: &Locale) {
+    let mut items: Vec<String> = Vec::new();
+
+    if let Some(peaks) = summary.peaks.as_ref() {
+        if let Some(ref year) = peaks.year {
+            items.push(peak_item("🗓️", t.peak_year, &year.year, year.messages, t));
+        }
+        if let Some(ref month) = peaks.month {
+            items.push(peak_item(
+                "📆",
+                t.peak_month,
+                &t.stats_month(&month.month),
+                month.messages,
+                t,
+            ));
+        }
+        if let Some(ref week) = peaks.week {
+            items.push(peak_item("📅", t.peak_week, &week.week, week.messages, t));
+        }
+        if let Some(ref day) = peaks.day {
+            items.push(peak_item("📍", t.peak_day, &day.day, day.messages, t));
+        }
+        if let Some(ref hour) = peaks.hour {
+            let hour_str = hour.hour.to_string();
+            let when = if let Some(ref date) = hour.date {
+                fill(t.hour_on, &[("hour", &hour_str), ("date", date)])
+            } else {
+                fill(t.hour_of_day, &[("hour", &hour_str)])
+            };
+            items.push(peak_item("🕐", t.peak_hour, &when, hour.messages, t));
+        }
+    }
+
+    if items.is_empty() {
+        return;
+    }
+
+    output.push_str(&format!("<h3>🚀 {}</h3>\n", escape(t.peaks)));
+    push_list(output, &items);
+}
+
+fn peak_item(emoji: &str, label: &str, when: &str, messages: i32, t: &Locale) -> String {
+    format!(
+        "<li>{} <strong>{}:</strong> {} ({})</li>",
+        emoji,
+        escape(label),
+        escape(when),
+        escape(&t.message_count(messages))
+    )
+}
+
+fn render_activity(
+    output: &mut String,
+    activity: &Activity,
+    scope: &Scope,
+    summary: &Summary,
+    t: &Locale,
+) {
+    output.push_str(&format!(
+        "<section id=\"activity\">\n<h2>📈 {}</h2>\n",
+        escape(t.activity)
+    ));
+
+    // Peaks come first inside Activity
+    render_peak_activity(output, summary, t);
+
+    if let Some(personas) = time_of_day_personas(activity, t) {
+        output.push_str(&format!(
+            "<h3>🌗 {}</h3>\n<p>{}</p>\n",
+            escape(t.time_of_day),
+            escape(&personas)
+        ));
+    }
+
+    // Calendar heatmap (year/life)
+    let heatmap = calendar_heatmap(scope, activity);
+    if let Some(ref weeks) = heatmap {
+        render_heatmap(output, weeks, t);
+    }
+
+    // Time series (year, month, week, day) share one chart with view toggles
+    let mut series: Vec<(String, Vec<(String, i32)>)> = Vec::new();
+
+    // By year (life scope)
+    if let Some(ref by_year) = activity.by_year {
+        let mut years: Vec<_> = by_year.keys().cloned().collect();
+        years.sort();
+        let bars: Vec<(String, i32)> = years
+            .into_iter()
+            .map(|year| {
+                let count = by_year.get(&year).copied().unwrap_or(0);
+                (year, count)
+            })
+            .collect();
+        series.push((format!("📆 {}", t.by_year), bars));
+    }
+
+    // By month - only when meaningful for the scope (year/life, the months of a quarter)
+    let months = report_months(scope);
+    if !months.is_empty() {
+        if let Some(ref by_month) = activity.by_month {
+            series.push((
+                format!("📆 {}", t.by_month),
+                month_bars(by_month, &months, t),
+            ));
+        }
+    }
+
+    // By week (year scope)
+    if matches!(scope.kind, ScopeKind::Year) {
+        if let Some(ref by_week) = activity.by_week {
+            let mut weeks: Vec<_> = by_week.keys().cloned().collect();
+            weeks.sort();
+            let bars: Vec<(String, i32)> = weeks
+                .into_iter()
+                .map(|week| {
+                    let count = by_week.get(&week).copied().unwrap_or(0);
+                    (week, count)
+                })
+                .collect();
+            series.push((format!("📅 {}", t.by_week), bars));
+        }
+    }
+
+    // By week and by day from the daily counts of the heatmap (year/life)
+    if let Some(ref weeks) = heatmap {
+        if activity.by_week.is_none() || matches!(scope.kind, ScopeKind::Life) {
+            let bars: Vec<(String, i32)> = weeks
+                .iter()
+                .filter_map(|week| {
+                    let first = week.iter().flatten().next()?;
+                    let count = week.iter().flatten().map(|day| day.messages).sum();
+                    Some((first.date.format("%G-W%V").to_string(), count))
+                })
+                .collect();
+            series.push((format!("📅 {}", t.by_week), bars));
+        }
+        let bars: Vec<(String, i32)> = weeks
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|day| (t.short_date(day.date), day.messages))
+            .collect();
+        series.push((format!("📅 {}", t.by_day), bars));
+    }
+
+    // By day (month scope)
+    if matches!(scope.kind, ScopeKind::Month) {
+        if let Some(ref by_day) = activity.by_day {
+            let bars: Vec<(String, i32)> = (1..=31)
+                .map(|day| {
+                    let key = format!("{:02}", day);
+                    let count = by_day.get(&key).copied().unwrap_or(0);
+                    (key, count)
+                })
+                .collect();
+            series.push((format!("📅 {}", t.by_day), bars));
+        }
+    }
+    render_chart_group(output, &series, t);
+
+    // By weekday
+    if let Some(ref by_weekday) = activity.by_weekday {
+        let bars: Vec<(String, i32)> = WEEKDAY_KEYS
+            .iter()
+            .zip(t.weekdays_short)
+            .map(|(key, label)| {
+                let count = by_weekday.get(*key).copied().unwrap_or(0);
+                (label.to_string(), count)
+            })
+            .collect();
+        render_chart(output, &format!("📅 {}", t.by_weekday), &bars, t);
+    }
+
+    // By hour
+    if let Some(ref by_hour) = activity.by_hour {
+        let bars: Vec<(String, i32)> = (0..24)
+            .map(|hour| {
+                let key = format!("{:02}", hour);
+                let count = by_hour.get(&key).copied().unwrap_or(0);
+                (key, count)
+            })
+            .collect();
+        render_chart(output, &format!("🕐 {}", t.by_hour), &bars, t);
+    }
+
+    output.push_str("</section>\n");
+}
+
+/// Renders the calendar heatmap as an inline SVG: one column per week, one row per weekday.
+fn render_heatmap(output: &mut String, weeks: &[HeatmapWeek], t: &Locale) {
+    const CELL: usize = 11;
+    const PITCH: usize = 13;
+    const LEFT: usize = 28;
+    const TOP: usize = 16;
+
+    let width = LEFT + weeks.len() * PITCH;
+    let height = TOP + 7 * PITCH;
+    let days: Vec<(String, i32)> = weeks
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|day| (t.long_date(day.date), day.messages))
+        .collect();
+    let label = if days.is_empty() {
+        t.messages_per_day.to_string()
+    } else {
+        format!("{}: {}", t.messages_per_day, chart_summary(&days, t))
+    };
+    output.push_str(&format!("<h3>🗓️ {}</h3>\n", escape(t.calendar)));
+    output.push_str(&format!(
+        "<svg class=\"heatmap\" viewBox=\"0 0 {} {}\" role=\"img\" aria-label=\"{}\">\n",
+        width,
+        height,
+        escape(&label)
+    ));
+
+    for (week, name) in heatmap_month_starts(weeks, t) {
+        output.push_str(&format!(
+            "<text x=\"{}\" y=\"10\">{}</text>\n",
+            LEFT + week * PITCH,
+            escape(name)
+        ));
+    }
+    for weekday in [0, 2, 4] {
+        output.push_str(&format!(
+            "<text x=\"0\" y=\"{}\">{}</text>\n",
+            TOP + weekday * PITCH + 9,
+            escape(t.weekdays_short[weekday])
+        ));
+    }
+
+    for (week, days) in weeks.iter().enumerate() {
+        for (weekday, day) in days.iter().enumerate() {
+            let Some(day) = day else {
+                continue;
+            };
+            output.push_str(&format!(
+                "<rect class=\"l{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\"><title>{}: {}</title></rect>\n",
+                day.level,
+                LEFT + week * PITCH,
+                TOP + weekday * PITCH,
+                CELL,
+                CELL,
+                escape(&t.long_date(day.date)),
+                escape(&t.message_count(day.messages))
+            ));
+        }
+    }
+    output.push_str("</svg>\n");
+}
+
+/// Bars of `by_month` over `months` (1 to 12), labelled with short month names.
+fn month_bars(
+    by_month: &std::collections::HashMap<String, i32>,
+    months: &[u32],
+    t: &Locale,
+) -> Vec<(String, i32)> {
+    months
+        .iter()
+        .map(|&month| {
+            let count = by_month.get(&format!("{:02}", month)).copied().unwrap_or(0);
+            (t.month_short(month).to_string(), count)
+        })
+        .collect()
+}
+
+/// Renders a vertical bar chart with pure CSS, one bar per (label, count).
+/// Charts of the same data at different granularities. With more than one, the
+/// script shows one at a time with toggle buttons; without it they all show.
+fn render_chart_group(output: &mut String, series: &[(String, Vec<(String, i32)>)], t: &Locale) {
+    let series: Vec<_> = series.iter().filter(|(_, bars)| !bars.is_empty()).collect();
+    if series.len() < 2 {
+        for (title, bars) in series {
+            render_chart(output, title, bars, t);
+        }
+        return;
+    }
+
+    output.push_str("<div class=\"chart-group\">\n");
+    for (title, bars) in series {
+        output.push_str("<div class=\"chart-view\">\n");
+        render_chart(output, title, bars, t);
+        output.push_str("</div>\n");
+    }
+    output.push_str("</div>\n");
+}
+
+fn render_chart(output: &mut String, title: &str, bars: &[(String, i32)], t: &Locale) {
+    if bars.is_empty() {
+        return;
+    }
+
+    let max = bars
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    // Too many bars for gaps and ticks (e.g. a year by day): hover shows them
+    let class = if bars.len() > DENSE_CHART_BARS {
+        "chart dense"
+    } else {
+        "chart"
+    };
+    // The bars only show to sight: assistive technologies read the summary instead
+    output.push_str(&format!(
+        "<h3>{}</h3>\n<div class=\"{}\" role=\"img\" aria-label=\"{}\">\n",
+        escape(title),
+        class,
+        escape(&format!("{}: {}", title, chart_summary(bars, t)))
+    ));
+    for (label, count) in bars {
+        let height = (*count).max(0) as f64 / max as f64 * 100.0;
+        output.push_str(&format!(
+            "<div class=\"col\" title=\"{}: {}\"><div class=\"bar\" style=\"height:{:.1}%\"></div><span class=\"tick\">{}</span></div>\n",
+            escape(label),
+            t.number(*count),
+            height,
+            escape(label)
+        ));
+    }
+    output.push_str("</div>\n");
+}
+
+/// Text alternative of a chart: the range of its bars and the highest and lowest
+/// ones (e.g. "from Jan to Dec, highest Oct (512), lowest Apr (290)").
+fn chart_summary(bars: &[(String, i32)], t: &Locale) -> String {
+    let (Some(first), Some(last)) = (bars.first(), bars.last()) else {
+        return String::new();
+    };
+    let max = bars
+        .iter()
+        .fold(first, |max, bar| if bar.1 > max.1 { bar } else { max });
+    let min = bars
+        .iter()
+        .fold(first, |min, bar| if bar.1 < min.1 { bar } else { min });
+    fill(
+        t.chart_summary,
+        &[
+            ("first", &first.0),
+            ("last", &last.0),
+            ("max", &max.0),
+            ("max_count", &t.number(max.1)),
+            ("min", &min.0),
+            ("min_count", &t.number(min.1)),
+        ],
+    )
+}
+
+fn render_fun(output: &mut String, fun: &Fun, t: &Locale) {
+    if fun.fields.is_empty() {
+        return;
+    }
+
+    output.push_str(&format!(
+        "<section id=\"fun\">\n<h2>🎪 {}</h2>\n",
+        escape(t.fun_facts)
+    ));
+
+    // Headline: estimated typing time
+    if let Some(headline) = typing_headline(fun, t) {
+        output.push_str(&format!(
+            "<p class=\"headline\">{}</p>\n",
+            escape(&headline)
+        ));
+    }
+
+    let items: Vec<String> = fun_entries(fun, t)
+        .into_iter()
+        .map(|entry| match entry.label {
+            Some(label) => format!(
+                "<li>{} <strong>{}:</strong> {}</li>",
+                entry.emoji,
+                escape(&label),
+                escape(&entry.value)
+            ),
+            None => format!("<li>{} {}</li>", entry.emoji, escape(&entry.value)),
+        })
+        .collect();
+    push_list(output, &items);
+    output.push_str("</section>\n");
+}
+
+fn push_list(output: &mut String, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    output.push_str("<ul>\n");
+    for item in items {
+        output.push_str(item);
+        output.push('\n');
+    }
+    output.push_str("</ul>\n");
+}
+
+pub(super) fn link(text: &str, href: &str) -> String {
+    format!("<a href=\"{}\">{}</a>", escape(href), escape(text))
+}
+
+/// Escapes text for use in HTML content and attribute values
+pub(super) fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::render::common::example_stats;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("<b>\"Tom\" & 'Jerry'</b>"),
+            "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn test_render_contains_all_sections() {
+        let html = render(
+            &example_stats(),
+            Lang::En,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        for id in [
+            "account",
+            "summary",
+            "rooms",
+            "people",
+            "dms",
+            "conversations",
+            "message-lengths",
+            "typed-emojis",
+            "created-rooms",
+            "reactions",
+            "activity",
+            "fun",
+        ] {
+            assert!(
+                html.contains(&format!("<section id=\"{}\">", id)),
+                "missing section {}",
+                id
+            );
+        }
+        assert!(html.contains(
+            "<footer>Generated 2025-12-20 by <code>my 0.1.0</code> · window 2025 · data from 2025-01-02 to 2025-12-19 · @alice:example.org</footer>"
+        ));
+        // The only script is the inline one: still a single self-contained file
+        assert_eq!(html.matches("<script").count(), 1);
+        assert!(!html.contains("<script src"));
+    }
+
+    #[test]
+    fn test_render_calendar_heatmap() {
+        let mut stats = example_stats();
+        let html = render(
+            &stats,
+            Lang::En,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+
+        // One cell per day of 2025, the peak day at the highest level
+        assert!(html.contains("<svg class=\"heatmap\""));
+        assert_eq!(html.matches("<rect class=\"l").count(), 365);
+        assert!(html.contains("<title>Oct 21, 2025: 45 messages</title>"));
+        assert!(html.contains("<rect class=\"l3\""));
+
+        // Life covers the last 52 weeks
+        stats.scope.kind = ScopeKind::Life;
+        let weeks = calendar_heatmap(&stats.scope, stats.activity.as_ref().unwrap()).unwrap();
+        assert_eq!(weeks.len(), 52);
+
+        // Other scopes have no heatmap
+        stats.scope.kind = ScopeKind::Month;
+        assert!(!render(
+            &stats,
+            Lang::En,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default()
+        )
+        .unwrap()
+        .contains("class=\"heatmap\""));
+    }
+
+    #[test]
+    fn test_with_auto_refresh() {
+        let html = "<html>\n<head>\n<meta charset=\"utf-8\">\n</head>\n</html>\n";
+        assert_eq!(
+            with_auto_refresh(html, 30),
+            "<html>\n<head>\n<meta http-equiv=\"refresh\" content=\"30\">\n\
+             <meta charset=\"utf-8\">\n</head>\n</html>\n"
+        );
+    }
+
+    #[test]
+    fn test_budget_warning() {
+        let html = render(
+            &example_stats(),
+            Lang::En,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+
+        assert_eq!(budget_warning(&html, 0), None);
+        assert_eq!(budget_warning(&html, 10_000), None);
+
+        let warning = budget_warning(&html, 1).expect("report should exceed 1 KB");
+        assert!(warning.contains("over the 1 KB budget"));
+        assert!(warning.contains("largest section: activity"));
+    }
+
+    #[test]
+    fn test_render_escapes_room_names() {
+        let mut stats = example_stats();
+        let rooms = stats.rooms.as_mut().unwrap();
+        rooms.top.as_mut().unwrap()[0].name = Some("<script>alert(1)</script>".to_string());
+
+        let html = render(
+            &stats,
+            Lang::En,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!html.contains("<script>alert"));
+    }
+
+    #[test]
+    fn test_render_avatar() {
+        let mut stats = example_stats();
+        stats.account.avatar_url = Some("mxc://example.org/abc".to_string());
+        let html = render(
+            &stats,
+            Lang::En,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+        assert!(html.contains("<code>mxc://example.org/abc</code>"));
+        assert!(!html.contains("room-avatar\" src"));
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("avatar-abc.png"), b"\x89PNG").unwrap();
+        stats.account.avatar_file = Some("avatar-abc.png".to_string());
+        let top = stats.rooms.as_mut().unwrap().top.as_mut().unwrap();
+        top[0].avatar_file = Some("room-avatar-def.gif".to_string());
+        let avatars = Avatars {
+            account: Avatar::load(&stats, dir.path()).unwrap(),
+            rooms: HashMap::from([(
+                "room-avatar-def.gif".to_string(),
+                Avatar {
+                    file_name: "room-avatar-def.gif".to_string(),
+                    bytes: b"GIF89a".to_vec(),
+                },
+            )]),
+        };
+        let html = render(
+            &stats,
+            Lang::En,
+            HtmlTheme::Auto,
+            &avatars,
+            &LinkBase::default(),
+        )
+        .unwrap();
+        assert!(html.contains("<img class=\"avatar\" src=\"data:image/png;base64,iVBORw==\""));
+        assert!(!html.contains("mxc://"));
+        // The top room is shown with its avatar, the others without
+        assert_eq!(html.matches("<img class=\"room-avatar\"").count(), 1);
+        assert!(html.contains("src=\"data:image/gif;base64,R0lGODlh\""));
+
+        // Stats files only name a file next to them
+        stats.account.avatar_file = Some("../avatar-abc.png".to_string());
+        assert!(Avatar::load(&stats, dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_render_message_kinds() {
+        let mut stats = example_stats();
+        let html = render(
+            &stats,
+            Lang::En,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+        assert!(html.contains("<h3>Message kinds by room type</h3>"));
+        // Scaled to private rooms, the busiest room type
+        assert!(html.contains(
+            "<span>Private rooms</span><div class=\"stack\" role=\"img\" aria-label=\"Private rooms: Text: 2,050, Media: 240, Voice: 12\"><span class=\"kind-text\" style=\"width: 89.1%\" title=\"Text: 2,050\"></span>"
+        ));
+        // Kinds without messages get no segment
+        assert!(html.contains("title=\"Media: 31\"></span></div>\n"));
+
+        stats.rooms.as_mut().unwrap().messages_by_room_type_kind = None;
+        let html = render(
+            &stats,
+            Lang::En,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+        assert!(!html.contains("Message kinds"));
+    }
+
+    #[test]
+    fn test_chart_summary() {
+        let bars = vec![
+            ("Mon".to_string(), 12),
+            ("Tue".to_string(), 1500),
+            ("Wed".to_string(), 3),
+            ("Thu".to_string(), 1500),
+        ];
+        assert_eq!(
+            chart_summary(&bars, Lang::En.locale()),
+            "from Mon to Thu, highest Tue (1,500), lowest Wed (3)"
+        );
+        assert_eq!(chart_summary(&[], Lang::En.locale()), "");
+    }
+
+    #[test]
+    fn test_render_localized() {
+        let html = render(
+            &example_stats(),
+            Lang::De,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+        assert!(html.contains("<html lang=\"de\">"));
+        assert!(html.contains("<h2>📊 Zusammenfassung</h2>"));
+        assert!(!html.contains("Summary"));
+    }
+
+    #[test]
+    fn test_render_compare_previous() {
+        let mut stats = example_stats();
+        stats.previous = Some(PreviousWindow {
+            key: "2024".to_string(),
+            messages_sent: 5200,
+            days_active: None,
+            reactions: Some(800),
+        });
+        let html = render(
+            &stats,
+            Lang::En,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+        assert!(html.contains(
+            "<span class=\"value\">4,832</span><span class=\"label\">💬 Messages sent</span><span class=\"delta\">▼ 7% vs 2024</span>"
+        ));
+        assert!(html.contains("<span class=\"note\">(▲ 40% vs 2024)</span></p>"));
+    }
+
+    #[test]
+    fn test_render_theme() {
+        let stats = example_stats();
+        let auto = render(
+            &stats,
+            Lang::En,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+        assert!(auto.contains("@media (prefers-color-scheme: dark)"));
+
+        let sunset = HtmlTheme::parse("sunset").unwrap();
+        let html = render(
+            &stats,
+            Lang::En,
+            sunset,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+        assert!(html.contains("--accent: #b84f30;"));
+        assert!(!html.contains("prefers-color-scheme"));
+    }
+
+    #[test]
+    fn test_time_series_chart_group() {
+        let html = render(
+            &example_stats(),
+            Lang::En,
+            HtmlTheme::Auto,
+            &Avatars::default(),
+            &LinkBase::default(),
+        )
+        .unwrap();
+        let group = html.split("<div class=\"chart-group\">").nth(1).unwrap();
+        let group = group.split("<h3>📅 By weekday</h3>").next().unwrap();
+        assert!(group.contains("<h3>📆 By month</h3>"));
+        // Week and day views come from the daily counts of the year
+        assert!(group.contains("<h3>📅 By week</h3>"));
+        assert!(group.contains("title=\"2025-W43: "));
+        assert!(group.contains("<h3>📅 By day</h3>"));
+        assert!(group.contains("title=\"Oct 21: 45\""));
+        // Weekday and hour charts stay outside the group
+        assert!(group.ends_with("</div>\n</div>\n"));
+        assert!(html.contains("<script>\ndocument.querySelectorAll('.chart-group')"));
+        assert!(!html.contains("src=\"http"));
+    }
+}
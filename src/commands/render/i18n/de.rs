@@ -0,0 +1,245 @@
+//! German report wording.
+use super::Locale;
+use crate::timefmt::DurationUnits;
+
+pub static LOCALE: Locale = Locale {
+    code: "de",
+    thousands_separator: '.',
+    decimal_separator: ',',
+    percent_sign: "{value}\u{a0}%",
+
+    title: "Dein Matrix: {scope}",
+    account: "Konto",
+    summary: "Zusammenfassung",
+    rooms: "Räume",
+    created_rooms: "Von dir erstellte Räume",
+    reactions: "Reaktionen",
+    activity: "Aktivität",
+    fun_facts: "Kurioses",
+    peaks: "Spitzen",
+    calendar: "Kalender",
+    by_year: "Nach Jahr",
+    by_month: "Nach Monat",
+    by_week: "Nach Woche",
+    by_day: "Nach Tag",
+    by_weekday: "Nach Wochentag",
+    by_hour: "Nach Uhrzeit (Ortszeit)",
+
+    user_id: "Benutzer-ID",
+    display_name: "Anzeigename",
+    avatar: "Avatar",
+    total_joined_rooms: "Beigetretene Räume",
+    timezone: "Zeitzone",
+    messages_sent: "Gesendete Nachrichten",
+    active_days: "Aktive Tage",
+    dm_rooms: "Direktnachrichten",
+    public_rooms: "Öffentliche Räume",
+    private_rooms: "Private Räume",
+    sections_refer_to: "Alle folgenden Abschnitte beziehen sich auf {scope}.",
+
+    scope_year: "Jahr {key}",
+    scope_quarter: "Quartal {key}",
+    scope_month: "Monat {key}",
+    scope_week: "Woche {key}",
+    scope_day: "Tag {key}",
+    scope_last_days: "Letzte {count} Tage",
+    scope_last_months: "Letzte {count} Monate",
+    scope_life: "Bisher insgesamt",
+    phrase_year: "das Jahr {key}",
+    phrase_quarter: "das Quartal {key}",
+    phrase_month: "den Monat {key}",
+    phrase_week: "die Woche {key}",
+    phrase_day: "den Tag {key}",
+    phrase_last_days: "die letzten {count} Tage",
+    phrase_last_months: "die letzten {count} Monate",
+    phrase_life: "deine gesamte Zeit auf Matrix",
+
+    rooms_sentence: "Du hast {messages} Nachrichten in {rooms} Räumen gesendet.",
+    most_active_rooms: "Deine aktivsten Räume",
+    message_kinds: "Nachrichtenarten nach Raumtyp",
+    kind_text: "Text",
+    kind_media: "Medien",
+    kind_voice: "Sprache",
+    unnamed_room: "(unbenannter Raum)",
+    rank: "Rang",
+    name: "Name",
+    messages: "Nachrichten",
+    percent_of_total: "% gesamt",
+    homeservers: "Wo du dich herumtreibst",
+    server: "Server",
+    conversation_balance: "Gesprächsbilanz in deinen Direktnachrichten",
+    sent: "Gesendet",
+    received: "Empfangen",
+    your_share: "Dein Anteil",
+    dm_balance_sentence: "Du hast {share} der Nachrichten mit {name} gesendet.",
+    room_trend: "{first} Nachrichten in der ersten Hälfte, {second} in der zweiten",
+    trend_note: "↑ und ↓: mehr oder weniger Nachrichten in der zweiten Hälfte als in der ersten.",
+    since_joined_one: "{count} Raum enthält nur Nachrichten seit deinem Beitritt.",
+    since_joined_other: "{count} Räume enthalten nur Nachrichten seit deinem Beitritt.",
+    joined_since: "seit deinem Beitritt {when}",
+    joined_in: "im {month}",
+    joined_on: "am {date}",
+
+    created_sentence: "Du hast {count} Räume {when} erstellt.",
+    created_year: "dieses Jahr",
+    created_quarter: "dieses Quartal",
+    created_month: "diesen Monat",
+    created_week: "diese Woche",
+    created_day: "heute",
+    created_last_days: "in den letzten {count} Tagen",
+    created_last_months: "in den letzten {count} Monaten",
+    created_life: "bisher insgesamt",
+
+    reactions_sentence:
+        "Mit {count} Reaktionen auf deine Nachrichten hast du Leute zum Lächeln gebracht!",
+    top_reactions: "Häufigste Reaktionen",
+    most_reacted_messages: "Nachrichten mit den meisten Reaktionen",
+    most_loved_rooms: "Wo du am meisten Liebe bekommst",
+    reactions_by_month: "Erhaltene Reaktionen pro Monat",
+    emoji: "Emoji",
+    custom_emote: "eigenes Emoji",
+    count: "Anzahl",
+    link: "Link",
+    view: "ansehen",
+
+    peak_year: "Spitzenjahr",
+    peak_month: "Spitzenmonat",
+    peak_week: "Spitzenwoche",
+    peak_day: "Spitzentag",
+    peak_hour: "Spitzenstunde",
+    hour_on: "{hour}:00 Uhr am {date}",
+    hour_of_day: "{hour}:00 Uhr",
+    one_message: "{n} Nachricht",
+    n_messages: "{n} Nachrichten",
+    year: "Jahr",
+    month: "Monat",
+    week: "Woche",
+    messages_per_day: "Nachrichten pro Tag",
+    chart_summary: "von {first} bis {last}, Höchstwert {max} ({max_count}), Tiefstwert {min} ({min_count})",
+    less: "Weniger",
+    more: "Mehr",
+    time_of_day: "Tageszeit",
+    persona: "{rooms} {when} (Spitze um {hour})",
+    persona_dm: "Direktnachrichten",
+    persona_public: "öffentliche Räume",
+    persona_private: "private Räume",
+    morning: "morgens",
+    afternoon: "nachmittags",
+    evening: "abends",
+    night: "nachts",
+    months_short: [
+        "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+    ],
+    months_long: [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+    weekdays_short: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+    weekdays_long: [
+        "Montag",
+        "Dienstag",
+        "Mittwoch",
+        "Donnerstag",
+        "Freitag",
+        "Samstag",
+        "Sonntag",
+    ],
+    short_date: "{day}. {month}",
+    long_date: "{day}. {month} {year}",
+    month_year: "{month} {year}",
+
+    typing_headline: "⌨️ Du hast ungefähr {time} mit dem Schreiben von Nachrichten verbracht.",
+    durations: DurationUnits {
+        seconds: "Sekunden",
+        sec: "Sek.",
+        min: "Min.",
+        hr: "Std.",
+        one_minute: "{n} Minute",
+        n_minutes: "{n} Minuten",
+        n_hours: "{n} Stunden",
+        one_day: "{n} Tag",
+        n_days: "{n} Tage",
+    },
+    first_to_reply: "{n}-mal als Erste:r geantwortet",
+    conversation_starts: "Du hast {n}-mal das Gespräch eröffnet",
+    emote_messages: "Du hast {n}-mal /me benutzt",
+    timezones: "{n} Zeitzonen",
+    react_every: "Du reagierst alle {n} gesendeten Nachrichten",
+    react_never: "Du reagierst nie",
+    twins: "{first} & {second} ({percent} gleiche Stunden)",
+    reaction_variety: "Eine deiner Nachrichten hat {n} verschiedene Emoji gesammelt",
+    longest_silence: "Deine längste Funkstille dauerte {days} ({range})",
+    fun_labels: &[
+        ("longest_message_chars", "Längste Nachricht (Zeichen)"),
+        ("favorite_weekday", "Lieblingswochentag"),
+        ("peak_hour", "Spitzenstunde"),
+        ("longest_streak_days", "Längste Serie"),
+        ("current_streak_days", "Aktuelle Serie"),
+        ("edits_per_message", "Bearbeitete Nachrichten"),
+        ("crawl_duration_seconds", "Crawl-Dauer"),
+        ("lurking_rooms", "Stille Räume"),
+        (
+            "sent_encrypted_messages_ratio",
+            "Verschlüsselte Nachrichten",
+        ),
+        ("first_responder_replies", "Schnellste Antwort"),
+        ("message_twins", "Zwillingsräume"),
+        ("matrix_age_days", "Auf Matrix seit"),
+        ("timezones", "Gematrixt aus"),
+        ("favorite_packs", "Liebste Sticker- und Emote-Pakete"),
+    ],
+
+    generated_by: "Erstellt am {date} mit {tool}",
+    provenance: "Zeitraum {window} · Daten vom {from} bis {to} · {accounts}",
+    regenerated: "Neu erstellt am {date} (zuvor am {previous}): {count} neu entdeckte Nachrichten",
+    regenerated_unchanged: "Neu erstellt am {date} (zuvor am {previous}): keine neu entdeckten Nachrichten",
+
+    archive: "Dein Matrix-Archiv",
+    all_reports: "Alle Berichte",
+
+    vs_previous: "{change} ggü. {previous}",
+
+    people: "Personen",
+    talked_with_most: "Mit wem du am meisten geschrieben hast",
+    most_seen_senders: "Am aktivsten in deinen Gruppenräumen",
+    shared_rooms: "Räume",
+    people_sentence:
+        "Am meisten hast du mit {name} geschrieben: {messages} in deinen Direktnachrichten.",
+
+    dms: "Direktnachrichten",
+    dm_correspondents: "Personen, denen du direkt geschrieben hast",
+    wrote_to_most: "Wem du am meisten geschrieben hast",
+    dms_sentence: "{name} hat die meisten deiner Direktnachrichten bekommen: {messages}.",
+
+    conversations: "Unterhaltungen",
+    main_timeline: "In der Hauptzeitleiste",
+    in_threads: "In Threads",
+    threads_posted_in: "Threads, in denen du geschrieben hast",
+    replies: "Antworten",
+    conversations_sentence: "{share} deiner Nachrichten gingen in Threads, {replies} antworteten auf eine andere Nachricht.",
+
+    message_lengths: "Nachrichtenlängen",
+    short_messages: "Kurz (bis 20 Zeichen)",
+    medium_messages: "Mittel (21 bis 140 Zeichen)",
+    long_messages: "Lang (über 140 Zeichen)",
+    longest_message: "Längste Nachricht",
+    characters: "{count} Zeichen",
+    message_lengths_sentence: "{short} deiner Textnachrichten waren kurz, {long} länger als 140 Zeichen.",
+
+    typed_emojis: "Deine liebsten getippten Emojis",
+    most_typed_emojis: "Am häufigsten getippte Emojis",
+    typed_emojis_sentence: "Du hast {count} Emojis in deinen Nachrichten getippt, am häufigsten {emoji}.",
+
+    reactions_given: "Reaktionen, die du vergeben hast",
+    reactions_given_sentence: "Du hast {count}-mal auf Nachrichten reagiert, am häufigsten mit {emoji}.",
+};
@@ -0,0 +1,237 @@
+//! English report wording (the default).
+use super::Locale;
+use crate::timefmt::DurationUnits;
+
+pub static LOCALE: Locale = Locale {
+    code: "en",
+    thousands_separator: ',',
+    decimal_separator: '.',
+    percent_sign: "{value}%",
+
+    title: "Your Matrix {scope}",
+    account: "Account",
+    summary: "Summary",
+    rooms: "Rooms",
+    created_rooms: "Rooms You Created",
+    reactions: "Reactions",
+    activity: "Activity",
+    fun_facts: "Fun Facts",
+    peaks: "Peaks",
+    calendar: "Calendar",
+    by_year: "By year",
+    by_month: "By month",
+    by_week: "By week",
+    by_day: "By day",
+    by_weekday: "By weekday",
+    by_hour: "By hour (local time)",
+
+    user_id: "User ID",
+    display_name: "Display name",
+    avatar: "Avatar",
+    total_joined_rooms: "Total joined rooms",
+    timezone: "Timezone",
+    messages_sent: "Messages sent",
+    active_days: "Active days",
+    dm_rooms: "DM rooms",
+    public_rooms: "Public rooms",
+    private_rooms: "Private rooms",
+    sections_refer_to: "All sections below refer to {scope}.",
+
+    scope_year: "Year {key}",
+    scope_quarter: "Quarter {key}",
+    scope_month: "Month {key}",
+    scope_week: "Week {key}",
+    scope_day: "Day {key}",
+    scope_last_days: "Last {count} days",
+    scope_last_months: "Last {count} months",
+    scope_life: "Life-to-date",
+    phrase_year: "the year {key}",
+    phrase_quarter: "the quarter {key}",
+    phrase_month: "the month {key}",
+    phrase_week: "the week {key}",
+    phrase_day: "the day {key}",
+    phrase_last_days: "the last {count} days",
+    phrase_last_months: "the last {count} months",
+    phrase_life: "your life on Matrix so far",
+
+    rooms_sentence: "You sent {messages} messages in {rooms} rooms.",
+    most_active_rooms: "Your most active rooms",
+    message_kinds: "Message kinds by room type",
+    kind_text: "Text",
+    kind_media: "Media",
+    kind_voice: "Voice",
+    unnamed_room: "(unnamed room)",
+    rank: "Rank",
+    name: "Name",
+    messages: "Messages",
+    percent_of_total: "% of total",
+    homeservers: "Where you hang out",
+    server: "Server",
+    conversation_balance: "Conversation balance in your DMs",
+    sent: "Sent",
+    received: "Received",
+    your_share: "Your share",
+    dm_balance_sentence: "You sent {share} of the messages with {name}.",
+    room_trend: "{first} messages in the first half, {second} in the second",
+    trend_note: "↑ and ↓: more or fewer messages in the second half than in the first.",
+    since_joined_one: "{count} room only covers messages since you joined.",
+    since_joined_other: "{count} rooms cover only messages since you joined.",
+    joined_since: "covers since you joined {when}",
+    joined_in: "in {month}",
+    joined_on: "on {date}",
+
+    created_sentence: "You created {count} rooms {when}.",
+    created_year: "this year",
+    created_quarter: "this quarter",
+    created_month: "this month",
+    created_week: "this week",
+    created_day: "today",
+    created_last_days: "in the last {count} days",
+    created_last_months: "in the last {count} months",
+    created_life: "in your lifetime",
+
+    reactions_sentence: "You made people smile with {count} reactions on your messages!",
+    top_reactions: "Top reactions",
+    most_reacted_messages: "Most reacted messages",
+    most_loved_rooms: "Where you get the most love",
+    reactions_by_month: "Reactions received by month",
+    emoji: "Emoji",
+    custom_emote: "custom emote",
+    count: "Count",
+    link: "Link",
+    view: "view",
+
+    peak_year: "Peak year",
+    peak_month: "Peak month",
+    peak_week: "Peak week",
+    peak_day: "Peak day",
+    peak_hour: "Peak hour",
+    hour_on: "{hour}:00 on {date}",
+    hour_of_day: "{hour}:00",
+    one_message: "{n} message",
+    n_messages: "{n} messages",
+    year: "Year",
+    month: "Month",
+    week: "Week",
+    messages_per_day: "Messages per day",
+    chart_summary:
+        "from {first} to {last}, highest {max} ({max_count}), lowest {min} ({min_count})",
+    less: "Less",
+    more: "More",
+    time_of_day: "Time of day",
+    persona: "{rooms} {when} (peak {hour})",
+    persona_dm: "DMs",
+    persona_public: "public rooms",
+    persona_private: "private rooms",
+    morning: "in the morning",
+    afternoon: "in the afternoon",
+    evening: "in the evening",
+    night: "at night",
+    months_short: [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+    months_long: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    weekdays_short: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    weekdays_long: [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ],
+    short_date: "{month} {day}",
+    long_date: "{month} {day}, {year}",
+    month_year: "{month} {year}",
+
+    typing_headline: "⌨️ You spent roughly {time} writing messages.",
+    durations: DurationUnits {
+        seconds: "seconds",
+        sec: "sec",
+        min: "min",
+        hr: "hr",
+        one_minute: "{n} minute",
+        n_minutes: "{n} minutes",
+        n_hours: "{n} hours",
+        one_day: "{n} day",
+        n_days: "{n} days",
+    },
+    first_to_reply: "first to reply {n} times",
+    conversation_starts: "You started the conversation {n} times",
+    emote_messages: "You /me'd {n} times",
+    timezones: "{n} timezones",
+    react_every: "You react on every {n} sent messages",
+    react_never: "You react on never",
+    twins: "{first} & {second} ({percent} alike hours)",
+    reaction_variety: "One of your messages collected {n} different emoji",
+    longest_silence: "Your quietest stretch was {days} ({range})",
+    fun_labels: &[
+        ("sent_encrypted_messages_ratio", "Encrypted messages"),
+        ("first_responder_replies", "Community first responder"),
+        ("message_twins", "Message twins"),
+        ("matrix_age_days", "On Matrix for"),
+        ("timezones", "Matrixed from"),
+        ("favorite_packs", "Favorite sticker & emote packs"),
+    ],
+
+    generated_by: "Generated {date} by {tool}",
+    provenance: "window {window} · data from {from} to {to} · {accounts}",
+    regenerated:
+        "Regenerated {date} (last generated {previous}): {count} newly discovered messages",
+    regenerated_unchanged:
+        "Regenerated {date} (last generated {previous}): no newly discovered messages",
+
+    archive: "Your Matrix archive",
+    all_reports: "All reports",
+
+    vs_previous: "{change} vs {previous}",
+
+    people: "People",
+    talked_with_most: "People you talked with most",
+    most_seen_senders: "Most seen in your group rooms",
+    shared_rooms: "Rooms",
+    people_sentence: "You talked most with {name}: {messages} exchanged in your DMs.",
+
+    dms: "DMs",
+    dm_correspondents: "People you wrote to in DMs",
+    wrote_to_most: "People you wrote to most",
+    dms_sentence: "{name} got the most of your DMs: {messages}.",
+
+    conversations: "Conversations",
+    main_timeline: "In the main timeline",
+    in_threads: "In threads",
+    threads_posted_in: "Threads you posted in",
+    replies: "Replies",
+    conversations_sentence:
+        "{share} of your messages went to threads, and {replies} replied to another message.",
+
+    message_lengths: "Message Lengths",
+    short_messages: "Short (up to 20 characters)",
+    medium_messages: "Medium (21 to 140 characters)",
+    long_messages: "Long (over 140 characters)",
+    longest_message: "Longest message",
+    characters: "{count} characters",
+    message_lengths_sentence:
+        "{short} of your text messages were short, and {long} ran past 140 characters.",
+
+    typed_emojis: "Your Favorite Emoji to Type",
+    most_typed_emojis: "Most typed emoji",
+    typed_emojis_sentence: "You typed {count} emoji in your messages, {emoji} most of all.",
+
+    reactions_given: "Reactions you gave",
+    reactions_given_sentence: "You reacted {count} times to messages, with {emoji} most of all.",
+};
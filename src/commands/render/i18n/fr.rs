@@ -0,0 +1,235 @@
+//! French report wording.
+use super::Locale;
+use crate::timefmt::DurationUnits;
+
+pub static LOCALE: Locale = Locale {
+    code: "fr",
+    thousands_separator: '\u{a0}',
+    decimal_separator: ',',
+    percent_sign: "{value}\u{a0}%",
+
+    title: "Ton Matrix : {scope}",
+    account: "Compte",
+    summary: "Résumé",
+    rooms: "Salons",
+    created_rooms: "Salons que tu as créés",
+    reactions: "Réactions",
+    activity: "Activité",
+    fun_facts: "Anecdotes",
+    peaks: "Records",
+    calendar: "Calendrier",
+    by_year: "Par année",
+    by_month: "Par mois",
+    by_week: "Par semaine",
+    by_day: "Par jour",
+    by_weekday: "Par jour de la semaine",
+    by_hour: "Par heure (heure locale)",
+
+    user_id: "Identifiant",
+    display_name: "Nom affiché",
+    avatar: "Avatar",
+    total_joined_rooms: "Salons rejoints",
+    timezone: "Fuseau horaire",
+    messages_sent: "Messages envoyés",
+    active_days: "Jours actifs",
+    dm_rooms: "Messages privés",
+    public_rooms: "Salons publics",
+    private_rooms: "Salons privés",
+    sections_refer_to: "Toutes les sections ci-dessous portent sur {scope}.",
+
+    scope_year: "Année {key}",
+    scope_quarter: "Trimestre {key}",
+    scope_month: "Mois {key}",
+    scope_week: "Semaine {key}",
+    scope_day: "Jour {key}",
+    scope_last_days: "{count} derniers jours",
+    scope_last_months: "{count} derniers mois",
+    scope_life: "Depuis toujours",
+    phrase_year: "l'année {key}",
+    phrase_quarter: "le trimestre {key}",
+    phrase_month: "le mois {key}",
+    phrase_week: "la semaine {key}",
+    phrase_day: "le jour {key}",
+    phrase_last_days: "les {count} derniers jours",
+    phrase_last_months: "les {count} derniers mois",
+    phrase_life: "toute ta vie sur Matrix",
+
+    rooms_sentence: "Tu as envoyé {messages} messages dans {rooms} salons.",
+    most_active_rooms: "Tes salons les plus actifs",
+    message_kinds: "Types de messages par type de salon",
+    kind_text: "Texte",
+    kind_media: "Médias",
+    kind_voice: "Vocal",
+    unnamed_room: "(salon sans nom)",
+    rank: "Rang",
+    name: "Nom",
+    messages: "Messages",
+    percent_of_total: "% du total",
+    homeservers: "Là où tu traînes",
+    server: "Serveur",
+    conversation_balance: "Équilibre de tes conversations privées",
+    sent: "Envoyés",
+    received: "Reçus",
+    your_share: "Ta part",
+    dm_balance_sentence: "Tu as envoyé {share} des messages avec {name}.",
+    room_trend: "{first} messages dans la première moitié, {second} dans la seconde",
+    trend_note: "↑ et ↓ : plus ou moins de messages dans la seconde moitié que dans la première.",
+    since_joined_one: "{count} salon ne couvre que les messages depuis ton arrivée.",
+    since_joined_other: "{count} salons ne couvrent que les messages depuis ton arrivée.",
+    joined_since: "depuis ton arrivée {when}",
+    joined_in: "en {month}",
+    joined_on: "le {date}",
+
+    created_sentence: "Tu as créé {count} salons {when}.",
+    created_year: "cette année",
+    created_quarter: "ce trimestre",
+    created_month: "ce mois-ci",
+    created_week: "cette semaine",
+    created_day: "aujourd'hui",
+    created_last_days: "ces {count} derniers jours",
+    created_last_months: "ces {count} derniers mois",
+    created_life: "depuis toujours",
+
+    reactions_sentence: "Tes messages ont fait sourire avec {count} réactions !",
+    top_reactions: "Réactions préférées",
+    most_reacted_messages: "Messages les plus réactés",
+    most_loved_rooms: "Là où tu reçois le plus d'amour",
+    reactions_by_month: "Réactions reçues par mois",
+    emoji: "Emoji",
+    custom_emote: "émoji personnalisé",
+    count: "Nombre",
+    link: "Lien",
+    view: "voir",
+
+    peak_year: "Meilleure année",
+    peak_month: "Meilleur mois",
+    peak_week: "Meilleure semaine",
+    peak_day: "Meilleur jour",
+    peak_hour: "Heure de pointe",
+    hour_on: "{hour} h le {date}",
+    hour_of_day: "{hour} h",
+    one_message: "{n} message",
+    n_messages: "{n} messages",
+    year: "Année",
+    month: "Mois",
+    week: "Semaine",
+    messages_per_day: "Messages par jour",
+    chart_summary: "de {first} à {last}, maximum {max} ({max_count}), minimum {min} ({min_count})",
+    less: "Moins",
+    more: "Plus",
+    time_of_day: "Moment de la journée",
+    persona: "{rooms} {when} (pic à {hour})",
+    persona_dm: "messages privés",
+    persona_public: "salons publics",
+    persona_private: "salons privés",
+    morning: "le matin",
+    afternoon: "l'après-midi",
+    evening: "le soir",
+    night: "la nuit",
+    months_short: [
+        "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.",
+        "déc.",
+    ],
+    months_long: [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    weekdays_short: ["lun", "mar", "mer", "jeu", "ven", "sam", "dim"],
+    weekdays_long: [
+        "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+    ],
+    short_date: "{day} {month}",
+    long_date: "{day} {month} {year}",
+    month_year: "{month} {year}",
+
+    typing_headline: "⌨️ Tu as passé environ {time} à écrire des messages.",
+    durations: DurationUnits {
+        seconds: "secondes",
+        sec: "s",
+        min: "min",
+        hr: "h",
+        one_minute: "{n} minute",
+        n_minutes: "{n} minutes",
+        n_hours: "{n} heures",
+        one_day: "{n} jour",
+        n_days: "{n} jours",
+    },
+    first_to_reply: "premier à répondre {n} fois",
+    conversation_starts: "Tu as lancé la conversation {n} fois",
+    emote_messages: "Tu as fait {n} /me",
+    timezones: "{n} fuseaux horaires",
+    react_every: "Tu réagis tous les {n} messages envoyés",
+    react_never: "Tu ne réagis jamais",
+    twins: "{first} & {second} ({percent} d'heures en commun)",
+    reaction_variety: "Un de tes messages a récolté {n} emoji différents",
+    longest_silence: "Ta plus longue pause a duré {days} ({range})",
+    fun_labels: &[
+        ("longest_message_chars", "Plus long message (caractères)"),
+        ("favorite_weekday", "Jour préféré"),
+        ("peak_hour", "Heure de pointe"),
+        ("longest_streak_days", "Plus longue série"),
+        ("current_streak_days", "Série en cours"),
+        ("edits_per_message", "Messages modifiés"),
+        ("crawl_duration_seconds", "Durée de collecte"),
+        ("lurking_rooms", "Salons observés en silence"),
+        ("sent_encrypted_messages_ratio", "Messages chiffrés"),
+        ("first_responder_replies", "Premier à répondre"),
+        ("message_twins", "Salons jumeaux"),
+        ("matrix_age_days", "Sur Matrix depuis"),
+        ("timezones", "Matrixé depuis"),
+        ("favorite_packs", "Packs de stickers et d'émojis préférés"),
+    ],
+
+    generated_by: "Généré le {date} par {tool}",
+    provenance: "fenêtre {window} · données du {from} au {to} · {accounts}",
+    regenerated: "Régénéré le {date} (précédemment le {previous}) : {count} messages nouvellement découverts",
+    regenerated_unchanged: "Régénéré le {date} (précédemment le {previous}) : aucun nouveau message découvert",
+
+    archive: "Tes archives Matrix",
+    all_reports: "Tous les rapports",
+
+    vs_previous: "{change} par rapport à {previous}",
+
+    people: "Personnes",
+    talked_with_most: "Les personnes avec qui tu as le plus échangé",
+    most_seen_senders: "Les plus actifs dans tes salons de groupe",
+    shared_rooms: "Salons",
+    people_sentence: "Tu as le plus échangé avec {name} : {messages} dans tes messages privés.",
+
+    dms: "Messages privés",
+    dm_correspondents: "Personnes à qui tu as écrit en privé",
+    wrote_to_most: "Les personnes à qui tu as le plus écrit",
+    dms_sentence: "{name} a reçu le plus de tes messages privés : {messages}.",
+
+    conversations: "Conversations",
+    main_timeline: "Dans le fil principal",
+    in_threads: "Dans des fils de discussion",
+    threads_posted_in: "Fils où tu as écrit",
+    replies: "Réponses",
+    conversations_sentence: "{share} de tes messages sont allés dans des fils de discussion, et {replies} répondaient à un autre message.",
+
+    message_lengths: "Longueur des messages",
+    short_messages: "Courts (jusqu'à 20 caractères)",
+    medium_messages: "Moyens (de 21 à 140 caractères)",
+    long_messages: "Longs (plus de 140 caractères)",
+    longest_message: "Plus long message",
+    characters: "{count} caractères",
+    message_lengths_sentence: "{short} de tes messages texte étaient courts, et {long} dépassaient 140 caractères.",
+
+    typed_emojis: "Tes émojis préférés à taper",
+    most_typed_emojis: "Émojis les plus tapés",
+    typed_emojis_sentence: "Tu as tapé {count} émojis dans tes messages, surtout {emoji}.",
+
+    reactions_given: "Réactions que tu as données",
+    reactions_given_sentence: "Tu as réagi {count} fois à des messages, surtout avec {emoji}.",
+};
@@ -0,0 +1,513 @@
+/// Report localization.
+///
+/// Each language is a [`Locale`]: section titles, labels, sentences (with
+/// `{name}` placeholders, see [`fill`]), month and weekday names and number
+/// separators. Renderers take every user-facing word from the locale; the data
+/// itself (room names, dates used as keys) is shown as-is.
+use crate::timefmt::{self, DurationUnits};
+use anyhow::Result;
+use serde::Serialize;
+
+mod de;
+mod en;
+mod fr;
+
+/// Language of the rendered reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    Fr,
+    De,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 3] = [Lang::En, Lang::Fr, Lang::De];
+
+    /// Parses a language code (`en`, `fr`, `de`).
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|lang| lang.locale().code == input)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Unknown language '{}' (expected one of: en, fr, de)", input)
+            })
+    }
+
+    pub fn locale(&self) -> &'static Locale {
+        match self {
+            Lang::En => &en::LOCALE,
+            Lang::Fr => &fr::LOCALE,
+            Lang::De => &de::LOCALE,
+        }
+    }
+}
+
+/// Report wording in one language.
+///
+/// Serialized as `t` in the Markdown template context, so custom templates can
+/// use the same wording (e.g. `{{ t.summary }}`).
+#[derive(Debug, Serialize)]
+pub struct Locale {
+    /// Language code, also used as the HTML `lang` attribute
+    pub code: &'static str,
+    #[serde(skip)]
+    pub thousands_separator: char,
+    #[serde(skip)]
+    pub decimal_separator: char,
+    /// `{value}`, e.g. "{value}%"
+    #[serde(skip)]
+    pub percent_sign: &'static str,
+
+    // Title and section headings
+    /// `{scope}`
+    pub title: &'static str,
+    pub account: &'static str,
+    pub summary: &'static str,
+    pub rooms: &'static str,
+    pub created_rooms: &'static str,
+    pub reactions: &'static str,
+    pub activity: &'static str,
+    pub fun_facts: &'static str,
+    pub peaks: &'static str,
+    pub calendar: &'static str,
+    pub by_year: &'static str,
+    pub by_month: &'static str,
+    pub by_week: &'static str,
+    pub by_day: &'static str,
+    pub by_weekday: &'static str,
+    pub by_hour: &'static str,
+
+    // Account and summary
+    pub user_id: &'static str,
+    pub display_name: &'static str,
+    pub avatar: &'static str,
+    pub total_joined_rooms: &'static str,
+    /// Timezone hours and days are counted in
+    pub timezone: &'static str,
+    pub messages_sent: &'static str,
+    pub active_days: &'static str,
+    pub dm_rooms: &'static str,
+    pub public_rooms: &'static str,
+    pub private_rooms: &'static str,
+    /// `{scope}`
+    pub sections_refer_to: &'static str,
+
+    // Scope labels (`{key}`, e.g. "Year 2025") and phrases ("the year 2025")
+    pub scope_year: &'static str,
+    pub scope_quarter: &'static str,
+    pub scope_month: &'static str,
+    pub scope_week: &'static str,
+    pub scope_day: &'static str,
+    /// `{count}` of a rolling window, e.g. "Last 30 days"
+    pub scope_last_days: &'static str,
+    pub scope_last_months: &'static str,
+    pub scope_life: &'static str,
+    pub phrase_year: &'static str,
+    pub phrase_quarter: &'static str,
+    pub phrase_month: &'static str,
+    pub phrase_week: &'static str,
+    pub phrase_day: &'static str,
+    pub phrase_last_days: &'static str,
+    pub phrase_last_months: &'static str,
+    pub phrase_life: &'static str,
+
+    // Rooms
+    /// `{messages}`, `{rooms}`
+    pub rooms_sentence: &'static str,
+    pub most_active_rooms: &'static str,
+    pub message_kinds: &'static str,
+    pub kind_text: &'static str,
+    pub kind_media: &'static str,
+    pub kind_voice: &'static str,
+    pub unnamed_room: &'static str,
+    pub rank: &'static str,
+    pub name: &'static str,
+    pub messages: &'static str,
+    pub percent_of_total: &'static str,
+    /// Table of the messages sent per homeserver of the rooms
+    pub homeservers: &'static str,
+    pub server: &'static str,
+    pub conversation_balance: &'static str,
+    pub sent: &'static str,
+    pub received: &'static str,
+    pub your_share: &'static str,
+    /// `{share}`, `{name}`
+    pub dm_balance_sentence: &'static str,
+    /// `{first}`, `{second}`: a top room's messages in each half of the window
+    pub room_trend: &'static str,
+    /// Legend of the ↑ and ↓ arrows of the top rooms
+    pub trend_note: &'static str,
+    /// `{count}`
+    pub since_joined_one: &'static str,
+    /// `{count}`
+    pub since_joined_other: &'static str,
+    /// `{when}`, one of `joined_in` / `joined_on`
+    pub joined_since: &'static str,
+    /// `{month}`
+    pub joined_in: &'static str,
+    /// `{date}`
+    pub joined_on: &'static str,
+
+    // Created rooms
+    /// `{count}`, `{when}`
+    pub created_sentence: &'static str,
+    pub created_year: &'static str,
+    pub created_quarter: &'static str,
+    pub created_month: &'static str,
+    pub created_week: &'static str,
+    pub created_day: &'static str,
+    pub created_last_days: &'static str,
+    pub created_last_months: &'static str,
+    pub created_life: &'static str,
+
+    // Reactions
+    /// `{count}`
+    pub reactions_sentence: &'static str,
+    pub top_reactions: &'static str,
+    pub most_reacted_messages: &'static str,
+    pub most_loved_rooms: &'static str,
+    pub reactions_by_month: &'static str,
+    pub emoji: &'static str,
+    /// Custom emote (image) reaction whose shortcode is unknown
+    pub custom_emote: &'static str,
+    pub count: &'static str,
+    pub link: &'static str,
+    pub view: &'static str,
+
+    // Activity
+    pub peak_year: &'static str,
+    pub peak_month: &'static str,
+    pub peak_week: &'static str,
+    pub peak_day: &'static str,
+    pub peak_hour: &'static str,
+    /// `{hour}`, `{date}`
+    pub hour_on: &'static str,
+    /// `{hour}`
+    pub hour_of_day: &'static str,
+    /// `{n}`
+    pub one_message: &'static str,
+    /// `{n}`
+    pub n_messages: &'static str,
+    pub year: &'static str,
+    pub month: &'static str,
+    pub week: &'static str,
+    pub messages_per_day: &'static str,
+    /// `{first}`, `{last}`, `{max}`, `{max_count}`, `{min}`, `{min_count}`: text
+    /// alternative of HTML charts
+    pub chart_summary: &'static str,
+    pub less: &'static str,
+    pub more: &'static str,
+    pub time_of_day: &'static str,
+    /// `{rooms}`, `{when}`, `{hour}` (an `hour_of_day`)
+    pub persona: &'static str,
+    pub persona_dm: &'static str,
+    pub persona_public: &'static str,
+    pub persona_private: &'static str,
+    pub morning: &'static str,
+    pub afternoon: &'static str,
+    pub evening: &'static str,
+    pub night: &'static str,
+    pub months_short: [&'static str; 12],
+    pub months_long: [&'static str; 12],
+    /// Monday first, at most 3 characters (they label the Markdown calendar rows)
+    pub weekdays_short: [&'static str; 7],
+    /// Monday first
+    pub weekdays_long: [&'static str; 7],
+    /// `{month}` (short), `{day}`
+    pub short_date: &'static str,
+    /// `{month}` (short), `{day}`, `{year}`
+    pub long_date: &'static str,
+    /// `{month}` (long), `{year}`
+    pub month_year: &'static str,
+
+    // Fun
+    /// `{time}`
+    pub typing_headline: &'static str,
+    #[serde(skip)]
+    pub durations: DurationUnits,
+    /// `{n}`
+    pub first_to_reply: &'static str,
+    /// `{n}`
+    pub conversation_starts: &'static str,
+    /// `{n}`: `/me` messages
+    pub emote_messages: &'static str,
+    /// `{n}`
+    pub timezones: &'static str,
+    /// `{n}`
+    pub react_every: &'static str,
+    pub react_never: &'static str,
+    /// `{first}`, `{second}`, `{percent}`
+    pub twins: &'static str,
+    /// `{n}`: distinct emoji on the user's most varied message
+    pub reaction_variety: &'static str,
+    /// `{days}` (a duration in days), `{range}` (dates)
+    pub longest_silence: &'static str,
+    /// Labels of Fun fields by key; other keys are shown as their humanized name
+    #[serde(skip)]
+    pub fun_labels: &'static [(&'static str, &'static str)],
+
+    /// `{date}`, `{tool}`
+    pub generated_by: &'static str,
+    /// `{window}`, `{from}`, `{to}`, `{accounts}`
+    pub provenance: &'static str,
+    /// `{date}`, `{previous}`, `{count}`: note of `--changelog` on re-crawled stats
+    pub regenerated: &'static str,
+    /// `{date}`, `{previous}`: same, when the crawl found no new messages
+    pub regenerated_unchanged: &'static str,
+
+    // Static site of several reports (`my render --site`)
+    pub archive: &'static str,
+    pub all_reports: &'static str,
+
+    // Change against the previous window (`--compare-previous`)
+    pub vs_previous: &'static str,
+
+    // People section
+    pub people: &'static str,
+    pub talked_with_most: &'static str,
+    pub most_seen_senders: &'static str,
+    pub shared_rooms: &'static str,
+    /// `{name}`, `{messages}` (a message count)
+    pub people_sentence: &'static str,
+
+    // DMs section
+    pub dms: &'static str,
+    pub dm_correspondents: &'static str,
+    pub wrote_to_most: &'static str,
+    /// `{name}`, `{messages}` (a message count)
+    pub dms_sentence: &'static str,
+
+    // Conversations section
+    pub conversations: &'static str,
+    pub main_timeline: &'static str,
+    pub in_threads: &'static str,
+    pub threads_posted_in: &'static str,
+    pub replies: &'static str,
+    /// `{share}` (a percentage), `{replies}` (a number)
+    pub conversations_sentence: &'static str,
+
+    // Message lengths section
+    pub message_lengths: &'static str,
+    pub short_messages: &'static str,
+    pub medium_messages: &'static str,
+    pub long_messages: &'static str,
+    pub longest_message: &'static str,
+    /// `{count}` (a number)
+    pub characters: &'static str,
+    /// `{short}`, `{long}` (percentages)
+    pub message_lengths_sentence: &'static str,
+
+    // Typed emojis section
+    pub typed_emojis: &'static str,
+    pub most_typed_emojis: &'static str,
+    /// `{count}` (a number), `{emoji}` (the favorite one)
+    pub typed_emojis_sentence: &'static str,
+
+    // Reactions the user gave, in the Reactions section
+    pub reactions_given: &'static str,
+    /// `{count}` (a number), `{emoji}` (the most sent one)
+    pub reactions_given_sentence: &'static str,
+}
+
+impl Locale {
+    /// Formats a number with the locale's thousand separator.
+    pub fn number(&self, n: i32) -> String {
+        super::common::format_number(n).replace(',', &self.thousands_separator.to_string())
+    }
+
+    /// Formats a decimal number with the locale's decimal separator.
+    pub fn decimal(&self, value: f64, precision: usize) -> String {
+        format!("{:.*}", precision, value).replace('.', &self.decimal_separator.to_string())
+    }
+
+    /// Formats a ratio as a percentage (e.g. "12.5%").
+    pub fn percent(&self, ratio: f64, precision: usize) -> String {
+        fill(
+            self.percent_sign,
+            &[("value", &self.decimal(ratio * 100.0, precision))],
+        )
+    }
+
+    /// "1 message" / "12 messages"
+    pub fn message_count(&self, n: i32) -> String {
+        let template = if n == 1 {
+            self.one_message
+        } else {
+            self.n_messages
+        };
+        fill(template, &[("n", &self.number(n))])
+    }
+
+    /// Precise duration, e.g. "2 min 22 sec".
+    pub fn duration(&self, seconds: i64) -> String {
+        timefmt::format_duration(seconds, &self.durations, self.thousands_separator)
+    }
+
+    /// Rounded duration for estimates, e.g. "31 hours".
+    pub fn minutes(&self, minutes: i64) -> String {
+        timefmt::humanize_minutes(minutes, &self.durations, self.thousands_separator)
+    }
+
+    /// Number of days, e.g. "15 days".
+    pub fn days(&self, days: i64) -> String {
+        timefmt::format_days(days, &self.durations, self.thousands_separator)
+    }
+
+    /// Short month name, from 1 (January) to 12.
+    pub fn month_short(&self, month: u32) -> &'static str {
+        self.months_short[(month as usize).clamp(1, 12) - 1]
+    }
+
+    /// Long month name, from 1 (January) to 12.
+    pub fn month_long(&self, month: u32) -> &'static str {
+        self.months_long[(month as usize).clamp(1, 12) - 1]
+    }
+
+    /// Month the stats name in English (e.g. `October`), in this language.
+    ///
+    /// Unknown names are kept as they are.
+    pub fn stats_month(&self, name: &str) -> String {
+        match name.parse::<chrono::Month>() {
+            Ok(month) => self.month_long(month.number_from_month()).to_string(),
+            Err(_) => name.to_string(),
+        }
+    }
+
+    /// Weekday the stats name in English (e.g. `Thursday` or `Thu`), in this
+    /// language.
+    ///
+    /// Unknown names are kept as they are.
+    pub fn stats_weekday(&self, name: &str) -> String {
+        match name.parse::<chrono::Weekday>() {
+            Ok(weekday) => self.weekdays_long[weekday.num_days_from_monday() as usize].to_string(),
+            Err(_) => name.to_string(),
+        }
+    }
+
+    /// Date with a short month, e.g. "Oct 21".
+    pub fn short_date(&self, date: chrono::NaiveDate) -> String {
+        use chrono::Datelike;
+        fill(
+            self.short_date,
+            &[
+                ("month", self.month_short(date.month())),
+                ("day", &date.day().to_string()),
+            ],
+        )
+    }
+
+    /// Date with a short month and the year, e.g. "Oct 21, 2025".
+    pub fn long_date(&self, date: chrono::NaiveDate) -> String {
+        use chrono::Datelike;
+        fill(
+            self.long_date,
+            &[
+                ("month", self.month_short(date.month())),
+                ("day", &date.day().to_string()),
+                ("year", &date.year().to_string()),
+            ],
+        )
+    }
+
+    /// Label of a Fun field, if the locale has one.
+    pub fn fun_label(&self, key: &str) -> Option<&'static str> {
+        self.fun_labels
+            .iter()
+            .find(|(field, _)| *field == key)
+            .map(|(_, label)| *label)
+    }
+}
+
+/// Replaces `{name}` placeholders in a locale string.
+pub fn fill(template: &str, args: &[(&str, &str)]) -> String {
+    let mut text = template.to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lang() {
+        assert_eq!(Lang::parse("FR").unwrap(), Lang::Fr);
+        assert_eq!(Lang::parse("de").unwrap(), Lang::De);
+        assert!(Lang::parse("es").is_err());
+    }
+
+    #[test]
+    fn test_numbers_and_dates() {
+        let date = chrono::NaiveDate::from_ymd_opt(2025, 10, 21).unwrap();
+
+        let en = Lang::En.locale();
+        assert_eq!(en.number(12345), "12,345");
+        assert_eq!(en.percent(0.125, 1), "12.5%");
+        assert_eq!(en.long_date(date), "Oct 21, 2025");
+
+        let fr = Lang::Fr.locale();
+        assert_eq!(fr.number(12345), "12\u{a0}345");
+        assert_eq!(fr.percent(0.125, 1), "12,5\u{a0}%");
+        assert_eq!(fr.message_count(1), "1 message");
+
+        assert_eq!(fr.stats_month("October"), "octobre");
+        assert_eq!(fr.stats_weekday("Thursday"), "jeudi");
+
+        let de = Lang::De.locale();
+        assert_eq!(de.number(12345), "12.345");
+        assert_eq!(de.long_date(date), "21. Okt 2025");
+        assert_eq!(de.stats_weekday("Sun"), "Sonntag");
+        assert_eq!(de.stats_month("Smarch"), "Smarch");
+    }
+
+    #[test]
+    fn test_locales_are_complete() {
+        for lang in Lang::ALL {
+            let locale = lang.locale();
+            assert!(locale.title.contains("{scope}"), "{}", locale.code);
+            assert!(locale.rooms_sentence.contains("{messages}"));
+            assert!(locale.rooms_sentence.contains("{rooms}"));
+            assert!(locale.created_sentence.contains("{when}"));
+            assert!(locale.typing_headline.contains("{time}"));
+            assert!(locale.twins.contains("{percent}"));
+            assert!(locale.reaction_variety.contains("{n}"));
+            assert!(locale.longest_silence.contains("{days}"));
+            assert!(locale.longest_silence.contains("{range}"));
+            assert!(locale.conversation_starts.contains("{n}"));
+            assert!(locale.emote_messages.contains("{n}"));
+            assert!(locale.persona.contains("{hour}"));
+            assert!(locale.dm_balance_sentence.contains("{share}"));
+            assert!(locale.room_trend.contains("{first}"));
+            assert!(locale.room_trend.contains("{second}"));
+            assert!(locale.vs_previous.contains("{change}"));
+            assert!(locale.vs_previous.contains("{previous}"));
+            assert!(locale.people_sentence.contains("{name}"));
+            assert!(locale.people_sentence.contains("{messages}"));
+            assert!(locale.dms_sentence.contains("{name}"));
+            assert!(locale.dms_sentence.contains("{messages}"));
+            assert!(locale.conversations_sentence.contains("{share}"));
+            assert!(locale.conversations_sentence.contains("{replies}"));
+            assert!(locale.characters.contains("{count}"));
+            assert!(locale.message_lengths_sentence.contains("{short}"));
+            assert!(locale.message_lengths_sentence.contains("{long}"));
+            assert!(locale.typed_emojis_sentence.contains("{count}"));
+            assert!(locale.typed_emojis_sentence.contains("{emoji}"));
+            assert!(locale.reactions_given_sentence.contains("{count}"));
+            assert!(locale.reactions_given_sentence.contains("{emoji}"));
+            // Weekday names label the Markdown calendar rows
+            assert!(locale.weekdays_short.iter().all(|d| d.chars().count() <= 3));
+        }
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(
+            fill("{a} & {b} & {a}", &[("a", "x"), ("b", "y")]),
+            "x & y & x"
+        );
+    }
+}
@@ -0,0 +1,422 @@
+/// Normalized JSON renderer.
+///
+/// Writes a render-ready document for dashboards and scripts, distinct from the
+/// stats file: keys are sorted, absent values are left out, time series are
+/// arrays in chronological order, and shares of messages and reactions are
+/// pre-computed as percentages (rounded to one decimal).
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+use super::common::Provenance;
+use crate::stats::*;
+
+/// Version of the normalized layout, bumped when it changes.
+const FORMAT_VERSION: i32 = 1;
+
+/// Stats keys of weekdays, Monday first.
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Renders the normalized JSON report.
+pub fn render(stats: &Stats) -> Result<String> {
+    let messages_sent = stats.summary.messages_sent;
+    let document = json!({
+        "format_version": FORMAT_VERSION,
+        "generated_at": stats.generated_at,
+        "timezone": stats.timezone,
+        "scope": {
+            "type": stats.scope.kind,
+            "key": stats.scope.key,
+            "label": stats.scope.label,
+        },
+        "account": stats.account,
+        "coverage": stats.coverage,
+        "summary": summary(stats),
+        "rooms": stats.rooms.as_ref().map(|rooms| rooms_section(rooms, messages_sent.get())),
+        "people": stats.people.as_ref().map(people_section),
+        "dms": stats.dms.as_ref().map(dms_section),
+        "conversations": stats.conversations,
+        "message_lengths": stats.message_lengths,
+        "typed_emojis": stats.typed_emojis,
+        "created_rooms": stats.created_rooms,
+        "reactions": stats.reactions.as_ref().map(reactions_section),
+        "activity": stats
+            .activity
+            .as_ref()
+            .map(|activity| activity_section(activity, messages_sent.get())),
+        "fun": stats.fun,
+        "previous": stats.previous,
+        "previous_crawl": stats.previous_crawl,
+        "provenance": provenance(stats),
+    });
+
+    let mut output = serde_json::to_string_pretty(&normalize(document))?;
+    output.push('\n');
+    Ok(output)
+}
+
+/// Footer data of the other formats: tool, window, coverage, accounts, date.
+fn provenance(stats: &Stats) -> Value {
+    let provenance = Provenance::of(stats);
+    json!({
+        "tool": provenance.tool,
+        "window": provenance.window,
+        "coverage": { "from": provenance.coverage_from, "to": provenance.coverage_to },
+        "accounts": provenance.accounts,
+        "generated_at": provenance.generated_at,
+    })
+}
+
+fn summary(stats: &Stats) -> Value {
+    let summary = &stats.summary;
+    let messages_per_active_day = stats
+        .coverage
+        .days_active
+        .filter(|days| days.get() > 0)
+        .map(|days| round1(summary.messages_sent.get() as f64 / days.get() as f64));
+
+    json!({
+        "messages_sent": summary.messages_sent,
+        "active_rooms": summary.active_rooms,
+        "active_days": stats.coverage.days_active,
+        "messages_per_active_day": messages_per_active_day,
+        "room_types": room_types(stats),
+        "peaks": summary.peaks,
+    })
+}
+
+/// Rooms and messages per room type, with each type's share of messages.
+fn room_types(stats: &Stats) -> Vec<Value> {
+    let by_type = stats
+        .rooms
+        .as_ref()
+        .and_then(|rooms| rooms.messages_by_room_type.as_ref());
+    let summary = &stats.summary;
+    [
+        ("dm", summary.dm_rooms, by_type.and_then(|t| t.dm)),
+        (
+            "public",
+            summary.public_rooms,
+            by_type.and_then(|t| t.public),
+        ),
+        (
+            "private",
+            summary.private_rooms,
+            by_type.and_then(|t| t.private),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, rooms, messages)| rooms.is_some() || messages.is_some())
+    .map(|(key, rooms, messages)| {
+        json!({
+            "type": key,
+            "rooms": rooms,
+            "messages": messages,
+            "percentage": messages.and_then(|m| percentage(m.get(), summary.messages_sent.get())),
+        })
+    })
+    .collect()
+}
+
+fn rooms_section(rooms: &Rooms, messages_sent: i32) -> Value {
+    let top: Vec<Value> = rooms
+        .top
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, room)| {
+            json!({
+                "rank": i + 1,
+                "name": room.name,
+                "messages": room.messages,
+                "percentage": room
+                    .percentage
+                    .map(|percentage| round1(percentage.get()))
+                    .or_else(|| percentage(room.messages.get(), messages_sent)),
+                "permalink": room.permalink,
+                "joined_at": room.joined_at,
+                "trend": room.trend,
+            })
+        })
+        .collect();
+    let by_server: Vec<Value> = rooms
+        .by_server
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, entry)| {
+            json!({
+                "rank": i + 1,
+                "server": entry.server,
+                "messages": entry.messages,
+                "percentage": round1(entry.percentage.get()),
+            })
+        })
+        .collect();
+
+    json!({
+        "total": rooms.total,
+        "top": top,
+        "by_server": by_server,
+    })
+}
+
+fn ranked_people<'a>(people: impl IntoIterator<Item = &'a PersonEntry>) -> Vec<Value> {
+    people
+        .into_iter()
+        .enumerate()
+        .map(|(i, person)| {
+            json!({
+                "rank": i + 1,
+                "user_id": person.user_id,
+                "display_name": person.display_name,
+                "messages": person.messages,
+                "rooms": person.rooms,
+            })
+        })
+        .collect()
+}
+
+fn people_section(people: &People) -> Value {
+    json!({
+        "dm_partners": ranked_people(people.dm_partners.iter().flatten()),
+        "group_senders": ranked_people(people.group_senders.iter().flatten()),
+    })
+}
+
+fn dms_section(dms: &Dms) -> Value {
+    json!({
+        "total": dms.total,
+        "top": ranked_people(&dms.top),
+    })
+}
+
+fn reactions_section(reactions: &Reactions) -> Value {
+    let emojis = reactions.top_emojis.as_deref().unwrap_or_default();
+    let total = reactions
+        .total
+        .unwrap_or_else(|| emojis.iter().map(|entry| entry.count).sum());
+    let top_messages: Vec<Value> = reactions
+        .top_messages
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, entry)| {
+            json!({
+                "rank": i + 1,
+                "permalink": entry.permalink,
+                "preview": entry.preview,
+                "reactions": entry.reaction_count,
+            })
+        })
+        .collect();
+    let top_rooms: Vec<Value> = reactions
+        .top_rooms
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, entry)| {
+            json!({
+                "rank": i + 1,
+                "name": entry.name,
+                "reactions": entry.reaction_count,
+                "percentage": percentage(entry.reaction_count, total),
+                "permalink": entry.permalink,
+            })
+        })
+        .collect();
+
+    let given = reactions.given.as_ref().map(|given| {
+        json!({
+            "total": given.total,
+            "top_emojis": ranked_emojis(&given.top_emojis, given.total),
+        })
+    });
+
+    json!({
+        "total": total,
+        "top_emojis": ranked_emojis(emojis, total),
+        "top_messages": top_messages,
+        "top_rooms": top_rooms,
+        "given": given,
+    })
+}
+
+/// Ranked emojis, with their share of `total`.
+fn ranked_emojis(emojis: &[EmojiEntry], total: i32) -> Vec<Value> {
+    emojis
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            json!({
+                "rank": i + 1,
+                "emoji": entry.emoji,
+                "shortcode": entry.shortcode,
+                "pack": entry.pack,
+                "count": entry.count,
+                "percentage": percentage(entry.count, total),
+            })
+        })
+        .collect()
+}
+
+fn activity_section(activity: &Activity, messages_sent: i32) -> Value {
+    let weekday = activity.by_weekday.as_ref().map(|by_weekday| {
+        WEEKDAYS
+            .iter()
+            .map(|key| {
+                let count = by_weekday.get(*key).copied().unwrap_or(0);
+                point(key, count, messages_sent)
+            })
+            .collect::<Vec<_>>()
+    });
+    let weekday_hour = activity.by_weekday_hour.as_ref().map(|by_weekday_hour| {
+        WEEKDAYS
+            .iter()
+            .map(|key| {
+                json!({
+                    "weekday": key,
+                    "hours": hours(by_weekday_hour.get(*key)),
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+    let room_type_hour = activity.by_room_type_hour.as_ref().map(|by_type| {
+        ["dm", "public", "private"]
+            .iter()
+            .filter_map(|key| {
+                let row = by_type.get(*key)?;
+                Some(json!({ "type": key, "hours": hours(Some(row)) }))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    json!({
+        "by_year": activity.by_year.as_ref().map(|map| series(map, messages_sent)),
+        "by_month": activity.by_month.as_ref().map(|map| series(map, messages_sent)),
+        "by_week": activity.by_week.as_ref().map(|map| series(map, messages_sent)),
+        "by_day": activity.by_day.as_ref().map(|map| series(map, messages_sent)),
+        "by_weekday": weekday,
+        "by_hour": activity.by_hour.as_ref().map(|map| {
+            (0..24)
+                .map(|hour| {
+                    let key = format!("{:02}", hour);
+                    let count = map.get(&key).copied().unwrap_or(0);
+                    point(&key, count, messages_sent)
+                })
+                .collect::<Vec<_>>()
+        }),
+        "by_weekday_hour": weekday_hour,
+        "by_room_type_hour": room_type_hour,
+    })
+}
+
+/// Points of a time series, in key order (keys are zero-padded or ISO dates).
+fn series(map: &HashMap<String, i32>, messages_sent: i32) -> Vec<Value> {
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+    keys.into_iter()
+        .map(|key| point(key, map[key], messages_sent))
+        .collect()
+}
+
+fn point(key: &str, messages: i32, messages_sent: i32) -> Value {
+    json!({
+        "key": key,
+        "messages": messages,
+        "percentage": percentage(messages, messages_sent),
+    })
+}
+
+/// Messages of each hour from 00 to 23, with missing hours as zero.
+fn hours(row: Option<&HashMap<String, i32>>) -> Vec<i32> {
+    (0..24)
+        .map(|hour| {
+            row.and_then(|row| row.get(&format!("{:02}", hour)))
+                .copied()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Share of `part` in `total`, in percent with one decimal.
+fn percentage(part: i32, total: i32) -> Option<f64> {
+    (total > 0).then(|| round1(part as f64 / total as f64 * 100.0))
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Sorts object keys and drops null values, recursively.
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, normalize(value)))
+                .collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(entries.into_iter().collect::<Map<_, _>>())
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(normalize).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::render::common::example_stats;
+
+    #[test]
+    fn test_render_normalized() {
+        let output = render(&example_stats()).unwrap();
+        let document: Value = serde_json::from_str(&output).unwrap();
+
+        // Keys are sorted and nulls are gone
+        let keys: Vec<&String> = document.as_object().unwrap().keys().collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
+        assert!(!output.contains("null"));
+
+        assert_eq!(document["format_version"], json!(1));
+        assert_eq!(document["provenance"]["tool"], json!("my 0.1.0"));
+        assert_eq!(
+            document["provenance"]["accounts"],
+            json!(["@alice:example.org"])
+        );
+        assert_eq!(document["summary"]["messages_sent"], json!(4832));
+        assert_eq!(document["summary"]["messages_per_active_day"], json!(22.0));
+
+        let room_types = document["summary"]["room_types"].as_array().unwrap();
+        assert_eq!(room_types[0]["type"], json!("dm"));
+        assert_eq!(room_types[0]["percentage"], json!(33.5));
+
+        // The unnamed room keeps its rank, without a name
+        let second = &document["rooms"]["top"][1];
+        assert_eq!(second["rank"], json!(2));
+        assert!(second.get("name").is_none());
+
+        let by_month = document["activity"]["by_month"].as_array().unwrap();
+        assert_eq!(by_month[0]["key"], json!("01"));
+        assert_eq!(by_month.len(), 12);
+        let by_hour = document["activity"]["by_hour"].as_array().unwrap();
+        assert_eq!(by_hour.len(), 24);
+        let by_weekday = document["activity"]["by_weekday"].as_array().unwrap();
+        assert_eq!(by_weekday[6]["key"], json!("Sun"));
+
+        let senders = document["people"]["group_senders"].as_array().unwrap();
+        assert_eq!(senders[2]["rank"], json!(3));
+        assert!(senders[2].get("display_name").is_none());
+    }
+
+    #[test]
+    fn test_percentage() {
+        assert_eq!(percentage(1, 3), Some(33.3));
+        assert_eq!(percentage(2, 3), Some(66.7));
+        assert_eq!(percentage(5, 0), None);
+    }
+}
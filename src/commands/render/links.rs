@@ -0,0 +1,175 @@
+/// Where report links point.
+///
+/// Stats files keep matrix.to permalinks; reports can point them at a Matrix
+/// client instead (`--link-base`), for every renderer that shows links.
+use anyhow::{Context, Result};
+
+use crate::stats::{Reactions, Stats};
+
+/// Prefix of the permalinks stored in stats files.
+const MATRIX_TO: &str = "https://matrix.to/#/";
+
+/// Base of the links in reports.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LinkBase {
+    /// matrix.to, which lets the reader pick a client
+    #[default]
+    MatrixTo,
+    /// Element Web at this URL (`#/room/…` and `#/user/…` routes)
+    Element(String),
+    /// URL where `{id}` is replaced by the target, as in matrix.to links
+    Template(String),
+}
+
+impl LinkBase {
+    /// Parses `matrix.to`, `element` (app.element.io), the URL of an Element
+    /// Web install, or a URL template containing `{id}`.
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        if input.eq_ignore_ascii_case("element") {
+            return Ok(LinkBase::Element("https://app.element.io".to_string()));
+        }
+        let with_scheme = if input.contains("://") {
+            input.to_string()
+        } else {
+            format!("https://{}", input)
+        };
+        let url = url::Url::parse(&with_scheme.replace("{id}", "id"))
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https") && url.host().is_some())
+            .with_context(|| {
+                format!(
+                    "Invalid link base '{}' (expected matrix.to, element, a client URL \
+                     or a URL with {{id}})",
+                    input
+                )
+            })?;
+
+        if with_scheme.contains("{id}") {
+            Ok(LinkBase::Template(with_scheme))
+        } else if url.host_str() == Some("matrix.to") {
+            Ok(LinkBase::MatrixTo)
+        } else {
+            let base = with_scheme.trim_end_matches('/');
+            let base = base.strip_suffix("/#").unwrap_or(base);
+            Ok(LinkBase::Element(base.trim_end_matches('/').to_string()))
+        }
+    }
+
+    /// Link to `target`: a user ID, or a room ID or alias optionally followed by
+    /// `/` and an event ID.
+    pub fn link(&self, target: &str) -> String {
+        match self {
+            LinkBase::MatrixTo => format!("{}{}", MATRIX_TO, target),
+            LinkBase::Element(base) if target.starts_with('@') => {
+                format!("{}/#/user/{}", base, target)
+            }
+            LinkBase::Element(base) => format!("{}/#/room/{}", base, target),
+            LinkBase::Template(template) => template.replace("{id}", target),
+        }
+    }
+
+    /// Points a matrix.to permalink at this base; other links are kept.
+    pub fn rewrite(&self, permalink: &str) -> String {
+        match permalink.strip_prefix(MATRIX_TO) {
+            Some(target) => self.link(target),
+            None => permalink.to_string(),
+        }
+    }
+
+    /// Rewrites every permalink of `stats`.
+    pub fn apply(&self, stats: &mut Stats) {
+        if *self == LinkBase::MatrixTo {
+            return;
+        }
+        if let Some(rooms) = stats.rooms.as_mut() {
+            for entry in rooms.top.iter_mut().flatten() {
+                entry.permalink = self.rewrite(&entry.permalink);
+            }
+            for entry in rooms.dm_balance.iter_mut().flatten() {
+                entry.permalink = self.rewrite(&entry.permalink);
+            }
+        }
+        if let Some(reactions) = stats.reactions.as_mut() {
+            self.apply_reactions(reactions);
+        }
+        if let Some(longest) = stats
+            .message_lengths
+            .as_mut()
+            .and_then(|lengths| lengths.longest.as_mut())
+        {
+            longest.permalink = self.rewrite(&longest.permalink);
+        }
+        for breakdown in stats.per_room.iter_mut().flatten() {
+            breakdown.permalink = self.rewrite(&breakdown.permalink);
+            if let Some(reactions) = breakdown.reactions.as_mut() {
+                self.apply_reactions(reactions);
+            }
+        }
+    }
+
+    fn apply_reactions(&self, reactions: &mut Reactions) {
+        for entry in reactions.top_messages.iter_mut().flatten() {
+            entry.permalink = self.rewrite(&entry.permalink);
+        }
+        for entry in reactions.top_rooms.iter_mut().flatten() {
+            entry.permalink = self.rewrite(&entry.permalink);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_link_base() {
+        assert_eq!(LinkBase::parse("matrix.to").unwrap(), LinkBase::MatrixTo);
+        assert_eq!(
+            LinkBase::parse("https://matrix.to/#/").unwrap(),
+            LinkBase::MatrixTo
+        );
+        assert_eq!(
+            LinkBase::parse("element").unwrap(),
+            LinkBase::Element("https://app.element.io".to_string())
+        );
+        assert_eq!(
+            LinkBase::parse("chat.example.org/#/").unwrap(),
+            LinkBase::Element("https://chat.example.org".to_string())
+        );
+        assert_eq!(
+            LinkBase::parse("https://client.example.org/open?target={id}").unwrap(),
+            LinkBase::Template("https://client.example.org/open?target={id}".to_string())
+        );
+        assert!(LinkBase::parse("ftp://example.org").is_err());
+        assert!(LinkBase::parse("").is_err());
+    }
+
+    #[test]
+    fn test_link_targets() {
+        let element = LinkBase::parse("https://app.element.io/").unwrap();
+        assert_eq!(
+            element.link("@alice:example.org"),
+            "https://app.element.io/#/user/@alice:example.org"
+        );
+        assert_eq!(
+            element.rewrite("https://matrix.to/#/!abc:example.org/$event1"),
+            "https://app.element.io/#/room/!abc:example.org/$event1"
+        );
+        // Links that are not matrix.to permalinks are kept
+        assert_eq!(
+            element.rewrite("https://example.org/page"),
+            "https://example.org/page"
+        );
+
+        let template = LinkBase::parse("https://client.example.org/open?target={id}").unwrap();
+        assert_eq!(
+            template.link("!abc:example.org"),
+            "https://client.example.org/open?target=!abc:example.org"
+        );
+        assert_eq!(
+            LinkBase::MatrixTo.link("@alice:example.org"),
+            "https://matrix.to/#/@alice:example.org"
+        );
+    }
+}
@@ -1,630 +1,1300 @@
+use super::common::*;
+use super::i18n::{fill, Lang, Locale};
+use super::links::LinkBase;
 use crate::stats::*;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::path::Path;
+use tera::Tera;
+
+/// Default templates, one per report section. A `--template-dir` may override
+/// any of them by file name.
+const TEMPLATES: [(&str, &str); 14] = [
+    ("report.md", include_str!("templates/md/report.md")),
+    ("header.md", include_str!("templates/md/header.md")),
+    ("summary.md", include_str!("templates/md/summary.md")),
+    ("rooms.md", include_str!("templates/md/rooms.md")),
+    ("people.md", include_str!("templates/md/people.md")),
+    ("dms.md", include_str!("templates/md/dms.md")),
+    (
+        "conversations.md",
+        include_str!("templates/md/conversations.md"),
+    ),
+    (
+        "message_lengths.md",
+        include_str!("templates/md/message_lengths.md"),
+    ),
+    (
+        "typed_emojis.md",
+        include_str!("templates/md/typed_emojis.md"),
+    ),
+    (
+        "created_rooms.md",
+        include_str!("templates/md/created_rooms.md"),
+    ),
+    ("reactions.md", include_str!("templates/md/reactions.md")),
+    ("activity.md", include_str!("templates/md/activity.md")),
+    ("fun.md", include_str!("templates/md/fun.md")),
+    ("footer.md", include_str!("templates/md/footer.md")),
+];
 
 /// Render stats to Markdown following md_report_layout.md
-pub fn render(stats: &Stats) -> Result<String> {
-    let mut output = String::new();
-
-    // 1. Title, metadata, and account details
-    render_header(&mut output, stats);
-
-    // 2. Summary (including active days from coverage)
-    render_summary(
-        &mut output,
-        &stats.summary,
-        stats.coverage.days_active,
-        &stats.scope,
-    );
-
-    // 3. Rooms
-    if let Some(ref rooms) = stats.rooms {
-        render_rooms(
-            &mut output,
-            rooms,
-            stats.summary.messages_sent,
-            &stats.scope,
-        );
-    }
+pub fn render(stats: &Stats, lang: Lang) -> Result<String> {
+    render_with_templates(stats, None, lang, &LinkBase::default())
+}
 
-    // 4. Created rooms
-    if let Some(ref created_rooms) = stats.created_rooms {
-        render_created_rooms(&mut output, created_rooms, &stats.scope);
-    }
+/// Render stats to Markdown in `lang`, with templates from `template_dir`
+/// taking precedence over the built-in ones, and the account linked through
+/// `links` (permalinks of `stats` are used as they are).
+pub fn render_with_templates(
+    stats: &Stats,
+    template_dir: Option<&Path>,
+    lang: Lang,
+    links: &LinkBase,
+) -> Result<String> {
+    let tera = load_templates(template_dir)?;
+    let context = tera::Context::from_serialize(report_context(stats, lang.locale(), links))
+        .context("Failed to build template context")?;
+    tera.render("report.md", &context)
+        .map_err(|e| anyhow::anyhow!(template_error(&e)))
+        .context("Failed to render Markdown template")
+}
 
-    // 5. Reactions
-    if let Some(ref reactions) = stats.reactions {
-        render_reactions(&mut output, reactions);
-    }
+fn load_templates(template_dir: Option<&Path>) -> Result<Tera> {
+    let mut tera = Tera::default();
+    tera.add_raw_templates(TEMPLATES.to_vec())
+        .context("Failed to load built-in Markdown templates")?;
 
-    // 6. Activity
-    if let Some(ref activity) = stats.activity {
-        render_activity(&mut output, activity, &stats.scope, &stats.summary);
+    let Some(dir) = template_dir else {
+        return Ok(tera);
+    };
+
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read template directory: {}", dir.display()))?;
+    let mut custom = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read template: {}", path.display()))?;
+        custom.push((name.to_string(), content));
     }
+    tera.add_raw_templates(custom)
+        .map_err(|e| anyhow::anyhow!(template_error(&e)))
+        .with_context(|| format!("Invalid template in {}", dir.display()))?;
+
+    Ok(tera)
+}
 
-    // 7. Fun
-    if let Some(ref fun) = stats.fun {
-        render_fun(&mut output, fun);
+/// Tera keeps the useful part (line, expected token) in the error sources.
+fn template_error(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
     }
+    message
+}
 
-    Ok(output)
+// Template context. Numbers are pre-formatted strings so templates don't need
+// to know about thousand separators; optional values stay `None` when unknown.
+// Sentences with numbers in them come ready-made from the locale, and `t` holds
+// the locale itself for headings and labels.
+
+#[derive(Serialize)]
+struct ReportContext {
+    t: &'static Locale,
+    /// Title after the emoji, without the display name (e.g. "Your Matrix Year 2025")
+    title: String,
+    scope_label: String,
+    /// Empty for life, where every section already covers everything
+    scope_phrase: String,
+    account: AccountContext,
+    /// Timezone of the hours and days, absent from older stats
+    timezone: Option<String>,
+    summary: SummaryContext,
+    rooms: Option<RoomsContext>,
+    people: Option<PeopleContext>,
+    dms: Option<DmsContext>,
+    conversations: Option<ConversationsContext>,
+    message_lengths: Option<MessageLengthsContext>,
+    typed_emojis: Option<TypedEmojisContext>,
+    created_rooms: Option<CreatedRoomsContext>,
+    reactions: Option<ReactionsContext>,
+    activity: Option<ActivityContext>,
+    fun: Option<FunContext>,
+    /// Provenance line (tool, window, coverage, account, date)
+    footer: String,
+    /// What re-crawled stats discovered, with `--changelog`
+    changelog: Option<String>,
 }
 
-fn render_header(output: &mut String, stats: &Stats) {
-    let account = &stats.account;
-    let scope_label = scope_label(&stats.scope);
+#[derive(Serialize)]
+struct AccountContext {
+    user_id: String,
+    permalink: String,
+    display_name: Option<String>,
+    /// MXC URI, shown when there is no avatar image
+    avatar_url: Option<String>,
+    /// Avatar image, relative to the report (copied next to it)
+    avatar_file: Option<String>,
+    rooms_total: i32,
+}
 
-    // Title with display name if available
-    if let Some(ref display_name) = account.display_name {
-        output.push_str(&format!(
-            "# 🎉 Your Matrix {} — {}\n",
-            scope_label, display_name
-        ));
-    } else {
-        output.push_str(&format!("# 🎉 Your Matrix {}\n", scope_label));
-    }
-
-    // Account details
-    output.push_str("### 🧑 Account\n");
-    let user_permalink = format!("https://matrix.to/#/{}", account.user_id);
-    output.push_str(&format!(
-        "- **User ID:** [{}]({})\n",
-        account.user_id, user_permalink
-    ));
-    if let Some(ref name) = account.display_name {
-        output.push_str(&format!("- **Display name:** {}\n", name));
-    }
-    if let Some(ref avatar) = account.avatar_url {
-        // Convert mxc:// URL to HTTPS media endpoint
-        let avatar_https = if avatar.starts_with("mxc://") {
-            let mxc_parts: Vec<&str> = avatar.strip_prefix("mxc://").unwrap().split('/').collect();
-            if mxc_parts.len() >= 2 {
-                format!(
-                    "https://matrix.org/_matrix/media/r0/download/{}/{}",
-                    mxc_parts[0], mxc_parts[1]
-                )
-            } else {
-                avatar.clone()
-            }
-        } else {
-            avatar.clone()
-        };
-        output.push_str(&format!(
-            "- **Avatar:** [{}]({})\n",
-            avatar_https, avatar_https
-        ));
-    }
-    output.push_str(&format!(
-        "- **Total joined rooms:** {}\n",
-        account.rooms_total
-    ));
-    output.push('\n');
+#[derive(Serialize)]
+struct SummaryContext {
+    messages_sent: String,
+    active_days: Option<String>,
+    /// Changes against the previous window (`--compare-previous`)
+    messages_sent_delta: Option<String>,
+    active_days_delta: Option<String>,
+    dm_rooms: Option<String>,
+    public_rooms: Option<String>,
+    private_rooms: Option<String>,
+    /// "All sections below refer to …", empty for life
+    scope_note: String,
 }
 
-// Coverage section intentionally removed from rendering; active days are shown in Summary.
+#[derive(Serialize)]
+struct RoomsContext {
+    messages_sent: String,
+    total: i32,
+    sentence: String,
+    since_joined_note: Option<String>,
+    top: Vec<TopRoomRow>,
+    trend_note: Option<String>,
+    by_server: Vec<ServerRow>,
+    dm_balance: Vec<DmBalanceRow>,
+    /// Share of the messages sent in the busiest DM
+    dm_balance_sentence: Option<String>,
+}
 
-fn render_summary(output: &mut String, summary: &Summary, active_days: Option<i32>, scope: &Scope) {
-    output.push_str("### 📊 Summary\n");
-    output.push_str(&format!(
-        "- 💬 **Messages sent:** {}\n",
-        format_number(summary.messages_sent)
-    ));
-    if let Some(days) = active_days {
-        output.push_str(&format!("- 🔥 **Active days:** {}\n", days));
-    }
+#[derive(Serialize)]
+struct PeopleContext {
+    /// Who the user talked with the most in DMs
+    sentence: Option<String>,
+    dm_partners: Vec<PersonRow>,
+    group_senders: Vec<PersonRow>,
+}
 
-    if let Some(dm_rooms) = summary.dm_rooms {
-        output.push_str(&format!("- 👥 **DM rooms:** {}\n", dm_rooms));
-    }
+#[derive(Serialize)]
+struct DmsContext {
+    /// Who the user wrote to the most
+    sentence: String,
+    total: String,
+    top: Vec<PersonRow>,
+}
 
-    if let Some(public_rooms) = summary.public_rooms {
-        output.push_str(&format!("- 🌐 **Public rooms:** {}\n", public_rooms));
-    }
+#[derive(Serialize)]
+struct ConversationsContext {
+    sentence: String,
+    top_level: String,
+    in_threads: String,
+    threads: String,
+    replies: String,
+}
 
-    if let Some(private_rooms) = summary.private_rooms {
-        output.push_str(&format!("- 🔒 **Private rooms:** {}\n", private_rooms));
-    }
+#[derive(Serialize)]
+struct MessageLengthsContext {
+    sentence: String,
+    short: String,
+    medium: String,
+    long: String,
+    longest: Option<LongestMessageRow>,
+}
 
-    // Explicit note that the rest of the report refers to the given scope (skip for life)
-    if !matches!(scope.kind, ScopeKind::Life) {
-        output.push_str(&format!(
-            "\n*All sections below refer to {}.*\n\n",
-            scope_phrase(scope)
-        ));
-    } else {
-        output.push('\n');
-    }
+#[derive(Serialize)]
+struct TypedEmojisContext {
+    sentence: String,
+    top: Vec<TopEmojiRow>,
 }
 
-fn render_peak_activity(output: &mut String, summary: &Summary) {
-    let mut lines: Vec<String> = Vec::new();
+#[derive(Serialize)]
+struct LongestMessageRow {
+    /// Formatted length (e.g. "1,024 characters")
+    chars: String,
+    permalink: String,
+}
 
-    if let Some(peaks) = summary.peaks.as_ref() {
-        if let Some(ref year) = peaks.year {
-            lines.push(format!(
-                "- 🗓️ **Peak year:** {} ({} messages)",
-                year.year,
-                format_number(year.messages)
-            ));
-        }
+#[derive(Serialize)]
+struct PersonRow {
+    rank: usize,
+    name: String,
+    /// None for DM partners named by their initials
+    permalink: Option<String>,
+    messages: String,
+    rooms: Option<i32>,
+}
 
-        if let Some(ref month) = peaks.month {
-            lines.push(format!(
-                "- 📆 **Peak month:** {} ({} messages)",
-                month.month,
-                format_number(month.messages)
-            ));
-        }
+#[derive(Serialize)]
+struct ServerRow {
+    rank: usize,
+    server: String,
+    messages: String,
+    percentage: String,
+}
 
-        if let Some(ref week) = peaks.week {
-            lines.push(format!(
-                "- 📅 **Peak week:** {} ({} messages)",
-                week.week,
-                format_number(week.messages)
-            ));
-        }
+#[derive(Serialize)]
+struct DmBalanceRow {
+    rank: usize,
+    name: String,
+    permalink: String,
+    sent: String,
+    received: String,
+    share: String,
+}
 
-        if let Some(ref day) = peaks.day {
-            lines.push(format!(
-                "- 📍 **Peak day:** {} ({} messages)",
-                day.day,
-                format_number(day.messages)
-            ));
-        }
+#[derive(Serialize)]
+struct TopRoomRow {
+    rank: usize,
+    name: String,
+    permalink: String,
+    joined_phrase: Option<String>,
+    messages: String,
+    /// ↑ or ↓ when the room grew or faded over the window
+    trend: Option<&'static str>,
+    percentage: String,
+}
 
-        if let Some(ref hour) = peaks.hour {
-            let when = if let Some(ref date) = hour.date {
-                format!("{}:00 on {}", hour.hour, date)
-            } else {
-                format!("{}:00", hour.hour)
-            };
-
-            lines.push(format!(
-                "- 🕐 **Peak hour:** {} ({} messages)",
-                when,
-                format_number(hour.messages)
-            ));
-        }
-    }
+#[derive(Serialize)]
+struct CreatedRoomsContext {
+    total: String,
+    context: String,
+    sentence: String,
+    dm_rooms: Option<String>,
+    public_rooms: Option<String>,
+    private_rooms: Option<String>,
+}
 
-    if lines.is_empty() {
-        return;
-    }
+#[derive(Serialize)]
+struct ReactionsContext {
+    total: Option<String>,
+    sentence: Option<String>,
+    top_emojis: Vec<TopEmojiRow>,
+    given: Option<GivenReactionsContext>,
+    top_messages: Vec<TopMessageRow>,
+    top_rooms: Vec<TopReactedRoomRow>,
+    /// Jan to Dec for year/life scopes, the months of a quarter
+    by_month: Vec<String>,
+    /// Short names of the months of `by_month`
+    month_labels: Vec<&'static str>,
+    month_sparkline: Option<Sparkline>,
+}
 
-    output.push_str("#### 🚀 Peaks\n");
-    for line in lines {
-        output.push_str(&line);
-        output.push('\n');
-    }
-    output.push('\n');
+#[derive(Serialize)]
+struct GivenReactionsContext {
+    sentence: String,
+    top_emojis: Vec<TopEmojiRow>,
 }
 
-fn render_activity(output: &mut String, activity: &Activity, scope: &Scope, summary: &Summary) {
-    output.push_str("### 📈 Activity\n");
+#[derive(Serialize)]
+struct TopEmojiRow {
+    rank: usize,
+    emoji: String,
+    count: String,
+    /// Block characters scaled to the most used emoji
+    bar: String,
+}
 
-    // Peaks come first inside Activity
-    render_peak_activity(output, summary);
+#[derive(Serialize)]
+struct TopMessageRow {
+    rank: usize,
+    permalink: String,
+    /// Excerpt of the message, escaped for a link text
+    preview: Option<String>,
+    count: String,
+}
 
-    // By year (life scope)
-    if let Some(ref by_year) = activity.by_year {
-        output.push_str("#### 📆 By year\n");
-        output.push_str("| Year | Messages |\n");
-        output.push_str("| ---- | -------- |\n");
+#[derive(Serialize)]
+struct TopReactedRoomRow {
+    rank: usize,
+    name: String,
+    permalink: String,
+    count: String,
+}
 
-        let mut years: Vec<_> = by_year.keys().cloned().collect();
-        years.sort();
-        for year in years {
-            let count = by_year.get(&year).copied().unwrap_or(0);
-            output.push_str(&format!("| {} | {} |\n", year, format_number(count)));
-        }
-        output.push('\n');
-    }
-
-    // By month - only when meaningful for the scope (year/life)
-    if matches!(scope.kind, ScopeKind::Year | ScopeKind::Life) {
-        if let Some(ref by_month) = activity.by_month {
-            output.push_str("#### 📆 By month\n");
-
-            // January to June
-            output.push_str("| Jan | Feb | Mar | Apr | May | Jun |\n");
-            output.push_str("| --- | --- | --- | --- | --- | --- |\n");
-            output.push('|');
-            for month in 1..=6 {
-                let month_key = format!("{:02}", month);
-                let count = by_month.get(&month_key).copied().unwrap_or(0);
-                output.push_str(&format!(" {} |", format_number(count)));
-            }
-            output.push('\n');
-
-            // July to December
-            output.push_str("\n| Jul | Aug | Sep | Oct | Nov | Dec |\n");
-            output.push_str("| --- | --- | --- | --- | --- | --- |\n");
-            output.push('|');
-            for month in 7..=12 {
-                let month_key = format!("{:02}", month);
-                let count = by_month.get(&month_key).copied().unwrap_or(0);
-                output.push_str(&format!(" {} |", format_number(count)));
-            }
-            output.push_str("\n\n");
-        }
+#[derive(Serialize)]
+struct ActivityContext {
+    peaks: Vec<PeakLine>,
+    /// When each room type is busiest
+    personas: Option<String>,
+    heatmap: Option<HeatmapContext>,
+    by_year: Vec<CountRow>,
+    /// Jan to Dec for year/life scopes, the months of a quarter
+    by_month: Vec<String>,
+    /// Short names of the months of `by_month`
+    month_labels: Vec<&'static str>,
+    month_sparkline: Option<Sparkline>,
+    /// Year scope only
+    by_week: Vec<CountRow>,
+    /// Days 01 to 31, month scope only
+    by_day: Vec<String>,
+    /// One row per month of days 01 to 31, year scope only
+    by_day_of_year: Vec<MonthDaysRow>,
+    /// Mon to Sun
+    by_weekday: Vec<String>,
+    /// Hours 00 to 23
+    by_hour: Vec<String>,
+    hour_sparkline: Option<Sparkline>,
+}
+
+/// Activity at a glance, above the tables: readable where wide tables are not.
+#[derive(Serialize)]
+struct Sparkline {
+    bars: String,
+    axis: String,
+}
+
+#[derive(Serialize)]
+struct MonthDaysRow {
+    label: &'static str,
+    /// Empty past the end of the month
+    counts: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct PeakLine {
+    emoji: &'static str,
+    label: &'static str,
+    value: String,
+}
+
+#[derive(Serialize)]
+struct HeatmapContext {
+    header: String,
+    rows: Vec<HeatmapRow>,
+    legend: String,
+}
+
+#[derive(Serialize)]
+struct HeatmapRow {
+    label: String,
+    cells: String,
+}
+
+#[derive(Serialize)]
+struct CountRow {
+    label: String,
+    count: String,
+}
+
+#[derive(Serialize)]
+struct FunContext {
+    headline: Option<String>,
+    entries: Vec<FunLine>,
+}
+
+#[derive(Serialize)]
+struct FunLine {
+    emoji: String,
+    label: Option<String>,
+    value: String,
+}
+
+fn report_context(stats: &Stats, t: &'static Locale, links: &LinkBase) -> ReportContext {
+    let scope_label = scope_label(&stats.scope, t);
+    let deltas = previous_deltas(stats, t);
+    let scope_phrase = if matches!(stats.scope.kind, ScopeKind::Life) {
+        String::new()
+    } else {
+        scope_phrase(&stats.scope, t)
+    };
+
+    ReportContext {
+        t,
+        title: fill(t.title, &[("scope", &scope_label)]),
+        account: account_context(&stats.account, links),
+        timezone: stats.timezone.clone(),
+        summary: summary_context(
+            &stats.summary,
+            stats.coverage.days_active.map(Count::get),
+            &scope_phrase,
+            &deltas,
+            t,
+        ),
+        scope_label,
+        scope_phrase,
+        rooms: stats.rooms.as_ref().map(|rooms| {
+            rooms_context(
+                rooms,
+                stats.summary.messages_sent.get(),
+                &stats.coverage,
+                &stats.scope,
+                t,
+            )
+        }),
+        people: stats
+            .people
+            .as_ref()
+            .map(|people| people_context(people, links, t)),
+        dms: stats.dms.as_ref().map(|dms| dms_context(dms, links, t)),
+        conversations: stats
+            .conversations
+            .as_ref()
+            .map(|conversations| conversations_context(conversations, t)),
+        message_lengths: stats
+            .message_lengths
+            .as_ref()
+            .map(|lengths| message_lengths_context(lengths, t)),
+        typed_emojis: stats
+            .typed_emojis
+            .as_ref()
+            .map(|typed| typed_emojis_context(typed, t)),
+        created_rooms: stats
+            .created_rooms
+            .as_ref()
+            .map(|created| created_rooms_context_for(created, &stats.scope, t)),
+        reactions: stats
+            .reactions
+            .as_ref()
+            .map(|reactions| reactions_context(reactions, &stats.scope, &deltas, t)),
+        activity: stats
+            .activity
+            .as_ref()
+            .map(|activity| activity_context(activity, &stats.scope, &stats.summary, t)),
+        fun: stats.fun.as_ref().and_then(|fun| fun_context(fun, t)),
+        footer: escape_inline(&Provenance::of(stats).line(t)),
+        changelog: changelog_note(stats, t).map(|note| escape_inline(&note)),
     }
+}
 
-    // By week (year scope)
-    if matches!(scope.kind, ScopeKind::Year) {
-        if let Some(ref by_week) = activity.by_week {
-            output.push_str("#### 📅 By week\n");
-            output.push_str("| Week | Messages |\n");
-            output.push_str("| ---- | -------- |\n");
+fn account_context(account: &Account, links: &LinkBase) -> AccountContext {
+    AccountContext {
+        user_id: account.user_id.clone(),
+        permalink: links.link(&account.user_id),
+        display_name: account.display_name.clone(),
+        avatar_url: account.avatar_url.clone(),
+        avatar_file: account.avatar_file.clone(),
+        rooms_total: account.rooms_total.get(),
+    }
+}
 
-            let mut weeks: Vec<_> = by_week.keys().cloned().collect();
-            weeks.sort();
-            for week in weeks {
-                let count = by_week.get(&week).copied().unwrap_or(0);
-                output.push_str(&format!("| {} | {} |\n", week, format_number(count)));
-            }
-            output.push('\n');
-        }
+fn summary_context(
+    summary: &Summary,
+    active_days: Option<i32>,
+    scope_phrase: &str,
+    deltas: &PreviousDeltas,
+    t: &Locale,
+) -> SummaryContext {
+    SummaryContext {
+        messages_sent: t.number(summary.messages_sent.get()),
+        active_days: active_days.map(|days| days.to_string()),
+        messages_sent_delta: deltas.messages_sent.clone(),
+        active_days_delta: deltas.active_days.clone(),
+        dm_rooms: summary.dm_rooms.map(|n| n.to_string()),
+        public_rooms: summary.public_rooms.map(|n| n.to_string()),
+        private_rooms: summary.private_rooms.map(|n| n.to_string()),
+        scope_note: if scope_phrase.is_empty() {
+            String::new()
+        } else {
+            fill(t.sections_refer_to, &[("scope", scope_phrase)])
+        },
     }
+}
 
-    // By day (month scope)
-    if matches!(scope.kind, ScopeKind::Month) {
-        if let Some(ref by_day) = activity.by_day {
-            output.push_str("#### 📅 By day\n");
-            output.push_str(
-                "| 01 | 02 | 03 | 04 | 05 | 06 | 07 | 08 | 09 | 10 | 11 | 12 | 13 | 14 | 15 |",
-            );
-            output.push('\n');
-            output.push_str(
-                "| -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- |",
-            );
-            output.push('\n');
-            output.push('|');
-            for day in 1..=15 {
-                let key = format!("{:02}", day);
-                let count = by_day.get(&key).copied().unwrap_or(0);
-                output.push_str(&format!(" {} |", format_number(count)));
-            }
-            output.push('\n');
+fn rooms_context(
+    rooms: &Rooms,
+    messages_sent: i32,
+    coverage: &Coverage,
+    scope: &Scope,
+    t: &Locale,
+) -> RoomsContext {
+    let top = rooms
+        .top
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, room)| TopRoomRow {
+            rank: i + 1,
+            name: room
+                .name
+                .clone()
+                .unwrap_or_else(|| t.unnamed_room.to_string()),
+            permalink: room.permalink.clone(),
+            joined_phrase: room
+                .joined_at
+                .as_deref()
+                .and_then(|d| joined_phrase(d, scope, t)),
+            messages: t.number(room.messages.get()),
+            trend: room_trend(room, t).map(|(arrow, _)| arrow),
+            percentage: room
+                .percentage
+                .map(|pct| t.decimal(pct.get(), 1))
+                .unwrap_or_else(|| "-".to_string()),
+        })
+        .collect();
+
+    let by_server = rooms
+        .by_server
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, entry)| ServerRow {
+            rank: i + 1,
+            server: entry.server.clone(),
+            messages: t.number(entry.messages.get()),
+            percentage: t.decimal(entry.percentage.get(), 1),
+        })
+        .collect();
+
+    let dm_balance: Vec<DmBalanceRow> = rooms
+        .dm_balance
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, dm)| DmBalanceRow {
+            rank: i + 1,
+            name: dm
+                .name
+                .clone()
+                .unwrap_or_else(|| t.unnamed_room.to_string()),
+            permalink: dm.permalink.clone(),
+            sent: t.number(dm.sent),
+            received: t.number(dm.received),
+            share: dm_sent_share(dm, t),
+        })
+        .collect();
+
+    RoomsContext {
+        messages_sent: t.number(messages_sent),
+        total: rooms.total.get(),
+        sentence: fill(
+            t.rooms_sentence,
+            &[
+                ("messages", &t.number(messages_sent)),
+                ("rooms", &format!("**{}**", rooms.total)),
+            ],
+        ),
+        // Membership-aware coverage: some rooms only show history from the user's join
+        since_joined_note: coverage
+            .since_joined_rooms
+            .map(|count| since_joined_note(count.get(), t)),
+        top,
+        trend_note: trend_note(rooms.top.as_deref().unwrap_or_default(), t),
+        by_server,
+        dm_balance_sentence: dm_balance.first().map(|dm| {
+            fill(
+                t.dm_balance_sentence,
+                &[("share", &format!("**{}**", dm.share)), ("name", &dm.name)],
+            )
+        }),
+        dm_balance,
+    }
+}
 
-            output.push('\n');
-            output.push_str(
-                "| 16 | 17 | 18 | 19 | 20 | 21 | 22 | 23 | 24 | 25 | 26 | 27 | 28 | 29 | 30 | 31 |",
-            );
-            output.push('\n');
-            output.push_str(
-                "| -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- |",
-            );
-            output.push('\n');
-            output.push('|');
-            for day in 16..=31 {
-                let key = format!("{:02}", day);
-                let count = by_day.get(&key).copied().unwrap_or(0);
-                output.push_str(&format!(" {} |", format_number(count)));
-            }
-            output.push_str("\n\n");
-        }
+fn created_rooms_context_for(
+    created_rooms: &CreatedRooms,
+    scope: &Scope,
+    t: &Locale,
+) -> CreatedRoomsContext {
+    let total = t.number(created_rooms.total.get());
+    let context = created_rooms_context(scope, t);
+    CreatedRoomsContext {
+        sentence: fill(
+            t.created_sentence,
+            &[("count", &format!("**{}**", total)), ("when", &context)],
+        ),
+        total,
+        context,
+        dm_rooms: created_rooms.dm_rooms.map(|n| t.number(n.get())),
+        public_rooms: created_rooms.public_rooms.map(|n| t.number(n.get())),
+        private_rooms: created_rooms.private_rooms.map(|n| t.number(n.get())),
     }
+}
 
-    // By weekday - horizontal display
-    if let Some(ref by_weekday) = activity.by_weekday {
-        output.push_str("#### 📅 By weekday\n");
-        output.push_str("| Mon | Tue | Wed | Thu | Fri | Sat | Sun |\n");
-        output.push_str("| --- | --- | --- | --- | --- | --- | --- |\n");
+/// Width of the longest top emoji bar, in characters.
+const EMOJI_BAR_WIDTH: usize = 16;
+
+fn person_rows<'a>(
+    ranked: impl IntoIterator<Item = &'a PersonEntry>,
+    links: &LinkBase,
+    t: &Locale,
+) -> Vec<PersonRow> {
+    ranked
+        .into_iter()
+        .enumerate()
+        .map(|(i, person)| PersonRow {
+            rank: i + 1,
+            name: person_name(person).to_string(),
+            permalink: person.user_id.as_deref().map(|user_id| links.link(user_id)),
+            messages: t.number(person.messages),
+            rooms: person.rooms,
+        })
+        .collect()
+}
 
-        output.push('|');
-        let weekdays = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
-        for day in weekdays {
-            let count = by_weekday.get(day).copied().unwrap_or(0);
-            output.push_str(&format!(" {} |", format_number(count)));
-        }
-        output.push_str("\n\n");
+fn people_context(people: &People, links: &LinkBase, t: &Locale) -> PeopleContext {
+    let rows = |ranked: &Option<Vec<PersonEntry>>| person_rows(ranked.iter().flatten(), links, t);
+    PeopleContext {
+        sentence: people.dm_partners.iter().flatten().next().map(|person| {
+            fill(
+                t.people_sentence,
+                &[
+                    ("name", &format!("**{}**", person_name(person))),
+                    ("messages", &t.message_count(person.messages)),
+                ],
+            )
+        }),
+        dm_partners: rows(&people.dm_partners),
+        group_senders: rows(&people.group_senders),
     }
+}
 
-    // By hour - horizontal display in 2 tables (00-11 and 12-23)
-    if let Some(ref by_hour) = activity.by_hour {
-        output.push_str("#### 🕐 By hour (local time)\n");
+fn dms_context(dms: &Dms, links: &LinkBase, t: &Locale) -> DmsContext {
+    let sentence = dms.top.first().map_or_else(String::new, |person| {
+        fill(
+            t.dms_sentence,
+            &[
+                ("name", &format!("**{}**", person_name(person))),
+                ("messages", &t.message_count(person.messages)),
+            ],
+        )
+    });
+    DmsContext {
+        sentence,
+        total: t.number(dms.total),
+        top: person_rows(&dms.top, links, t),
+    }
+}
 
-        // Hours 00-11
-        output.push_str("| 00 | 01 | 02 | 03 | 04 | 05 | 06 | 07 | 08 | 09 | 10 | 11 |\n");
-        output.push_str("| -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- |\n");
-        output.push('|');
-        for hour in 0..12 {
-            let hour_key = format!("{:02}", hour);
-            let count = by_hour.get(&hour_key).copied().unwrap_or(0);
-            output.push_str(&format!(" {} |", format_number(count)));
-        }
-        output.push('\n');
-
-        // Hours 12-23
-        output.push_str("\n| 12 | 13 | 14 | 15 | 16 | 17 | 18 | 19 | 20 | 21 | 22 | 23 |\n");
-        output.push_str("| -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- | -- |\n");
-        output.push('|');
-        for hour in 12..24 {
-            let hour_key = format!("{:02}", hour);
-            let count = by_hour.get(&hour_key).copied().unwrap_or(0);
-            output.push_str(&format!(" {} |", format_number(count)));
-        }
-        output.push_str("\n\n");
-    }
-}
-
-fn render_rooms(output: &mut String, rooms: &Rooms, messages_sent: i32, _scope: &Scope) {
-    output.push_str("### 🏘️ Rooms\n");
-    output.push_str(&format!(
-        "You sent {} messages in **{}** rooms.\n\n",
-        format_number(messages_sent),
-        rooms.total
-    ));
-
-    if let Some(ref top) = rooms.top {
-        if !top.is_empty() {
-            output.push_str("Your most active rooms:\n\n");
-            output.push_str("| Rank | Name | Messages | % of total |\n");
-            output.push_str("| ---- | ---- | -------- | ---------- |\n");
-
-            for (i, room) in top.iter().take(5).enumerate() {
-                let rank = i + 1;
-                let name = room.name.as_deref().unwrap_or("(unnamed room)");
-                let percentage_str = if let Some(pct) = room.percentage {
-                    format!("{:.1}", pct)
-                } else {
-                    String::from("-")
-                };
-
-                // Clickable room name with permalink
-                let name_display = format!("[{}]({})", name, room.permalink);
-
-                output.push_str(&format!(
-                    "| {} | {} | {} | {} |\n",
-                    rank,
-                    name_display,
-                    format_number(room.messages),
-                    percentage_str
-                ));
-            }
-            output.push('\n');
-        }
+fn conversations_context(conversations: &Conversations, t: &Locale) -> ConversationsContext {
+    ConversationsContext {
+        sentence: fill(
+            t.conversations_sentence,
+            &[
+                ("share", &format!("**{}**", thread_share(conversations, t))),
+                ("replies", &t.number(conversations.replies)),
+            ],
+        ),
+        top_level: t.number(conversations.top_level),
+        in_threads: t.number(conversations.in_threads),
+        threads: t.number(conversations.threads),
+        replies: t.number(conversations.replies),
     }
 }
 
-fn render_reactions(output: &mut String, reactions: &Reactions) {
-    output.push_str("### 😊 Reactions\n");
+fn message_lengths_context(lengths: &MessageLengths, t: &Locale) -> MessageLengthsContext {
+    MessageLengthsContext {
+        sentence: fill(
+            t.message_lengths_sentence,
+            &[
+                (
+                    "short",
+                    &format!("**{}**", length_share(lengths, lengths.short, t)),
+                ),
+                ("long", &length_share(lengths, lengths.long, t)),
+            ],
+        ),
+        short: t.number(lengths.short),
+        medium: t.number(lengths.medium),
+        long: t.number(lengths.long),
+        longest: lengths.longest.as_ref().map(|longest| LongestMessageRow {
+            chars: characters(longest.chars, t),
+            permalink: longest.permalink.clone(),
+        }),
+    }
+}
 
-    if let Some(total) = reactions.total {
-        output.push_str(&format!(
-            "You made people smile with **{}** reactions on your messages!\n\n",
-            format_number(total)
-        ));
+fn typed_emojis_context(typed: &TypedEmojis, t: &Locale) -> TypedEmojisContext {
+    TypedEmojisContext {
+        sentence: fill(
+            t.typed_emojis_sentence,
+            &[
+                ("count", &format!("**{}**", t.number(typed.total))),
+                ("emoji", &favorite_emoji(&typed.top, t)),
+            ],
+        ),
+        top: emoji_rows(&typed.top, t),
     }
+}
+
+/// Ranked emoji with their bars, scaled to the most used one.
+fn emoji_rows(entries: &[EmojiEntry], t: &Locale) -> Vec<TopEmojiRow> {
+    let max = entries.iter().map(|entry| entry.count).max().unwrap_or(0);
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| TopEmojiRow {
+            rank: i + 1,
+            emoji: emoji_label(entry, t),
+            count: t.number(entry.count),
+            bar: bar(entry.count, max, EMOJI_BAR_WIDTH),
+        })
+        .collect()
+}
 
-    // Top emojis
-    if let Some(ref top_emojis) = reactions.top_emojis {
-        if !top_emojis.is_empty() {
-            output.push_str("**Top reactions**\n\n");
-            output.push_str("| Rank | Emoji | Count |\n");
-            output.push_str("| ---- | ----- | ----- |\n");
-
-            for (i, emoji_entry) in top_emojis.iter().take(5).enumerate() {
-                let rank = i + 1;
-                output.push_str(&format!(
-                    "| {} | {} | {} |\n",
-                    rank,
-                    emoji_entry.emoji,
-                    format_number(emoji_entry.count)
-                ));
+fn reactions_context(
+    reactions: &Reactions,
+    scope: &Scope,
+    deltas: &PreviousDeltas,
+    t: &Locale,
+) -> ReactionsContext {
+    let total = reactions.total.map(|n| t.number(n));
+    // When reactions came in, for scopes of several months
+    let report_months = report_months(scope);
+    let by_month = reactions
+        .by_month
+        .as_ref()
+        .filter(|_| !report_months.is_empty());
+    let months = month_keys(&report_months);
+    ReactionsContext {
+        sentence: total.as_ref().map(|total| {
+            let sentence = fill(
+                t.reactions_sentence,
+                &[("count", &format!("**{}**", total))],
+            );
+            match &deltas.reactions {
+                Some(delta) => format!("{} ({})", sentence, delta),
+                None => sentence,
             }
-            output.push('\n');
-        }
+        }),
+        total,
+        top_emojis: emoji_rows(reactions.top_emojis.as_deref().unwrap_or_default(), t),
+        given: reactions.given.as_ref().map(|given| GivenReactionsContext {
+            sentence: fill(
+                t.reactions_given_sentence,
+                &[
+                    ("count", &format!("**{}**", t.number(given.total))),
+                    ("emoji", &favorite_emoji(&given.top_emojis, t)),
+                ],
+            ),
+            top_emojis: emoji_rows(&given.top_emojis, t),
+        }),
+        top_messages: reactions
+            .top_messages
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, entry)| TopMessageRow {
+                rank: i + 1,
+                permalink: entry.permalink.clone(),
+                preview: entry.preview.as_deref().map(escape_inline),
+                count: t.number(entry.reaction_count),
+            })
+            .collect(),
+        top_rooms: reactions
+            .top_rooms
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, entry)| TopReactedRoomRow {
+                rank: i + 1,
+                name: entry
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| t.unnamed_room.to_string()),
+                permalink: entry.permalink.clone(),
+                count: t.number(entry.reaction_count),
+            })
+            .collect(),
+        by_month: if by_month.is_some() {
+            counts(by_month, months.iter().map(String::as_str), t)
+        } else {
+            Vec::new()
+        },
+        month_labels: month_labels(&report_months, t),
+        month_sparkline: by_month.map(|by_month| month_sparkline(by_month, &report_months, t)),
     }
+}
 
-    // Top messages
-    if let Some(ref top_messages) = reactions.top_messages {
-        if !top_messages.is_empty() {
-            output.push_str("**Most reacted messages**\n\n");
-            output.push_str("| Rank | Link | Reactions |\n");
-            output.push_str("| ---- | ---- | --------- |\n");
-
-            for (i, msg_entry) in top_messages.iter().take(5).enumerate() {
-                let rank = i + 1;
-                output.push_str(&format!(
-                    "| {} | [view]({}) | {} |\n",
-                    rank,
-                    msg_entry.permalink,
-                    format_number(msg_entry.reaction_count)
-                ));
-            }
-            output.push('\n');
+/// Escapes Markdown syntax in free text shown inline, e.g. inside a table cell
+/// or a link text (private).
+pub(super) fn escape_inline(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '[' | ']' | '|' | '*' | '_' | '`' | '<') {
+            escaped.push('\\');
         }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+fn peak_lines(summary: &Summary, t: &'static Locale) -> Vec<PeakLine> {
+    let mut lines = Vec::new();
+    let Some(peaks) = summary.peaks.as_ref() else {
+        return lines;
+    };
+    let mut push = |emoji, label, when: String, messages: i32| {
+        lines.push(PeakLine {
+            emoji,
+            label,
+            value: format!("{} ({})", when, t.message_count(messages)),
+        })
+    };
+
+    if let Some(ref year) = peaks.year {
+        push("🗓️", t.peak_year, year.year.clone(), year.messages);
+    }
+    if let Some(ref month) = peaks.month {
+        push(
+            "📆",
+            t.peak_month,
+            t.stats_month(&month.month),
+            month.messages,
+        );
+    }
+    if let Some(ref week) = peaks.week {
+        push("📅", t.peak_week, week.week.clone(), week.messages);
+    }
+    if let Some(ref day) = peaks.day {
+        push("📍", t.peak_day, day.day.clone(), day.messages);
+    }
+    if let Some(ref hour) = peaks.hour {
+        let hour_str = hour.hour.to_string();
+        let when = if let Some(ref date) = hour.date {
+            fill(t.hour_on, &[("hour", &hour_str), ("date", date)])
+        } else {
+            fill(t.hour_of_day, &[("hour", &hour_str)])
+        };
+        push("🕐", t.peak_hour, when, hour.messages);
     }
+
+    lines
+}
+
+/// Counts for fixed keys, in order, with missing keys as zero.
+fn counts<'a>(
+    map: Option<&std::collections::HashMap<String, i32>>,
+    keys: impl IntoIterator<Item = &'a str>,
+    t: &Locale,
+) -> Vec<String> {
+    let Some(map) = map else {
+        return Vec::new();
+    };
+    keys.into_iter()
+        .map(|key| t.number(map.get(key).copied().unwrap_or(0)))
+        .collect()
+}
+
+/// Rows sorted by key.
+fn sorted_rows(map: Option<&std::collections::HashMap<String, i32>>, t: &Locale) -> Vec<CountRow> {
+    let Some(map) = map else {
+        return Vec::new();
+    };
+    let mut keys: Vec<_> = map.keys().collect();
+    keys.sort();
+    keys.into_iter()
+        .map(|key| CountRow {
+            label: key.clone(),
+            count: t.number(map[key]),
+        })
+        .collect()
 }
 
-fn render_created_rooms(output: &mut String, created_rooms: &CreatedRooms, scope: &Scope) {
-    output.push_str("### 🏗️ Rooms You Created\n");
+fn activity_context(
+    activity: &Activity,
+    scope: &Scope,
+    summary: &Summary,
+    t: &'static Locale,
+) -> ActivityContext {
+    let two_digits = |range: std::ops::RangeInclusive<u32>| -> Vec<String> {
+        range.map(|n| format!("{:02}", n)).collect()
+    };
+    let report_months = report_months(scope);
+    let months = month_keys(&report_months);
+    let days = two_digits(1..=31);
+    let hours = two_digits(0..=23);
+
+    // By month only when meaningful for the scope (year/life, the months of a
+    // quarter), by week for a year, by day for a month
+    let by_month = if !report_months.is_empty() {
+        counts(
+            activity.by_month.as_ref(),
+            months.iter().map(String::as_str),
+            t,
+        )
+    } else {
+        Vec::new()
+    };
+    let by_week = if matches!(scope.kind, ScopeKind::Year) {
+        sorted_rows(activity.by_week.as_ref(), t)
+    } else {
+        Vec::new()
+    };
+    let month_sparkline = if by_month.is_empty() {
+        None
+    } else {
+        activity
+            .by_month
+            .as_ref()
+            .map(|by_month| month_sparkline(by_month, &report_months, t))
+    };
+    let by_day = if matches!(scope.kind, ScopeKind::Month) {
+        counts(activity.by_day.as_ref(), days.iter().map(String::as_str), t)
+    } else {
+        Vec::new()
+    };
 
-    // Add contextual sentence based on scope
-    let scope_context = match scope.kind {
-        ScopeKind::Year => "this year",
-        ScopeKind::Month => "this month",
-        ScopeKind::Week => "this week",
-        ScopeKind::Day => "today",
-        ScopeKind::Life => "in your lifetime",
+    let by_day_of_year = if matches!(scope.kind, ScopeKind::Year) {
+        day_of_year_rows(activity.by_day.as_ref(), &scope.key, t)
+    } else {
+        Vec::new()
     };
-    output.push_str(&format!(
-        "You created **{}** rooms {}.\n\n",
-        format_number(created_rooms.total),
-        scope_context
-    ));
 
-    if let Some(dm_rooms) = created_rooms.dm_rooms {
-        output.push_str(&format!("- 👥 **DM rooms:** {}\n", format_number(dm_rooms)));
+    ActivityContext {
+        // Peaks come first inside Activity
+        peaks: peak_lines(summary, t),
+        personas: time_of_day_personas(activity, t),
+        heatmap: calendar_heatmap(scope, activity).map(|weeks| heatmap_context(&weeks, t)),
+        by_year: sorted_rows(activity.by_year.as_ref(), t),
+        by_month,
+        month_labels: month_labels(&report_months, t),
+        month_sparkline,
+        by_week,
+        by_day,
+        by_day_of_year,
+        by_weekday: counts(activity.by_weekday.as_ref(), WEEKDAY_KEYS, t),
+        by_hour: counts(
+            activity.by_hour.as_ref(),
+            hours.iter().map(String::as_str),
+            t,
+        ),
+        hour_sparkline: activity.by_hour.as_ref().map(|by_hour| Sparkline {
+            bars: sparkline(&raw_counts(by_hour, &hours)),
+            axis: HOUR_AXIS.to_string(),
+        }),
     }
+}
 
-    if let Some(public_rooms) = created_rooms.public_rooms {
-        output.push_str(&format!(
-            "- 🌐 **Public rooms:** {}\n",
-            format_number(public_rooms)
-        ));
+/// Month by day matrix of a year, from `by_day` keyed by full dates.
+fn day_of_year_rows(
+    by_day: Option<&std::collections::HashMap<String, i32>>,
+    year: &str,
+    t: &Locale,
+) -> Vec<MonthDaysRow> {
+    let (Some(by_day), Ok(year)) = (by_day, year.parse::<i32>()) else {
+        return Vec::new();
+    };
+    (1..=12u32)
+        .zip(t.months_short)
+        .map(|(month, label)| MonthDaysRow {
+            label,
+            counts: (1..=31u32)
+                .map(|day| match NaiveDate::from_ymd_opt(year, month, day) {
+                    Some(date) => {
+                        let key = date.format("%Y-%m-%d").to_string();
+                        t.number(by_day.get(&key).copied().unwrap_or(0))
+                    }
+                    None => String::new(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Sparkline of `by_month` over `months` (1 to 12), with the initials of the
+/// months as axis.
+fn month_sparkline(
+    by_month: &std::collections::HashMap<String, i32>,
+    months: &[u32],
+    t: &Locale,
+) -> Sparkline {
+    Sparkline {
+        bars: sparkline(&raw_counts(by_month, &month_keys(months))),
+        axis: month_labels(months, t)
+            .iter()
+            .filter_map(|name| name.chars().next())
+            .collect(),
     }
+}
 
-    if let Some(private_rooms) = created_rooms.private_rooms {
-        output.push_str(&format!(
-            "- 🔒 **Private rooms:** {}\n",
-            format_number(private_rooms)
-        ));
+/// `by_month` keys of `months` (1 to 12), e.g. `07`.
+fn month_keys(months: &[u32]) -> Vec<String> {
+    months.iter().map(|month| format!("{:02}", month)).collect()
+}
+
+fn month_labels(months: &[u32], t: &Locale) -> Vec<&'static str> {
+    months.iter().map(|&month| t.month_short(month)).collect()
+}
+
+/// Counts of `keys`, zero when missing.
+fn raw_counts(map: &std::collections::HashMap<String, i32>, keys: &[String]) -> Vec<i32> {
+    keys.iter()
+        .map(|key| map.get(key).copied().unwrap_or(0))
+        .collect()
+}
+
+/// Heatmap cells by intensity level, from no messages to the busiest days.
+pub const HEATMAP_EMOJI: [&str; HEATMAP_LEVELS] = ["⬜", "🟨", "🟧", "🟥"];
+
+/// Lays out the calendar heatmap as emoji blocks: one column per week, one row per weekday.
+fn heatmap_context(weeks: &[HeatmapWeek], t: &Locale) -> HeatmapContext {
+    // Month names above the week they start in (cells are two columns wide)
+    let mut header = vec![' '; weeks.len() * 2 + 3];
+    let mut free_from = 0;
+    for (week, name) in heatmap_month_starts(weeks, t) {
+        let column = week * 2;
+        if column < free_from {
+            continue;
+        }
+        for (offset, ch) in name.chars().enumerate() {
+            if let Some(cell) = header.get_mut(column + offset) {
+                *cell = ch;
+            }
+        }
+        free_from = column + name.chars().count() + 1;
     }
+    let header: String = header.into_iter().collect();
+
+    let rows = t
+        .weekdays_short
+        .iter()
+        .enumerate()
+        .map(|(weekday, label)| {
+            let cells: String = weeks
+                .iter()
+                .map(|week| match &week[weekday] {
+                    Some(day) => HEATMAP_EMOJI[day.level],
+                    None => "  ",
+                })
+                .collect();
+            HeatmapRow {
+                // Labels are padded so the rows line up under the month header
+                label: format!("{:<3}", label),
+                cells: cells.trim_end().to_string(),
+            }
+        })
+        .collect();
 
-    output.push('\n');
+    HeatmapContext {
+        header: header.trim_end().to_string(),
+        rows,
+        legend: HEATMAP_EMOJI.join(""),
+    }
 }
 
-fn render_fun(output: &mut String, fun: &Fun) {
+fn fun_context(fun: &Fun, t: &Locale) -> Option<FunContext> {
     if fun.fields.is_empty() {
-        return;
+        return None;
     }
 
-    output.push_str("### 🎪 Fun Facts\n");
+    Some(FunContext {
+        // Headline: estimated typing time
+        headline: typing_headline(fun, t),
+        entries: fun_entries(fun, t)
+            .into_iter()
+            .map(|entry| FunLine {
+                emoji: entry.emoji.to_string(),
+                label: entry.label,
+                value: entry.value,
+            })
+            .collect(),
+    })
+}
 
-    // Render each field with human-friendly formatting using insertion order from IndexMap
-    for (key, value) in &fun.fields {
-        let formatted_key = key.replace('_', " ");
-        let formatted_key = uppercase_first_char(&formatted_key);
-        let display_key = if key == "sent_encrypted_messages_ratio" {
-            "Encrypted messages".to_string()
-        } else {
-            formatted_key.clone()
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::render::common::example_stats;
+
+    #[test]
+    fn test_custom_template_overrides_section() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("fun.md"),
+            "## Fun\n{% for entry in fun.entries %}* {{ entry.value }}\n{% endfor %}",
+        )
+        .unwrap();
+
+        let stats = example_stats();
+        let markdown =
+            render_with_templates(&stats, Some(dir.path()), Lang::En, &LinkBase::default())
+                .unwrap();
+        let default = render(&stats, Lang::En).unwrap();
+
+        assert!(markdown.contains("## Fun\n* "));
+        assert!(!markdown.contains("### 🎪 Fun Facts"));
+        // Other sections keep the built-in templates
+        let before_fun = default.split("### 🎪 Fun Facts").next().unwrap();
+        assert!(markdown.starts_with(before_fun));
+    }
 
-        let formatted_value = match value {
-            serde_json::Value::Number(n) => {
-                if let Some(i) = n.as_i64() {
-                    // Special handling for crawl duration
-                    if key == "crawl_duration_seconds" {
-                        let seconds = i;
-                        if seconds < 60 {
-                            format!("{} seconds", seconds)
-                        } else if seconds < 3600 {
-                            let mins = seconds / 60;
-                            let secs = seconds % 60;
-                            if secs > 0 {
-                                format!("{} min {} sec", mins, secs)
-                            } else {
-                                format!("{} min", mins)
-                            }
-                        } else {
-                            let hours = seconds / 3600;
-                            let mins = (seconds % 3600) / 60;
-                            if mins > 0 {
-                                format!("{} hr {} min", hours, mins)
-                            } else {
-                                format!("{} hr", hours)
-                            }
-                        }
-                    } else {
-                        format_number(i as i32)
-                    }
-                } else if let Some(f) = n.as_f64() {
-                    // Special handling for reactions_per_message
-                    if key == "reactions_per_message" {
-                        if f > 0.0 {
-                            let messages_per_reaction = 1.0 / f;
-                            format!("every {:.0} sent messages", messages_per_reaction)
-                        } else {
-                            "never".to_string()
-                        }
-                    } else if key.ends_with("_per_message") || key.ends_with("_ratio") {
-                        format!("{:.1}%", f * 100.0)
-                    } else {
-                        format!("{:.2}", f)
-                    }
-                } else {
-                    n.to_string()
-                }
-            }
-            serde_json::Value::String(s) => s.clone(),
-            _ => value.to_string(),
-        };
+    #[test]
+    fn test_render_localized() {
+        let stats = example_stats();
 
-        // Add emoji based on field type
-        let emoji = match key.as_str() {
-            "longest_message_chars" => "📝",
-            "favorite_weekday" => "📅",
-            "peak_hour" => "🕐",
-            "longest_streak_days" => "🔥",
-            "reactions_per_message" => "😊",
-            "edits_per_message" => "✏️",
-            "crawl_duration_seconds" => "⏱️",
-            "lurking_rooms" => "👀",
-            "sent_encrypted_messages_ratio" => "🔐",
-            _ => "✨",
-        };
+        let fr = render(&stats, Lang::Fr).unwrap();
+        assert!(fr.contains("### 📊 Résumé"));
+        assert!(fr.contains("4\u{a0}832"));
+        assert!(!fr.contains("### 📊 Summary"));
+        assert!(fr.ends_with(
+            "---\n\n*Généré le 2025-12-20 par my 0.1.0 · fenêtre 2025 · données du 2025-01-02 au 2025-12-19 · @alice:example.org*\n"
+        ));
 
-        // Special formatting for reactions_per_message
-        let formatted_line = if key == "reactions_per_message" {
-            format!("- {} You react on {}\n", emoji, formatted_value)
-        } else {
-            format!("- {} **{}:** {}\n", emoji, display_key, formatted_value)
-        };
+        let de = render(&stats, Lang::De).unwrap();
+        assert!(de.contains("### 📊 Zusammenfassung"));
+        assert!(de.contains("4.832"));
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let mut stats = example_stats();
+        stats.scope = Scope::new(ScopeKind::Rolling, "last-30-days");
+
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(markdown.contains("Your Matrix Last 30 days"));
+        assert!(markdown.contains("You created **2** rooms in the last 30 days."));
+        assert!(!markdown.contains("#### 📆 By month"));
 
-        output.push_str(&formatted_line);
+        let fr = render(&stats, Lang::Fr).unwrap();
+        assert!(fr.contains("Ton Matrix : 30 derniers jours"));
     }
 
-    output.push('\n');
-}
+    #[test]
+    fn test_compare_previous() {
+        let mut stats = example_stats();
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(!markdown.contains("vs 2024"));
+
+        stats.previous = Some(PreviousWindow {
+            key: "2024".to_string(),
+            messages_sent: 4096,
+            days_active: Some(220),
+            reactions: Some(0),
+        });
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(markdown.contains("- 💬 **Messages sent:** 4,832 (▲ 18% vs 2024)\n"));
+        assert!(markdown.contains("- 🔥 **Active days:** 220 (= 0% vs 2024)\n"));
+        // Nothing to compare with
+        assert!(!markdown.contains("messages! ("));
+
+        let fr = render(&stats, Lang::Fr).unwrap();
+        assert!(fr.contains("(▲ 18\u{a0}% par rapport à 2024)"));
+    }
 
-fn scope_label(scope: &Scope) -> String {
-    if let Some(label) = &scope.label {
-        return label.clone();
+    #[test]
+    fn test_changelog() {
+        let mut stats = example_stats();
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(!markdown.contains("Regenerated"));
+
+        stats.previous_crawl = Some(PreviousCrawl {
+            generated_at: "2025-11-30".to_string(),
+            messages_sent: 3628,
+        });
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(markdown.ends_with(
+            "@alice:example.org*\n\n*Regenerated 2025-12-20 (last generated 2025-11-30): \
+             +1,204 newly discovered messages*\n"
+        ));
+
+        stats.previous_crawl.as_mut().unwrap().messages_sent = 4832;
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(markdown.contains("): no newly discovered messages*\n"));
     }
 
-    match scope.kind {
-        ScopeKind::Year => format!("Year {}", scope.key),
-        ScopeKind::Month => format!("Month {}", scope.key),
-        ScopeKind::Week => format!("Week {}", scope.key),
-        ScopeKind::Day => format!("Day {}", scope.key),
-        ScopeKind::Life => "Life-to-date".to_string(),
+    #[test]
+    fn test_time_of_day_personas() {
+        let mut stats = example_stats();
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(markdown.contains(
+            "#### 🌗 Time of day\nPrivate rooms in the evening (peak 20:00), DMs in the evening (peak 21:00), public rooms in the afternoon (peak 17:00).\n"
+        ));
+
+        // A single room type has nothing to compare with
+        let activity = stats.activity.as_mut().unwrap();
+        let by_type = activity.by_room_type_hour.as_mut().unwrap();
+        by_type.retain(|room_type, _| room_type == "dm");
+        assert!(!render(&stats, Lang::En).unwrap().contains("Time of day"));
     }
-}
 
-fn scope_phrase(scope: &Scope) -> String {
-    if let Some(label) = &scope.label {
-        return label.clone();
+    #[test]
+    fn test_sparklines() {
+        let mut stats = example_stats();
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(markdown.contains("#### 📆 By month\n```text\n▅▇▆▅▆▇▇▆▇██▇\nJFMAMJJASOND\n```\n"));
+        assert!(markdown
+            .contains("```text\n▁▁▁▁▁▁▁▂▃▄▄▅▄▄▄▅▅▅▄▄▆██▃\n0     6     12    18   23\n```\n"));
+
+        // A quarter only shows its own months
+        stats.scope = Scope::new(ScopeKind::Quarter, "2025-Q3");
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(markdown.contains("\nJAS\n```\n"));
+        assert!(
+            markdown.contains("| Jul | Aug | Sep |\n| --- | --- | --- |\n| 420 | 380 | 445 |\n\n#")
+        );
+        assert!(!markdown.contains("| Jan |"));
+
+        // By month is not shown for shorter windows
+        stats.scope.kind = ScopeKind::Month;
+        assert!(!render(&stats, Lang::En).unwrap().contains("JFMAMJJASOND"));
     }
 
-    match scope.kind {
-        ScopeKind::Year => format!("the year {}", scope.key),
-        ScopeKind::Month => format!("the month {}", scope.key),
-        ScopeKind::Week => format!("the week {}", scope.key),
-        ScopeKind::Day => format!("the day {}", scope.key),
-        ScopeKind::Life => "your life on Matrix so far".to_string(),
+    #[test]
+    fn test_day_of_year_matrix() {
+        let mut stats = example_stats();
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(markdown.contains("#### 📅 By day\n| Month | 01 | 02 |"));
+        assert!(markdown.contains("\n| Jan | 0 | 23 | 30 |"));
+        // Days past the end of the month stay empty
+        assert!(markdown.contains(" | 13 | 19 | 0 | 0 | 0 |  |  |  |\n| Mar |"));
+
+        stats.scope.kind = ScopeKind::Life;
+        assert!(!render(&stats, Lang::En).unwrap().contains("| Month |"));
     }
-}
 
-/// Format a number with thousand separators (raw integers, no abbreviation)
-fn format_number(n: i32) -> String {
-    let is_negative = n < 0;
-    // Work with absolute value as i64 to safely handle i32::MIN
-    let abs_str = (n as i64).abs().to_string();
-    let mut grouped_rev = String::new();
+    #[test]
+    fn test_top_emoji_bars() {
+        let markdown = render(&example_stats(), Lang::En).unwrap();
+        assert!(markdown.contains("| 1 | 😂 | ████████████████ 180 |\n"));
+        assert!(markdown.contains("| 5 | 🤔 | ████████▌ 95 |\n"));
+    }
 
-    // Insert commas every three digits, starting from the right
-    for (count, ch) in abs_str.chars().rev().enumerate() {
-        if count > 0 && count.is_multiple_of(3) {
-            grouped_rev.push(',');
-        }
-        grouped_rev.push(ch);
+    #[test]
+    fn test_fun_durations() {
+        let stats = example_stats();
+        let en = render(&stats, Lang::En).unwrap();
+        assert!(en.contains("- ⏱️ **Crawl duration:** 2 min 22 sec\n"));
+        assert!(en.contains("- 🔥 **Longest streak:** 15 days\n"));
+        assert!(en.contains("- 🤫 Your quietest stretch was 9 days (Aug 4 – Aug 12)\n"));
+
+        let fr = render(&stats, Lang::Fr).unwrap();
+        assert!(fr.contains("- 🔥 **Plus longue série:** 15 jours\n"));
+        assert!(fr.contains("- ⚡ **Série en cours:** 4 jours\n"));
+
+        // A silence over New Year shows the years
+        let mut stats = stats;
+        stats.fun.as_mut().unwrap().fields["longest_silence"] =
+            serde_json::json!({"days": 5, "from": "2024-12-30", "to": "2025-01-03"});
+        let en = render(&stats, Lang::En).unwrap();
+        assert!(en.contains("was 5 days (Dec 30, 2024 – Jan 3, 2025)\n"));
     }
 
-    // Reverse back to normal order
-    let mut formatted: String = grouped_rev.chars().rev().collect();
-    if is_negative {
-        formatted.insert(0, '-');
+    #[test]
+    fn test_message_previews() {
+        let mut stats = example_stats();
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(markdown.contains("| 1 | [view](https://matrix.to/#/!abc:example.org/$event1) |"));
+
+        let top_messages = stats.reactions.as_mut().unwrap().top_messages.as_mut();
+        top_messages.unwrap()[0].preview = Some("ship it | [now] *please*".to_string());
+        let markdown = render(&stats, Lang::En).unwrap();
+        assert!(markdown.contains(
+            "| 1 | [ship it \\| \\[now\\] \\*please\\*](https://matrix.to/#/!abc:example.org/$event1) |"
+        ));
     }
-    formatted
-}
 
-/// Uppercase the first character of a string
-fn uppercase_first_char(s: &str) -> String {
-    let mut chars = s.chars();
-    match chars.next() {
-        None => String::new(),
-        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+    #[test]
+    fn test_invalid_template_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("header.md"), "{% if %}").unwrap();
+
+        let err = render_with_templates(
+            &example_stats(),
+            Some(dir.path()),
+            Lang::En,
+            &LinkBase::default(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Invalid template"));
     }
 }
@@ -1 +1,86 @@
+pub mod badge;
+pub mod card;
+mod common;
+pub mod compress;
+pub mod digest;
+pub mod html;
+pub mod i18n;
+pub mod json;
+pub mod links;
 pub mod md;
+pub mod org;
+pub mod output_name;
+pub mod pdf;
+pub mod renderer;
+pub mod site;
+pub mod term;
+pub mod theme;
+pub mod txt;
+
+pub use common::{limit_rankings, make_golden, room_report, Avatar, Avatars};
+
+use card::CardTheme;
+use compress::Compression;
+use i18n::Lang;
+use links::LinkBase;
+use output_name::OutputName;
+use std::path::PathBuf;
+use theme::HtmlTheme;
+
+/// Output tunables for rendering, set from CLI flags.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// Compressed copies to write next to the HTML report
+    pub compress: Vec<Compression>,
+    /// Size above which the HTML report triggers a warning, in KB (0 disables the check)
+    pub html_budget_kb: u64,
+    /// Color theme of the HTML report
+    pub theme: HtmlTheme,
+    /// Color theme of the PNG recap card
+    pub card_theme: CardTheme,
+    /// Directory of Markdown templates overriding the built-in ones
+    pub template_dir: Option<PathBuf>,
+    /// Language of the Markdown, HTML, PDF and terminal reports
+    pub lang: Lang,
+    /// Base of the links to rooms, messages and the account
+    pub link_base: LinkBase,
+    /// Also render a mini-report for each room breakdown of the stats
+    pub per_room: bool,
+    /// Entries shown in each ranking; `None` shows all entries of the stats
+    pub top: Option<usize>,
+    /// Pin what changes between runs over the same stats (see [`make_golden`])
+    pub golden: bool,
+    /// Provisional report of a running crawl: file names get a `-live` suffix and
+    /// the HTML report reloads itself every this many seconds (0 never)
+    pub live: Option<u64>,
+    /// Annotate headline numbers with their change against the previous window
+    pub compare_previous: bool,
+    /// Note what the stats gained over the ones they replaced when their window was crawled again
+    pub changelog: bool,
+    /// Pattern of report file names; `None` names them `my-<scope>-<key>`
+    pub output_name: Option<OutputName>,
+    /// Put the account localpart in default file names (`my-alice-year-2025.md`)
+    pub account_in_filenames: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            compress: Vec::new(),
+            html_budget_kb: html::DEFAULT_BUDGET_KB,
+            theme: HtmlTheme::default(),
+            card_theme: CardTheme::default(),
+            template_dir: None,
+            lang: Lang::default(),
+            link_base: LinkBase::default(),
+            per_room: false,
+            top: None,
+            golden: false,
+            live: None,
+            compare_previous: false,
+            changelog: false,
+            output_name: None,
+            account_in_filenames: false,
+        }
+    }
+}
@@ -0,0 +1,248 @@
+/// Org-mode renderer.
+///
+/// Translates the Markdown report (same sections, wording and templates) into
+/// Org markup, for Emacs users who keep their reviews in Org files: headings
+/// become `*` outlines, tables Org tables, fenced blocks example blocks and
+/// links `[[url][text]]`.
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::i18n::Lang;
+use super::links::LinkBase;
+use super::md;
+use crate::stats::Stats;
+
+/// Render stats to Org in `lang`, from the Markdown report rendered with
+/// the templates of `template_dir` and the links of `links`.
+pub fn render(
+    stats: &Stats,
+    template_dir: Option<&Path>,
+    lang: Lang,
+    links: &LinkBase,
+) -> Result<String> {
+    let markdown = md::render_with_templates(stats, template_dir, lang, links)?;
+    Ok(from_markdown(&markdown))
+}
+
+/// Translates the Markdown of the report into Org.
+fn from_markdown(markdown: &str) -> String {
+    let mut out = Vec::new();
+    let mut in_block = false;
+
+    for line in markdown.lines() {
+        let line = line.trim_end();
+        if line.starts_with("```") {
+            out.push(if in_block {
+                "#+end_example".to_string()
+            } else {
+                "#+begin_example".to_string()
+            });
+            in_block = !in_block;
+        } else if in_block {
+            out.push(line.to_string());
+        } else if line.trim_start().starts_with('|') {
+            out.push(table_row(line.trim()));
+        } else if let Some((level, text)) = heading(line) {
+            out.push(format!("{} {}", "*".repeat(level), inline(text, false)));
+        } else if line == "---" {
+            out.push("-----".to_string());
+        } else {
+            out.push(inline(line, false));
+        }
+    }
+
+    let mut org = out.join("\n");
+    org.push('\n');
+    org
+}
+
+/// Outline level and text of a Markdown heading. The report skips `##`, so
+/// `###` sections sit right below the title.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    let text = line[hashes..].strip_prefix(' ')?;
+    match hashes {
+        0 => None,
+        1 | 2 => Some((hashes, text)),
+        _ => Some((hashes - 1, text)),
+    }
+}
+
+/// An Org table row, or the rule below the header for a Markdown separator row.
+fn table_row(row: &str) -> String {
+    let cells: Vec<&str> = row
+        .trim_matches('|')
+        .split(" | ")
+        .map(|cell| cell.trim())
+        .collect();
+    if is_table_separator(row) {
+        let rules: Vec<String> = cells
+            .iter()
+            .map(|cell| "-".repeat(cell.len() + 2))
+            .collect();
+        return format!("|{}|", rules.join("+"));
+    }
+    let cells: Vec<String> = cells.iter().map(|cell| inline(cell, true)).collect();
+    format!("| {} |", cells.join(" | "))
+}
+
+fn is_table_separator(row: &str) -> bool {
+    row.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+/// Translates Markdown emphasis, code, links and images of `text` into Org.
+/// Characters escaped in the Markdown are written as Org entities where Org
+/// would read them as markup.
+fn inline(text: &str, in_table: bool) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 < chars.len() => {
+                out.push_str(&literal(chars[i + 1], in_table));
+                i += 2;
+            }
+            '!' if chars.get(i + 1) == Some(&'[') => match link(&chars, i + 1) {
+                // Images (the avatar) are linked by file name and shown inline by Org
+                Some((_, target, end)) => {
+                    out.push_str(&format!("[[file:{}]]", target));
+                    i = end;
+                }
+                None => {
+                    out.push('!');
+                    i += 1;
+                }
+            },
+            '[' => match link(&chars, i) {
+                Some((label, target, end)) => {
+                    out.push_str(&format!("[[{}][{}]]", target, inline(&label, in_table)));
+                    i = end;
+                }
+                None => {
+                    out.push('[');
+                    i += 1;
+                }
+            },
+            '`' => match chars[i + 1..].iter().position(|&c| c == '`') {
+                Some(len) => {
+                    let code: String = chars[i + 1..i + 1 + len].iter().collect();
+                    out.push_str(&format!("~{}~", code));
+                    i += len + 2;
+                }
+                None => {
+                    out.push('`');
+                    i += 1;
+                }
+            },
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                out.push('*');
+                i += 2;
+            }
+            '*' => {
+                out.push('/');
+                i += 1;
+            }
+            c => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Label, target and end index of the Markdown link opening at `start`.
+fn link(chars: &[char], start: usize) -> Option<(String, String, usize)> {
+    let mut i = start + 1;
+    let mut depth = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => depth += 1,
+            ']' if depth > 0 => depth -= 1,
+            ']' => break,
+            _ => {}
+        }
+        i += 1;
+    }
+    if chars.get(i + 1) != Some(&'(') {
+        return None;
+    }
+    let target_start = i + 2;
+    let target_len = chars[target_start..].iter().position(|&c| c == ')')?;
+    let label = chars[start + 1..i].iter().collect();
+    let target = chars[target_start..target_start + target_len]
+        .iter()
+        .collect();
+    Some((label, target, target_start + target_len + 1))
+}
+
+/// An escaped Markdown character as Org text.
+fn literal(c: char, in_table: bool) -> String {
+    match c {
+        '*' => "\\ast{}".to_string(),
+        '_' => "\\under{}".to_string(),
+        '[' => "\\lbrack{}".to_string(),
+        ']' => "\\rbrack{}".to_string(),
+        '\\' => "\\backslash{}".to_string(),
+        '|' if in_table => "\\vert{}".to_string(),
+        c => c.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::render::common::example_stats;
+
+    #[test]
+    fn test_inline() {
+        assert_eq!(
+            inline(
+                "🏘️ **Rooms** in [Friends](https://matrix.to/#/!a) · *covers*",
+                false
+            ),
+            "🏘️ *Rooms* in [[https://matrix.to/#/!a][Friends]] · /covers/"
+        );
+        assert_eq!(inline("[not a link", false), "[not a link");
+        assert_eq!(
+            inline("[ship it \\| \\[now\\]](https://matrix.to/#/$e)", true),
+            "[[https://matrix.to/#/$e][ship it \\vert{} \\lbrack{}now\\rbrack{}]]"
+        );
+        assert_eq!(
+            inline("**Avatar:** ![Avatar](avatar-abc.png)", false),
+            "*Avatar:* [[file:avatar-abc.png]]"
+        );
+        assert_eq!(
+            inline("`mxc://a/b` a\\_b", false),
+            "~mxc://a/b~ a\\under{}b"
+        );
+    }
+
+    #[test]
+    fn test_from_markdown() {
+        let markdown = "# Title\n### Rooms\n#### Peaks\n\n\
+                        | Rank | Name |\n| ---- | ---- |\n| 1 | **Bob** |\n\n\
+                        ```text\n*** **\n```\n\n---\n";
+        assert_eq!(
+            from_markdown(markdown),
+            "* Title\n** Rooms\n*** Peaks\n\n\
+             | Rank | Name |\n|------+------|\n| 1 | *Bob* |\n\n\
+             #+begin_example\n*** **\n#+end_example\n\n-----\n"
+        );
+    }
+
+    #[test]
+    fn test_render_mirrors_markdown_sections() {
+        let org = render(&example_stats(), None, Lang::En, &LinkBase::default()).unwrap();
+        assert!(org.starts_with("* 🎉 Your Matrix Year 2025"));
+        assert!(org.contains("\n** 🏘️ Rooms\n"));
+        assert!(org.contains("\n*** 🚀 Peaks\n"));
+        assert!(org.contains("|------+------+----------+------------|"));
+        assert!(!org.contains("```"));
+        assert!(!org.contains("\n### "));
+    }
+}
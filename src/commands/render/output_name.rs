@@ -0,0 +1,105 @@
+/// Report file names chosen with `--output-name`.
+///
+/// Reports are named `my-<scope>-<key>` by default. A pattern lets several
+/// accounts render into one directory without overwriting each other's
+/// reports, or follow the user's own naming.
+use anyhow::{bail, Result};
+
+use crate::stats::{ScopeKind, Stats};
+
+/// Placeholders a pattern may use.
+const PLACEHOLDERS: [&str; 4] = ["scope", "key", "user", "format"];
+
+/// Pattern of report file names, without extension (e.g. `{user}-{key}`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputName(String);
+
+impl OutputName {
+    /// Parses a pattern made of text and `{scope}`, `{key}`, `{user}` and
+    /// `{format}` placeholders. Names stay in the output directory, so path
+    /// separators are refused.
+    pub fn parse(input: &str) -> Result<Self> {
+        let pattern = input.trim();
+        if pattern.is_empty() {
+            bail!("Empty output name (e.g. {{user}}-{{scope}}-{{key}})");
+        }
+        if pattern.contains(['/', '\\']) || pattern == "." || pattern == ".." {
+            bail!(
+                "Invalid output name '{}': reports are written in the output directory, \
+                 use --output to change it",
+                pattern
+            );
+        }
+
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                bail!("Invalid output name '{}': unclosed '{{'", pattern);
+            };
+            let placeholder = &rest[start + 1..start + end];
+            if !PLACEHOLDERS.contains(&placeholder) {
+                bail!(
+                    "Unknown placeholder '{{{}}}' in output name (expected {{scope}}, {{key}}, \
+                     {{user}} or {{format}})",
+                    placeholder
+                );
+            }
+            rest = &rest[start + end + 1..];
+        }
+        Ok(Self(pattern.to_string()))
+    }
+
+    /// File name of the `format` report of `stats`, without suffix and extension.
+    pub fn render(&self, stats: &Stats, format: &str) -> String {
+        let scope = match stats.scope.kind {
+            ScopeKind::Year => "year",
+            ScopeKind::Quarter => "quarter",
+            ScopeKind::Month => "month",
+            ScopeKind::Week => "week",
+            ScopeKind::Day => "day",
+            ScopeKind::Rolling => "rolling",
+            ScopeKind::Life => "life",
+        };
+        self.0
+            .replace("{scope}", scope)
+            .replace("{key}", &stats.scope.key)
+            .replace("{user}", &user_part(&stats.account.user_id))
+            .replace("{format}", format)
+    }
+}
+
+/// User ID as a file name part (e.g. `@alice:example.org` -> `alice_example.org`).
+fn user_part(user_id: &str) -> String {
+    user_id
+        .trim_start_matches('@')
+        .chars()
+        .map(|ch| match ch {
+            ':' | '/' | '\\' => '_',
+            ch => ch,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::render::common::example_stats;
+
+    #[test]
+    fn test_render_placeholders() {
+        let stats = example_stats();
+        let name = OutputName::parse("{user}-{scope}-{key}").unwrap();
+        assert_eq!(name.render(&stats, "md"), "alice_example.org-year-2025");
+
+        let name = OutputName::parse("recap {key} ({format})").unwrap();
+        assert_eq!(name.render(&stats, "html"), "recap 2025 (html)");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(OutputName::parse("").is_err());
+        assert!(OutputName::parse("reports/{key}").is_err());
+        assert!(OutputName::parse("{year}").is_err());
+        assert!(OutputName::parse("my-{key").is_err());
+    }
+}
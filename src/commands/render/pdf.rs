@@ -0,0 +1,655 @@
+/// PDF renderer.
+///
+/// Lays out the Markdown report (same sections and wording) on A4 pages with a
+/// small pure-Rust PDF writer. Uses the standard Helvetica/Courier fonts, which
+/// every PDF viewer provides, so nothing is embedded and the file stays small.
+/// Those fonts only cover the WinAnsi encoding: the emoji decorating headings and
+/// list items are left out, arrows and bars get ASCII stand-ins, and any other
+/// character (emoji in rankings, names in other scripts) shows as `?`.
+use super::common::SPARKS;
+use super::i18n::Lang;
+use super::md;
+use crate::stats::Stats;
+use anyhow::Result;
+use std::collections::BTreeSet;
+
+const PAGE_WIDTH: f64 = 595.0;
+const PAGE_HEIGHT: f64 = 842.0;
+const MARGIN: f64 = 56.0;
+const CONTENT_WIDTH: f64 = PAGE_WIDTH - 2.0 * MARGIN;
+
+/// Shown in place of the characters the standard fonts can't show.
+const PLACEHOLDER: char = '?';
+
+/// Render stats to a PDF document, with a warning when some characters had to
+/// be replaced by [`PLACEHOLDER`]
+pub fn render(stats: &Stats, lang: Lang) -> Result<(Vec<u8>, Option<String>)> {
+    let markdown = md::render(stats, lang)?;
+    let mut layout = Layout::new();
+
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim_end();
+
+        if line.starts_with("```") {
+            // Fenced block (calendar heatmap, sparklines): monospaced, with emoji cells and
+            // sparkline blocks as ASCII shades
+            let mut block = Vec::new();
+            i += 1;
+            while i < lines.len() && !lines[i].starts_with("```") {
+                block.push(plain_block_line(lines[i]));
+                i += 1;
+            }
+            i += 1;
+            layout.preformatted(&block);
+            continue;
+        }
+
+        if line.starts_with('|') {
+            // Collect the whole table block
+            let mut rows = Vec::new();
+            while i < lines.len() && lines[i].trim_start().starts_with('|') {
+                let row = lines[i].trim();
+                if !is_table_separator(row) {
+                    rows.push(
+                        row.trim_matches('|')
+                            .split(" | ")
+                            .map(|cell| clean_inline(cell.trim()))
+                            .collect::<Vec<_>>(),
+                    );
+                }
+                i += 1;
+            }
+            layout.table(&rows);
+            continue;
+        }
+
+        if let Some(text) = line.strip_prefix("# ") {
+            layout.paragraph(&clean_text(text), Font::Bold, 20.0, 8.0);
+        } else if let Some(text) = line.strip_prefix("### ") {
+            layout.gap(6.0);
+            layout.paragraph(&clean_text(text), Font::Bold, 15.0, 4.0);
+        } else if let Some(text) = line.strip_prefix("#### ") {
+            layout.paragraph(&clean_text(text), Font::Bold, 12.0, 2.0);
+        } else if let Some(text) = line.strip_prefix("- ") {
+            layout.paragraph(
+                &format!("\u{2022} {}", clean_text(text)),
+                Font::Regular,
+                10.5,
+                1.0,
+            );
+        } else if line == "---" {
+            layout.gap(12.0);
+        } else if line.starts_with('*') && !line.starts_with("**") {
+            // Emphasized line: the provenance footer
+            layout.paragraph(&clean_text(line), Font::Regular, 8.5, 1.0);
+        } else if line.is_empty() {
+            layout.gap(6.0);
+        } else {
+            layout.paragraph(&clean_text(line), Font::Regular, 10.5, 1.0);
+        }
+        i += 1;
+    }
+
+    let title = layout.encodable(&md_title(&markdown));
+    let warning = unsupported_warning(&layout.unsupported);
+    Ok((write_pdf(&layout.finish(), &title), warning))
+}
+
+/// Document title: the report's first heading without emoji.
+fn md_title(markdown: &str) -> String {
+    markdown
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(clean_text)
+        .unwrap_or_default()
+}
+
+/// Most replaced characters named in the warning.
+const WARNING_MAX_CHARS: usize = 10;
+
+/// Warning naming the characters replaced by [`PLACEHOLDER`], if any.
+fn unsupported_warning(unsupported: &BTreeSet<char>) -> Option<String> {
+    if unsupported.is_empty() {
+        return None;
+    }
+    let mut shown: Vec<String> = unsupported
+        .iter()
+        .take(WARNING_MAX_CHARS)
+        .map(char::to_string)
+        .collect();
+    if unsupported.len() > WARNING_MAX_CHARS {
+        shown.push("…".to_string());
+    }
+    Some(format!(
+        "The PDF fonts cannot show {} character(s), replaced with '{}': {}",
+        unsupported.len(),
+        PLACEHOLDER,
+        shown.join(" ")
+    ))
+}
+/// ASCII stand-ins for the heatmap emoji, two columns wide like the emoji they replace.
+const HEATMAP_SHADES: [&str; md::HEATMAP_EMOJI.len()] = [". ", "- ", "+ ", "# "];
+
+/// ASCII stand-ins for the sparkline levels, from quiet to busiest.
+const SPARK_SHADES: [char; SPARKS.len()] = ['_', '.', ':', '-', '=', '+', '*', '#'];
+
+/// Keeps a preformatted line's alignment while replacing characters the standard fonts can't show.
+fn plain_block_line(line: &str) -> String {
+    let mut line = line.to_string();
+    for (emoji, shade) in md::HEATMAP_EMOJI.iter().zip(HEATMAP_SHADES) {
+        line = line.replace(emoji, shade);
+    }
+    line.trim_end().to_string()
+}
+
+fn is_table_separator(row: &str) -> bool {
+    row.chars().all(|c| matches!(c, '|' | '-' | ' ' | ':'))
+}
+
+/// Strips inline Markdown and the emoji decorating the start of a line of text.
+fn clean_text(text: &str) -> String {
+    let text = clean_inline(text);
+    text.trim_start_matches(|c: char| {
+        c.is_whitespace() || (!c.is_alphanumeric() && win_ansi_byte(c).is_none())
+    })
+    .to_string()
+}
+
+/// Strips inline Markdown (bold, italics, links).
+fn clean_inline(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('[') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find("](").and_then(|mid| {
+            after[mid + 2..]
+                .find(')')
+                .map(|end| (mid, mid + 2 + end + 1))
+        }) {
+            Some((mid, end)) => {
+                // Images (the avatar) are not drawn: drop them with their alt text
+                if out.ends_with('!') {
+                    out.pop();
+                } else {
+                    out.push_str(&after[..mid]);
+                }
+                rest = &after[end..];
+            }
+            None => {
+                out.push('[');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+
+    // Backslash escapes of free text (e.g. message previews)
+    let mut unescaped = String::with_capacity(out.len());
+    let mut chars = out.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            _ => unescaped.push(c),
+        }
+    }
+
+    let out = unescaped.replace("**", "");
+    let out: String = out.chars().filter(|c| *c != '*').collect();
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// ============================================================================
+// Layout
+// ============================================================================
+
+#[derive(Clone, Copy, PartialEq)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        }
+    }
+
+    /// Text width in points, from the standard font metrics.
+    fn width(&self, text: &str, size: f64) -> f64 {
+        let units: f64 = text
+            .chars()
+            .map(|c| match self {
+                Font::Mono => 600.0,
+                Font::Regular => helvetica_width(c),
+                // Helvetica-Bold is slightly wider on average
+                Font::Bold => helvetica_width(c) * 1.06,
+            })
+            .sum();
+        units * size / 1000.0
+    }
+}
+
+/// Helvetica advance widths (per 1000 units) for printable ASCII.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, // ' '../
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // 0..9
+    278, 278, 584, 584, 584, 556, 1015, // :..@
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, // A..M
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // N..Z
+    278, 278, 278, 469, 556, 333, // [..`
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, // a..m
+    556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, // n..z
+    334, 260, 334, 584, // {..~
+];
+
+fn helvetica_width(c: char) -> f64 {
+    match c as u32 {
+        code @ 32..=126 => HELVETICA_WIDTHS[(code - 32) as usize] as f64,
+        _ => 556.0,
+    }
+}
+
+/// A positioned run of text.
+struct TextRun {
+    x: f64,
+    y: f64,
+    font: Font,
+    size: f64,
+    text: String,
+}
+
+/// Flows text top-down over A4 pages.
+struct Layout {
+    pages: Vec<Vec<TextRun>>,
+    y: f64,
+    /// Characters replaced by [`PLACEHOLDER`]
+    unsupported: BTreeSet<char>,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self {
+            pages: vec![Vec::new()],
+            y: PAGE_HEIGHT - MARGIN,
+            unsupported: BTreeSet::new(),
+        }
+    }
+
+    /// Maps `text` to characters the standard fonts can show, keeping track of
+    /// those replaced by [`PLACEHOLDER`].
+    fn encodable(&mut self, text: &str) -> String {
+        text.chars()
+            .filter_map(|c| {
+                if win_ansi_byte(c).is_some() {
+                    return Some(c);
+                }
+                if let Some(level) = SPARKS.iter().position(|&spark| spark == c) {
+                    return Some(SPARK_SHADES[level]);
+                }
+                match c {
+                    // Parts of emoji sequences, shown with the emoji they belong to
+                    '\u{200D}' | '\u{FE0E}' | '\u{FE0F}' | '\u{1F3FB}'..='\u{1F3FF}' => None,
+                    // Partial blocks ending the bars of rankings
+                    '\u{2589}'..='\u{258F}' => Some('#'),
+                    '↑' => Some('+'),
+                    '↓' => Some('-'),
+                    _ => {
+                        self.unsupported.insert(c);
+                        Some(PLACEHOLDER)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn gap(&mut self, points: f64) {
+        self.y -= points;
+    }
+
+    /// Moves to the next line, starting a new page when the current one is full.
+    fn advance(&mut self, line_height: f64) {
+        if self.y - line_height < MARGIN {
+            self.pages.push(Vec::new());
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+        self.y -= line_height;
+    }
+
+    fn push(&mut self, x: f64, font: Font, size: f64, text: String) {
+        let y = self.y;
+        self.pages
+            .last_mut()
+            .expect("layout always has a page")
+            .push(TextRun {
+                x,
+                y,
+                font,
+                size,
+                text,
+            });
+    }
+
+    /// Word-wrapped paragraph, followed by `spacing` points.
+    fn paragraph(&mut self, text: &str, font: Font, size: f64, spacing: f64) {
+        let text = self.encodable(text);
+        if text.is_empty() {
+            return;
+        }
+        for line in wrap(&text, font, size, CONTENT_WIDTH) {
+            self.advance(size * 1.4);
+            self.push(MARGIN, font, size, line);
+        }
+        self.gap(spacing);
+    }
+
+    /// Monospaced table, shrunk to fit the page width when needed.
+    fn table(&mut self, rows: &[Vec<String>]) {
+        let rows: Vec<Vec<String>> = rows
+            .iter()
+            .map(|row| row.iter().map(|cell| self.encodable(cell)).collect())
+            .collect();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+        let widths: Vec<usize> = (0..columns)
+            .map(|col| {
+                rows.iter()
+                    .filter_map(|row| row.get(col))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let total_chars = widths.iter().sum::<usize>() + 2 * (columns - 1);
+        let size = (CONTENT_WIDTH / (total_chars as f64 * 0.6)).min(9.5);
+
+        for (index, row) in rows.iter().enumerate() {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            self.advance(size * 1.45);
+            self.push(MARGIN, Font::Mono, size, line.trim_end().to_string());
+            if index == 0 {
+                self.gap(2.0);
+            }
+        }
+        self.gap(4.0);
+    }
+
+    /// Monospaced lines kept as-is, shrunk to fit the page width when needed.
+    fn preformatted(&mut self, lines: &[String]) {
+        let lines: Vec<String> = lines.iter().map(|line| self.encodable(line)).collect();
+        let Some(longest) = lines.iter().map(|line| line.chars().count()).max() else {
+            return;
+        };
+        let size = (CONTENT_WIDTH / (longest.max(1) as f64 * 0.6)).min(9.5);
+        for line in lines {
+            self.advance(size * 1.3);
+            self.push(MARGIN, Font::Mono, size, line);
+        }
+        self.gap(4.0);
+    }
+
+    fn finish(self) -> Vec<Vec<TextRun>> {
+        self.pages
+    }
+}
+
+/// Greedy word wrap to `max_width` points.
+fn wrap(text: &str, font: Font, size: f64, max_width: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split(' ') {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+        if font.width(&candidate, size) > max_width && !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+            current = word.to_string();
+        } else {
+            current = candidate;
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+// ============================================================================
+// PDF serialization
+// ============================================================================
+
+/// Maps a character to its WinAnsiEncoding byte, if representable.
+fn win_ansi_byte(c: char) -> Option<u8> {
+    match c {
+        ' '..='~' => Some(c as u8),
+        '\u{A0}'..='\u{FF}' => Some(c as u32 as u8),
+        '€' => Some(0x80),
+        '…' => Some(0x85),
+        '‘' => Some(0x91),
+        '’' => Some(0x92),
+        '“' => Some(0x93),
+        '”' => Some(0x94),
+        '•' => Some(0x95),
+        '–' => Some(0x96),
+        '—' => Some(0x97),
+        _ => None,
+    }
+}
+
+/// Encodes text as a PDF literal string in WinAnsiEncoding.
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for byte in text.chars().filter_map(win_ansi_byte) {
+        if matches!(byte, b'(' | b')' | b'\\') {
+            out.push(b'\\');
+        }
+        out.push(byte);
+    }
+    out.push(b')');
+    out
+}
+
+fn write_pdf(pages: &[Vec<TextRun>], title: &str) -> Vec<u8> {
+    // Fixed objects: 1 catalog, 2 page tree, 3-5 fonts, 6 info; then page + content per page
+    let first_page_obj = 7;
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", first_page_obj + 2 * i))
+        .collect();
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    objects.push(
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+    );
+    for base_font in ["Helvetica", "Helvetica-Bold", "Courier"] {
+        objects.push(
+            format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                base_font
+            )
+            .into_bytes(),
+        );
+    }
+    let mut info = b"<< /Title ".to_vec();
+    info.extend(pdf_string(title));
+    info.extend(b" /Producer (my) >>");
+    objects.push(info);
+
+    for (i, runs) in pages.iter().enumerate() {
+        let content_obj = first_page_obj + 2 * i + 1;
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH, PAGE_HEIGHT, content_obj
+            )
+            .into_bytes(),
+        );
+
+        let mut stream = Vec::new();
+        for run in runs {
+            stream.extend(
+                format!(
+                    "BT /{} {:.2} Tf {:.2} {:.2} Td ",
+                    run.font.resource(),
+                    run.size,
+                    run.x,
+                    run.y
+                )
+                .into_bytes(),
+            );
+            stream.extend(pdf_string(&run.text));
+            stream.extend(b" Tj ET\n");
+        }
+        let mut content = format!("<< /Length {} >>\nstream\n", stream.len()).into_bytes();
+        content.extend(stream);
+        content.extend(b"\nendstream");
+        objects.push(content);
+    }
+
+    let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+
+    let xref_offset = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+    }
+    pdf.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R /Info 6 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        )
+        .into_bytes(),
+    );
+    pdf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::render::common::example_stats;
+
+    #[test]
+    fn test_clean_inline() {
+        assert_eq!(
+            clean_inline("🏘️ **Rooms** in [Friends](https://matrix.to/#/!a) · *covers*"),
+            "🏘️ Rooms in Friends · covers"
+        );
+        assert_eq!(clean_inline("[not a link"), "[not a link");
+        assert_eq!(
+            clean_inline("[ship it \\| \\[now\\]](https://matrix.to/#/$e)"),
+            "ship it | [now]"
+        );
+        assert_eq!(
+            clean_inline("**Avatar:** ![Avatar](avatar-abc.png)"),
+            "Avatar:"
+        );
+    }
+
+    #[test]
+    fn test_clean_text_strips_decorations() {
+        assert_eq!(clean_text("🏘️ **Rooms**"), "Rooms");
+        assert_eq!(clean_text("↩️ **Replies:** 1,150"), "Replies: 1,150");
+        // Only decorations: data keeps its emoji and other scripts
+        assert_eq!(clean_text("You typed 😂 most"), "You typed 😂 most");
+        assert_eq!(clean_text("Привет 👋"), "Привет 👋");
+    }
+
+    #[test]
+    fn test_encodable_replaces_unsupported_characters() {
+        let mut layout = Layout::new();
+        assert_eq!(layout.encodable("Café € ↑"), "Café € +");
+        assert_eq!(layout.encodable(" ▁▄█ ██▌"), " _-# ###");
+        assert_eq!(layout.encodable("❤️ Привет"), "? ??????");
+        assert_eq!(
+            unsupported_warning(&layout.unsupported).unwrap(),
+            "The PDF fonts cannot show 7 character(s), replaced with '?': П в е и р т ❤"
+        );
+        assert_eq!(unsupported_warning(&BTreeSet::new()), None);
+    }
+
+    #[test]
+    fn test_pdf_string_escapes_and_encodes() {
+        assert_eq!(
+            pdf_string("a (b) \\ é 🎉"),
+            b"(a \\(b\\) \\\\ \xE9 )".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_render_produces_valid_structure() {
+        let (pdf, warning) = render(&example_stats(), Lang::En).unwrap();
+        // The emoji of the rankings
+        assert!(warning.unwrap().contains("😂"));
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(text.trim_end().ends_with("%%EOF"));
+        assert!(text.contains("/Title (Your Matrix Year 2025 "));
+        assert!(text.contains("/Type /Pages"));
+
+        // startxref points at the xref table
+        let startxref = text.rfind("startxref\n").unwrap() + "startxref\n".len();
+        let offset: usize = text[startxref..].lines().next().unwrap().parse().unwrap();
+        assert!(pdf[offset..].starts_with(b"xref"));
+
+        // Every xref entry points at its object
+        let xref = &text[offset..];
+        for (i, entry) in xref
+            .lines()
+            .skip(3)
+            .take_while(|l| l.ends_with(" n "))
+            .enumerate()
+        {
+            let object_offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[object_offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_plain_block_line() {
+        assert_eq!(plain_block_line("Mon ⬜🟥  🟨"), "Mon . #   -");
+        assert_eq!(plain_block_line("    Jan     Feb   "), "    Jan     Feb");
+    }
+
+    #[test]
+    fn test_wrap() {
+        let text = "word ".repeat(100);
+        let lines = wrap(text.trim(), Font::Regular, 10.0, 100.0);
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(Font::Regular.width(line, 10.0) <= 100.0);
+        }
+    }
+}
@@ -0,0 +1,495 @@
+/// Report formats selected with `--formats`.
+///
+/// Each format is a [`ReportRenderer`] that turns stats into output files; the
+/// caller writes them. Built-in formats come from [`builtin_renderers`], and
+/// downstream crates can add their own to the list before dispatching.
+use anyhow::Result;
+
+use super::badge::{self, BadgeMetric};
+use super::common::Avatars;
+use super::{card, digest, html, json, md, org, pdf, term, txt, RenderOptions};
+use crate::stats::{Scope, ScopeKind, Stats};
+
+/// A file produced by a renderer.
+#[derive(Debug)]
+pub struct OutputFile {
+    /// File name in the output directory; `None` prints to standard output
+    pub name: Option<String>,
+    /// Shown with the path once written (e.g. "📄 Markdown"); `None` writes
+    /// silently, for supporting files such as the avatar image
+    pub label: Option<String>,
+    pub contents: Vec<u8>,
+}
+
+impl OutputFile {
+    /// A report file, announced with `label` once written
+    pub fn report(label: &str, name: String, contents: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: Some(name),
+            label: Some(label.to_string()),
+            contents: contents.into(),
+        }
+    }
+
+    /// Output printed to the terminal
+    pub fn stdout(contents: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: None,
+            label: None,
+            contents: contents.into(),
+        }
+    }
+}
+
+/// A report format.
+pub trait ReportRenderer {
+    /// Name of the format in `--formats` (e.g. `md`)
+    fn format(&self) -> &'static str;
+
+    /// Renders the report of `stats`.
+    fn render(&self, stats: &Stats) -> Result<Vec<OutputFile>>;
+}
+
+/// What built-in renderers share for one report.
+#[derive(Clone, Copy)]
+pub struct ReportContext<'a> {
+    pub options: &'a RenderOptions,
+    /// Avatar images: the Markdown report shows the account's, the HTML
+    /// report also those of the top rooms
+    pub avatars: &'a Avatars,
+    /// Appended to file names (e.g. `-live`, `-room-1`)
+    pub suffix: &'a str,
+}
+
+impl ReportContext<'_> {
+    /// File name of the `format` report of `stats`, e.g. `my-year-2025.md`.
+    pub fn filename(&self, stats: &Stats, format: &str, extension: &str) -> String {
+        self.filename_with_suffix(stats, format, self.suffix, extension)
+    }
+
+    /// Same as [`Self::filename`], with `suffix` instead of the context's.
+    fn filename_with_suffix(
+        &self,
+        stats: &Stats,
+        format: &str,
+        suffix: &str,
+        extension: &str,
+    ) -> String {
+        match self.options.output_name {
+            Some(ref name) => format!("{}{}.{}", name.render(stats, format), suffix, extension),
+            None => self.with_account(stats, report_filename(stats, suffix, extension)),
+        }
+    }
+
+    /// `filename`, a default `my-...` name, with the account localpart after `my-`
+    /// when the options ask for it.
+    fn with_account(&self, stats: &Stats, filename: String) -> String {
+        if !self.options.account_in_filenames {
+            return filename;
+        }
+        match filename.strip_prefix("my-") {
+            Some(rest) => format!("my-{}-{}", localpart(&stats.account.user_id), rest),
+            None => filename,
+        }
+    }
+}
+
+/// Localpart of `user_id` as a file name part (e.g. `@alice:example.org` -> `alice`).
+fn localpart(user_id: &str) -> String {
+    let user_id = user_id.trim_start_matches('@');
+    user_id
+        .split_once(':')
+        .map_or(user_id, |(localpart, _)| localpart)
+        .replace(['/', '\\'], "_")
+}
+
+/// The built-in formats.
+pub fn builtin_renderers<'a>(ctx: ReportContext<'a>) -> Vec<Box<dyn ReportRenderer + 'a>> {
+    vec![
+        Box::new(MarkdownRenderer(ctx)),
+        Box::new(HtmlRenderer(ctx)),
+        Box::new(OrgRenderer(ctx)),
+        Box::new(DigestRenderer(ctx)),
+        Box::new(PdfRenderer(ctx)),
+        Box::new(TermRenderer(ctx)),
+        Box::new(TxtRenderer(ctx)),
+        Box::new(JsonRenderer(ctx)),
+        Box::new(BadgeRenderer(ctx)),
+        Box::new(CardRenderer(ctx)),
+    ]
+}
+
+/// File name of a report: `my-<scope>-<key><suffix>.<extension>`.
+pub fn report_filename(stats: &Stats, suffix: &str, extension: &str) -> String {
+    scope_filename(&stats.scope, suffix, extension)
+}
+
+/// File name of a report of `scope`, see [`report_filename`].
+pub fn scope_filename(scope: &Scope, suffix: &str, extension: &str) -> String {
+    match scope.kind {
+        ScopeKind::Year => format!("my-year-{}{}.{}", scope.key, suffix, extension),
+        ScopeKind::Quarter => format!("my-quarter-{}{}.{}", scope.key, suffix, extension),
+        ScopeKind::Month => format!("my-month-{}{}.{}", scope.key, suffix, extension),
+        ScopeKind::Week => format!("my-week-{}{}.{}", scope.key, suffix, extension),
+        ScopeKind::Day => format!("my-day-{}{}.{}", scope.key, suffix, extension),
+        ScopeKind::Rolling => format!("my-{}{}.{}", scope.key, suffix, extension),
+        ScopeKind::Life => format!("my-life{}.{}", suffix, extension),
+    }
+}
+
+/// Inserts `suffix` before the extension of `filename`.
+fn with_suffix(filename: &str, suffix: &str) -> String {
+    match filename.rsplit_once('.') {
+        Some((stem, extension)) => format!("{}{}.{}", stem, suffix, extension),
+        None => format!("{}{}", filename, suffix),
+    }
+}
+
+struct MarkdownRenderer<'a>(ReportContext<'a>);
+
+impl ReportRenderer for MarkdownRenderer<'_> {
+    fn format(&self) -> &'static str {
+        "md"
+    }
+
+    fn render(&self, stats: &Stats) -> Result<Vec<OutputFile>> {
+        let options = self.0.options;
+        let markdown = md::render_with_templates(
+            stats,
+            options.template_dir.as_deref(),
+            options.lang,
+            &options.link_base,
+        )?;
+        let mut files = vec![OutputFile::report(
+            "📄 Markdown",
+            self.0.filename(stats, self.format(), "md"),
+            markdown,
+        )];
+        // The report links the avatar by file name
+        if let Some(ref avatar) = self.0.avatars.account {
+            files.push(OutputFile {
+                name: Some(avatar.file_name.clone()),
+                label: None,
+                contents: avatar.bytes.clone(),
+            });
+        }
+        Ok(files)
+    }
+}
+
+struct OrgRenderer<'a>(ReportContext<'a>);
+
+impl ReportRenderer for OrgRenderer<'_> {
+    fn format(&self) -> &'static str {
+        "org"
+    }
+
+    fn render(&self, stats: &Stats) -> Result<Vec<OutputFile>> {
+        let options = self.0.options;
+        let org = org::render(
+            stats,
+            options.template_dir.as_deref(),
+            options.lang,
+            &options.link_base,
+        )?;
+        let mut files = vec![OutputFile::report(
+            "📓 Org",
+            self.0.filename(stats, self.format(), "org"),
+            org,
+        )];
+        // Like the Markdown report, links the avatar by file name
+        if let Some(ref avatar) = self.0.avatars.account {
+            files.push(OutputFile {
+                name: Some(avatar.file_name.clone()),
+                label: None,
+                contents: avatar.bytes.clone(),
+            });
+        }
+        Ok(files)
+    }
+}
+
+struct HtmlRenderer<'a>(ReportContext<'a>);
+
+impl ReportRenderer for HtmlRenderer<'_> {
+    fn format(&self) -> &'static str {
+        "html"
+    }
+
+    fn render(&self, stats: &Stats) -> Result<Vec<OutputFile>> {
+        let options = self.0.options;
+        let mut html = html::render(
+            stats,
+            options.lang,
+            options.theme,
+            self.0.avatars,
+            &options.link_base,
+        )?;
+        if let Some(seconds) = options.live.filter(|&seconds| seconds > 0) {
+            html = html::with_auto_refresh(&html, seconds);
+        }
+        if let Some(warning) = html::budget_warning(&html, options.html_budget_kb) {
+            eprintln!("⚠️  Warning: {}", warning);
+        }
+
+        let filename = self.0.filename(stats, self.format(), "html");
+        let mut files = Vec::new();
+        for compression in &options.compress {
+            let compressed = compression.compress(html.as_bytes())?;
+            files.push(OutputFile::report(
+                &format!(
+                    "🗜️  Compressed ({} KB)",
+                    (compressed.len() as u64).div_ceil(1024)
+                ),
+                format!("{}.{}", filename, compression.extension()),
+                compressed,
+            ));
+        }
+        files.insert(0, OutputFile::report("🌐 HTML", filename, html));
+        Ok(files)
+    }
+}
+
+struct DigestRenderer<'a>(ReportContext<'a>);
+
+impl ReportRenderer for DigestRenderer<'_> {
+    fn format(&self) -> &'static str {
+        "digest"
+    }
+
+    fn render(&self, stats: &Stats) -> Result<Vec<OutputFile>> {
+        let lang = self.0.options.lang;
+        let suffix = format!("{}-digest", self.0.suffix);
+        Ok(vec![
+            OutputFile::report(
+                "🗒️  Digest",
+                self.0
+                    .filename_with_suffix(stats, self.format(), &suffix, "md"),
+                digest::render(stats, lang)?,
+            ),
+            OutputFile::report(
+                "🗒️  Digest (text)",
+                self.0
+                    .filename_with_suffix(stats, self.format(), &suffix, "txt"),
+                digest::render_text(stats, lang)?,
+            ),
+        ])
+    }
+}
+
+struct PdfRenderer<'a>(ReportContext<'a>);
+
+impl ReportRenderer for PdfRenderer<'_> {
+    fn format(&self) -> &'static str {
+        "pdf"
+    }
+
+    fn render(&self, stats: &Stats) -> Result<Vec<OutputFile>> {
+        let (pdf, warning) = pdf::render(stats, self.0.options.lang)?;
+        if let Some(warning) = warning {
+            eprintln!("⚠️  Warning: {}", warning);
+        }
+        Ok(vec![OutputFile::report(
+            "📕 PDF",
+            self.0.filename(stats, self.format(), "pdf"),
+            pdf,
+        )])
+    }
+}
+
+struct TermRenderer<'a>(ReportContext<'a>);
+
+impl ReportRenderer for TermRenderer<'_> {
+    fn format(&self) -> &'static str {
+        "term"
+    }
+
+    fn render(&self, stats: &Stats) -> Result<Vec<OutputFile>> {
+        let report = term::render(stats, self.0.options.lang, term::use_color())?;
+        Ok(vec![OutputFile::stdout(report)])
+    }
+}
+
+struct TxtRenderer<'a>(ReportContext<'a>);
+
+impl ReportRenderer for TxtRenderer<'_> {
+    fn format(&self) -> &'static str {
+        "txt"
+    }
+
+    fn render(&self, stats: &Stats) -> Result<Vec<OutputFile>> {
+        Ok(vec![OutputFile::report(
+            "📃 Text",
+            self.0.filename(stats, self.format(), "txt"),
+            txt::render(stats, self.0.options.lang)?,
+        )])
+    }
+}
+
+struct JsonRenderer<'a>(ReportContext<'a>);
+
+impl ReportRenderer for JsonRenderer<'_> {
+    fn format(&self) -> &'static str {
+        "json"
+    }
+
+    fn render(&self, stats: &Stats) -> Result<Vec<OutputFile>> {
+        let json = json::render(stats)?;
+        Ok(vec![OutputFile::report(
+            "🧾 JSON",
+            self.0.filename(stats, self.format(), "json"),
+            json,
+        )])
+    }
+}
+
+struct BadgeRenderer<'a>(ReportContext<'a>);
+
+impl ReportRenderer for BadgeRenderer<'_> {
+    fn format(&self) -> &'static str {
+        "badge"
+    }
+
+    fn render(&self, stats: &Stats) -> Result<Vec<OutputFile>> {
+        let metric = BadgeMetric::MessagesSent;
+        let svg = badge::render(stats, metric)?;
+        let filename = match self.0.options.output_name {
+            Some(_) => self.0.filename_with_suffix(
+                stats,
+                self.format(),
+                &format!("-{}{}", metric.name(), self.0.suffix),
+                "svg",
+            ),
+            None => self.0.with_account(
+                stats,
+                with_suffix(&badge::filename(stats, metric), self.0.suffix),
+            ),
+        };
+        Ok(vec![OutputFile::report("🏷️  Badge", filename, svg)])
+    }
+}
+
+struct CardRenderer<'a>(ReportContext<'a>);
+
+impl ReportRenderer for CardRenderer<'_> {
+    fn format(&self) -> &'static str {
+        "card"
+    }
+
+    fn render(&self, stats: &Stats) -> Result<Vec<OutputFile>> {
+        let card = card::render(stats, self.0.options.card_theme)?;
+        Ok(vec![OutputFile::report(
+            "🖼️  Card",
+            self.0.filename(stats, self.format(), "png"),
+            card,
+        )])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::render::common::example_stats;
+    use crate::commands::render::output_name::OutputName;
+    use crate::commands::render::Avatar;
+
+    #[test]
+    fn test_builtin_renderers() {
+        let options = RenderOptions::default();
+        let avatars = Avatars {
+            account: Some(Avatar {
+                file_name: "avatar-abc.png".to_string(),
+                bytes: vec![1, 2, 3],
+            }),
+            ..Avatars::default()
+        };
+        let renderers = builtin_renderers(ReportContext {
+            options: &options,
+            avatars: &avatars,
+            suffix: "-live",
+        });
+
+        let mut formats: Vec<&str> = renderers.iter().map(|r| r.format()).collect();
+        formats.sort();
+        formats.dedup();
+        assert_eq!(formats.len(), renderers.len());
+
+        let md = renderers.iter().find(|r| r.format() == "md").unwrap();
+        let files = md.render(&example_stats()).unwrap();
+        let names: Vec<_> = files.iter().map(|file| file.name.as_deref()).collect();
+        // The avatar keeps its name, the report links it
+        assert_eq!(
+            names,
+            [Some("my-year-2025-live.md"), Some("avatar-abc.png")]
+        );
+        assert!(files[1].label.is_none());
+
+        assert_eq!(
+            with_suffix("my-year-2025-messages.svg", "-room-1"),
+            "my-year-2025-messages-room-1.svg"
+        );
+    }
+
+    #[test]
+    fn test_output_name() {
+        let options = RenderOptions {
+            output_name: Some(OutputName::parse("{user}-{key}").unwrap()),
+            ..RenderOptions::default()
+        };
+        let avatars = Avatars::default();
+        let renderers = builtin_renderers(ReportContext {
+            options: &options,
+            avatars: &avatars,
+            suffix: "-room-1",
+        });
+        let names = |format: &str| -> Vec<String> {
+            let renderer = renderers.iter().find(|r| r.format() == format).unwrap();
+            renderer
+                .render(&example_stats())
+                .unwrap()
+                .into_iter()
+                .filter_map(|file| file.name)
+                .collect()
+        };
+
+        assert_eq!(names("json"), ["alice_example.org-2025-room-1.json"]);
+        assert_eq!(
+            names("digest"),
+            [
+                "alice_example.org-2025-room-1-digest.md",
+                "alice_example.org-2025-room-1-digest.txt"
+            ]
+        );
+        assert_eq!(
+            names("badge"),
+            ["alice_example.org-2025-messages_sent-room-1.svg"]
+        );
+    }
+
+    #[test]
+    fn test_account_in_filenames() {
+        let options = RenderOptions {
+            account_in_filenames: true,
+            ..RenderOptions::default()
+        };
+        let avatars = Avatars::default();
+        let renderers = builtin_renderers(ReportContext {
+            options: &options,
+            avatars: &avatars,
+            suffix: "",
+        });
+        let names = |format: &str| -> Vec<String> {
+            let renderer = renderers.iter().find(|r| r.format() == format).unwrap();
+            renderer
+                .render(&example_stats())
+                .unwrap()
+                .into_iter()
+                .filter_map(|file| file.name)
+                .collect()
+        };
+
+        assert_eq!(names("json"), ["my-alice-year-2025.json"]);
+        assert_eq!(names("badge"), ["my-alice-year-2025-messages_sent.svg"]);
+        assert_eq!(localpart("@bob/x:example.org"), "bob_x");
+    }
+}
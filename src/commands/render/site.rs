@@ -0,0 +1,407 @@
+//! Static site of several reports (`my render --site`).
+//!
+//! Renders one HTML page per window and an `index.html` listing them: life to
+//! date first, then each year with its months, weeks and days. Every page
+//! starts with the same navigation bar: the index, the enclosing window and the
+//! previous and next reports of the same kind.
+
+use super::common::{scope_label, Avatars};
+use super::html::{self, escape, link};
+use super::i18n::{fill, Locale};
+use super::renderer::{scope_filename, OutputFile};
+use super::RenderOptions;
+use crate::stats::{Scope, ScopeKind, Stats};
+use crate::window::WindowScope;
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// File name of the entry page of the site.
+pub const INDEX_FILE: &str = "index.html";
+
+/// A report of the site: stats prepared for rendering, with their avatars.
+pub struct SiteReport {
+    pub stats: Stats,
+    pub avatars: Avatars,
+}
+
+/// Renders a page per report and the index linking them all.
+pub fn render(reports: &[SiteReport], options: &RenderOptions) -> Result<Vec<OutputFile>> {
+    if reports.is_empty() {
+        anyhow::bail!("No stats to render: the site needs at least one stats file");
+    }
+    let t = options.lang.locale();
+
+    let mut reports: Vec<&SiteReport> = reports.iter().collect();
+    reports.sort_by_key(|report| sort_key(&report.stats.scope));
+    if let Some(pair) = reports
+        .windows(2)
+        .find(|pair| same_window(&pair[0].stats.scope, &pair[1].stats.scope))
+    {
+        anyhow::bail!(
+            "Several stats files cover the window {}: keep one per window",
+            pair[0].stats.scope.key
+        );
+    }
+    let scopes: Vec<&Scope> = reports.iter().map(|report| &report.stats.scope).collect();
+
+    let mut files = Vec::new();
+    for (i, report) in reports.iter().enumerate() {
+        let html = html::render(
+            &report.stats,
+            options.lang,
+            options.theme,
+            &report.avatars,
+            &options.link_base,
+        )?;
+        let html = html::with_navigation(&html, &navigation(&scopes, i, t));
+        if let Some(warning) = html::budget_warning(&html, options.html_budget_kb) {
+            eprintln!("⚠️  Warning: {}: {}", report.stats.scope.key, warning);
+        }
+        files.push(OutputFile::report(
+            "🌐 HTML",
+            page_file(&report.stats.scope),
+            html,
+        ));
+    }
+
+    let stats: Vec<&Stats> = reports.iter().map(|report| &report.stats).collect();
+    files.push(OutputFile::report(
+        "🗂️  Index",
+        INDEX_FILE.to_string(),
+        render_index(&stats, options, t),
+    ));
+    Ok(files)
+}
+
+/// File name of the page of a window.
+fn page_file(scope: &Scope) -> String {
+    scope_filename(scope, "", "html")
+}
+
+/// Orders life first, then years, quarters, months, weeks, days and rolling
+/// windows, each by start date.
+fn sort_key(scope: &Scope) -> (u8, Option<NaiveDate>, String) {
+    let from = WindowScope::parse(&scope.key)
+        .ok()
+        .map(|window| window.from);
+    (rank(scope.kind), from, scope.key.clone())
+}
+
+/// Depth of a kind of window, from life (0) to days, then rolling windows.
+fn rank(kind: ScopeKind) -> u8 {
+    match kind {
+        ScopeKind::Life => 0,
+        ScopeKind::Year => 1,
+        ScopeKind::Quarter => 2,
+        ScopeKind::Month => 3,
+        ScopeKind::Week => 4,
+        ScopeKind::Day => 5,
+        ScopeKind::Rolling => 6,
+    }
+}
+
+fn same_window(a: &Scope, b: &Scope) -> bool {
+    if a.kind != b.kind {
+        return false;
+    }
+    match (WindowScope::parse(&a.key), WindowScope::parse(&b.key)) {
+        _ if a.kind == ScopeKind::Life => true,
+        (Ok(a), Ok(b)) => a.same_dates(&b),
+        _ => a.key == b.key,
+    }
+}
+
+/// Year a window belongs to in the index (ISO year for weeks).
+fn year_of(scope: &Scope) -> Option<&str> {
+    match scope.kind {
+        ScopeKind::Life | ScopeKind::Rolling => None,
+        _ => scope.key.split('-').next(),
+    }
+}
+
+/// Whether the window `parent` contains `child`: life contains every other
+/// window, a year its quarters, months, ISO weeks and days, a quarter its
+/// months and days, and a month its days.
+fn encloses(parent: &Scope, child: &Scope) -> bool {
+    if parent.kind == ScopeKind::Life {
+        return child.kind != ScopeKind::Life;
+    }
+    let (Ok(parent_window), Ok(child_window)) = (
+        WindowScope::parse(&parent.key),
+        WindowScope::parse(&child.key),
+    ) else {
+        return false;
+    };
+    let windows = match (parent.kind, child.kind) {
+        (ScopeKind::Year | ScopeKind::Quarter, ScopeKind::Month)
+        | (ScopeKind::Month, ScopeKind::Day) => parent_window.sub_windows(),
+        (ScopeKind::Year, ScopeKind::Quarter) => parent_window.quarters(),
+        (ScopeKind::Year, ScopeKind::Week) => parent_window.weeks(),
+        (ScopeKind::Year | ScopeKind::Quarter, ScopeKind::Day) => parent_window.days(),
+        _ => return false,
+    };
+    windows
+        .iter()
+        .any(|window| window.same_dates(&child_window))
+}
+
+/// Links of the navigation bar of the page `scopes[index]`; `scopes` are sorted by [`sort_key`].
+fn navigation(scopes: &[&Scope], index: usize, t: &Locale) -> Vec<(String, String)> {
+    let scope = scopes[index];
+    let mut links = vec![(format!("🗂️ {}", t.all_reports), INDEX_FILE.to_string())];
+
+    // The nearest enclosing window: a day's month before its quarter and its year
+    let parent = scopes
+        .iter()
+        .filter(|other| encloses(other, scope))
+        .max_by_key(|other| rank(other.kind));
+    if let Some(parent) = parent {
+        links.push((format!("↑ {}", scope_label(parent, t)), page_file(parent)));
+    }
+
+    let previous = index
+        .checked_sub(1)
+        .map(|i| scopes[i])
+        .filter(|other| other.kind == scope.kind);
+    if let Some(previous) = previous {
+        links.push((
+            format!("← {}", scope_label(previous, t)),
+            page_file(previous),
+        ));
+    }
+    let next = scopes
+        .get(index + 1)
+        .filter(|other| other.kind == scope.kind);
+    if let Some(next) = next {
+        links.push((format!("{} →", scope_label(next, t)), page_file(next)));
+    }
+    links
+}
+
+/// Index page: life to date and rolling windows, then each year (most recent
+/// first) with the reports of its quarters, months, weeks and days.
+fn render_index(stats: &[&Stats], options: &RenderOptions, t: &Locale) -> String {
+    let mut output = String::new();
+    let title = match stats
+        .iter()
+        .rev()
+        .find_map(|stats| stats.account.display_name.as_deref())
+    {
+        Some(display_name) => format!("{} — {}", t.archive, display_name),
+        None => t.archive.to_string(),
+    };
+    output.push_str(&format!("<h1>🗂️ {}</h1>\n", escape(&title)));
+
+    let mut years: BTreeMap<&str, (Option<&Stats>, Vec<&Stats>)> = BTreeMap::new();
+    for &report in stats {
+        match (report.scope.kind, year_of(&report.scope)) {
+            (ScopeKind::Life, _) | (_, None) => {}
+            (ScopeKind::Year, Some(year)) => years.entry(year).or_default().0 = Some(report),
+            (_, Some(year)) => years.entry(year).or_default().1.push(report),
+        }
+    }
+
+    if let Some(life) = stats
+        .iter()
+        .find(|stats| stats.scope.kind == ScopeKind::Life)
+    {
+        output.push_str("<section id=\"life\">\n");
+        output.push_str(&format!("<h2>{}</h2>\n", index_link(life, t)));
+        output.push_str(&format!(
+            "<p>{}</p>\n",
+            escape(&t.message_count(life.summary.messages_sent.get()))
+        ));
+        output.push_str("</section>\n");
+    }
+
+    // Rolling windows belong to no year: each gets a section, as life does
+    for rolling in stats
+        .iter()
+        .filter(|stats| stats.scope.kind == ScopeKind::Rolling)
+    {
+        output.push_str(&format!(
+            "<section id=\"{}\">\n",
+            escape(&rolling.scope.key)
+        ));
+        output.push_str(&format!("<h2>{}</h2>\n", index_link(rolling, t)));
+        output.push_str(&format!(
+            "<p>{}</p>\n",
+            escape(&t.message_count(rolling.summary.messages_sent.get()))
+        ));
+        output.push_str("</section>\n");
+    }
+
+    for (year, (year_stats, windows)) in years.iter().rev() {
+        output.push_str(&format!("<section id=\"year-{}\">\n", escape(year)));
+        match year_stats {
+            Some(year_stats) => {
+                output.push_str(&format!("<h2>{}</h2>\n", index_link(year_stats, t)));
+                output.push_str(&format!(
+                    "<p>{}</p>\n",
+                    escape(&t.message_count(year_stats.summary.messages_sent.get()))
+                ));
+            }
+            None => output.push_str(&format!(
+                "<h2>{}</h2>\n",
+                escape(&fill(t.scope_year, &[("key", year)]))
+            )),
+        }
+        if !windows.is_empty() {
+            output.push_str("<ul>\n");
+            for stats in windows {
+                output.push_str(&format!(
+                    "<li>{} · {}</li>\n",
+                    index_link(stats, t),
+                    escape(&t.message_count(stats.summary.messages_sent.get()))
+                ));
+            }
+            output.push_str("</ul>\n");
+        }
+        output.push_str("</section>\n");
+    }
+
+    html::document(&title, t, options.theme, &output)
+}
+
+fn index_link(stats: &Stats, t: &Locale) -> String {
+    link(&scope_label(&stats.scope, t), &page_file(&stats.scope))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(kind: ScopeKind, key: &str) -> Scope {
+        Scope {
+            kind,
+            key: key.to_string(),
+            label: None,
+        }
+    }
+
+    fn sorted(mut scopes: Vec<Scope>) -> Vec<Scope> {
+        scopes.sort_by_key(sort_key);
+        scopes
+    }
+
+    #[test]
+    fn test_sort_key_orders_by_kind_then_date() {
+        let scopes = sorted(vec![
+            scope(ScopeKind::Month, "2025-10"),
+            scope(ScopeKind::Rolling, "last-30-days"),
+            scope(ScopeKind::Year, "2025"),
+            scope(ScopeKind::Week, "2025-W9"),
+            scope(ScopeKind::Month, "2025-02"),
+            scope(ScopeKind::Life, "life"),
+            scope(ScopeKind::Week, "2025-W10"),
+            scope(ScopeKind::Year, "2024"),
+        ]);
+        let keys: Vec<&str> = scopes.iter().map(|scope| scope.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "life",
+                "2024",
+                "2025",
+                "2025-02",
+                "2025-10",
+                "2025-W9",
+                "2025-W10",
+                "last-30-days"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_navigation() {
+        let t = crate::commands::render::i18n::Lang::En.locale();
+        let scopes = sorted(vec![
+            scope(ScopeKind::Year, "2025"),
+            scope(ScopeKind::Month, "2025-02"),
+            scope(ScopeKind::Month, "2025-03"),
+            scope(ScopeKind::Month, "2025-04"),
+            scope(ScopeKind::Day, "2025-03-14"),
+            scope(ScopeKind::Quarter, "2025-Q1"),
+        ]);
+        let scopes: Vec<&Scope> = scopes.iter().collect();
+
+        let links = navigation(&scopes, 3, t);
+        let hrefs: Vec<&str> = links.iter().map(|(_, href)| href.as_str()).collect();
+        assert_eq!(
+            hrefs,
+            [
+                "index.html",
+                "my-quarter-2025-Q1.html",
+                "my-month-2025-02.html",
+                "my-month-2025-04.html"
+            ]
+        );
+        assert_eq!(links[1].0, "↑ Quarter 2025-Q1");
+        assert_eq!(links[2].0, "← Month 2025-02");
+
+        // A day goes up to its month, a quarter to its year; a year without
+        // life page has no parent
+        let links = navigation(&scopes, 5, t);
+        assert_eq!(links[1].1, "my-month-2025-03.html");
+        assert_eq!(links.len(), 2);
+        assert_eq!(navigation(&scopes, 1, t)[1].1, "my-year-2025.html");
+        assert_eq!(navigation(&scopes, 0, t).len(), 1);
+    }
+
+    #[test]
+    fn test_render_rejects_duplicate_windows() {
+        let stats: Stats =
+            serde_json::from_str(include_str!("../../../examples/stats/example-stats.json"))
+                .expect("example stats should parse");
+        let reports = [
+            SiteReport {
+                stats: stats.clone(),
+                avatars: Avatars::default(),
+            },
+            SiteReport {
+                stats,
+                avatars: Avatars::default(),
+            },
+        ];
+        let error = render(&reports, &RenderOptions::default()).unwrap_err();
+        assert!(error.to_string().contains("2025"));
+    }
+
+    #[test]
+    fn test_render_site() {
+        let year: Stats =
+            serde_json::from_str(include_str!("../../../examples/stats/example-stats.json"))
+                .expect("example stats should parse");
+        let mut month = year.clone();
+        month.scope = scope(ScopeKind::Month, "2025-03");
+        let reports = [
+            SiteReport {
+                stats: month,
+                avatars: Avatars::default(),
+            },
+            SiteReport {
+                stats: year,
+                avatars: Avatars::default(),
+            },
+        ];
+
+        let files = render(&reports, &RenderOptions::default()).expect("site should render");
+        let names: Vec<&str> = files
+            .iter()
+            .filter_map(|file| file.name.as_deref())
+            .collect();
+        assert_eq!(
+            names,
+            ["my-year-2025.html", "my-month-2025-03.html", "index.html"]
+        );
+
+        let month_page = String::from_utf8(files[1].contents.clone()).unwrap();
+        assert!(month_page.contains("<main>\n<nav><a href=\"index.html\">"));
+        assert!(month_page.contains("<a href=\"my-year-2025.html\">↑ Year 2025</a>"));
+
+        let index = String::from_utf8(files[2].contents.clone()).unwrap();
+        assert!(index.contains("<section id=\"year-2025\">"));
+        assert!(index.contains("<a href=\"my-month-2025-03.html\">Month 2025-03</a>"));
+    }
+}
@@ -0,0 +1,541 @@
+/// Terminal report renderer.
+///
+/// Prints the recap to stdout: a boxed header, sections with unicode bar
+/// charts and the fun facts. ANSI colors are only used when stdout is a
+/// terminal (and `NO_COLOR` is unset), so the output stays readable when piped
+/// to a file or another command.
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::common::{
+    bar, changelog_note, emoji_label, fun_entries, person_name, previous_deltas, report_months,
+    scope_label, scope_phrase, sparkline, typing_headline, Provenance, HOUR_AXIS, WEEKDAY_KEYS,
+};
+use super::i18n::{fill, Lang, Locale};
+use crate::stats::*;
+
+/// Display width of the report, in columns.
+const WIDTH: usize = 64;
+/// Width of the longest bar, in columns.
+const BAR_WIDTH: usize = 28;
+/// Room names longer than this are truncated.
+const NAME_WIDTH: usize = 24;
+
+/// Whether stdout can show colors.
+pub fn use_color() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Renders the report for a terminal, with ANSI colors when `color` is set.
+pub fn render(stats: &Stats, lang: Lang, color: bool) -> Result<String> {
+    let t = lang.locale();
+    let style = Style { color };
+    let mut output = String::new();
+
+    render_header(&mut output, stats, t, style);
+    render_summary(&mut output, stats, t, style);
+    if let Some(ref rooms) = stats.rooms {
+        render_rooms(&mut output, rooms, t, style);
+    }
+    if let Some(ref people) = stats.people {
+        render_people(&mut output, people, t, style);
+    }
+    if let Some(ref dms) = stats.dms {
+        render_dms(&mut output, dms, t, style);
+    }
+    if let Some(ref conversations) = stats.conversations {
+        render_conversations(&mut output, conversations, t, style);
+    }
+    if let Some(ref lengths) = stats.message_lengths {
+        render_message_lengths(&mut output, lengths, t, style);
+    }
+    if let Some(ref typed) = stats.typed_emojis {
+        render_typed_emojis(&mut output, typed, t, style);
+    }
+    if let Some(ref reactions) = stats.reactions {
+        render_reactions(&mut output, reactions, &stats.scope, t, style);
+    }
+    if let Some(ref activity) = stats.activity {
+        render_activity(&mut output, activity, &stats.scope, t, style);
+    }
+    if let Some(ref fun) = stats.fun {
+        render_fun(&mut output, fun, t, style);
+    }
+    render_footer(&mut output, stats, t, style);
+
+    Ok(output)
+}
+
+/// ANSI styling, or plain text when colors are off.
+#[derive(Clone, Copy)]
+struct Style {
+    color: bool,
+}
+
+impl Style {
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn bold(&self, text: &str) -> String {
+        self.paint("1", text)
+    }
+
+    fn dim(&self, text: &str) -> String {
+        self.paint("2", text)
+    }
+
+    fn accent(&self, text: &str) -> String {
+        self.paint("32", text)
+    }
+}
+
+fn render_header(output: &mut String, stats: &Stats, t: &Locale, style: Style) {
+    let title = format!(
+        "🎉 {}",
+        fill(t.title, &[("scope", &scope_label(&stats.scope, t))])
+    );
+    let account = match stats.account.display_name {
+        Some(ref name) => format!("{} ({})", name, stats.account.user_id),
+        None => stats.account.user_id.clone(),
+    };
+    let inner = WIDTH - 4;
+
+    output.push_str(&style.accent(&format!("╭{}╮", "─".repeat(WIDTH - 2))));
+    output.push('\n');
+    let mut lines = vec![
+        (truncate(&title, inner), true),
+        (truncate(&account, inner), false),
+    ];
+    if let Some(ref timezone) = stats.timezone {
+        let timezone = format!("{}: {}", t.timezone, timezone);
+        lines.push((truncate(&timezone, inner), false));
+    }
+    for (line, styled) in lines {
+        let padding = " ".repeat(inner - line.width());
+        let text = if styled {
+            style.bold(&line)
+        } else {
+            style.dim(&line)
+        };
+        output.push_str(&format!(
+            "{} {}{} {}\n",
+            style.accent("│"),
+            text,
+            padding,
+            style.accent("│")
+        ));
+    }
+    output.push_str(&style.accent(&format!("╰{}╯", "─".repeat(WIDTH - 2))));
+    output.push('\n');
+}
+
+fn render_summary(output: &mut String, stats: &Stats, t: &Locale, style: Style) {
+    render_heading(output, &format!("📊 {}", t.summary), style);
+    let summary = &stats.summary;
+    let deltas = previous_deltas(stats, t);
+    let mut rows = vec![(
+        t.messages_sent,
+        t.number(summary.messages_sent.get()),
+        deltas.messages_sent,
+    )];
+    if let Some(days) = stats.coverage.days_active {
+        rows.push((t.active_days, t.number(days.get()), deltas.active_days));
+    }
+    for (label, count) in [
+        (t.dm_rooms, summary.dm_rooms),
+        (t.public_rooms, summary.public_rooms),
+        (t.private_rooms, summary.private_rooms),
+    ] {
+        if let Some(count) = count {
+            rows.push((label, t.number(count.get()), None));
+        }
+    }
+
+    let label_width = rows.iter().map(|(label, _, _)| label.width()).max();
+    for (label, value, delta) in &rows {
+        let delta = delta
+            .as_ref()
+            .map(|delta| format!("  {}", style.dim(delta)))
+            .unwrap_or_default();
+        output.push_str(&format!(
+            "  {}  {}{}\n",
+            pad(label, label_width.unwrap_or(0)),
+            style.bold(value),
+            delta
+        ));
+    }
+    let phrase = scope_phrase(&stats.scope, t);
+    if !phrase.is_empty() {
+        output.push_str(&format!(
+            "  {}\n",
+            style.dim(&fill(t.sections_refer_to, &[("scope", &phrase)]))
+        ));
+    }
+}
+
+fn render_rooms(output: &mut String, rooms: &Rooms, t: &Locale, style: Style) {
+    let bars: Vec<(String, i32)> = rooms
+        .top
+        .iter()
+        .flatten()
+        .map(|room| {
+            let name = room.name.as_deref().unwrap_or(t.unnamed_room);
+            (truncate(name, NAME_WIDTH), room.messages.get())
+        })
+        .collect();
+    if !bars.is_empty() {
+        render_heading(output, &format!("💬 {}", t.most_active_rooms), style);
+        render_bars(output, &bars, t, style);
+    }
+
+    let bars: Vec<(String, i32)> = rooms
+        .by_server
+        .iter()
+        .flatten()
+        .map(|entry| (truncate(&entry.server, NAME_WIDTH), entry.messages.get()))
+        .collect();
+    if !bars.is_empty() {
+        render_heading(output, &format!("🌐 {}", t.homeservers), style);
+        render_bars(output, &bars, t, style);
+    }
+}
+
+fn render_people(output: &mut String, people: &People, t: &Locale, style: Style) {
+    for (heading, ranked) in [
+        (format!("🫂 {}", t.talked_with_most), &people.dm_partners),
+        (format!("👥 {}", t.most_seen_senders), &people.group_senders),
+    ] {
+        let bars: Vec<(String, i32)> = ranked
+            .iter()
+            .flatten()
+            .map(|person| (truncate(person_name(person), NAME_WIDTH), person.messages))
+            .collect();
+        if !bars.is_empty() {
+            render_heading(output, &heading, style);
+            render_bars(output, &bars, t, style);
+        }
+    }
+}
+
+fn render_dms(output: &mut String, dms: &Dms, t: &Locale, style: Style) {
+    let bars: Vec<(String, i32)> = dms
+        .top
+        .iter()
+        .map(|person| (truncate(person_name(person), NAME_WIDTH), person.messages))
+        .collect();
+    if !bars.is_empty() {
+        render_heading(output, &format!("✉️ {}", t.wrote_to_most), style);
+        render_bars(output, &bars, t, style);
+    }
+}
+
+fn render_conversations(
+    output: &mut String,
+    conversations: &Conversations,
+    t: &Locale,
+    style: Style,
+) {
+    render_heading(output, &format!("🧵 {}", t.conversations), style);
+    let bars = vec![
+        (t.main_timeline.to_string(), conversations.top_level),
+        (t.in_threads.to_string(), conversations.in_threads),
+        (t.replies.to_string(), conversations.replies),
+    ];
+    render_bars(output, &bars, t, style);
+}
+
+fn render_message_lengths(output: &mut String, lengths: &MessageLengths, t: &Locale, style: Style) {
+    render_heading(output, &format!("✍️ {}", t.message_lengths), style);
+    let bars = vec![
+        (t.short_messages.to_string(), lengths.short),
+        (t.medium_messages.to_string(), lengths.medium),
+        (t.long_messages.to_string(), lengths.long),
+    ];
+    render_bars(output, &bars, t, style);
+}
+
+fn render_typed_emojis(output: &mut String, typed: &TypedEmojis, t: &Locale, style: Style) {
+    let bars: Vec<(String, i32)> = typed
+        .top
+        .iter()
+        .map(|entry| (emoji_label(entry, t), entry.count))
+        .collect();
+    if !bars.is_empty() {
+        render_heading(output, &format!("⌨️ {}", t.typed_emojis), style);
+        render_bars(output, &bars, t, style);
+    }
+}
+
+fn render_reactions(
+    output: &mut String,
+    reactions: &Reactions,
+    scope: &Scope,
+    t: &Locale,
+    style: Style,
+) {
+    let bars: Vec<(String, i32)> = reactions
+        .top_emojis
+        .iter()
+        .flatten()
+        .map(|entry| (emoji_label(entry, t), entry.count))
+        .collect();
+    if !bars.is_empty() {
+        render_heading(output, &format!("😊 {}", t.top_reactions), style);
+        render_bars(output, &bars, t, style);
+    }
+
+    let bars: Vec<(String, i32)> = reactions
+        .given
+        .iter()
+        .flat_map(|given| &given.top_emojis)
+        .map(|entry| (emoji_label(entry, t), entry.count))
+        .collect();
+    if !bars.is_empty() {
+        render_heading(output, &format!("🫶 {}", t.reactions_given), style);
+        render_bars(output, &bars, t, style);
+    }
+
+    let bars: Vec<(String, i32)> = reactions
+        .top_rooms
+        .iter()
+        .flatten()
+        .map(|entry| {
+            let name = entry.name.as_deref().unwrap_or(t.unnamed_room);
+            (truncate(name, NAME_WIDTH), entry.reaction_count)
+        })
+        .collect();
+    if !bars.is_empty() {
+        render_heading(output, &format!("💖 {}", t.most_loved_rooms), style);
+        render_bars(output, &bars, t, style);
+    }
+
+    let months = report_months(scope);
+    if !months.is_empty() {
+        if let Some(ref by_month) = reactions.by_month {
+            let bars = month_bars(by_month, &months, t);
+            render_heading(output, &format!("📆 {}", t.reactions_by_month), style);
+            render_bars(output, &bars, t, style);
+        }
+    }
+}
+
+fn render_activity(
+    output: &mut String,
+    activity: &Activity,
+    scope: &Scope,
+    t: &Locale,
+    style: Style,
+) {
+    render_heading(output, &format!("📈 {}", t.activity), style);
+
+    // By month for year/life and the months of a quarter, by day for a month
+    let months = report_months(scope);
+    if !months.is_empty() {
+        if let Some(ref by_month) = activity.by_month {
+            let bars = month_bars(by_month, &months, t);
+            render_subheading(output, &format!("📆 {}", t.by_month), style);
+            render_bars(output, &bars, t, style);
+        }
+    }
+    if matches!(scope.kind, ScopeKind::Month) {
+        if let Some(ref by_day) = activity.by_day {
+            let bars = keyed_bars(by_day, (1..=31).map(|day| format!("{:02}", day)));
+            render_subheading(output, &format!("📅 {}", t.by_day), style);
+            render_bars(output, &bars, t, style);
+        }
+    }
+
+    if let Some(ref by_weekday) = activity.by_weekday {
+        let bars = WEEKDAY_KEYS
+            .iter()
+            .zip(t.weekdays_short)
+            .map(|(key, label)| {
+                let count = by_weekday.get(*key).copied().unwrap_or(0);
+                (label.to_string(), count)
+            })
+            .collect::<Vec<_>>();
+        render_subheading(output, &format!("📅 {}", t.by_weekday), style);
+        render_bars(output, &bars, t, style);
+    }
+
+    if let Some(ref by_hour) = activity.by_hour {
+        let counts: Vec<i32> = keyed_bars(by_hour, (0..24).map(|hour| format!("{:02}", hour)))
+            .into_iter()
+            .map(|(_, count)| count)
+            .collect();
+        render_subheading(output, &format!("🕐 {}", t.by_hour), style);
+        output.push_str(&format!("  {}\n", style.accent(&sparkline(&counts))));
+        output.push_str(&format!("  {}\n", style.dim(HOUR_AXIS)));
+    }
+}
+
+fn render_fun(output: &mut String, fun: &Fun, t: &Locale, style: Style) {
+    let entries = fun_entries(fun, t);
+    let headline = typing_headline(fun, t);
+    if entries.is_empty() && headline.is_none() {
+        return;
+    }
+    render_heading(output, &format!("🎪 {}", t.fun_facts), style);
+    if let Some(headline) = headline {
+        output.push_str(&format!("  ⌨️  {}\n", style.bold(&headline)));
+    }
+    for entry in entries {
+        match entry.label {
+            Some(label) => output.push_str(&format!(
+                "  {} {} {}\n",
+                entry.emoji,
+                style.dim(&format!("{}:", label)),
+                entry.value
+            )),
+            None => output.push_str(&format!("  {} {}\n", entry.emoji, entry.value)),
+        }
+    }
+}
+
+/// Provenance line, wrapped between its " · " parts to fit the report width.
+fn render_footer(output: &mut String, stats: &Stats, t: &Locale, style: Style) {
+    let line = Provenance::of(stats).line(t);
+    let mut current = String::new();
+    let mut lines = Vec::new();
+    for part in line.split(" · ") {
+        if !current.is_empty() && current.width() + part.width() + 3 > WIDTH - 2 {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str(" · ");
+        }
+        current.push_str(part);
+    }
+    lines.push(current);
+
+    lines.extend(changelog_note(stats, t));
+
+    output.push('\n');
+    for line in lines {
+        output.push_str(&format!("  {}\n", style.dim(&line)));
+    }
+}
+
+/// Section heading as a rule: "── 📊 Summary ──────".
+fn render_heading(output: &mut String, title: &str, style: Style) {
+    let rule = WIDTH.saturating_sub(title.width() + 4);
+    output.push('\n');
+    output.push_str(&format!(
+        "{} {} {}\n",
+        style.accent("──"),
+        style.bold(title),
+        style.accent(&"─".repeat(rule))
+    ));
+}
+
+fn render_subheading(output: &mut String, title: &str, style: Style) {
+    output.push_str(&format!("\n  {}\n", style.bold(title)));
+}
+
+/// Labeled horizontal bars, scaled to the largest count.
+fn render_bars(output: &mut String, bars: &[(String, i32)], t: &Locale, style: Style) {
+    let max = bars.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    let label_width = bars.iter().map(|(label, _)| label.width()).max();
+    for (label, count) in bars {
+        output.push_str(&format!(
+            "  {}  {} {}\n",
+            pad(label, label_width.unwrap_or(0)),
+            style.accent(&bar(*count, max, BAR_WIDTH)),
+            style.dim(&t.number(*count))
+        ));
+    }
+}
+
+/// Counts for the given keys, in order, with missing keys as zero.
+fn keyed_bars(
+    map: &HashMap<String, i32>,
+    keys: impl Iterator<Item = String>,
+) -> Vec<(String, i32)> {
+    keys.map(|key| {
+        let count = map.get(&key).copied().unwrap_or(0);
+        (key, count)
+    })
+    .collect()
+}
+
+/// Bars of `by_month` over `months` (1 to 12), labelled with short month names.
+fn month_bars(by_month: &HashMap<String, i32>, months: &[u32], t: &Locale) -> Vec<(String, i32)> {
+    keyed_bars(by_month, months.iter().map(|month| format!("{:02}", month)))
+        .into_iter()
+        .zip(months)
+        .map(|((_, count), &month)| (t.month_short(month).to_string(), count))
+        .collect()
+}
+
+/// Pads to a display width (wide characters count as two columns).
+fn pad(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())))
+}
+
+/// Truncates to a display width, ending with "…" when shortened.
+fn truncate(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    let mut width = 0;
+    for ch in text.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if width + ch_width + 1 > max_width {
+            break;
+        }
+        truncated.push(ch);
+        width += ch_width;
+    }
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::render::common::example_stats;
+
+    #[test]
+    fn test_render_plain_and_colored() {
+        let stats = example_stats();
+
+        let plain = render(&stats, Lang::En, false).unwrap();
+        assert!(!plain.contains('\x1b'));
+        assert!(plain.starts_with("╭─"));
+        assert!(plain.contains("── 📊 Summary ─"));
+        assert!(plain.contains("Messages sent  4,832\n"));
+        assert!(plain.contains("🎪 Fun Facts"));
+        // The header box lines up despite the emoji
+        let box_lines: Vec<&str> = plain.lines().take(4).collect();
+        assert!(box_lines.iter().all(|line| line.width() == WIDTH));
+
+        let colored = render(&stats, Lang::En, true).unwrap();
+        assert!(colored.contains("\x1b[1m4,832\x1b[0m"));
+
+        assert!(plain.ends_with(
+            "  Generated 2025-12-20 by my 0.1.0 · window 2025\n  data from 2025-01-02 to 2025-12-19 · @alice:example.org\n"
+        ));
+
+        let fr = render(&stats, Lang::Fr, false).unwrap();
+        assert!(fr.contains("── 📊 Résumé ─"));
+    }
+
+    #[test]
+    fn test_bars() {
+        assert_eq!(bar(10, 10, 4), "████");
+        assert_eq!(bar(5, 10, 4), "██");
+        assert_eq!(bar(1, 100, 4), "▏");
+        assert_eq!(bar(0, 100, 4), "");
+        assert_eq!(sparkline(&[0, 1, 4, 8]), " ▂▅█");
+        assert_eq!(truncate("Rust programming language", 10), "Rust prog…");
+        assert_eq!(pad("日本", 6), "日本  ");
+    }
+}
@@ -0,0 +1,149 @@
+/// HTML report themes.
+///
+/// A theme is a palette of CSS custom properties; the stylesheet only refers to
+/// the variables. Adding a theme means adding an entry to [`PALETTES`].
+use anyhow::{Context, Result};
+
+/// Colors of an HTML report, as CSS values.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub name: &'static str,
+    pub bg: &'static str,
+    pub card: &'static str,
+    pub text: &'static str,
+    pub muted: &'static str,
+    pub accent: &'static str,
+    pub border: &'static str,
+    /// Calendar heatmap levels 1 to 3 (level 0 uses `border`)
+    pub heat: [&'static str; 3],
+}
+
+pub const LIGHT: Palette = Palette {
+    name: "light",
+    bg: "#f6f7fb",
+    card: "#ffffff",
+    text: "#1f2330",
+    muted: "#6b7185",
+    accent: "#097f5d",
+    border: "#e3e6ef",
+    heat: ["#9be9c8", "#3fcf9f", "#08855f"],
+};
+
+pub const DARK: Palette = Palette {
+    name: "dark",
+    bg: "#11131a",
+    card: "#1c1f2a",
+    text: "#eceef4",
+    muted: "#9a9fb0",
+    accent: "#2ad9a3",
+    border: "#2c3040",
+    heat: ["#0f5e46", "#16a37a", "#45e6b4"],
+};
+
+/// Built-in palettes, selectable by name with `--theme`.
+pub const PALETTES: &[Palette] = &[
+    LIGHT,
+    DARK,
+    Palette {
+        name: "ocean",
+        bg: "#eef5fb",
+        card: "#ffffff",
+        text: "#15283a",
+        muted: "#5b7087",
+        accent: "#1c6eca",
+        border: "#d6e3f0",
+        heat: ["#a9cdf5", "#5a9ff0", "#1659b0"],
+    },
+    Palette {
+        name: "sunset",
+        bg: "#fdf4ee",
+        card: "#ffffff",
+        text: "#35201a",
+        muted: "#85655a",
+        accent: "#b84f30",
+        border: "#f1dfd4",
+        heat: ["#f7c2a4", "#ef8a5c", "#c2461f"],
+    },
+];
+
+/// Theme of the HTML report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HtmlTheme {
+    /// Light, or dark when the viewer prefers a dark color scheme
+    #[default]
+    Auto,
+    Fixed(&'static Palette),
+}
+
+impl HtmlTheme {
+    /// Parses a theme name (`auto` or one of [`PALETTES`]).
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim().to_ascii_lowercase();
+        if input == "auto" {
+            return Ok(HtmlTheme::Auto);
+        }
+        PALETTES
+            .iter()
+            .find(|palette| palette.name == input)
+            .map(HtmlTheme::Fixed)
+            .with_context(|| {
+                let names: Vec<&str> = PALETTES.iter().map(|p| p.name).collect();
+                format!(
+                    "Unknown theme '{}' (expected auto or one of: {})",
+                    input,
+                    names.join(", ")
+                )
+            })
+    }
+
+    /// CSS declaring the theme's custom properties.
+    pub fn css(&self) -> String {
+        match self {
+            HtmlTheme::Auto => format!(
+                "{}@media (prefers-color-scheme: dark) {{ {} }}\n",
+                variables(&LIGHT),
+                variables(&DARK).trim_end()
+            ),
+            HtmlTheme::Fixed(palette) => variables(palette),
+        }
+    }
+}
+
+fn variables(palette: &Palette) -> String {
+    format!(
+        ":root {{ --bg: {}; --card: {}; --text: {}; --muted: {}; --accent: {}; --border: {}; --heat1: {}; --heat2: {}; --heat3: {}; }}\n",
+        palette.bg,
+        palette.card,
+        palette.text,
+        palette.muted,
+        palette.accent,
+        palette.border,
+        palette.heat[0],
+        palette.heat[1],
+        palette.heat[2]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_theme() {
+        assert_eq!(HtmlTheme::parse("auto").unwrap(), HtmlTheme::Auto);
+        assert_eq!(HtmlTheme::parse("Dark").unwrap(), HtmlTheme::Fixed(&DARK));
+        let err = HtmlTheme::parse("neon").unwrap_err().to_string();
+        assert!(err.contains("auto or one of: light, dark, ocean, sunset"));
+    }
+
+    #[test]
+    fn test_theme_css() {
+        let auto = HtmlTheme::Auto.css();
+        assert!(auto.starts_with(":root { --bg: #f6f7fb;"));
+        assert!(auto.contains("@media (prefers-color-scheme: dark) { :root { --bg: #11131a;"));
+
+        let ocean = HtmlTheme::parse("ocean").unwrap().css();
+        assert!(ocean.contains("--accent: #1c6eca;"));
+        assert!(!ocean.contains("@media"));
+    }
+}
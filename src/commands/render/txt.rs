@@ -0,0 +1,609 @@
+/// Plain text report renderer.
+///
+/// The report as labeled lines of text: no tables, bars, box drawing or
+/// decorative emoji, so that it reads well through a screen reader and pipes
+/// cleanly into mail bodies, pagers or other tools. Each section starts with
+/// its title on a line of its own, followed by `Label: value` lines and
+/// numbered rankings. Reaction emojis are kept: they are the data.
+use anyhow::Result;
+use std::collections::HashMap;
+
+use super::common::{
+    changelog_note, characters, created_rooms_context, dm_sent_share, emoji_label, favorite_emoji,
+    fun_entries, length_share, person_name, previous_deltas, report_months, room_trend,
+    scope_label, scope_phrase, thread_share, trend_note, typing_headline, Provenance, WEEKDAY_KEYS,
+};
+use super::i18n::{fill, Lang, Locale};
+use crate::stats::*;
+
+/// Renders the report as plain text.
+pub fn render(stats: &Stats, lang: Lang) -> Result<String> {
+    let t = lang.locale();
+    let mut sections = Vec::new();
+
+    sections.push(render_header(stats, t));
+    sections.push(render_summary(stats, t));
+    if let Some(ref rooms) = stats.rooms {
+        sections.push(render_rooms(rooms, stats.summary.messages_sent.get(), t));
+    }
+    if let Some(ref people) = stats.people {
+        sections.push(render_people(people, t));
+    }
+    if let Some(ref dms) = stats.dms {
+        sections.push(render_dms(dms, t));
+    }
+    if let Some(ref conversations) = stats.conversations {
+        sections.push(render_conversations(conversations, t));
+    }
+    if let Some(ref lengths) = stats.message_lengths {
+        sections.push(render_message_lengths(lengths, t));
+    }
+    if let Some(ref typed) = stats.typed_emojis {
+        sections.push(render_typed_emojis(typed, t));
+    }
+    if let Some(ref created_rooms) = stats.created_rooms {
+        sections.push(render_created_rooms(created_rooms, &stats.scope, t));
+    }
+    if let Some(ref reactions) = stats.reactions {
+        let delta = previous_deltas(stats, t).reactions;
+        sections.push(render_reactions(
+            reactions,
+            &stats.scope,
+            delta.as_deref(),
+            t,
+        ));
+    }
+    if let Some(ref activity) = stats.activity {
+        sections.push(render_activity(activity, &stats.summary, &stats.scope, t));
+    }
+    if let Some(ref fun) = stats.fun {
+        sections.push(render_fun(fun, t));
+    }
+    sections.push(
+        std::iter::once(Provenance::of(stats).line(t))
+            .chain(changelog_note(stats, t))
+            .collect(),
+    );
+
+    let sections: Vec<String> = sections
+        .into_iter()
+        .filter(|lines| !lines.is_empty())
+        .map(|lines| lines.join("\n"))
+        .collect();
+    Ok(format!("{}\n", sections.join("\n\n")))
+}
+
+fn render_header(stats: &Stats, t: &Locale) -> Vec<String> {
+    let title = fill(t.title, &[("scope", &scope_label(&stats.scope, t))]);
+    let mut lines = vec![title];
+    lines.push(format!("{}: {}", t.user_id, stats.account.user_id));
+    if let Some(ref name) = stats.account.display_name {
+        lines.push(format!("{}: {}", t.display_name, name));
+    }
+    if let Some(ref timezone) = stats.timezone {
+        lines.push(format!("{}: {}", t.timezone, timezone));
+    }
+    lines.push(format!(
+        "{}: {}",
+        t.total_joined_rooms,
+        t.number(stats.account.rooms_total.get())
+    ));
+    lines
+}
+
+fn render_summary(stats: &Stats, t: &Locale) -> Vec<String> {
+    let summary = &stats.summary;
+    let deltas = previous_deltas(stats, t);
+    let mut lines = vec![t.summary.to_string()];
+    lines.push(with_delta(
+        format!(
+            "{}: {}",
+            t.messages_sent,
+            t.number(summary.messages_sent.get())
+        ),
+        deltas.messages_sent.as_deref(),
+    ));
+    if let Some(days) = stats.coverage.days_active {
+        lines.push(with_delta(
+            format!("{}: {}", t.active_days, t.number(days.get())),
+            deltas.active_days.as_deref(),
+        ));
+    }
+    for (label, count) in [
+        (t.dm_rooms, summary.dm_rooms),
+        (t.public_rooms, summary.public_rooms),
+        (t.private_rooms, summary.private_rooms),
+    ] {
+        if let Some(count) = count {
+            lines.push(format!("{}: {}", label, t.number(count.get())));
+        }
+    }
+    let phrase = scope_phrase(&stats.scope, t);
+    if !phrase.is_empty() {
+        lines.push(fill(t.sections_refer_to, &[("scope", &phrase)]));
+    }
+    lines
+}
+
+fn render_rooms(rooms: &Rooms, messages_sent: i32, t: &Locale) -> Vec<String> {
+    let mut lines = vec![t.rooms.to_string()];
+    lines.push(fill(
+        t.rooms_sentence,
+        &[
+            ("messages", &t.number(messages_sent)),
+            ("rooms", &t.number(rooms.total.get())),
+        ],
+    ));
+
+    let top = rooms.top.as_deref().unwrap_or_default();
+    if !top.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("{}:", t.most_active_rooms));
+        for (i, room) in top.iter().enumerate() {
+            let mut line = format!(
+                "{}. {}: {}",
+                i + 1,
+                room.name.as_deref().unwrap_or(t.unnamed_room),
+                t.message_count(room.messages.get())
+            );
+            if let Some((arrow, _)) = room_trend(room, t) {
+                line.push_str(&format!(" {}", arrow));
+            }
+            if let Some(percentage) = room.percentage {
+                line.push_str(&format!(" ({})", t.percent(percentage.get() / 100.0, 1)));
+            }
+            lines.push(line);
+        }
+        if let Some(note) = trend_note(top, t) {
+            lines.push(note);
+        }
+    }
+
+    let by_server = rooms.by_server.as_deref().unwrap_or_default();
+    if !by_server.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("{}:", t.homeservers));
+        for (i, entry) in by_server.iter().enumerate() {
+            lines.push(format!(
+                "{}. {}: {} ({})",
+                i + 1,
+                entry.server,
+                t.message_count(entry.messages.get()),
+                t.percent(entry.percentage.get() / 100.0, 1)
+            ));
+        }
+    }
+
+    let dms = rooms.dm_balance.as_deref().unwrap_or_default();
+    if !dms.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("{}:", t.conversation_balance));
+        for (i, dm) in dms.iter().enumerate() {
+            lines.push(format!(
+                "{}. {} ({}: {}, {}: {}, {}: {})",
+                i + 1,
+                dm.name.as_deref().unwrap_or(t.unnamed_room),
+                t.sent,
+                t.number(dm.sent),
+                t.received,
+                t.number(dm.received),
+                t.your_share,
+                dm_sent_share(dm, t)
+            ));
+        }
+    }
+    lines
+}
+
+fn render_people(people: &People, t: &Locale) -> Vec<String> {
+    let mut lines = vec![t.people.to_string()];
+    if let Some(person) = people.dm_partners.iter().flatten().next() {
+        lines.push(fill(
+            t.people_sentence,
+            &[
+                ("name", person_name(person)),
+                ("messages", &t.message_count(person.messages)),
+            ],
+        ));
+    }
+
+    for (heading, ranked) in [
+        (t.talked_with_most, &people.dm_partners),
+        (t.most_seen_senders, &people.group_senders),
+    ] {
+        let ranked = ranked.as_deref().unwrap_or_default();
+        if ranked.is_empty() {
+            continue;
+        }
+        lines.push(String::new());
+        lines.push(format!("{}:", heading));
+        for (i, person) in ranked.iter().enumerate() {
+            lines.push(person_line(i + 1, person, t));
+        }
+    }
+    lines
+}
+
+fn render_dms(dms: &Dms, t: &Locale) -> Vec<String> {
+    let mut lines = vec![t.dms.to_string()];
+    if let Some(person) = dms.top.first() {
+        lines.push(fill(
+            t.dms_sentence,
+            &[
+                ("name", person_name(person)),
+                ("messages", &t.message_count(person.messages)),
+            ],
+        ));
+    }
+    lines.push(format!("{}: {}", t.dm_correspondents, t.number(dms.total)));
+
+    if !dms.top.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("{}:", t.wrote_to_most));
+        for (i, person) in dms.top.iter().enumerate() {
+            lines.push(person_line(i + 1, person, t));
+        }
+    }
+    lines
+}
+
+fn person_line(rank: usize, person: &PersonEntry, t: &Locale) -> String {
+    // The user ID tells apart people with the same display name
+    let mut line = match (&person.display_name, &person.user_id) {
+        (Some(name), Some(user_id)) => format!("{}. {} ({})", rank, name, user_id),
+        _ => format!("{}. {}", rank, person_name(person)),
+    };
+    line.push_str(&format!(": {}", t.message_count(person.messages)));
+    if let Some(rooms) = person.rooms {
+        line.push_str(&format!(" ({}: {})", t.shared_rooms, t.number(rooms)));
+    }
+    line
+}
+
+fn render_conversations(conversations: &Conversations, t: &Locale) -> Vec<String> {
+    let mut lines = vec![t.conversations.to_string()];
+    lines.push(fill(
+        t.conversations_sentence,
+        &[
+            ("share", &thread_share(conversations, t)),
+            ("replies", &t.number(conversations.replies)),
+        ],
+    ));
+    for (label, count) in [
+        (t.main_timeline, conversations.top_level),
+        (t.in_threads, conversations.in_threads),
+        (t.threads_posted_in, conversations.threads),
+        (t.replies, conversations.replies),
+    ] {
+        lines.push(format!("{}: {}", label, t.number(count)));
+    }
+    lines
+}
+
+fn render_message_lengths(lengths: &MessageLengths, t: &Locale) -> Vec<String> {
+    let mut lines = vec![t.message_lengths.to_string()];
+    lines.push(fill(
+        t.message_lengths_sentence,
+        &[
+            ("short", &length_share(lengths, lengths.short, t)),
+            ("long", &length_share(lengths, lengths.long, t)),
+        ],
+    ));
+    for (label, count) in [
+        (t.short_messages, lengths.short),
+        (t.medium_messages, lengths.medium),
+        (t.long_messages, lengths.long),
+    ] {
+        lines.push(format!("{}: {}", label, t.number(count)));
+    }
+    if let Some(ref longest) = lengths.longest {
+        lines.push(format!(
+            "{}: {} ({})",
+            t.longest_message,
+            characters(longest.chars, t),
+            longest.permalink
+        ));
+    }
+    lines
+}
+
+fn render_typed_emojis(typed: &TypedEmojis, t: &Locale) -> Vec<String> {
+    let mut lines = vec![t.typed_emojis.to_string()];
+    if let Some(favorite) = typed.top.first() {
+        lines.push(fill(
+            t.typed_emojis_sentence,
+            &[
+                ("count", &t.number(typed.total)),
+                ("emoji", &emoji_label(favorite, t)),
+            ],
+        ));
+    }
+    for (i, entry) in typed.top.iter().enumerate() {
+        lines.push(format!(
+            "{}. {}: {}",
+            i + 1,
+            emoji_label(entry, t),
+            t.number(entry.count)
+        ));
+    }
+    lines
+}
+
+fn render_created_rooms(created_rooms: &CreatedRooms, scope: &Scope, t: &Locale) -> Vec<String> {
+    let mut lines = vec![t.created_rooms.to_string()];
+    lines.push(fill(
+        t.created_sentence,
+        &[
+            ("count", &t.number(created_rooms.total.get())),
+            ("when", &created_rooms_context(scope, t)),
+        ],
+    ));
+    for (label, count) in [
+        (t.dm_rooms, created_rooms.dm_rooms),
+        (t.public_rooms, created_rooms.public_rooms),
+        (t.private_rooms, created_rooms.private_rooms),
+    ] {
+        if let Some(count) = count {
+            lines.push(format!("{}: {}", label, t.number(count.get())));
+        }
+    }
+    lines
+}
+
+fn render_reactions(
+    reactions: &Reactions,
+    scope: &Scope,
+    delta: Option<&str>,
+    t: &Locale,
+) -> Vec<String> {
+    let mut lines = vec![t.reactions.to_string()];
+    if let Some(total) = reactions.total {
+        lines.push(with_delta(
+            fill(t.reactions_sentence, &[("count", &t.number(total))]),
+            delta,
+        ));
+    }
+
+    let top_emojis = reactions.top_emojis.as_deref().unwrap_or_default();
+    if !top_emojis.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("{}:", t.top_reactions));
+        for (i, entry) in top_emojis.iter().enumerate() {
+            lines.push(format!(
+                "{}. {}: {}",
+                i + 1,
+                emoji_label(entry, t),
+                t.number(entry.count)
+            ));
+        }
+    }
+
+    if let Some(ref given) = reactions.given {
+        lines.push(String::new());
+        lines.push(format!("{}:", t.reactions_given));
+        lines.push(fill(
+            t.reactions_given_sentence,
+            &[
+                ("count", &t.number(given.total)),
+                ("emoji", &favorite_emoji(&given.top_emojis, t)),
+            ],
+        ));
+        for (i, entry) in given.top_emojis.iter().enumerate() {
+            lines.push(format!(
+                "{}. {}: {}",
+                i + 1,
+                emoji_label(entry, t),
+                t.number(entry.count)
+            ));
+        }
+    }
+
+    let top_messages = reactions.top_messages.as_deref().unwrap_or_default();
+    if !top_messages.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("{}:", t.most_reacted_messages));
+        for (i, entry) in top_messages.iter().enumerate() {
+            let message = match entry.preview {
+                Some(ref preview) => format!("\"{}\" ({})", preview, entry.permalink),
+                None => entry.permalink.clone(),
+            };
+            lines.push(format!(
+                "{}. {}: {}",
+                i + 1,
+                message,
+                t.number(entry.reaction_count)
+            ));
+        }
+    }
+
+    let top_rooms = reactions.top_rooms.as_deref().unwrap_or_default();
+    if !top_rooms.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("{}:", t.most_loved_rooms));
+        for (i, entry) in top_rooms.iter().enumerate() {
+            lines.push(format!(
+                "{}. {}: {}",
+                i + 1,
+                entry.name.as_deref().unwrap_or(t.unnamed_room),
+                t.number(entry.reaction_count)
+            ));
+        }
+    }
+
+    let months = report_months(scope);
+    if !months.is_empty() {
+        if let Some(ref by_month) = reactions.by_month {
+            lines.push(String::new());
+            lines.push(format!("{}:", t.reactions_by_month));
+            lines.extend(month_lines(by_month, &months, t));
+        }
+    }
+    lines
+}
+
+fn render_activity(
+    activity: &Activity,
+    summary: &Summary,
+    scope: &Scope,
+    t: &Locale,
+) -> Vec<String> {
+    let mut lines = vec![t.activity.to_string()];
+
+    if let Some(ref peaks) = summary.peaks {
+        let mut push_peak = |label: &str, when: &str, messages: i32| {
+            lines.push(format!(
+                "{}: {} ({})",
+                label,
+                when,
+                t.message_count(messages)
+            ));
+        };
+        if let Some(ref year) = peaks.year {
+            push_peak(t.peak_year, &year.year, year.messages);
+        }
+        if let Some(ref month) = peaks.month {
+            push_peak(t.peak_month, &t.stats_month(&month.month), month.messages);
+        }
+        if let Some(ref week) = peaks.week {
+            push_peak(t.peak_week, &week.week, week.messages);
+        }
+        if let Some(ref day) = peaks.day {
+            push_peak(t.peak_day, &day.day, day.messages);
+        }
+        if let Some(ref hour) = peaks.hour {
+            let hour_str = hour.hour.to_string();
+            let when = match hour.date {
+                Some(ref date) => fill(t.hour_on, &[("hour", &hour_str), ("date", date)]),
+                None => fill(t.hour_of_day, &[("hour", &hour_str)]),
+            };
+            push_peak(t.peak_hour, &when, hour.messages);
+        }
+    }
+
+    // By month for year/life and the months of a quarter, by day for a month
+    let months = report_months(scope);
+    if !months.is_empty() {
+        if let Some(ref by_month) = activity.by_month {
+            lines.push(String::new());
+            lines.push(format!("{}:", t.by_month));
+            lines.extend(month_lines(by_month, &months, t));
+        }
+    }
+    if matches!(scope.kind, ScopeKind::Month) {
+        if let Some(ref by_day) = activity.by_day {
+            lines.push(String::new());
+            lines.push(format!("{}:", t.by_day));
+            for day in 1..=31 {
+                if let Some(count) = by_day.get(&format!("{:02}", day)) {
+                    lines.push(format!("{}: {}", day, t.number(*count)));
+                }
+            }
+        }
+    }
+
+    if let Some(ref by_weekday) = activity.by_weekday {
+        lines.push(String::new());
+        lines.push(format!("{}:", t.by_weekday));
+        for (key, name) in WEEKDAY_KEYS.iter().zip(t.weekdays_long) {
+            let count = by_weekday.get(*key).copied().unwrap_or(0);
+            lines.push(format!("{}: {}", name, t.number(count)));
+        }
+    }
+
+    if let Some(ref by_hour) = activity.by_hour {
+        lines.push(String::new());
+        lines.push(format!("{}:", t.by_hour));
+        for hour in 0..24 {
+            let count = by_hour.get(&format!("{:02}", hour)).copied().unwrap_or(0);
+            let label = fill(t.hour_of_day, &[("hour", &hour.to_string())]);
+            lines.push(format!("{}: {}", label, t.number(count)));
+        }
+    }
+    lines
+}
+
+fn render_fun(fun: &Fun, t: &Locale) -> Vec<String> {
+    let entries = fun_entries(fun, t);
+    let headline = typing_headline(fun, t);
+    if entries.is_empty() && headline.is_none() {
+        return Vec::new();
+    }
+    let mut lines = vec![t.fun_facts.to_string()];
+    if let Some(headline) = headline {
+        lines.push(without_emoji(&headline).to_string());
+    }
+    for entry in entries {
+        match entry.label {
+            Some(label) => lines.push(format!("{}: {}", label, entry.value)),
+            None => lines.push(entry.value),
+        }
+    }
+    lines
+}
+
+/// One line per month of `months` (1 to 12), named in full, with missing months as zero.
+fn month_lines(by_month: &HashMap<String, i32>, months: &[u32], t: &Locale) -> Vec<String> {
+    months
+        .iter()
+        .map(|&month| {
+            let count = by_month.get(&format!("{:02}", month)).copied().unwrap_or(0);
+            format!("{}: {}", t.month_long(month), t.number(count))
+        })
+        .collect()
+}
+
+/// Appends the change against the previous window, e.g. "Messages sent: 4,832 (▲ 18% vs 2024)".
+fn with_delta(line: String, delta: Option<&str>) -> String {
+    match delta {
+        Some(delta) => format!("{} ({})", line, delta),
+        None => line,
+    }
+}
+
+/// Drops the emoji some locale sentences start with (e.g. "⌨️ You spent…").
+fn without_emoji(text: &str) -> &str {
+    text.trim_start_matches(|ch: char| !ch.is_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::render::common::example_stats;
+
+    #[test]
+    fn test_render_labeled_sections() {
+        let text = render(&example_stats(), Lang::En).unwrap();
+
+        assert!(text.starts_with("Your Matrix Year 2025\nUser ID: @alice:example.org\n"));
+        assert!(text.contains("\nTimezone: Europe/Paris\nTotal joined rooms: 27\n"));
+        assert!(text.contains("\n\nSummary\nMessages sent: 4,832\n"));
+        assert!(text.contains("\nJanuary: "));
+        assert!(text.contains("\nMonday: "));
+        assert!(text.contains("\n\nFun Facts\n"));
+        assert!(text.contains("\n1. Bob (Sent: 580, Received: 372, Your share: 61%)\n"));
+        assert!(text.contains("\n1. Erin (@erin:example.org): 1,320 messages (Rooms: 3)\n"));
+        assert!(text.contains("\n3. @grace:example.org: 610 messages (Rooms: 1)\n"));
+        assert!(text.ends_with(
+            "Generated 2025-12-20 by my 0.1.0 · window 2025 · data from 2025-01-02 to 2025-12-19 · @alice:example.org\n"
+        ));
+        // Nothing but words and numbers: no tables, bars or decorative emoji
+        // (emoji only appear as data, e.g. the ones typed or reacted with)
+        for decoration in ['|', '█', '▁', '─', '📊', '⌨'] {
+            assert!(!text.contains(decoration), "{:?}", decoration);
+        }
+        assert!(!text.lines().any(|line| line.starts_with('🎉')));
+    }
+
+    #[test]
+    fn test_render_localized() {
+        let text = render(&example_stats(), Lang::Fr).unwrap();
+        assert!(text.contains("\n\nRésumé\n"));
+        assert!(text.contains("\njanvier: "));
+    }
+
+    #[test]
+    fn test_without_emoji() {
+        assert_eq!(without_emoji("⌨️ You spent 3 hours"), "You spent 3 hours");
+        assert_eq!(without_emoji("42 rooms"), "42 rooms");
+    }
+}
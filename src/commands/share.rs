@@ -0,0 +1,89 @@
+/// Post a recap into a Matrix room.
+///
+/// Sends the digest of saved stats with the logged-in session, as a formatted
+/// message: the Markdown digest as plain body and its HTML as
+/// `org.matrix.custom.html` body, so clients without formatting still read it.
+use anyhow::{Context, Result};
+use matrix_sdk::ruma::events::room::message::RoomMessageEventContent;
+use matrix_sdk::ruma::{OwnedRoomId, RoomAliasId, RoomId};
+use matrix_sdk::{Client, RoomState};
+
+use crate::commands::crawl::discovery::fetch_room_list;
+use crate::commands::crawl::progress::CrawlLog;
+use crate::commands::export::load_account_stats;
+use crate::commands::render::digest;
+use crate::commands::render::i18n::Lang;
+use crate::commands::render::links::LinkBase;
+
+/// Share the recap of a previously crawled window into `room` (ID or alias).
+///
+/// Shows the message and asks before sending, unless `yes` is set.
+pub async fn run(
+    room: &str,
+    window: &str,
+    user_id_flag: Option<String>,
+    lang: Lang,
+    link_base: &LinkBase,
+    yes: bool,
+) -> Result<()> {
+    let (account_id, account_dir, mut stats) = load_account_stats(window, user_id_flag)?;
+    link_base.apply(&mut stats);
+    let body = digest::render(&stats, lang)?;
+    let html_body = digest::render_matrix_html(&stats, lang)?;
+
+    eprintln!("{}", body);
+    if !yes {
+        let confirmed = inquire::Confirm::new(&format!("Send this recap to {}?", room))
+            .with_default(true)
+            .prompt()?;
+        if !confirmed {
+            eprintln!("Not sent");
+            return Ok(());
+        }
+    }
+
+    let client = crate::sdk::restore_client_for_account(&account_dir, &account_id)
+        .await
+        .context("Failed to restore client")?;
+    let room_id = resolve_room_id(&client, room).await?;
+
+    // Rooms are only known locally once synced: discover them if needed
+    let joined = match client.get_room(&room_id) {
+        Some(joined) => Some(joined),
+        None => {
+            fetch_room_list(&client, &CrawlLog::new(&account_id)).await?;
+            client.get_room(&room_id)
+        }
+    };
+    let Some(joined) = joined.filter(|joined| joined.state() == RoomState::Joined) else {
+        anyhow::bail!("{} has not joined {}: join it first", account_id, room);
+    };
+
+    let content = RoomMessageEventContent::text_html(body, html_body);
+    let response = joined
+        .send(content)
+        .await
+        .context("Failed to send the recap")?;
+    eprintln!("✅ Recap sent to {} ({})", room, response.event_id);
+    Ok(())
+}
+
+/// Room ID of a room ID or alias (e.g. #friends:example.org).
+pub(crate) async fn resolve_room_id(client: &Client, room: &str) -> Result<OwnedRoomId> {
+    if room.starts_with('#') {
+        let alias =
+            RoomAliasId::parse(room).with_context(|| format!("Invalid room alias: {}", room))?;
+        let response = client
+            .resolve_room_alias(&alias)
+            .await
+            .with_context(|| format!("Failed to resolve room alias {}", room))?;
+        Ok(response.room_id)
+    } else {
+        RoomId::parse(room).with_context(|| {
+            format!(
+                "Invalid room: {} (expected an ID like !abc:example.org or an alias like #room:example.org)",
+                room
+            )
+        })
+    }
+}
@@ -1,8 +1,12 @@
 use crate::account_selector::AccountSelector;
 use crate::commands::crawl::db;
+use crate::commands::crawl::decision::{has_new_events, window_coverage_gap, CoverageGap};
+use crate::commands::crawl::progress::completeness_marker;
+use crate::commands::crawl::types::{RoomInfo, RoomJoinState};
 use crate::commands::login::{account_id_to_dirname, resolve_data_root};
 use crate::sdk::restore_client_for_account;
 use crate::timefmt::format_timestamp;
+use crate::window::WindowScope;
 use anyhow::{Context, Result};
 use matrix_sdk::Client;
 use std::collections::HashMap;
@@ -17,7 +21,7 @@ use unicode_width::UnicodeWidthStr;
 /// - `⠧` for in-progress
 /// - `✗` for error
 /// - `?` for unknown/null status (should not occur in normal usage)
-fn get_status_symbol(metadata: &db::RoomCrawlMetadata) -> &'static str {
+pub(crate) fn get_status_symbol(metadata: &db::RoomCrawlMetadata) -> &'static str {
     use db::CrawlStatus;
 
     match &metadata.last_crawl_status {
@@ -41,7 +45,7 @@ fn get_status_symbol(metadata: &db::RoomCrawlMetadata) -> &'static str {
 ///
 /// # Returns
 /// HashMap mapping room_id strings to display names (defaults to room_id if unavailable)
-async fn get_room_names(
+pub(crate) async fn get_room_names(
     client: &Client,
     rooms_metadata: &[db::RoomCrawlMetadata],
 ) -> HashMap<String, String> {
@@ -52,12 +56,7 @@ async fn get_room_names(
         match metadata.room_id.as_str().try_into() {
             Ok(room_id) => {
                 if let Some(room) = client.get_room(room_id) {
-                    let name = room
-                        .display_name()
-                        .await
-                        .ok()
-                        .map(|n| n.to_string())
-                        .unwrap_or_else(|| metadata.room_id.clone());
+                    let name = crate::commands::crawl::resolve_room_name(&room).await;
                     room_names.insert(metadata.room_id.clone(), name);
                 } else {
                     room_names.insert(metadata.room_id.clone(), metadata.room_id.clone());
@@ -97,6 +96,16 @@ pub async fn list_rooms(account_id: &str) -> Result<()> {
         return Ok(());
     }
 
+    // Latest events seen by the last discovery, to flag rooms with newer events
+    let room_list = db
+        .get_room_list()
+        .context("Failed to read the cached room list")?;
+    let discovered: HashMap<&str, &RoomInfo> = room_list
+        .iter()
+        .flat_map(|list| &list.rooms)
+        .map(|room| (room.room_id.as_str(), room))
+        .collect();
+
     // Build room names map in a scoped block to ensure client is dropped before printing
     let room_names = {
         // Restore client session to get room names
@@ -113,7 +122,14 @@ pub async fn list_rooms(account_id: &str) -> Result<()> {
         names
     };
 
-    eprintln!("Rooms for {}:\n", account_id);
+    eprintln!("Rooms for {}:", account_id);
+    match &room_list {
+        Some(list) => eprintln!(
+            "(🆕 new events at the last discovery, {})\n",
+            format_timestamp(list.discovered_ts)
+        ),
+        None => eprintln!(),
+    }
 
     // Print each room with its status
     for metadata in rooms {
@@ -125,7 +141,11 @@ pub async fn list_rooms(account_id: &str) -> Result<()> {
 
         // Format room info with proper alignment
         let truncated_name = truncate_middle(room_name, 40);
-        let creation_marker = if metadata.fully_crawled { " 💯" } else { "" };
+        let creation_marker = completeness_marker(metadata.fully_crawled, metadata.history_limited);
+        let new_marker = match discovered.get(metadata.room_id.as_str()) {
+            Some(room) if has_new_events(Some(&metadata), room) => " 🆕",
+            _ => "",
+        };
 
         if let Some(oldest) = metadata.oldest_event_ts {
             let oldest_str = crate::timefmt::format_timestamp_opt(Some(oldest));
@@ -138,23 +158,97 @@ pub async fn list_rooms(account_id: &str) -> Result<()> {
                 String::new()
             };
 
+            // History visibility starts at the user's join: say so
+            let joined_str = match metadata.joined_at_ts {
+                Some(joined) if metadata.history_limited => {
+                    format!(" since you joined {}", crate::timefmt::format_date(joined))
+                }
+                _ => String::new(),
+            };
+
             eprintln!(
-                "  {} {} {:>5} events from {}{}{}",
+                "  {} {} {:>5} events from {}{}{}{}{}",
                 status_symbol,
                 truncated_name,
                 metadata.total_events_fetched,
                 &oldest_short,
                 user_events_str,
-                creation_marker
+                creation_marker,
+                joined_str,
+                new_marker
             );
         } else {
-            eprintln!("  {} {}", status_symbol, truncated_name);
+            eprintln!("  {} {}{}", status_symbol, truncated_name, new_marker);
         }
     }
 
     Ok(())
 }
 
+/// Prints whether the crawl database covers a window for an account, from the
+/// room list cached by the latest discovery.
+///
+/// Returns whether the window can be rendered without crawling first.
+fn print_window_coverage(db: &db::CrawlDb, window_scope: &WindowScope) -> Result<bool> {
+    let window = &window_scope.key;
+    let Some(room_list) = db
+        .get_room_list()
+        .context("Failed to read the cached room list")?
+    else {
+        println!(
+            "  Coverage of {}: ✗ no room list yet, run 'my crawl' once",
+            window
+        );
+        return Ok(false);
+    };
+
+    let (window_start_ts, window_end_ts) = window_scope.to_timestamp_range();
+    let mut joined = 0;
+    let mut gaps: Vec<CoverageGap> = Vec::new();
+    for room in &room_list.rooms {
+        if room.join_state != RoomJoinState::Joined {
+            continue;
+        }
+        joined += 1;
+        if let Some(gap) = window_coverage_gap(db, room, window_start_ts, window_end_ts)? {
+            gaps.push(gap);
+        }
+    }
+
+    if gaps.is_empty() {
+        println!(
+            "  Coverage of {}: ✓ all {} joined rooms crawled (room list from {})",
+            window,
+            joined,
+            format_timestamp(room_list.discovered_ts)
+        );
+        return Ok(true);
+    }
+
+    println!(
+        "  Coverage of {}: ✗ {} of {} joined rooms not covered (room list from {})",
+        window,
+        gaps.len(),
+        joined,
+        format_timestamp(room_list.discovered_ts)
+    );
+    for (gap, label) in [
+        (CoverageGap::NeverCrawled, "never crawled"),
+        (
+            CoverageGap::CrawlUnfinished,
+            "last crawl failed or interrupted",
+        ),
+        (CoverageGap::MissingOlderEvents, "missing older events"),
+        (CoverageGap::MissingNewerEvents, "missing newer events"),
+    ] {
+        let count = gaps.iter().filter(|g| **g == gap).count();
+        if count > 0 {
+            println!("    {} {}", count, label);
+        }
+    }
+    Ok(false)
+}
+
 /// Truncates a string to a maximum display width with middle ellipsis if needed.
 ///
 /// Uses Unicode display width (columns) rather than character count, accounting for
@@ -236,6 +330,50 @@ pub struct AccountStatus {
     pub db_passphrase_exists: bool,
     pub access_token_exists: bool,
     pub cross_signing_status: String,
+    pub key_backup_status: String,
+    /// Whether encrypted history should be readable during the next crawl
+    pub encrypted_history_status: String,
+}
+
+/// Server-side key backup, as seen from the `matrix-year-cli` device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyBackup {
+    /// Enabled on this device: room keys can be downloaded while crawling
+    Enabled,
+    /// A backup exists on the server but this device can't use it
+    NotEnabled,
+    /// No backup on the server
+    Missing,
+    /// The homeserver could not be asked
+    Unreachable,
+}
+
+impl KeyBackup {
+    fn describe(&self) -> &'static str {
+        match self {
+            KeyBackup::Enabled => "✓ Enabled",
+            KeyBackup::NotEnabled => "⚠ On the server, not enabled on this device",
+            KeyBackup::Missing => "✗ No backup on the server",
+            KeyBackup::Unreachable => "⚠ Unable to reach the key backup",
+        }
+    }
+}
+
+/// Cross-signing and key backup state of an account's device.
+struct EncryptionStatus {
+    cross_signing: String,
+    key_backup: String,
+    encrypted_history: String,
+}
+
+impl EncryptionStatus {
+    fn unavailable(reason: &str) -> Self {
+        Self {
+            cross_signing: reason.to_string(),
+            key_backup: reason.to_string(),
+            encrypted_history: "⚠ Unknown".to_string(),
+        }
+    }
 }
 
 /// Check the complete status of an account (files, credentials, verification).
@@ -247,18 +385,22 @@ pub async fn check_account_status(account_dir: &Path, account_id: &str) -> Resul
     let session_exists = session_path.exists();
     let credentials_exists = cred_path.exists();
 
-    let (db_passphrase_exists, access_token_exists, cross_signing_status) =
+    let (db_passphrase_exists, access_token_exists, encryption) =
         if let Ok(secrets_store) = crate::secrets::AccountSecretsStore::new(account_id) {
             let db = secrets_store.get_db_passphrase().is_some();
             let access = secrets_store.get_access_token().is_some();
 
-            let xsign_status = check_cross_signing_status(account_dir, account_id, &secrets_store)
+            let encryption = check_encryption_status(account_dir, account_id)
                 .await
-                .unwrap_or_else(|_| "⚠ Unable to check".to_string());
+                .unwrap_or_else(|_| EncryptionStatus::unavailable("⚠ Unable to check"));
 
-            (db, access, xsign_status)
+            (db, access, encryption)
         } else {
-            (false, false, "⚠ Unable to check".to_string())
+            (
+                false,
+                false,
+                EncryptionStatus::unavailable("⚠ Unable to check"),
+            )
         };
 
     Ok(AccountStatus {
@@ -266,33 +408,16 @@ pub async fn check_account_status(account_dir: &Path, account_id: &str) -> Resul
         credentials_exists,
         db_passphrase_exists,
         access_token_exists,
-        cross_signing_status,
+        cross_signing_status: encryption.cross_signing,
+        key_backup_status: encryption.key_backup,
+        encrypted_history_status: encryption.encrypted_history,
     })
 }
 
 /// Check the verification state of an account's cross-signing setup.
 pub async fn check_verification_state(
-    account_dir: &std::path::Path,
-    account_id: &str,
+    client: &Client,
 ) -> Result<matrix_sdk::encryption::VerificationState> {
-    // Check if the SDK database exists
-    let sdk_store_dir = account_dir.join("sdk");
-    if !sdk_store_dir.exists() {
-        anyhow::bail!("SDK database not initialized");
-    }
-
-    // Try to check cross-signing by restoring session
-    let session_path = account_dir.join("meta/session.json");
-    if !session_path.exists() {
-        anyhow::bail!("Session metadata not found");
-    }
-
-    // Restore the client for this account
-    let client = crate::sdk::restore_client_for_account(account_dir, account_id).await?;
-
-    // Perform minimal sync to update verification_state
-    crate::sdk::sync_encryption_state(&client).await?;
-
     // Check if cross-signing is set up on the account
     let xsign_enabled = client
         .encryption()
@@ -309,7 +434,47 @@ pub async fn check_verification_state(
     Ok(client.encryption().verification_state().get())
 }
 
-pub async fn run(user_id_flag: Option<String>, list: bool) -> Result<()> {
+/// Check whether the device can download room keys from the server-side backup.
+async fn check_key_backup(client: &Client) -> KeyBackup {
+    let backups = client.encryption().backups();
+    if backups.are_enabled().await {
+        return KeyBackup::Enabled;
+    }
+
+    match backups.fetch_exists_on_server().await {
+        Ok(true) => KeyBackup::NotEnabled,
+        Ok(false) => KeyBackup::Missing,
+        Err(_) => KeyBackup::Unreachable,
+    }
+}
+
+/// Predicts whether encrypted history will be readable during the next crawl.
+///
+/// Old room keys come from the key backup, which only a verified device gets
+/// access to; without it, only messages whose keys were shared with this device
+/// after login can be decrypted.
+fn encrypted_history_outlook(
+    verification: matrix_sdk::encryption::VerificationState,
+    backup: KeyBackup,
+) -> &'static str {
+    use matrix_sdk::encryption::VerificationState;
+
+    match (verification, backup) {
+        (VerificationState::Verified, KeyBackup::Enabled) => "✓ Readable",
+        (VerificationState::Verified, KeyBackup::Unreachable) => {
+            "⚠ Unknown (key backup unreachable)"
+        }
+        (VerificationState::Verified, _) => {
+            "⚠ Partly readable (no key backup, only keys received since login)"
+        }
+        (VerificationState::Unverified, _) => {
+            "✗ Mostly unreadable (verify this device with 'my login')"
+        }
+        (VerificationState::Unknown, _) => "⚠ Unknown (device verification unknown)",
+    }
+}
+
+pub async fn run(user_id_flag: Option<String>, list: bool, window: Option<String>) -> Result<()> {
     // If --list is requested, show room listing instead of status
     if list {
         // Select accounts (with multi-select enabled)
@@ -332,6 +497,13 @@ pub async fn run(user_id_flag: Option<String>, list: bool) -> Result<()> {
     }
 
     // Otherwise, show normal status
+    let window_scope = window
+        .as_deref()
+        .map(WindowScope::parse)
+        .transpose()
+        .context("Failed to parse window")?;
+    let mut ready = true;
+
     let mut selector = AccountSelector::new()?;
     let accounts = selector.select_accounts(user_id_flag, true)?;
 
@@ -339,6 +511,7 @@ pub async fn run(user_id_flag: Option<String>, list: bool) -> Result<()> {
         println!("\nAccount: {}", account_id);
         if !account_dir.exists() {
             println!("  [!] Account directory missing: {}", account_dir.display());
+            ready = false;
             continue;
         }
         println!("  Directory: {}", account_dir.display());
@@ -379,6 +552,8 @@ pub async fn run(user_id_flag: Option<String>, list: bool) -> Result<()> {
                     }
                 );
                 println!("  Cross-signing: {}", status.cross_signing_status);
+                println!("  Key backup: {}", status.key_backup_status);
+                println!("  Encrypted history: {}", status.encrypted_history_status);
             }
             Err(_) => {
                 // Fallback: just check files exist
@@ -413,15 +588,61 @@ pub async fn run(user_id_flag: Option<String>, list: bool) -> Result<()> {
                     }
                 }
 
-                match db.fully_crawled_room_count() {
-                    Ok(count) => {
-                        println!("  Fully crawled rooms: {}", count);
+                match db.get_room_list() {
+                    Ok(Some(list)) => {
+                        let mut joined = 0;
+                        let mut with_new_events = 0;
+                        for room in &list.rooms {
+                            if room.join_state != RoomJoinState::Joined {
+                                continue;
+                            }
+                            joined += 1;
+                            let metadata = db.get_room_metadata(&room.room_id).ok().flatten();
+                            if has_new_events(metadata.as_ref(), room) {
+                                with_new_events += 1;
+                            }
+                        }
+                        println!(
+                            "  Room list: {} joined, {} with events not crawled yet (as of {})",
+                            joined,
+                            with_new_events,
+                            format_timestamp(list.discovered_ts)
+                        );
                     }
+                    Ok(None) => {}
+                    Err(e) => {
+                        eprintln!("  Error reading cached room list: {}", e);
+                    }
+                }
+
+                match db.fully_crawled_room_count() {
+                    Ok(count) => match db.history_limited_room_count() {
+                        Ok(limited) if limited > 0 => println!(
+                            "  Fully crawled rooms: {} ({} limited by history visibility)",
+                            count, limited
+                        ),
+                        _ => println!("  Fully crawled rooms: {}", count),
+                    },
                     Err(e) => {
                         eprintln!("  Error reading fully crawled count: {}", e);
                     }
                 }
 
+                match db.get_account_creation() {
+                    Ok(Some(creation)) => println!(
+                        "  Account created: {} ({})",
+                        format_timestamp(creation.ts),
+                        match creation.source {
+                            db::CreationSource::Server => "from homeserver",
+                            db::CreationSource::Events => "estimated from crawled rooms",
+                        }
+                    ),
+                    Ok(None) => {}
+                    Err(e) => {
+                        eprintln!("  Error reading account creation: {}", e);
+                    }
+                }
+
                 match db.get_time_window() {
                     Ok(Some(window)) => {
                         let start_str = match window.window_start {
@@ -449,45 +670,98 @@ pub async fn run(user_id_flag: Option<String>, list: bool) -> Result<()> {
                         eprintln!("  Error reading time window: {}", e);
                     }
                 }
+
+                if let Some(ref scope) = window_scope {
+                    match print_window_coverage(&db, scope) {
+                        Ok(covered) => ready &= covered,
+                        Err(e) => {
+                            eprintln!("  Error checking the coverage of {}: {}", scope.key, e);
+                            ready = false;
+                        }
+                    }
+                }
             }
             Err(e) => {
                 eprintln!("  Error loading crawl database: {}", e);
+                ready = false;
             }
         }
     }
+
+    if let Some(ref scope) = window_scope {
+        if ready {
+            println!("\n✓ Ready: the crawled events cover {}", scope.key);
+        } else {
+            println!(
+                "\n✗ Not ready: run 'my crawl {}' before rendering it",
+                scope.key
+            );
+        }
+    }
     Ok(())
 }
 
-async fn check_cross_signing_status(
+async fn check_encryption_status(
     account_dir: &std::path::Path,
     account_id: &str,
-    _secrets_store: &crate::secrets::AccountSecretsStore,
-) -> Result<String> {
+) -> Result<EncryptionStatus> {
     // Check if the SDK database exists
     let sdk_store_dir = account_dir.join("sdk");
     if !sdk_store_dir.exists() {
-        return Ok("Not initialized".to_string());
+        return Ok(EncryptionStatus::unavailable("Not initialized"));
     }
 
     // Try to check cross-signing by restoring session
     let session_path = account_dir.join("meta/session.json");
     if !session_path.exists() {
-        return Ok("Unable to check (no session)".to_string());
+        return Ok(EncryptionStatus::unavailable(
+            "Unable to check (no session)",
+        ));
     }
 
-    // Use the reusable check_verification_state function
-    let verification_state = check_verification_state(account_dir, account_id)
+    // Restore the client for this account
+    let client = crate::sdk::restore_client_for_account(account_dir, account_id).await?;
+
+    // Perform minimal sync to update verification_state
+    crate::sdk::sync_encryption_state(&client).await?;
+
+    let verification_state = check_verification_state(&client)
         .await
         .unwrap_or(matrix_sdk::encryption::VerificationState::Unknown);
+    let key_backup = check_key_backup(&client).await;
 
     // Determine status based on account-level cross-signing verification
-    match verification_state {
-        matrix_sdk::encryption::VerificationState::Verified => Ok("✓ Device verified".to_string()),
-        matrix_sdk::encryption::VerificationState::Unverified => {
-            Ok("✗ Device not verified".to_string())
-        }
-        matrix_sdk::encryption::VerificationState::Unknown => {
-            Ok("⚠ Device verification unknown".to_string())
-        }
+    let cross_signing = match verification_state {
+        matrix_sdk::encryption::VerificationState::Verified => "✓ Device verified",
+        matrix_sdk::encryption::VerificationState::Unverified => "✗ Device not verified",
+        matrix_sdk::encryption::VerificationState::Unknown => "⚠ Device verification unknown",
+    };
+
+    Ok(EncryptionStatus {
+        cross_signing: cross_signing.to_string(),
+        key_backup: key_backup.describe().to_string(),
+        encrypted_history: encrypted_history_outlook(verification_state, key_backup).to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::encryption::VerificationState;
+
+    #[test]
+    fn test_encrypted_history_outlook() {
+        assert_eq!(
+            encrypted_history_outlook(VerificationState::Verified, KeyBackup::Enabled),
+            "✓ Readable"
+        );
+        assert!(
+            encrypted_history_outlook(VerificationState::Verified, KeyBackup::Missing)
+                .starts_with("⚠ Partly readable")
+        );
+        assert!(
+            encrypted_history_outlook(VerificationState::Unverified, KeyBackup::Enabled)
+                .contains("my login")
+        );
     }
 }
@@ -0,0 +1,365 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::account_selector::AccountSelector;
+use crate::commands::login::resolve_data_root;
+use crate::commands::render::links::LinkBase;
+
+/// User settings written by `my init`, stored globally.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct Config {
+    /// IANA timezone used to bucket activity (e.g. Europe/Paris). System local
+    /// time when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+
+    /// Directory reports are written to when `--output` is not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_dir: Option<PathBuf>,
+
+    /// Base of the links in reports when `--link-base` is not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_base: Option<String>,
+
+    /// Entries kept and shown in each ranking when `--top` is not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top: Option<usize>,
+
+    /// Trips to other timezones, for the timezones fun fact
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub travel: Vec<TravelPeriod>,
+
+    /// How DM partners are named in stats files, which may be shared
+    #[serde(default, skip_serializing_if = "DmNames::is_full")]
+    pub dm_names: DmNames,
+
+    /// Whether default report file names carry the account localpart
+    #[serde(default, skip_serializing_if = "FilenameAccount::is_auto")]
+    pub filename_account: FilenameAccount,
+}
+
+/// Whether default report file names carry the account localpart
+/// (`my-alice-year-2025.md`), so that the reports of several accounts can
+/// share an output directory.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilenameAccount {
+    /// Only when several accounts are logged in
+    #[default]
+    Auto,
+    /// Always, even with a single account
+    Always,
+    /// Never: `my-year-2025.md` whatever the account
+    Never,
+}
+
+impl FilenameAccount {
+    pub fn is_auto(&self) -> bool {
+        *self == FilenameAccount::Auto
+    }
+}
+
+/// How the other party of DMs is named in stats files.
+///
+/// DM rooms are usually named after the other party, so their names follow
+/// the same setting.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DmNames {
+    /// Display names, user IDs and DM room names as they are
+    #[default]
+    Full,
+    /// Initials of the names (e.g. "B. S."), without user IDs
+    Initials,
+    /// No DM partner ranking and no DM room names
+    Hidden,
+}
+
+impl DmNames {
+    pub fn is_full(&self) -> bool {
+        *self == DmNames::Full
+    }
+
+    /// `name` as this setting allows it in stats files.
+    pub fn apply(&self, name: &str) -> Option<String> {
+        match self {
+            DmNames::Full => Some(name.to_string()),
+            DmNames::Initials => Some(initials(name)),
+            DmNames::Hidden => None,
+        }
+    }
+}
+
+/// Initials of a name or user ID (e.g. "Bob Smith" -> "B. S.", "@bob:example.org" -> "B.").
+pub fn initials(name: &str) -> String {
+    let name = match name.strip_prefix('@') {
+        Some(user_id) => user_id.split(':').next().unwrap_or(user_id),
+        None => name,
+    };
+    let initials: Vec<String> = name
+        .split_whitespace()
+        .filter_map(|word| word.chars().find(|ch| ch.is_alphanumeric()))
+        .map(|ch| format!("{}.", ch.to_uppercase()))
+        .collect();
+    if initials.is_empty() {
+        return "?".to_string();
+    }
+    initials.join(" ")
+}
+
+/// Days spent in another timezone, maintained by the user in the config file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TravelPeriod {
+    /// First day of the trip, in home local time
+    pub from: NaiveDate,
+    /// Last day of the trip, included
+    pub to: NaiveDate,
+    /// IANA timezone of the trip (e.g. America/New_York)
+    pub timezone: String,
+}
+
+impl TravelPeriod {
+    /// Whether the trip covers `date`
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        (self.from..=self.to).contains(&date)
+    }
+}
+
+impl Config {
+    /// Path of the global config file
+    pub fn path() -> Result<PathBuf> {
+        Ok(resolve_data_root()?.join("global").join("config.json"))
+    }
+
+    /// Load the config from the global config file
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::path()?)
+    }
+
+    /// Save the config to the global config file
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::path()?)
+    }
+
+    /// Load the config from `path`, defaulting when the file does not exist
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        let config: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        if let Some(timezone) = &config.timezone {
+            parse_timezone(timezone)
+                .with_context(|| format!("Invalid config file: {}", path.display()))?;
+        }
+        if let Some(link_base) = &config.link_base {
+            LinkBase::parse(link_base)
+                .with_context(|| format!("Invalid config file: {}", path.display()))?;
+        }
+        if config.top == Some(0) {
+            anyhow::bail!(
+                "Invalid config file: {}: top must be at least 1",
+                path.display()
+            );
+        }
+        for trip in &config.travel {
+            parse_timezone(&trip.timezone)
+                .with_context(|| format!("Invalid config file: {}", path.display()))?;
+            if trip.to < trip.from {
+                anyhow::bail!(
+                    "Invalid config file: {}: travel ends before it starts ({} to {})",
+                    path.display(),
+                    trip.from,
+                    trip.to
+                );
+            }
+        }
+        Ok(config)
+    }
+
+    /// Save the config to `path`
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context("Failed to create global directory")?;
+        }
+        let contents = serde_json::to_string_pretty(self).context("Failed to serialize config")?;
+        fs::write(path, contents).context("Failed to write config file")?;
+        Ok(())
+    }
+
+    /// Whether default report file names carry the account localpart, see
+    /// [`FilenameAccount`].
+    pub fn account_in_filenames(&self) -> bool {
+        match self.filename_account {
+            FilenameAccount::Always => true,
+            FilenameAccount::Never => false,
+            FilenameAccount::Auto => AccountSelector::account_count().unwrap_or(0) > 1,
+        }
+    }
+
+    /// Output directory for reports: `output` if given, else the configured one,
+    /// else the current directory.
+    pub fn output_dir(&self, output: Option<PathBuf>) -> PathBuf {
+        output
+            .or_else(|| self.output_dir.clone())
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// Base of report links: `link_base` if given, else the configured one,
+    /// else matrix.to.
+    pub fn link_base(&self, link_base: Option<LinkBase>) -> LinkBase {
+        link_base
+            .or_else(|| {
+                self.link_base
+                    .as_deref()
+                    .and_then(|value| LinkBase::parse(value).ok())
+            })
+            .unwrap_or_default()
+    }
+
+    /// Entries of each ranking: `top` if given, else the configured number.
+    /// Unset when neither is, for callers to pick their own default.
+    pub fn top(&self, top: Option<usize>) -> Option<usize> {
+        top.or(self.top)
+    }
+
+    /// Makes local time follow the configured timezone.
+    ///
+    /// Activity is bucketed in local time, which honors `TZ`: it is set from
+    /// the config unless already set in the environment, which wins. Must run
+    /// before other threads start.
+    pub fn apply_timezone(&self) {
+        if let Some(timezone) = &self.timezone {
+            if std::env::var_os("TZ").is_none() {
+                std::env::set_var("TZ", timezone);
+            }
+        }
+    }
+}
+
+/// Makes local time follow `timezone`, over the config and the environment
+/// (`--timezone`). Must run before other threads start.
+pub fn override_timezone(timezone: chrono_tz::Tz) {
+    std::env::set_var("TZ", timezone.name());
+}
+
+/// System timezone name, from `TZ` or the `/etc/localtime` link.
+pub fn system_timezone() -> Option<String> {
+    if let Some(tz) = std::env::var_os("TZ") {
+        let name = tz.to_string_lossy().trim_start_matches(':').to_string();
+        return parse_timezone(&name).ok().map(|_| name);
+    }
+    let target = fs::read_link("/etc/localtime").ok()?;
+    let target = target.to_string_lossy();
+    let (_, name) = target.split_once("zoneinfo/")?;
+    parse_timezone(name).ok().map(|_| name.to_string())
+}
+
+/// Timezone activity is bucketed in, for reports: its IANA name, else the
+/// current UTC offset of local time (e.g. UTC+02:00).
+pub fn bucketing_timezone() -> String {
+    system_timezone().unwrap_or_else(|| chrono::Local::now().format("UTC%:z").to_string())
+}
+
+/// Checks that `name` is an IANA timezone (e.g. Europe/Paris).
+pub fn parse_timezone(name: &str) -> Result<chrono_tz::Tz> {
+    name.parse::<chrono_tz::Tz>()
+        .map_err(|_| anyhow::anyhow!("Unknown timezone '{}' (e.g. Europe/Paris)", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_config_load_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::load_from(&temp_dir.path().join("config.json")).unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_config_save_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("global").join("config.json");
+        let config = Config {
+            timezone: Some("Europe/Paris".to_string()),
+            output_dir: Some(PathBuf::from("reports")),
+            link_base: Some("element".to_string()),
+            top: Some(10),
+            travel: vec![TravelPeriod {
+                from: NaiveDate::from_ymd_opt(2025, 3, 10).unwrap(),
+                to: NaiveDate::from_ymd_opt(2025, 3, 20).unwrap(),
+                timezone: "America/New_York".to_string(),
+            }],
+            dm_names: DmNames::Initials,
+            filename_account: FilenameAccount::Always,
+        };
+
+        config.save_to(&path).unwrap();
+        assert_eq!(Config::load_from(&path).unwrap(), config);
+        assert!(config.travel[0].covers(NaiveDate::from_ymd_opt(2025, 3, 20).unwrap()));
+        assert!(!config.travel[0].covers(NaiveDate::from_ymd_opt(2025, 3, 21).unwrap()));
+
+        fs::write(&path, r#"{"timezone": "Mars/Olympus"}"#).unwrap();
+        assert!(Config::load_from(&path).is_err());
+        fs::write(&path, r#"{"dm_names": "nicknames"}"#).unwrap();
+        assert!(Config::load_from(&path).is_err());
+        fs::write(&path, r#"{"filename_account": "sometimes"}"#).unwrap();
+        assert!(Config::load_from(&path).is_err());
+        fs::write(&path, r#"{"link_base": "ftp://example.org"}"#).unwrap();
+        assert!(Config::load_from(&path).is_err());
+        fs::write(&path, r#"{"top": 0}"#).unwrap();
+        assert!(Config::load_from(&path).is_err());
+        fs::write(
+            &path,
+            r#"{"travel": [{"from": "2025-03-20", "to": "2025-03-10", "timezone": "Asia/Tokyo"}]}"#,
+        )
+        .unwrap();
+        assert!(Config::load_from(&path).is_err());
+    }
+
+    #[test]
+    fn test_config_output_dir() {
+        let config = Config {
+            output_dir: Some(PathBuf::from("reports")),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.output_dir(Some(PathBuf::from("elsewhere"))),
+            PathBuf::from("elsewhere")
+        );
+        assert_eq!(config.output_dir(None), PathBuf::from("reports"));
+        assert_eq!(Config::default().output_dir(None), PathBuf::from("."));
+    }
+
+    #[test]
+    fn test_config_top() {
+        let config = Config {
+            top: Some(10),
+            ..Config::default()
+        };
+        assert_eq!(config.top(Some(3)), Some(3));
+        assert_eq!(config.top(None), Some(10));
+        assert_eq!(Config::default().top(None), None);
+    }
+
+    #[test]
+    fn test_dm_names() {
+        assert_eq!(initials("Bob Smith"), "B. S.");
+        assert_eq!(initials("élodie"), "É.");
+        assert_eq!(initials("@bob:example.org"), "B.");
+        assert_eq!(initials("🦊"), "?");
+
+        assert_eq!(DmNames::Full.apply("Bob"), Some("Bob".to_string()));
+        assert_eq!(DmNames::Initials.apply("Bob"), Some("B.".to_string()));
+        assert_eq!(DmNames::Hidden.apply("Bob"), None);
+    }
+}
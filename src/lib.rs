@@ -1,6 +1,7 @@
 // Library exports for testing
 pub mod account_selector;
 pub mod commands;
+pub mod config;
 pub mod logging;
 pub mod sdk;
 pub mod secrets;
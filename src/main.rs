@@ -1,9 +1,27 @@
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use commands::crawl::checkpoint::{self, CheckpointOptions};
+use commands::crawl::metrics::DEFAULT_TYPING_WPM;
+use commands::crawl::quiet_hours::QuietHours;
+use commands::crawl::room_type_override::RoomTypeOverride;
+use commands::crawl::CrawlOptions;
+use commands::render::badge::BadgeMetric;
+use commands::render::card::CardTheme;
+use commands::render::compress::Compression;
+use commands::render::i18n::Lang;
+use commands::render::links::LinkBase;
+use commands::render::output_name::OutputName;
+use commands::render::renderer::{builtin_renderers, ReportContext};
+use commands::render::theme::HtmlTheme;
+use commands::render::RenderOptions;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
 
 mod account_selector;
 mod commands;
+mod config;
 mod logging;
 mod sdk;
 mod secrets;
@@ -11,59 +29,130 @@ mod stats;
 mod timefmt;
 mod window;
 
+/// Counts allocations for `my bench`, in builds with the `bench` feature only:
+/// it slows down every allocation.
+#[cfg(feature = "bench")]
+#[global_allocator]
+static ALLOCATOR: commands::bench::CountingAllocator = commands::bench::CountingAllocator;
+
 // Help text constants
 const HELP_MAIN: &str = "\
 my — Matrix recap tool (year, month, week, day, life)
 
 Commands:
+    init                Set up a first account and settings (wizard)
     login               Log into a Matrix account
     logout              Log out from a Matrix account
     status              Show account and credential status
+    dashboard           Browse rooms and crawl progress interactively
     crawl <window>      Crawl Matrix data for a time window
+    crawl --suggest     Suggest the next window to crawl
+    crawl --dry-run     Show the rooms a crawl would fetch (no sync)
     reset               Reset crawl metadata and SDK data
+    merge               Merge account directories of the same user ID
     render              Render reports from stats files
+    render-all          Render every year or month of a life crawl
+    export ical         Export daily activity as a calendar (.ics)
+    badge               Write an SVG badge (e.g. 2025 | 12,345 messages)
+    share --room <room> Post the recap digest into a Matrix room
+    bench               Benchmark event processing on synthetic events
+    completions <shell> Print a shell completion script (bash, zsh, fish...)
+    help [<command>]    Show help, or the options of a command
     <window>            Crawl and render for a time window (shorthand)
 
 Time Windows:
     2025                Year
+    2025-Q3             Quarter
     2025-03             Month
     2025-W12            Week
     2025-03-15          Day
+    last-30-days        Rolling window (also last-12-months)
     life                Entire history
 
+Global options:
+    --user-id <id>      Account to work on (default: prompt, or all accounts)
+    -o, --output <dir>  Output directory of reports and exports
+
 Examples:
+    my init
     my login
     my 2025                          # Crawl + render year 2025
     my 2025 --output reports         # With custom output directory
     my crawl 2025-03 --user-id @me:example.org
+    my crawl life --quiet-hours 09:00-18:00   # Throttle during the workday
+    my crawl life --checkpoint-every 1h       # Log progress every hour
+    my crawl --suggest                        # What to crawl next
+    my status --window 2025                   # Is 2025 crawled enough to render?
     my render --stats examples/stats/example-stats.json
+    my render-all --scopes years,months          # After 'my crawl life'
+    my export ical --window 2025
+    my badge --metric messages_sent --window 2025
+    my share --room '#friends:example.org' --window 2025
+    my bench --events 1M
+    my completions zsh > ~/.zfunc/_my
 
 More help:
-    my --help render";
+    my help render
+    my help crawl";
 
 const HELP_RENDER: &str = "\
 Render reports from stats files
 
 Usage:
     my render --stats <path> [--formats <list>] [--output <dir>]
+    my render --live [<window>] [--refresh <secs>]
+    my render --site [<path>...] [--output <dir>]
 
 Options:
-    --stats <path>       Path to stats JSON file (required)
-    --formats <list>     Comma-separated formats (md,html). Default: md
-    --output <dir>       Output directory (default: current directory)
+    --stats <path>         Path to stats JSON file (required unless --live or --site)
+    --live [<window>]      Provisional report of a running crawl (default: the latest one)
+    --refresh <secs>       With --live, re-render every <secs> seconds (default: 30, 0 renders once)
+    --site [<path>...]     HTML site of several stats files or directories, with an index.html
+                           (default: the saved stats of the account)
+    --user-id <id>         With --live or --site, account of the crawl or of the saved stats
+    --formats <list>       Comma-separated formats (md,html,org,pdf,term,txt,json,card,badge,digest). Default: md,html
+    --output <dir>         Output directory (default: set by my init, else current directory),
+                           or - to print a single format to standard output
+    --output-name <pattern> Report file names: {scope}, {key}, {user}, {format} (default: my-{scope}-{key})
+    --compress <list>      Also write compressed HTML (gzip,br)
+    --html-budget-kb <n>   Warn above this HTML size (default: 500, 0 disables)
+    --theme <name>         HTML colors: auto, light, dark, ocean, sunset (default: auto)
+    --card-theme <name>    Recap card colors: dark or light (default: dark)
+    --template-dir <dir>   Markdown templates overriding the built-in ones
+    --lang <code>          Report language: en, fr or de (default: en)
+    --link-base <base>     Links to matrix.to (default), element, a client URL or a URL with {id}
+    --compare-previous     Show changes against the previous window (e.g. ▲ 18% vs 2024)
+    --changelog            Note the messages discovered since the window was last crawled
 
 Examples:
     my render --stats examples/stats/example-stats.json
     my render --stats examples/stats/example-stats.json --formats md
-    my render --stats stats.json --output reports";
+    my render --stats stats.json --output reports
+    my render --stats stats.json --formats term
+    my render --stats stats.json --formats json
+    my render --stats stats.json --formats html --compress gzip
+    my render --stats stats.json --formats card --card-theme light
+    my render --stats stats.json --lang fr
+    my render --stats stats-2025-W12.json --formats digest
+    my render --stats stats-2025.json --compare-previous
+    my render --stats stats.json --output-name '{user}-{scope}-{key}'
+    my render --stats stats.json --formats md --output - | glow -
+    my render --live life
+    my render --site --output site";
 
 #[derive(Parser)]
-#[command(name = "my", disable_help_flag = true)]
+#[command(name = "my", disable_help_subcommand = true)]
 #[command(about = "Matrix year-in-review tool", long_about = None)]
 struct Cli {
-    /// Show help (global or per topic). Example: my --help render
-    #[arg(long, value_name = "TOPIC", num_args = 0..=1, default_missing_value = "")]
-    help: Option<String>,
+    /// Matrix user id (e.g. @alice:example.org). If omitted, the command prompts for
+    /// an account or works on all of them
+    #[arg(long, global = true)]
+    user_id: Option<String>,
+
+    /// Output directory of reports and exports (defaults to the one set by `my init`,
+    /// else the current directory), or - to print a single report to standard output
+    #[arg(short, long, global = true)]
+    output: Option<PathBuf>,
 
     /// Subcommand or time window (e.g., login, crawl, 2025)
     #[command(subcommand)]
@@ -72,115 +161,497 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
+    /// First-run wizard: login, timezone, output directory and a first month crawl
+    Init,
     /// Log into a Matrix account and securely store credentials
-    Login {
-        /// Matrix user id (e.g. @alice:example.org). If omitted, interactive selection/creation.
-        #[arg(long)]
-        user_id: Option<String>,
-    },
+    Login,
     /// Log out from a Matrix account and remove stored credentials
-    Logout {
-        /// Matrix user id (e.g. @alice:example.org). If omitted, interactive selection.
-        #[arg(long)]
-        user_id: Option<String>,
-    },
+    Logout,
     /// Show account and credential status
     Status {
-        /// Matrix user id (e.g. @alice:example.org). If omitted, show all.
-        #[arg(long)]
-        user_id: Option<String>,
         /// List all rooms with their crawl metadata
         #[arg(long)]
         list: bool,
+        /// Check whether the crawled events cover a window (e.g. 2025) before rendering it
+        #[arg(long, conflicts_with = "list")]
+        window: Option<String>,
     },
+    /// Interactive view of the crawled rooms: coverage, filters, re-crawls and live progress
+    Dashboard,
     /// Crawl Matrix messages into the SDK database for a time window
     Crawl {
-        /// Time window (e.g. 2025, 2025-03, 2025-W12, 2025-03-15, life)
-        window: String,
-        /// Matrix user id (e.g. @alice:example.org). If omitted, crawl all accounts.
+        /// Time window (e.g. 2025, 2025-Q3, 2025-03, 2025-W12, 2025-03-15, last-30-days, life)
+        #[arg(required_unless_present = "suggest")]
+        window: Option<String>,
+        /// Suggest the most useful window to crawl next instead of crawling
+        #[arg(long, conflicts_with = "window")]
+        suggest: bool,
+        /// Show which rooms would be crawled, from the room list cached by the last crawl
+        #[arg(long, conflicts_with_all = ["suggest", "fixture"])]
+        dry_run: bool,
+        /// Local time range to throttle crawling (e.g. 09:00-18:00 or 22:00-07:00)
+        #[arg(long, value_parser = QuietHours::parse)]
+        quiet_hours: Option<QuietHours>,
+        /// Rooms crawled concurrently during quiet hours (0 waits until they end)
+        #[arg(long, default_value_t = 1)]
+        quiet_concurrency: usize,
+        /// Typing speed (words per minute) used to estimate time spent writing
+        #[arg(long, default_value_t = DEFAULT_TYPING_WPM, value_parser = clap::value_parser!(u32).range(1..))]
+        typing_wpm: u32,
+        /// Force a room's type, e.g. !abc:example.org=private (dm, public, private; repeatable)
+        #[arg(long = "room-type", value_name = "ROOM=TYPE", value_parser = RoomTypeOverride::parse)]
+        room_type_overrides: Vec<RoomTypeOverride>,
+        /// Also record stats isolated to each top room (for render --per-room)
         #[arg(long)]
-        user_id: Option<String>,
-    },
-    /// Reset crawl metadata and SDK data (keeps credentials)
-    Reset {
-        /// Matrix user id (e.g. @alice:example.org). If omitted, reset all accounts.
+        per_room: bool,
+        /// Keep a short excerpt of the most reacted messages (media are not kept)
+        #[arg(long)]
+        message_previews: bool,
+        /// Do not record who sent the messages of your rooms (no People section)
         #[arg(long)]
-        user_id: Option<String>,
+        no_people: bool,
+        /// Count each form of an emoji (skin tones, variation selectors) apart
+        #[arg(long)]
+        no_emoji_folding: bool,
+        /// IANA timezone activity is bucketed in (e.g. Europe/Paris), over the config file and TZ
+        #[arg(long, value_parser = config::parse_timezone)]
+        timezone: Option<chrono_tz::Tz>,
+        /// Entries kept in each ranking: rooms, emojis, messages (default: config file, else 5)
+        #[arg(long, env = "MY_TOP", value_parser = parse_top)]
+        top: Option<usize>,
+        /// Log a progress checkpoint at this interval while the crawl runs (e.g. 30m, 2h)
+        #[arg(long, value_name = "INTERVAL", value_parser = checkpoint::parse_interval)]
+        checkpoint_every: Option<Duration>,
+        /// Also POST each checkpoint as JSON to this URL
+        #[arg(long, value_name = "URL", requires = "checkpoint_every", value_parser = checkpoint::parse_webhook)]
+        checkpoint_webhook: Option<Url>,
+        /// Also send each checkpoint as a notice to this room (ID or alias)
+        #[arg(long, value_name = "ROOM", requires = "checkpoint_every")]
+        checkpoint_room: Option<String>,
+        /// Replay synthetic events from a fixture directory instead of a homeserver
+        #[arg(long, value_name = "DIR", conflicts_with_all = ["suggest", "user_id"])]
+        fixture: Option<PathBuf>,
     },
-    /// Render reports from stats files (md, html)
+    /// Reset crawl metadata and SDK data (keeps credentials)
+    Reset,
+    /// Merge account directories logged into the same user ID (keeps the latest login)
+    Merge,
+    /// Render reports from stats files (md, html, pdf, card, badge)
     Render {
         /// Path to JSON stats file
+        #[arg(
+            long,
+            required_unless_present_any = ["live", "site"],
+            conflicts_with_all = ["live", "site"]
+        )]
+        stats: Option<PathBuf>,
+        /// Provisional report of the running crawl of a window (default: the latest crawl)
+        #[arg(long, value_name = "WINDOW", num_args = 0..=1, default_missing_value = "")]
+        live: Option<String>,
+        /// With --live, seconds between renders; the HTML report reloads as often (0 renders once)
+        #[arg(long, default_value_t = 30, requires = "live")]
+        refresh: u64,
+        /// HTML site of several stats files, or directories of stats-*.json files, linked
+        /// from an index.html (default: the saved stats of the account)
+        #[arg(long, value_name = "PATH", num_args = 0.., conflicts_with = "live")]
+        site: Option<Vec<PathBuf>>,
+        /// Comma-separated formats (md,html,org,pdf,term,txt,json,card,badge,digest). Default: md,html.
+        #[arg(long, default_value = "")]
+        formats: String,
+        /// Report file names, without extension: {scope}, {key}, {user}, {format} (e.g. {user}-{key})
+        #[arg(long, value_name = "PATTERN", value_parser = OutputName::parse, conflicts_with = "site")]
+        output_name: Option<OutputName>,
+        /// Also write compressed HTML copies (gzip, br), e.g. --compress gzip,br
+        #[arg(long, default_value = "")]
+        compress: String,
+        /// Warn when the HTML report exceeds this size in KB (0 disables)
+        #[arg(long, default_value_t = commands::render::html::DEFAULT_BUDGET_KB)]
+        html_budget_kb: u64,
+        /// Color theme of the HTML report (auto follows the system; light, dark, ocean, sunset)
+        #[arg(long, default_value = "auto", value_parser = HtmlTheme::parse)]
+        theme: HtmlTheme,
+        /// Color theme of the PNG recap card (dark, light)
+        #[arg(long, default_value = "dark", value_parser = CardTheme::parse)]
+        card_theme: CardTheme,
+        /// Directory of Markdown templates overriding the built-in ones (e.g. fun.md)
+        #[arg(long)]
+        template_dir: Option<PathBuf>,
+        /// Language of the Markdown, HTML, PDF and terminal reports (en, fr, de)
+        #[arg(long, default_value = "en", value_parser = Lang::parse)]
+        lang: Lang,
+        /// Base of report links: matrix.to, element, an Element Web URL or a URL with {id}
+        #[arg(long, value_parser = LinkBase::parse)]
+        link_base: Option<LinkBase>,
+        /// Also render a mini-report per top room (needs stats crawled with --per-room)
         #[arg(long)]
-        stats: PathBuf,
-        /// Comma-separated formats (md,html). Empty renders all.
+        per_room: bool,
+        /// Entries shown in each ranking (default: config file, else all entries of the stats file)
+        #[arg(long, env = "MY_TOP", value_parser = parse_top)]
+        top: Option<usize>,
+        /// Reproducible output for snapshot tests: fixed generation date and tool, ties ordered by name
+        #[arg(long)]
+        golden: bool,
+        /// Show the change of headline numbers against the previous window's stats, when saved
+        #[arg(long)]
+        compare_previous: bool,
+        /// Note the messages discovered since the window was last crawled, when crawled again
+        #[arg(long)]
+        changelog: bool,
+    },
+    /// Render a report for every year and/or month of the latest life crawl
+    RenderAll {
+        /// Windows to report, comma-separated: years, months (e.g. years,months)
+        #[arg(long, required = true, value_delimiter = ',', value_parser = parse_scope)]
+        scopes: Vec<stats::ScopeKind>,
+        /// Comma-separated formats (md,html,org,pdf,txt,json,card,badge,digest). Default: md,html.
         #[arg(long, default_value = "")]
         formats: String,
-        /// Output directory (defaults to current directory)
-        #[arg(short, long)]
-        output: Option<PathBuf>,
+        /// Color theme of the HTML reports (auto follows the system; light, dark, ocean, sunset)
+        #[arg(long, default_value = "auto", value_parser = HtmlTheme::parse)]
+        theme: HtmlTheme,
+        /// Language of the reports (en, fr, de)
+        #[arg(long, default_value = "en", value_parser = Lang::parse)]
+        lang: Lang,
+        /// Base of report links: matrix.to, element, an Element Web URL or a URL with {id}
+        #[arg(long, value_parser = LinkBase::parse)]
+        link_base: Option<LinkBase>,
+    },
+    /// Export stats into third-party formats (ical)
+    Export {
+        #[command(subcommand)]
+        format: ExportFormat,
+    },
+    /// Write an SVG badge of a stat from saved stats (e.g. "2025 | 12,345 messages")
+    Badge {
+        /// Stat to show (messages_sent, active_rooms, days_active, reactions)
+        #[arg(long, default_value = "messages_sent", value_parser = BadgeMetric::parse)]
+        metric: BadgeMetric,
+        /// Time window of previously crawled stats (e.g. 2025, 2025-03, life). Defaults to the current year.
+        #[arg(long)]
+        window: Option<String>,
+    },
+    /// Post the recap digest of saved stats into a Matrix room, as a formatted message
+    Share {
+        /// Room ID or alias to post into (e.g. #friends:example.org)
+        #[arg(long)]
+        room: String,
+        /// Time window of previously crawled stats (e.g. 2025, 2025-03, life). Defaults to the current year.
+        #[arg(long)]
+        window: Option<String>,
+        /// Language of the message (en, fr, de)
+        #[arg(long, default_value = "en", value_parser = Lang::parse)]
+        lang: Lang,
+        /// Base of message links: matrix.to, element, an Element Web URL or a URL with {id}
+        #[arg(long, value_parser = LinkBase::parse)]
+        link_base: Option<LinkBase>,
+        /// Send without showing the message and asking first
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Benchmark event processing and stats building on synthetic events
+    Bench {
+        /// Synthetic events to process (e.g. 100k, 1M)
+        #[arg(long, default_value = "1M", value_parser = commands::bench::parse_count)]
+        events: usize,
+        /// Rooms the events are spread over
+        #[arg(long, default_value_t = 50, value_parser = clap::value_parser!(u32).range(1..))]
+        rooms: u32,
+        /// Seed of the event generator, for reproducible runs
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+    },
+    /// Print a shell completion script. Example: my completions bash > ~/.local/share/bash-completion/completions/my
+    Completions {
+        /// Shell to complete in (bash, elvish, fish, powershell, zsh)
+        shell: clap_complete::Shell,
+    },
+    /// Show help, or the options of a command. Example: my help render
+    Help {
+        /// Command to show the options of (e.g. crawl, render)
+        topic: Option<String>,
     },
     /// Crawl and render for a time window (shorthand: my 2025)
     #[command(external_subcommand)]
     Window(Vec<String>),
 }
 
+#[derive(Subcommand)]
+enum ExportFormat {
+    /// Daily activity as an iCalendar feed (one all-day event per active day)
+    Ical {
+        /// Time window of previously crawled stats (e.g. 2025, 2025-03, life)
+        #[arg(long)]
+        window: String,
+    },
+}
+
 /// Arguments for the window shorthand command parsed via clap
 #[derive(Parser, Debug)]
 struct WindowArgs {
-    /// Time window (e.g. 2025, 2025-03, 2025-W12, 2025-03-15, life)
+    /// Time window (e.g. 2025, 2025-Q3, 2025-03, 2025-W12, 2025-03-15, last-30-days, life)
     window: String,
     /// Matrix user id (optional). If omitted, prompts for selection.
     #[arg(long)]
     user_id: Option<String>,
-    /// Comma-separated formats (md,html). Default: md.
+    /// Comma-separated formats (md,html,org,pdf,term,txt,json,card,badge,digest). Default: md,html.
     #[arg(long, default_value = "")]
     formats: String,
-    /// Output directory (defaults to current directory).
+    /// Output directory (defaults to the one set by `my init`, else the current directory),
+    /// or - to print a single format to standard output
     #[arg(short, long)]
     output: Option<PathBuf>,
+    /// Report file names, without extension: {scope}, {key}, {user}, {format} (e.g. {user}-{key})
+    #[arg(long, value_name = "PATTERN", value_parser = OutputName::parse)]
+    output_name: Option<OutputName>,
+    /// Also write compressed HTML copies (gzip, br), e.g. --compress gzip,br
+    #[arg(long, default_value = "")]
+    compress: String,
+    /// Warn when the HTML report exceeds this size in KB (0 disables)
+    #[arg(long, default_value_t = commands::render::html::DEFAULT_BUDGET_KB)]
+    html_budget_kb: u64,
+    /// Color theme of the HTML report (auto follows the system; light, dark, ocean, sunset)
+    #[arg(long, default_value = "auto", value_parser = HtmlTheme::parse)]
+    theme: HtmlTheme,
+    /// Color theme of the PNG recap card (dark, light)
+    #[arg(long, default_value = "dark", value_parser = CardTheme::parse)]
+    card_theme: CardTheme,
+    /// Directory of Markdown templates overriding the built-in ones (e.g. fun.md)
+    #[arg(long)]
+    template_dir: Option<PathBuf>,
+    /// Language of the Markdown, HTML, PDF and terminal reports (en, fr, de)
+    #[arg(long, default_value = "en", value_parser = Lang::parse)]
+    lang: Lang,
+    /// Base of report links: matrix.to, element, an Element Web URL or a URL with {id}
+    #[arg(long, value_parser = LinkBase::parse)]
+    link_base: Option<LinkBase>,
+    /// Local time range to throttle crawling (e.g. 09:00-18:00 or 22:00-07:00)
+    #[arg(long, value_parser = QuietHours::parse)]
+    quiet_hours: Option<QuietHours>,
+    /// Rooms crawled concurrently during quiet hours (0 waits until they end)
+    #[arg(long, default_value_t = 1)]
+    quiet_concurrency: usize,
+    /// Typing speed (words per minute) used to estimate time spent writing
+    #[arg(long, default_value_t = DEFAULT_TYPING_WPM, value_parser = clap::value_parser!(u32).range(1..))]
+    typing_wpm: u32,
+    /// Force a room's type, e.g. !abc:example.org=private (dm, public, private; repeatable)
+    #[arg(long = "room-type", value_name = "ROOM=TYPE", value_parser = RoomTypeOverride::parse)]
+    room_type_overrides: Vec<RoomTypeOverride>,
+    /// Also render a mini-report per top room
+    #[arg(long)]
+    per_room: bool,
+    /// Keep a short excerpt of the most reacted messages (media are not kept)
+    #[arg(long)]
+    message_previews: bool,
+    /// Do not record who sent the messages of your rooms (no People section)
+    #[arg(long)]
+    no_people: bool,
+    /// Count each form of an emoji (skin tones, variation selectors) apart
+    #[arg(long)]
+    no_emoji_folding: bool,
+    /// IANA timezone activity is bucketed in (e.g. Europe/Paris), over the config file and TZ
+    #[arg(long, value_parser = config::parse_timezone)]
+    timezone: Option<chrono_tz::Tz>,
+    /// Entries kept and shown in each ranking: rooms, emojis, messages (default: config file, else 5)
+    #[arg(long, env = "MY_TOP", value_parser = parse_top)]
+    top: Option<usize>,
+    /// Show the change of headline numbers against the previous window's stats, when saved
+    #[arg(long)]
+    compare_previous: bool,
+    /// Note the messages discovered since the window was last crawled, when crawled again
+    #[arg(long)]
+    changelog: bool,
+    /// Log a progress checkpoint at this interval while the crawl runs (e.g. 30m, 2h)
+    #[arg(long, value_name = "INTERVAL", value_parser = checkpoint::parse_interval)]
+    checkpoint_every: Option<Duration>,
+    /// Also POST each checkpoint as JSON to this URL
+    #[arg(long, value_name = "URL", requires = "checkpoint_every", value_parser = checkpoint::parse_webhook)]
+    checkpoint_webhook: Option<Url>,
+    /// Also send each checkpoint as a notice to this room (ID or alias)
+    #[arg(long, value_name = "ROOM", requires = "checkpoint_every")]
+    checkpoint_room: Option<String>,
+}
+
+/// Parses a ranking size, which must be at least 1.
+fn parse_top(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(top) if top >= 1 => Ok(top),
+        _ => Err(format!(
+            "expected a number of entries (1 or more), got '{}'",
+            value
+        )),
+    }
+}
+
+/// Parses a scope of `my render-all`: years or months.
+fn parse_scope(value: &str) -> Result<stats::ScopeKind, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "years" | "year" => Ok(stats::ScopeKind::Year),
+        "months" | "month" => Ok(stats::ScopeKind::Month),
+        _ => Err(format!("expected years or months, got '{}'", value)),
+    }
 }
 
 fn main() -> Result<()> {
-    let cli = Cli::parse();
-
-    if let Some(help_topic) = cli.help {
-        let topic = help_topic.trim();
-        if topic.is_empty() {
-            println!("{}", HELP_MAIN);
-        } else if topic.eq_ignore_ascii_case("render") {
-            println!("{}", HELP_RENDER);
-        } else {
-            println!("Unknown help topic: {}", topic);
+    // Help is shown before loading the config, which it does not need
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(topic) = top_level_help(&args) {
+        let topic = topic.trim();
+        if !topic.is_empty() {
+            eprintln!(
+                "Note: 'my --help {}' is deprecated, use 'my help {}'",
+                topic, topic
+            );
         }
+        return print_help(topic);
+    }
+
+    let cli = Cli::parse_from(args);
+    if let Some(Commands::Help { ref topic }) = cli.command {
+        return print_help(topic.as_deref().unwrap_or_default().trim());
+    }
+    if let Some(Commands::Completions { shell }) = cli.command {
+        clap_complete::generate(shell, &mut Cli::command(), "my", &mut std::io::stdout());
         return Ok(());
     }
 
-    if let Some(cmd) = cli.command {
+    // Before any runtime starts: setting TZ is not thread-safe (so is `--timezone`)
+    let config = config::Config::load()?;
+    config.apply_timezone();
+
+    let Cli {
+        command,
+        user_id,
+        output,
+        ..
+    } = cli;
+    if let Some(cmd) = command {
         match cmd {
-            Commands::Login { user_id } => {
+            Commands::Init => {
+                let taste = tokio::runtime::Runtime::new()
+                    .context("Failed to create Tokio runtime")?
+                    .block_on(commands::init::run())?;
+                if let Some(window) = taste {
+                    // The wizard may have changed the settings
+                    let config = config::Config::load()?;
+                    let options = CrawlOptions {
+                        top: config.top(None).unwrap_or(stats::DEFAULT_TOP),
+                        ..CrawlOptions::default()
+                    };
+                    let render_options = RenderOptions {
+                        link_base: config.link_base(None),
+                        top: Some(options.top),
+                        account_in_filenames: config.account_in_filenames(),
+                        ..RenderOptions::default()
+                    };
+                    handle_window(
+                        window,
+                        None,
+                        String::new(),
+                        config.output_dir(None),
+                        &options,
+                        &render_options,
+                    )?;
+                    eprintln!(
+                        "\n🎉 Run 'my {}' for your year in review.",
+                        chrono::Local::now().format("%Y")
+                    );
+                }
+                return Ok(());
+            }
+            Commands::Login => {
                 tokio::runtime::Runtime::new()
                     .context("Failed to create Tokio runtime")?
                     .block_on(commands::login::run(user_id))?;
                 return Ok(());
             }
-            Commands::Logout { user_id } => {
+            Commands::Logout => {
                 tokio::runtime::Runtime::new()
                     .context("Failed to create Tokio runtime")?
                     .block_on(commands::logout::run(user_id))?;
                 return Ok(());
             }
-            Commands::Status { user_id, list } => {
+            Commands::Status { list, window } => {
+                tokio::runtime::Runtime::new()
+                    .context("Failed to create Tokio runtime")?
+                    .block_on(commands::status::run(user_id, list, window))?;
+                return Ok(());
+            }
+            Commands::Dashboard => {
                 tokio::runtime::Runtime::new()
                     .context("Failed to create Tokio runtime")?
-                    .block_on(commands::status::run(user_id, list))?;
+                    .block_on(commands::dashboard::run(user_id))?;
                 return Ok(());
             }
-            Commands::Crawl { window, user_id } => {
+            Commands::Crawl {
+                window,
+                suggest,
+                dry_run,
+                quiet_hours,
+                quiet_concurrency,
+                typing_wpm,
+                room_type_overrides,
+                per_room,
+                message_previews,
+                no_people,
+                no_emoji_folding,
+                timezone,
+                top,
+                checkpoint_every,
+                checkpoint_webhook,
+                checkpoint_room,
+                fixture,
+            } => {
+                if let Some(timezone) = timezone {
+                    config::override_timezone(timezone);
+                }
+                if suggest {
+                    commands::crawl::suggest::run(user_id)?;
+                    return Ok(());
+                }
+                let window = window.context("Missing window")?;
+                if dry_run {
+                    commands::crawl::dry_run::run(&window, user_id)?;
+                    return Ok(());
+                }
+                let options = CrawlOptions {
+                    quiet_hours,
+                    quiet_concurrency,
+                    typing_wpm,
+                    room_type_overrides,
+                    per_room,
+                    message_previews,
+                    people: !no_people,
+                    fold_emojis: !no_emoji_folding,
+                    top: config.top(top).unwrap_or(stats::DEFAULT_TOP),
+                    travel: config.travel.clone(),
+                    dm_names: config.dm_names,
+                    checkpoints: checkpoint_every.map(|every| CheckpointOptions {
+                        every,
+                        webhook: checkpoint_webhook,
+                        notice_room: checkpoint_room,
+                    }),
+                };
+                if let Some(fixture_dir) = fixture {
+                    let (_, stats) =
+                        commands::crawl::fixture::run(&window, &fixture_dir, &options)?;
+                    // Stay out of the data directory: a fixture is not a real account
+                    let stats_path = fixture_dir.join(format!("stats-{}.json", stats.scope.key));
+                    let stats_json = serde_json::to_string_pretty(&stats)
+                        .context("Failed to serialize stats")?;
+                    std::fs::write(&stats_path, stats_json)
+                        .context(format!("Failed to write stats file: {:?}", stats_path))?;
+
+                    eprintln!("📊 Stats saved: {}", stats_path.display());
+                    return Ok(());
+                }
                 let account_stats = tokio::runtime::Runtime::new()
                     .context("Failed to create Tokio runtime")?
-                    .block_on(commands::crawl::run(window, user_id))?;
+                    .block_on(async {
+                        let cancel = commands::crawl::cancel_on_ctrl_c();
+                        commands::crawl::run(window, user_id, &options, &cancel).await
+                    })?;
 
-                for (account_id, stats) in account_stats {
+                for (account_id, mut stats) in account_stats {
                     let data_dir = commands::login::resolve_data_root()?;
                     let account_dirname = commands::login::account_id_to_dirname(&account_id);
                     let account_dir = data_dir.join("accounts").join(&account_dirname);
@@ -191,6 +662,7 @@ fn main() -> Result<()> {
                         "Failed to create account directory: {:?}",
                         account_dir
                     ))?;
+                    record_previous_crawl(&mut stats, &stats_path);
 
                     let stats_json = serde_json::to_string_pretty(&stats)
                         .context("Failed to serialize stats")?;
@@ -202,20 +674,160 @@ fn main() -> Result<()> {
 
                 return Ok(());
             }
-            Commands::Reset { user_id } => {
+            Commands::Reset => {
                 tokio::runtime::Runtime::new()
                     .context("Failed to create Tokio runtime")?
                     .block_on(commands::reset::run(user_id))?;
                 return Ok(());
             }
+            Commands::Merge => {
+                commands::merge::run(user_id)?;
+                return Ok(());
+            }
             Commands::Render {
                 stats,
+                live,
+                refresh,
+                site,
+                formats,
+                output_name,
+                compress,
+                html_budget_kb,
+                theme,
+                card_theme,
+                template_dir,
+                lang,
+                link_base,
+                per_room,
+                top,
+                golden,
+                compare_previous,
+                changelog,
+            } => {
+                let render_options = RenderOptions {
+                    compress: Compression::parse_list(&compress)?,
+                    html_budget_kb,
+                    theme,
+                    card_theme,
+                    template_dir,
+                    lang,
+                    link_base: config.link_base(link_base),
+                    per_room,
+                    top: config.top(top),
+                    golden,
+                    live: live.is_some().then_some(refresh),
+                    compare_previous,
+                    changelog,
+                    output_name,
+                    // The pages of a site link each other by their default names
+                    account_in_filenames: site.is_none() && config.account_in_filenames(),
+                };
+                let output_dir = config.output_dir(output);
+                match (stats, live, site) {
+                    (_, _, Some(paths)) => {
+                        handle_site_render(paths, user_id, output_dir, &render_options)?
+                    }
+                    (_, Some(window), None) => handle_live_render(
+                        (!window.is_empty()).then_some(window),
+                        user_id,
+                        formats,
+                        output_dir,
+                        &render_options,
+                    )?,
+                    (Some(stats), None, None) => {
+                        handle_render(stats, formats, output_dir, &render_options)?
+                    }
+                    (None, None, None) => unreachable!("clap requires --stats, --live or --site"),
+                }
+                return Ok(());
+            }
+            Commands::RenderAll {
+                scopes,
                 formats,
-                output,
+                theme,
+                lang,
+                link_base,
             } => {
-                handle_render(stats, formats, output)?;
+                let render_options = RenderOptions {
+                    compress: Vec::new(),
+                    html_budget_kb: commands::render::html::DEFAULT_BUDGET_KB,
+                    theme,
+                    card_theme: CardTheme::default(),
+                    template_dir: None,
+                    lang,
+                    link_base: config.link_base(link_base),
+                    per_room: false,
+                    top: config.top(None),
+                    golden: false,
+                    live: None,
+                    compare_previous: false,
+                    changelog: false,
+                    output_name: None,
+                    account_in_filenames: config.account_in_filenames(),
+                };
+                handle_render_all(
+                    &scopes,
+                    user_id,
+                    formats,
+                    config.output_dir(output),
+                    &render_options,
+                )?;
                 return Ok(());
             }
+            Commands::Export { format } => {
+                match format {
+                    ExportFormat::Ical { window } => {
+                        let output_dir = config.output_dir(output);
+                        let path = commands::export::run_ical(&window, user_id, &output_dir)?;
+                        eprintln!("📅 iCalendar: {}", path.display());
+                    }
+                }
+                return Ok(());
+            }
+            Commands::Badge { metric, window } => {
+                let window =
+                    window.unwrap_or_else(|| chrono::Local::now().format("%Y").to_string());
+                let output_dir = config.output_dir(output);
+                let path = commands::badge::run(&window, user_id, metric, &output_dir)?;
+                eprintln!("🏷️  Badge: {}", path.display());
+                return Ok(());
+            }
+            Commands::Share {
+                room,
+                window,
+                lang,
+                link_base,
+                yes,
+            } => {
+                let window =
+                    window.unwrap_or_else(|| chrono::Local::now().format("%Y").to_string());
+                tokio::runtime::Runtime::new()
+                    .context("Failed to create Tokio runtime")?
+                    .block_on(commands::share::run(
+                        &room,
+                        &window,
+                        user_id,
+                        lang,
+                        &config.link_base(link_base),
+                        yes,
+                    ))?;
+                return Ok(());
+            }
+            Commands::Bench {
+                events,
+                rooms,
+                seed,
+            } => {
+                commands::bench::run(&commands::bench::BenchOptions {
+                    events,
+                    rooms: rooms as usize,
+                    seed,
+                })?;
+                return Ok(());
+            }
+            Commands::Help { .. } | Commands::Completions { .. } => {
+                unreachable!("help and completions are shown before loading the config")
+            }
             Commands::Window(args) => {
                 if args.is_empty() {
                     anyhow::bail!("Window pattern required (e.g., my 2025)");
@@ -224,14 +836,99 @@ fn main() -> Result<()> {
                 let mut argv = vec!["window".to_string()];
                 argv.extend(args);
                 let parsed = WindowArgs::try_parse_from(argv)?;
+                if let Some(timezone) = parsed.timezone {
+                    config::override_timezone(timezone);
+                }
+                let top = config.top(parsed.top).unwrap_or(stats::DEFAULT_TOP);
 
-                handle_window(parsed.window, parsed.user_id, parsed.formats, parsed.output)?;
+                let options = CrawlOptions {
+                    quiet_hours: parsed.quiet_hours,
+                    quiet_concurrency: parsed.quiet_concurrency,
+                    typing_wpm: parsed.typing_wpm,
+                    room_type_overrides: parsed.room_type_overrides,
+                    per_room: parsed.per_room,
+                    message_previews: parsed.message_previews,
+                    people: !parsed.no_people,
+                    fold_emojis: !parsed.no_emoji_folding,
+                    top,
+                    travel: config.travel.clone(),
+                    dm_names: config.dm_names,
+                    checkpoints: parsed.checkpoint_every.map(|every| CheckpointOptions {
+                        every,
+                        webhook: parsed.checkpoint_webhook,
+                        notice_room: parsed.checkpoint_room,
+                    }),
+                };
+                let render_options = RenderOptions {
+                    compress: Compression::parse_list(&parsed.compress)?,
+                    html_budget_kb: parsed.html_budget_kb,
+                    theme: parsed.theme,
+                    card_theme: parsed.card_theme,
+                    template_dir: parsed.template_dir,
+                    lang: parsed.lang,
+                    link_base: config.link_base(parsed.link_base),
+                    per_room: parsed.per_room,
+                    top: Some(top),
+                    golden: false,
+                    live: None,
+                    compare_previous: parsed.compare_previous,
+                    changelog: parsed.changelog,
+                    output_name: parsed.output_name,
+                    account_in_filenames: config.account_in_filenames(),
+                };
+                handle_window(
+                    parsed.window,
+                    parsed.user_id.or(user_id),
+                    parsed.formats,
+                    config.output_dir(parsed.output.or(output)),
+                    &options,
+                    &render_options,
+                )?;
                 return Ok(());
             }
         }
     }
 
-    eprintln!("No action specified. Try 'my --help' for usage.");
+    eprintln!("No action specified. Try 'my help' for usage.");
+    Ok(())
+}
+
+/// Topic of `my --help [<topic>]`, empty without one; a topic is the deprecated
+/// form of `my help <topic>`.
+///
+/// Read before clap parses the arguments: a help flag of `my` itself would be
+/// propagated to the commands, which have their own `--help`.
+fn top_level_help(args: &[String]) -> Option<&str> {
+    match args {
+        [_, flag, rest @ ..] if flag == "--help" => {
+            Some(rest.first().map(String::as_str).unwrap_or_default())
+        }
+        _ => None,
+    }
+}
+
+/// Prints the overview without a topic, the render guide for `render`, and the
+/// generated help of any other command.
+fn print_help(topic: &str) -> Result<()> {
+    if topic.is_empty() {
+        println!("{}", HELP_MAIN);
+        return Ok(());
+    }
+    if topic.eq_ignore_ascii_case("render") {
+        println!("{}", HELP_RENDER);
+        return Ok(());
+    }
+    let mut cli = Cli::command();
+    // Propagates the global flags (--user-id, --output) to the commands
+    cli.build();
+    let Some(command) = cli.find_subcommand(topic) else {
+        anyhow::bail!("Unknown help topic: {} (see 'my help')", topic);
+    };
+    command
+        .clone()
+        .bin_name(format!("my {}", command.get_name()))
+        .print_help()
+        .context("Failed to print help")?;
     Ok(())
 }
 
@@ -239,7 +936,9 @@ fn handle_window(
     window: String,
     user_id_flag: Option<String>,
     formats: String,
-    output: Option<PathBuf>,
+    output_dir: PathBuf,
+    options: &CrawlOptions,
+    render_options: &RenderOptions,
 ) -> Result<()> {
     eprintln!("🔍 Window: {}", window);
 
@@ -261,12 +960,12 @@ fn handle_window(
     eprintln!("\n🔄 Crawling {}...", window);
     let account_stats = tokio::runtime::Runtime::new()
         .context("Failed to create Tokio runtime")?
-        .block_on(commands::crawl::run(
-            window.clone(),
-            Some(account_id.clone()),
-        ))?;
+        .block_on(async {
+            let cancel = commands::crawl::cancel_on_ctrl_c();
+            commands::crawl::run(window.clone(), Some(account_id.clone()), options, &cancel).await
+        })?;
 
-    let (acc_id, stats) = account_stats
+    let (acc_id, mut stats) = account_stats
         .into_iter()
         .next()
         .context("Expected exactly one account's stats from crawl::run")?;
@@ -278,6 +977,7 @@ fn handle_window(
         "Failed to create account directory: {:?}",
         account_dir
     ))?;
+    record_previous_crawl(&mut stats, &stats_path);
 
     let stats_json = serde_json::to_string_pretty(&stats).context("Failed to serialize stats")?;
     std::fs::write(&stats_path, stats_json)
@@ -286,59 +986,567 @@ fn handle_window(
     eprintln!("📊 Stats saved: {}", stats_path.display());
 
     eprintln!("\n📝 Rendering reports...");
-    let output_dir = output.unwrap_or_else(|| PathBuf::from("."));
-    render_stats(&stats, &output_dir, &formats)?;
+    render_stats(&stats, account_dir, &output_dir, &formats, render_options)?;
 
     eprintln!("\n✅ Done! Window {} processed for {}", window, acc_id);
 
     Ok(())
 }
 
-fn handle_render(stats_path: PathBuf, formats: String, output: Option<PathBuf>) -> Result<()> {
+fn handle_render(
+    stats_path: PathBuf,
+    formats: String,
+    output_dir: PathBuf,
+    render_options: &RenderOptions,
+) -> Result<()> {
     let stats = stats::Stats::load_from_file(&stats_path)?;
-    let output_dir = output.unwrap_or_else(|| PathBuf::from("."));
-    render_stats(&stats, &output_dir, &formats)?;
+    let stats_dir = stats_path.parent().unwrap_or_else(|| Path::new("."));
+    render_stats(&stats, stats_dir, &output_dir, &formats, render_options)?;
     Ok(())
 }
 
-fn render_stats(stats: &stats::Stats, output_dir: &Path, formats_arg: &str) -> Result<()> {
-    std::fs::create_dir_all(output_dir).with_context(|| {
+/// Renders several stats files as a static HTML site with an index page.
+///
+/// `paths` are stats files or directories whose `stats-*.json` files are all
+/// rendered; without paths, the stats saved for the selected account are used.
+fn handle_site_render(
+    paths: Vec<PathBuf>,
+    user_id_flag: Option<String>,
+    output_dir: PathBuf,
+    render_options: &RenderOptions,
+) -> Result<()> {
+    let paths = if paths.is_empty() {
+        let mut selector = account_selector::AccountSelector::new()?;
+        let accounts = selector.select_accounts(user_id_flag, false)?;
+        match accounts.as_slice() {
+            [] => anyhow::bail!("No accounts found. Use 'my login' first."),
+            [(_, account_dir)] => vec![account_dir.clone()],
+            _ => anyhow::bail!(
+                "Multiple accounts found. A site covers exactly one account. \
+                 Use --user-id to specify which account."
+            ),
+        }
+    } else {
+        paths
+    };
+    if is_stdout(&output_dir) {
+        anyhow::bail!("A site is several files and needs an output directory, not --output -");
+    }
+
+    let mut stats_paths = Vec::new();
+    for path in paths {
+        if !path.is_dir() {
+            stats_paths.push(path);
+            continue;
+        }
+        let entries = std::fs::read_dir(&path)
+            .with_context(|| format!("Failed to read directory: {}", path.display()))?;
+        let mut found = Vec::new();
+        for entry in entries {
+            let entry_path = entry?.path();
+            let is_stats = entry_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("stats-") && name.ends_with(".json"));
+            if is_stats {
+                found.push(entry_path);
+            }
+        }
+        if found.is_empty() {
+            eprintln!(
+                "⚠️  Warning: No stats-*.json files in {}, crawl a window first",
+                path.display()
+            );
+        }
+        found.sort();
+        stats_paths.extend(found);
+    }
+
+    let mut reports = Vec::new();
+    for stats_path in &stats_paths {
+        let stats = stats::Stats::load_from_file(stats_path)?;
+        let stats_dir = stats_path.parent().unwrap_or_else(|| Path::new("."));
+        let (stats, avatars) = prepare_stats(&stats, stats_dir, render_options);
+        reports.push(commands::render::site::SiteReport { stats, avatars });
+    }
+
+    std::fs::create_dir_all(&output_dir).with_context(|| {
         format!(
             "Failed to create output directory: {}",
             output_dir.display()
         )
     })?;
+    for file in commands::render::site::render(&reports, render_options)? {
+        write_output(file, &output_dir)?;
+    }
+    Ok(())
+}
 
+/// Renders a report for each year and/or month of the latest life crawl.
+///
+/// The stats come from the room stats the life crawl stored for each year and
+/// month, so no window is crawled again.
+fn handle_render_all(
+    scopes: &[stats::ScopeKind],
+    user_id_flag: Option<String>,
+    formats: String,
+    output_dir: PathBuf,
+    render_options: &RenderOptions,
+) -> Result<()> {
+    let mut selector = account_selector::AccountSelector::new()?;
+    let accounts = selector.select_accounts(user_id_flag, false)?;
+    let (account_id, account_dir) = match accounts.as_slice() {
+        [] => anyhow::bail!("No accounts found. Use 'my login' first."),
+        [account] => account,
+        _ => anyhow::bail!(
+            "Multiple accounts found. Render-all requires exactly one account. \
+             Use --user-id to specify which account."
+        ),
+    };
+    if is_stdout(&output_dir) {
+        anyhow::bail!(
+            "render-all writes several reports and needs an output directory, not --output -"
+        );
+    }
+
+    let db = commands::crawl::db::CrawlDb::init(account_dir)?;
+    let mut windows = Vec::new();
+    for scope in scopes {
+        windows.extend(commands::crawl::periods::windows(&db, *scope)?);
+    }
+    if windows.is_empty() {
+        anyhow::bail!(
+            "No stats by year or month for {}: run 'my crawl life' first",
+            account_id
+        );
+    }
+
+    for window in &windows {
+        eprintln!("\n📝 Rendering {}...", window.key);
+        let stats = commands::crawl::periods::build(&db, account_id, window)?;
+        render_stats(&stats, account_dir, &output_dir, &formats, render_options)?;
+    }
+    eprintln!(
+        "\n✅ Done! {} report(s) rendered for {}",
+        windows.len(),
+        account_id
+    );
+    Ok(())
+}
+
+/// Renders the provisional reports of a running crawl until it completes.
+///
+/// Reads the stats of the rooms crawled so far from the crawl database, so it
+/// can run next to `my crawl`, and re-renders every `render_options.live`
+/// seconds.
+fn handle_live_render(
+    window: Option<String>,
+    user_id_flag: Option<String>,
+    formats: String,
+    output_dir: PathBuf,
+    render_options: &RenderOptions,
+) -> Result<()> {
+    let mut selector = account_selector::AccountSelector::new()?;
+    let accounts = selector.select_accounts(user_id_flag, false)?;
+    let (account_id, account_dir) = match accounts.as_slice() {
+        [] => anyhow::bail!("No accounts found. Use 'my login' first."),
+        [account] => account,
+        _ => anyhow::bail!(
+            "Multiple accounts found. Live render requires exactly one account. \
+             Use --user-id to specify which account."
+        ),
+    };
+    let refresh = render_options.live.unwrap_or_default();
+
+    loop {
+        let Some(live) =
+            commands::crawl::live::snapshot(account_id, account_dir, window.as_deref())?
+        else {
+            anyhow::bail!(
+                "No crawl {}recorded for {}: start one with 'my crawl <window>'",
+                window
+                    .as_deref()
+                    .map(|window| format!("of {} ", window))
+                    .unwrap_or_default(),
+                account_id
+            );
+        };
+        let crawl = &live.crawl;
+        if let Some(finished_ts) = crawl.finished_ts {
+            eprintln!(
+                "✅ Crawl of {} finished {}. Render its stats with: my render --stats {}",
+                crawl.window_key,
+                timefmt::format_timestamp(finished_ts),
+                account_dir
+                    .join(format!("stats-{}.json", crawl.window_key))
+                    .display()
+            );
+            return Ok(());
+        }
+
+        eprintln!(
+            "\n⏳ Crawl of {} started {}: {} of {} room(s) crawled",
+            crawl.window_key,
+            timefmt::format_timestamp(crawl.started_ts),
+            crawl.rooms_done,
+            crawl.rooms_total
+        );
+        render_stats(
+            &live.stats,
+            account_dir,
+            &output_dir,
+            &formats,
+            render_options,
+        )?;
+
+        if refresh == 0 {
+            return Ok(());
+        }
+        std::thread::sleep(std::time::Duration::from_secs(refresh));
+    }
+}
+
+/// Renders the reports of `stats`, read from (or saved to) `stats_dir`.
+fn render_stats(
+    stats: &stats::Stats,
+    stats_dir: &Path,
+    output_dir: &Path,
+    formats_arg: &str,
+    render_options: &RenderOptions,
+) -> Result<()> {
     let formats: Vec<&str> = if formats_arg.is_empty() {
-        vec!["md"]
+        vec!["md", "html"]
     } else {
         formats_arg.split(',').map(|s| s.trim()).collect()
     };
 
-    for format in formats {
-        match format {
-            "md" => {
-                let markdown = commands::render::md::render(stats)?;
-                let filename = default_md_filename(stats);
-                let output_path = output_dir.join(filename);
-                std::fs::write(&output_path, markdown)?;
-                eprintln!("📄 Markdown: {}", output_path.display());
+    if is_stdout(output_dir) {
+        if formats.len() != 1 {
+            anyhow::bail!(
+                "--output - writes a single report to standard output: \
+                 pick one format with --formats (e.g. --formats md)"
+            );
+        }
+        if render_options.per_room {
+            anyhow::bail!("--per-room writes one report per room and needs an output directory");
+        }
+    } else {
+        std::fs::create_dir_all(output_dir).with_context(|| {
+            format!(
+                "Failed to create output directory: {}",
+                output_dir.display()
+            )
+        })?;
+    }
+
+    let (stats, avatars) = prepare_stats(stats, stats_dir, render_options);
+    let avatars = &avatars;
+    let stats = &stats;
+
+    let suffix = if render_options.live.is_some() {
+        "-live"
+    } else {
+        ""
+    };
+    render_formats(stats, avatars, output_dir, &formats, render_options, suffix)?;
+
+    if render_options.per_room {
+        let breakdowns = stats.per_room.as_deref().unwrap_or_default();
+        if breakdowns.is_empty() {
+            eprintln!(
+                "⚠️  Warning: No per-room stats in this file, crawl with --per-room to record them"
+            );
+        }
+        let t = render_options.lang.locale();
+        for (i, breakdown) in breakdowns.iter().enumerate() {
+            let room_stats = commands::render::room_report(stats, breakdown, t);
+            let suffix = format!("{}-room-{}", suffix, i + 1);
+            render_formats(
+                &room_stats,
+                avatars,
+                output_dir,
+                &formats,
+                render_options,
+                &suffix,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies the render options to a copy of `stats` and loads its avatars from `stats_dir`.
+fn prepare_stats(
+    stats: &stats::Stats,
+    stats_dir: &Path,
+    render_options: &RenderOptions,
+) -> (stats::Stats, commands::render::Avatars) {
+    let mut stats = stats.clone();
+    let mut avatars = commands::render::Avatars::default();
+    match commands::render::Avatar::load(&stats, stats_dir) {
+        Ok(avatar) => avatars.account = avatar,
+        Err(e) => {
+            eprintln!("⚠️  Warning: {:#}", e);
+            stats.account.avatar_file = None;
+        }
+    }
+    let top_rooms = stats
+        .rooms
+        .iter_mut()
+        .flat_map(|rooms| rooms.top.iter_mut().flatten());
+    for room in top_rooms {
+        let Some(file_name) = room.avatar_file.clone() else {
+            continue;
+        };
+        match commands::render::Avatar::read(&file_name, stats_dir) {
+            Ok(avatar) => {
+                avatars.rooms.insert(file_name, avatar);
             }
-            _ => {
-                eprintln!("⚠️  Warning: Unknown format '{}', skipping", format);
+            Err(e) => {
+                eprintln!("⚠️  Warning: {:#}", e);
+                room.avatar_file = None;
             }
         }
     }
+    if render_options.golden {
+        // Before limiting, so that ties are cut the same way every time
+        commands::render::make_golden(&mut stats);
+    }
+    if let Some(top) = render_options.top {
+        commands::render::limit_rankings(&mut stats, top);
+    }
+    render_options.link_base.apply(&mut stats);
+    if render_options.compare_previous {
+        stats.previous = load_previous(&stats, stats_dir);
+    }
+    if !render_options.changelog {
+        stats.previous_crawl = None;
+    }
+    (stats, avatars)
+}
+
+/// Records in `stats` the headline numbers of the stats file at `stats_path` they
+/// replace, so that `--changelog` can tell what the new crawl discovered.
+fn record_previous_crawl(stats: &mut stats::Stats, stats_path: &Path) {
+    if !stats_path.exists() {
+        return;
+    }
+    match stats::Stats::load_from_file(stats_path) {
+        Ok(previous) => stats.previous_crawl = Some(stats::PreviousCrawl::of(&previous)),
+        Err(e) => eprintln!("⚠️  Warning: {:#}", e),
+    }
+}
+
+/// Headline numbers of the window before `stats`, from its stats file next to them.
+fn load_previous(stats: &stats::Stats, stats_dir: &Path) -> Option<stats::PreviousWindow> {
+    let previous = window::WindowScope::parse(&stats.scope.key)
+        .ok()?
+        .previous()?;
+    let path = stats_dir.join(format!("stats-{}.json", previous.key));
+    if !path.exists() {
+        eprintln!(
+            "⚠️  Warning: No stats for {} to compare with, crawl it with 'my crawl {}'",
+            previous.key, previous.key
+        );
+        return None;
+    }
+    match stats::Stats::load_from_file(&path) {
+        Ok(previous) => Some(stats::PreviousWindow::of(&previous)),
+        Err(e) => {
+            eprintln!("⚠️  Warning: {:#}", e);
+            None
+        }
+    }
+}
+
+/// Renders one report in each format; `suffix` is appended to file names.
+fn render_formats(
+    stats: &stats::Stats,
+    avatars: &commands::render::Avatars,
+    output_dir: &Path,
+    formats: &[&str],
+    render_options: &RenderOptions,
+    suffix: &str,
+) -> Result<()> {
+    let renderers = builtin_renderers(ReportContext {
+        options: render_options,
+        avatars,
+        suffix,
+    });
+    let mut owners = (!is_stdout(output_dir)).then(|| ReportOwners::load(output_dir));
+
+    for &format in formats {
+        let Some(renderer) = renderers
+            .iter()
+            .find(|renderer| renderer.format() == format)
+        else {
+            eprintln!("⚠️  Warning: Unknown format '{}', skipping", format);
+            continue;
+        };
+        let files = renderer.render(stats)?;
+        if is_stdout(output_dir) {
+            // Only the report itself: the avatar and compressed copies need a directory
+            if let Some(file) = files.into_iter().next() {
+                std::io::stdout().write_all(&file.contents)?;
+            }
+            continue;
+        }
+        for file in files {
+            if let (Some(owners), Some(name), Some(_)) = (&mut owners, &file.name, &file.label) {
+                let previous = owners.record(name, &stats.account.user_id);
+                // Live reports are meant to be replaced on every refresh
+                if let (Some(previous), None) = (previous, render_options.live) {
+                    eprintln!(
+                        "⚠️  Warning: Replacing {}, the report of {}. Set \
+                         \"filename_account\": \"always\" in the config or use --output-name \
+                         to keep both.",
+                        output_dir.join(name).display(),
+                        previous
+                    );
+                }
+            }
+            write_output(file, output_dir)?;
+        }
+    }
+    if let Some(owners) = owners {
+        owners.save();
+    }
 
     Ok(())
 }
 
-fn default_md_filename(stats: &stats::Stats) -> String {
-    match stats.scope.kind {
-        stats::ScopeKind::Year => format!("my-year-{}.md", stats.scope.key),
-        stats::ScopeKind::Month => format!("my-month-{}.md", stats.scope.key),
-        stats::ScopeKind::Week => format!("my-week-{}.md", stats.scope.key),
-        stats::ScopeKind::Day => format!("my-day-{}.md", stats.scope.key),
-        stats::ScopeKind::Life => "my-life.md".to_string(),
+/// File of an output directory recording the account each report is for.
+const REPORT_OWNERS_FILE: &str = ".my-reports.json";
+
+/// Accounts of the reports in an output directory, by file name, so that replacing
+/// another account's report can be told from re-rendering one's own.
+struct ReportOwners {
+    path: PathBuf,
+    owners: std::collections::BTreeMap<String, String>,
+}
+
+impl ReportOwners {
+    /// Loads the record of `output_dir`; missing or unreadable, it starts empty.
+    fn load(output_dir: &Path) -> Self {
+        let path = output_dir.join(REPORT_OWNERS_FILE);
+        let owners = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self { path, owners }
+    }
+
+    /// Records `name` as a report of `user_id`, returning the other account it
+    /// was a report of, if any.
+    fn record(&mut self, name: &str, user_id: &str) -> Option<String> {
+        self.owners
+            .insert(name.to_string(), user_id.to_string())
+            .filter(|previous| previous != user_id)
+    }
+
+    /// Writes the record back, warning when it cannot.
+    fn save(&self) {
+        let result = serde_json::to_string_pretty(&self.owners)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(std::fs::write(&self.path, json)?));
+        if let Err(e) = result {
+            eprintln!(
+                "⚠️  Warning: Failed to write {}: {:#}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Whether reports go to standard output (`--output -`) rather than into a directory.
+fn is_stdout(output_dir: &Path) -> bool {
+    output_dir == Path::new("-")
+}
+
+/// Writes a rendered file into `output_dir`, or to standard output when it has no name.
+fn write_output(file: commands::render::renderer::OutputFile, output_dir: &Path) -> Result<()> {
+    let Some(name) = file.name else {
+        std::io::stdout().write_all(&file.contents)?;
+        return Ok(());
+    };
+    let output_path = output_dir.join(name);
+    std::fs::write(&output_path, &file.contents)?;
+    if let Some(label) = file.label {
+        eprintln!("{}: {}", label, output_path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_help_subcommand_and_legacy_flag() {
+        let cli = Cli::try_parse_from(["my", "help", "crawl"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Help { topic: Some(ref topic) }) if topic == "crawl"
+        ));
+        let cli = Cli::try_parse_from(["my", "help"]).unwrap();
+        assert!(matches!(cli.command, Some(Commands::Help { topic: None })));
+
+        // Legacy form kept for scripts written against older versions
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            top_level_help(&args(&["my", "--help", "render"])),
+            Some("render")
+        );
+        assert_eq!(top_level_help(&args(&["my", "--help"])), Some(""));
+        assert_eq!(top_level_help(&args(&["my", "crawl", "--help"])), None);
+
+        // Each command has its own --help
+        let error = Cli::try_parse_from(["my", "status", "--help"])
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), clap::error::ErrorKind::DisplayHelp);
+
+        assert!(print_help("no-such-command").is_err());
+    }
+
+    #[test]
+    fn test_global_flags() {
+        let cli = Cli::try_parse_from(["my", "status", "--user-id", "@alice:example.org"]).unwrap();
+        assert_eq!(cli.user_id.as_deref(), Some("@alice:example.org"));
+        let cli =
+            Cli::try_parse_from(["my", "-o", "reports", "export", "ical", "--window", "2025"])
+                .unwrap();
+        assert_eq!(cli.output, Some(PathBuf::from("reports")));
+
+        let cli = Cli::try_parse_from(["my", "completions", "fish"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Completions {
+                shell: clap_complete::Shell::Fish
+            })
+        ));
+    }
+
+    #[test]
+    fn test_report_owners_tell_other_accounts() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut owners = ReportOwners::load(dir.path());
+        assert_eq!(owners.record("my-year-2025.md", "@alice:example.org"), None);
+        owners.save();
+
+        let mut owners = ReportOwners::load(dir.path());
+        // Re-rendering one's own report is not worth a warning
+        assert_eq!(owners.record("my-year-2025.md", "@alice:example.org"), None);
+        assert_eq!(
+            owners.record("my-year-2025.md", "@bob:example.org"),
+            Some("@alice:example.org".to_string())
+        );
+        assert_eq!(owners.record("my-year-2024.md", "@bob:example.org"), None);
     }
 }
@@ -0,0 +1,7 @@
+//! Stats file: the data model shared by the crawl and the renderers, and its
+//! validation against the JSON schema.
+pub mod model;
+#[cfg(test)]
+mod schema;
+
+pub use model::*;
@@ -0,0 +1,853 @@
+//! Data model of the stats file (see `docs/stats_spec.md`).
+//!
+//! Sections built by the crawl go through constructors that check the
+//! invariants of the schema (counts are not negative, percentages are within
+//! 0–100), so that invalid stats fail where they are built rather than when a
+//! renderer or another tool reads them.
+//!
+//! Checked fields are [`Count`]s and [`Percentage`]s, which can only hold
+//! valid values: struct literals have to build them through [`Count::new`] and
+//! [`Percentage::new`], and loading a stats file fails on invalid ones.
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// Version of the stats format written by this build (`schema_version`).
+pub const SCHEMA_VERSION: i32 = 1;
+
+/// Entries kept in each ranking (top rooms, emojis, messages) by default.
+pub const DEFAULT_TOP: usize = 5;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Stats {
+    pub schema_version: i32,
+    pub scope: Scope,
+    pub generated_at: String,
+    /// Tool and version that built the stats (e.g. `my 0.1.0`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generator: Option<String>,
+    /// Timezone activity was bucketed in (e.g. `Europe/Paris`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    pub account: Account,
+    pub coverage: Coverage,
+    pub summary: Summary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity: Option<Activity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rooms: Option<Rooms>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reactions: Option<Reactions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_rooms: Option<CreatedRooms>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fun: Option<Fun>,
+    /// Who the user talked with, unless crawled with `--no-people`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub people: Option<People>,
+    /// Whom the user wrote to the most in DMs, unless crawled with `--no-people`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dms: Option<Dms>,
+    /// Where the user's messages went: threads or the main timeline
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversations: Option<Conversations>,
+    /// How long the user's text messages were, and the longest of them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_lengths: Option<MessageLengths>,
+    /// Emoji the user typed in their messages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typed_emojis: Option<TypedEmojis>,
+    /// Breakdowns of the top rooms, recorded when crawling with `--per-room`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_room: Option<Vec<RoomBreakdown>>,
+    /// Headline numbers of the preceding window, added at render time by `--compare-previous`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<PreviousWindow>,
+    /// Headline numbers of the stats file of the same window this one replaced,
+    /// recorded when the window is crawled again
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_crawl: Option<PreviousCrawl>,
+}
+
+impl Stats {
+    /// Stats of a window generated today by this build, without any optional
+    /// section; sections are added with the `with_*` methods or by the metric
+    /// collectors.
+    pub fn new(scope: Scope, account: Account, coverage: Coverage, summary: Summary) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            scope,
+            generated_at: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            generator: Some(format!("my {}", env!("CARGO_PKG_VERSION"))),
+            timezone: None,
+            account,
+            coverage,
+            summary,
+            activity: None,
+            rooms: None,
+            reactions: None,
+            created_rooms: None,
+            fun: None,
+            people: None,
+            dms: None,
+            conversations: None,
+            message_lengths: None,
+            typed_emojis: None,
+            per_room: None,
+            previous: None,
+            previous_crawl: None,
+        }
+    }
+
+    pub fn with_timezone(mut self, timezone: String) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    pub fn with_activity(mut self, activity: Option<Activity>) -> Self {
+        self.activity = activity;
+        self
+    }
+
+    pub fn with_rooms(mut self, rooms: Option<Rooms>) -> Self {
+        self.rooms = rooms;
+        self
+    }
+
+    pub fn with_created_rooms(mut self, created_rooms: Option<CreatedRooms>) -> Self {
+        self.created_rooms = created_rooms;
+        self
+    }
+
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read stats file: {}", path.display()))?;
+
+        let stats: Stats = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse JSON from: {}", path.display()))?;
+
+        Ok(stats)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScopeKind {
+    Year,
+    Quarter,
+    Month,
+    Week,
+    Day,
+    Rolling,
+    Life,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Scope {
+    #[serde(rename = "type")]
+    pub kind: ScopeKind,
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl Scope {
+    pub fn new(kind: ScopeKind, key: &str) -> Self {
+        Self {
+            kind,
+            key: key.to_string(),
+            label: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Account {
+    pub user_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
+    /// Avatar image file, next to the stats file (e.g. `avatar-abc123.png`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_file: Option<String>,
+    /// Account creation date (YYYY-MM-DD), when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    pub rooms_total: Count,
+}
+
+impl Account {
+    pub fn new(user_id: &str, rooms_total: i32) -> Result<Self> {
+        Ok(Self {
+            user_id: user_id.to_string(),
+            display_name: None,
+            avatar_url: None,
+            avatar_file: None,
+            created_at: None,
+            rooms_total: Count::new("account.rooms_total", rooms_total)?,
+        })
+    }
+
+    pub fn with_display_name(mut self, display_name: Option<String>) -> Self {
+        self.display_name = display_name;
+        self
+    }
+
+    pub fn with_avatar_url(mut self, avatar_url: Option<String>) -> Self {
+        self.avatar_url = avatar_url;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Coverage {
+    pub from: String,
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_active: Option<Count>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_joined_rooms: Option<Count>,
+}
+
+impl Coverage {
+    /// Coverage from and to dates (YYYY-MM-DD); `days_active` and
+    /// `since_joined_rooms` are left out when 0.
+    pub fn new(
+        from: String,
+        to: String,
+        days_active: i32,
+        since_joined_rooms: i32,
+    ) -> Result<Self> {
+        Ok(Self {
+            from,
+            to,
+            days_active: optional_count("coverage.days_active", days_active)?,
+            since_joined_rooms: optional_count("coverage.since_joined_rooms", since_joined_rooms)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Summary {
+    pub messages_sent: Count,
+    pub active_rooms: Count,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dm_rooms: Option<Count>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_rooms: Option<Count>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_rooms: Option<Count>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peaks: Option<Peaks>,
+}
+
+impl Summary {
+    pub fn new(messages_sent: i32, active_rooms: i32) -> Result<Self> {
+        Ok(Self {
+            messages_sent: Count::new("summary.messages_sent", messages_sent)?,
+            active_rooms: Count::new("summary.active_rooms", active_rooms)?,
+            dm_rooms: None,
+            public_rooms: None,
+            private_rooms: None,
+            peaks: None,
+        })
+    }
+
+    /// Active rooms of each type, left out when 0.
+    pub fn with_room_types(mut self, dm: i32, public: i32, private: i32) -> Result<Self> {
+        self.dm_rooms = optional_count("summary.dm_rooms", dm)?;
+        self.public_rooms = optional_count("summary.public_rooms", public)?;
+        self.private_rooms = optional_count("summary.private_rooms", private)?;
+        Ok(self)
+    }
+
+    pub fn with_peaks(mut self, peaks: Option<Peaks>) -> Self {
+        self.peaks = peaks;
+        self
+    }
+}
+
+/// Headline numbers of the window before the report's one, to show deltas against.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PreviousWindow {
+    /// Window key (e.g. `2024` for a 2025 report)
+    pub key: String,
+    pub messages_sent: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_active: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reactions: Option<i32>,
+}
+
+impl PreviousWindow {
+    pub fn of(stats: &Stats) -> Self {
+        Self {
+            key: stats.scope.key.clone(),
+            messages_sent: stats.summary.messages_sent.get(),
+            days_active: stats.coverage.days_active.map(Count::get),
+            reactions: stats
+                .reactions
+                .as_ref()
+                .and_then(|reactions| reactions.total),
+        }
+    }
+}
+
+/// Headline numbers of an earlier crawl of the same window, to tell what a new
+/// crawl discovered.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PreviousCrawl {
+    /// Generation date of the replaced stats
+    pub generated_at: String,
+    pub messages_sent: i32,
+}
+
+impl PreviousCrawl {
+    pub fn of(stats: &Stats) -> Self {
+        Self {
+            generated_at: stats.generated_at.clone(),
+            messages_sent: stats.summary.messages_sent.get(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MessagesByRoomType {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dm: Option<Count>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private: Option<Count>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public: Option<Count>,
+}
+
+impl MessagesByRoomType {
+    /// Messages sent in each room type, left out when 0.
+    pub fn new(dm: i32, public: i32, private: i32) -> Result<Self> {
+        Ok(Self {
+            dm: optional_count("rooms.messages_by_room_type.dm", dm)?,
+            private: optional_count("rooms.messages_by_room_type.private", private)?,
+            public: optional_count("rooms.messages_by_room_type.public", public)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Peaks {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<PeakYear>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub month: Option<PeakMonth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub week: Option<PeakWeek>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day: Option<PeakDay>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hour: Option<PeakHour>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PeakMonth {
+    pub month: String,
+    pub messages: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PeakYear {
+    pub year: String,
+    pub messages: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PeakWeek {
+    pub week: String,
+    pub messages: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PeakDay {
+    pub day: String,
+    pub messages: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PeakHour {
+    pub hour: String,
+    pub messages: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Activity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_month: Option<HashMap<String, i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_week: Option<HashMap<String, i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_weekday: Option<HashMap<String, i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_hour: Option<HashMap<String, i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_day: Option<HashMap<String, i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_year: Option<HashMap<String, i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_weekday_hour: Option<HashMap<String, HashMap<String, i32>>>,
+    /// Hour profile of each room type (`dm`, `public`, `private`), then hour (`00`–`23`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_room_type_hour: Option<HashMap<String, HashMap<String, i32>>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Rooms {
+    pub total: Count,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top: Option<Vec<RoomEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages_by_room_type: Option<MessagesByRoomType>,
+    /// Messages of each room type (`dm`, `public`, `private`) by kind (`text`, `media`, `voice`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages_by_room_type_kind: Option<HashMap<String, HashMap<String, i32>>>,
+    /// Messages sent and received in the busiest DMs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dm_balance: Option<Vec<DmBalanceEntry>>,
+    /// Messages sent by homeserver of the rooms, busiest first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_server: Option<Vec<ServerEntry>>,
+}
+
+impl Rooms {
+    pub fn new(total: i32) -> Result<Self> {
+        Ok(Self {
+            total: Count::new("rooms.total", total)?,
+            top: None,
+            messages_by_room_type: None,
+            messages_by_room_type_kind: None,
+            dm_balance: None,
+            by_server: None,
+        })
+    }
+
+    /// Top rooms, left out when empty.
+    pub fn with_top(mut self, top: Vec<RoomEntry>) -> Self {
+        self.top = (!top.is_empty()).then_some(top);
+        self
+    }
+
+    /// Messages by homeserver, left out when empty.
+    pub fn with_by_server(mut self, by_server: Vec<ServerEntry>) -> Self {
+        self.by_server = (!by_server.is_empty()).then_some(by_server);
+        self
+    }
+
+    pub fn with_messages_by_room_type(mut self, messages: MessagesByRoomType) -> Self {
+        self.messages_by_room_type = Some(messages);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RoomEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub messages: Count,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percentage: Option<Percentage>,
+    pub permalink: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub joined_at: Option<String>,
+    /// Room avatar image file, next to the stats file (e.g. `room-avatar-abc123.png`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trend: Option<RoomTrend>,
+}
+
+impl RoomEntry {
+    pub fn new(permalink: String, messages: i32) -> Result<Self> {
+        Ok(Self {
+            name: None,
+            messages: Count::new("rooms.top.messages", messages)?,
+            percentage: None,
+            permalink,
+            joined_at: None,
+            avatar_file: None,
+            trend: None,
+        })
+    }
+
+    pub fn with_name(mut self, name: Option<String>) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Share of the messages sent, in percent (0–100).
+    pub fn with_percentage(mut self, percentage: f64) -> Result<Self> {
+        self.percentage = Some(Percentage::new("rooms.top.percentage", percentage)?);
+        Ok(self)
+    }
+
+    /// Day the user joined the room (YYYY-MM-DD), when history visibility starts there.
+    pub fn with_joined_at(mut self, joined_at: Option<String>) -> Self {
+        self.joined_at = joined_at;
+        self
+    }
+
+    pub fn with_trend(mut self, trend: Option<RoomTrend>) -> Self {
+        self.trend = trend;
+        self
+    }
+}
+
+/// Messages the user sent in a room in the first and the second half of the
+/// window, to tell growing conversations from fading ones.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct RoomTrend {
+    pub first_half: Count,
+    pub second_half: Count,
+}
+
+impl RoomTrend {
+    pub fn new(first_half: i32, second_half: i32) -> Result<Self> {
+        Ok(Self {
+            first_half: Count::new("rooms.top.trend.first_half", first_half)?,
+            second_half: Count::new("rooms.top.trend.second_half", second_half)?,
+        })
+    }
+}
+
+/// Messages the user sent in the rooms of a homeserver, the server part of
+/// their room ID (e.g. `matrix.org`).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ServerEntry {
+    pub server: String,
+    pub messages: Count,
+    /// Share of all messages sent
+    pub percentage: Percentage,
+}
+
+impl ServerEntry {
+    pub fn new(server: String, messages: i32, percentage: f64) -> Result<Self> {
+        Ok(Self {
+            server,
+            messages: Count::new("rooms.by_server.messages", messages)?,
+            percentage: Percentage::new("rooms.by_server.percentage", percentage)?,
+        })
+    }
+}
+
+/// Conversation balance of a DM: messages the user sent and received there.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DmBalanceEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub sent: i32,
+    pub received: i32,
+    pub permalink: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Reactions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_emojis: Option<Vec<EmojiEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_messages: Option<Vec<MessageReactionEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_rooms: Option<Vec<RoomReactionEntry>>,
+    /// Reactions received by month of the reaction (`01`–`12`), local time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_month: Option<HashMap<String, i32>>,
+    /// Reactions the user sent, the other way round
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given: Option<GivenReactions>,
+}
+
+/// Reactions the user sent to messages.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GivenReactions {
+    /// Reactions sent in all, ranked or not
+    pub total: i32,
+    pub top_emojis: Vec<EmojiEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EmojiEntry {
+    /// Most used form of the emoji, or the mxc URL of a custom emote
+    pub emoji: String,
+    pub count: i32,
+    /// Shortcode of a custom emote, without colons, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shortcode: Option<String>,
+    /// Name of the emote pack of a custom emote, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack: Option<String>,
+    /// Other forms counted with it (skin tones, variation selectors)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Vec<String>>,
+}
+
+impl EmojiEntry {
+    /// Whether the reaction is a custom emote (an image) rather than an emoji.
+    pub fn is_custom(&self) -> bool {
+        self.emoji.starts_with("mxc://")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MessageReactionEntry {
+    pub permalink: String,
+    pub reaction_count: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RoomReactionEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub reaction_count: i32,
+    pub permalink: String,
+}
+
+/// Messages, peaks, activity and reactions of a single room.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RoomBreakdown {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub permalink: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_active: Option<Count>,
+    pub summary: Summary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity: Option<Activity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reactions: Option<Reactions>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreatedRooms {
+    pub total: Count,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dm_rooms: Option<Count>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_rooms: Option<Count>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_rooms: Option<Count>,
+}
+
+impl CreatedRooms {
+    /// Rooms created by the user; the counts by type are left out when 0.
+    pub fn new(total: i32, dm: i32, public: i32, private: i32) -> Result<Self> {
+        Ok(Self {
+            total: Count::new("created_rooms.total", total)?,
+            dm_rooms: optional_count("created_rooms.dm_rooms", dm)?,
+            public_rooms: optional_count("created_rooms.public_rooms", public)?,
+            private_rooms: optional_count("created_rooms.private_rooms", private)?,
+        })
+    }
+}
+
+/// People the user talked with the most, by Matrix user ID.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct People {
+    /// The other party of the user's DMs, by messages exchanged
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dm_partners: Option<Vec<PersonEntry>>,
+    /// Other members of the user's group rooms, by messages sent there
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_senders: Option<Vec<PersonEntry>>,
+}
+
+/// The other party of the user's DMs, by messages the user sent them.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Dms {
+    /// People the user wrote to in DMs, ranked or not
+    pub total: i32,
+    pub top: Vec<PersonEntry>,
+}
+
+/// The user's messages in threads and in the main timeline, and their replies.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Conversations {
+    /// Messages sent in the main timeline of rooms
+    pub top_level: i32,
+    /// Messages sent inside threads
+    pub in_threads: i32,
+    /// Distinct threads the user posted in
+    pub threads: i32,
+    /// Messages replying to another one (`m.in_reply_to`), in threads or not
+    pub replies: i32,
+}
+
+/// The user's text messages by length, in characters of their body.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MessageLengths {
+    /// Messages of at most 20 characters
+    pub short: i32,
+    /// Messages of 21 to 140 characters
+    pub medium: i32,
+    /// Messages of more than 140 characters
+    pub long: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longest: Option<LongestMessage>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LongestMessage {
+    pub chars: i32,
+    pub permalink: String,
+}
+
+/// Emoji typed in the user's text messages, as opposed to reactions.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TypedEmojis {
+    /// Emoji typed in all, ranked or not
+    pub total: i32,
+    pub top: Vec<EmojiEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PersonEntry {
+    /// Absent for DM partners named by their initials (`dm_names` setting)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    pub messages: i32,
+    /// Group rooms shared with the user (group senders only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rooms: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Fun {
+    #[serde(flatten)]
+    pub fields: IndexMap<String, serde_json::Value>,
+}
+
+/// A count of the stats file, never negative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(try_from = "i32", into = "i32")]
+pub struct Count(i32);
+
+impl Count {
+    /// `value` as the `field` count, an error when negative.
+    pub fn new(field: &str, value: i32) -> Result<Self> {
+        if value < 0 {
+            bail!("Invalid stats: {} is negative ({})", field, value);
+        }
+        Ok(Self(value))
+    }
+
+    pub fn get(self) -> i32 {
+        self.0
+    }
+}
+
+impl TryFrom<i32> for Count {
+    type Error = String;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        Count::new("count", value).map_err(|e| e.to_string())
+    }
+}
+
+impl From<Count> for i32 {
+    fn from(count: Count) -> i32 {
+        count.0
+    }
+}
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A percentage of the stats file, within 0–100.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Deserialize, Serialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Percentage(f64);
+
+impl Percentage {
+    /// `value` as the `field` percentage, an error outside 0–100.
+    pub fn new(field: &str, value: f64) -> Result<Self> {
+        if !(0.0..=100.0).contains(&value) {
+            bail!("Invalid stats: {} is not a percentage ({})", field, value);
+        }
+        Ok(Self(value))
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for Percentage {
+    type Error = String;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Percentage::new("percentage", value).map_err(|e| e.to_string())
+    }
+}
+
+impl From<Percentage> for f64 {
+    fn from(percentage: Percentage) -> f64 {
+        percentage.0
+    }
+}
+
+/// [`Count::new`], for counts left out of the stats when 0 (private).
+fn optional_count(field: &str, value: i32) -> Result<Option<Count>> {
+    Ok(Some(Count::new(field, value)?).filter(|count| count.get() > 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constructors_check_invariants() {
+        assert!(Summary::new(-1, 0).is_err());
+        assert!(Summary::new(10, 2)
+            .unwrap()
+            .with_room_types(1, 0, -1)
+            .is_err());
+        assert!(Account::new("@alice:example.org", -3).is_err());
+        assert!(CreatedRooms::new(1, -1, 0, 0).is_err());
+
+        let room = RoomEntry::new("https://matrix.to/#/!room".to_string(), 5).unwrap();
+        assert!(room.clone().with_percentage(100.5).is_err());
+        assert!(room.clone().with_percentage(f64::NAN).is_err());
+        assert_eq!(
+            room.with_percentage(42.0)
+                .unwrap()
+                .percentage
+                .map(Percentage::get),
+            Some(42.0)
+        );
+    }
+
+    #[test]
+    fn test_zero_counts_are_left_out() {
+        let summary = Summary::new(10, 2)
+            .unwrap()
+            .with_room_types(2, 0, 0)
+            .unwrap();
+        assert_eq!(summary.dm_rooms.map(Count::get), Some(2));
+        assert_eq!(summary.public_rooms, None);
+
+        let coverage = Coverage::new("2025-01-01".into(), "2025-12-31".into(), 0, 3).unwrap();
+        assert_eq!(coverage.days_active, None);
+        assert_eq!(coverage.since_joined_rooms.map(Count::get), Some(3));
+
+        assert!(Rooms::new(2).unwrap().with_top(Vec::new()).top.is_none());
+    }
+}
@@ -0,0 +1,452 @@
+//! Validation of stats files against the JSON schema (`docs/stats_schema.json`),
+//! used by tests to keep the model, the examples and the schema in sync.
+use anyhow::{anyhow, bail, Context, Result};
+use jsonschema::{Draft, JSONSchema};
+use std::path::Path;
+
+use super::model::Stats;
+
+/// Validate stats JSON against the JSON schema
+pub fn validate(stats_json: &serde_json::Value, schema: &JSONSchema) -> Result<()> {
+    match schema.validate(stats_json) {
+        Ok(_) => Ok(()),
+        Err(errors) => {
+            let error_messages: Vec<String> = errors
+                .map(|e| format!("  - {}: {}", e.instance_path, e))
+                .collect();
+            bail!("Stats validation failed:\n{}", error_messages.join("\n"))
+        }
+    }
+}
+
+/// Load and compile the JSON schema
+pub fn load_schema(schema_path: &Path) -> Result<JSONSchema> {
+    let schema_content = std::fs::read_to_string(schema_path)
+        .with_context(|| format!("Failed to read schema file: {}", schema_path.display()))?;
+
+    let schema_json: serde_json::Value =
+        serde_json::from_str(&schema_content).with_context(|| {
+            format!(
+                "Failed to parse schema JSON from: {}",
+                schema_path.display()
+            )
+        })?;
+
+    JSONSchema::options()
+        .with_draft(Draft::Draft7)
+        .compile(&schema_json)
+        .map_err(|e| anyhow!("Failed to compile JSON schema: {}", e))
+}
+
+/// Load stats from file and validate against schema
+pub fn load_and_validate(stats_path: &Path, schema_path: &Path) -> Result<Stats> {
+    // Load stats JSON
+    let stats_content = std::fs::read_to_string(stats_path)
+        .with_context(|| format!("Failed to read stats file: {}", stats_path.display()))?;
+
+    let stats_json: serde_json::Value = serde_json::from_str(&stats_content)
+        .with_context(|| format!("Failed to parse stats JSON from: {}", stats_path.display()))?;
+
+    // Load and compile schema
+    let schema = load_schema(schema_path)?;
+
+    // Validate
+    validate(&stats_json, &schema)?;
+
+    // Deserialize to Stats struct
+    let stats: Stats = serde_json::from_value(stats_json)
+        .with_context(|| format!("Failed to deserialize stats from: {}", stats_path.display()))?;
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::model::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn get_schema_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("docs/stats_schema.json")
+    }
+
+    fn get_example_stats_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("examples/stats/example-stats.json")
+    }
+
+    #[test]
+    fn test_load_schema() {
+        let schema_path = get_schema_path();
+        let result = load_schema(&schema_path);
+        assert!(result.is_ok(), "Failed to load schema: {:?}", result.err());
+    }
+
+    #[test]
+    fn test_validate_example_stats() {
+        let schema_path = get_schema_path();
+        let stats_path = get_example_stats_path();
+
+        // Load and validate
+        let result = load_and_validate(&stats_path, &schema_path);
+        assert!(
+            result.is_ok(),
+            "Example stats validation failed: {:?}",
+            result.err()
+        );
+
+        // Verify the loaded stats
+        let stats = result.unwrap();
+        assert_eq!(stats.schema_version, 1);
+        assert_eq!(stats.scope.kind, ScopeKind::Year);
+        assert_eq!(stats.scope.key, "2025");
+    }
+
+    #[test]
+    fn test_validate_per_room() {
+        let schema_path = get_schema_path();
+        let schema = load_schema(&schema_path).expect("Failed to load schema");
+
+        let mut stats = json!({
+            "schema_version": 1,
+            "scope": {"type": "year", "key": "2025"},
+            "generated_at": "2025-12-31",
+            "account": {
+                "user_id": "@test:example.org",
+                "rooms_total": 10
+            },
+            "coverage": {
+                "from": "2025-01-01",
+                "to": "2025-12-31"
+            },
+            "summary": {
+                "messages_sent": 100,
+                "active_rooms": 5
+            },
+            "per_room": [{
+                "name": "Friends",
+                "permalink": "https://matrix.to/#/!abc:example.org",
+                "days_active": 12,
+                "summary": {
+                    "messages_sent": 60,
+                    "active_rooms": 1
+                },
+                "reactions": {"total": 4}
+            }]
+        });
+        let result = validate(&stats, &schema);
+        assert!(
+            result.is_ok(),
+            "Per-room stats should validate: {:?}",
+            result
+        );
+
+        // Breakdowns reuse the account-level section schemas
+        stats["per_room"][0]["summary"]["messages_sent"] = json!(-1);
+        assert!(validate(&stats, &schema).is_err());
+    }
+
+    #[test]
+    fn test_validate_missing_required_field() {
+        let schema_path = get_schema_path();
+        let schema = load_schema(&schema_path).expect("Failed to load schema");
+
+        // Missing 'scope' field
+        let invalid_stats = json!({
+            "schema_version": 1,
+            "generated_at": "2025-12-31",
+            "account": {
+                "user_id": "@test:example.org",
+                "rooms_total": 10
+            },
+            "coverage": {
+                "from": "2025-01-01",
+                "to": "2025-12-31"
+            },
+            "summary": {
+                "messages_sent": 100,
+                "active_rooms": 5
+            }
+        });
+
+        let result = validate(&invalid_stats, &schema);
+        assert!(
+            result.is_err(),
+            "Should fail validation for missing 'scope'"
+        );
+        let err_msg = format!("{:?}", result.err().unwrap());
+        assert!(
+            err_msg.contains("scope"),
+            "Error should mention missing field"
+        );
+    }
+
+    #[test]
+    fn test_validate_invalid_date_format() {
+        let schema_path = get_schema_path();
+        let schema = load_schema(&schema_path).expect("Failed to load schema");
+
+        // Invalid date format
+        let invalid_stats = json!({
+            "schema_version": 1,
+            "scope": {"type": "year", "key": "2025"},
+            "generated_at": "not-a-date",
+            "account": {
+                "user_id": "@test:example.org",
+                "rooms_total": 10
+            },
+            "coverage": {
+                "from": "2025-01-01",
+                "to": "2025-12-31"
+            },
+            "summary": {
+                "messages_sent": 100,
+                "active_rooms": 5
+            }
+        });
+
+        let result = validate(&invalid_stats, &schema);
+        assert!(
+            result.is_err(),
+            "Should fail validation for invalid date format"
+        );
+    }
+
+    #[test]
+    fn test_validate_negative_count() {
+        let schema_path = get_schema_path();
+        let schema = load_schema(&schema_path).expect("Failed to load schema");
+
+        // Negative messages_sent
+        let invalid_stats = json!({
+            "schema_version": 1,
+            "scope": {"type": "year", "key": "2025"},
+            "generated_at": "2025-12-31",
+            "account": {
+                "user_id": "@test:example.org",
+                "rooms_total": 10
+            },
+            "coverage": {
+                "from": "2025-01-01",
+                "to": "2025-12-31"
+            },
+            "summary": {
+                "messages_sent": -100,
+                "active_rooms": 5
+            }
+        });
+
+        let result = validate(&invalid_stats, &schema);
+        assert!(result.is_err(), "Should fail validation for negative count");
+    }
+
+    #[test]
+    fn test_validate_additional_properties() {
+        let schema_path = get_schema_path();
+        let schema = load_schema(&schema_path).expect("Failed to load schema");
+
+        // Extra field in account object
+        let invalid_stats = json!({
+            "schema_version": 1,
+            "scope": {"type": "year", "key": "2025"},
+            "generated_at": "2025-12-31",
+            "account": {
+                "user_id": "@test:example.org",
+                "rooms_total": 10,
+                "unexpected_field": "should fail"
+            },
+            "coverage": {
+                "from": "2025-01-01",
+                "to": "2025-12-31"
+            },
+            "summary": {
+                "messages_sent": 100,
+                "active_rooms": 5
+            }
+        });
+
+        let result = validate(&invalid_stats, &schema);
+        assert!(
+            result.is_err(),
+            "Should fail validation for additional properties"
+        );
+    }
+
+    #[test]
+    fn test_validate_percentage_range() {
+        let schema_path = get_schema_path();
+        let schema = load_schema(&schema_path).expect("Failed to load schema");
+
+        // Percentage > 100
+        let invalid_stats = json!({
+            "schema_version": 1,
+            "scope": {"type": "year", "key": "2025"},
+            "generated_at": "2025-12-31",
+            "account": {
+                "user_id": "@test:example.org",
+                "rooms_total": 10
+            },
+            "coverage": {
+                "from": "2025-01-01",
+                "to": "2025-12-31"
+            },
+            "summary": {
+                "messages_sent": 100,
+                "active_rooms": 5
+            },
+            "rooms": {
+                "total": 3,
+                "top": [
+                    {
+                        "messages": 50,
+                        "percentage": 150.0,
+                        "permalink": "https://matrix.to/#/!room:test/$evt"
+                    }
+                ]
+            }
+        });
+
+        let result = validate(&invalid_stats, &schema);
+        assert!(
+            result.is_err(),
+            "Should fail validation for percentage > 100"
+        );
+    }
+
+    #[test]
+    fn test_validate_scope_types() {
+        let schema_path = get_schema_path();
+        let schema = load_schema(&schema_path).expect("Failed to load schema");
+
+        // Invalid scope type
+        let invalid_stats = json!({
+            "schema_version": 1,
+            "scope": {"type": "invalid", "key": "2025"},
+            "generated_at": "2025-12-31",
+            "account": {
+                "user_id": "@test:example.org",
+                "rooms_total": 10
+            },
+            "coverage": {
+                "from": "2025-01-01",
+                "to": "2025-12-31"
+            },
+            "summary": {
+                "messages_sent": 100,
+                "active_rooms": 5
+            }
+        });
+
+        let result = validate(&invalid_stats, &schema);
+        assert!(
+            result.is_err(),
+            "Should fail validation for invalid scope type"
+        );
+    }
+
+    /// Stats with every section checked by the model constructors, built
+    /// through them.
+    fn constructed_stats() -> serde_json::Value {
+        let room = RoomEntry::new("https://matrix.to/#/!abc:example.org".to_string(), 60)
+            .unwrap()
+            .with_name(Some("Friends".to_string()))
+            .with_percentage(60.0)
+            .unwrap()
+            .with_trend(Some(RoomTrend::new(20, 35).unwrap()));
+        let rooms = Rooms::new(5)
+            .unwrap()
+            .with_top(vec![room])
+            .with_messages_by_room_type(MessagesByRoomType::new(40, 0, 60).unwrap());
+        let stats = Stats::new(
+            Scope::new(ScopeKind::Year, "2025"),
+            Account::new("@test:example.org", 10).unwrap(),
+            Coverage::new("2025-01-01".to_string(), "2025-12-31".to_string(), 42, 1).unwrap(),
+            Summary::new(100, 5)
+                .unwrap()
+                .with_room_types(2, 0, 3)
+                .unwrap(),
+        )
+        .with_timezone("Europe/Paris".to_string())
+        .with_rooms(Some(rooms))
+        .with_created_rooms(Some(CreatedRooms::new(1, 0, 1, 0).unwrap()));
+        serde_json::to_value(stats).unwrap()
+    }
+
+    #[test]
+    fn test_constructed_stats_match_schema() {
+        let schema = load_schema(&get_schema_path()).expect("Failed to load schema");
+        let result = validate(&constructed_stats(), &schema);
+        assert!(result.is_ok(), "Constructed stats failed: {:?}", result);
+    }
+
+    #[test]
+    fn test_example_stats_round_trip() {
+        // Every field of the example is known to the model and written back as the schema expects
+        let schema = load_schema(&get_schema_path()).expect("Failed to load schema");
+        let stats = Stats::load_from_file(&get_example_stats_path()).unwrap();
+        let result = validate(&serde_json::to_value(stats).unwrap(), &schema);
+        assert!(result.is_ok(), "Round trip failed: {:?}", result);
+    }
+
+    /// Generates one test per invariant shared by the schema and the model
+    /// constructors: the schema rejects `value` at `pointer` of the
+    /// constructed stats, and so does the constructor of that field.
+    macro_rules! invariant_tests {
+        ($($name:ident: $pointer:literal = $value:expr => $constructor:expr;)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let schema = load_schema(&get_schema_path()).expect("Failed to load schema");
+                    let mut stats = constructed_stats();
+                    *stats
+                        .pointer_mut($pointer)
+                        .expect("pointer into the constructed stats") = json!($value);
+                    assert!(
+                        validate(&stats, &schema).is_err(),
+                        "The schema accepts {} at {}",
+                        $value,
+                        $pointer
+                    );
+                    assert!(
+                        $constructor.is_err(),
+                        "The model accepts {} at {}",
+                        $value,
+                        $pointer
+                    );
+                    assert!(
+                        serde_json::from_value::<Stats>(stats).is_err(),
+                        "A stats file loads with {} at {}",
+                        $value,
+                        $pointer
+                    );
+                }
+            )*
+        };
+    }
+
+    invariant_tests! {
+        test_invariant_messages_sent: "/summary/messages_sent" = -1 => Summary::new(-1, 5);
+        test_invariant_active_rooms: "/summary/active_rooms" = -1 => Summary::new(100, -1);
+        test_invariant_dm_rooms: "/summary/dm_rooms" = -1
+            => Summary::new(100, 5).and_then(|summary| summary.with_room_types(-1, 0, 3));
+        test_invariant_rooms_total: "/account/rooms_total" = -1
+            => Account::new("@test:example.org", -1);
+        test_invariant_days_active: "/coverage/days_active" = -1
+            => Coverage::new("2025-01-01".to_string(), "2025-12-31".to_string(), -1, 1);
+        test_invariant_rooms: "/rooms/total" = -1 => Rooms::new(-1);
+        test_invariant_room_messages: "/rooms/top/0/messages" = -1
+            => RoomEntry::new("https://matrix.to/#/!abc:example.org".to_string(), -1);
+        test_invariant_room_percentage: "/rooms/top/0/percentage" = 150.0
+            => RoomEntry::new("https://matrix.to/#/!abc:example.org".to_string(), 60)
+                .and_then(|room| room.with_percentage(150.0));
+        test_invariant_negative_percentage: "/rooms/top/0/percentage" = -0.5
+            => RoomEntry::new("https://matrix.to/#/!abc:example.org".to_string(), 60)
+                .and_then(|room| room.with_percentage(-0.5));
+        test_invariant_room_trend: "/rooms/top/0/trend/second_half" = -1
+            => RoomTrend::new(20, -1);
+        test_invariant_messages_by_room_type: "/rooms/messages_by_room_type/dm" = -1
+            => MessagesByRoomType::new(-1, 0, 60);
+        test_invariant_created_rooms: "/created_rooms/total" = -1
+            => CreatedRooms::new(-1, 0, 1, 0);
+    }
+}
@@ -8,7 +8,136 @@ pub fn format_timestamp(ts_millis: i64) -> String {
     }
 }
 
+/// Format the UTC day of a millisecond timestamp, or return a placeholder on error.
+pub fn format_date(ts_millis: i64) -> String {
+    match Utc.timestamp_millis_opt(ts_millis) {
+        chrono::LocalResult::Single(datetime) => datetime.format("%Y-%m-%d").to_string(),
+        _ => "invalid timestamp".to_string(),
+    }
+}
+
 /// Format an optional millisecond timestamp, using '-' when missing.
 pub fn format_timestamp_opt(ts: Option<i64>) -> String {
     ts.map(format_timestamp).unwrap_or_else(|| "-".to_string())
 }
+
+/// Words used to spell durations, supplied by the report locale.
+///
+/// Templates take the count as `{n}`.
+#[derive(Debug)]
+pub struct DurationUnits {
+    /// Durations under a minute (e.g. "45 seconds")
+    pub seconds: &'static str,
+    pub sec: &'static str,
+    pub min: &'static str,
+    pub hr: &'static str,
+    pub one_minute: &'static str,
+    pub n_minutes: &'static str,
+    pub n_hours: &'static str,
+    pub one_day: &'static str,
+    pub n_days: &'static str,
+}
+
+/// Precise duration: "45 seconds", "3 min 20 sec", "2 hr 5 min".
+pub fn format_duration(seconds: i64, units: &DurationUnits, separator: char) -> String {
+    let seconds = seconds.max(0);
+    if seconds < 60 {
+        return format!("{} {}", seconds, units.seconds);
+    }
+    let (major, major_unit, minor, minor_unit) = if seconds < 3600 {
+        (seconds / 60, units.min, seconds % 60, units.sec)
+    } else {
+        (seconds / 3600, units.hr, (seconds % 3600) / 60, units.min)
+    };
+    let major = group_digits(major, separator);
+    if minor > 0 {
+        format!("{} {} {} {}", major, major_unit, minor, minor_unit)
+    } else {
+        format!("{} {}", major, major_unit)
+    }
+}
+
+/// Rounded duration for estimates: minutes below 1.5 hours, whole hours above
+/// ("25 minutes", "31 hours").
+pub fn humanize_minutes(minutes: i64, units: &DurationUnits, separator: char) -> String {
+    if minutes < 90 {
+        let template = if minutes == 1 {
+            units.one_minute
+        } else {
+            units.n_minutes
+        };
+        template.replace("{n}", &minutes.to_string())
+    } else {
+        let hours = (minutes as f64 / 60.0).round() as i64;
+        units
+            .n_hours
+            .replace("{n}", &group_digits(hours, separator))
+    }
+}
+
+/// Number of days: "1 day", "15 days".
+pub fn format_days(days: i64, units: &DurationUnits, separator: char) -> String {
+    let template = if days == 1 {
+        units.one_day
+    } else {
+        units.n_days
+    };
+    template.replace("{n}", &group_digits(days, separator))
+}
+
+/// Groups digits by three (e.g. 12,345 with ',').
+fn group_digits(n: i64, separator: char) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    if n < 0 {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNITS: DurationUnits = DurationUnits {
+        seconds: "seconds",
+        sec: "sec",
+        min: "min",
+        hr: "hr",
+        one_minute: "{n} minute",
+        n_minutes: "{n} minutes",
+        n_hours: "{n} hours",
+        one_day: "{n} day",
+        n_days: "{n} days",
+    };
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(1_736_899_200_000), "2025-01-15");
+        assert_eq!(format_date(i64::MAX), "invalid timestamp");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(45, &UNITS, ','), "45 seconds");
+        assert_eq!(format_duration(142, &UNITS, ','), "2 min 22 sec");
+        assert_eq!(format_duration(180, &UNITS, ','), "3 min");
+        assert_eq!(format_duration(7_500, &UNITS, ','), "2 hr 5 min");
+    }
+
+    #[test]
+    fn test_humanize_minutes_and_days() {
+        assert_eq!(humanize_minutes(1, &UNITS, ','), "1 minute");
+        assert_eq!(humanize_minutes(89, &UNITS, ','), "89 minutes");
+        assert_eq!(humanize_minutes(1_860, &UNITS, ','), "31 hours");
+        assert_eq!(humanize_minutes(90_000, &UNITS, '.'), "1.500 hours");
+        assert_eq!(format_days(1, &UNITS, ','), "1 day");
+        assert_eq!(format_days(15, &UNITS, ','), "15 days");
+    }
+}
@@ -1,17 +1,17 @@
 /// Window parsing module for temporal scope handling
 ///
-/// Parses window strings (e.g., '2025', '2025-03', '2025-W12', '2025-03-15', 'life')
-/// into temporal boundaries for crawling and stats generation.
+/// Parses window strings (e.g., '2025', '2025-Q3', '2025-03', '2025-W12', '2025-03-15',
+/// 'last-30-days', 'life') into temporal boundaries for crawling and stats generation.
 use anyhow::{anyhow, Result};
-use chrono::{Datelike, Local, NaiveDate};
+use chrono::{Datelike, Local, NaiveDate, Weekday};
 
 /// Represents a parsed temporal window with date range
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct WindowScope {
-    /// Window key as provided by user (e.g., "2025", "2025-03", "2025-W12", "2025-03-15", "life")
+    /// Window key as provided by user (e.g., "2025", "2025-Q3", "2025-03", "2025-W12", "2025-03-15", "life")
     pub key: String,
-    /// Scope type (year, month, week, day, life)
+    /// Scope type (year, quarter, month, week, day, rolling, life)
     pub scope_type: crate::stats::ScopeKind,
     /// Start date (inclusive, UTC)
     pub from: NaiveDate,
@@ -24,13 +24,21 @@ impl WindowScope {
     ///
     /// Supported formats:
     /// - "2025" → entire year 2025
+    /// - "2025-Q3" → third quarter of 2025 (July to September)
     /// - "2025-03" → March 2025
     /// - "2025-W12" → ISO week 12 of 2025
     /// - "2025-03-15" → specific day
+    /// - "last-30-days", "last-12-months" → up to today
     /// - "life" → from epoch to today
     pub fn parse(window: &str) -> Result<Self> {
         let window = window.trim();
 
+        if let Some((count, unit)) = parse_rolling(window) {
+            let today = Local::now().naive_utc().date();
+            return Self::rolling(count, unit, today)
+                .ok_or_else(|| anyhow!("Invalid window: {} goes back too far", window));
+        }
+
         if window == "life" {
             let today = Local::now().naive_utc().date();
             return Ok(WindowScope {
@@ -57,6 +65,16 @@ impl WindowScope {
             }
         }
 
+        // Try quarter: "YYYY-QN"
+        if let Some((year_str, quarter_str)) = window.split_once("-Q") {
+            if let (Ok(year), Ok(quarter)) = (year_str.parse::<i32>(), quarter_str.parse::<u32>()) {
+                if (1970..=2099).contains(&year) && (1..=4).contains(&quarter) {
+                    return Self::quarter(year, quarter)
+                        .ok_or_else(|| anyhow!("Invalid quarter: {}-Q{}", year, quarter));
+                }
+            }
+        }
+
         // Try week: "YYYY-WXX"
         if let Some(pos) = window.find("-W") {
             let year_str = &window[..pos];
@@ -64,13 +82,9 @@ impl WindowScope {
 
             if let (Ok(year), Ok(week)) = (year_str.parse::<i32>(), week_str.parse::<u32>()) {
                 if (1970..=2099).contains(&year) && (1..=53).contains(&week) {
-                    // ISO week date: find the Monday of week 1 for the year
-                    let jan_4 = NaiveDate::from_ymd_opt(year, 1, 4)
-                        .ok_or_else(|| anyhow!("Invalid year: {}", year))?;
-                    let week_1_monday = jan_4
-                        - chrono::Duration::days(jan_4.weekday().number_from_monday() as i64 - 1);
-
-                    let from = week_1_monday + chrono::Duration::days((week as i64 - 1) * 7);
+                    // ISO week date: week 53 only exists in long ISO years
+                    let from = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+                        .ok_or_else(|| anyhow!("Invalid week: {} has no week {}", year, week))?;
                     let to = from + chrono::Duration::days(6);
 
                     return Ok(WindowScope {
@@ -124,13 +138,183 @@ impl WindowScope {
         }
 
         Err(anyhow!(
-            "Invalid window format: '{}'. Expected: '2025', '2025-03', '2025-W12', '2025-03-15', or 'life'",
+            "Invalid window format: '{}'. Expected: '2025', '2025-Q3', '2025-03', '2025-W12', '2025-03-15', 'last-30-days', 'last-12-months', or 'life'",
             window
         ))
     }
 
+    /// Windows one level down, in order: the months of a year or a quarter,
+    /// the days of a month or a week. Empty for days, rolling windows and life.
+    pub fn sub_windows(&self) -> Vec<WindowScope> {
+        match self.scope_type {
+            crate::stats::ScopeKind::Year | crate::stats::ScopeKind::Quarter => self.months(),
+            crate::stats::ScopeKind::Month | crate::stats::ScopeKind::Week => self.days(),
+            crate::stats::ScopeKind::Day
+            | crate::stats::ScopeKind::Rolling
+            | crate::stats::ScopeKind::Life => Vec::new(),
+        }
+    }
+
+    /// The 12 months of a year window, or the 3 of a quarter (empty for
+    /// other windows).
+    pub fn months(&self) -> Vec<WindowScope> {
+        if !matches!(
+            self.scope_type,
+            crate::stats::ScopeKind::Year | crate::stats::ScopeKind::Quarter
+        ) {
+            return Vec::new();
+        }
+        (self.from.month()..=self.to.month())
+            .filter_map(|month| Self::month(self.from.year(), month))
+            .collect()
+    }
+
+    /// The 4 quarters of a year window (empty for other windows).
+    pub fn quarters(&self) -> Vec<WindowScope> {
+        if self.scope_type != crate::stats::ScopeKind::Year {
+            return Vec::new();
+        }
+        (1..=4)
+            .filter_map(|quarter| Self::quarter(self.from.year(), quarter))
+            .collect()
+    }
+
+    /// The ISO weeks of a year window: 52 or 53, keyed `YYYY-Wnn` like the
+    /// `by_week` buckets. Week 1 may start in December of the previous year,
+    /// and the last days of December may belong to week 1 of the next year.
+    pub fn weeks(&self) -> Vec<WindowScope> {
+        if self.scope_type != crate::stats::ScopeKind::Year {
+            return Vec::new();
+        }
+        let year = self.from.year();
+        (1..=53)
+            .filter_map(|week| {
+                let from = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+                Some(WindowScope {
+                    key: format!("{}-W{:02}", year, week),
+                    scope_type: crate::stats::ScopeKind::Week,
+                    from,
+                    to: from + chrono::Duration::days(6),
+                })
+            })
+            .collect()
+    }
+
+    /// Every day of the window, in order (empty for life).
+    pub fn days(&self) -> Vec<WindowScope> {
+        if self.scope_type == crate::stats::ScopeKind::Life {
+            return Vec::new();
+        }
+        self.from
+            .iter_days()
+            .take_while(|date| *date <= self.to)
+            .map(|date| WindowScope {
+                key: date.format("%Y-%m-%d").to_string(),
+                scope_type: crate::stats::ScopeKind::Day,
+                from: date,
+                to: date,
+            })
+            .collect()
+    }
+
+    /// Whether both windows cover the same dates, whatever their keys (e.g. `2025-W9` and `2025-W09`).
+    pub fn same_dates(&self, other: &WindowScope) -> bool {
+        self.from == other.from && self.to == other.to
+    }
+
+    /// The window of the same kind just before this one: the previous year,
+    /// quarter, month, ISO week or day. `None` for life and rolling windows,
+    /// which move with today.
+    pub fn previous(&self) -> Option<WindowScope> {
+        match self.scope_type {
+            crate::stats::ScopeKind::Year => {
+                let from = NaiveDate::from_ymd_opt(self.from.year() - 1, 1, 1)?;
+                Some(WindowScope {
+                    key: from.year().to_string(),
+                    scope_type: crate::stats::ScopeKind::Year,
+                    from,
+                    to: NaiveDate::from_ymd_opt(from.year(), 12, 31)?,
+                })
+            }
+            crate::stats::ScopeKind::Quarter => {
+                let from = self.from.checked_sub_months(chrono::Months::new(3))?;
+                Self::quarter(from.year(), from.month().div_ceil(3))
+            }
+            crate::stats::ScopeKind::Month => {
+                let from = self.from.checked_sub_months(chrono::Months::new(1))?;
+                Self::month(from.year(), from.month())
+            }
+            crate::stats::ScopeKind::Week => {
+                let from = self.from - chrono::Duration::days(7);
+                let week = from.iso_week();
+                Some(WindowScope {
+                    key: format!("{}-W{:02}", week.year(), week.week()),
+                    scope_type: crate::stats::ScopeKind::Week,
+                    from,
+                    to: from + chrono::Duration::days(6),
+                })
+            }
+            crate::stats::ScopeKind::Day => {
+                let date = self.from.pred_opt()?;
+                Some(WindowScope {
+                    key: date.format("%Y-%m-%d").to_string(),
+                    scope_type: crate::stats::ScopeKind::Day,
+                    from: date,
+                    to: date,
+                })
+            }
+            crate::stats::ScopeKind::Rolling | crate::stats::ScopeKind::Life => None,
+        }
+    }
+
+    fn month(year: i32, month: u32) -> Option<WindowScope> {
+        let from = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let to = from
+            .checked_add_months(chrono::Months::new(1))?
+            .pred_opt()?;
+        Some(WindowScope {
+            key: format!("{}-{:02}", year, month),
+            scope_type: crate::stats::ScopeKind::Month,
+            from,
+            to,
+        })
+    }
+
+    fn quarter(year: i32, quarter: u32) -> Option<WindowScope> {
+        let from = NaiveDate::from_ymd_opt(year, quarter * 3 - 2, 1)?;
+        let to = from
+            .checked_add_months(chrono::Months::new(3))?
+            .pred_opt()?;
+        Some(WindowScope {
+            key: format!("{}-Q{}", year, quarter),
+            scope_type: crate::stats::ScopeKind::Quarter,
+            from,
+            to,
+        })
+    }
+
+    /// The `count` days or months up to `today`, included.
+    fn rolling(count: u32, unit: RollingUnit, today: NaiveDate) -> Option<WindowScope> {
+        let from = match unit {
+            RollingUnit::Days => {
+                today.checked_sub_signed(chrono::Duration::days(count as i64 - 1))?
+            }
+            RollingUnit::Months => today
+                .checked_sub_months(chrono::Months::new(count))?
+                .succ_opt()?,
+        };
+        if from.year() < 1970 {
+            return None;
+        }
+        Some(WindowScope {
+            key: format!("last-{}-{}", count, unit.name()),
+            scope_type: crate::stats::ScopeKind::Rolling,
+            from,
+            to: today,
+        })
+    }
+
     /// Check if this window includes today's date
-    #[allow(dead_code)]
     pub fn covers_now(&self) -> bool {
         let today = Local::now().naive_utc().date();
         self.from <= today && today <= self.to
@@ -166,6 +350,35 @@ impl WindowScope {
     }
 }
 
+/// Unit of a rolling window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollingUnit {
+    Days,
+    Months,
+}
+
+impl RollingUnit {
+    fn name(self) -> &'static str {
+        match self {
+            RollingUnit::Days => "days",
+            RollingUnit::Months => "months",
+        }
+    }
+}
+
+/// Length of a rolling window key such as `last-30-days` or `last-12-months`
+/// (2 or more); `None` for other windows.
+pub fn parse_rolling(window: &str) -> Option<(u32, RollingUnit)> {
+    let (count, unit) = window.strip_prefix("last-")?.split_once('-')?;
+    let unit = match unit {
+        "days" => RollingUnit::Days,
+        "months" => RollingUnit::Months,
+        _ => return None,
+    };
+    let count: u32 = count.parse().ok().filter(|count| *count >= 2)?;
+    Some((count, unit))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,6 +422,20 @@ mod tests {
         assert_eq!(ws.to, NaiveDate::from_ymd_opt(2025, 2, 28).unwrap());
     }
 
+    #[test]
+    fn test_parse_quarter() {
+        let ws = WindowScope::parse("2025-Q3").unwrap();
+        assert_eq!(ws.key, "2025-Q3");
+        assert_eq!(ws.scope_type, crate::stats::ScopeKind::Quarter);
+        assert_eq!(ws.from, NaiveDate::from_ymd_opt(2025, 7, 1).unwrap());
+        assert_eq!(ws.to, NaiveDate::from_ymd_opt(2025, 9, 30).unwrap());
+
+        let ws = WindowScope::parse("2024-Q1").unwrap();
+        assert_eq!(ws.to, NaiveDate::from_ymd_opt(2024, 3, 31).unwrap());
+        let ws = WindowScope::parse("2025-Q4").unwrap();
+        assert_eq!(ws.to, NaiveDate::from_ymd_opt(2025, 12, 31).unwrap());
+    }
+
     #[test]
     fn test_parse_day() {
         let ws = WindowScope::parse("2025-03-15").unwrap();
@@ -228,6 +455,160 @@ mod tests {
         assert!(ws.to <= NaiveDate::from_ymd_opt(2025, 3, 24).unwrap());
     }
 
+    #[test]
+    fn test_parse_week_53() {
+        let ws = WindowScope::parse("2026-W53").unwrap();
+        assert_eq!(ws.from, NaiveDate::from_ymd_opt(2026, 12, 28).unwrap());
+        assert_eq!(ws.to, NaiveDate::from_ymd_opt(2027, 1, 3).unwrap());
+    }
+
+    #[test]
+    fn test_months() {
+        let months = WindowScope::parse("2024").unwrap().months();
+        assert_eq!(months.len(), 12);
+        assert_eq!(months[0].key, "2024-01");
+        assert_eq!(months[1].to, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(months[11].key, "2024-12");
+        assert_eq!(
+            months[11].to,
+            NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()
+        );
+        for month in &months {
+            let parsed = WindowScope::parse(&month.key).unwrap();
+            assert!(parsed.same_dates(month));
+        }
+        assert!(WindowScope::parse("2024-03").unwrap().months().is_empty());
+
+        let months = WindowScope::parse("2025-Q2").unwrap().months();
+        let keys: Vec<&str> = months.iter().map(|month| month.key.as_str()).collect();
+        assert_eq!(keys, ["2025-04", "2025-05", "2025-06"]);
+    }
+
+    #[test]
+    fn test_quarters() {
+        let quarters = WindowScope::parse("2025").unwrap().quarters();
+        assert_eq!(quarters.len(), 4);
+        assert_eq!(quarters[0].key, "2025-Q1");
+        assert_eq!(
+            quarters[3].to,
+            NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()
+        );
+        assert!(quarters
+            .windows(2)
+            .all(|pair| pair[0].to.succ_opt() == Some(pair[1].from)));
+        assert!(WindowScope::parse("2025-Q1").unwrap().quarters().is_empty());
+    }
+
+    #[test]
+    fn test_weeks() {
+        let weeks = WindowScope::parse("2025").unwrap().weeks();
+        assert_eq!(weeks.len(), 52);
+        // Week 1 of 2025 starts in December 2024
+        assert_eq!(weeks[0].key, "2025-W01");
+        assert_eq!(
+            weeks[0].from,
+            NaiveDate::from_ymd_opt(2024, 12, 30).unwrap()
+        );
+        assert_eq!(weeks[51].to, NaiveDate::from_ymd_opt(2025, 12, 28).unwrap());
+
+        let weeks = WindowScope::parse("2026").unwrap().weeks();
+        assert_eq!(weeks.len(), 53);
+        assert_eq!(weeks[52].key, "2026-W53");
+        assert!(WindowScope::parse("2026-W53")
+            .unwrap()
+            .same_dates(&weeks[52]));
+        // Consecutive weeks, without gaps
+        assert!(weeks
+            .windows(2)
+            .all(|pair| pair[0].to.succ_opt() == Some(pair[1].from)));
+    }
+
+    #[test]
+    fn test_days() {
+        let days = WindowScope::parse("2024-02").unwrap().days();
+        assert_eq!(days.len(), 29);
+        assert_eq!(days[0].key, "2024-02-01");
+        assert_eq!(days[28].key, "2024-02-29");
+        assert_eq!(WindowScope::parse("2025").unwrap().days().len(), 365);
+        assert_eq!(
+            WindowScope::parse("2025-W01").unwrap().days()[0].key,
+            "2024-12-30"
+        );
+        assert!(WindowScope::parse("life").unwrap().days().is_empty());
+    }
+
+    #[test]
+    fn test_sub_windows() {
+        let sub_keys = |window: &str| -> Vec<String> {
+            WindowScope::parse(window)
+                .unwrap()
+                .sub_windows()
+                .into_iter()
+                .map(|sub| sub.key)
+                .collect()
+        };
+        assert_eq!(sub_keys("2025").len(), 12);
+        assert_eq!(sub_keys("2025-Q3"), ["2025-07", "2025-08", "2025-09"]);
+        assert_eq!(sub_keys("2025-04").len(), 30);
+        assert_eq!(sub_keys("2025-W12").len(), 7);
+        assert!(sub_keys("2025-03-15").is_empty());
+        assert!(sub_keys("life").is_empty());
+    }
+
+    #[test]
+    fn test_previous() {
+        let previous = |window: &str| {
+            WindowScope::parse(window)
+                .unwrap()
+                .previous()
+                .map(|previous| previous.key)
+        };
+        assert_eq!(previous("2025").as_deref(), Some("2024"));
+        assert_eq!(previous("2025-03").as_deref(), Some("2025-02"));
+        assert_eq!(previous("2025-01").as_deref(), Some("2024-12"));
+        assert_eq!(previous("2025-Q3").as_deref(), Some("2025-Q2"));
+        assert_eq!(previous("2025-Q1").as_deref(), Some("2024-Q4"));
+        assert_eq!(previous("2025-W12").as_deref(), Some("2025-W11"));
+        // 2020 is a long ISO year
+        assert_eq!(previous("2021-W01").as_deref(), Some("2020-W53"));
+        assert_eq!(previous("2025-W01").as_deref(), Some("2024-W52"));
+        assert_eq!(previous("2025-03-01").as_deref(), Some("2025-02-28"));
+        assert_eq!(previous("life"), None);
+
+        let week = WindowScope::parse("2021-W01").unwrap().previous().unwrap();
+        assert!(week.same_dates(&WindowScope::parse("2020-W53").unwrap()));
+    }
+
+    #[test]
+    fn test_parse_rolling() {
+        assert_eq!(parse_rolling("last-30-days"), Some((30, RollingUnit::Days)));
+        assert_eq!(
+            parse_rolling("last-12-months"),
+            Some((12, RollingUnit::Months))
+        );
+        assert_eq!(parse_rolling("last-1-days"), None);
+        assert_eq!(parse_rolling("last-2-weeks"), None);
+        assert_eq!(parse_rolling("2025-03"), None);
+
+        let today = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let ws = WindowScope::rolling(7, RollingUnit::Days, today).unwrap();
+        assert_eq!(ws.key, "last-7-days");
+        assert_eq!(ws.from, NaiveDate::from_ymd_opt(2025, 3, 9).unwrap());
+        assert_eq!(ws.to, today);
+        assert_eq!(ws.days().len(), 7);
+
+        let ws = WindowScope::rolling(12, RollingUnit::Months, today).unwrap();
+        assert_eq!(ws.from, NaiveDate::from_ymd_opt(2024, 3, 16).unwrap());
+        assert_eq!(ws.to, today);
+
+        let ws = WindowScope::parse("last-30-days").unwrap();
+        assert_eq!(ws.scope_type, crate::stats::ScopeKind::Rolling);
+        assert!(ws.covers_now());
+        assert!(ws.previous().is_none());
+        assert!(WindowScope::parse("last-100000-days").is_err());
+        assert!(WindowScope::parse("last-4000000000-days").is_err());
+    }
+
     #[test]
     fn test_parse_life() {
         let ws = WindowScope::parse("life").unwrap();
@@ -242,7 +623,11 @@ mod tests {
     fn test_invalid_window() {
         assert!(WindowScope::parse("invalid").is_err());
         assert!(WindowScope::parse("2025-W99").is_err());
+        // 2025 is a short ISO year
+        assert!(WindowScope::parse("2025-W53").is_err());
         assert!(WindowScope::parse("2025-13").is_err());
+        assert!(WindowScope::parse("2025-Q5").is_err());
+        assert!(WindowScope::parse("2025-Q0").is_err());
         assert!(WindowScope::parse("2025-02-30").is_err());
     }
 }
@@ -0,0 +1,93 @@
+use my::commands::render::{self, html, i18n::Lang, links::LinkBase, md, theme::HtmlTheme};
+use my::stats::Stats;
+use std::path::{Path, PathBuf};
+
+/// Snapshot tests of the full Markdown and HTML reports of the example stats.
+///
+/// Reports are rendered in golden mode (`my render --golden`), so they only
+/// change when the rendering does. After an intended change, refresh the
+/// snapshots and review the diff:
+///
+///   UPDATE_GOLDEN=1 cargo test --test golden_reports
+fn golden_stats() -> Stats {
+    let mut stats: Stats =
+        serde_json::from_str(include_str!("../examples/stats/example-stats.json"))
+            .expect("example stats should parse");
+    render::make_golden(&mut stats);
+    stats
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name)
+}
+
+fn assert_golden(name: &str, actual: &str) {
+    let path = golden_path(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).expect("failed to write golden file");
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing {}, run with UPDATE_GOLDEN=1", path.display()));
+    if let Some((line, (expected, actual))) = expected
+        .lines()
+        .zip(actual.lines())
+        .enumerate()
+        .find(|(_, (expected, actual))| expected != actual)
+    {
+        panic!(
+            "{} differs at line {}:\n  expected: {}\n    actual: {}\n(run with UPDATE_GOLDEN=1 after an intended change)",
+            name,
+            line + 1,
+            expected,
+            actual
+        );
+    }
+    assert_eq!(
+        expected.lines().count(),
+        actual.lines().count(),
+        "{} has a different number of lines (run with UPDATE_GOLDEN=1 after an intended change)",
+        name
+    );
+}
+
+#[test]
+fn test_golden_markdown() {
+    let markdown = md::render(&golden_stats(), Lang::En).unwrap();
+    assert_golden("my-year-2025.md", &markdown);
+}
+
+#[test]
+fn test_golden_html() {
+    let html = html::render(
+        &golden_stats(),
+        Lang::En,
+        HtmlTheme::Auto,
+        &render::Avatars::default(),
+        &LinkBase::default(),
+    )
+    .unwrap();
+    assert_golden("my-year-2025.html", &html);
+}
+
+#[test]
+fn test_golden_is_stable() {
+    // Same tool, same date and same order whatever the crawl decided for ties
+    let mut stats = golden_stats();
+    let emojis = stats
+        .reactions
+        .as_mut()
+        .and_then(|reactions| reactions.top_emojis.as_mut())
+        .unwrap();
+    emojis.reverse();
+    stats.generator = Some("my 9.9.9".to_string());
+    stats.generated_at = "2030-06-01".to_string();
+    render::make_golden(&mut stats);
+
+    assert_eq!(
+        md::render(&stats, Lang::En).unwrap(),
+        md::render(&golden_stats(), Lang::En).unwrap()
+    );
+}
@@ -0,0 +1,238 @@
+use my::commands::render::{self, html, i18n::Lang, links::LinkBase, theme};
+use my::stats::Stats;
+
+/// Accessibility checks of the HTML report.
+///
+/// A small linter runs over the reports of every example stats file, in every
+/// language: document language, heading outline, table captions and header
+/// scopes, text alternatives of images and charts, and link texts. The built-in
+/// themes are checked for the WCAG AA contrast of their text colors.
+const EXAMPLES: [&str; 5] = [
+    include_str!("../examples/stats/example-stats.json"),
+    include_str!("../examples/stats/example-stats-2025-03.json"),
+    include_str!("../examples/stats/example-stats-2025-W12.json"),
+    include_str!("../examples/stats/example-stats-2025-03-15.json"),
+    include_str!("../examples/stats/example-stats-life.json"),
+];
+
+/// WCAG AA contrast ratio of normal text.
+const MIN_TEXT_CONTRAST: f64 = 4.5;
+
+/// Opening tags `<name ...>` of `html`.
+fn tags<'a>(html: &'a str, name: &str) -> Vec<&'a str> {
+    let open = format!("<{}", name);
+    html.match_indices(open.as_str())
+        .filter_map(|(start, _)| {
+            let rest = &html[start..];
+            if !rest[open.len()..].starts_with([' ', '>']) {
+                return None;
+            }
+            rest.find('>').map(|end| &rest[..=end])
+        })
+        .collect()
+}
+
+/// Value of `attribute` in an opening tag.
+fn attribute<'a>(tag: &'a str, attribute: &str) -> Option<&'a str> {
+    let key = format!(" {}=\"", attribute);
+    let start = tag.find(&key)? + key.len();
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+/// Accessibility problems of an HTML report, empty when it passes.
+fn lint(html: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if !tags(html, "html")
+        .into_iter()
+        .any(|tag| attribute(tag, "lang").is_some_and(|lang| !lang.is_empty()))
+    {
+        problems.push("the document has no language".to_string());
+    }
+
+    // One h1, then no skipped heading levels
+    let mut levels = Vec::new();
+    for (index, _) in html.match_indices("<h") {
+        let level = html[index + 2..]
+            .chars()
+            .next()
+            .and_then(|c| c.to_digit(10));
+        let closed = html[index + 3..].starts_with(['>', ' ']);
+        if let (Some(level), true) = (level, closed) {
+            levels.push(level);
+        }
+    }
+    if levels.first() != Some(&1) || levels.iter().filter(|&&level| level == 1).count() != 1 {
+        problems.push(format!("expected a single h1 first, got {:?}", levels));
+    }
+    for pair in levels.windows(2) {
+        if pair[1] > pair[0] + 1 {
+            problems.push(format!("heading h{} follows h{}", pair[1], pair[0]));
+        }
+    }
+
+    for (index, _) in html.match_indices("<table>") {
+        let rest = html[index + "<table>".len()..].trim_start();
+        let caption = rest
+            .strip_prefix("<caption>")
+            .and_then(|rest| rest.split("</caption>").next())
+            .map(strip_tags);
+        if caption.is_none_or(|caption| caption.trim().is_empty()) {
+            problems.push(format!("table without caption: {:.60}", rest));
+        }
+    }
+    for tag in tags(html, "th") {
+        if attribute(tag, "scope").is_none() {
+            problems.push(format!("header cell without scope: {}", tag));
+        }
+    }
+
+    for tag in tags(html, "img") {
+        if attribute(tag, "alt").is_none() {
+            problems.push(format!("image without alt text: {:.80}", tag));
+        }
+    }
+
+    // Charts are drawn for sight only: they need a text alternative
+    let charts = tags(html, "svg")
+        .into_iter()
+        .chain(tags(html, "div").into_iter().filter(|tag| {
+            attribute(tag, "class").is_some_and(|class| {
+                class
+                    .split(' ')
+                    .any(|class| class == "chart" || class == "stack")
+            })
+        }));
+    for tag in charts {
+        if attribute(tag, "role") != Some("img") {
+            problems.push(format!("chart without role=\"img\": {}", tag));
+        }
+    }
+    for tag in ["div", "svg", "span"]
+        .into_iter()
+        .flat_map(|name| tags(html, name))
+        .filter(|tag| attribute(tag, "role") == Some("img"))
+    {
+        if attribute(tag, "aria-label").is_none_or(|label| label.trim().is_empty()) {
+            problems.push(format!("image without aria-label: {}", tag));
+        }
+    }
+
+    for (index, _) in html.match_indices("<a ") {
+        let text = html[index..]
+            .split_once('>')
+            .and_then(|(_, rest)| rest.split("</a>").next())
+            .map(strip_tags)
+            .unwrap_or_default();
+        if text.trim().is_empty() {
+            problems.push(format!("link without text: {:.80}", &html[index..]));
+        }
+    }
+
+    problems
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Relative luminance of a `#rrggbb` color.
+fn luminance(color: &str) -> f64 {
+    let channel = |index: usize| {
+        let value = u8::from_str_radix(&color[index..index + 2], 16).unwrap() as f64 / 255.0;
+        if value <= 0.03928 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(1) + 0.7152 * channel(3) + 0.0722 * channel(5)
+}
+
+fn contrast(a: &str, b: &str) -> f64 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+#[test]
+fn test_html_reports_pass_linter() {
+    for example in EXAMPLES {
+        let stats: Stats = serde_json::from_str(example).expect("example stats should parse");
+        for lang in Lang::ALL {
+            let html = html::render(
+                &stats,
+                lang,
+                theme::HtmlTheme::Auto,
+                &render::Avatars::default(),
+                &LinkBase::default(),
+            )
+            .unwrap();
+            let problems = lint(&html);
+            assert!(
+                problems.is_empty(),
+                "{} report ({:?}):\n{}",
+                stats.scope.key,
+                lang,
+                problems.join("\n")
+            );
+        }
+    }
+}
+
+#[test]
+fn test_linter_finds_problems() {
+    let html = "<html><body><h2>Title</h2><table><tr><th>A</th></tr></table>\
+                <img src=\"a.png\"><div class=\"chart\"></div><a href=\"#\"></a></body></html>";
+    let problems = lint(html);
+    for expected in [
+        "the document has no language",
+        "expected a single h1 first",
+        "table without caption",
+        "header cell without scope",
+        "image without alt text",
+        "chart without role",
+        "link without text",
+    ] {
+        assert!(
+            problems.iter().any(|problem| problem.starts_with(expected)),
+            "{} not in {:?}",
+            expected,
+            problems
+        );
+    }
+}
+
+#[test]
+fn test_theme_contrast() {
+    for palette in theme::PALETTES {
+        for (name, color) in [
+            ("text", palette.text),
+            ("muted", palette.muted),
+            ("accent", palette.accent),
+        ] {
+            for (background, background_color) in [("bg", palette.bg), ("card", palette.card)] {
+                let ratio = contrast(color, background_color);
+                assert!(
+                    ratio >= MIN_TEXT_CONTRAST,
+                    "{} theme: {} on {} has a contrast of {:.2}, below {}",
+                    palette.name,
+                    name,
+                    background,
+                    ratio,
+                    MIN_TEXT_CONTRAST
+                );
+            }
+        }
+    }
+}